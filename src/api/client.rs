@@ -1,6 +1,9 @@
+use crate::api::network;
+use crate::auth::types::StoredCredentials;
 use crate::auth::{CredentialStore, OAuthClient};
 use crate::config;
 use crate::error::GitAiError;
+use crate::utils::debug_log;
 use once_cell::sync::Lazy;
 use std::sync::Mutex;
 use url::Url;
@@ -13,21 +16,45 @@ static REFRESH_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
 /// Attempt to load stored credentials and refresh if needed.
 /// Returns None on any failure (not logged in, expired, refresh failed).
 /// Uses in-process Mutex for thread safety during token refresh.
-fn try_load_auth_token() -> Option<String> {
-    let store = CredentialStore::new();
+///
+/// `rejected` is an access token the server just answered 401 to; it is refreshed
+/// even if it hasn't expired yet.
+///
+/// A service token in `GIT_AI_TOKEN` takes precedence over stored credentials.
+fn try_load_auth_token(rejected: Option<&str>) -> Option<String> {
+    if let Some(token) = crate::auth::env_token() {
+        return (rejected != Some(token.as_str())).then_some(token);
+    }
+    usable_auth_token(&CredentialStore::new(), OAuthClient::new, rejected)
+}
+
+/// The stored access token, refreshed with `client` and persisted to `store` when it
+/// has expired or is the `rejected` one. Service tokens are never refreshed.
+fn usable_auth_token(
+    store: &CredentialStore,
+    client: impl FnOnce() -> OAuthClient,
+    rejected: Option<&str>,
+) -> Option<String> {
+    let needs_refresh = |creds: &StoredCredentials| {
+        creds.is_access_token_expired(300) || rejected == Some(creds.access_token.as_str())
+    };
 
     let creds = match store.load() {
         Ok(Some(c)) => c,
         _ => return None,
     };
 
+    if creds.service {
+        return (rejected != Some(creds.access_token.as_str())).then_some(creds.access_token);
+    }
+
     // If refresh token expired, can't authenticate
     if creds.is_refresh_token_expired() {
         return None;
     }
 
     // Fast path: if access token is valid (with 5 min buffer), use it directly
-    if !creds.is_access_token_expired(300) {
+    if !needs_refresh(&creds) {
         return Some(creds.access_token);
     }
 
@@ -42,19 +69,21 @@ fn try_load_auth_token() -> Option<String> {
     };
 
     // Check again if access token is now valid (another thread may have refreshed)
-    if !creds.is_access_token_expired(300) {
+    if !needs_refresh(&creds) {
         return Some(creds.access_token);
     }
 
     // Still expired - we need to refresh
-    let client = OAuthClient::new();
-    match client.refresh_access_token(&creds.refresh_token) {
+    match client().refresh_access_token(&creds.refresh_token) {
         Ok(new_creds) => {
-            // Store refreshed credentials (ignore errors - we still have the token)
+            // Persist the rotated refresh token too; ignore errors - we still have the token
             let _ = store.store(&new_creds);
             Some(new_creds.access_token)
         }
-        Err(_) => None,
+        Err(e) => {
+            debug_log(&e);
+            None
+        }
     }
     // Mutex guard is automatically released when _guard is dropped
 }
@@ -70,18 +99,21 @@ pub struct ApiContext {
     pub api_key: Option<String>,
     /// Request timeout in seconds
     pub timeout_secs: Option<u64>,
+    /// Whether `auth_token` came from the credential store, so a 401 can be retried
+    /// once with a refreshed token
+    refreshable: bool,
 }
 
 impl ApiContext {
-    /// Get the default API base URL from config
+    /// Get the default API base URL from config, or from the active auth profile
     fn default_base_url() -> String {
-        config::Config::get().api_base_url().to_string()
+        crate::auth::profiles::api_base_url()
     }
 
     /// Create a GET request with common headers (User-Agent, X-Distinct-ID)
     /// Use this for all HTTP GET requests to ensure consistent headers.
     pub fn http_get(url: &str) -> minreq::Request {
-        minreq::get(url)
+        network::with_proxy(minreq::get(url), url)
             .with_header(
                 "User-Agent",
                 format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
@@ -92,7 +124,7 @@ impl ApiContext {
     /// Create a POST request with common headers (User-Agent, X-Distinct-ID)
     /// Use this for all HTTP POST requests to ensure consistent headers.
     pub fn http_post(url: &str) -> minreq::Request {
-        minreq::post(url)
+        network::with_proxy(minreq::post(url), url)
             .with_header(
                 "User-Agent",
                 format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
@@ -106,9 +138,10 @@ impl ApiContext {
         let cfg = config::Config::get();
         Self {
             base_url: base_url.unwrap_or_else(Self::default_base_url),
-            auth_token: try_load_auth_token(),
+            auth_token: try_load_auth_token(None),
             api_key: cfg.api_key().map(|s| s.to_string()),
             timeout_secs: Some(30),
+            refreshable: true,
         }
     }
 
@@ -122,6 +155,7 @@ impl ApiContext {
             auth_token: None,
             api_key: cfg.api_key().map(|s| s.to_string()),
             timeout_secs: Some(30),
+            refreshable: false,
         }
     }
 
@@ -135,6 +169,7 @@ impl ApiContext {
             auth_token: Some(auth_token),
             api_key: cfg.api_key().map(|s| s.to_string()),
             timeout_secs: Some(30),
+            refreshable: false,
         }
     }
 
@@ -163,40 +198,44 @@ impl ApiContext {
         let url = self.build_url(endpoint)?;
         let body_json = serde_json::to_string(body).map_err(GitAiError::JsonError)?;
 
-        let mut request = Self::http_post(&url)
-            .with_header("Content-Type", "application/json")
-            .with_body(body_json);
-
-        // Add authentication header if token is present
-        if let Some(token) = &self.auth_token {
-            request = request.with_header("Authorization", format!("Bearer {}", token));
-        }
-
-        // Add API key header if present
-        if let Some(api_key) = &self.api_key {
-            request = request.with_header("X-API-Key", api_key);
-        }
-
-        // Set timeout if specified
-        if let Some(timeout) = self.timeout_secs {
-            request = request.with_timeout(timeout);
-        }
-
-        let response = request
-            .send()
-            .map_err(|e| GitAiError::Generic(format!("HTTP request failed: {}", e)))?;
-
-        Ok(response)
+        self.send(|| {
+            Self::http_post(&url)
+                .with_header("Content-Type", "application/json")
+                .with_body(body_json.clone())
+        })
     }
 
     /// Make a GET request
     pub fn get(&self, endpoint: &str) -> Result<minreq::Response, GitAiError> {
         let url = self.build_url(endpoint)?;
+        self.send(|| Self::http_get(&url))
+    }
 
-        let mut request = Self::http_get(&url);
+    /// Send the request `build` makes with the context's headers. A 401 to a stored
+    /// token is retried once with a refreshed one.
+    fn send(&self, build: impl Fn() -> minreq::Request) -> Result<minreq::Response, GitAiError> {
+        let response = self.send_with_token(build(), self.auth_token.as_deref())?;
+
+        if response.status_code == 401
+            && self.refreshable
+            && let Some(token) = try_load_auth_token(self.auth_token.as_deref())
+        {
+            debug_log("API rejected the access token; retrying with a refreshed one");
+            return self.send_with_token(build(), Some(&token));
+        }
+        Ok(response)
+    }
+
+    fn send_with_token(
+        &self,
+        mut request: minreq::Request,
+        token: Option<&str>,
+    ) -> Result<minreq::Response, GitAiError> {
+        network::ensure_online().map_err(GitAiError::Generic)?;
+        crate::auth::profiles::check_binding(&self.base_url).map_err(GitAiError::Generic)?;
 
         // Add authentication header if token is present
-        if let Some(token) = &self.auth_token {
+        if let Some(token) = token {
             request = request.with_header("Authorization", format!("Bearer {}", token));
         }
 
@@ -210,11 +249,9 @@ impl ApiContext {
             request = request.with_timeout(timeout);
         }
 
-        let response = request
+        request
             .send()
-            .map_err(|e| GitAiError::Generic(format!("HTTP request failed: {}", e)))?;
-
-        Ok(response)
+            .map_err(|e| GitAiError::Generic(format!("HTTP request failed: {}", e)))
     }
 }
 
@@ -333,6 +370,113 @@ mod tests {
         assert!(result.is_err());
     }
 
+    // ============= Token Refresh Tests =============
+
+    fn store_credentials(access_token: &str, access_expires_in: i64) -> CredentialStore {
+        let now = chrono::Utc::now().timestamp();
+        let store = CredentialStore::new();
+        store
+            .store(&StoredCredentials {
+                access_token: access_token.to_string(),
+                refresh_token: "old_refresh".to_string(),
+                access_token_expires_at: now + access_expires_in,
+                refresh_token_expires_at: now + 86400,
+                service: false,
+            })
+            .unwrap();
+        store
+    }
+
+    /// An OAuth server answering one token request with rotated credentials
+    fn token_server() -> (String, std::thread::JoinHandle<String>) {
+        use std::io::{BufRead, BufReader, Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if let Some((name, value)) = line.split_once(':')
+                    && name.eq_ignore_ascii_case("content-length")
+                {
+                    content_length = value.trim().parse().unwrap();
+                }
+                if line.trim().is_empty() {
+                    break;
+                }
+            }
+            let mut body = vec![0; content_length];
+            reader.read_exact(&mut body).unwrap();
+
+            let response = r#"{"access_token":"new_access","token_type":"Bearer","expires_in":3600,"refresh_token":"new_refresh","refresh_expires_in":7776000}"#;
+            write!(
+                &stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                response.len(),
+                response
+            )
+            .unwrap();
+            String::from_utf8(body).unwrap()
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_usable_auth_token_returns_valid_token() {
+        let store = store_credentials("current_access", 3600);
+        let token = usable_auth_token(&store, || panic!("should not refresh"), None);
+        assert_eq!(token.as_deref(), Some("current_access"));
+        // A rejected token other than the stored one was already replaced
+        let token = usable_auth_token(&store, || panic!("should not refresh"), Some("stale"));
+        assert_eq!(token.as_deref(), Some("current_access"));
+        store.clear().unwrap();
+    }
+
+    #[test]
+    fn test_usable_auth_token_refreshes_and_persists() {
+        for (access_expires_in, rejected) in [(-60, None), (3600, Some("old_access"))] {
+            let store = store_credentials("old_access", access_expires_in);
+            let (url, server) = token_server();
+            let client = || OAuthClient::with_base_url(&url).unwrap();
+
+            let token = usable_auth_token(&store, client, rejected);
+            assert_eq!(token.as_deref(), Some("new_access"));
+            assert!(
+                server
+                    .join()
+                    .unwrap()
+                    .contains("\"refresh_token\":\"old_refresh\"")
+            );
+
+            let stored = store.load().unwrap().unwrap();
+            assert_eq!(stored.access_token, "new_access");
+            assert_eq!(stored.refresh_token, "new_refresh");
+            store.clear().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_usable_auth_token_uses_service_token_as_is() {
+        let store = CredentialStore::new();
+        store
+            .store(&StoredCredentials::service_token("gitai_svc_123"))
+            .unwrap();
+        let token = usable_auth_token(&store, || panic!("should not refresh"), None);
+        assert_eq!(token.as_deref(), Some("gitai_svc_123"));
+        // A rejected service token can't be replaced
+        let token = usable_auth_token(
+            &store,
+            || panic!("should not refresh"),
+            Some("gitai_svc_123"),
+        );
+        assert_eq!(token, None);
+        store.clear().unwrap();
+    }
+
     // ============= Mutex Thread Safety Tests =============
 
     #[test]
@@ -2,7 +2,10 @@ pub mod bundle;
 pub mod cas;
 pub mod client;
 pub mod metrics;
+pub mod network;
+pub mod org;
 pub mod types;
+pub mod user;
 
 pub use client::{ApiClient, ApiContext};
 pub use metrics::upload_metrics_with_retry;
@@ -0,0 +1,167 @@
+//! Network settings every HTTP request goes through, for corporate networks.
+//!
+//! Requests use the proxy named by `https_proxy`/`http_proxy` (or `all_proxy`), in
+//! either case, unless `no_proxy` lists the host. TLS trusts the system's root
+//! certificates and the built-in ones, plus those of the `ca_bundle` config when set.
+//!
+//! In offline mode (`network = false` or `GIT_AI_OFFLINE=1`) nothing is sent at all:
+//! every request site checks [`ensure_online`] first.
+
+use crate::config::Config;
+use crate::utils::debug_log;
+
+pub const OFFLINE_NOTE: &str = "git-ai is in offline mode (network = false or GIT_AI_OFFLINE=1)";
+
+pub fn offline() -> bool {
+    !Config::get().network_enabled()
+}
+
+/// Fails in offline mode; call before sending any request
+pub fn ensure_online() -> Result<(), String> {
+    if offline() {
+        return Err(format!("{}, no request was made", OFFLINE_NOTE));
+    }
+    Ok(())
+}
+
+/// Route `request` for `url` through the environment's proxy, if any
+pub fn with_proxy(request: minreq::Request, url: &str) -> minreq::Request {
+    let Some(proxy) = proxy_for(url, |var| std::env::var(var).ok()) else {
+        return request;
+    };
+    match minreq::Proxy::new(&proxy) {
+        Ok(proxy) => request.with_proxy(proxy),
+        Err(e) => {
+            debug_log(&format!("ignoring proxy {}: {}", proxy, e));
+            request
+        }
+    }
+}
+
+/// Trust the `ca_bundle` config's certificates. TLS reads them from `SSL_CERT_FILE`
+/// on first use, so this has to run before any request or thread is started; an
+/// `SSL_CERT_FILE` already in the environment wins.
+pub fn init_ca_bundle() {
+    if std::env::var_os("SSL_CERT_FILE").is_some() {
+        return;
+    }
+    let Some(path) = Config::get().ca_bundle() else {
+        return;
+    };
+    if !std::path::Path::new(path).is_file() {
+        crate::logging::warn(&format!("ca_bundle file not found: {}", path));
+        return;
+    }
+    // SAFETY: called while the process is still single-threaded
+    unsafe { std::env::set_var("SSL_CERT_FILE", path) };
+}
+
+/// Proxy URL for `url`. Lowercase variables take precedence, as they do for curl.
+fn proxy_for(url: &str, env: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let var = |name: &str| {
+        env(&name.to_ascii_lowercase())
+            .or_else(|| env(name))
+            .filter(|value| !value.trim().is_empty())
+    };
+    let (scheme, rest) = url.split_once("://")?;
+    let host = host_of(rest);
+    if let Some(no_proxy) = var("NO_PROXY")
+        && bypasses_proxy(&no_proxy, &host)
+    {
+        return None;
+    }
+    match scheme.to_ascii_lowercase().as_str() {
+        "https" => var("HTTPS_PROXY"),
+        "http" => var("HTTP_PROXY"),
+        _ => None,
+    }
+    .or_else(|| var("ALL_PROXY"))
+}
+
+/// Host of the part of a URL after `scheme://`, lowercased and without port
+fn host_of(rest: &str) -> String {
+    let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host_port = authority.rsplit('@').next().unwrap_or_default();
+    let host = match host_port.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or_default(),
+        None => host_port.split(':').next().unwrap_or_default(),
+    };
+    host.to_ascii_lowercase()
+}
+
+/// Whether `no_proxy` (comma-separated hosts and domains, or `*`) covers `host`
+fn bypasses_proxy(no_proxy: &str, host: &str) -> bool {
+    no_proxy
+        .split(',')
+        .map(|entry| {
+            entry
+                .trim()
+                .trim_start_matches("*.")
+                .trim_start_matches('.')
+        })
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| {
+            let entry = entry.to_ascii_lowercase();
+            // Ports in entries aren't distinguished
+            let entry = entry.split(':').next().unwrap_or_default();
+            entry == "*" || host == entry || host.ends_with(&format!(".{}", entry))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let pairs: Vec<(String, String)> = pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        move |var| {
+            pairs
+                .iter()
+                .find(|(name, _)| name == var)
+                .map(|(_, value)| value.clone())
+        }
+    }
+
+    #[test]
+    fn test_proxy_for() {
+        let vars = env(&[
+            ("HTTPS_PROXY", "http://proxy.corp:3128"),
+            ("http_proxy", "http://plain.corp:3128"),
+            ("NO_PROXY", "localhost,.internal.corp, 10.0.0.1"),
+        ]);
+        assert_eq!(
+            proxy_for("https://usegitai.com/api", &vars).as_deref(),
+            Some("http://proxy.corp:3128")
+        );
+        assert_eq!(
+            proxy_for("http://usegitai.com", &vars).as_deref(),
+            Some("http://plain.corp:3128")
+        );
+        assert_eq!(proxy_for("http://localhost:8080/x", &vars), None);
+        assert_eq!(proxy_for("https://git-ai.internal.corp", &vars), None);
+        assert_eq!(proxy_for("https://user@10.0.0.1:443", &vars), None);
+        assert_eq!(proxy_for("https://usegitai.com", env(&[])), None);
+        assert_eq!(
+            proxy_for("https://usegitai.com", env(&[("ALL_PROXY", "proxy:8080")])).as_deref(),
+            Some("proxy:8080")
+        );
+        assert_eq!(
+            proxy_for(
+                "https://usegitai.com",
+                env(&[("https_proxy", "proxy:8080"), ("no_proxy", "*")])
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_host_of() {
+        assert_eq!(host_of("Example.com:443/path"), "example.com");
+        assert_eq!(host_of("user:pw@example.com"), "example.com");
+        assert_eq!(host_of("[::1]:8080/x"), "::1");
+        assert_eq!(host_of("example.com?q=1"), "example.com");
+    }
+}
@@ -0,0 +1,25 @@
+use crate::api::client::ApiClient;
+use crate::authorship::org_metrics::OrgMetrics;
+use crate::error::GitAiError;
+
+/// Org metrics API endpoints
+impl ApiClient {
+    /// Upload aggregated metrics for the organization of the stored credentials
+    pub fn upload_org_metrics(&self, metrics: &OrgMetrics) -> Result<(), GitAiError> {
+        let response = self
+            .context()
+            .post_json("/worker/org-metrics/upload", metrics)?;
+        let body = response
+            .as_str()
+            .map_err(|e| GitAiError::Generic(format!("Failed to read response body: {}", e)))?;
+
+        match response.status_code {
+            200 | 202 | 204 => Ok(()),
+            401 => Err(GitAiError::AuthExpired("Unauthorized".to_string())),
+            status_code => Err(GitAiError::Generic(format!(
+                "Unexpected status code {}: {}",
+                status_code, body
+            ))),
+        }
+    }
+}
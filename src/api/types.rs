@@ -44,6 +44,18 @@ impl From<&FileDiffJson> for ApiFileRecord {
     }
 }
 
+/// The logged-in user, from `GET /worker/oauth/userinfo`
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UserInfo {
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Organization the token belongs to
+    #[serde(default)]
+    pub org: Option<String>,
+}
+
 /// Bundle data containing prompts and optional files
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct BundleData {
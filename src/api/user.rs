@@ -0,0 +1,25 @@
+use crate::api::client::ApiClient;
+use crate::api::types::UserInfo;
+use crate::error::GitAiError;
+
+/// Identity API endpoints
+impl ApiClient {
+    /// Who the stored credentials belong to
+    pub fn user_info(&self) -> Result<UserInfo, GitAiError> {
+        let response = self.context().get("/worker/oauth/userinfo")?;
+        let body = response
+            .as_str()
+            .map_err(|e| GitAiError::Generic(format!("Failed to read response body: {}", e)))?;
+
+        match response.status_code {
+            200 => serde_json::from_str(body).map_err(GitAiError::JsonError),
+            401 => Err(GitAiError::AuthExpired(
+                "The server no longer accepts these credentials".to_string(),
+            )),
+            status_code => Err(GitAiError::Generic(format!(
+                "Unexpected status code {}: {}",
+                status_code, body
+            ))),
+        }
+    }
+}
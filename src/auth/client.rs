@@ -1,12 +1,25 @@
 use crate::api::client::ApiContext;
-use crate::auth::types::{DeviceAuthResponse, OAuthError, StoredCredentials, TokenResponse};
-use crate::config;
+use crate::api::network;
+use crate::auth::oidc::{LoopbackRedirect, Oidc, Pkce};
+use crate::auth::profiles;
+use crate::auth::types::{
+    DeviceAuthResponse, IssuerTokenResponse, OAuthError, StoredCredentials, TokenResponse,
+};
+use crate::utils::debug_log;
 use std::thread;
 use std::time::Duration;
 
+const CLIENT_ID: &str = "git-ai-cli";
+
+/// Lifetimes assumed for tokens of OIDC issuers that don't say
+const DEFAULT_ACCESS_LIFETIME_SECS: u64 = 60 * 60;
+const DEFAULT_REFRESH_LIFETIME_SECS: u64 = 90 * 24 * 60 * 60;
+
 /// OAuth client for device authorization flow
 pub struct OAuthClient {
     base_url: String,
+    /// Identity provider that replaces the git-ai OAuth endpoints, from the profile
+    oidc: Option<Oidc>,
 }
 
 /// Validate that a URL uses HTTPS (security requirement for OAuth)
@@ -32,15 +45,21 @@ fn validate_https_url(url: &str) -> Result<(), String> {
 
 impl OAuthClient {
     pub fn new() -> Self {
-        let config = config::Config::get();
-        let base_url = config.api_base_url().to_string();
-
-        // Validate HTTPS in release mode (panics on invalid URL - fail-safe)
-        if let Err(e) = validate_https_url(&base_url) {
-            panic!("{}", e);
+        // Panics on invalid URL - fail-safe
+        match Self::for_profile(profiles::active().as_deref()) {
+            Ok(client) => client,
+            Err(e) => panic!("{}", e),
         }
+    }
 
-        Self { base_url }
+    /// Client for a profile's API, or its OIDC issuer when it has one
+    pub fn for_profile(profile: Option<&str>) -> Result<Self, String> {
+        let mut client = Self::with_base_url(&profiles::api_base_url_for(profile))?;
+        if let Some(config) = profiles::oidc_for(profile) {
+            validate_https_url(&config.issuer)?;
+            client.oidc = Some(Oidc::new(config));
+        }
+        Ok(client)
     }
 
     /// Create an OAuthClient with a custom base URL (for install script flow)
@@ -48,19 +67,134 @@ impl OAuthClient {
         validate_https_url(base_url)?;
         Ok(Self {
             base_url: base_url.trim_end_matches('/').to_string(),
+            oidc: None,
+        })
+    }
+
+    /// Credentials from a token endpoint response
+    fn credentials_from(&self, response_body: &str) -> Result<StoredCredentials, String> {
+        let token_response = if self.oidc.is_some() {
+            let issued: IssuerTokenResponse = serde_json::from_str(response_body)
+                .map_err(|e| format!("Invalid token response: {}", e))?;
+            let expires_in = issued.expires_in.unwrap_or(DEFAULT_ACCESS_LIFETIME_SECS);
+            TokenResponse {
+                access_token: issued.access_token,
+                token_type: "Bearer".to_string(),
+                expires_in,
+                // Without a refresh token the login lasts as long as the access token
+                refresh_expires_in: match issued.refresh_token {
+                    Some(_) => issued
+                        .refresh_expires_in
+                        .unwrap_or(DEFAULT_REFRESH_LIFETIME_SECS),
+                    None => expires_in,
+                },
+                refresh_token: issued.refresh_token.unwrap_or_default(),
+            }
+        } else {
+            serde_json::from_str(response_body)
+                .map_err(|e| format!("Invalid token response: {}", e))?
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        Ok(StoredCredentials {
+            access_token: token_response.access_token,
+            refresh_token: token_response.refresh_token,
+            access_token_expires_at: now + token_response.expires_in as i64,
+            refresh_token_expires_at: now + token_response.refresh_expires_in as i64,
+            service: false,
         })
     }
 
-    /// Common token exchange logic - POST to /worker/oauth/token with given body
-    fn exchange_token(&self, body: serde_json::Value) -> Result<StoredCredentials, String> {
-        let url = format!("{}/worker/oauth/token", self.base_url);
+    /// Whether `login` can use the browser flow rather than the device flow
+    pub fn supports_browser_login(&self) -> bool {
+        self.oidc.is_some()
+    }
+
+    fn client_id(&self) -> &str {
+        match &self.oidc {
+            Some(oidc) => &oidc.config.client_id,
+            None => CLIENT_ID,
+        }
+    }
+
+    pub fn token_endpoint(&self) -> Result<String, String> {
+        match &self.oidc {
+            Some(oidc) => oidc
+                .endpoints()?
+                .token_endpoint
+                .ok_or_else(|| format!("OIDC issuer {} has no token endpoint", oidc.config.issuer)),
+            None => Ok(format!("{}/worker/oauth/token", self.base_url)),
+        }
+    }
 
-        let response = ApiContext::http_post(&url)
-            .with_header("Content-Type", "application/json")
-            .with_body(body.to_string())
+    /// POST `fields`: as JSON to git-ai, form-encoded to an OIDC issuer. Issuers don't get
+    /// git-ai's distinct id header.
+    fn post(&self, url: &str, fields: &[(&str, &str)]) -> Result<minreq::Response, String> {
+        network::ensure_online()?;
+        validate_https_url(url)?;
+        let request = if self.oidc.is_some() {
+            let body = url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs(fields)
+                .finish();
+            network::with_proxy(minreq::post(url), url)
+                .with_header(
+                    "User-Agent",
+                    format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
+                )
+                .with_header("Content-Type", "application/x-www-form-urlencoded")
+                .with_body(body)
+        } else {
+            let body: serde_json::Map<String, serde_json::Value> = fields
+                .iter()
+                .map(|(key, value)| (key.to_string(), serde_json::json!(value)))
+                .collect();
+            ApiContext::http_post(url)
+                .with_header("Content-Type", "application/json")
+                .with_body(serde_json::Value::Object(body).to_string())
+        };
+        request
             .with_timeout(30)
             .send()
-            .map_err(|e| format!("Failed to connect to server: {}", e))?;
+            .map_err(|e| format!("Failed to connect to server: {}", e))
+    }
+
+    /// Common token exchange logic - POST to the token endpoint with given fields
+    fn exchange_token(&self, fields: &[(&str, &str)]) -> Result<StoredCredentials, String> {
+        self.exchange_token_retrying(fields, &[])
+    }
+
+    /// Token exchange that waits out each of `retry_delays` and tries again after a
+    /// connection failure, a 429, or a 5xx
+    fn exchange_token_retrying(
+        &self,
+        fields: &[(&str, &str)],
+        retry_delays: &[Duration],
+    ) -> Result<StoredCredentials, String> {
+        let url = self.token_endpoint()?;
+        network::ensure_online()?;
+        validate_https_url(&url)?;
+        let mut retry_delays = retry_delays.iter();
+        let response = loop {
+            let result = self.post(&url, fields);
+            let transient = match &result {
+                Ok(response) => response.status_code == 429 || response.status_code >= 500,
+                Err(_) => true,
+            };
+            match retry_delays.next() {
+                Some(delay) if transient => {
+                    debug_log(&format!(
+                        "Token request failed ({}); retrying in {:?}",
+                        match &result {
+                            Ok(response) => response.status_code.to_string(),
+                            Err(e) => e.clone(),
+                        },
+                        delay
+                    ));
+                    thread::sleep(*delay);
+                }
+                _ => break result?,
+            }
+        };
 
         let response_body = response
             .as_str()
@@ -78,29 +212,29 @@ impl OAuthClient {
             return Err(msg);
         }
 
-        let token_response: TokenResponse = serde_json::from_str(response_body)
-            .map_err(|e| format!("Invalid token response: {}", e))?;
-
-        let now = chrono::Utc::now().timestamp();
-        Ok(StoredCredentials {
-            access_token: token_response.access_token,
-            refresh_token: token_response.refresh_token,
-            access_token_expires_at: now + token_response.expires_in as i64,
-            refresh_token_expires_at: now + token_response.refresh_expires_in as i64,
-        })
+        self.credentials_from(response_body)
     }
 
     /// Start the device authorization flow
     /// Returns (device_code, user_code, verification_url, expires_in, interval)
     pub fn start_device_flow(&self) -> Result<DeviceAuthResponse, String> {
-        let url = format!("{}/worker/oauth/device/code", self.base_url);
-
-        let response = ApiContext::http_post(&url)
-            .with_header("Content-Type", "application/json")
-            .with_body("{}")
-            .with_timeout(30)
-            .send()
-            .map_err(|e| format!("Failed to connect to server: {}", e))?;
+        let response = match &self.oidc {
+            Some(oidc) => {
+                let url = oidc.endpoints()?.device_authorization_endpoint.ok_or_else(|| {
+                    format!(
+                        "OIDC issuer {} doesn't support device login; run 'git-ai login' without --device",
+                        oidc.config.issuer
+                    )
+                })?;
+                let scope = oidc.scope();
+                let mut fields = vec![("client_id", self.client_id()), ("scope", &scope)];
+                if let Some(audience) = &oidc.config.audience {
+                    fields.push(("audience", audience));
+                }
+                self.post(&url, &fields)?
+            }
+            None => self.post(&format!("{}/worker/oauth/device/code", self.base_url), &[])?,
+        };
 
         if response.status_code != 200 {
             return Err(format!(
@@ -126,7 +260,8 @@ impl OAuthClient {
         interval: u32,
         expires_in: u32,
     ) -> Result<StoredCredentials, String> {
-        let url = format!("{}/worker/oauth/token", self.base_url);
+        let url = self.token_endpoint()?;
+        network::ensure_online()?;
         let mut elapsed = 0u32;
         let mut current_interval = interval;
 
@@ -135,34 +270,21 @@ impl OAuthClient {
             thread::sleep(Duration::from_secs(current_interval as u64));
             elapsed += current_interval;
 
-            let body = serde_json::json!({
-                "grant_type": "urn:ietf:params:oauth:grant-type:device_code",
-                "device_code": device_code,
-                "client_id": "git-ai-cli"
-            });
-
-            let response = ApiContext::http_post(&url)
-                .with_header("Content-Type", "application/json")
-                .with_body(body.to_string())
-                .with_timeout(30)
-                .send()
-                .map_err(|e| format!("Failed to connect to server: {}", e))?;
+            let response = self.post(
+                &url,
+                &[
+                    ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+                    ("device_code", device_code),
+                    ("client_id", self.client_id()),
+                ],
+            )?;
 
             let response_body = response
                 .as_str()
                 .map_err(|e| format!("Invalid response encoding: {}", e))?;
 
             if response.status_code == 200 {
-                let token_response: TokenResponse = serde_json::from_str(response_body)
-                    .map_err(|e| format!("Invalid token response: {}", e))?;
-
-                let now = chrono::Utc::now().timestamp();
-                return Ok(StoredCredentials {
-                    access_token: token_response.access_token,
-                    refresh_token: token_response.refresh_token,
-                    access_token_expires_at: now + token_response.expires_in as i64,
-                    refresh_token_expires_at: now + token_response.refresh_expires_in as i64,
-                });
+                return self.credentials_from(response_body);
             }
 
             // Parse error response
@@ -203,26 +325,117 @@ impl OAuthClient {
 
     /// Refresh the access token using a refresh token
     pub fn refresh_access_token(&self, refresh_token: &str) -> Result<StoredCredentials, String> {
-        let body = serde_json::json!({
-            "grant_type": "refresh_token",
-            "refresh_token": refresh_token,
-            "client_id": "git-ai-cli"
-        });
+        self.exchange_token(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", self.client_id()),
+        ])
+        .map_err(|e| format!("Token refresh failed: {}", e))
+    }
 
-        self.exchange_token(body)
-            .map_err(|e| format!("Token refresh failed: {}", e))
+    /// Exchange an install nonce for credentials (auto-login from web install page),
+    /// retrying transient failures after each of `retry_delays`
+    pub fn exchange_install_nonce(
+        &self,
+        nonce: &str,
+        retry_delays: &[Duration],
+    ) -> Result<StoredCredentials, String> {
+        self.exchange_token_retrying(
+            &[
+                ("grant_type", "install_nonce"),
+                ("install_nonce", nonce),
+                ("client_id", CLIENT_ID),
+            ],
+            retry_delays,
+        )
+        .map_err(|e| format!("Nonce exchange failed: {}", e))
     }
 
-    /// Exchange an install nonce for credentials (auto-login from web install page)
-    pub fn exchange_install_nonce(&self, nonce: &str) -> Result<StoredCredentials, String> {
-        let body = serde_json::json!({
-            "grant_type": "install_nonce",
-            "install_nonce": nonce,
-            "client_id": "git-ai-cli"
-        });
+    /// Log in through the OIDC issuer in a browser: authorization code flow with PKCE,
+    /// redirected to a local listener. `open` shows the authorization URL to the user.
+    pub fn authorize_in_browser(
+        &self,
+        open: impl FnOnce(&str),
+    ) -> Result<StoredCredentials, String> {
+        let Some(oidc) = &self.oidc else {
+            return Err("Browser login needs an OIDC issuer; use the device flow".to_string());
+        };
+        network::ensure_online()?;
+        let endpoint = oidc.endpoints()?.authorization_endpoint.ok_or_else(|| {
+            format!(
+                "OIDC issuer {} has no authorization endpoint",
+                oidc.config.issuer
+            )
+        })?;
+        validate_https_url(&endpoint)?;
+
+        let pkce = Pkce::generate();
+        let state = uuid::Uuid::new_v4().simple().to_string();
+        let redirect = LoopbackRedirect::bind()?;
+        let scope = oidc.scope();
+        let mut params = vec![
+            ("response_type", "code"),
+            ("client_id", self.client_id()),
+            ("redirect_uri", redirect.redirect_uri.as_str()),
+            ("scope", scope.as_str()),
+            ("state", state.as_str()),
+            ("code_challenge", pkce.challenge.as_str()),
+            ("code_challenge_method", "S256"),
+        ];
+        if let Some(audience) = &oidc.config.audience {
+            params.push(("audience", audience));
+        }
+        let url = url::Url::parse_with_params(&endpoint, &params)
+            .map_err(|e| format!("Invalid authorization endpoint {}: {}", endpoint, e))?;
+        open(url.as_str());
+
+        let code = redirect.wait_for_code(&state)?;
+        self.exchange_token(&[
+            ("grant_type", "authorization_code"),
+            ("code", &code),
+            ("redirect_uri", &redirect.redirect_uri),
+            ("client_id", self.client_id()),
+            ("code_verifier", &pkce.verifier),
+        ])
+        .map_err(|e| format!("Authorization failed: {}", e))
+    }
 
-        self.exchange_token(body)
-            .map_err(|e| format!("Nonce exchange failed: {}", e))
+    /// Revoke a token server-side (RFC 7009); `hint` is `access_token` or `refresh_token`.
+    /// Revoking the refresh token also invalidates the access tokens issued from it.
+    pub fn revoke_token(&self, token: &str, hint: &str) -> Result<(), String> {
+        let url = match &self.oidc {
+            Some(oidc) => match oidc.endpoints()?.revocation_endpoint {
+                Some(url) => url,
+                // Nothing to call; the tokens expire on their own
+                None => return Ok(()),
+            },
+            None => format!("{}/worker/oauth/revoke", self.base_url),
+        };
+        let response = self.post(
+            &url,
+            &[
+                ("token", token),
+                ("token_type_hint", hint),
+                ("client_id", self.client_id()),
+            ],
+        )?;
+
+        // Unknown and already revoked tokens also get a 200
+        if response.status_code == 200 {
+            return Ok(());
+        }
+        let error: OAuthError = response
+            .as_str()
+            .ok()
+            .and_then(|body| serde_json::from_str(body).ok())
+            .unwrap_or(OAuthError {
+                error: format!("server error ({})", response.status_code),
+                error_description: None,
+            });
+        Err(format!(
+            "Token revocation failed: {}",
+            error.error_description.unwrap_or(error.error)
+        ))
     }
 }
 
@@ -428,6 +641,7 @@ mod tests {
             refresh_token: "test".to_string(),
             access_token_expires_at: now + expires_in as i64,
             refresh_token_expires_at: now + refresh_expires_in as i64,
+            service: false,
         };
 
         // Access token should expire in about 1 hour
@@ -438,4 +652,186 @@ mod tests {
         assert!(creds.refresh_token_expires_at > now + 86400 * 89);
         assert!(creds.refresh_token_expires_at <= now + 86400 * 91);
     }
+
+    // ============= Revocation Tests =============
+
+    /// Serve one request with `status` and `response`, returning its request line and body
+    fn one_shot_server(
+        status: &'static str,
+        response: &'static str,
+    ) -> (String, std::thread::JoinHandle<String>) {
+        let (url, server) = scripted_server(vec![(status, response)]);
+        (
+            url,
+            std::thread::spawn(move || server.join().unwrap().remove(0)),
+        )
+    }
+
+    /// Serve a request per `(status, response)`, in turn, returning their request lines
+    /// and bodies
+    fn scripted_server(
+        responses: Vec<(&'static str, &'static str)>,
+    ) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        use std::io::{BufRead, BufReader, Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, response) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some((name, value)) = line.split_once(':')
+                        && name.eq_ignore_ascii_case("content-length")
+                    {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                    if line.trim().is_empty() {
+                        break;
+                    }
+                }
+                let mut body = vec![0; content_length];
+                reader.read_exact(&mut body).unwrap();
+                write!(
+                    &stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    response.len(),
+                    response
+                )
+                .unwrap();
+                requests.push(format!(
+                    "{}{}",
+                    request_line.trim(),
+                    String::from_utf8(body).unwrap()
+                ));
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_exchange_install_nonce_retries_transient_failures() {
+        let (url, server) = scripted_server(vec![
+            ("503 Service Unavailable", ""),
+            ("429 Too Many Requests", ""),
+            (
+                "200 OK",
+                r#"{"access_token":"a","token_type":"Bearer","expires_in":3600,"refresh_token":"r","refresh_expires_in":7776000}"#,
+            ),
+        ]);
+        let client = OAuthClient::with_base_url(&url).unwrap();
+        let delays = [Duration::from_millis(1); 3];
+        let creds = client.exchange_install_nonce("n1", &delays).unwrap();
+        assert_eq!(creds.access_token, "a");
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[2].contains(r#""install_nonce":"n1""#));
+
+        // A rejected nonce isn't retried
+        let (url, server) = one_shot_server("400 Bad Request", r#"{"error":"invalid_grant"}"#);
+        let client = OAuthClient::with_base_url(&url).unwrap();
+        let err = client.exchange_install_nonce("n1", &delays).unwrap_err();
+        assert_eq!(err, "Nonce exchange failed: invalid_grant");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_revoke_token() {
+        let (url, server) = one_shot_server("200 OK", "");
+        let client = OAuthClient::with_base_url(&url).unwrap();
+        client.revoke_token("refresh_1", "refresh_token").unwrap();
+        let request = server.join().unwrap();
+        assert!(
+            request.starts_with("POST /worker/oauth/revoke"),
+            "{}",
+            request
+        );
+        assert!(request.contains(r#""token":"refresh_1""#), "{}", request);
+        assert!(request.contains(r#""token_type_hint":"refresh_token""#));
+
+        let (url, server) = one_shot_server("401 Unauthorized", r#"{"error":"invalid_client"}"#);
+        let client = OAuthClient::with_base_url(&url).unwrap();
+        let err = client
+            .revoke_token("refresh_1", "refresh_token")
+            .unwrap_err();
+        assert!(err.contains("invalid_client"), "{}", err);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_oidc_browser_login_with_pkce() {
+        let (token_url, server) = one_shot_server(
+            "200 OK",
+            r#"{"access_token":"idp_access","token_type":"Bearer","expires_in":600}"#,
+        );
+        let client = OAuthClient {
+            base_url: "https://api.acme.dev".to_string(),
+            oidc: Some(Oidc::new(crate::auth::oidc::OidcConfig {
+                issuer: "https://sso.acme.dev".to_string(),
+                client_id: "acme-cli".to_string(),
+                audience: Some("https://api.acme.dev".to_string()),
+                authorization_endpoint: Some("https://sso.acme.dev/authorize".to_string()),
+                token_endpoint: Some(format!("{}/token", token_url)),
+                ..Default::default()
+            })),
+        };
+
+        let mut challenge = String::new();
+        let creds = client
+            .authorize_in_browser(|url| {
+                // Play the browser: the issuer redirects back with a code
+                let url = url::Url::parse(url).unwrap();
+                let param = |name: &str| {
+                    url.query_pairs()
+                        .find(|(key, _)| key == name)
+                        .unwrap()
+                        .1
+                        .to_string()
+                };
+                assert_eq!(param("client_id"), "acme-cli");
+                assert_eq!(param("audience"), "https://api.acme.dev");
+                assert_eq!(param("code_challenge_method"), "S256");
+                challenge = param("code_challenge");
+                let redirect = format!(
+                    "{}?code=the_code&state={}",
+                    param("redirect_uri"),
+                    param("state")
+                );
+                std::thread::spawn(move || minreq::get(redirect).send().unwrap());
+            })
+            .unwrap();
+
+        assert_eq!(creds.access_token, "idp_access");
+        // No refresh token: the login lasts as long as the access token
+        assert_eq!(
+            creds.refresh_token_expires_at,
+            creds.access_token_expires_at
+        );
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("POST /token"), "{}", request);
+        assert!(
+            request.contains("grant_type=authorization_code"),
+            "{}",
+            request
+        );
+        assert!(request.contains("code=the_code"), "{}", request);
+        assert!(request.contains("client_id=acme-cli"), "{}", request);
+        let verifier = request
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("code_verifier="))
+            .unwrap();
+        assert_eq!(
+            crate::auth::oidc::Pkce::from_verifier(verifier.to_string()).challenge,
+            challenge
+        );
+    }
 }
@@ -1,6 +1,8 @@
 #[cfg(all(not(test), feature = "keyring"))]
 use crate::auth::credential_backend::KeyringBackend;
 use crate::auth::credential_backend::{CredentialBackend, FileBackend};
+#[cfg(not(test))]
+use crate::auth::profiles;
 use crate::auth::types::StoredCredentials;
 #[cfg(not(test))]
 use crate::config::Config;
@@ -18,12 +20,27 @@ pub struct CredentialStore {
 }
 
 impl CredentialStore {
-    /// Create a new credential store, testing keyring availability
+    /// Create a credential store for the active profile, testing keyring availability
     pub fn new() -> Self {
         // In test builds, always use file-based storage to avoid keyring blocking issues
+        #[cfg(test)]
+        {
+            Self::for_profile(None)
+        }
+
+        #[cfg(not(test))]
+        {
+            Self::for_profile(profiles::active().as_deref())
+        }
+    }
+
+    /// Create the credential store of a named profile, or the default one for `None`
+    pub fn for_profile(profile: Option<&str>) -> Self {
         #[cfg(test)]
         {
             let path = Self::default_test_path();
+            let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+            let path = path.with_file_name(profile_entry_name(&file_name, profile));
             Self {
                 backend: Box::new(FileBackend::new(path)),
             }
@@ -32,11 +49,21 @@ impl CredentialStore {
         // Production build with keyring feature enabled
         #[cfg(all(not(test), feature = "keyring"))]
         {
-            let use_keyring = Config::get().get_feature_flags().auth_keyring;
+            let use_keyring = Self::keyring_selected();
 
             if use_keyring && KeyringBackend::is_available(SERVICE_NAME) {
+                let keyring =
+                    KeyringBackend::new(SERVICE_NAME, &profile_entry_name(USERNAME, profile));
+                // Credentials of a login made before switching to the keyring
+                let file = FileBackend::new(Self::default_production_path(profile));
+                if let Err(e) = migrate(&file, &keyring) {
+                    eprintln!(
+                        "Warning: Failed to move credentials into the system keyring: {}",
+                        e
+                    );
+                }
                 Self {
-                    backend: Box::new(KeyringBackend::new(SERVICE_NAME, USERNAME)),
+                    backend: Box::new(keyring),
                 }
             } else {
                 if use_keyring {
@@ -46,7 +73,7 @@ impl CredentialStore {
                     );
                 }
                 Self {
-                    backend: Box::new(FileBackend::new(Self::default_production_path())),
+                    backend: Box::new(FileBackend::new(Self::default_production_path(profile))),
                 }
             }
         }
@@ -54,23 +81,33 @@ impl CredentialStore {
         // Production build without keyring feature
         #[cfg(all(not(test), not(feature = "keyring")))]
         {
-            let use_keyring = Config::get().get_feature_flags().auth_keyring;
+            let use_keyring = Self::keyring_selected();
 
             if use_keyring {
                 // User wanted keyring but binary was built without keyring support
                 use std::io::IsTerminal;
                 if std::io::stderr().is_terminal() {
                     eprintln!(
-                        "Note: auth_keyring is enabled but this binary was built without keyring support. Using file-based storage."
+                        "Note: keyring credential storage is selected but this binary was built without keyring support. Using file-based storage."
                     );
                 }
             }
             Self {
-                backend: Box::new(FileBackend::new(Self::default_production_path())),
+                backend: Box::new(FileBackend::new(Self::default_production_path(profile))),
             }
         }
     }
 
+    /// The `credential_store` config, or the `auth_keyring` feature flag when unset
+    #[cfg(not(test))]
+    fn keyring_selected() -> bool {
+        let config = Config::get();
+        match config.credential_store() {
+            Some(store) => store == "keyring",
+            None => config.get_feature_flags().auth_keyring,
+        }
+    }
+
     /// Create a credential store with a custom backend (for testing)
     #[cfg(test)]
     pub fn with_backend(backend: Box<dyn CredentialBackend>) -> Self {
@@ -78,12 +115,12 @@ impl CredentialStore {
     }
 
     #[cfg(not(test))]
-    fn default_production_path() -> PathBuf {
+    fn default_production_path(profile: Option<&str>) -> PathBuf {
         dirs::home_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join(".git-ai")
             .join("internal")
-            .join("credentials")
+            .join(profile_entry_name("credentials", profile))
     }
 
     #[cfg(test)]
@@ -139,6 +176,30 @@ impl CredentialStore {
     }
 }
 
+/// File or keyring entry holding a profile's credentials; the default profile keeps
+/// the name used before profiles existed.
+fn profile_entry_name(base: &str, profile: Option<&str>) -> String {
+    match profile {
+        Some(profile) => format!("{}-{}", base, profile),
+        None => base.to_string(),
+    }
+}
+
+/// Move credentials from `from` into `to` unless `to` already has some; returns
+/// whether anything moved.
+#[cfg(any(test, feature = "keyring"))]
+fn migrate(from: &dyn CredentialBackend, to: &dyn CredentialBackend) -> Result<bool, String> {
+    if to.load()?.is_some() {
+        return Ok(false);
+    }
+    let Some(value) = from.load()? else {
+        return Ok(false);
+    };
+    to.store(&value)?;
+    from.clear()?;
+    Ok(true)
+}
+
 impl Default for CredentialStore {
     fn default() -> Self {
         Self::new()
@@ -158,6 +219,7 @@ mod tests {
             refresh_token: "test_refresh_token_67890".to_string(),
             access_token_expires_at: chrono::Utc::now().timestamp() + 3600,
             refresh_token_expires_at: chrono::Utc::now().timestamp() + 86400 * 90,
+            service: false,
         }
     }
 
@@ -489,4 +551,41 @@ mod tests {
         let file_store = CredentialStore::new();
         assert_eq!(file_store.backend_name(), "file");
     }
+
+    // ============= Migration Tests =============
+
+    #[test]
+    fn test_migrate_moves_file_credentials_into_keyring() {
+        let file = MockBackend::new();
+        let keyring = MockBackend::new();
+        file.store("old-login").unwrap();
+
+        assert!(migrate(&file, &keyring).unwrap());
+        assert_eq!(keyring.get_value().as_deref(), Some("old-login"));
+        assert!(!file.has_value());
+
+        // Nothing left to move
+        assert!(!migrate(&file, &keyring).unwrap());
+    }
+
+    #[test]
+    fn test_migrate_keeps_existing_keyring_credentials() {
+        let file = MockBackend::new();
+        let keyring = MockBackend::new();
+        file.store("old-login").unwrap();
+        keyring.store("current-login").unwrap();
+
+        assert!(!migrate(&file, &keyring).unwrap());
+        assert_eq!(keyring.get_value().as_deref(), Some("current-login"));
+        assert!(file.has_value());
+    }
+
+    #[test]
+    fn test_profile_entry_name() {
+        assert_eq!(profile_entry_name("credentials", None), "credentials");
+        assert_eq!(
+            profile_entry_name("credentials", Some("acme")),
+            "credentials-acme"
+        );
+    }
 }
@@ -1,9 +1,22 @@
 pub mod client;
 pub mod credential_backend;
 pub mod credentials;
+pub mod oidc;
+pub mod profiles;
 pub mod types;
 
 pub use client::OAuthClient;
 #[cfg(all(not(test), feature = "keyring"))]
 pub use credential_backend::KeyringBackend;
 pub use credentials::CredentialStore;
+
+/// Variable holding a service token, for CI jobs that can't log in interactively
+pub const TOKEN_ENV: &str = "GIT_AI_TOKEN";
+
+/// The service token in `GIT_AI_TOKEN`, if set
+pub fn env_token() -> Option<String> {
+    std::env::var(TOKEN_ENV)
+        .ok()
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+}
@@ -0,0 +1,291 @@
+//! Logging in through an organization's own OpenID Connect provider.
+//!
+//! An auth profile with an `oidc` section sends every OAuth request to that issuer
+//! instead of the git-ai API, which is expected to accept the issuer's access tokens.
+//! Endpoints are discovered from `<issuer>/.well-known/openid-configuration` unless
+//! configured. Browser logins use the authorization code flow with PKCE (RFC 7636) and
+//! a loopback redirect (RFC 8252); `login --device` uses the issuer's device flow.
+
+use crate::api::network;
+use crate::utils::debug_log;
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use url::Url;
+
+/// How long a browser login may take
+const BROWSER_LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+const DEFAULT_SCOPES: &[&str] = &["openid", "offline_access"];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OidcConfig {
+    pub issuer: String,
+    pub client_id: String,
+    /// API identifier some providers need to issue access tokens for it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audience: Option<String>,
+    /// Defaults to `openid offline_access`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub authorization_endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_authorization_endpoint: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revocation_endpoint: Option<String>,
+}
+
+/// Endpoints of the issuer, as published in its discovery document
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Endpoints {
+    pub authorization_endpoint: Option<String>,
+    pub token_endpoint: Option<String>,
+    pub device_authorization_endpoint: Option<String>,
+    pub revocation_endpoint: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct Oidc {
+    pub config: OidcConfig,
+    discovered: OnceLock<Endpoints>,
+}
+
+impl Oidc {
+    pub fn new(config: OidcConfig) -> Self {
+        Oidc {
+            config,
+            discovered: OnceLock::new(),
+        }
+    }
+
+    pub fn scope(&self) -> String {
+        if self.config.scopes.is_empty() {
+            DEFAULT_SCOPES.join(" ")
+        } else {
+            self.config.scopes.join(" ")
+        }
+    }
+
+    /// Configured endpoints, with the missing ones filled in from discovery
+    pub fn endpoints(&self) -> Result<Endpoints, String> {
+        let config = &self.config;
+        let configured = Endpoints {
+            authorization_endpoint: config.authorization_endpoint.clone(),
+            token_endpoint: config.token_endpoint.clone(),
+            device_authorization_endpoint: config.device_authorization_endpoint.clone(),
+            revocation_endpoint: config.revocation_endpoint.clone(),
+        };
+        if configured.authorization_endpoint.is_some() && configured.token_endpoint.is_some() {
+            return Ok(configured);
+        }
+        let discovered = match self.discovered.get() {
+            Some(discovered) => discovered,
+            None => {
+                let discovered = discover(&config.issuer)?;
+                self.discovered.get_or_init(|| discovered)
+            }
+        };
+        Ok(Endpoints {
+            authorization_endpoint: configured
+                .authorization_endpoint
+                .or_else(|| discovered.authorization_endpoint.clone()),
+            token_endpoint: configured
+                .token_endpoint
+                .or_else(|| discovered.token_endpoint.clone()),
+            device_authorization_endpoint: configured
+                .device_authorization_endpoint
+                .or_else(|| discovered.device_authorization_endpoint.clone()),
+            revocation_endpoint: configured
+                .revocation_endpoint
+                .or_else(|| discovered.revocation_endpoint.clone()),
+        })
+    }
+}
+
+fn discover(issuer: &str) -> Result<Endpoints, String> {
+    network::ensure_online()?;
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        issuer.trim_end_matches('/')
+    );
+    debug_log(&format!("OIDC discovery: {}", url));
+    let response = network::with_proxy(minreq::get(&url), &url)
+        .with_header(
+            "User-Agent",
+            format!("git-ai/{}", env!("CARGO_PKG_VERSION")),
+        )
+        .with_timeout(30)
+        .send()
+        .map_err(|e| format!("Failed to reach OIDC issuer: {}", e))?;
+    if response.status_code != 200 {
+        return Err(format!(
+            "OIDC discovery failed ({}) for {}",
+            response.status_code, url
+        ));
+    }
+    let body = response
+        .as_str()
+        .map_err(|e| format!("Invalid discovery response: {}", e))?;
+    serde_json::from_str(body).map_err(|e| format!("Invalid discovery document: {}", e))
+}
+
+/// A PKCE verifier and its S256 challenge
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl Pkce {
+    pub fn generate() -> Self {
+        let random: Vec<u8> = [uuid::Uuid::new_v4(), uuid::Uuid::new_v4()]
+            .iter()
+            .flat_map(|id| *id.as_bytes())
+            .collect();
+        Self::from_verifier(URL_SAFE_NO_PAD.encode(random))
+    }
+
+    pub(crate) fn from_verifier(verifier: String) -> Self {
+        let challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(verifier.as_bytes()));
+        Pkce {
+            verifier,
+            challenge,
+        }
+    }
+}
+
+/// Listener for the authorization response redirected to `http://127.0.0.1:<port>/callback`
+pub struct LoopbackRedirect {
+    listener: TcpListener,
+    pub redirect_uri: String,
+}
+
+impl LoopbackRedirect {
+    pub fn bind() -> Result<Self, String> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .map_err(|e| format!("Failed to listen for the login redirect: {}", e))?;
+        let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+        Ok(LoopbackRedirect {
+            listener,
+            redirect_uri: format!("http://127.0.0.1:{}/callback", port),
+        })
+    }
+
+    /// Wait for the redirect and return its authorization code, checking `state`
+    pub fn wait_for_code(&self, state: &str) -> Result<String, String> {
+        self.listener
+            .set_nonblocking(true)
+            .map_err(|e| e.to_string())?;
+        let deadline = Instant::now() + BROWSER_LOGIN_TIMEOUT;
+        loop {
+            let stream = match self.listener.accept() {
+                Ok((stream, _)) => stream,
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    if Instant::now() >= deadline {
+                        return Err("Timed out waiting for the browser login".to_string());
+                    }
+                    std::thread::sleep(Duration::from_millis(100));
+                    continue;
+                }
+                Err(e) => return Err(format!("Login redirect failed: {}", e)),
+            };
+            let _ = stream.set_nonblocking(false);
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(10)));
+            let mut request_line = String::new();
+            if BufReader::new(&stream)
+                .read_line(&mut request_line)
+                .is_err()
+            {
+                continue;
+            }
+            // Browsers also ask for things like /favicon.ico
+            let Some(result) = parse_redirect(&request_line, state) else {
+                let _ = write!(
+                    &stream,
+                    "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                );
+                continue;
+            };
+            let page = match &result {
+                Ok(_) => "Logged in to git-ai. You can close this window.",
+                Err(_) => "git-ai login failed. See your terminal for details.",
+            };
+            let _ = write!(
+                &stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                page.len(),
+                page
+            );
+            return result;
+        }
+    }
+}
+
+/// The authorization response in a `GET /callback?...` request line; `None` for other paths
+fn parse_redirect(request_line: &str, state: &str) -> Option<Result<String, String>> {
+    let target = request_line.split_whitespace().nth(1)?;
+    let url = Url::parse(&format!("http://127.0.0.1{}", target)).ok()?;
+    if url.path() != "/callback" {
+        return None;
+    }
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.to_string())
+    };
+    if let Some(error) = param("error") {
+        let description = param("error_description").unwrap_or_default();
+        return Some(Err(format!(
+            "Authorization failed: {} {}",
+            error, description
+        )
+        .trim()
+        .to_string()));
+    }
+    if param("state").as_deref() != Some(state) {
+        return Some(Err(
+            "Login redirect had the wrong state; please try again".to_string()
+        ));
+    }
+    Some(param("code").ok_or_else(|| "Login redirect had no authorization code".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pkce_challenge() {
+        // Example from RFC 7636, appendix B
+        let pkce = Pkce::from_verifier("dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk".to_string());
+        assert_eq!(
+            pkce.challenge,
+            "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"
+        );
+        assert_eq!(Pkce::generate().verifier.len(), 43);
+    }
+
+    #[test]
+    fn test_parse_redirect() {
+        assert_eq!(
+            parse_redirect("GET /callback?code=abc&state=s1 HTTP/1.1\r\n", "s1"),
+            Some(Ok("abc".to_string()))
+        );
+        assert!(matches!(
+            parse_redirect("GET /callback?code=abc&state=other HTTP/1.1", "s1"),
+            Some(Err(_))
+        ));
+        assert_eq!(
+            parse_redirect("GET /callback?error=access_denied&state=s1 HTTP/1.1", "s1"),
+            Some(Err("Authorization failed: access_denied".to_string()))
+        );
+        assert_eq!(parse_redirect("GET /favicon.ico HTTP/1.1", "s1"), None);
+    }
+}
@@ -0,0 +1,311 @@
+//! Named login profiles, for people who work with several git-ai organizations.
+//!
+//! Each profile has its own stored credentials and may point at its own API. The
+//! active profile is, in order: the one passed with `--profile`, the one the current
+//! repository is bound to, `GIT_AI_PROFILE`, or the first configured profile whose
+//! `repositories` match a remote of the current repository. Without any of those the
+//! unnamed default profile is used.
+//!
+//! A repository is bound with `git config git-ai.profile <name>` and/or
+//! `git config git-ai.apiBaseUrl <url>`. API requests made from a bound repository
+//! with any other profile or API are refused, so its data can't go to the wrong
+//! account by accident.
+
+use crate::auth::oidc::OidcConfig;
+use crate::config::Config;
+use crate::git::find_repository_in_path;
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+pub const PROFILE_ENV: &str = "GIT_AI_PROFILE";
+
+/// Name that refers to the unnamed profile
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Repository git config binding it to a profile
+pub const REPO_PROFILE_KEY: &str = "git-ai.profile";
+
+/// Repository git config binding it to an API
+pub const REPO_API_KEY: &str = "git-ai.apiBaseUrl";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuthProfile {
+    /// API the profile logs in to, instead of `api_base_url`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub api_base_url: Option<String>,
+    /// Remote URL globs of the repositories that use this profile
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub repositories: Vec<String>,
+    /// The organization's own identity provider, used instead of git-ai's login
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oidc: Option<OidcConfig>,
+}
+
+/// Profile and API the current repository is bound to
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RepoBinding {
+    pub profile: Option<String>,
+    pub api_base_url: Option<String>,
+}
+
+impl RepoBinding {
+    pub fn is_bound(&self) -> bool {
+        self.profile.is_some() || self.api_base_url.is_some()
+    }
+
+    /// Refuse a request by `profile` to `api_base_url` that the binding doesn't allow
+    pub fn check(&self, profile: Option<&str>, api_base_url: &str) -> Result<(), String> {
+        if let Some(bound) = &self.profile
+            && named(bound).as_deref() != profile
+        {
+            return Err(format!(
+                "This repository is bound to profile '{}' ({}), not '{}'",
+                bound,
+                REPO_PROFILE_KEY,
+                profile.unwrap_or(DEFAULT_PROFILE)
+            ));
+        }
+        if let Some(bound) = &self.api_base_url
+            && bound.trim_end_matches('/') != api_base_url.trim_end_matches('/')
+        {
+            return Err(format!(
+                "This repository is bound to {} ({}), not {}",
+                bound, REPO_API_KEY, api_base_url
+            ));
+        }
+        Ok(())
+    }
+}
+
+static SELECTED: OnceLock<Option<String>> = OnceLock::new();
+static ACTIVE: OnceLock<Option<String>> = OnceLock::new();
+static BINDING: OnceLock<RepoBinding> = OnceLock::new();
+
+pub fn validate_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid profile name '{}': use letters, digits, '-' and '_'",
+            name
+        ))
+    }
+}
+
+/// Use `name` for the rest of the process, whatever the environment or repository say.
+pub fn select(name: &str) -> Result<(), String> {
+    validate_name(name)?;
+    let _ = SELECTED.set(named(name));
+    Ok(())
+}
+
+/// Remove `--profile <name>` / `--profile=<name>` from `args` and select that profile.
+pub fn take_flag(args: &[String]) -> Result<Vec<String>, String> {
+    let mut rest = Vec::with_capacity(args.len());
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--profile" => {
+                let name = args.get(i + 1).ok_or("--profile requires a profile name")?;
+                select(name)?;
+                i += 1;
+            }
+            arg if arg.starts_with("--profile=") => select(&arg["--profile=".len()..])?,
+            arg => rest.push(arg.to_string()),
+        }
+        i += 1;
+    }
+    Ok(rest)
+}
+
+/// The active profile's name; `None` for the default profile
+pub fn active() -> Option<String> {
+    ACTIVE
+        .get_or_init(|| {
+            if let Some(selected) = SELECTED.get() {
+                return selected.clone();
+            }
+            if let Some(bound) = &binding().profile {
+                return named(bound);
+            }
+            if let Ok(name) = std::env::var(PROFILE_ENV)
+                && !name.is_empty()
+            {
+                if let Err(e) = validate_name(&name) {
+                    crate::logging::warn(&format!("Ignoring {}: {}", PROFILE_ENV, e));
+                    return None;
+                }
+                return named(&name);
+            }
+            let profiles = Config::get().auth_profiles();
+            if profiles.is_empty() {
+                return None;
+            }
+            let current_dir = std::env::current_dir().ok()?;
+            let repo = find_repository_in_path(&current_dir.to_string_lossy()).ok()?;
+            let remotes = repo.remotes_with_urls().ok()?;
+            profile_for_remotes(profiles, &remotes)
+        })
+        .clone()
+}
+
+/// Binding of the repository in the current directory, read once
+pub fn binding() -> &'static RepoBinding {
+    BINDING.get_or_init(|| {
+        let Some(repo) = std::env::current_dir()
+            .ok()
+            .and_then(|dir| find_repository_in_path(&dir.to_string_lossy()).ok())
+        else {
+            return RepoBinding::default();
+        };
+        let value = |key: &str| {
+            repo.config_get_str(key)
+                .ok()
+                .flatten()
+                .map(|value| value.trim().to_string())
+                .filter(|value| !value.is_empty())
+        };
+        let profile = value(REPO_PROFILE_KEY).filter(|name| match validate_name(name) {
+            Ok(()) => true,
+            Err(e) => {
+                crate::logging::warn(&format!("Ignoring {}: {}", REPO_PROFILE_KEY, e));
+                false
+            }
+        });
+        RepoBinding {
+            profile,
+            api_base_url: value(REPO_API_KEY),
+        }
+    })
+}
+
+/// Refuse API requests to `api_base_url` that the current repository's binding doesn't allow
+pub fn check_binding(api_base_url: &str) -> Result<(), String> {
+    binding().check(active().as_deref(), api_base_url)
+}
+
+/// API base URL of the active profile
+pub fn api_base_url() -> String {
+    api_base_url_for(active().as_deref())
+}
+
+/// API base URL of a profile, `None` being the default one
+pub fn api_base_url_for(profile: Option<&str>) -> String {
+    let config = Config::get();
+    profile
+        .and_then(|name| config.auth_profiles().get(name)?.api_base_url.clone())
+        .unwrap_or_else(|| config.api_base_url().to_string())
+}
+
+/// OIDC issuer of a profile, `None` being the default one
+pub fn oidc_for(profile: Option<&str>) -> Option<OidcConfig> {
+    Config::get().auth_profiles().get(profile?)?.oidc.clone()
+}
+
+/// The default profile, the configured ones, and the active one if it isn't configured
+pub fn known() -> Vec<Option<String>> {
+    let mut known: Vec<Option<String>> = vec![None];
+    known.extend(Config::get().auth_profiles().keys().cloned().map(Some));
+    if let Some(active) = active()
+        && !known.contains(&Some(active.clone()))
+    {
+        known.push(Some(active));
+    }
+    known
+}
+
+fn named(name: &str) -> Option<String> {
+    (name != DEFAULT_PROFILE).then(|| name.to_string())
+}
+
+fn profile_for_remotes(
+    profiles: &BTreeMap<String, AuthProfile>,
+    remotes: &[(String, String)],
+) -> Option<String> {
+    profiles
+        .iter()
+        .find(|(_, profile)| {
+            profile.repositories.iter().any(|pattern| {
+                Pattern::new(pattern)
+                    .is_ok_and(|pattern| remotes.iter().any(|(_, url)| pattern.matches(url)))
+            })
+        })
+        .and_then(|(name, _)| named(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_name() {
+        assert!(validate_name("work").is_ok());
+        assert!(validate_name("customer_a-2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../evil").is_err());
+        assert!(validate_name("two words").is_err());
+    }
+
+    #[test]
+    fn test_repo_binding_check() {
+        assert!(
+            RepoBinding::default()
+                .check(Some("any"), "https://x")
+                .is_ok()
+        );
+
+        let work = RepoBinding {
+            profile: Some("work".to_string()),
+            api_base_url: Some("https://git-ai.acme.dev/".to_string()),
+        };
+        assert!(work.check(Some("work"), "https://git-ai.acme.dev").is_ok());
+        assert_eq!(
+            work.check(None, "https://git-ai.acme.dev"),
+            Err(
+                "This repository is bound to profile 'work' (git-ai.profile), not 'default'"
+                    .to_string()
+            )
+        );
+        assert!(work.check(Some("work"), "https://usegitai.com").is_err());
+
+        let default = RepoBinding {
+            profile: Some(DEFAULT_PROFILE.to_string()),
+            api_base_url: None,
+        };
+        assert!(default.check(None, "https://usegitai.com").is_ok());
+        assert!(default.check(Some("work"), "https://usegitai.com").is_err());
+    }
+
+    #[test]
+    fn test_profile_for_remotes() {
+        let profiles: BTreeMap<String, AuthProfile> = [
+            (
+                "acme".to_string(),
+                AuthProfile {
+                    api_base_url: Some("https://git-ai.acme.dev".to_string()),
+                    repositories: vec!["https://github.com/acme/*".to_string()],
+                    oidc: None,
+                },
+            ),
+            ("personal".to_string(), AuthProfile::default()),
+        ]
+        .into_iter()
+        .collect();
+        let remotes = |url: &str| vec![("origin".to_string(), url.to_string())];
+
+        assert_eq!(
+            profile_for_remotes(&profiles, &remotes("https://github.com/acme/api")),
+            Some("acme".to_string())
+        );
+        assert_eq!(
+            profile_for_remotes(&profiles, &remotes("https://github.com/me/blog")),
+            None
+        );
+    }
+}
@@ -13,6 +13,9 @@ pub struct StoredCredentials {
     pub access_token_expires_at: i64,
     /// Unix timestamp when the refresh token expires
     pub refresh_token_expires_at: i64,
+    /// A service token from `git-ai login --token`: it never expires or refreshes
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub service: bool,
 }
 
 /// Custom Debug implementation that redacts sensitive token values
@@ -23,11 +26,23 @@ impl fmt::Debug for StoredCredentials {
             .field("refresh_token", &"[REDACTED]")
             .field("access_token_expires_at", &self.access_token_expires_at)
             .field("refresh_token_expires_at", &self.refresh_token_expires_at)
+            .field("service", &self.service)
             .finish()
     }
 }
 
 impl StoredCredentials {
+    /// Credentials holding a service token, for CI and other non-interactive use
+    pub fn service_token(token: &str) -> Self {
+        StoredCredentials {
+            access_token: token.to_string(),
+            refresh_token: String::new(),
+            access_token_expires_at: i64::MAX,
+            refresh_token_expires_at: i64::MAX,
+            service: true,
+        }
+    }
+
     /// Check if the access token is expired or will expire within the given buffer (seconds)
     pub fn is_access_token_expired(&self, buffer_secs: i64) -> bool {
         let now = chrono::Utc::now().timestamp();
@@ -63,6 +78,19 @@ pub struct TokenResponse {
     pub refresh_expires_in: u64,
 }
 
+/// Response from an OIDC issuer's token endpoint, which may leave out the refresh
+/// token and lifetimes
+#[derive(Debug, Deserialize)]
+pub struct IssuerTokenResponse {
+    pub access_token: String,
+    #[serde(default)]
+    pub expires_in: Option<u64>,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub refresh_expires_in: Option<u64>,
+}
+
 /// OAuth error response
 #[derive(Debug, Deserialize)]
 pub struct OAuthError {
@@ -80,6 +108,7 @@ mod tests {
             refresh_token: "test_refresh_token".to_string(),
             access_token_expires_at: access_expires_at,
             refresh_token_expires_at: refresh_expires_at,
+            service: false,
         }
     }
 
@@ -199,4 +228,19 @@ mod tests {
         assert!(debug_output.contains("1234567890"));
         assert!(debug_output.contains("9876543210"));
     }
+
+    #[test]
+    fn test_service_flag_is_optional() {
+        let stored = r#"{"access_token":"a","refresh_token":"r","access_token_expires_at":1,"refresh_token_expires_at":2}"#;
+        let creds: StoredCredentials = serde_json::from_str(stored).unwrap();
+        assert!(!creds.service);
+        assert!(!serde_json::to_string(&creds).unwrap().contains("service"));
+
+        let service = StoredCredentials::service_token("svc");
+        assert!(!service.is_access_token_expired(300));
+        assert!(!service.is_refresh_token_expired());
+        let round_trip: StoredCredentials =
+            serde_json::from_str(&serde_json::to_string(&service).unwrap()).unwrap();
+        assert!(round_trip.service);
+    }
 }
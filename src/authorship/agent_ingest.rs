@@ -0,0 +1,257 @@
+//! Attribute commits made by cloud agents (Devin, the Copilot coding agent, cloud
+//! Claude) from the webhook payloads or session exports describing them.
+//!
+//! Those agents push commits from their own machines, so no checkpoint ever runs. Once
+//! the commits are fetched, every line they add is attributed to the agent, with the
+//! session and model from the payload when it has them.
+//!
+//! Payloads are read loosely: GitHub `push` / `pull_request` webhook bodies and flat
+//! `{session_id, model, branch, base, commits}` exports are both understood.
+
+use crate::authorship::history_import::{
+    CommitInfo, commits_with_notes, list_commits, synthesize_authorship_log,
+};
+use crate::authorship::working_log::AgentId;
+use crate::error::GitAiError;
+use crate::git::refs::notes_add;
+use crate::git::repository::Repository;
+use serde_json::Value;
+
+/// Agents payloads can be ingested for, and the tool their commits are attributed to
+pub const PROVIDERS: &[(&str, &str)] = &[
+    ("devin", "devin"),
+    ("copilot", "github-copilot"),
+    ("claude", "claude"),
+    ("generic", ""),
+];
+
+/// What a payload says an agent produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AgentWork {
+    pub agent_id: AgentId,
+    /// Commits named by the payload, newest last
+    pub commits: Vec<String>,
+    /// Branch the agent pushed to
+    pub branch: Option<String>,
+    /// Commit or branch the agent's work started from
+    pub base: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct IngestSummary {
+    pub attributed: Vec<String>,
+    pub already_attributed: Vec<String>,
+    /// Commits named by the payload that aren't in the repository (yet)
+    pub missing: Vec<String>,
+}
+
+pub fn parse_payload(provider: &str, payload: &Value) -> Result<AgentWork, GitAiError> {
+    let Some((_, default_tool)) = PROVIDERS.iter().find(|(name, _)| *name == provider) else {
+        return Err(GitAiError::Generic(format!(
+            "Unknown provider: {} (expected one of {})",
+            provider,
+            PROVIDERS
+                .iter()
+                .map(|(name, _)| *name)
+                .collect::<Vec<_>>()
+                .join(", ")
+        )));
+    };
+
+    let str_at = |pointers: &[&str]| {
+        pointers
+            .iter()
+            .find_map(|pointer| payload.pointer(pointer).and_then(Value::as_str))
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+
+    let tool = match str_at(&["/tool"]) {
+        Some(tool) => tool,
+        None if !default_tool.is_empty() => default_tool.to_string(),
+        None => {
+            return Err(GitAiError::Generic(
+                "generic payloads must name their tool".to_string(),
+            ));
+        }
+    };
+
+    let mut commits: Vec<String> = payload
+        .get("commits")
+        .and_then(Value::as_array)
+        .map(|commits| {
+            commits
+                .iter()
+                .filter_map(|commit| {
+                    commit
+                        .as_str()
+                        .or_else(|| commit.get("id").and_then(Value::as_str))
+                        .or_else(|| commit.get("sha").and_then(Value::as_str))
+                })
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    if let Some(head) = str_at(&["/head_commit/id", "/pull_request/head/sha", "/head_sha"])
+        && !commits.contains(&head)
+    {
+        commits.push(head);
+    }
+
+    let branch = str_at(&["/branch", "/pull_request/head/ref", "/ref"])
+        .map(|branch| branch.trim_start_matches("refs/heads/").to_string());
+    let base = str_at(&["/base", "/pull_request/base/sha", "/before"])
+        .filter(|base| base.chars().any(|c| c != '0'));
+    let session_id = str_at(&["/session_id", "/session/id", "/pull_request/node_id"]);
+    let model = str_at(&["/model", "/session/model"]).unwrap_or_else(|| "unknown".to_string());
+
+    if commits.is_empty() && branch.is_none() {
+        return Err(GitAiError::Generic(
+            "payload names no commits or branch".to_string(),
+        ));
+    }
+
+    let id = session_id.unwrap_or_else(|| {
+        format!(
+            "ingested-{}",
+            commits
+                .last()
+                .or(branch.as_ref())
+                .cloned()
+                .unwrap_or_default()
+        )
+    });
+    Ok(AgentWork {
+        agent_id: AgentId { tool, id, model },
+        commits,
+        branch,
+        base,
+    })
+}
+
+/// Attribute the commits of `work` that are in `repo` and have no authorship yet.
+pub fn ingest(
+    repo: &Repository,
+    work: &AgentWork,
+    dry_run: bool,
+) -> Result<IngestSummary, GitAiError> {
+    let mut summary = IngestSummary::default();
+    let mut found: Vec<CommitInfo> = Vec::new();
+
+    if work.commits.is_empty() {
+        // Only a branch: take what it has on top of its base
+        let branch = work.branch.as_deref().unwrap_or_default();
+        let tip = resolve_branch(repo, branch).ok_or_else(|| {
+            GitAiError::Generic(format!("Branch {} has not been fetched", branch))
+        })?;
+        let base = work.base.clone().ok_or_else(|| {
+            GitAiError::Generic(format!(
+                "payload for branch {} names no commits or base",
+                branch
+            ))
+        })?;
+        found = list_commits(repo, &[tip, format!("^{}", base)])?;
+    } else {
+        for sha in &work.commits {
+            let exists = repo
+                .git(&["cat-file", "-e", &format!("{}^{{commit}}", sha)])
+                .is_ok();
+            if !exists {
+                summary.missing.push(sha.clone());
+                continue;
+            }
+            found.extend(list_commits(repo, &["--no-walk".to_string(), sha.clone()])?);
+        }
+    }
+
+    let annotated = commits_with_notes(repo);
+    for commit in found {
+        if annotated.contains(&commit.sha) {
+            summary.already_attributed.push(commit.sha);
+            continue;
+        }
+        if !dry_run {
+            let log = synthesize_authorship_log(
+                repo,
+                &commit,
+                100,
+                std::slice::from_ref(&work.agent_id),
+            )?;
+            let note = log.serialize_to_string().map_err(|_| {
+                GitAiError::Generic("Failed to serialize authorship log".to_string())
+            })?;
+            notes_add(repo, &commit.sha, &note)?;
+        }
+        summary.attributed.push(commit.sha);
+    }
+    Ok(summary)
+}
+
+fn resolve_branch(repo: &Repository, branch: &str) -> Option<String> {
+    [
+        format!("refs/remotes/origin/{}", branch),
+        format!("refs/heads/{}", branch),
+    ]
+    .into_iter()
+    .find_map(|reference| {
+        repo.git(&["rev-parse", "--verify", "--quiet", &reference])
+            .ok()
+            .map(|sha| sha.trim().to_string())
+            .filter(|sha| !sha.is_empty())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_github_push_webhook() {
+        let payload = json!({
+            "ref": "refs/heads/copilot/fix-42",
+            "before": "0000000000000000000000000000000000000000",
+            "commits": [{"id": "aaa"}, {"id": "bbb"}],
+            "head_commit": {"id": "bbb"},
+            "sender": {"login": "Copilot"},
+        });
+        let work = parse_payload("copilot", &payload).unwrap();
+        assert_eq!(work.agent_id.tool, "github-copilot");
+        assert_eq!(work.agent_id.model, "unknown");
+        assert_eq!(work.agent_id.id, "ingested-bbb");
+        assert_eq!(work.commits, vec!["aaa", "bbb"]);
+        assert_eq!(work.branch.as_deref(), Some("copilot/fix-42"));
+        // An all-zero `before` means the branch is new
+        assert_eq!(work.base, None);
+    }
+
+    #[test]
+    fn test_parse_session_export() {
+        let payload = json!({
+            "session_id": "devin-123",
+            "model": "claude-sonnet-4-5",
+            "branch": "devin/feature",
+            "base": "main",
+        });
+        let work = parse_payload("devin", &payload).unwrap();
+        assert_eq!(
+            work.agent_id,
+            AgentId {
+                tool: "devin".to_string(),
+                id: "devin-123".to_string(),
+                model: "claude-sonnet-4-5".to_string(),
+            }
+        );
+        assert!(work.commits.is_empty());
+        assert_eq!(work.base.as_deref(), Some("main"));
+    }
+
+    #[test]
+    fn test_parse_rejects_incomplete_payloads() {
+        assert!(parse_payload("nope", &json!({"commits": ["a"]})).is_err());
+        assert!(parse_payload("generic", &json!({"commits": ["a"]})).is_err());
+        assert!(parse_payload("claude", &json!({"session_id": "s"})).is_err());
+        let work = parse_payload("generic", &json!({"tool": "acme", "commits": ["a"]})).unwrap();
+        assert_eq!(work.agent_id.tool, "acme");
+    }
+}
@@ -0,0 +1,131 @@
+//! Pseudonyms for the people, paths and repositories in the output of `export`,
+//! `report` and `sync-prompts --org` when `--anonymize` is given, so that AI adoption
+//! data can be shared with vendors or researchers without naming people or showing how
+//! the code is laid out.
+//!
+//! A value maps to the same pseudonym on every run: a keyed SHA-256 of it, so that the
+//! pseudonyms can't be reversed by hashing guessed names. The key is
+//! `GIT_AI_ANONYMIZE_KEY` when set (share it to get the same pseudonyms on every
+//! machine of an organization), otherwise one generated on first use and kept in
+//! `~/.git-ai/internal/anonymize_key`.
+//!
+//! - authors become `author-<hash>`, compared case-insensitively
+//! - paths become `file-<hash>`, keeping only their extension so that languages can
+//!   still be told apart; directories are dropped along with the rest of the path
+//! - repositories become `repo-<hash>`
+//! - timestamps are rounded down to the start of their UTC day
+
+use crate::error::GitAiError;
+use sha2::{Digest, Sha256};
+
+/// Environment variable holding a shared key
+pub const KEY_ENV: &str = "GIT_AI_ANONYMIZE_KEY";
+
+/// Length of a timestamp bucket, in seconds
+pub const TIMESTAMP_BUCKET_SECS: i64 = 24 * 3600;
+
+#[derive(Debug, Clone)]
+pub struct Anonymizer {
+    key: String,
+}
+
+impl Anonymizer {
+    /// The anonymizer of `GIT_AI_ANONYMIZE_KEY`, or of this machine's key
+    pub fn load() -> Result<Anonymizer, GitAiError> {
+        if let Ok(key) = std::env::var(KEY_ENV)
+            && !key.is_empty()
+        {
+            return Ok(Anonymizer::with_key(&key));
+        }
+        let path = crate::config::anonymize_key_path().ok_or_else(|| {
+            GitAiError::Generic(format!(
+                "Could not determine where to keep the anonymization key, set {}",
+                KEY_ENV
+            ))
+        })?;
+        if let Ok(key) = std::fs::read_to_string(&path)
+            && !key.trim().is_empty()
+        {
+            return Ok(Anonymizer::with_key(key.trim()));
+        }
+        let key = format!(
+            "{}{}",
+            uuid::Uuid::new_v4().simple(),
+            uuid::Uuid::new_v4().simple()
+        );
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, &key)?;
+        Ok(Anonymizer::with_key(&key))
+    }
+
+    pub fn with_key(key: &str) -> Anonymizer {
+        Anonymizer {
+            key: key.to_string(),
+        }
+    }
+
+    fn pseudonym(&self, kind: &str, value: &str, len: usize) -> String {
+        let mut hasher = Sha256::new();
+        for part in [self.key.as_str(), kind, value] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        let digest = format!("{:x}", hasher.finalize());
+        format!("{}-{}", kind, &digest[..len])
+    }
+
+    pub fn author(&self, author: &str) -> String {
+        self.pseudonym("author", &author.trim().to_lowercase(), 10)
+    }
+
+    pub fn path(&self, path: &str) -> String {
+        let name = path.rsplit('/').next().unwrap_or(path);
+        let pseudonym = self.pseudonym("file", path, 12);
+        match name.rsplit_once('.') {
+            Some((stem, extension)) if !stem.is_empty() && extension.len() <= 10 => {
+                format!("{}.{}", pseudonym, extension)
+            }
+            _ => pseudonym,
+        }
+    }
+
+    pub fn repo(&self, repo: &str) -> String {
+        self.pseudonym("repo", repo, 10)
+    }
+
+    pub fn timestamp(&self, timestamp: i64) -> i64 {
+        timestamp.div_euclid(TIMESTAMP_BUCKET_SECS) * TIMESTAMP_BUCKET_SECS
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pseudonyms_are_stable_and_keyed() {
+        let a = Anonymizer::with_key("key-a");
+        let b = Anonymizer::with_key("key-a");
+        let other = Anonymizer::with_key("key-b");
+
+        assert_eq!(a.author("Alice Smith"), b.author(" alice smith"));
+        assert!(a.author("Alice Smith").starts_with("author-"));
+        assert_ne!(a.author("Alice Smith"), other.author("Alice Smith"));
+        assert_ne!(a.author("Alice Smith"), a.author("Bob"));
+
+        let path = a.path("src/payments/charge.rs");
+        assert!(
+            path.starts_with("file-") && path.ends_with(".rs"),
+            "{}",
+            path
+        );
+        assert!(!path.contains("payments"));
+        assert_ne!(path, a.path("src/billing/charge.rs"));
+        assert!(!a.path("Makefile").contains('.'));
+
+        assert_eq!(a.timestamp(1_700_000_000), 1_699_920_000);
+        assert_eq!(a.timestamp(1_699_920_000), 1_699_920_000);
+    }
+}
@@ -0,0 +1,219 @@
+//! Background finalization of commit authorship (`async_post_commit`).
+//!
+//! Finalizing a commit reads its whole working log and writes the note, which adds
+//! noticeable latency to every commit in large repos. With `async_post_commit` enabled
+//! the commit hook only records an intent (one small file per commit) and spawns
+//! `git-ai finalize-commits` to do the work. Anything that needs the notes first —
+//! `git-ai status`, the next proxied git command or checkpoint — drains the queue
+//! itself, waiting for a running finalizer to finish.
+//!
+//! The post-commit stats chart is not printed in this mode.
+
+use crate::authorship::post_commit::post_commit;
+use crate::error::GitAiError;
+use crate::git::refs::show_authorship_note;
+use crate::git::repository::Repository;
+use crate::utils::debug_log;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// A lock older than this belongs to a finalizer that died
+const STALE_LOCK_AGE: Duration = Duration::from_secs(120);
+/// How long a caller that needs the notes waits for a running finalizer
+const LOCK_WAIT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FinalizeIntent {
+    pub base_commit: Option<String>,
+    pub commit_sha: String,
+    pub human_author: String,
+}
+
+fn queue_dir(repo: &Repository) -> PathBuf {
+    repo.storage.repo_path.join("ai").join("pending_commits")
+}
+
+/// Queue `intent` for the background finalizer.
+pub fn record_intent(repo: &Repository, intent: &FinalizeIntent) -> Result<(), GitAiError> {
+    let dir = queue_dir(repo);
+    fs::create_dir_all(&dir)?;
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    // Named by time so the queue drains in commit order: each commit's working log is
+    // produced by finalizing the one before it
+    let path = dir.join(format!("{:024}-{}.json", nanos, intent.commit_sha));
+    let tmp_path = path.with_extension("tmp");
+    fs::write(&tmp_path, serde_json::to_vec(intent)?)?;
+    fs::rename(&tmp_path, &path)?;
+    Ok(())
+}
+
+/// Start `git-ai finalize-commits` for this repository and return immediately.
+pub fn spawn_background_finalizer(repo: &Repository) {
+    let (Ok(exe), Ok(workdir)) = (crate::utils::current_git_ai_exe(), repo.workdir()) else {
+        return;
+    };
+    let spawned = Command::new(exe)
+        .arg("finalize-commits")
+        .current_dir(workdir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn();
+    if let Err(e) = spawned {
+        debug_log(&format!("Failed to spawn background finalizer: {}", e));
+    }
+}
+
+/// Finalize every queued commit, oldest first. Returns how many intents were handled.
+///
+/// With `wait`, blocks while another process holds the queue so that on return every
+/// commit queued before the call has its note. Without it, gives up instead; whoever
+/// holds the queue keeps draining until it is empty.
+pub fn finalize_pending_commits(repo: &Repository, wait: bool) -> Result<usize, GitAiError> {
+    let dir = queue_dir(repo);
+    if !dir.is_dir() {
+        return Ok(0);
+    }
+    let Some(_lock) = QueueLock::acquire(dir.join("lock"), wait)? else {
+        return Ok(0);
+    };
+
+    let mut handled = 0;
+    loop {
+        let mut intents: Vec<PathBuf> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        if intents.is_empty() {
+            return Ok(handled);
+        }
+        intents.sort();
+
+        for path in intents {
+            match fs::read(&path)
+                .map_err(GitAiError::from)
+                .and_then(|bytes| Ok(serde_json::from_slice::<FinalizeIntent>(&bytes)?))
+            {
+                Ok(intent) => finalize(repo, intent),
+                Err(e) => debug_log(&format!("Dropping unreadable intent {:?}: {}", path, e)),
+            }
+            fs::remove_file(&path)?;
+            handled += 1;
+        }
+    }
+}
+
+fn finalize(repo: &Repository, intent: FinalizeIntent) {
+    // Already done, e.g. by the reconciliation pass
+    if show_authorship_note(repo, &intent.commit_sha).is_some() {
+        return;
+    }
+    debug_log(&format!("Finalizing queued commit {}", intent.commit_sha));
+    if let Err(e) = post_commit(
+        repo,
+        intent.base_commit,
+        intent.commit_sha.clone(),
+        intent.human_author,
+        true,
+    ) {
+        debug_log(&format!(
+            "Failed to finalize queued commit {}: {}",
+            intent.commit_sha, e
+        ));
+    }
+}
+
+/// Exclusive ownership of the queue, released on drop.
+struct QueueLock {
+    path: PathBuf,
+}
+
+impl QueueLock {
+    fn acquire(path: PathBuf, wait: bool) -> Result<Option<QueueLock>, GitAiError> {
+        let started = Instant::now();
+        loop {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(_) => return Ok(Some(QueueLock { path })),
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e.into()),
+            }
+
+            let stale = fs::metadata(&path)
+                .and_then(|m| m.modified())
+                .ok()
+                .and_then(|modified| modified.elapsed().ok())
+                .is_some_and(|age| age > STALE_LOCK_AGE);
+            if stale {
+                let _ = fs::remove_file(&path);
+                continue;
+            }
+            if !wait {
+                return Ok(None);
+            }
+            if started.elapsed() > LOCK_WAIT {
+                return Err(GitAiError::Generic(
+                    "Timed out waiting for the background finalizer".to_string(),
+                ));
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+impl Drop for QueueLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::refs::get_authorship;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_queued_commits_are_finalized_in_order() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        assert_eq!(finalize_pending_commits(repo, true).unwrap(), 0);
+
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo
+            .trigger_checkpoint_with_ai("Claude", None, None)
+            .unwrap();
+        tmp_repo.git_command(&["commit", "-m", "First"]).unwrap();
+        let first = tmp_repo.head_commit_sha().unwrap();
+        record_intent(
+            repo,
+            &FinalizeIntent {
+                base_commit: None,
+                commit_sha: first.clone(),
+                human_author: "Test User <test@example.com>".to_string(),
+            },
+        )
+        .unwrap();
+
+        // Another process owns the queue: a non-waiting caller leaves it alone
+        let lock = QueueLock::acquire(queue_dir(repo).join("lock"), false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(finalize_pending_commits(repo, false).unwrap(), 0);
+        drop(lock);
+
+        assert_eq!(finalize_pending_commits(repo, true).unwrap(), 1);
+        let log = get_authorship(repo, &first).unwrap();
+        assert_eq!(log.attestations[0].file_path, "a.txt");
+        assert_eq!(finalize_pending_commits(repo, true).unwrap(), 0);
+    }
+}
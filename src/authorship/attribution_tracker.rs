@@ -3,6 +3,7 @@
 //! This library maintains attribution ranges as files are edited, preserving
 //! authorship information even through moves, edits, and whitespace changes.
 
+use crate::authorship::diff_cache::{CachedDiff, DiffCache};
 use crate::authorship::imara_diff_utils::{ByteDiff, ByteDiffOp, DiffOp, capture_diff_slices};
 use crate::authorship::move_detection::{DeletedLine, InsertedLine, detect_moves};
 use crate::authorship::working_log::CheckpointKind;
@@ -274,9 +275,69 @@ impl Default for AttributionConfig {
     }
 }
 
+impl DiffComputation {
+    fn to_cached(&self) -> CachedDiff {
+        CachedDiff {
+            ops: self
+                .diffs
+                .iter()
+                .map(|diff| {
+                    let op = match diff.op() {
+                        ByteDiffOp::Equal => 'E',
+                        ByteDiffOp::Delete => 'D',
+                        ByteDiffOp::Insert => 'I',
+                    };
+                    (op, diff.data().len())
+                })
+                .collect(),
+            substantive_new_ranges: self.substantive_new_ranges.clone(),
+        }
+    }
+
+    /// Rebuild the diff from a cached one, taking the bytes from the contents; `None`
+    /// when it doesn't fit them
+    fn from_cached(cached: CachedDiff, old_content: &str, new_content: &str) -> Option<Self> {
+        let (old, new) = (old_content.as_bytes(), new_content.as_bytes());
+        let (mut old_pos, mut new_pos) = (0, 0);
+        let mut diffs = Vec::with_capacity(cached.ops.len());
+        for (op, len) in cached.ops {
+            match op {
+                'E' => {
+                    diffs.push(ByteDiff::new(
+                        ByteDiffOp::Equal,
+                        old.get(old_pos..old_pos + len)?,
+                    ));
+                    old_pos += len;
+                    new_pos += len;
+                }
+                'D' => {
+                    diffs.push(ByteDiff::new(
+                        ByteDiffOp::Delete,
+                        old.get(old_pos..old_pos + len)?,
+                    ));
+                    old_pos += len;
+                }
+                'I' => {
+                    diffs.push(ByteDiff::new(
+                        ByteDiffOp::Insert,
+                        new.get(new_pos..new_pos + len)?,
+                    ));
+                    new_pos += len;
+                }
+                _ => return None,
+            }
+        }
+        (old_pos == old.len() && new_pos == new.len()).then_some(DiffComputation {
+            diffs,
+            substantive_new_ranges: cached.substantive_new_ranges,
+        })
+    }
+}
+
 /// Main attribution tracker
 pub struct AttributionTracker {
     config: AttributionConfig,
+    diff_cache: Option<DiffCache>,
 }
 
 impl AttributionTracker {
@@ -284,13 +345,47 @@ impl AttributionTracker {
     pub fn new() -> Self {
         AttributionTracker {
             config: AttributionConfig::default(),
+            diff_cache: None,
         }
     }
 
     /// Create a new attribution tracker with custom configuration
     #[allow(dead_code)]
     pub fn with_config(config: AttributionConfig) -> Self {
-        AttributionTracker { config }
+        AttributionTracker {
+            config,
+            diff_cache: None,
+        }
+    }
+
+    /// Reuse the diffs already computed between the same two contents from `cache`
+    pub fn with_diff_cache(mut self, cache: DiffCache) -> Self {
+        self.diff_cache = Some(cache);
+        self
+    }
+
+    fn compute_diffs_cached(
+        &self,
+        old_content: &str,
+        new_content: &str,
+    ) -> Result<DiffComputation, GitAiError> {
+        let Some((cache, key)) = self
+            .diff_cache
+            .as_ref()
+            .and_then(|cache| Some((cache, DiffCache::key(old_content, new_content)?)))
+        else {
+            return self.compute_diffs(old_content, new_content);
+        };
+        if let Some(computation) = cache
+            .get(&key)
+            .and_then(|cached| DiffComputation::from_cached(cached, old_content, new_content))
+        {
+            debug_log("[BENCHMARK] compute_diffs served from the diff cache");
+            return Ok(computation);
+        }
+        let computation = self.compute_diffs(old_content, new_content)?;
+        cache.put(&key, &computation.to_cached());
+        Ok(computation)
     }
 
     fn compute_diffs(
@@ -499,7 +594,7 @@ impl AttributionTracker {
         ts: u128,
     ) -> Result<Vec<Attribution>, GitAiError> {
         // Phase 1: Compute diff
-        let diff_result = self.compute_diffs(old_content, new_content)?;
+        let diff_result = self.compute_diffs_cached(old_content, new_content)?;
 
         // Phase 2: Build deletion and insertion catalogs
         let (deletions, insertions) = self.build_diff_catalog(&diff_result.diffs);
@@ -2258,4 +2353,30 @@ mod tests {
         assert_eq!(ai_block.start_line, 2);
         assert_eq!(ai_block.end_line, 17);
     }
+
+    #[test]
+    fn diff_cache_gives_the_same_attributions() {
+        let dir = tempfile::tempdir().unwrap();
+        let cached =
+            AttributionTracker::new().with_diff_cache(DiffCache::new(dir.path().to_path_buf()));
+        let old: String = (0..300)
+            .map(|i| format!("let value_{} = {};\n", i, i))
+            .collect();
+        let new = old.replace("let value_150 = 150;", "let value_150 = compute(150);");
+        let old_attrs = vec![Attribution::new(0, old.len(), "Alice".into(), TEST_TS)];
+
+        let expected = AttributionTracker::new()
+            .update_attributions(&old, &new, &old_attrs, "Bob", TEST_TS + 1)
+            .unwrap();
+        for _ in 0..2 {
+            let updated = cached
+                .update_attributions(&old, &new, &old_attrs, "Bob", TEST_TS + 1)
+                .unwrap();
+            assert_eq!(updated, expected);
+        }
+        assert!(dir.path().read_dir().unwrap().next().is_some());
+
+        let computation = cached.compute_diffs(&old, &new).unwrap();
+        assert!(DiffComputation::from_cached(computation.to_cached(), &old, &old).is_none());
+    }
 }
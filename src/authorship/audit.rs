@@ -0,0 +1,286 @@
+//! The audit trail `git-ai audit export` writes as evidence of how AI authorship was
+//! recorded and checked: one JSON record per line, each chained to the one before it.
+//!
+//! Records are, per commit of the range and oldest first:
+//! - `reclassification`: an edit `review-pending` confirmed as AI or dismissed to the
+//!   human before the commit, from the local audit log
+//! - `attribution`: what the commit's authorship note credits to each tool and model
+//!   (`attributed: false` when it has none)
+//! - `policy_evaluation`: the commit checked against `push_policy` and
+//!   `.git-ai/policy.toml`, and the violations found
+//!
+//! An export starts with a `header` record and may end with a `seal`, whose
+//! `signature` signs its `prev_hash` with the git signing key.
+//!
+//! Every record has a `seq` and the `prev_hash` of the record before it (zeros for the
+//! first), and ends with its `hash`: the SHA-256 of the line as written up to the
+//! `,"hash":` that precedes it. Editing, dropping or reordering any record thus breaks
+//! every hash after it, and a file can only grow: exporting into an existing file
+//! continues its chain and skips the commits it already records.
+
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::policy;
+use crate::error::GitAiError;
+use crate::git::repo_storage::RepoStorage;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::io::Write;
+
+/// Version of the record format
+pub const AUDIT_FORMAT_VERSION: u32 = 1;
+
+/// `prev_hash` of the first record
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuditEvent {
+    Header {
+        format: String,
+        version: u32,
+        generator: String,
+        repository: String,
+        range: String,
+    },
+    Reclassification(Reclassification),
+    Attribution {
+        commit: String,
+        subject: String,
+        author: String,
+        attributed: bool,
+        ai_lines: u32,
+        tools: Vec<ToolLines>,
+        /// Files with AI-authored lines
+        files: Vec<String>,
+        /// Of those, the files a human checkpoint touched after the last AI edit
+        human_checked_files: Vec<String>,
+    },
+    PolicyEvaluation {
+        commit: String,
+        checked_against: String,
+        passed: bool,
+        violations: Vec<String>,
+    },
+    Seal {
+        format: String,
+        signature: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolLines {
+    pub tool: String,
+    pub model: String,
+    pub lines: u32,
+}
+
+/// An edit `review-pending` confirmed or dismissed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Reclassification {
+    /// Unix seconds
+    pub reviewed_at: i64,
+    /// `confirm` (kept as AI) or `dismiss` (handed back to the human)
+    pub action: String,
+    pub session: String,
+    pub tool: String,
+    pub model: String,
+    pub files: Vec<String>,
+    pub lines: u32,
+    /// HEAD when the edit was reviewed; the commit made on it carries the edit
+    pub base_commit: String,
+    pub user: String,
+    /// Filled in by the export
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+/// A record of an export, as written
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub seq: u64,
+    /// Unix seconds at which the event happened
+    pub timestamp: i64,
+    #[serde(flatten)]
+    pub event: AuditEvent,
+    pub prev_hash: String,
+    #[serde(default, skip_serializing)]
+    pub hash: String,
+}
+
+/// Appends records to a chain
+pub struct AuditChain {
+    next_seq: u64,
+    prev_hash: String,
+    lines: Vec<String>,
+}
+
+impl Default for AuditChain {
+    fn default() -> Self {
+        AuditChain {
+            next_seq: 0,
+            prev_hash: GENESIS_HASH.to_string(),
+            lines: Vec::new(),
+        }
+    }
+}
+
+impl AuditChain {
+    /// The chain continued after `records`, which must be intact
+    pub fn after(records: &[AuditRecord]) -> AuditChain {
+        match records.last() {
+            Some(last) => AuditChain {
+                next_seq: last.seq + 1,
+                prev_hash: last.hash.clone(),
+                lines: Vec::new(),
+            },
+            None => AuditChain::default(),
+        }
+    }
+
+    pub fn push(&mut self, timestamp: i64, event: AuditEvent) -> Result<(), GitAiError> {
+        let record = AuditRecord {
+            seq: self.next_seq,
+            timestamp,
+            event,
+            prev_hash: self.prev_hash.clone(),
+            hash: String::new(),
+        };
+        let json = serde_json::to_string(&record)?;
+        let body = json.strip_suffix('}').unwrap_or(&json);
+        let hash = format!("{:x}", Sha256::digest(body.as_bytes()));
+        self.lines.push(format!("{},\"hash\":\"{}\"}}", body, hash));
+        self.next_seq += 1;
+        self.prev_hash = hash;
+        Ok(())
+    }
+
+    /// Hash of the last record, which a seal signs
+    pub fn head(&self) -> &str {
+        &self.prev_hash
+    }
+
+    /// The records pushed, one line each
+    pub fn into_lines(self) -> Vec<String> {
+        self.lines
+    }
+}
+
+/// The records of an export, checking the chain; the error names the first broken record
+pub fn verify(source: &str) -> Result<Vec<AuditRecord>, GitAiError> {
+    let broken = |line: usize, why: &str| {
+        GitAiError::Generic(format!("audit trail broken at line {}: {}", line, why))
+    };
+    let mut records: Vec<AuditRecord> = Vec::new();
+    for (i, line) in source.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+        let Some((body, hash)) = line
+            .rsplit_once(",\"hash\":\"")
+            .and_then(|(body, rest)| Some((body, rest.strip_suffix("\"}")?)))
+        else {
+            return Err(broken(i + 1, "no hash"));
+        };
+        if format!("{:x}", Sha256::digest(body.as_bytes())) != hash {
+            return Err(broken(i + 1, "hash does not match the record"));
+        }
+        let mut record: AuditRecord = serde_json::from_str(line)
+            .map_err(|e| broken(i + 1, &format!("invalid record: {}", e)))?;
+        record.hash = hash.to_string();
+        let (expected_seq, expected_prev) = match records.last() {
+            Some(prev) => (prev.seq + 1, prev.hash.as_str()),
+            None => (0, GENESIS_HASH),
+        };
+        if record.seq != expected_seq || record.prev_hash != expected_prev {
+            return Err(broken(i + 1, "record does not follow the one before it"));
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+/// AI lines of a commit's note, by tool and model
+pub fn tool_lines(log: &AuthorshipLog) -> Vec<ToolLines> {
+    let mut lines: BTreeMap<(&str, &str), u32> = BTreeMap::new();
+    for by_prompt in policy::ai_lines_by_file(log).values() {
+        for (hash, count) in by_prompt {
+            if let Some(prompt) = log.metadata.prompts.get(hash) {
+                let agent = &prompt.agent_id;
+                *lines.entry((&agent.tool, &agent.model)).or_default() += count;
+            }
+        }
+    }
+    lines
+        .into_iter()
+        .map(|((tool, model), lines)| ToolLines {
+            tool: tool.to_string(),
+            model: model.to_string(),
+            lines,
+        })
+        .collect()
+}
+
+/// Record a reclassification in the repository's audit log, which is only ever appended to
+pub fn record_reclassification(
+    storage: &RepoStorage,
+    reclassification: &Reclassification,
+) -> Result<(), GitAiError> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&storage.audit_log)?;
+    writeln!(file, "{}", serde_json::to_string(reclassification)?)?;
+    Ok(())
+}
+
+/// Every reclassification in the repository's audit log, oldest first
+pub fn read_reclassifications(storage: &RepoStorage) -> Result<Vec<Reclassification>, GitAiError> {
+    let source = match std::fs::read_to_string(&storage.audit_log) {
+        Ok(source) => source,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    // A line cut short by a crash is skipped rather than failing every export
+    Ok(source
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy_evaluation(commit: &str) -> AuditEvent {
+        AuditEvent::PolicyEvaluation {
+            commit: commit.to_string(),
+            checked_against: "push_policy".to_string(),
+            passed: true,
+            violations: vec![],
+        }
+    }
+
+    #[test]
+    fn test_verify_detects_edited_and_missing_records() {
+        let mut chain = AuditChain::default();
+        chain.push(10, policy_evaluation("a")).unwrap();
+        chain.push(20, policy_evaluation("b")).unwrap();
+        let lines = chain.into_lines();
+        assert!(lines[0].contains(&format!("\"prev_hash\":\"{}\"", GENESIS_HASH)));
+
+        let records = verify(&lines.join("\n")).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1].event, policy_evaluation("b"));
+
+        let mut chain = AuditChain::after(&records);
+        chain.push(30, policy_evaluation("c")).unwrap();
+        let mut appended = lines.clone();
+        appended.extend(chain.into_lines());
+        assert_eq!(verify(&appended.join("\n")).unwrap()[2].seq, 2);
+
+        let edited = lines[0].replace("\"passed\":true", "\"passed\":false");
+        let error = verify(&[edited, lines[1].clone()].join("\n")).unwrap_err();
+        assert!(error.to_string().contains("line 1: hash does not match"));
+
+        let error = verify(&appended[1..].join("\n")).unwrap_err();
+        assert!(error.to_string().contains("line 1: record does not follow"));
+    }
+}
@@ -3,7 +3,7 @@ use crate::authorship::working_log::CheckpointKind;
 use crate::git::repository::Repository;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fmt;
 use std::io::{BufRead, Write};
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -27,6 +27,10 @@ pub struct AuthorshipMetadata {
     pub git_ai_version: Option<String>,
     pub base_commit_sha: String,
     pub prompts: BTreeMap<String, PromptRecord>,
+    /// Files with AI-authored lines that a human checkpoint touched after the last AI
+    /// edit to them, for policy rules that require one
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub human_checked_files: BTreeSet<String>,
 }
 
 impl AuthorshipMetadata {
@@ -36,6 +40,7 @@ impl AuthorshipMetadata {
             git_ai_version: Some(GIT_AI_VERSION.to_string()),
             base_commit_sha: String::new(),
             prompts: BTreeMap::new(),
+            human_checked_files: BTreeSet::new(),
         }
     }
 }
@@ -0,0 +1,114 @@
+//! Git identities that platform agents commit under.
+//!
+//! Hosted agents (the Copilot coding agent, Devin, Jules, ...) push commits authored
+//! by their own bot accounts. `import` and the post-fetch import attribute those
+//! commits to the agent's tool even when they carry no trailers. Bots that aren't AI,
+//! like dependabot and renovate, are deliberately absent.
+
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BotAuthor {
+    /// Glob matched case-insensitively against the author name, email, and
+    /// `Name <email>`
+    pub pattern: String,
+    /// Tool the bot's commits are attributed to
+    pub tool: String,
+}
+
+const BUILTIN_BOT_AUTHORS: &[(&str, &str)] = &[
+    ("copilot-swe-agent*", "github-copilot"),
+    ("*+copilot@users.noreply.github.com", "github-copilot"),
+    ("devin-ai-integration*", "devin"),
+    ("claude[[]bot]", "claude"),
+    ("*+claude[[]bot]@users.noreply.github.com", "claude"),
+    ("cursoragent@cursor.com", "cursor"),
+    ("chatgpt-codex-connector*", "codex"),
+    ("google-labs-jules*", "jules"),
+    ("gemini-code-assist*", "gemini"),
+];
+
+/// Tool of the first bot `name <email>` matches; configured `bots` are tried before
+/// the built-in ones.
+pub fn bot_tool(bots: &[BotAuthor], name: &str, email: &str) -> Option<String> {
+    let name = name.trim().to_ascii_lowercase();
+    let email = email.trim().to_ascii_lowercase();
+    let identity = format!("{} <{}>", name, email);
+    let matches = |pattern: &str| {
+        Pattern::new(&pattern.to_ascii_lowercase()).is_ok_and(|pattern| {
+            pattern.matches(&name) || pattern.matches(&email) || pattern.matches(&identity)
+        })
+    };
+
+    bots.iter()
+        .map(|bot| (bot.pattern.as_str(), bot.tool.as_str()))
+        .chain(BUILTIN_BOT_AUTHORS.iter().copied())
+        .find(|(pattern, _)| matches(pattern))
+        .map(|(_, tool)| tool.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_bots() {
+        assert_eq!(
+            bot_tool(&[], "Copilot", "198982749+Copilot@users.noreply.github.com").as_deref(),
+            Some("github-copilot")
+        );
+        assert_eq!(
+            bot_tool(
+                &[],
+                "devin-ai-integration[bot]",
+                "158243242+devin-ai-integration[bot]@users.noreply.github.com"
+            )
+            .as_deref(),
+            Some("devin")
+        );
+        assert_eq!(
+            bot_tool(
+                &[],
+                "claude[bot]",
+                "209825114+claude[bot]@users.noreply.github.com"
+            )
+            .as_deref(),
+            Some("claude")
+        );
+        assert_eq!(
+            bot_tool(
+                &[],
+                "dependabot[bot]",
+                "49699333+dependabot[bot]@users.noreply.github.com"
+            ),
+            None
+        );
+        assert_eq!(bot_tool(&[], "Jane Doe", "jane@example.com"), None);
+    }
+
+    #[test]
+    fn test_configured_bots_win() {
+        let bots = vec![BotAuthor {
+            pattern: "*@agents.acme.dev".to_string(),
+            tool: "acme-bot".to_string(),
+        }];
+        assert_eq!(
+            bot_tool(&bots, "Acme Agent", "run-42@agents.acme.dev").as_deref(),
+            Some("acme-bot")
+        );
+        let bots = vec![BotAuthor {
+            pattern: "copilot*".to_string(),
+            tool: "copilot-internal".to_string(),
+        }];
+        assert_eq!(
+            bot_tool(
+                &bots,
+                "copilot-swe-agent[bot]",
+                "x@users.noreply.github.com"
+            )
+            .as_deref(),
+            Some("copilot-internal")
+        );
+    }
+}
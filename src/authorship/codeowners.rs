@@ -0,0 +1,186 @@
+//! `CODEOWNERS`, read for policies that make owners of a path acknowledge AI-authored
+//! changes to it.
+//!
+//! The file is looked up where GitHub and GitLab do (`.github/`, the root, `docs/`, the
+//! first one found wins) and matched the same way: the last matching line decides the
+//! owners of a path, and a line with no owners leaves the path unowned. Patterns follow
+//! gitignore rules: a leading `/` or an inner `/` anchors a pattern at the root, `*`
+//! does not cross a `/`, and a pattern naming a directory covers everything in it.
+//! GitLab section headers (`[Section]`) are skipped; their lines count like any other.
+
+use crate::error::GitAiError;
+use crate::git::repository::Repository;
+use glob::{MatchOptions, Pattern};
+
+/// Where `CODEOWNERS` is looked up, in order
+pub const CODEOWNERS_PATHS: &[&str] = &[".github/CODEOWNERS", "CODEOWNERS", "docs/CODEOWNERS"];
+
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct CodeOwners {
+    rules: Vec<OwnerRule>,
+}
+
+#[derive(Debug, Clone)]
+struct OwnerRule {
+    /// A file the pattern names
+    file: Option<Pattern>,
+    /// Anything inside a directory the pattern names
+    contents: Option<Pattern>,
+    owners: Vec<String>,
+}
+
+impl CodeOwners {
+    /// The `CODEOWNERS` of the working tree, if it has one
+    pub fn load(repo: &Repository) -> Result<Option<CodeOwners>, GitAiError> {
+        let workdir = repo.workdir()?;
+        for path in CODEOWNERS_PATHS {
+            match std::fs::read_to_string(workdir.join(path)) {
+                Ok(source) => return Ok(Some(CodeOwners::parse(&source))),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Ok(None)
+    }
+
+    /// The `CODEOWNERS` committed at `rev`, if it has one
+    pub fn load_at(repo: &Repository, rev: &str) -> Result<Option<CodeOwners>, GitAiError> {
+        let paths: Vec<String> = CODEOWNERS_PATHS.iter().map(|p| p.to_string()).collect();
+        let mut blobs = repo.blobs_at_paths(rev, &paths)?;
+        Ok(CODEOWNERS_PATHS
+            .iter()
+            .find_map(|path| blobs.remove(*path))
+            .map(|source| CodeOwners::parse(&String::from_utf8_lossy(&source))))
+    }
+
+    /// Lines that are not valid patterns are skipped, as the forges do
+    pub fn parse(source: &str) -> CodeOwners {
+        let rules = source
+            .lines()
+            .map(|line| line.split(" #").next().unwrap_or_default().trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter(|line| !line.starts_with('[') && !line.starts_with("^["))
+            .filter_map(|line| {
+                let mut parts = line.split_whitespace();
+                let pattern = parts.next()?;
+                let (file, contents) = compile(pattern)?;
+                Some(OwnerRule {
+                    file,
+                    contents,
+                    owners: parts.map(str::to_string).collect(),
+                })
+            })
+            .collect();
+        CodeOwners { rules }
+    }
+
+    /// Owners of `path` (`@user`, `@org/team` or an email); empty when unowned
+    pub fn owners_of(&self, path: &str) -> &[String] {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                rule.file
+                    .as_ref()
+                    .is_some_and(|p| p.matches_with(path, MATCH_OPTIONS))
+                    || rule
+                        .contents
+                        .as_ref()
+                        .is_some_and(|p| p.matches_with(path, MATCH_OPTIONS))
+            })
+            .map_or(&[], |rule| rule.owners.as_slice())
+    }
+}
+
+/// Whether `reviewer` (a trailer value such as `Alice <alice@example.com>`, or a login)
+/// names `owner`
+pub fn names_owner(reviewer: &str, owner: &str) -> bool {
+    let reviewer = reviewer.to_lowercase();
+    let owner = owner.to_lowercase();
+    let continues_name = |c: char| c.is_alphanumeric() || matches!(c, '-' | '_' | '/');
+    reviewer.match_indices(&owner).any(|(start, _)| {
+        let before = reviewer[..start].chars().next_back();
+        let after = reviewer[start + owner.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(continues_name)
+    })
+}
+
+/// The patterns matching the files a `CODEOWNERS` pattern names, and the contents of
+/// the directories it names
+fn compile(pattern: &str) -> Option<(Option<Pattern>, Option<Pattern>)> {
+    let dir_only = pattern.ends_with('/');
+    let trimmed = pattern.trim_end_matches('/');
+    let anchored = trimmed.starts_with('/') || trimmed.contains('/');
+    let trimmed = trimmed.trim_start_matches('/');
+    if trimmed.is_empty() {
+        return None;
+    }
+    let base = if anchored || trimmed.starts_with("**") {
+        trimmed.to_string()
+    } else {
+        format!("**/{}", trimmed)
+    };
+    let last = trimmed.rsplit('/').next().unwrap_or(trimmed);
+    // `docs/*` owns the files directly in `docs`, not those further down
+    let names_directory = !last.contains(['*', '?', '[']) || last == "**";
+    let file = if dir_only {
+        None
+    } else {
+        Some(Pattern::new(&base).ok()?)
+    };
+    let contents = if names_directory || dir_only {
+        Some(Pattern::new(&format!("{}/**", base)).ok()?)
+    } else {
+        None
+    };
+    Some((file, contents))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_owners_of_uses_the_last_matching_line() {
+        let owners = CodeOwners::parse(
+            "# Owners\n\
+             *                 @org/everyone\n\
+             *.rs              @rustaceans # all Rust\n\
+             /src/payments/    @alice alice@example.com\n\
+             docs/*            @writers\n\
+             build/\n\
+             [Frontend]\n\
+             /web/**/*.ts      @org/frontend\n",
+        );
+        assert_eq!(owners.owners_of("README.md"), ["@org/everyone"]);
+        assert_eq!(owners.owners_of("src/lib.rs"), ["@rustaceans"]);
+        assert_eq!(
+            owners.owners_of("src/payments/charge/card.rs"),
+            ["@alice", "alice@example.com"]
+        );
+        assert_eq!(owners.owners_of("docs/intro.md"), ["@writers"]);
+        assert_eq!(owners.owners_of("docs/api/intro.md"), ["@org/everyone"]);
+        assert!(owners.owners_of("tools/build/out.txt").is_empty());
+        assert_eq!(owners.owners_of("web/app/main.ts"), ["@org/frontend"]);
+        assert_eq!(owners.owners_of("web/main.ts"), ["@org/frontend"]);
+    }
+
+    #[test]
+    fn test_names_owner() {
+        assert!(names_owner(
+            "Alice <alice@example.com>",
+            "alice@example.com"
+        ));
+        assert!(names_owner("@alice", "@alice"));
+        assert!(names_owner("Alice (@Alice)", "@alice"));
+        assert!(!names_owner("@alice-bot", "@alice"));
+        assert!(!names_owner("bob@alice.com", "@alice"));
+        assert!(!names_owner("@org/team-b", "@org/team"));
+    }
+}
@@ -0,0 +1,495 @@
+//! Standardized commit-message trailers describing AI involvement.
+//!
+//! Trailers make attribution visible to people who don't run git-ai (e.g. in
+//! `git log` or a forge UI). They are derived from the pending working log when a
+//! commit is being prepared, and can be parsed back out of commit messages.
+
+use crate::authorship::virtual_attribution::VirtualAttributions;
+use crate::commands::status::count_ai_lines_from_initial;
+use crate::error::GitAiError;
+use crate::git::repo_storage::InitialAttributions;
+use crate::git::repository::Repository;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+pub const CO_AUTHORED_BY: &str = "Co-authored-by";
+pub const AI_ASSISTED: &str = "AI-Assisted";
+pub const AI_TOOLS: &str = "AI-Tools";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trailer {
+    pub key: String,
+    pub value: String,
+}
+
+impl Trailer {
+    pub fn new(key: &str, value: impl Into<String>) -> Self {
+        Trailer {
+            key: key.to_string(),
+            value: value.into(),
+        }
+    }
+}
+
+/// Summary of AI involvement in a set of changes, as carried by trailers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrailerSummary {
+    /// Percentage (0-100) of added lines attributed to AI
+    pub ai_percent: u32,
+    /// Tool ids as recorded in checkpoints (e.g. "cursor", "claude")
+    pub tools: BTreeSet<String>,
+}
+
+/// Git identity used for `Co-authored-by` for tools that have a well-known one.
+pub fn co_author_identity(tool: &str) -> Option<&'static str> {
+    match tool {
+        "claude" | "claude-code" => Some("Claude <noreply@anthropic.com>"),
+        "cursor" => Some("Cursor Agent <cursoragent@cursor.com>"),
+        "github-copilot" => Some("Copilot <198982749+Copilot@users.noreply.github.com>"),
+        _ => None,
+    }
+}
+
+/// Build the trailers for a summary. Returns nothing when no AI lines are pending.
+pub fn build_trailers(summary: &TrailerSummary) -> Vec<Trailer> {
+    if summary.ai_percent == 0 || summary.tools.is_empty() {
+        return Vec::new();
+    }
+
+    let mut trailers: Vec<Trailer> = Vec::new();
+    let mut seen_identities = HashSet::new();
+    for tool in &summary.tools {
+        if let Some(identity) = co_author_identity(tool)
+            && seen_identities.insert(identity)
+        {
+            trailers.push(Trailer::new(CO_AUTHORED_BY, identity));
+        }
+    }
+    trailers.push(Trailer::new(
+        AI_ASSISTED,
+        format!("{}%", summary.ai_percent),
+    ));
+    trailers.push(Trailer::new(
+        AI_TOOLS,
+        summary.tools.iter().cloned().collect::<Vec<_>>().join(","),
+    ));
+    trailers
+}
+
+fn parse_trailer_line(line: &str) -> Option<Trailer> {
+    let (key, value) = line.split_once(':')?;
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+    Some(Trailer::new(key, value.trim()))
+}
+
+/// Parse the trailer block (last paragraph made up only of `Key: value` lines) of a
+/// commit message.
+pub fn parse_trailers(message: &str) -> Vec<Trailer> {
+    let content_lines: Vec<&str> = message
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect();
+    let end = content_lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let start = content_lines[..end]
+        .iter()
+        .rposition(|line| line.trim().is_empty())
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    // The subject line alone is never a trailer block
+    if start == 0 {
+        return Vec::new();
+    }
+
+    let block: Vec<Option<Trailer>> = content_lines[start..end]
+        .iter()
+        .map(|line| parse_trailer_line(line))
+        .collect();
+    if block.iter().any(|t| t.is_none()) {
+        return Vec::new();
+    }
+    block.into_iter().flatten().collect()
+}
+
+/// Recover a summary from trailers previously written by [`build_trailers`].
+pub fn summary_from_trailers(trailers: &[Trailer]) -> Option<TrailerSummary> {
+    let mut summary = TrailerSummary::default();
+    let mut found = false;
+    for trailer in trailers {
+        if trailer.key.eq_ignore_ascii_case(AI_ASSISTED) {
+            if let Ok(percent) = trailer.value.trim_end_matches('%').trim().parse::<u32>() {
+                summary.ai_percent = percent.min(100);
+                found = true;
+            }
+        } else if trailer.key.eq_ignore_ascii_case(AI_TOOLS) {
+            summary.tools.extend(
+                trailer
+                    .value
+                    .split(',')
+                    .map(|t| t.trim().to_string())
+                    .filter(|t| !t.is_empty()),
+            );
+        }
+    }
+    found.then_some(summary)
+}
+
+/// Append trailers to a commit message, keeping git's comment section at the end.
+///
+/// Idempotent: `AI-Assisted`/`AI-Tools` values are replaced in place, and
+/// `Co-authored-by` lines that already exist are not duplicated. A message with no
+/// content yet is returned unchanged so aborting an empty commit still works.
+pub fn append_trailers(message: &str, trailers: &[Trailer]) -> String {
+    if trailers.is_empty() {
+        return message.to_string();
+    }
+
+    let mut lines: Vec<String> = message.lines().map(|l| l.to_string()).collect();
+    // Content ends at the last line that is neither blank nor a comment
+    let content_end = match lines
+        .iter()
+        .rposition(|l| !l.trim().is_empty() && !l.starts_with('#'))
+    {
+        Some(i) => i + 1,
+        None => return message.to_string(),
+    };
+    let tail: Vec<String> = lines.split_off(content_end);
+
+    let mut to_append: Vec<String> = Vec::new();
+    for trailer in trailers {
+        let replaceable = trailer.key.eq_ignore_ascii_case(AI_ASSISTED)
+            || trailer.key.eq_ignore_ascii_case(AI_TOOLS);
+        let existing = lines.iter().position(|line| {
+            parse_trailer_line(line).is_some_and(|t| {
+                t.key.eq_ignore_ascii_case(&trailer.key)
+                    && (replaceable || t.value == trailer.value)
+            })
+        });
+        let rendered = format!("{}: {}", trailer.key, trailer.value);
+        match existing {
+            Some(i) if replaceable => lines[i] = rendered,
+            Some(_) => {}
+            None => to_append.push(rendered),
+        }
+    }
+
+    if !to_append.is_empty() {
+        let message_so_far = lines.join("\n");
+        if parse_trailers(&message_so_far).is_empty() {
+            lines.push(String::new());
+        }
+        lines.extend(to_append);
+    }
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    for line in tail {
+        result.push_str(&line);
+        result.push('\n');
+    }
+    result
+}
+
+/// Attribution of the staged changes, computed from the working log for HEAD.
+struct PendingAttribution {
+    initial: InitialAttributions,
+    /// Lines added by the staged diff of the files the working log covers
+    added_lines: u32,
+}
+
+fn pending_attribution(repo: &Repository) -> Result<Option<PendingAttribution>, GitAiError> {
+    let head_sha = repo.head()?.target()?;
+    let working_log = repo.storage.working_log_for_base_commit(&head_sha);
+    let checkpoints = working_log.read_all_checkpoints()?;
+    let initial_files = working_log.read_initial_attributions().files;
+    if checkpoints.is_empty() && initial_files.is_empty() {
+        return Ok(None);
+    }
+
+    let staged: HashSet<String> = repo
+        .git(&["diff", "--cached", "--name-only", "--no-renames"])?
+        .lines()
+        .filter(|l| !l.is_empty())
+        .map(|l| l.to_string())
+        .collect();
+    let pathspecs: HashSet<String> = checkpoints
+        .iter()
+        .flat_map(|cp| cp.entries.iter().map(|e| e.file.clone()))
+        .chain(initial_files.into_keys())
+        .filter(|f| staged.contains(f))
+        .collect();
+    if pathspecs.is_empty() {
+        return Ok(None);
+    }
+
+    let working_va =
+        VirtualAttributions::from_just_working_log(repo.clone(), head_sha.clone(), None)?;
+    let (_, initial) = working_va.to_authorship_log_and_initial_working_log(
+        repo,
+        &head_sha,
+        &head_sha,
+        Some(&pathspecs),
+    )?;
+
+    let mut numstat_args = vec!["diff", "--cached", "--numstat", "--"];
+    numstat_args.extend(pathspecs.iter().map(String::as_str));
+    let added_lines: u32 = repo
+        .git(&numstat_args)?
+        .lines()
+        .filter_map(|line| line.split('\t').next()?.parse::<u32>().ok())
+        .sum();
+
+    Ok(Some(PendingAttribution {
+        initial,
+        added_lines,
+    }))
+}
+
+/// Summarize what is currently staged using the working log for HEAD.
+pub fn pending_trailer_summary(repo: &Repository) -> Result<TrailerSummary, GitAiError> {
+    let Some(pending) = pending_attribution(repo)? else {
+        return Ok(TrailerSummary::default());
+    };
+
+    let ai_lines = count_ai_lines_from_initial(&pending.initial);
+    let ai_percent = if pending.added_lines == 0 {
+        0
+    } else {
+        ((ai_lines as f64 / pending.added_lines as f64) * 100.0)
+            .round()
+            .min(100.0) as u32
+    };
+    let tools = pending
+        .initial
+        .prompts
+        .values()
+        .map(|p| p.agent_id.tool.clone())
+        .collect();
+
+    Ok(TrailerSummary { ai_percent, tools })
+}
+
+/// Per-agent breakdown of staged lines, shown to the committer before they commit.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PendingLineSummary {
+    /// (tool, model) -> AI lines
+    pub agents: BTreeMap<(String, String), u32>,
+    pub human_lines: u32,
+}
+
+pub fn pending_line_summary(repo: &Repository) -> Result<PendingLineSummary, GitAiError> {
+    let Some(pending) = pending_attribution(repo)? else {
+        return Ok(PendingLineSummary::default());
+    };
+
+    let mut summary = PendingLineSummary::default();
+    for line_attrs in pending.initial.files.values() {
+        for line_attr in line_attrs {
+            if let Some(prompt) = pending.initial.prompts.get(&line_attr.author_id) {
+                let key = (prompt.agent_id.tool.clone(), prompt.agent_id.model.clone());
+                *summary.agents.entry(key).or_insert(0) +=
+                    line_attr.end_line - line_attr.start_line + 1;
+            }
+        }
+    }
+    let ai_lines: u32 = summary.agents.values().sum();
+    summary.human_lines = pending.added_lines.saturating_sub(ai_lines);
+    Ok(summary)
+}
+
+/// Name of a checkpoint tool as shown to people.
+pub fn tool_display_name(tool: &str) -> &str {
+    match tool {
+        "claude" | "claude-code" => "Claude Code",
+        "cursor" => "Cursor",
+        "github-copilot" => "GitHub Copilot",
+        "gemini" => "Gemini",
+        "continue-cli" => "Continue",
+        "droid" => "Droid",
+        "opencode" => "OpenCode",
+        other => other,
+    }
+}
+
+const SUMMARY_COMMENT_PREFIX: &str = "# AI changes pending:";
+
+/// Render the summary as a single git comment line, e.g.
+/// `# AI changes pending: 212 lines from Cursor (claude-3.7), 40 human lines`.
+pub fn summary_comment(summary: &PendingLineSummary) -> Option<String> {
+    if summary.agents.is_empty() {
+        return None;
+    }
+    let mut parts: Vec<(u32, String)> = summary
+        .agents
+        .iter()
+        .map(|((tool, model), lines)| {
+            let name = tool_display_name(tool);
+            let label = if model.is_empty() || model == "unknown" {
+                name.to_string()
+            } else {
+                format!("{} ({})", name, model)
+            };
+            (*lines, label)
+        })
+        .collect();
+    parts.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+    let agents: Vec<String> = parts
+        .into_iter()
+        .map(|(lines, label)| format!("{} {} from {}", lines, plural_lines(lines), label))
+        .collect();
+    Some(format!(
+        "{} {}, {} human {}",
+        SUMMARY_COMMENT_PREFIX,
+        agents.join(", "),
+        summary.human_lines,
+        plural_lines(summary.human_lines)
+    ))
+}
+
+fn plural_lines(count: u32) -> &'static str {
+    if count == 1 { "line" } else { "lines" }
+}
+
+/// Insert (or replace) the summary comment at the top of git's comment section.
+pub fn insert_summary_comment(message: &str, comment: &str) -> String {
+    let mut lines: Vec<&str> = message
+        .lines()
+        .filter(|line| !line.starts_with(SUMMARY_COMMENT_PREFIX))
+        .collect();
+    let insert_at = lines
+        .iter()
+        .position(|line| line.starts_with('#'))
+        .unwrap_or(lines.len());
+    lines.insert(insert_at, comment);
+
+    let mut result = lines.join("\n");
+    result.push('\n');
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(percent: u32, tools: &[&str]) -> TrailerSummary {
+        TrailerSummary {
+            ai_percent: percent,
+            tools: tools.iter().map(|t| t.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_build_trailers() {
+        let trailers = build_trailers(&summary(43, &["cursor", "claude"]));
+        assert_eq!(
+            trailers,
+            vec![
+                Trailer::new(CO_AUTHORED_BY, "Claude <noreply@anthropic.com>"),
+                Trailer::new(CO_AUTHORED_BY, "Cursor Agent <cursoragent@cursor.com>"),
+                Trailer::new(AI_ASSISTED, "43%"),
+                Trailer::new(AI_TOOLS, "claude,cursor"),
+            ]
+        );
+        assert!(build_trailers(&summary(0, &["cursor"])).is_empty());
+    }
+
+    #[test]
+    fn test_append_trailers_keeps_comments_last() {
+        let message = "Fix bug\n\n# Please enter the commit message\n# Lines starting with '#'\n";
+        let out = append_trailers(message, &build_trailers(&summary(50, &["gemini"])));
+        assert_eq!(
+            out,
+            "Fix bug\n\nAI-Assisted: 50%\nAI-Tools: gemini\n\n# Please enter the commit message\n# Lines starting with '#'\n"
+        );
+    }
+
+    #[test]
+    fn test_append_trailers_is_idempotent_and_updates_values() {
+        let first = append_trailers(
+            "Add feature\n\nSigned-off-by: Dev <dev@example.com>\n",
+            &build_trailers(&summary(20, &["claude"])),
+        );
+        assert_eq!(
+            first,
+            "Add feature\n\nSigned-off-by: Dev <dev@example.com>\nCo-authored-by: Claude <noreply@anthropic.com>\nAI-Assisted: 20%\nAI-Tools: claude\n"
+        );
+        let second = append_trailers(&first, &build_trailers(&summary(35, &["claude"])));
+        assert_eq!(second, first.replace("20%", "35%"));
+    }
+
+    #[test]
+    fn test_append_trailers_leaves_empty_message_untouched() {
+        let message = "\n# Please enter the commit message\n";
+        let out = append_trailers(message, &build_trailers(&summary(50, &["cursor"])));
+        assert_eq!(out, message);
+    }
+
+    #[test]
+    fn test_parse_trailers() {
+        let message = append_trailers(
+            "Subject\n\nBody text.\n",
+            &build_trailers(&summary(43, &["cursor"])),
+        );
+        assert_eq!(
+            parse_trailers(&message),
+            vec![
+                Trailer::new(CO_AUTHORED_BY, "Cursor Agent <cursoragent@cursor.com>"),
+                Trailer::new(AI_ASSISTED, "43%"),
+                Trailer::new(AI_TOOLS, "cursor"),
+            ]
+        );
+        assert!(parse_trailers("Subject: looks like a trailer\n").is_empty());
+        assert!(parse_trailers("Subject\n\nNot a trailer line\nKey: value\n").is_empty());
+    }
+
+    #[test]
+    fn test_summary_comment() {
+        let mut pending = PendingLineSummary::default();
+        pending
+            .agents
+            .insert(("cursor".to_string(), "claude-3.7".to_string()), 212);
+        pending
+            .agents
+            .insert(("claude".to_string(), "unknown".to_string()), 1);
+        pending.human_lines = 40;
+        assert_eq!(
+            summary_comment(&pending).unwrap(),
+            "# AI changes pending: 212 lines from Cursor (claude-3.7), 1 line from Claude Code, 40 human lines"
+        );
+        assert!(summary_comment(&PendingLineSummary::default()).is_none());
+    }
+
+    #[test]
+    fn test_insert_summary_comment_replaces_previous() {
+        let message = "\n# Please enter the commit message\n";
+        let once = insert_summary_comment(
+            message,
+            "# AI changes pending: 1 line from Cursor, 0 human lines",
+        );
+        assert_eq!(
+            once,
+            "\n# AI changes pending: 1 line from Cursor, 0 human lines\n# Please enter the commit message\n"
+        );
+        let twice = insert_summary_comment(
+            &once,
+            "# AI changes pending: 2 lines from Cursor, 0 human lines",
+        );
+        assert_eq!(twice, once.replace("1 line from", "2 lines from"));
+    }
+
+    #[test]
+    fn test_summary_from_trailers_round_trip() {
+        let message = append_trailers(
+            "Subject\n\nBody text.\n",
+            &build_trailers(&summary(43, &["cursor", "claude"])),
+        );
+        let parsed = summary_from_trailers(&parse_trailers(&message)).unwrap();
+        assert_eq!(parsed, summary(43, &["claude", "cursor"]));
+        assert!(summary_from_trailers(&parse_trailers("Subject\n\nReviewed-by: A\n")).is_none());
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::{BTreeMap, HashMap};
 
+use crate::authorship::path_config::PathConfig;
 use crate::authorship::range_authorship::should_ignore_file;
 use crate::commands::blame::GitAiBlameOptions;
 use crate::error::GitAiError;
@@ -22,9 +23,10 @@ pub fn diff_ai_accepted_stats(
     let added_lines_by_file = repo.diff_added_lines(from_ref, to_ref, None)?;
 
     let mut stats = DiffAiAcceptedStats::default();
+    let paths = PathConfig::for_repo(repo);
 
     for (file_path, mut lines) in added_lines_by_file {
-        if should_ignore_file(&file_path, ignore_patterns) {
+        if should_ignore_file(&file_path, ignore_patterns) || paths.ignores(&file_path) {
             continue;
         }
 
@@ -58,7 +60,10 @@ pub fn diff_ai_accepted_stats(
 
         let mut prompt_tool_map: HashMap<String, String> = HashMap::new();
         for (hash, record) in &prompt_records {
-            let tool_model = format!("{}::{}", record.agent_id.tool, record.agent_id.model);
+            let tool_model = crate::authorship::model_names::tool_model_key(
+                &record.agent_id.tool,
+                &record.agent_id.model,
+            );
             prompt_tool_map.insert(hash.clone(), tool_model);
         }
 
@@ -0,0 +1,189 @@
+//! On-disk cache of the diffs the attribution tracker computes between two versions
+//! of a file.
+//!
+//! A diff only depends on the two contents and on the diff algorithm, so entries are
+//! keyed by the content hash of each side and [`DIFF_ALGORITHM`], and never need
+//! invalidating: bump the algorithm version when the diff changes. Entries hold the
+//! operations as lengths only; the bytes are taken back from the contents on a hit.
+//!
+//! The cache lives next to the rewrite log, shared by the worktrees of a repository.
+//! When it grows past its cap the least recently used entries are removed, at most
+//! once per process.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::SystemTime;
+
+/// Version of the diff the tracker computes; part of every key
+pub const DIFF_ALGORITHM: &str = "token-aligned-imara-v1";
+
+/// Default cap on the size of the cache
+const MAX_CACHE_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Diffs of smaller files are cheaper to compute than to read back
+const MIN_CACHED_BYTES: usize = 4096;
+
+static EVICTED: AtomicBool = AtomicBool::new(false);
+
+/// A cached diff: `(op, length)` pairs, with `E`, `D` and `I` for equal, deleted and
+/// inserted bytes, and the byte ranges of the new content that changed substantively
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedDiff {
+    pub ops: Vec<(char, usize)>,
+    pub substantive_new_ranges: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+impl DiffCache {
+    pub fn new(dir: PathBuf) -> Self {
+        DiffCache {
+            dir,
+            max_bytes: MAX_CACHE_BYTES,
+        }
+    }
+
+    /// The key of the diff from `old_content` to `new_content`, or `None` when the
+    /// files are too small to be worth caching
+    pub fn key(old_content: &str, new_content: &str) -> Option<String> {
+        if old_content.len() + new_content.len() < MIN_CACHED_BYTES {
+            return None;
+        }
+        let mut hasher = Sha256::new();
+        hasher.update(DIFF_ALGORITHM.as_bytes());
+        hasher.update(content_hash(old_content));
+        hasher.update(content_hash(new_content));
+        Some(format!("{:x}", hasher.finalize()))
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(&key[..2]).join(&key[2..])
+    }
+
+    pub fn get(&self, key: &str) -> Option<CachedDiff> {
+        let path = self.path(key);
+        let diff = serde_json::from_slice(&fs::read(&path).ok()?).ok()?;
+        // Eviction goes by modification time, so a hit counts as a use
+        if let Ok(file) = fs::File::options().append(true).open(&path) {
+            let _ = file.set_modified(SystemTime::now());
+        }
+        Some(diff)
+    }
+
+    /// Store `diff` under `key`. Failing to cache is never an error for the caller.
+    pub fn put(&self, key: &str, diff: &CachedDiff) {
+        let path = self.path(key);
+        let Ok(json) = serde_json::to_vec(diff) else {
+            return;
+        };
+        if let Some(parent) = path.parent()
+            && fs::create_dir_all(parent).is_ok()
+        {
+            // Write then rename, so a concurrent reader never sees half an entry
+            let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+            if fs::write(&tmp, json).is_ok() && fs::rename(&tmp, &path).is_err() {
+                let _ = fs::remove_file(&tmp);
+            }
+        }
+        if !EVICTED.swap(true, Ordering::Relaxed) {
+            self.evict();
+        }
+    }
+
+    /// Remove the least recently used entries until the cache is under 3/4 of its cap,
+    /// if it is over the cap
+    fn evict(&self) {
+        let mut entries = Vec::new();
+        let mut total = 0;
+        for file in walk(&self.dir) {
+            if let Ok(metadata) = fs::metadata(&file) {
+                total += metadata.len();
+                let modified = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                entries.push((modified, metadata.len(), file));
+            }
+        }
+        if total <= self.max_bytes {
+            return;
+        }
+        entries.sort();
+        for (_, len, file) in entries {
+            if total <= self.max_bytes / 4 * 3 {
+                break;
+            }
+            if fs::remove_file(&file).is_ok() {
+                total -= len;
+            }
+        }
+    }
+}
+
+fn content_hash(content: &str) -> [u8; 32] {
+    Sha256::digest(content.as_bytes()).into()
+}
+
+/// The files of the two-level cache directory
+fn walk(dir: &Path) -> Vec<PathBuf> {
+    let Ok(shards) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    shards
+        .flatten()
+        .filter_map(|shard| fs::read_dir(shard.path()).ok())
+        .flat_map(|files| files.flatten().map(|file| file.path()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff(len: usize) -> CachedDiff {
+        CachedDiff {
+            ops: vec![('E', len), ('I', 3)],
+            substantive_new_ranges: vec![(len, len + 3)],
+        }
+    }
+
+    #[test]
+    fn test_key() {
+        let old = "a\n".repeat(MIN_CACHED_BYTES);
+        let new = format!("{}b\n", old);
+        let key = DiffCache::key(&old, &new).unwrap();
+        assert_eq!(key.len(), 64);
+        assert_eq!(DiffCache::key(&old, &new), Some(key.clone()));
+        assert_ne!(DiffCache::key(&new, &old), Some(key));
+        assert_eq!(DiffCache::key("a\n", "b\n"), None);
+    }
+
+    #[test]
+    fn test_put_get_and_evict() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut cache = DiffCache::new(dir.path().to_path_buf());
+        let key = "ab".repeat(32);
+        assert_eq!(cache.get(&key), None);
+        cache.put(&key, &diff(10));
+        assert_eq!(cache.get(&key), Some(diff(10)));
+
+        for i in 0..10 {
+            cache.put(&format!("{:02}{}", i, "c".repeat(62)), &diff(i));
+        }
+        let size: u64 = walk(dir.path())
+            .iter()
+            .map(|file| fs::metadata(file).unwrap().len())
+            .sum();
+        cache.max_bytes = size / 2;
+        cache.evict();
+        let remaining: u64 = walk(dir.path())
+            .iter()
+            .map(|file| fs::metadata(file).unwrap().len())
+            .sum();
+        assert!(remaining <= size / 2 / 4 * 3, "{} of {}", remaining, size);
+    }
+}
@@ -0,0 +1,349 @@
+//! Synthesize authorship logs for commits that arrive without git-ai notes.
+//!
+//! Contributors using other tooling may still record AI involvement in commit
+//! trailers (`AI-Assisted`, `AI-Tools`). Those commits get a coarse authorship log:
+//! the stated share of the commit's added lines is attributed to the listed tools,
+//! in file/line order, so stats stay meaningful even without line-level data.
+//! Commits authored by a known agent bot account (see [`bot_authors`]) are attributed
+//! to that agent entirely.
+//!
+//! [`bot_authors`]: crate::authorship::bot_authors
+
+use crate::authorship::authorship_log::{LineRange, PromptRecord};
+use crate::authorship::authorship_log_serialization::{
+    AttestationEntry, AuthorshipLog, generate_short_hash,
+};
+use crate::authorship::bot_authors::bot_tool;
+use crate::authorship::commit_trailers::{TrailerSummary, parse_trailers, summary_from_trailers};
+use crate::authorship::working_log::AgentId;
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::refs::notes_add;
+use crate::git::repository::{Repository, exec_git};
+use crate::utils::debug_log;
+use std::collections::HashSet;
+
+const EMPTY_TREE_HASH: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+/// Model recorded for prompts synthesized from trailers (trailers don't carry one).
+pub const IMPORTED_MODEL: &str = "unknown";
+
+#[derive(Debug, Default)]
+pub struct ImportSummary {
+    pub scanned: usize,
+    pub already_attributed: usize,
+    /// Commits (sha, summary) that received a synthesized authorship log
+    pub imported: Vec<(String, TrailerSummary)>,
+}
+
+pub(crate) struct CommitInfo {
+    pub(crate) sha: String,
+    pub(crate) first_parent: Option<String>,
+    pub(crate) message: String,
+    pub(crate) author_name: String,
+    pub(crate) author_email: String,
+}
+
+/// Import authorship for every commit selected by `rev_args` (as passed to `git log`).
+/// `on_import` sees each imported commit as soon as its note is written.
+pub fn import_commits(
+    repo: &Repository,
+    rev_args: &[String],
+    dry_run: bool,
+    mut on_import: impl FnMut(&str, &TrailerSummary),
+) -> Result<ImportSummary, GitAiError> {
+    let annotated = commits_with_notes(repo);
+    let mut summary = ImportSummary::default();
+
+    for commit in list_commits(repo, rev_args)? {
+        summary.scanned += 1;
+        if annotated.contains(&commit.sha) {
+            summary.already_attributed += 1;
+            continue;
+        }
+
+        let Some(trailer_summary) = summary_from_trailers(&parse_trailers(&commit.message))
+            .or_else(|| bot_summary(&commit))
+        else {
+            continue;
+        };
+        if trailer_summary.ai_percent == 0 || trailer_summary.tools.is_empty() {
+            continue;
+        }
+
+        if !dry_run {
+            let agents: Vec<AgentId> = trailer_summary
+                .tools
+                .iter()
+                .map(|tool| imported_agent_id(&commit.sha, tool))
+                .collect();
+            let log =
+                synthesize_authorship_log(repo, &commit, trailer_summary.ai_percent, &agents)?;
+            let note = log.serialize_to_string().map_err(|_| {
+                GitAiError::Generic("Failed to serialize authorship log".to_string())
+            })?;
+            notes_add(repo, &commit.sha, &note)?;
+            debug_log(&format!(
+                "Synthesized authorship for {} from trailers ({}% AI)",
+                commit.sha, trailer_summary.ai_percent
+            ));
+        }
+        on_import(&commit.sha, &trailer_summary);
+        summary.imported.push((commit.sha, trailer_summary));
+    }
+
+    Ok(summary)
+}
+
+pub(crate) fn commits_with_notes(repo: &Repository) -> HashSet<String> {
+    // Fails when refs/notes/ai doesn't exist yet, which just means nothing is annotated
+    repo.git(&["notes", "--ref=ai", "list"])
+        .map(|out| {
+            out.lines()
+                .filter_map(|line| line.split_whitespace().nth(1))
+                .map(|sha| sha.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub(crate) fn list_commits(
+    repo: &Repository,
+    rev_args: &[String],
+) -> Result<Vec<CommitInfo>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("log".to_string());
+    args.push("--format=%H%x00%P%x00%an%x00%ae%x00%B%x1e".to_string());
+    args.extend(rev_args.iter().cloned());
+
+    let output = exec_git(&args)?;
+    let stdout = String::from_utf8(output.stdout)?;
+
+    Ok(stdout
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut parts = record.trim_start_matches('\n').splitn(5, '\0');
+            let sha = parts.next()?.trim().to_string();
+            if sha.is_empty() {
+                return None;
+            }
+            let first_parent = parts
+                .next()?
+                .split_whitespace()
+                .next()
+                .map(|p| p.to_string());
+            let author_name = parts.next().unwrap_or_default().to_string();
+            let author_email = parts.next().unwrap_or_default().to_string();
+            let message = parts.next().unwrap_or_default().to_string();
+            Some(CommitInfo {
+                sha,
+                first_parent,
+                message,
+                author_name,
+                author_email,
+            })
+        })
+        .collect())
+}
+
+/// All of a commit made by a recognized agent bot account goes to the agent.
+fn bot_summary(commit: &CommitInfo) -> Option<TrailerSummary> {
+    let tool = bot_tool(
+        Config::get().bot_authors(),
+        &commit.author_name,
+        &commit.author_email,
+    )?;
+    Some(TrailerSummary {
+        ai_percent: 100,
+        tools: [tool].into_iter().collect(),
+    })
+}
+
+/// Attribute `ai_percent` of the lines `commit` adds to `agents`, in file/line order.
+pub(crate) fn synthesize_authorship_log(
+    repo: &Repository,
+    commit: &CommitInfo,
+    ai_percent: u32,
+    agents: &[AgentId],
+) -> Result<AuthorshipLog, GitAiError> {
+    let parent = commit.first_parent.as_deref().unwrap_or(EMPTY_TREE_HASH);
+    let added = repo.diff_added_lines(parent, &commit.sha, None)?;
+
+    let mut files: Vec<(String, Vec<u32>)> = added.into_iter().collect();
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    let total_added: usize = files.iter().map(|(_, lines)| lines.len()).sum();
+    let ai_lines = ((total_added as f64) * (ai_percent as f64) / 100.0).round() as usize;
+
+    // Split the AI share evenly across agents (earlier agents absorb the remainder)
+    let quotas: Vec<usize> = (0..agents.len())
+        .map(|i| ai_lines / agents.len() + usize::from(i < ai_lines % agents.len()))
+        .collect();
+
+    let mut log = AuthorshipLog::new();
+    log.metadata.base_commit_sha = commit.sha.clone();
+
+    let mut tool_index = 0;
+    let mut used_in_tool = 0;
+    'files: for (file, mut lines) in files {
+        lines.sort_unstable();
+        let mut remaining = lines.as_slice();
+        while !remaining.is_empty() {
+            while tool_index < agents.len() && used_in_tool >= quotas[tool_index] {
+                tool_index += 1;
+                used_in_tool = 0;
+            }
+            if tool_index >= agents.len() {
+                break 'files;
+            }
+
+            let take = (quotas[tool_index] - used_in_tool).min(remaining.len());
+            let (assigned, rest) = remaining.split_at(take);
+            remaining = rest;
+            used_in_tool += take;
+
+            let agent_id = &agents[tool_index];
+            let hash = generate_short_hash(&agent_id.id, &agent_id.tool);
+            log.get_or_create_file(&file)
+                .add_entry(AttestationEntry::new(
+                    hash,
+                    LineRange::compress_lines(assigned),
+                ));
+        }
+    }
+
+    for (agent_id, quota) in agents.iter().zip(quotas) {
+        if quota == 0 {
+            continue;
+        }
+        let agent_id = agent_id.clone();
+        log.metadata.prompts.insert(
+            generate_short_hash(&agent_id.id, &agent_id.tool),
+            PromptRecord {
+                agent_id,
+                human_author: None,
+                messages: Vec::new(),
+                total_additions: quota as u32,
+                total_deletions: 0,
+                accepted_lines: quota as u32,
+                overriden_lines: 0,
+                messages_url: None,
+            },
+        );
+    }
+
+    Ok(log)
+}
+
+fn imported_agent_id(commit_sha: &str, tool: &str) -> AgentId {
+    AgentId {
+        tool: tool.to_string(),
+        id: format!("imported-{}", commit_sha),
+        model: IMPORTED_MODEL.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::refs::get_authorship;
+    use crate::git::test_utils::TmpRepo;
+
+    fn commit_with_message(tmp_repo: &TmpRepo, file: &str, content: &str, message: &str) {
+        let path = tmp_repo.path().join(file);
+        std::fs::write(&path, content).unwrap();
+        let repo = tmp_repo.gitai_repo();
+        repo.git(&["add", file]).unwrap();
+        repo.git(&["commit", "-m", message]).unwrap();
+    }
+
+    #[test]
+    fn test_import_synthesizes_log_from_trailers() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        commit_with_message(&tmp_repo, "a.txt", "one\n", "Initial");
+        commit_with_message(
+            &tmp_repo,
+            "a.txt",
+            "one\ntwo\nthree\nfour\nfive\n",
+            "Add lines\n\nAI-Assisted: 50%\nAI-Tools: cursor\n",
+        );
+        let repo = tmp_repo.gitai_repo();
+
+        let mut streamed = Vec::new();
+        let summary = import_commits(repo, &["HEAD".to_string()], false, |sha, _| {
+            streamed.push(sha.to_string())
+        })
+        .unwrap();
+        assert_eq!(summary.scanned, 2);
+        assert_eq!(summary.imported.len(), 1);
+        assert_eq!(streamed, vec![summary.imported[0].0.clone()]);
+
+        let head = repo.head().unwrap().target().unwrap();
+        let log = get_authorship(repo, &head).expect("note should be written");
+        let prompt = log.metadata.prompts.values().next().unwrap();
+        assert_eq!(prompt.agent_id.tool, "cursor");
+        assert_eq!(prompt.accepted_lines, 2);
+        assert_eq!(
+            log.attestations[0].entries[0].line_ranges,
+            vec![LineRange::Range(2, 3)]
+        );
+
+        // A second run leaves already-attributed commits alone
+        let again = import_commits(repo, &["HEAD".to_string()], false, |_, _| {}).unwrap();
+        assert_eq!(again.already_attributed, 1);
+        assert!(again.imported.is_empty());
+    }
+
+    #[test]
+    fn test_import_dry_run_writes_nothing() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        commit_with_message(
+            &tmp_repo,
+            "a.txt",
+            "one\ntwo\n",
+            "Initial\n\nAI-Assisted: 100%\nAI-Tools: claude,cursor\n",
+        );
+        let repo = tmp_repo.gitai_repo();
+
+        let summary = import_commits(repo, &["HEAD".to_string()], true, |_, _| {}).unwrap();
+        assert_eq!(summary.imported.len(), 1);
+        let head = repo.head().unwrap().target().unwrap();
+        assert!(get_authorship(repo, &head).is_none());
+    }
+
+    #[test]
+    fn test_import_attributes_bot_authored_commits() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        commit_with_message(&tmp_repo, "a.txt", "one\n", "Initial");
+        let repo = tmp_repo.gitai_repo();
+        std::fs::write(tmp_repo.path().join("a.txt"), "one\ntwo\n").unwrap();
+        repo.git(&["add", "a.txt"]).unwrap();
+        repo.git(&[
+            "commit",
+            "-m",
+            "Fix the bug",
+            "--author=Copilot <198982749+Copilot@users.noreply.github.com>",
+        ])
+        .unwrap();
+        std::fs::write(tmp_repo.path().join("a.txt"), "one\ntwo\nthree\n").unwrap();
+        repo.git(&["add", "a.txt"]).unwrap();
+        repo.git(&[
+            "commit",
+            "-m",
+            "Bump deps",
+            "--author=dependabot[bot] <49699333+dependabot[bot]@users.noreply.github.com>",
+        ])
+        .unwrap();
+
+        let summary = import_commits(repo, &["HEAD".to_string()], false, |_, _| {}).unwrap();
+        assert_eq!(summary.imported.len(), 1);
+        let (sha, trailer_summary) = &summary.imported[0];
+        assert_eq!(trailer_summary.ai_percent, 100);
+        assert!(trailer_summary.tools.contains("github-copilot"));
+
+        let log = get_authorship(repo, sha).expect("note should be written");
+        assert_eq!(
+            log.attestations[0].entries[0].line_ranges,
+            vec![LineRange::Single(2)]
+        );
+    }
+}
@@ -0,0 +1,212 @@
+//! Languages of files, for grouping stats and reports.
+//!
+//! A file's language comes from its extension, or its name for the few files that
+//! have none (`Dockerfile`, `Makefile`). Files neither identifies are `Other`.
+//!
+//! User-defined `language_overrides` (path glob → language) take precedence, so a
+//! team can split out what extensions can't tell apart, e.g. `tests/**` as
+//! `Rust tests`. When several globs match a path the longest, most specific one wins.
+
+use glob::Pattern;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Language of files nothing identifies
+pub const OTHER: &str = "Other";
+
+const EXTENSIONS: &[(&str, &str)] = &[
+    ("rs", "Rust"),
+    ("py", "Python"),
+    ("pyi", "Python"),
+    ("js", "JavaScript"),
+    ("jsx", "JavaScript"),
+    ("mjs", "JavaScript"),
+    ("cjs", "JavaScript"),
+    ("ts", "TypeScript"),
+    ("tsx", "TypeScript"),
+    ("mts", "TypeScript"),
+    ("go", "Go"),
+    ("java", "Java"),
+    ("kt", "Kotlin"),
+    ("kts", "Kotlin"),
+    ("scala", "Scala"),
+    ("swift", "Swift"),
+    ("m", "Objective-C"),
+    ("c", "C"),
+    ("h", "C"),
+    ("cc", "C++"),
+    ("cpp", "C++"),
+    ("cxx", "C++"),
+    ("hh", "C++"),
+    ("hpp", "C++"),
+    ("cs", "C#"),
+    ("rb", "Ruby"),
+    ("php", "PHP"),
+    ("ex", "Elixir"),
+    ("exs", "Elixir"),
+    ("erl", "Erlang"),
+    ("hs", "Haskell"),
+    ("ml", "OCaml"),
+    ("clj", "Clojure"),
+    ("dart", "Dart"),
+    ("lua", "Lua"),
+    ("zig", "Zig"),
+    ("sh", "Shell"),
+    ("bash", "Shell"),
+    ("zsh", "Shell"),
+    ("ps1", "PowerShell"),
+    ("sql", "SQL"),
+    ("html", "HTML"),
+    ("htm", "HTML"),
+    ("css", "CSS"),
+    ("scss", "SCSS"),
+    ("vue", "Vue"),
+    ("svelte", "Svelte"),
+    ("md", "Markdown"),
+    ("mdx", "Markdown"),
+    ("json", "JSON"),
+    ("yaml", "YAML"),
+    ("yml", "YAML"),
+    ("toml", "TOML"),
+    ("xml", "XML"),
+    ("proto", "Protocol Buffers"),
+    ("tf", "Terraform"),
+    ("nix", "Nix"),
+];
+
+const FILE_NAMES: &[(&str, &str)] = &[
+    ("Dockerfile", "Dockerfile"),
+    ("Makefile", "Makefile"),
+    ("GNUmakefile", "Makefile"),
+    ("CMakeLists.txt", "CMake"),
+    ("Rakefile", "Ruby"),
+    ("Gemfile", "Ruby"),
+];
+
+/// AI-authored lines out of all the lines counted for a language
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct LanguageLines {
+    pub ai_lines: u32,
+    pub total_lines: u32,
+}
+
+impl LanguageLines {
+    /// Share (0-100) of the lines written by AI
+    pub fn ai_percent(&self) -> u32 {
+        (self.ai_lines * 100)
+            .checked_div(self.total_lines)
+            .unwrap_or(0)
+    }
+}
+
+/// Language of `path`, with `overrides` taking precedence over detection.
+pub fn language_of(path: &str, overrides: &BTreeMap<String, String>) -> String {
+    let overridden = overrides
+        .iter()
+        .filter(|(glob, _)| Pattern::new(glob).is_ok_and(|pattern| pattern.matches(path)))
+        .max_by_key(|(glob, _)| glob.len());
+    if let Some((_, language)) = overridden {
+        return language.clone();
+    }
+
+    let name = path.rsplit('/').next().unwrap_or(path);
+    if let Some((_, language)) = FILE_NAMES.iter().find(|(file, _)| *file == name) {
+        return language.to_string();
+    }
+    let language = name
+        .rsplit_once('.')
+        .filter(|(stem, _)| !stem.is_empty())
+        .and_then(|(_, extension)| {
+            EXTENSIONS
+                .iter()
+                .find(|(known, _)| known.eq_ignore_ascii_case(extension))
+        })
+        .map_or(OTHER, |(_, language)| language);
+    language.to_string()
+}
+
+/// `(path, AI lines, total lines)` of files, summed by language
+pub fn group_by_language<'a>(
+    files: impl IntoIterator<Item = (&'a str, u32, u32)>,
+    overrides: &BTreeMap<String, String>,
+) -> BTreeMap<String, LanguageLines> {
+    let mut languages: BTreeMap<String, LanguageLines> = BTreeMap::new();
+    for (path, ai_lines, total_lines) in files {
+        let lines = languages.entry(language_of(path, overrides)).or_default();
+        lines.ai_lines += ai_lines;
+        lines.total_lines += total_lines;
+    }
+    languages
+}
+
+/// Languages with the most lines first
+pub fn sorted_by_lines(languages: &BTreeMap<String, LanguageLines>) -> Vec<(&str, LanguageLines)> {
+    let mut sorted: Vec<(&str, LanguageLines)> = languages
+        .iter()
+        .map(|(language, lines)| (language.as_str(), *lines))
+        .collect();
+    sorted.sort_by_key(|(_, lines)| std::cmp::Reverse(lines.total_lines));
+    sorted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_languages_by_extension_and_name() {
+        let none = BTreeMap::new();
+        assert_eq!(language_of("src/main.rs", &none), "Rust");
+        assert_eq!(language_of("web/App.TSX", &none), "TypeScript");
+        assert_eq!(language_of("docker/Dockerfile", &none), "Dockerfile");
+        assert_eq!(language_of("README", &none), OTHER);
+        assert_eq!(language_of(".gitignore", &none), OTHER);
+        assert_eq!(language_of("data.bin", &none), OTHER);
+    }
+
+    #[test]
+    fn test_most_specific_override_wins() {
+        let overrides: BTreeMap<String, String> = [
+            ("*.h", "C++"),
+            ("tests/**", "Rust tests"),
+            ("tests/fixtures/**", "Fixtures"),
+        ]
+        .into_iter()
+        .map(|(glob, language)| (glob.to_string(), language.to_string()))
+        .collect();
+        assert_eq!(language_of("include/api.h", &overrides), "C++");
+        assert_eq!(language_of("tests/stats.rs", &overrides), "Rust tests");
+        assert_eq!(
+            language_of("tests/fixtures/sample.rs", &overrides),
+            "Fixtures"
+        );
+        assert_eq!(language_of("src/lib.rs", &overrides), "Rust");
+    }
+
+    #[test]
+    fn test_group_by_language() {
+        let languages = group_by_language(
+            [
+                ("src/lib.rs", 2, 10),
+                ("src/main.rs", 0, 5),
+                ("app.py", 30, 40),
+            ],
+            &BTreeMap::new(),
+        );
+        assert_eq!(
+            languages["Rust"],
+            LanguageLines {
+                ai_lines: 2,
+                total_lines: 15
+            }
+        );
+        assert_eq!(languages["Python"].ai_percent(), 75);
+        assert_eq!(
+            sorted_by_lines(&languages)
+                .iter()
+                .map(|(language, _)| *language)
+                .collect::<Vec<_>>(),
+            vec!["Python", "Rust"]
+        );
+    }
+}
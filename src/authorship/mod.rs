@@ -1,17 +1,36 @@
+pub mod agent_ingest;
+pub mod anonymize;
+pub mod async_finalize;
 pub mod attribution_tracker;
+pub mod audit;
 pub mod authorship_log;
 pub mod authorship_log_serialization;
+pub mod bot_authors;
+pub mod codeowners;
+pub mod commit_trailers;
 pub mod diff_ai_accepted;
+pub mod diff_cache;
+pub mod history_import;
 pub mod imara_diff_utils;
 pub mod internal_db;
+pub mod languages;
+pub mod model_names;
 pub mod move_detection;
+pub mod org_metrics;
+pub mod paste_detection;
+pub mod path_config;
+pub mod policy;
 pub mod post_commit;
 pub mod pre_commit;
 pub mod prompt_utils;
+pub mod push_policy;
 pub mod range_authorship;
 pub mod rebase_authorship;
+pub mod reconcile;
 pub mod secrets;
+pub mod spill;
 pub mod stats;
+pub mod status_summary;
 pub mod transcript;
 pub mod virtual_attribution;
 pub mod working_log;
@@ -0,0 +1,189 @@
+//! Canonical model families for aggregated stats.
+//!
+//! Agents report the same model under many identifiers: dated snapshots
+//! (`claude-sonnet-4-5-20250929`), provider prefixes (`anthropic/claude-sonnet-4.5`,
+//! `us.anthropic.claude-sonnet-4-5-20250929-v1:0`), `-latest` aliases and shorthands.
+//! Authorship notes keep the raw identifier; only the keys stats are grouped under
+//! are normalized, so new rules apply retroactively to old commits.
+//!
+//! User-defined `model_aliases` (raw identifier or glob → family) are checked against
+//! the raw identifier and again against the built-in result, and override both.
+
+use glob::Pattern;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::sync::OnceLock;
+
+/// Shorthands agents accept in place of a full model id
+const BUILTIN_ALIASES: &[(&str, &str)] = &[
+    ("sonnet", "claude-sonnet"),
+    ("opus", "claude-opus"),
+    ("haiku", "claude-haiku"),
+    ("chatgpt-4o", "gpt-4o"),
+];
+
+/// Prefixes cloud providers put in front of the vendor's model id
+const PROVIDER_PREFIXES: &[&str] = &[
+    "us.",
+    "eu.",
+    "apac.",
+    "global.",
+    "anthropic.",
+    "openai.",
+    "google.",
+    "meta.",
+];
+
+fn snapshot_suffix() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        // -20250929, -2024-08-06, -0613, -latest, and Bedrock's -v1:0 revisions
+        Regex::new(r"(-v\d+:\d+|:\d+|-\d{8}|-\d{4}-\d{2}-\d{2}|-\d{4}|-latest)$").unwrap()
+    })
+}
+
+fn claude_version_first() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^claude-(\d+(?:-\d+)?)-(opus|sonnet|haiku)$").unwrap())
+}
+
+/// Canonical family of `model`, using the aliases from the git-ai config.
+pub fn normalize_model(model: &str) -> String {
+    normalize_model_with(model, crate::config::Config::get().model_aliases())
+}
+
+/// Canonical family of `model`, with `aliases` taking precedence over the built-in rules.
+pub fn normalize_model_with(model: &str, aliases: &BTreeMap<String, String>) -> String {
+    let raw = model.trim().to_ascii_lowercase();
+    if raw.is_empty() {
+        return "unknown".to_string();
+    }
+    if let Some(family) = lookup_alias(aliases, &raw) {
+        return family;
+    }
+    let normalized = builtin_normalize(&raw);
+    lookup_alias(aliases, &normalized).unwrap_or(normalized)
+}
+
+/// Stats key for a tool and model, e.g. `claude::claude-sonnet-4-5`.
+pub fn tool_model_key(tool: &str, model: &str) -> String {
+    format!("{}::{}", tool, normalize_model(model))
+}
+
+fn lookup_alias(aliases: &BTreeMap<String, String>, model: &str) -> Option<String> {
+    // Exact entries win over globs regardless of ordering
+    aliases
+        .iter()
+        .find(|(from, _)| from.eq_ignore_ascii_case(model))
+        .or_else(|| {
+            aliases.iter().find(|(from, _)| {
+                Pattern::new(&from.to_ascii_lowercase()).is_ok_and(|pattern| pattern.matches(model))
+            })
+        })
+        .map(|(_, family)| family.clone())
+}
+
+fn builtin_normalize(raw: &str) -> String {
+    // openai/gpt-4.1, models/gemini-2.5-pro, openrouter/anthropic/claude-...
+    let mut model = raw.rsplit('/').next().unwrap_or(raw).to_string();
+
+    while let Some(prefix) = PROVIDER_PREFIXES.iter().find(|p| model.starts_with(*p)) {
+        model = model[prefix.len()..].to_string();
+    }
+
+    // Vertex pins snapshots with `@`: claude-3-5-sonnet@20241022
+    if let Some((base, _)) = model.split_once('@') {
+        model = base.to_string();
+    }
+
+    loop {
+        let stripped = snapshot_suffix().replace(&model, "").to_string();
+        if stripped == model || stripped.is_empty() {
+            break;
+        }
+        model = stripped;
+    }
+
+    if let Some((_, family)) = BUILTIN_ALIASES.iter().find(|(from, _)| *from == model) {
+        return family.to_string();
+    }
+
+    if model.starts_with("claude-") {
+        // claude-3.5-sonnet and claude-3-5-sonnet; put the tier first like newer ids do
+        model = model.replace('.', "-");
+        if let Some(captures) = claude_version_first().captures(&model) {
+            model = format!("claude-{}-{}", &captures[2], &captures[1]);
+        }
+    }
+
+    model
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(model: &str) -> String {
+        normalize_model_with(model, &BTreeMap::new())
+    }
+
+    #[test]
+    fn test_claude_variants_share_a_family() {
+        for variant in [
+            "claude-sonnet-4-5",
+            "claude-sonnet-4-5-20250929",
+            "Claude-Sonnet-4.5",
+            "anthropic/claude-sonnet-4.5",
+            "claude-4.5-sonnet",
+            "us.anthropic.claude-sonnet-4-5-20250929-v1:0",
+            "claude-sonnet-4-5@20250929",
+            "claude-sonnet-4-5-latest",
+        ] {
+            assert_eq!(normalize(variant), "claude-sonnet-4-5", "{}", variant);
+        }
+        assert_eq!(normalize("claude-3-5-sonnet-20241022"), "claude-sonnet-3-5");
+        assert_eq!(normalize("claude-opus-4-1"), "claude-opus-4-1");
+        assert_eq!(normalize("sonnet"), "claude-sonnet");
+    }
+
+    #[test]
+    fn test_other_vendors() {
+        assert_eq!(normalize("gpt-4o-2024-08-06"), "gpt-4o");
+        assert_eq!(normalize("openai/gpt-4.1"), "gpt-4.1");
+        assert_eq!(normalize("gpt-4-0613"), "gpt-4");
+        assert_eq!(normalize("chatgpt-4o-latest"), "gpt-4o");
+        assert_eq!(normalize("models/gemini-2.5-pro"), "gemini-2.5-pro");
+        assert_eq!(normalize("o1-preview"), "o1-preview");
+        assert_eq!(normalize("gpt-5"), "gpt-5");
+        assert_eq!(normalize("deepseek-v3"), "deepseek-v3");
+        assert_eq!(normalize(""), "unknown");
+        assert_eq!(normalize("unknown"), "unknown");
+    }
+
+    #[test]
+    fn test_configured_aliases() {
+        let aliases: BTreeMap<String, String> = [
+            ("acme-*".to_string(), "acme".to_string()),
+            ("claude-sonnet-4-5".to_string(), "sonnet-4.5".to_string()),
+            ("gpt-4o-2024-08-06".to_string(), "gpt-4o-august".to_string()),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(normalize_model_with("acme-large-v2", &aliases), "acme");
+        // Applied to the built-in result as well as the raw id
+        assert_eq!(
+            normalize_model_with("anthropic/claude-sonnet-4.5", &aliases),
+            "sonnet-4.5"
+        );
+        // Raw matches are checked before snapshots are stripped
+        assert_eq!(
+            normalize_model_with("gpt-4o-2024-08-06", &aliases),
+            "gpt-4o-august"
+        );
+        assert_eq!(
+            normalize_model_with("gpt-4o-2024-05-13", &aliases),
+            "gpt-4o"
+        );
+    }
+}
@@ -0,0 +1,311 @@
+//! Aggregated, privacy-filtered AI usage for an organization, built from the local
+//! prompts database and uploaded by `git-ai sync-prompts --org`.
+//!
+//! Only totals leave the machine: per repository (by normalized remote URL, or a hash of
+//! its path when it has none), under the configured team, per tool and model. Prompts,
+//! transcripts, commit SHAs and author identities are never included; authors only
+//! count towards `contributors`. File paths are left out unless `include_paths` is set,
+//! in which case each repository also lists the AI lines of the files its commits
+//! changed, read from their authorship notes. With `anonymize` (or `--anonymize`),
+//! repositories and paths are replaced by pseudonyms and the time window is widened to
+//! whole days (see `authorship::anonymize`).
+
+use crate::authorship::anonymize::{Anonymizer, TIMESTAMP_BUCKET_SECS};
+use crate::authorship::internal_db::PromptDbRecord;
+use crate::authorship::policy;
+use crate::git::refs::get_authorship;
+use crate::git::repository::find_repository_in_path;
+use crate::repo_url::normalize_repo_url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Version of the payload format
+pub const ORG_METRICS_VERSION: u32 = 1;
+
+fn default_interval_hours() -> u64 {
+    24
+}
+
+/// `org_sync` in the git-ai config; org mode is off unless it is set
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrgSyncConfig {
+    /// Team the metrics are reported under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+    /// How often hooks upload in the background
+    #[serde(default = "default_interval_hours")]
+    pub interval_hours: u64,
+    /// Also report the AI lines of each file
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub include_paths: bool,
+    /// Report repositories and paths under pseudonyms
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub anonymize: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrgMetrics {
+    pub version: u32,
+    /// Prompts updated from `since` up to `until` (Unix seconds) are counted
+    pub since: i64,
+    pub until: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub team: Option<String>,
+    pub repos: Vec<RepoMetrics>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepoMetrics {
+    pub repo: String,
+    pub contributors: u32,
+    pub tools: Vec<ToolMetrics>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub files: Option<BTreeMap<String, u32>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ToolMetrics {
+    pub tool: String,
+    pub model: String,
+    pub sessions: u32,
+    pub commits: u32,
+    pub ai_additions: u64,
+    pub ai_deletions: u64,
+    pub accepted_lines: u64,
+    pub overridden_lines: u64,
+}
+
+/// Totals of `prompts` updated from `since` up to `until`
+pub fn aggregate(
+    prompts: &[PromptDbRecord],
+    config: &OrgSyncConfig,
+    since: i64,
+    until: i64,
+) -> OrgMetrics {
+    #[derive(Default)]
+    struct RepoTotals<'a> {
+        authors: BTreeSet<&'a str>,
+        commits: BTreeSet<&'a str>,
+        tools: BTreeMap<(&'a str, &'a str), (ToolMetrics, BTreeSet<&'a str>)>,
+    }
+
+    let mut by_workdir: BTreeMap<Option<&str>, RepoTotals> = BTreeMap::new();
+    for prompt in prompts
+        .iter()
+        .filter(|p| p.updated_at >= since && p.updated_at < until)
+    {
+        let repo = by_workdir.entry(prompt.workdir.as_deref()).or_default();
+        if let Some(author) = &prompt.human_author {
+            repo.authors.insert(author);
+        }
+        let (tool, commits) = repo
+            .tools
+            .entry((prompt.tool.as_str(), prompt.model.as_str()))
+            .or_insert_with(|| {
+                let tool = ToolMetrics {
+                    tool: prompt.tool.clone(),
+                    model: prompt.model.clone(),
+                    ..Default::default()
+                };
+                (tool, BTreeSet::new())
+            });
+        tool.sessions += 1;
+        tool.ai_additions += prompt.total_additions.unwrap_or(0) as u64;
+        tool.ai_deletions += prompt.total_deletions.unwrap_or(0) as u64;
+        tool.accepted_lines += prompt.accepted_lines.unwrap_or(0) as u64;
+        tool.overridden_lines += prompt.overridden_lines.unwrap_or(0) as u64;
+        if let Some(sha) = &prompt.commit_sha {
+            commits.insert(sha);
+            repo.commits.insert(sha);
+        }
+    }
+
+    let mut repos: BTreeMap<String, RepoMetrics> = BTreeMap::new();
+    for (workdir, totals) in by_workdir {
+        let name = repo_name(workdir);
+        let repo = repos.entry(name.clone()).or_insert_with(|| RepoMetrics {
+            repo: name,
+            ..Default::default()
+        });
+        repo.contributors += totals.authors.len() as u32;
+        for (tool, commits) in totals.tools.into_values() {
+            match repo
+                .tools
+                .iter_mut()
+                .find(|t| t.tool == tool.tool && t.model == tool.model)
+            {
+                Some(existing) => {
+                    existing.sessions += tool.sessions;
+                    existing.commits += commits.len() as u32;
+                    existing.ai_additions += tool.ai_additions;
+                    existing.ai_deletions += tool.ai_deletions;
+                    existing.accepted_lines += tool.accepted_lines;
+                    existing.overridden_lines += tool.overridden_lines;
+                }
+                None => repo.tools.push(ToolMetrics {
+                    commits: commits.len() as u32,
+                    ..tool
+                }),
+            }
+        }
+        if config.include_paths
+            && let Some(workdir) = workdir
+        {
+            let files = repo.files.get_or_insert_with(BTreeMap::new);
+            for (file, lines) in ai_lines_by_file(workdir, &totals.commits) {
+                *files.entry(file).or_default() += lines;
+            }
+        }
+    }
+
+    OrgMetrics {
+        version: ORG_METRICS_VERSION,
+        since,
+        until,
+        team: config.team.clone(),
+        repos: repos.into_values().collect(),
+    }
+}
+
+/// `metrics` with its repositories and paths under pseudonyms, over whole days
+pub fn anonymized(metrics: OrgMetrics, anonymizer: &Anonymizer) -> OrgMetrics {
+    OrgMetrics {
+        since: anonymizer.timestamp(metrics.since),
+        until: anonymizer.timestamp(metrics.until + TIMESTAMP_BUCKET_SECS - 1),
+        repos: metrics
+            .repos
+            .into_iter()
+            .map(|repo| RepoMetrics {
+                repo: anonymizer.repo(&repo.repo),
+                files: repo.files.map(|files| {
+                    let mut anonymized = BTreeMap::new();
+                    for (path, lines) in files {
+                        *anonymized.entry(anonymizer.path(&path)).or_default() += lines;
+                    }
+                    anonymized
+                }),
+                ..repo
+            })
+            .collect(),
+        ..metrics
+    }
+}
+
+/// The normalized URL of the repository's default remote, so that clones on different
+/// machines add up; a hash of its path otherwise, which says nothing about it
+fn repo_name(workdir: Option<&str>) -> String {
+    let Some(workdir) = workdir else {
+        return "unknown".to_string();
+    };
+    let url = find_repository_in_path(workdir).ok().and_then(|repo| {
+        let remote = repo.get_default_remote().ok()??;
+        let remotes = repo.remotes_with_urls().ok()?;
+        let (_, url) = remotes.into_iter().find(|(name, _)| *name == remote)?;
+        normalize_repo_url(&url).ok()
+    });
+    url.unwrap_or_else(|| {
+        let digest = Sha256::digest(workdir.as_bytes());
+        format!("local:{}", &format!("{:x}", digest)[..12])
+    })
+}
+
+/// AI lines per file in the authorship notes of `commits`
+fn ai_lines_by_file(workdir: &str, commits: &BTreeSet<&str>) -> BTreeMap<String, u32> {
+    let mut files = BTreeMap::new();
+    let Ok(repo) = find_repository_in_path(workdir) else {
+        return files;
+    };
+    for sha in commits {
+        let Some(log) = get_authorship(&repo, sha) else {
+            continue;
+        };
+        for (file, by_prompt) in policy::ai_lines_by_file(&log) {
+            *files.entry(file).or_default() += by_prompt.values().sum::<u32>();
+        }
+    }
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::transcript::AiTranscript;
+
+    fn prompt(workdir: &str, tool: &str, author: &str, sha: &str, added: u32) -> PromptDbRecord {
+        PromptDbRecord {
+            id: format!("{}{}{}", tool, author, sha),
+            workdir: Some(workdir.to_string()),
+            tool: tool.to_string(),
+            model: format!("{}-model", tool),
+            external_thread_id: "thread".to_string(),
+            messages: AiTranscript { messages: vec![] },
+            commit_sha: Some(sha.to_string()),
+            agent_metadata: None,
+            human_author: Some(author.to_string()),
+            total_additions: Some(added),
+            total_deletions: Some(1),
+            accepted_lines: Some(added),
+            overridden_lines: None,
+            created_at: 100,
+            updated_at: 100,
+        }
+    }
+
+    #[test]
+    fn test_aggregate_keeps_only_totals() {
+        let prompts = vec![
+            prompt("/nowhere/a", "claude", "alice@example.com", "sha1", 10),
+            prompt("/nowhere/a", "claude", "bob@example.com", "sha2", 5),
+            prompt("/nowhere/a", "cursor", "alice@example.com", "sha2", 3),
+            prompt("/nowhere/b", "claude", "alice@example.com", "sha3", 7),
+        ];
+        let config = OrgSyncConfig {
+            team: Some("payments".to_string()),
+            interval_hours: 24,
+            include_paths: false,
+            anonymize: false,
+        };
+        let metrics = aggregate(&prompts, &config, 0, 200);
+        assert_eq!(metrics.team.as_deref(), Some("payments"));
+        assert_eq!(metrics.repos.len(), 2);
+
+        let a = metrics
+            .repos
+            .iter()
+            .find(|r| r.repo == repo_name(Some("/nowhere/a")))
+            .unwrap();
+        assert!(a.repo.starts_with("local:"));
+        assert_eq!(a.contributors, 2);
+        assert_eq!(a.files, None);
+        assert_eq!(
+            a.tools[0],
+            ToolMetrics {
+                tool: "claude".to_string(),
+                model: "claude-model".to_string(),
+                sessions: 2,
+                commits: 2,
+                ai_additions: 15,
+                ai_deletions: 2,
+                accepted_lines: 15,
+                overridden_lines: 0,
+            }
+        );
+        assert_eq!(a.tools[1].tool, "cursor");
+
+        let payload = serde_json::to_string(&metrics).unwrap();
+        for private in ["alice", "sha1", "/nowhere", "thread"] {
+            assert!(!payload.contains(private), "{} in {}", private, payload);
+        }
+
+        assert!(aggregate(&prompts, &config, 150, 200).repos.is_empty());
+
+        let anonymized = anonymized(metrics.clone(), &Anonymizer::with_key("key"));
+        assert_eq!(
+            (anonymized.since, anonymized.until),
+            (0, TIMESTAMP_BUCKET_SECS)
+        );
+        assert!(anonymized.repos.iter().all(|r| r.repo.starts_with("repo-")));
+        assert!(anonymized.repos.iter().any(|r| r.tools == a.tools));
+    }
+}
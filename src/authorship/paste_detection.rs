@@ -0,0 +1,199 @@
+//! Heuristic flagging of pasted AI output, behind the `paste_detection` feature flag.
+//!
+//! Editors report their edits to the daemon. Text typed by hand arrives as a stream of
+//! keystroke-scale insertions; a chat answer pasted into the buffer arrives as one large
+//! multi-line insertion with no typing right before it. Those are recorded as AI
+//! checkpoints marked low-confidence, which `git-ai review-pending` confirms (dropping
+//! the marker) or dismisses (handing the lines back to the human).
+
+use crate::authorship::authorship_log_serialization::generate_short_hash;
+use crate::authorship::working_log::{Checkpoint, CheckpointKind};
+use crate::error::GitAiError;
+use crate::git::repo_storage::PersistedWorkingLog;
+use std::collections::HashMap;
+
+/// Tool recorded for flagged pastes
+pub const PASTE_TOOL: &str = "pasted";
+
+/// `agent_metadata` key marking a checkpoint as awaiting review
+pub const CONFIDENCE_KEY: &str = "confidence";
+pub const LOW_CONFIDENCE: &str = "low";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasteHeuristic {
+    /// Smallest insertion, in lines, considered a paste
+    pub min_lines: usize,
+    /// Smallest insertion, in characters, considered a paste
+    pub min_chars: usize,
+    /// Largest insertion still counted as a keystroke
+    pub keystroke_max_chars: usize,
+    /// How recent a keystroke in the same file must be to rule out a paste
+    pub typing_window_ms: u128,
+}
+
+impl Default for PasteHeuristic {
+    fn default() -> Self {
+        PasteHeuristic {
+            min_lines: 8,
+            min_chars: 200,
+            keystroke_max_chars: 3,
+            typing_window_ms: 2000,
+        }
+    }
+}
+
+/// Edit history the heuristic needs, per file.
+#[derive(Debug, Default)]
+pub struct PasteDetector {
+    pub heuristic: PasteHeuristic,
+    last_keystroke_ms: HashMap<String, u128>,
+}
+
+impl PasteDetector {
+    /// Record an insertion of `text` into `file` at `now_ms`; returns true if it
+    /// looks like pasted output.
+    pub fn observe(&mut self, file: &str, text: &str, now_ms: u128) -> bool {
+        let heuristic = &self.heuristic;
+        if text.chars().count() <= heuristic.keystroke_max_chars {
+            self.last_keystroke_ms.insert(file.to_string(), now_ms);
+            return false;
+        }
+        if text.lines().count() < heuristic.min_lines || text.len() < heuristic.min_chars {
+            return false;
+        }
+        // Typing right before a big insertion points at snippets and completions
+        self.last_keystroke_ms
+            .get(file)
+            .is_none_or(|last| now_ms.saturating_sub(*last) > heuristic.typing_window_ms)
+    }
+}
+
+pub fn is_pending(checkpoint: &Checkpoint) -> bool {
+    checkpoint.kind != CheckpointKind::Human
+        && checkpoint
+            .agent_metadata
+            .as_ref()
+            .and_then(|metadata| metadata.get(CONFIDENCE_KEY))
+            .is_some_and(|confidence| confidence == LOW_CONFIDENCE)
+}
+
+/// Low-confidence checkpoints in the working log, oldest first.
+pub fn pending_checkpoints(
+    working_log: &PersistedWorkingLog,
+) -> Result<Vec<Checkpoint>, GitAiError> {
+    Ok(working_log
+        .read_all_checkpoints()?
+        .into_iter()
+        .filter(is_pending)
+        .collect())
+}
+
+/// Keep the AI attribution of the pending checkpoint for session `id`.
+/// Returns false if there is no such pending checkpoint.
+pub fn confirm(working_log: &PersistedWorkingLog, id: &str) -> Result<bool, GitAiError> {
+    let mut checkpoints = working_log.read_all_checkpoints()?;
+    let mut found = false;
+    for checkpoint in checkpoints.iter_mut().filter(|c| is_session(c, id)) {
+        if let Some(metadata) = checkpoint.agent_metadata.as_mut() {
+            metadata.remove(CONFIDENCE_KEY);
+        }
+        found = true;
+    }
+    if found {
+        working_log.write_all_checkpoints(&checkpoints)?;
+    }
+    Ok(found)
+}
+
+/// Attribute the lines of the pending checkpoint for session `id` to the human again,
+/// including in every later checkpoint that carried them forward.
+/// Returns false if there is no such pending checkpoint.
+pub fn dismiss(working_log: &PersistedWorkingLog, id: &str) -> Result<bool, GitAiError> {
+    let mut checkpoints = working_log.read_all_checkpoints()?;
+    let Some(tool) = checkpoints
+        .iter()
+        .find(|c| is_session(c, id))
+        .and_then(|c| c.agent_id.as_ref())
+        .map(|agent_id| agent_id.tool.clone())
+    else {
+        return Ok(false);
+    };
+
+    let author_id = generate_short_hash(id, &tool);
+    let human = CheckpointKind::Human.to_str();
+    for checkpoint in checkpoints.iter_mut() {
+        if is_session(checkpoint, id) {
+            checkpoint.kind = CheckpointKind::Human;
+            checkpoint.agent_id = None;
+            checkpoint.agent_metadata = None;
+            checkpoint.transcript = None;
+        }
+        for entry in checkpoint.entries.iter_mut() {
+            for attribution in entry.attributions.iter_mut() {
+                if attribution.author_id == author_id {
+                    attribution.author_id = human.clone();
+                }
+            }
+            for line_attribution in entry.line_attributions.iter_mut() {
+                if line_attribution.author_id == author_id {
+                    line_attribution.author_id = human.clone();
+                    line_attribution.overrode = None;
+                }
+            }
+        }
+    }
+    working_log.write_all_checkpoints(&checkpoints)?;
+    Ok(true)
+}
+
+fn is_session(checkpoint: &Checkpoint, id: &str) -> bool {
+    is_pending(checkpoint)
+        && checkpoint
+            .agent_id
+            .as_ref()
+            .is_some_and(|agent_id| agent_id.id == id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(lines: usize) -> String {
+        (0..lines)
+            .map(|i| format!("let value_{} = compute_something({});\n", i, i))
+            .collect()
+    }
+
+    #[test]
+    fn test_large_insertion_without_typing_is_a_paste() {
+        let mut detector = PasteDetector::default();
+        assert!(detector.observe("a.rs", &block(10), 10_000));
+        // Too small to matter
+        assert!(!detector.observe("a.rs", &block(2), 10_000));
+    }
+
+    #[test]
+    fn test_recent_typing_rules_out_a_paste() {
+        let mut detector = PasteDetector::default();
+        assert!(!detector.observe("a.rs", "f", 10_000));
+        assert!(!detector.observe("a.rs", &block(10), 11_000));
+        // Typing in another file doesn't count
+        assert!(detector.observe("b.rs", &block(10), 11_000));
+        // Nor does typing that stopped a while ago
+        assert!(detector.observe("a.rs", &block(10), 20_000));
+    }
+
+    #[test]
+    fn test_custom_thresholds() {
+        let mut detector = PasteDetector {
+            heuristic: PasteHeuristic {
+                min_lines: 2,
+                min_chars: 10,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert!(detector.observe("a.rs", &block(2), 10_000));
+        assert!(!detector.observe("a.rs", &block(1), 10_000));
+    }
+}
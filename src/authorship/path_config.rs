@@ -0,0 +1,177 @@
+//! Per-path settings of a repository, kept in `.git-ai/paths.toml` and honored by
+//! checkpoints, stats, policies and reports alike.
+//!
+//! ```toml
+//! [paths]
+//! "vendor/**" = "ignore"
+//! "tests/**" = "relaxed"
+//! "crypto/**" = "strict"
+//! ```
+//!
+//! - `ignore`: not checkpointed, left out of stats and reports, and never checked by a
+//!   policy, as if it were not part of the repository
+//! - `relaxed`: attributed and counted as usual, but no policy rule applies to it
+//! - `strict`: every policy rule applies to it, whatever the rule's `paths`
+//!
+//! As in `.gitattributes`, the last matching pattern decides. A pattern matches the
+//! whole path, or just the file name when it has no `/`; a trailing `/` names a
+//! directory and covers everything in it.
+
+use crate::error::GitAiError;
+use crate::git::repository::Repository;
+use glob::Pattern;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::SystemTime;
+use toml_edit::DocumentMut;
+
+/// Where the path settings live, relative to the repository root
+pub const PATHS_FILE: &str = ".git-ai/paths.toml";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathMode {
+    Ignore,
+    Relaxed,
+    Strict,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PathConfig {
+    /// In file order; the last match wins
+    entries: Vec<(String, Pattern, PathMode)>,
+}
+
+/// Path settings of each working tree, with the mtime they were read at
+type PathConfigCache = HashMap<PathBuf, (Option<SystemTime>, Arc<PathConfig>)>;
+
+static PATH_CONFIGS: OnceLock<Mutex<PathConfigCache>> = OnceLock::new();
+
+impl PathConfig {
+    /// The settings of the working tree; none when it has no settings file
+    pub fn load(repo: &Repository) -> Result<PathConfig, GitAiError> {
+        match std::fs::read_to_string(repo.workdir()?.join(PATHS_FILE)) {
+            Ok(source) => PathConfig::parse(&source),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(PathConfig::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// The settings committed at `rev`
+    pub fn load_at(repo: &Repository, rev: &str) -> Result<PathConfig, GitAiError> {
+        let mut blobs = repo.blobs_at_paths(rev, &[PATHS_FILE.to_string()])?;
+        match blobs.remove(PATHS_FILE) {
+            Some(source) => PathConfig::parse(&String::from_utf8_lossy(&source)),
+            None => Ok(PathConfig::default()),
+        }
+    }
+
+    /// [`PathConfig::load`], read again only when the file changes. An invalid file is
+    /// reported once and treated as empty, so that it never breaks a checkpoint.
+    pub fn for_repo(repo: &Repository) -> Arc<PathConfig> {
+        let Ok(workdir) = repo.workdir() else {
+            return Arc::default();
+        };
+        let mtime = std::fs::metadata(workdir.join(PATHS_FILE))
+            .and_then(|m| m.modified())
+            .ok();
+        let cache = PATH_CONFIGS.get_or_init(|| Mutex::new(HashMap::new()));
+        let mut cache = cache.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((cached_mtime, config)) = cache.get(&workdir)
+            && *cached_mtime == mtime
+        {
+            return Arc::clone(config);
+        }
+        let config = Arc::new(PathConfig::load(repo).unwrap_or_else(|e| {
+            crate::logging::warn(&format!("Ignoring {}: {}", PATHS_FILE, e));
+            PathConfig::default()
+        }));
+        cache.insert(workdir, (mtime, Arc::clone(&config)));
+        config
+    }
+
+    pub fn parse(source: &str) -> Result<PathConfig, GitAiError> {
+        let invalid = |message: String| GitAiError::Generic(format!("{}: {}", PATHS_FILE, message));
+        let doc: DocumentMut = source.parse().map_err(|e| invalid(format!("{}", e)))?;
+
+        let mut config = PathConfig::default();
+        for (key, item) in doc.iter() {
+            if key != "paths" {
+                return Err(invalid(format!("unknown key `{}`", key)));
+            }
+            let Some(paths) = item.as_table_like() else {
+                return Err(invalid("`paths` must be a table ([paths])".into()));
+            };
+            for (raw, mode) in paths.iter() {
+                let mode = match mode.as_str() {
+                    Some("ignore") => PathMode::Ignore,
+                    Some("relaxed") => PathMode::Relaxed,
+                    Some("strict") => PathMode::Strict,
+                    _ => {
+                        return Err(invalid(format!(
+                            "`{}` must be \"ignore\", \"relaxed\" or \"strict\"",
+                            raw
+                        )));
+                    }
+                };
+                let glob = match raw.strip_suffix('/') {
+                    Some(dir) => format!("{}/**", dir),
+                    None => raw.to_string(),
+                };
+                let pattern = Pattern::new(&glob)
+                    .map_err(|e| invalid(format!("invalid pattern `{}`: {}", raw, e)))?;
+                config.entries.push((glob, pattern, mode));
+            }
+        }
+        Ok(config)
+    }
+
+    /// The setting of `path` (relative to the repository root), if a pattern matches it
+    pub fn mode_of(&self, path: &str) -> Option<PathMode> {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        self.entries
+            .iter()
+            .rev()
+            .find(|(glob, pattern, _)| {
+                pattern.matches(path) || (!glob.contains('/') && pattern.matches(file_name))
+            })
+            .map(|(_, _, mode)| *mode)
+    }
+
+    pub fn ignores(&self, path: &str) -> bool {
+        self.mode_of(path) == Some(PathMode::Ignore)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_of_uses_the_last_matching_pattern() {
+        let config = PathConfig::parse(
+            r#"
+[paths]
+"vendor/" = "ignore"
+"vendor/patched/**" = "strict"
+"tests/**" = "relaxed"
+"*.pb.go" = "ignore"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.mode_of("vendor/lib/a.c"), Some(PathMode::Ignore));
+        assert_eq!(config.mode_of("vendor/patched/a.c"), Some(PathMode::Strict));
+        assert_eq!(config.mode_of("tests/unit/a.rs"), Some(PathMode::Relaxed));
+        assert_eq!(config.mode_of("api/v1/user.pb.go"), Some(PathMode::Ignore));
+        assert_eq!(config.mode_of("src/main.rs"), None);
+
+        let error = PathConfig::parse("[paths]\n\"a/**\" = \"skip\"\n").unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("`a/**` must be \"ignore\", \"relaxed\" or \"strict\""),
+            "{}",
+            error
+        );
+    }
+}
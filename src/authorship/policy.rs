@@ -0,0 +1,629 @@
+//! Declarative rules over AI authorship, kept in the repository as `.git-ai/policy.toml`
+//! and checked by `git-ai policy check`, `ci-gate`, `pre-push` and `pre-receive` next
+//! to the `push_policy` of the git-ai config.
+//!
+//! ```toml
+//! [[rules]]
+//! name = "payments"
+//! paths = ["src/payments/**"]
+//! max_ai_percent = 20
+//! require_human_checkpoint = true
+//!
+//! [[rules]]
+//! forbidden_tools = ["cursor"]
+//! forbidden_models = ["gpt-3.5*"]
+//! ```
+//!
+//! Each rule applies to the files matching one of its `paths` globs (all files when
+//! there are none) and checks, per commit:
+//! - `max_ai_percent`: the share of the lines the commit adds to those files that are
+//!   AI-authored
+//! - `forbidden_tools`, `forbidden_models`: globs no tool or model credited with lines
+//!   in those files may match
+//! - `require_human_checkpoint`: a human checkpoint touched each of those files after
+//!   the last AI edit to it, i.e. a human edited it before it was committed
+//! - `require_owner_review`: an owner of each of those files in `CODEOWNERS` (read from
+//!   the same tree as the policy) acknowledged the commit, in a `Reviewed-by:`,
+//!   `Acked-by:`, `Approved-by:` or `Signed-off-by:` trailer, or by approving the pull
+//!   request (passed to `ci-gate` as `--approved-by`)
+//!
+//! The settings of `.git-ai/paths.toml` override a rule's `paths`: no rule checks an
+//! `ignore` or `relaxed` path, and every rule checks a `strict` one.
+//!
+//! Rules are evaluated from authorship notes; commits without one are left to
+//! `push_policy.require_attribution`. Hooks read the policy a push is checked against
+//! from where the check runs: the working tree for `pre-push`, the default branch for
+//! `pre-receive`, and the target branch for `ci-gate`, so a change cannot relax the
+//! policy it is checked against.
+
+use crate::authorship::authorship_log::LineRange;
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::codeowners::{CodeOwners, names_owner};
+use crate::authorship::commit_trailers::parse_trailers;
+use crate::authorship::path_config::{PathConfig, PathMode};
+use crate::authorship::push_policy::PolicyViolation;
+use crate::authorship::working_log::{Checkpoint, CheckpointKind};
+use crate::error::GitAiError;
+use crate::git::refs::get_authorship;
+use crate::git::repository::Repository;
+use glob::Pattern;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use toml_edit::{DocumentMut, Item};
+
+/// Where the policy lives, relative to the repository root
+pub const POLICY_FILE: &str = ".git-ai/policy.toml";
+
+/// Trailers through which a reviewer acknowledges a commit
+const REVIEW_TRAILERS: &[&str] = &["Reviewed-by", "Acked-by", "Approved-by", "Signed-off-by"];
+
+#[derive(Debug, Clone, Default)]
+pub struct Policy {
+    pub rules: Vec<PolicyRule>,
+    /// Loaded with the policy when a rule requires owner review
+    pub codeowners: Option<CodeOwners>,
+    /// Reviewers who approved the whole change, e.g. from the pull request's reviews
+    pub approved_by: Vec<String>,
+    /// `.git-ai/paths.toml`, read from the same tree as the policy
+    pub path_config: PathConfig,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PolicyRule {
+    /// Shown in violation reports; `rule <n>` unless set
+    pub name: String,
+    /// Files the rule applies to; every file when empty
+    pub paths: Vec<Pattern>,
+    pub max_ai_percent: Option<u32>,
+    pub forbidden_tools: Vec<Pattern>,
+    pub forbidden_models: Vec<Pattern>,
+    pub require_human_checkpoint: bool,
+    pub require_owner_review: bool,
+}
+
+impl PolicyRule {
+    fn applies_to(&self, path: &str) -> bool {
+        self.paths.is_empty() || self.paths.iter().any(|p| p.matches(path))
+    }
+}
+
+impl Policy {
+    /// The policy of the working tree, if it has one
+    pub fn load(repo: &Repository) -> Result<Option<Policy>, GitAiError> {
+        let path = repo.workdir()?.join(POLICY_FILE);
+        let mut policy = match std::fs::read_to_string(&path) {
+            Ok(source) => Policy::parse(&source)?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        policy.path_config = PathConfig::load(repo)?;
+        if policy.requires_owner_review() {
+            policy.codeowners = CodeOwners::load(repo)?;
+        }
+        Ok(Some(policy))
+    }
+
+    /// The policy committed at `rev`, if it has one
+    pub fn load_at(repo: &Repository, rev: &str) -> Result<Option<Policy>, GitAiError> {
+        let mut blobs = repo.blobs_at_paths(rev, &[POLICY_FILE.to_string()])?;
+        let Some(source) = blobs.remove(POLICY_FILE) else {
+            return Ok(None);
+        };
+        let mut policy = Policy::parse(&String::from_utf8_lossy(&source))?;
+        policy.path_config = PathConfig::load_at(repo, rev)?;
+        if policy.requires_owner_review() {
+            policy.codeowners = CodeOwners::load_at(repo, rev)?;
+        }
+        Ok(Some(policy))
+    }
+
+    /// Whether `rule` checks `path`, given the path's setting in `.git-ai/paths.toml`
+    fn checks(&self, rule: &PolicyRule, path: &str) -> bool {
+        match self.path_config.mode_of(path) {
+            Some(PathMode::Ignore | PathMode::Relaxed) => false,
+            Some(PathMode::Strict) => true,
+            None => rule.applies_to(path),
+        }
+    }
+
+    fn requires_owner_review(&self) -> bool {
+        self.rules.iter().any(|r| r.require_owner_review)
+    }
+
+    pub fn parse(source: &str) -> Result<Policy, GitAiError> {
+        let invalid =
+            |message: String| GitAiError::Generic(format!("{}: {}", POLICY_FILE, message));
+        let doc: DocumentMut = source.parse().map_err(|e| invalid(format!("{}", e)))?;
+
+        let mut policy = Policy::default();
+        for (key, item) in doc.iter() {
+            if key != "rules" {
+                return Err(invalid(format!("unknown key `{}`", key)));
+            }
+            let Some(rules) = item.as_array_of_tables() else {
+                return Err(invalid(
+                    "`rules` must be an array of tables ([[rules]])".into(),
+                ));
+            };
+            for (i, table) in rules.iter().enumerate() {
+                let rule = parse_rule(i + 1, table)
+                    .map_err(|e| invalid(format!("rule {}: {}", i + 1, e)))?;
+                policy.rules.push(rule);
+            }
+        }
+        Ok(policy)
+    }
+
+    /// Check each of `commits` against every rule
+    pub fn evaluate_commits(
+        &self,
+        repo: &Repository,
+        commits: &[String],
+    ) -> Result<Vec<PolicyViolation>, GitAiError> {
+        let mut violations = Vec::new();
+        if self.rules.is_empty() {
+            return Ok(violations);
+        }
+        for sha in commits {
+            let Some(log) = get_authorship(repo, sha) else {
+                continue;
+            };
+            let message = repo.git(&["log", "-1", "--format=%B", sha])?;
+            let subject = message
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string();
+            let mut reviewers: Vec<String> = parse_trailers(&message)
+                .into_iter()
+                .filter(|t| {
+                    REVIEW_TRAILERS
+                        .iter()
+                        .any(|k| t.key.eq_ignore_ascii_case(k))
+                })
+                .map(|t| t.value)
+                .collect();
+            reviewers.extend(self.approved_by.iter().cloned());
+            let added_lines = if self.rules.iter().any(|r| r.max_ai_percent.is_some()) {
+                added_lines_by_file(repo, sha)?
+            } else {
+                HashMap::new()
+            };
+            for reason in self.violations_in(&log, &added_lines, &reviewers) {
+                violations.push(PolicyViolation {
+                    commit_sha: sha.clone(),
+                    subject: subject.clone(),
+                    reason,
+                });
+            }
+        }
+        Ok(violations)
+    }
+
+    /// Why the commit with authorship `log`, adding `added_lines` to each file and
+    /// acknowledged by `reviewers`, breaks the rules
+    fn violations_in(
+        &self,
+        log: &AuthorshipLog,
+        added_lines: &HashMap<String, u32>,
+        reviewers: &[String],
+    ) -> Vec<String> {
+        let ai_lines = ai_lines_by_file(log);
+        let mut reasons = Vec::new();
+        for rule in &self.rules {
+            let files: Vec<(&String, &BTreeMap<String, u32>)> = ai_lines
+                .iter()
+                .filter(|(file, _)| self.checks(rule, file))
+                .collect();
+
+            if let Some(max) = rule.max_ai_percent {
+                let ai: u32 = files
+                    .iter()
+                    .flat_map(|(_, by_prompt)| by_prompt.values())
+                    .sum();
+                let added: u32 = added_lines
+                    .iter()
+                    .filter(|(file, _)| self.checks(rule, file))
+                    .map(|(_, lines)| lines)
+                    .sum();
+                if added > 0 && ai > 0 {
+                    let percent = (ai.min(added) as f64 / added as f64 * 100.0).round() as u32;
+                    if percent > max {
+                        reasons.push(format!(
+                            "rule `{}`: {}% of the added lines are AI-authored, more than max_ai_percent ({}%)",
+                            rule.name, percent, max
+                        ));
+                    }
+                }
+            }
+
+            let mut forbidden: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+            for (file, by_prompt) in &files {
+                for hash in by_prompt.keys() {
+                    let Some(prompt) = log.metadata.prompts.get(hash) else {
+                        continue;
+                    };
+                    let agent = &prompt.agent_id;
+                    if rule.forbidden_tools.iter().any(|p| p.matches(&agent.tool)) {
+                        forbidden
+                            .entry(format!("tool `{}`", agent.tool))
+                            .or_default()
+                            .insert(file.as_str());
+                    }
+                    if rule
+                        .forbidden_models
+                        .iter()
+                        .any(|p| p.matches(&agent.model))
+                    {
+                        forbidden
+                            .entry(format!("model `{}`", agent.model))
+                            .or_default()
+                            .insert(file.as_str());
+                    }
+                }
+            }
+            for (what, files) in forbidden {
+                reasons.push(format!(
+                    "rule `{}`: AI-authored lines from forbidden {} in {}",
+                    rule.name,
+                    what,
+                    files.into_iter().collect::<Vec<_>>().join(", ")
+                ));
+            }
+
+            if rule.require_human_checkpoint {
+                for (file, _) in &files {
+                    if !log.metadata.human_checked_files.contains(*file) {
+                        reasons.push(format!(
+                            "rule `{}`: AI-authored lines in {} without a human checkpoint after them",
+                            rule.name, file
+                        ));
+                    }
+                }
+            }
+
+            if rule.require_owner_review {
+                let codeowners = self.codeowners.as_ref();
+                for (file, _) in &files {
+                    let owners = codeowners.map_or(&[][..], |c| c.owners_of(file));
+                    let acknowledged = owners
+                        .iter()
+                        .any(|owner| reviewers.iter().any(|r| names_owner(r, owner)));
+                    if !owners.is_empty() && !acknowledged {
+                        reasons.push(format!(
+                            "rule `{}`: AI-authored lines in {} not acknowledged by an owner ({})",
+                            rule.name,
+                            file,
+                            owners.join(", ")
+                        ));
+                    }
+                }
+            }
+        }
+        reasons
+    }
+}
+
+fn parse_rule(n: usize, table: &toml_edit::Table) -> Result<PolicyRule, String> {
+    let mut rule = PolicyRule {
+        name: format!("rule {}", n),
+        ..Default::default()
+    };
+    for (key, item) in table.iter() {
+        match key {
+            "name" => rule.name = item.as_str().ok_or("`name` must be a string")?.to_string(),
+            "paths" => rule.paths = parse_globs(key, item)?,
+            "max_ai_percent" => {
+                rule.max_ai_percent = Some(
+                    item.as_integer()
+                        .filter(|p| (0..=100).contains(p))
+                        .ok_or("`max_ai_percent` must be an integer from 0 to 100")?
+                        as u32,
+                )
+            }
+            "forbidden_tools" => rule.forbidden_tools = parse_globs(key, item)?,
+            "forbidden_models" => rule.forbidden_models = parse_globs(key, item)?,
+            "require_human_checkpoint" => {
+                rule.require_human_checkpoint = item
+                    .as_bool()
+                    .ok_or("`require_human_checkpoint` must be true or false")?
+            }
+            "require_owner_review" => {
+                rule.require_owner_review = item
+                    .as_bool()
+                    .ok_or("`require_owner_review` must be true or false")?
+            }
+            _ => return Err(format!("unknown key `{}`", key)),
+        }
+    }
+    Ok(rule)
+}
+
+fn parse_globs(key: &str, item: &Item) -> Result<Vec<Pattern>, String> {
+    let array = item
+        .as_array()
+        .ok_or_else(|| format!("`{}` must be an array of glob strings", key))?;
+    array
+        .iter()
+        .map(|value| {
+            let glob = value
+                .as_str()
+                .ok_or_else(|| format!("`{}` must be an array of glob strings", key))?;
+            Pattern::new(glob).map_err(|e| format!("invalid glob `{}` in `{}`: {}", glob, key, e))
+        })
+        .collect()
+}
+
+/// AI-authored lines of each file of `log`, by prompt hash
+pub fn ai_lines_by_file(log: &AuthorshipLog) -> BTreeMap<String, BTreeMap<String, u32>> {
+    let mut files: BTreeMap<String, BTreeMap<String, u32>> = BTreeMap::new();
+    for attestation in &log.attestations {
+        for entry in &attestation.entries {
+            if !log.metadata.prompts.contains_key(&entry.hash) {
+                continue;
+            }
+            let lines: u32 = entry
+                .line_ranges
+                .iter()
+                .map(|range| match range {
+                    LineRange::Single(_) => 1,
+                    LineRange::Range(start, end) => end.saturating_sub(*start) + 1,
+                })
+                .sum();
+            *files
+                .entry(attestation.file_path.clone())
+                .or_default()
+                .entry(entry.hash.clone())
+                .or_default() += lines;
+        }
+    }
+    files
+}
+
+/// Lines `sha` adds to each text file it changes
+fn added_lines_by_file(repo: &Repository, sha: &str) -> Result<HashMap<String, u32>, GitAiError> {
+    let numstat = repo.git(&["show", "--numstat", "--no-renames", "--format=", sha])?;
+    Ok(numstat
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.splitn(3, '\t');
+            let added = parts.next()?.parse().ok()?;
+            let _deleted = parts.next()?;
+            Some((parts.next()?.to_string(), added))
+        })
+        .collect())
+}
+
+/// Files a human checkpoint touched after the last AI checkpoint that touched them
+pub fn human_checked_files(checkpoints: &[Checkpoint]) -> BTreeSet<String> {
+    let mut last_kind: HashMap<&str, (bool, CheckpointKind)> = HashMap::new();
+    for checkpoint in checkpoints {
+        for entry in &checkpoint.entries {
+            let had_ai = last_kind
+                .get(entry.file.as_str())
+                .is_some_and(|(had_ai, _)| *had_ai);
+            let is_ai = checkpoint.kind != CheckpointKind::Human;
+            last_kind.insert(&entry.file, (had_ai || is_ai, checkpoint.kind));
+        }
+    }
+    last_kind
+        .into_iter()
+        .filter(|(_, (had_ai, kind))| *had_ai && *kind == CheckpointKind::Human)
+        .map(|(file, _)| file.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::authorship_log::PromptRecord;
+    use crate::authorship::authorship_log_serialization::{AttestationEntry, FileAttestation};
+    use crate::authorship::working_log::{AgentId, WorkingLogEntry};
+
+    fn log_with(files: &[(&str, &str, u32)]) -> AuthorshipLog {
+        let mut log = AuthorshipLog::new();
+        for (file, tool, lines) in files {
+            let hash = format!("{}-prompt", tool);
+            log.metadata.prompts.insert(
+                hash.clone(),
+                PromptRecord {
+                    agent_id: AgentId {
+                        tool: tool.to_string(),
+                        id: "session".to_string(),
+                        model: format!("{}-model", tool),
+                    },
+                    human_author: None,
+                    messages: vec![],
+                    total_additions: *lines,
+                    total_deletions: 0,
+                    accepted_lines: *lines,
+                    overriden_lines: 0,
+                    messages_url: None,
+                },
+            );
+            let mut attestation = FileAttestation::new(file.to_string());
+            attestation.entries.push(AttestationEntry::new(
+                hash,
+                vec![LineRange::Range(1, *lines)],
+            ));
+            log.attestations.push(attestation);
+        }
+        log
+    }
+
+    #[test]
+    fn test_parse_reports_the_offending_rule() {
+        let policy = Policy::parse(
+            r#"
+[[rules]]
+name = "payments"
+paths = ["src/payments/**"]
+max_ai_percent = 20
+require_human_checkpoint = true
+
+[[rules]]
+forbidden_tools = ["cursor"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(policy.rules.len(), 2);
+        assert_eq!(policy.rules[0].name, "payments");
+        assert_eq!(policy.rules[0].max_ai_percent, Some(20));
+        assert!(policy.rules[0].require_human_checkpoint);
+        assert_eq!(policy.rules[1].name, "rule 2");
+
+        let error = Policy::parse("[[rules]]\nmax_ai_percent = 120\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Generic error: .git-ai/policy.toml: rule 1: `max_ai_percent` must be an integer from 0 to 100"
+        );
+        let error = Policy::parse("[[rules]]\nmax_ai = 1\n").unwrap_err();
+        assert!(error.to_string().contains("rule 1: unknown key `max_ai`"));
+        assert!(Policy::parse("rules = 1\n").is_err());
+        assert!(Policy::parse("[[rules]\n").is_err());
+    }
+
+    #[test]
+    fn test_violations_in() {
+        let policy = Policy::parse(
+            r#"
+[[rules]]
+name = "payments"
+paths = ["src/payments/**"]
+max_ai_percent = 50
+require_human_checkpoint = true
+
+[[rules]]
+name = "no cursor"
+forbidden_tools = ["cursor"]
+forbidden_models = ["gpt-*"]
+"#,
+        )
+        .unwrap();
+
+        let mut log = log_with(&[
+            ("src/payments/charge.rs", "claude", 8),
+            ("README.md", "cursor", 2),
+        ]);
+        let added = HashMap::from([
+            ("src/payments/charge.rs".to_string(), 10),
+            ("README.md".to_string(), 2),
+        ]);
+        assert_eq!(
+            policy.violations_in(&log, &added, &[]),
+            vec![
+                "rule `payments`: 80% of the added lines are AI-authored, more than max_ai_percent (50%)",
+                "rule `payments`: AI-authored lines in src/payments/charge.rs without a human checkpoint after them",
+                "rule `no cursor`: AI-authored lines from forbidden tool `cursor` in README.md",
+            ]
+        );
+
+        log.metadata
+            .human_checked_files
+            .insert("src/payments/charge.rs".to_string());
+        let added = HashMap::from([
+            ("src/payments/charge.rs".to_string(), 20),
+            ("README.md".to_string(), 2),
+        ]);
+        assert_eq!(policy.violations_in(&log, &added, &[]).len(), 1);
+    }
+
+    #[test]
+    fn test_violations_in_requires_an_owner_acknowledgement() {
+        let mut policy = Policy::parse(
+            r#"
+[[rules]]
+name = "owned"
+require_owner_review = true
+"#,
+        )
+        .unwrap();
+        policy.codeowners = Some(CodeOwners::parse(
+            "/src/payments/ @alice pay@example.com\n/docs/ @writers\n",
+        ));
+        let log = log_with(&[
+            ("src/payments/charge.rs", "claude", 4),
+            ("src/lib.rs", "claude", 2),
+        ]);
+
+        assert_eq!(
+            policy.violations_in(
+                &log,
+                &HashMap::new(),
+                &["Bob <bob@example.com>".to_string()]
+            ),
+            vec![
+                "rule `owned`: AI-authored lines in src/payments/charge.rs not acknowledged by an owner (@alice, pay@example.com)"
+            ]
+        );
+        for reviewer in ["Payments <pay@example.com>", "@Alice"] {
+            assert!(
+                policy
+                    .violations_in(&log, &HashMap::new(), &[reviewer.to_string()])
+                    .is_empty()
+            );
+        }
+    }
+
+    #[test]
+    fn test_violations_in_follows_path_settings() {
+        let mut policy = Policy::parse(
+            r#"
+[[rules]]
+name = "payments"
+paths = ["src/payments/**"]
+require_human_checkpoint = true
+"#,
+        )
+        .unwrap();
+        policy.path_config = PathConfig::parse(
+            r#"
+[paths]
+"src/payments/fixtures/**" = "relaxed"
+"vendor/**" = "ignore"
+"crypto/**" = "strict"
+"#,
+        )
+        .unwrap();
+        let log = log_with(&[
+            ("src/payments/charge.rs", "claude", 4),
+            ("src/payments/fixtures/card.json", "claude", 4),
+            ("vendor/lib.rs", "claude", 4),
+            ("crypto/aes.rs", "claude", 4),
+        ]);
+
+        assert_eq!(
+            policy.violations_in(&log, &HashMap::new(), &[]),
+            vec![
+                "rule `payments`: AI-authored lines in crypto/aes.rs without a human checkpoint after them",
+                "rule `payments`: AI-authored lines in src/payments/charge.rs without a human checkpoint after them",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_human_checked_files() {
+        let checkpoint = |kind: CheckpointKind, files: &[&str]| {
+            Checkpoint::new(
+                kind,
+                String::new(),
+                "author".to_string(),
+                files
+                    .iter()
+                    .map(|file| {
+                        WorkingLogEntry::new(file.to_string(), String::new(), vec![], vec![])
+                    })
+                    .collect(),
+            )
+        };
+        let checkpoints = [
+            checkpoint(CheckpointKind::Human, &["human_only.rs", "reviewed.rs"]),
+            checkpoint(CheckpointKind::AiAgent, &["reviewed.rs", "unreviewed.rs"]),
+            checkpoint(CheckpointKind::Human, &["reviewed.rs"]),
+        ];
+        assert_eq!(
+            human_checked_files(&checkpoints),
+            BTreeSet::from(["reviewed.rs".to_string()])
+        );
+    }
+}
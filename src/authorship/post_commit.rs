@@ -1,5 +1,6 @@
 use crate::api::{ApiClient, ApiContext};
 use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::policy::human_checked_files;
 use crate::authorship::prompt_utils::{PromptUpdateResult, update_prompt_from_tool};
 use crate::authorship::secrets::{redact_secrets_from_prompts, strip_prompt_messages};
 use crate::authorship::stats::{stats_for_commit_stats, write_stats_to_terminal};
@@ -10,7 +11,7 @@ use crate::error::GitAiError;
 use crate::git::refs::notes_add;
 use crate::git::repository::Repository;
 use crate::utils::debug_log;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::io::IsTerminal;
 
 pub fn post_commit(
@@ -93,6 +94,21 @@ pub fn post_commit(
         )?;
 
     authorship_log.metadata.base_commit_sha = commit_sha.clone();
+    let ai_files: HashSet<&str> = authorship_log
+        .attestations
+        .iter()
+        .filter(|a| {
+            a.entries
+                .iter()
+                .any(|e| authorship_log.metadata.prompts.contains_key(&e.hash))
+        })
+        .map(|a| a.file_path.as_str())
+        .collect();
+    let human_checked: BTreeSet<String> = human_checked_files(&parent_working_log)
+        .into_iter()
+        .filter(|file| ai_files.contains(file.as_str()))
+        .collect();
+    authorship_log.metadata.human_checked_files = human_checked;
 
     // Handle prompts based on effective prompt storage mode for this repository
     // The effective mode considers include/exclude lists and fallback settings
@@ -1,8 +1,36 @@
 use crate::authorship::working_log::CheckpointKind;
+use crate::commands::checkpoint_agent::agent_registry;
+use crate::config::Config;
 use crate::error::GitAiError;
 use crate::git::repository::Repository;
 
 pub fn pre_commit(repo: &Repository, default_author: String) -> Result<(), GitAiError> {
+    // A machine identity owns whatever the commit adds beyond the agents' checkpoints
+    let config = Config::get();
+    if let Some(identity) =
+        agent_registry::machine_identity(config.custom_agents(), |var| std::env::var(var).ok())
+    {
+        let mut files: Vec<String> = repo.get_worktree_filenames()?.into_iter().collect();
+        files.sort();
+        let agent_run_result = crate::commands::wrap::run_result(
+            &identity.name,
+            "unknown",
+            CheckpointKind::AiAgent,
+            files,
+        );
+        return crate::commands::checkpoint::run(
+            repo,
+            &default_author,
+            CheckpointKind::AiAgent,
+            false,
+            false,
+            true,
+            Some(agent_run_result),
+            false,
+        )
+        .map(|_| ());
+    }
+
     // Run checkpoint as human editor.
     let result: Result<(usize, usize, usize), GitAiError> = crate::commands::checkpoint::run(
         repo,
@@ -4,6 +4,7 @@ use crate::authorship::transcript::AiTranscript;
 use crate::commands::checkpoint_agent::agent_presets::{
     ClaudePreset, ContinueCliPreset, CursorPreset, DroidPreset, GeminiPreset, GithubCopilotPreset,
 };
+use crate::commands::checkpoint_agent::codex_preset::CodexPreset;
 use crate::commands::checkpoint_agent::opencode_preset::OpenCodePreset;
 use crate::error::GitAiError;
 use crate::git::refs::{get_authorship, grep_ai_notes};
@@ -169,6 +170,7 @@ pub fn update_prompt_from_tool(
     match tool {
         "cursor" => update_cursor_prompt(external_thread_id, agent_metadata, current_model),
         "claude" => update_claude_prompt(agent_metadata, current_model),
+        "codex" => update_codex_prompt(agent_metadata, current_model),
         "gemini" => update_gemini_prompt(agent_metadata, current_model),
         "github-copilot" => update_github_copilot_prompt(agent_metadata, current_model),
         "continue-cli" => update_continue_cli_prompt(agent_metadata, current_model),
@@ -207,10 +209,17 @@ fn update_cursor_prompt(
         CursorPreset::fetch_latest_cursor_conversation(conversation_id)
     };
     match res {
-        Ok(Some((latest_transcript, _db_model))) => {
+        Ok(Some((latest_transcript, db_model))) => {
             // For Cursor, preserve the model from the checkpoint (which came from hook input)
-            // rather than using the database model
-            PromptUpdateResult::Updated(latest_transcript, current_model.to_string())
+            // rather than using the database model, unless the hook didn't report one
+            let model = if CursorPreset::is_known_model(current_model)
+                || !CursorPreset::is_known_model(&db_model)
+            {
+                current_model.to_string()
+            } else {
+                db_model
+            };
+            PromptUpdateResult::Updated(latest_transcript, model)
         }
         Ok(None) => PromptUpdateResult::Unchanged,
         Err(e) => {
@@ -230,6 +239,29 @@ fn update_cursor_prompt(
     }
 }
 
+/// Update Codex prompt from its rollout log
+fn update_codex_prompt(
+    metadata: Option<&HashMap<String, String>>,
+    current_model: &str,
+) -> PromptUpdateResult {
+    let Some(session_path) = metadata.and_then(|m| m.get("session_path")) else {
+        return PromptUpdateResult::Unchanged;
+    };
+    match CodexPreset::session_from_rollout(std::path::Path::new(session_path)) {
+        Ok(session) => PromptUpdateResult::Updated(
+            session.transcript,
+            session.model.unwrap_or_else(|| current_model.to_string()),
+        ),
+        Err(e) => {
+            debug_log(&format!(
+                "Failed to read Codex session log {}: {}",
+                session_path, e
+            ));
+            PromptUpdateResult::Failed(e)
+        }
+    }
+}
+
 /// Update Claude prompt from transcript file
 fn update_claude_prompt(
     metadata: Option<&HashMap<String, String>>,
@@ -0,0 +1,325 @@
+//! Policies checked over the commits about to be pushed, either client-side by the
+//! `pre-push` hook or server-side by `git-ai pre-receive`.
+//!
+//! Policies are configured under `push_policy` in the git-ai config file. A push is
+//! blocked when any pushed commit violates one of them; on the client,
+//! `git push --no-verify` skips the check entirely.
+//!
+//! A commit's attribution comes from its authorship note, or failing that from the
+//! `AI-Assisted` trailer written by `commit_trailers`. Notes are pushed separately from
+//! (and concurrently with) the branch, so a server may run its check before the notes
+//! arrive; trailers travel inside the commit and are the reliable source there.
+
+use crate::authorship::commit_trailers::{parse_trailers, summary_from_trailers};
+use crate::authorship::stats::stats_for_commit_stats;
+use crate::error::GitAiError;
+use crate::git::refs::get_authorship;
+use crate::git::repository::Repository;
+use crate::i18n::tr;
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+
+const ZERO_SHA: &str = "0000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PushPolicy {
+    /// Largest share (0-100) of a commit's added lines that may be AI-authored
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_ai_percent: Option<u32>,
+    /// Glob patterns for paths where AI-authored lines are not allowed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub protected_paths: Vec<String>,
+    /// Trailer key (e.g. `Reviewed-by`) that commits with AI lines must carry
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_review_marker: Option<String>,
+    /// Reject commits that carry neither an authorship note nor AI trailers
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub require_attribution: bool,
+}
+
+impl PushPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.max_ai_percent.is_none()
+            && self.protected_paths.is_empty()
+            && self.require_review_marker.is_none()
+            && !self.require_attribution
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyViolation {
+    pub commit_sha: String,
+    pub subject: String,
+    pub reason: String,
+}
+
+/// One line of `pre-push` stdin: `<local ref> <local sha> <remote ref> <remote sha>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushedRef {
+    pub local_sha: String,
+    pub remote_sha: String,
+}
+
+pub fn parse_pushed_refs(input: &str) -> Vec<PushedRef> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() != 4 {
+                return None;
+            }
+            Some(PushedRef {
+                local_sha: parts[1].to_string(),
+                remote_sha: parts[3].to_string(),
+            })
+        })
+        .collect()
+}
+
+/// One line of `pre-receive` stdin: `<old sha> <new sha> <ref name>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivedRef {
+    pub old_sha: String,
+    pub new_sha: String,
+    pub ref_name: String,
+}
+
+pub fn parse_received_refs(input: &str) -> Vec<ReceivedRef> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            if parts.len() != 3 {
+                return None;
+            }
+            Some(ReceivedRef {
+                old_sha: parts[0].to_string(),
+                new_sha: parts[1].to_string(),
+                ref_name: parts[2].to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Commits that `refs` would add to `remote`, oldest first.
+pub fn commits_to_push(
+    repo: &Repository,
+    remote: &str,
+    refs: &[PushedRef],
+) -> Result<Vec<String>, GitAiError> {
+    let mut commits: Vec<String> = Vec::new();
+    for pushed in refs {
+        // Deleting a remote ref pushes nothing
+        if pushed.local_sha == ZERO_SHA {
+            continue;
+        }
+        let mut args = vec![
+            "rev-list".to_string(),
+            "--reverse".to_string(),
+            pushed.local_sha.clone(),
+        ];
+        if pushed.remote_sha != ZERO_SHA && repo.revparse_single(&pushed.remote_sha).is_ok() {
+            args.push(format!("^{}", pushed.remote_sha));
+        } else {
+            args.push("--not".to_string());
+            args.push(format!("--remotes={}", remote));
+        }
+        let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        for sha in repo.git(&arg_refs)?.lines().filter(|l| !l.is_empty()) {
+            if !commits.iter().any(|c| c == sha) {
+                commits.push(sha.to_string());
+            }
+        }
+    }
+    Ok(commits)
+}
+
+/// Commits a receiving repository does not have yet, oldest first.
+///
+/// Runs before any ref is updated, so everything reachable from an existing ref is
+/// already known. Notes refs are skipped: their commits hold attribution, not code.
+pub fn commits_to_receive(
+    repo: &Repository,
+    refs: &[ReceivedRef],
+) -> Result<Vec<String>, GitAiError> {
+    let mut commits: Vec<String> = Vec::new();
+    for received in refs {
+        if received.new_sha == ZERO_SHA || received.ref_name.starts_with("refs/notes/") {
+            continue;
+        }
+        let output = repo.git(&["rev-list", "--reverse", &received.new_sha, "--not", "--all"])?;
+        for sha in output.lines().filter(|l| !l.is_empty()) {
+            if !commits.iter().any(|c| c == sha) {
+                commits.push(sha.to_string());
+            }
+        }
+    }
+    Ok(commits)
+}
+
+/// Check `commits` against `policy`.
+pub fn evaluate_commits(
+    repo: &Repository,
+    policy: &PushPolicy,
+    commits: &[String],
+) -> Result<Vec<PolicyViolation>, GitAiError> {
+    if policy.is_empty() {
+        return Ok(Vec::new());
+    }
+    let protected: Vec<Pattern> = policy
+        .protected_paths
+        .iter()
+        .filter_map(|p| Pattern::new(p).ok())
+        .collect();
+
+    let mut violations = Vec::new();
+    for sha in commits {
+        let message = repo.git(&["log", "-1", "--format=%B", sha])?;
+        let subject = message.lines().next().unwrap_or_default().to_string();
+        let trailers = parse_trailers(&message);
+        let mut violation = |reason: String| {
+            violations.push(PolicyViolation {
+                commit_sha: sha.clone(),
+                subject: subject.clone(),
+                reason,
+            })
+        };
+
+        let log = get_authorship(repo, sha);
+        let trailer_summary = summary_from_trailers(&trailers);
+        if log.is_none() && trailer_summary.is_none() {
+            if policy.require_attribution {
+                violation("no git-ai attribution (authorship note or AI trailers)".to_string());
+            }
+            continue;
+        }
+
+        let ai_files: Vec<&str> = log
+            .iter()
+            .flat_map(|log| {
+                log.attestations
+                    .iter()
+                    .filter(|a| {
+                        a.entries
+                            .iter()
+                            .any(|e| log.metadata.prompts.contains_key(&e.hash))
+                    })
+                    .map(|a| a.file_path.as_str())
+            })
+            .collect();
+        let trailer_percent = trailer_summary.as_ref().map(|s| s.ai_percent);
+        let has_ai = if log.is_some() {
+            !ai_files.is_empty()
+        } else {
+            trailer_percent.unwrap_or(0) > 0
+        };
+        if !has_ai {
+            continue;
+        }
+
+        if let Some(max) = policy.max_ai_percent {
+            let percent = if log.is_some() {
+                let stats = stats_for_commit_stats(repo, sha, &[])?;
+                (stats.git_diff_added_lines > 0).then(|| {
+                    (stats.ai_additions as f64 / stats.git_diff_added_lines as f64 * 100.0).round()
+                        as u32
+                })
+            } else {
+                trailer_percent
+            };
+            if let Some(percent) = percent
+                && percent > max
+            {
+                violation(format!(
+                    "{}% AI-authored exceeds max_ai_percent ({}%)",
+                    percent, max
+                ));
+            }
+        }
+
+        for file in &ai_files {
+            if protected.iter().any(|p| p.matches(file)) {
+                violation(format!("AI-authored lines in protected path {}", file));
+            }
+        }
+
+        if let Some(marker) = &policy.require_review_marker {
+            let has_marker = trailers.iter().any(|t| t.key.eq_ignore_ascii_case(marker));
+            if !has_marker {
+                violation(format!("missing required `{}:` trailer", marker));
+            }
+        }
+    }
+    Ok(violations)
+}
+
+/// Human-readable report printed when a push is blocked.
+pub fn format_violation_report(violations: &[PolicyViolation]) -> String {
+    let mut report = format!("{}\n", tr("push.blocked"));
+    push_violation_lines(&mut report, violations);
+    report.push_str(&format!("\n{}\n", tr("push.blocked_hint")));
+    report
+}
+
+/// Report sent back to the pusher when the server rejects a push.
+pub fn format_rejection_report(violations: &[PolicyViolation]) -> String {
+    let mut report = format!("{}\n", tr("push.rejected"));
+    push_violation_lines(&mut report, violations);
+    report.push_str(&format!("\n{}\n", tr("push.rejected_hint")));
+    report
+}
+
+fn push_violation_lines(report: &mut String, violations: &[PolicyViolation]) {
+    for v in violations {
+        report.push_str(&format!(
+            "  {} {}: {}\n",
+            &v.commit_sha[..7.min(v.commit_sha.len())],
+            v.subject,
+            v.reason
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pushed_refs() {
+        let input = format!(
+            "refs/heads/main abc123 refs/heads/main {}\nrefs/heads/gone {} refs/heads/gone def456\nbad line\n",
+            ZERO_SHA, ZERO_SHA
+        );
+        let refs = parse_pushed_refs(&input);
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].local_sha, "abc123");
+        assert_eq!(refs[1].remote_sha, "def456");
+    }
+
+    #[test]
+    fn test_parse_received_refs() {
+        let input = format!(
+            "{} abc123 refs/heads/main\ndef456 {} refs/heads/gone\nbad line\n",
+            ZERO_SHA, ZERO_SHA
+        );
+        let refs = parse_received_refs(&input);
+        assert_eq!(refs.len(), 2);
+        assert_eq!(refs[0].new_sha, "abc123");
+        assert_eq!(refs[0].ref_name, "refs/heads/main");
+        assert_eq!(refs[1].old_sha, "def456");
+    }
+
+    #[test]
+    fn test_format_violation_report() {
+        let report = format_violation_report(&[PolicyViolation {
+            commit_sha: "0123456789abcdef".to_string(),
+            subject: "Add login".to_string(),
+            reason: "AI-authored lines in protected path src/auth.rs".to_string(),
+        }]);
+        assert!(
+            report
+                .contains("  0123456 Add login: AI-authored lines in protected path src/auth.rs\n")
+        );
+        assert!(report.contains("--no-verify"));
+    }
+}
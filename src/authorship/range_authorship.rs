@@ -5,6 +5,7 @@ use serde::Deserialize;
 use serde::Serialize;
 
 use crate::authorship::diff_ai_accepted::diff_ai_accepted_stats;
+use crate::authorship::path_config::PathConfig;
 use crate::authorship::stats::{CommitStats, stats_for_commit_stats, stats_from_authorship_log};
 use crate::error::GitAiError;
 use crate::git::refs::{CommitAuthorship, get_commits_with_notes_from_list};
@@ -196,9 +197,10 @@ fn create_authorship_log_for_range(
     let all_changed_files = repo.diff_changed_files(start_sha, end_sha)?;
 
     // Filter out ignored files from the changed files
+    let paths = PathConfig::for_repo(repo);
     let changed_files: Vec<String> = all_changed_files
         .into_iter()
-        .filter(|file| !should_ignore_file(file, ignore_patterns))
+        .filter(|file| !should_ignore_file(file, ignore_patterns) && !paths.ignores(file))
         .collect();
 
     // Note: We intentionally do NOT filter to AI-touched files here.
@@ -219,6 +221,7 @@ fn create_authorship_log_for_range(
                     ),
                     base_commit_sha: end_sha.to_string(),
                     prompts: std::collections::BTreeMap::new(),
+                    human_checked_files: std::collections::BTreeSet::new(),
                 },
             },
         );
@@ -364,6 +367,7 @@ fn get_git_diff_stats_for_range(
 
     let mut added_lines = 0u32;
     let mut deleted_lines = 0u32;
+    let paths = PathConfig::for_repo(repo);
 
     // Parse numstat output
     for line in stdout.lines() {
@@ -376,7 +380,7 @@ fn get_git_diff_stats_for_range(
         if parts.len() >= 3 {
             // Check if this file should be ignored and skip it
             let filename = parts[2];
-            if should_ignore_file(filename, ignore_patterns) {
+            if should_ignore_file(filename, ignore_patterns) || paths.ignores(filename) {
                 continue;
             }
 
@@ -1127,7 +1127,7 @@ fn transform_attributions_to_final_state(
     use crate::authorship::attribution_tracker::AttributionTracker;
     use crate::authorship::virtual_attribution::VirtualAttributions;
 
-    let tracker = AttributionTracker::new();
+    let tracker = AttributionTracker::new().with_diff_cache(source_va.repo().storage.diff_cache());
     let ts = source_va.timestamp();
     let repo = source_va.repo().clone();
     let base_commit = source_va.base_commit().to_string();
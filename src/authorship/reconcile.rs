@@ -0,0 +1,177 @@
+//! Catch-up for commits that git-ai did not see being made.
+//!
+//! GUI clients and IDEs often run their own git, so neither the proxy nor a hook
+//! finalizes the commit: its checkpoints stay in the working log of its parent and it
+//! gets no authorship note. The next git-ai invocation (a proxied git command or a
+//! checkpoint) first drains the `async_finalize` queue, then compares HEAD with the
+//! last state it recorded and finalizes any such commits from the working log, oldest
+//! first, exactly as `post_commit` would have.
+//!
+//! Rewrites that keep the tree and parents (`jj describe`, a GUI "edit message") get
+//! the old commit's note and working log carried over instead.
+//!
+//! Only working logs holding AI attributions are finalized this way. A commit made
+//! from human-only changes loses nothing by staying without a note, and keeps showing
+//! up as unattributed rather than being silently claimed as human.
+
+use crate::authorship::async_finalize::finalize_pending_commits;
+use crate::authorship::post_commit::post_commit;
+use crate::authorship::working_log::CheckpointKind;
+use crate::error::GitAiError;
+use crate::git::jj::{is_colocated_jj_repo, jj_user_email};
+use crate::git::refs::{get_authorship, notes_add, show_authorship_note};
+use crate::git::repository::Repository;
+use crate::utils::debug_log;
+
+/// How far back from HEAD to look for unfinalized commits
+const MAX_RECONCILE_COMMITS: usize = 50;
+
+/// Finalize commits made since the last known HEAD. Returns the commits that got an
+/// authorship note.
+pub fn reconcile_unfinalized_commits(repo: &Repository) -> Result<Vec<String>, GitAiError> {
+    // Commits queued by `async_post_commit` are known, not unfinalized
+    finalize_pending_commits(repo, true)?;
+
+    // Runs before every proxied command, so keep the common case to one git call
+    let head = match repo.git(&["rev-parse", "--verify", "-q", "HEAD"]) {
+        Ok(out) if !out.trim().is_empty() => out.trim().to_string(),
+        _ => return Ok(Vec::new()),
+    };
+    let last_known = repo.storage.read_last_known_head();
+    if last_known.as_deref() == Some(head.as_str()) {
+        return Ok(Vec::new());
+    }
+    // Commits made mid-rebase or mid-cherry-pick are finalized when the operation ends;
+    // leave the recorded state alone so we look again afterwards
+    if repo.path().join("rebase-merge").exists()
+        || repo.path().join("rebase-apply").exists()
+        || repo.path().join("sequencer").exists()
+    {
+        return Ok(Vec::new());
+    }
+
+    if let Some(last_known) = &last_known {
+        carry_over_metadata_rewrite(repo, last_known, &head)?;
+    }
+    let finalized = finalize_commits_since(repo, &head, last_known.as_deref())?;
+    repo.storage.write_last_known_head(&head)?;
+    Ok(finalized)
+}
+
+struct CommitInfo {
+    sha: String,
+    parents: Vec<String>,
+    author: String,
+    author_email: String,
+}
+
+fn finalize_commits_since(
+    repo: &Repository,
+    head: &str,
+    last_known: Option<&str>,
+) -> Result<Vec<String>, GitAiError> {
+    let max_count = format!("--max-count={}", MAX_RECONCILE_COMMITS);
+    let mut args = vec![
+        "log",
+        "--first-parent",
+        &max_count,
+        "--format=%H%x09%P%x09%an <%ae>%x09%ae",
+        head,
+    ];
+    if let Some(last_known) = last_known
+        && repo.revparse_single(last_known).is_ok()
+    {
+        args.push("--not");
+        args.push(last_known);
+    }
+
+    // Newest first; stop at the first commit that already has a note
+    let mut pending: Vec<CommitInfo> = Vec::new();
+    for line in repo.git(&args)?.lines() {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 4 {
+            continue;
+        }
+        if show_authorship_note(repo, fields[0]).is_some() {
+            break;
+        }
+        pending.push(CommitInfo {
+            sha: fields[0].to_string(),
+            parents: fields[1].split_whitespace().map(str::to_string).collect(),
+            author: fields[2].to_string(),
+            author_email: fields[3].to_string(),
+        });
+    }
+
+    // Commits pulled from others carry their own attribution (or none); only the
+    // user's own commits can have consumed their working log
+    let jj_email = repo
+        .workdir()
+        .ok()
+        .filter(|workdir| is_colocated_jj_repo(workdir))
+        .and_then(|workdir| jj_user_email(&workdir));
+    let user_email = jj_email.or_else(|| repo.config_get_str("user.email").ok().flatten());
+
+    let mut finalized = Vec::new();
+    for commit in pending.into_iter().rev() {
+        if commit.parents.len() > 1 {
+            continue;
+        }
+        if let Some(email) = &user_email
+            && !email.eq_ignore_ascii_case(&commit.author_email)
+        {
+            continue;
+        }
+        let base = commit.parents.first().cloned();
+        let base_log = base.as_deref().unwrap_or("initial");
+        if !has_ai_attributions(repo, base_log) {
+            continue;
+        }
+
+        debug_log(&format!(
+            "Reconciling commit {} made outside git-ai (base {})",
+            commit.sha, base_log
+        ));
+        post_commit(repo, base, commit.sha.clone(), commit.author, true)?;
+        finalized.push(commit.sha);
+    }
+    Ok(finalized)
+}
+
+/// `new` replaced `old` without touching its content: same tree, same parents.
+fn carry_over_metadata_rewrite(repo: &Repository, old: &str, new: &str) -> Result<(), GitAiError> {
+    let tree_and_parents = |sha: &str| repo.git(&["log", "-1", "--format=%T %P", sha]).ok();
+    match (tree_and_parents(old), tree_and_parents(new)) {
+        (Some(old_info), Some(new_info)) if old_info == new_info => {}
+        _ => return Ok(()),
+    }
+    if show_authorship_note(repo, new).is_some() {
+        return Ok(());
+    }
+    let Some(mut log) = get_authorship(repo, old) else {
+        return Ok(());
+    };
+
+    debug_log(&format!(
+        "Carrying authorship across rewrite {} -> {}",
+        old, new
+    ));
+    log.metadata.base_commit_sha = new.to_string();
+    let authorship_json = log
+        .serialize_to_string()
+        .map_err(|_| GitAiError::Generic("Failed to serialize authorship log".to_string()))?;
+    notes_add(repo, new, &authorship_json)?;
+    repo.storage.rename_working_log(old, new)
+}
+
+fn has_ai_attributions(repo: &Repository, base_commit: &str) -> bool {
+    if !repo.storage.working_logs.join(base_commit).exists() {
+        return false;
+    }
+    let working_log = repo.storage.working_log_for_base_commit(base_commit);
+    !working_log.read_initial_attributions().files.is_empty()
+        || working_log
+            .read_all_checkpoints()
+            .map(|checkpoints| checkpoints.iter().any(|c| c.kind != CheckpointKind::Human))
+            .unwrap_or(false)
+}
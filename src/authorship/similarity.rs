@@ -0,0 +1,249 @@
+//! Near-duplicate detection for AI-authored code, run by `report` and `ci-gate` when
+//! `similarity` is set in the git-ai config, to catch agents copying logic instead of
+//! reusing it.
+//!
+//! Every run of `min_lines` (default 6) consecutive significant lines of a file is
+//! fingerprinted, after trimming and collapsing whitespace; a line is significant when
+//! it has at least three letters or digits, so braces and blank lines neither make nor
+//! break a match. A run of AI-authored lines is flagged when the same fingerprint occurs
+//! anywhere else in the repository, or in one of the `corpora`: directories of code
+//! from outside it (a vendored SDK, another service, a snippet collection). Overlapping
+//! runs matching the same file are reported as one finding.
+
+use crate::error::GitAiError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::Path;
+
+pub const DEFAULT_MIN_LINES: usize = 6;
+
+/// Files of a corpus larger than this are skipped
+const MAX_CORPUS_FILE_BYTES: u64 = 1 << 20;
+
+fn default_min_lines() -> usize {
+    DEFAULT_MIN_LINES
+}
+
+/// `similarity` in the git-ai config; the scanner is off unless it is set
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SimilarityConfig {
+    /// Significant lines a duplicate must span
+    #[serde(default = "default_min_lines")]
+    pub min_lines: usize,
+    /// Directories of outside code to compare with
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub corpora: Vec<String>,
+}
+
+/// AI-authored lines that repeat code found elsewhere
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SimilarityFinding {
+    pub path: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    /// A path of the repository, or of a file in a corpus
+    pub duplicate_of: String,
+    pub duplicate_line: u32,
+    /// Whether `duplicate_of` is in a corpus
+    pub external: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    source: usize,
+    start_line: u32,
+    end_line: u32,
+}
+
+#[derive(Debug, Default)]
+pub struct SimilarityScanner {
+    min_lines: usize,
+    /// Path of each file added, and whether it is from a corpus
+    sources: Vec<(String, bool)>,
+    index: HashMap<u64, Vec<Window>>,
+    ai_windows: Vec<(Window, u64)>,
+}
+
+impl SimilarityScanner {
+    /// A scanner holding the files of the configured corpora
+    pub fn new(config: &SimilarityConfig) -> Result<SimilarityScanner, GitAiError> {
+        let mut scanner = SimilarityScanner {
+            min_lines: config.min_lines.max(2),
+            ..Default::default()
+        };
+        for corpus in &config.corpora {
+            scanner.add_corpus(Path::new(corpus))?;
+        }
+        Ok(scanner)
+    }
+
+    fn add_corpus(&mut self, dir: &Path) -> Result<(), GitAiError> {
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.file_name().is_some_and(|name| name == ".git") {
+                continue;
+            }
+            if path.is_dir() {
+                self.add_corpus(&path)?;
+                continue;
+            }
+            if std::fs::metadata(&path)?.len() > MAX_CORPUS_FILE_BYTES {
+                continue;
+            }
+            // Binary files have nothing to compare
+            let Ok(contents) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let lines: Vec<(&str, bool)> = contents.lines().map(|line| (line, false)).collect();
+            self.add(&path.display().to_string(), true, &lines);
+        }
+        Ok(())
+    }
+
+    /// Add a file of the repository: each of its lines, and whether it is AI-authored
+    pub fn add_file(&mut self, path: &str, lines: &[(&str, bool)]) {
+        self.add(path, false, lines);
+    }
+
+    fn add(&mut self, path: &str, external: bool, lines: &[(&str, bool)]) {
+        let source = self.sources.len();
+        self.sources.push((path.to_string(), external));
+        let significant: Vec<(u32, String, bool)> = lines
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (text, ai))| Some((i as u32 + 1, normalize(text)?, *ai)))
+            .collect();
+        for run in significant.windows(self.min_lines) {
+            let mut hasher = DefaultHasher::new();
+            for (_, text, _) in run {
+                text.hash(&mut hasher);
+            }
+            let hash = hasher.finish();
+            let window = Window {
+                source,
+                start_line: run[0].0,
+                end_line: run[run.len() - 1].0,
+            };
+            self.index.entry(hash).or_default().push(window);
+            if run.iter().all(|(_, _, ai)| *ai) {
+                self.ai_windows.push((window, hash));
+            }
+        }
+    }
+
+    /// The AI-authored runs of lines found elsewhere, in the order their files were added
+    pub fn findings(&self) -> Vec<SimilarityFinding> {
+        let mut findings: Vec<SimilarityFinding> = Vec::new();
+        for (window, hash) in &self.ai_windows {
+            let Some(other) = self.index[hash].iter().find(|other| {
+                other.source != window.source
+                    || other.end_line < window.start_line
+                    || other.start_line > window.end_line
+            }) else {
+                continue;
+            };
+            let (path, _) = &self.sources[window.source];
+            let (duplicate_of, external) = &self.sources[other.source];
+            if let Some(last) = findings.last_mut()
+                && last.path == *path
+                && last.duplicate_of == *duplicate_of
+                && window.start_line <= last.end_line
+            {
+                last.end_line = last.end_line.max(window.end_line);
+                continue;
+            }
+            findings.push(SimilarityFinding {
+                path: path.clone(),
+                start_line: window.start_line,
+                end_line: window.end_line,
+                duplicate_of: duplicate_of.clone(),
+                duplicate_line: other.start_line,
+                external: *external,
+            });
+        }
+        findings
+    }
+}
+
+/// The line with its whitespace collapsed, if it is significant
+fn normalize(line: &str) -> Option<String> {
+    if line.chars().filter(|c| c.is_alphanumeric()).count() < 3 {
+        return None;
+    }
+    Some(line.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines<'a>(text: &'a [&'a str], ai: bool) -> Vec<(&'a str, bool)> {
+        text.iter().map(|line| (*line, ai)).collect()
+    }
+
+    #[test]
+    fn test_findings_flag_ai_copies_of_existing_code() {
+        let mut scanner = SimilarityScanner::new(&SimilarityConfig {
+            min_lines: 3,
+            corpora: vec![],
+        })
+        .unwrap();
+        let existing = [
+            "fn total(items: &[Item]) -> u32 {",
+            "    let mut sum = 0;",
+            "    for item in items {",
+            "        sum += item.price;",
+            "    }",
+            "    sum",
+            "}",
+        ];
+        scanner.add_file("src/cart.rs", &lines(&existing, false));
+
+        // The same loop, reindented, with a different signature
+        let copied = [
+            "fn checkout(order: &Order) -> u32 {",
+            "  let mut sum = 0;",
+            "",
+            "  for item in items {",
+            "      sum  += item.price;",
+            "  }",
+            "  charge(sum)",
+            "}",
+        ];
+        scanner.add_file("src/order.rs", &lines(&copied, true));
+        // Human-written copies are left alone
+        scanner.add_file("src/human.rs", &lines(&existing, false));
+        // As are AI lines that repeat nothing
+        scanner.add_file(
+            "src/new.rs",
+            &lines(&["fn new() {", "    let fresh = 1;", "    ship(fresh);", "}"], true),
+        );
+
+        assert_eq!(
+            scanner.findings(),
+            vec![SimilarityFinding {
+                path: "src/order.rs".to_string(),
+                start_line: 2,
+                end_line: 5,
+                duplicate_of: "src/cart.rs".to_string(),
+                duplicate_line: 2,
+                external: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_findings_ignore_repeats_within_the_same_run() {
+        let mut scanner = SimilarityScanner::new(&SimilarityConfig {
+            min_lines: 2,
+            corpora: vec![],
+        })
+        .unwrap();
+        scanner.add_file("a.txt", &lines(&["same line"; 3], true));
+        assert!(scanner.findings().is_empty());
+
+        scanner.add_file("b.txt", &lines(&["same line"; 2], false));
+        assert_eq!(scanner.findings().len(), 1);
+    }
+}
@@ -0,0 +1,136 @@
+//! A map that moves to disk past a number of entries, for aggregations over histories
+//! too long to keep an entry per commit in memory.
+//!
+//! Entries stay in a `HashMap` until it holds `max_in_memory` of them, then move in one
+//! transaction to a SQLite database in the temporary directory, which is removed when
+//! the map is dropped. Lookups check memory first, then the database.
+
+use crate::error::GitAiError;
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// Default number of entries kept in memory
+pub const MAX_IN_MEMORY: usize = 50_000;
+
+pub struct SpillMap<V> {
+    memory: HashMap<String, V>,
+    max_in_memory: usize,
+    disk: Option<(Connection, PathBuf)>,
+}
+
+impl<V: Serialize + DeserializeOwned> SpillMap<V> {
+    pub fn new() -> Self {
+        Self::with_max_in_memory(MAX_IN_MEMORY)
+    }
+
+    pub fn with_max_in_memory(max_in_memory: usize) -> Self {
+        SpillMap {
+            memory: HashMap::new(),
+            max_in_memory: max_in_memory.max(1),
+            disk: None,
+        }
+    }
+
+    pub fn insert(&mut self, key: String, value: V) -> Result<(), GitAiError> {
+        self.memory.insert(key, value);
+        if self.memory.len() >= self.max_in_memory {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, key: &str) -> Result<Option<V>, GitAiError>
+    where
+        V: Clone,
+    {
+        if let Some(value) = self.memory.get(key) {
+            return Ok(Some(value.clone()));
+        }
+        let Some((conn, _)) = &self.disk else {
+            return Ok(None);
+        };
+        let json: Option<String> = conn
+            .query_row(
+                "SELECT value FROM entries WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        json.map(|json| serde_json::from_str(&json).map_err(GitAiError::from))
+            .transpose()
+    }
+
+    /// Move the entries in memory to the database
+    fn spill(&mut self) -> Result<(), GitAiError> {
+        if self.disk.is_none() {
+            let path = std::env::temp_dir()
+                .join(format!("git-ai-spill-{}.db", uuid::Uuid::new_v4().simple()));
+            let conn = Connection::open(&path)?;
+            conn.execute_batch(
+                "PRAGMA journal_mode = OFF;
+                 PRAGMA synchronous = OFF;
+                 CREATE TABLE entries (key TEXT PRIMARY KEY, value TEXT NOT NULL);",
+            )?;
+            self.disk = Some((conn, path));
+        }
+        let Some((conn, _)) = self.disk.as_mut() else {
+            return Ok(());
+        };
+        let tx = conn.transaction()?;
+        {
+            let mut insert =
+                tx.prepare("INSERT OR REPLACE INTO entries (key, value) VALUES (?1, ?2)")?;
+            for (key, value) in self.memory.drain() {
+                insert.execute(params![key, serde_json::to_string(&value)?])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+impl<V: Serialize + DeserializeOwned> Default for SpillMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> Drop for SpillMap<V> {
+    fn drop(&mut self) {
+        if let Some((conn, path)) = self.disk.take() {
+            drop(conn);
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spills_past_max_in_memory() {
+        let mut map = SpillMap::with_max_in_memory(3);
+        for i in 0..10 {
+            map.insert(format!("sha{}", i), (i, format!("author {}", i)))
+                .unwrap();
+        }
+        assert!(map.disk.is_some());
+        assert!(map.memory.len() < 3);
+        for i in 0..10 {
+            assert_eq!(
+                map.get(&format!("sha{}", i)).unwrap(),
+                Some((i, format!("author {}", i)))
+            );
+        }
+        assert_eq!(map.get("missing").unwrap(), None);
+
+        let path = map.disk.as_ref().unwrap().1.clone();
+        assert!(path.exists());
+        drop(map);
+        assert!(!path.exists());
+    }
+}
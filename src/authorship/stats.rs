@@ -1,11 +1,13 @@
 use crate::authorship::diff_ai_accepted::diff_ai_accepted_stats;
+use crate::authorship::languages::{LanguageLines, sorted_by_lines};
 use crate::authorship::transcript::Message;
 use crate::error::GitAiError;
 use crate::git::refs::get_authorship;
-use crate::git::repository::Repository;
+use crate::git::repository::{Repository, exec_git_lines};
 use crate::utils::debug_log;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::sync::Arc;
 
 const EMPTY_TREE_HASH: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
 
@@ -93,6 +95,48 @@ pub fn stats_command(
     Ok(())
 }
 
+/// Print the stats of every commit `rev_args` selects (as passed to `git log`) as one
+/// JSON object per line, in `git log` order. The commits are read as `git log` prints
+/// them and computed a batch at a time, so memory doesn't grow with the history.
+/// Returns the number printed.
+pub fn stream_commit_stats(
+    repo: &Repository,
+    rev_args: &[String],
+    ignore_patterns: &[String],
+) -> Result<usize, GitAiError> {
+    const BATCH_SIZE: usize = 64;
+
+    let mut args = repo.global_args_for_exec();
+    args.push("log".to_string());
+    args.push("--format=%H".to_string());
+    args.extend(rev_args.iter().cloned());
+
+    let print_batch = |batch: &[String]| -> Result<(), GitAiError> {
+        for (sha, stats) in batch
+            .iter()
+            .zip(stats_for_commits_stats(repo, batch, ignore_patterns)?)
+        {
+            let mut record = serde_json::to_value(&stats)?;
+            record["commit"] = serde_json::json!(sha);
+            println!("{}", record);
+        }
+        Ok(())
+    };
+
+    let mut printed = 0;
+    let mut batch = Vec::with_capacity(BATCH_SIZE);
+    for sha in exec_git_lines(&args)? {
+        batch.push(sha?);
+        if batch.len() == BATCH_SIZE {
+            print_batch(&batch)?;
+            printed += batch.len();
+            batch.clear();
+        }
+    }
+    print_batch(&batch)?;
+    Ok(printed + batch.len())
+}
+
 pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
     let mut output = String::new();
 
@@ -296,6 +340,39 @@ pub fn write_stats_to_terminal(stats: &CommitStats, print: bool) -> String {
     output
 }
 
+/// One row per language, most added lines first, with its share of AI lines
+pub fn write_language_stats_to_terminal(languages: &BTreeMap<String, LanguageLines>) -> String {
+    if languages.is_empty() {
+        return "No lines added\n".to_string();
+    }
+    let sorted = sorted_by_lines(languages);
+    let width = sorted
+        .iter()
+        .map(|(language, _)| language.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max("Language".len());
+    let mut output = format!(
+        "{:<width$}  {:>8}  {:>8}  {:>8}\n",
+        "Language",
+        "Added",
+        "AI",
+        "AI share",
+        width = width
+    );
+    for (language, lines) in sorted {
+        output.push_str(&format!(
+            "{:<width$}  {:>8}  {:>8}  {:>7}%\n",
+            language,
+            lines.total_lines,
+            lines.ai_lines,
+            lines.ai_percent(),
+            width = width
+        ));
+    }
+    output
+}
+
 #[allow(dead_code)]
 pub fn write_stats_to_markdown(stats: &CommitStats) -> String {
     let mut output = String::new();
@@ -475,9 +552,9 @@ pub fn stats_from_authorship_log(
             commit_stats.total_ai_deletions += prompt_record.total_deletions;
             commit_stats.mixed_additions += prompt_record.overriden_lines;
 
-            let key = format!(
-                "{}::{}",
-                prompt_record.agent_id.tool, prompt_record.agent_id.model
+            let key = crate::authorship::model_names::tool_model_key(
+                &prompt_record.agent_id.tool,
+                &prompt_record.agent_id.model,
             );
             let tool_stats = commit_stats.tool_model_breakdown.entry(key).or_default();
             tool_stats.total_ai_additions += prompt_record.total_additions;
@@ -569,6 +646,38 @@ pub fn stats_for_commit_stats(
     ))
 }
 
+/// `stats_for_commit_stats` of each of `commit_shas`, in the same order, computed
+/// concurrently using the MAX_CONCURRENT pattern. Range aggregations (shortlog, graph,
+/// digest, export) spend most of their time here, one commit after another otherwise.
+pub fn stats_for_commits_stats(
+    repo: &Repository,
+    commit_shas: &[String],
+    ignore_patterns: &[String],
+) -> Result<Vec<CommitStats>, GitAiError> {
+    const MAX_CONCURRENT: usize = 16;
+
+    smol::block_on(async {
+        let semaphore = Arc::new(smol::lock::Semaphore::new(MAX_CONCURRENT));
+        let tasks: Vec<_> = commit_shas
+            .iter()
+            .map(|sha| {
+                let sha = sha.clone();
+                let repo = repo.clone();
+                let ignore_patterns = ignore_patterns.to_vec();
+                let semaphore = Arc::clone(&semaphore);
+                smol::spawn(async move {
+                    let _permit = semaphore.acquire().await;
+                    smol::unblock(move || stats_for_commit_stats(&repo, &sha, &ignore_patterns))
+                        .await
+                })
+            })
+            .collect();
+        futures::future::join_all(tasks).await
+    })
+    .into_iter()
+    .collect()
+}
+
 /// Get git diff statistics between commit and its parent
 pub fn get_git_diff_stats(
     repo: &Repository,
@@ -588,6 +697,8 @@ pub fn get_git_diff_stats(
     let mut added_lines = 0u32;
     let mut deleted_lines = 0u32;
 
+    let paths = crate::authorship::path_config::PathConfig::for_repo(repo);
+
     // Parse numstat output
     for line in stdout.lines() {
         if line.trim().is_empty() {
@@ -604,7 +715,9 @@ pub fn get_git_diff_stats(
         if parts.len() >= 3 {
             // Check if this file should be ignored
             let filename = parts[2];
-            if crate::authorship::range_authorship::should_ignore_file(filename, ignore_patterns) {
+            if crate::authorship::range_authorship::should_ignore_file(filename, ignore_patterns)
+                || paths.ignores(filename)
+            {
                 continue;
             }
 
@@ -626,7 +739,9 @@ pub fn get_git_diff_stats(
 }
 
 /// Calculate time waiting for AI from transcript messages
-fn calculate_waiting_time(transcript: &crate::authorship::transcript::AiTranscript) -> u64 {
+pub(crate) fn calculate_waiting_time(
+    transcript: &crate::authorship::transcript::AiTranscript,
+) -> u64 {
     let mut total_waiting_time = 0u64;
     let messages = transcript.messages();
 
@@ -0,0 +1,159 @@
+//! Headline numbers of a working log, kept up to date as checkpoints are appended.
+//!
+//! `git-ai status` used to rebuild the virtual attributions of every checkpointed
+//! file to count the AI lines pending for the next commit. Each checkpoint entry
+//! already holds the line attributions of its file, so the summary keeps the AI line
+//! count of each file as of its latest entry, together with the totals and the rows
+//! status lists, and `status` only has to read it back. `git-ai status --exact` still
+//! does the full pass.
+//!
+//! The summary records the size of `checkpoints.jsonl` it was computed from. Anything
+//! that rewrites the checkpoints without updating it leaves it stale, and it is then
+//! rebuilt by streaming the checkpoints, which still skips the attribution pass.
+
+use crate::authorship::authorship_log_serialization::generate_short_hash;
+use crate::authorship::stats::calculate_waiting_time;
+use crate::authorship::working_log::{Checkpoint, CheckpointKind};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+pub const SUMMARY_FILE: &str = "status_summary.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusSummary {
+    /// Size of `checkpoints.jsonl` when the summary was written
+    pub checkpoints_bytes: u64,
+    /// AI-attributed lines of each file, as of its latest checkpoint entry
+    pub ai_lines: BTreeMap<String, u32>,
+    /// Lines added and deleted by AI checkpoints
+    pub total_ai_additions: u32,
+    pub total_ai_deletions: u32,
+    /// Seconds spent waiting for each agent session, from its latest transcript
+    pub waiting_time: BTreeMap<String, u64>,
+    /// The checkpoints, oldest first
+    pub checkpoints: Vec<CheckpointSummary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CheckpointSummary {
+    pub timestamp: u64,
+    pub additions: u32,
+    pub deletions: u32,
+    pub is_human: bool,
+    pub tool: Option<String>,
+    pub model: Option<String>,
+}
+
+impl StatusSummary {
+    pub fn from_checkpoints<'a>(checkpoints: impl IntoIterator<Item = &'a Checkpoint>) -> Self {
+        let mut summary = StatusSummary::default();
+        for checkpoint in checkpoints {
+            summary.apply(checkpoint);
+        }
+        summary
+    }
+
+    /// Account for `checkpoint`, appended after those already summarized
+    pub fn apply(&mut self, checkpoint: &Checkpoint) {
+        let human = CheckpointKind::Human.to_str();
+        for entry in &checkpoint.entries {
+            let ai_lines = entry
+                .line_attributions
+                .iter()
+                .filter(|attr| attr.author_id != human)
+                .map(|attr| attr.end_line.saturating_sub(attr.start_line) + 1)
+                .sum();
+            self.ai_lines.insert(entry.file.clone(), ai_lines);
+        }
+
+        let is_human = checkpoint.kind == CheckpointKind::Human;
+        if !is_human {
+            self.total_ai_additions += checkpoint.line_stats.additions;
+            self.total_ai_deletions += checkpoint.line_stats.deletions;
+            if let (Some(agent_id), Some(transcript)) =
+                (&checkpoint.agent_id, &checkpoint.transcript)
+            {
+                self.waiting_time.insert(
+                    generate_short_hash(&agent_id.id, &agent_id.tool),
+                    calculate_waiting_time(transcript),
+                );
+            }
+        }
+
+        self.checkpoints.push(CheckpointSummary {
+            timestamp: checkpoint.timestamp,
+            additions: checkpoint.line_stats.additions,
+            deletions: checkpoint.line_stats.deletions,
+            is_human,
+            tool: checkpoint.agent_id.as_ref().map(|a| a.tool.clone()),
+            model: checkpoint.agent_id.as_ref().map(|a| a.model.clone()),
+        });
+    }
+
+    /// AI lines pending for the next commit
+    pub fn ai_accepted(&self) -> u32 {
+        self.ai_lines.values().sum()
+    }
+
+    pub fn time_waiting_for_ai(&self) -> u64 {
+        self.waiting_time.values().sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::attribution_tracker::LineAttribution;
+    use crate::authorship::working_log::{AgentId, WorkingLogEntry};
+
+    fn checkpoint(kind: CheckpointKind, entries: Vec<WorkingLogEntry>) -> Checkpoint {
+        let agent_id = (kind != CheckpointKind::Human).then(|| AgentId {
+            tool: "cursor".to_string(),
+            id: "session".to_string(),
+            model: "gpt-4".to_string(),
+        });
+        let mut checkpoint = Checkpoint::new(kind, String::new(), "dev".to_string(), entries);
+        checkpoint.agent_id = agent_id;
+        checkpoint.line_stats.additions = 3;
+        checkpoint
+    }
+
+    fn entry(file: &str, attributions: &[(u32, u32, &str)]) -> WorkingLogEntry {
+        WorkingLogEntry::new(
+            file.to_string(),
+            "blob".to_string(),
+            Vec::new(),
+            attributions
+                .iter()
+                .map(|(start, end, author)| {
+                    LineAttribution::new(*start, *end, author.to_string(), None)
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_latest_entry_of_each_file_counts() {
+        let checkpoints = vec![
+            checkpoint(
+                CheckpointKind::AiAgent,
+                vec![
+                    entry("a.rs", &[(1, 3, "abc")]),
+                    entry("b.rs", &[(2, 2, "abc")]),
+                ],
+            ),
+            checkpoint(
+                CheckpointKind::Human,
+                vec![entry("a.rs", &[(1, 1, "human"), (2, 3, "abc")])],
+            ),
+        ];
+        let summary = StatusSummary::from_checkpoints(&checkpoints);
+
+        assert_eq!(summary.ai_lines["a.rs"], 2);
+        assert_eq!(summary.ai_accepted(), 3);
+        assert_eq!(summary.total_ai_additions, 3);
+        assert_eq!(summary.checkpoints.len(), 2);
+        assert!(summary.checkpoints[1].is_human);
+        assert_eq!(summary.checkpoints[0].tool.as_deref(), Some("cursor"));
+    }
+}
@@ -6,6 +6,7 @@ use crate::authorship::working_log::CheckpointKind;
 use crate::commands::blame::{GitAiBlameOptions, OLDEST_AI_BLAME_DATE};
 use crate::error::GitAiError;
 use crate::git::repository::Repository;
+use crate::utils::debug_log;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -299,7 +300,6 @@ impl VirtualAttributions {
     ) -> Result<Self, GitAiError> {
         let working_log = repo.storage.working_log_for_base_commit(&base_commit);
         let initial_attributions = working_log.read_initial_attributions();
-        let checkpoints = working_log.read_all_checkpoints().unwrap_or_default();
 
         let mut attributions: HashMap<String, (Vec<Attribution>, Vec<LineAttribution>)> =
             HashMap::new();
@@ -319,26 +319,22 @@ impl VirtualAttributions {
                 .insert(String::new(), prompt_record.clone());
         }
 
-        // Process INITIAL attributions
-        for (file_path, line_attrs) in &initial_attributions.files {
-            // Get the latest file content from working directory
-            if let Ok(workdir) = repo.workdir() {
-                let abs_path = workdir.join(file_path);
-                let file_content = if abs_path.exists() {
-                    std::fs::read_to_string(&abs_path).unwrap_or_default()
-                } else {
-                    String::new()
-                };
-                file_contents.insert(file_path.clone(), file_content.clone());
-
-                // Convert line attributions to character attributions
-                let char_attrs = line_attributions_to_attributions(line_attrs, &file_content, 0);
-                attributions.insert(file_path.clone(), (char_attrs, line_attrs.clone()));
-            }
+        // Line attributions of each file: INITIAL first, then later checkpoints override
+        // earlier ones. Only the final ones are converted to character attributions.
+        let workdir = repo.workdir().ok();
+        let mut line_attributions: HashMap<String, Vec<LineAttribution>> = HashMap::new();
+        if workdir.is_some() {
+            line_attributions.extend(initial_attributions.files);
         }
 
         // Collect attributions from all checkpoints (later checkpoints override earlier ones)
-        for checkpoint in &checkpoints {
+        // Checkpoints are streamed; a corrupt one ends the working log there
+        let checkpoints = working_log.checkpoints().into_iter().flatten();
+        for checkpoint in checkpoints.map_while(|checkpoint| {
+            checkpoint
+                .map_err(|e| debug_log(&format!("Stopped reading the working log: {}", e)))
+                .ok()
+        }) {
             // Add prompts from checkpoint
             if let Some(agent_id) = &checkpoint.agent_id {
                 let author_id =
@@ -377,25 +373,19 @@ impl VirtualAttributions {
             }
 
             // Collect attributions from checkpoint entries
-            for entry in &checkpoint.entries {
-                // Get the latest file content from working directory
-                if let Ok(workdir) = repo.workdir() {
-                    let abs_path = workdir.join(&entry.file);
-                    let file_content = if abs_path.exists() {
-                        std::fs::read_to_string(&abs_path).unwrap_or_default()
-                    } else {
-                        String::new()
-                    };
-                    file_contents.insert(entry.file.clone(), file_content);
-                }
-
-                // Use the line attributions from the checkpoint
-                let line_attrs = entry.line_attributions.clone();
-                let file_content = file_contents.get(&entry.file).cloned().unwrap_or_default();
-                let char_attrs = line_attributions_to_attributions(&line_attrs, &file_content, 0);
+            for entry in checkpoint.entries {
+                line_attributions.insert(entry.file, entry.line_attributions);
+            }
+        }
 
-                attributions.insert(entry.file.clone(), (char_attrs, line_attrs));
+        // Read each file from the working directory and convert its attributions, in parallel
+        for (file_path, content, char_attrs, line_attrs) in
+            smol::block_on(load_working_files(workdir, line_attributions))
+        {
+            if let Some(content) = content {
+                file_contents.insert(file_path.clone(), content);
             }
+            attributions.insert(file_path, (char_attrs, line_attrs));
         }
 
         // Calculate final metrics for each prompt
@@ -841,10 +831,9 @@ impl VirtualAttributions {
                     } else {
                         // Convert working directory line number to commit line number
                         // by subtracting the count of unstaged lines before this line
-                        let adjustment = unstaged_lines
-                            .iter()
-                            .filter(|&&l| l < workdir_line_num)
-                            .count() as u32;
+                        // (unstaged_lines is sorted, so that count is a binary search)
+                        let adjustment =
+                            unstaged_lines.partition_point(|&l| l < workdir_line_num) as u32;
                         let commit_line_num = workdir_line_num - adjustment;
 
                         // Check if this commit line number is in any committed hunk
@@ -1320,7 +1309,7 @@ pub fn merge_attributions_favoring_first(
 ) -> Result<VirtualAttributions, GitAiError> {
     use crate::authorship::attribution_tracker::AttributionTracker;
 
-    let tracker = AttributionTracker::new();
+    let tracker = AttributionTracker::new().with_diff_cache(primary.repo.storage.diff_cache());
     let ts = primary.ts;
     let repo = primary.repo.clone();
     let base_commit = primary.base_commit.clone();
@@ -1656,6 +1645,56 @@ fn ceil_char_boundary(content: &str, idx: usize) -> usize {
     i
 }
 
+/// The working directory content of each file (`None` without a working directory)
+/// with its line attributions converted to character attributions, using the
+/// MAX_CONCURRENT pattern.
+#[allow(clippy::type_complexity)]
+async fn load_working_files(
+    workdir: Option<std::path::PathBuf>,
+    line_attributions: HashMap<String, Vec<LineAttribution>>,
+) -> Vec<(
+    String,
+    Option<String>,
+    Vec<Attribution>,
+    Vec<LineAttribution>,
+)> {
+    const MAX_CONCURRENT: usize = 30;
+
+    let semaphore = Arc::new(smol::lock::Semaphore::new(MAX_CONCURRENT));
+    let mut tasks = Vec::new();
+
+    for (file_path, line_attrs) in line_attributions {
+        let workdir = workdir.clone();
+        let semaphore = Arc::clone(&semaphore);
+
+        let task = smol::spawn(async move {
+            let _permit = semaphore.acquire().await;
+
+            smol::unblock(move || {
+                let content = workdir.map(|workdir| {
+                    let abs_path = workdir.join(&file_path);
+                    if abs_path.exists() {
+                        std::fs::read_to_string(&abs_path).unwrap_or_default()
+                    } else {
+                        String::new()
+                    }
+                });
+                let char_attrs = line_attributions_to_attributions(
+                    &line_attrs,
+                    content.as_deref().unwrap_or_default(),
+                    0,
+                );
+                (file_path, content, char_attrs, line_attrs)
+            })
+            .await
+        });
+
+        tasks.push(task);
+    }
+
+    futures::future::join_all(tasks).await
+}
+
 /// Compute attributions for a single file at a specific commit
 #[allow(clippy::type_complexity)]
 fn compute_attributions_for_file(
@@ -73,7 +73,8 @@ pub fn get_gitlab_ci_context() -> Result<Option<CiContext>, GitAiError> {
 
     println!("[GitLab CI] Querying API: {}", endpoint);
 
-    let response = minreq::get(&endpoint)
+    crate::api::network::ensure_online().map_err(GitAiError::Generic)?;
+    let response = crate::api::network::with_proxy(minreq::get(&endpoint), &endpoint)
         .with_header(auth_header_name, &auth_token)
         .with_header(
             "User-Agent",
@@ -0,0 +1,191 @@
+//! `git-ai apply-ai-patch`: apply an agent's unified diff and attribute exactly the
+//! lines it adds.
+//!
+//! The files the patch touches are checkpointed as human first, so edits already in
+//! them stay human. If recording the AI checkpoint fails the patch is reversed again,
+//! leaving the tree as it was.
+
+use crate::authorship::reconcile::reconcile_unfinalized_commits;
+use crate::authorship::working_log::CheckpointKind;
+use crate::commands::checkpoint;
+use crate::commands::wrap::{author_name, run_result};
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::{Repository, exec_git_stdin};
+use crate::utils::debug_log;
+use std::io::Read;
+
+pub fn handle_apply_ai_patch(args: &[String]) {
+    let mut tool = None;
+    let mut model = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--tool" if i + 1 < args.len() => {
+                tool = Some(args[i + 1].clone());
+                i += 2;
+            }
+            "--model" if i + 1 < args.len() => {
+                model = Some(args[i + 1].clone());
+                i += 2;
+            }
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                eprintln!("Usage: git-ai apply-ai-patch --tool <name> [--model <name>] < patch");
+                std::process::exit(1);
+            }
+        }
+    }
+    let Some(tool) = tool.filter(|t| !t.trim().is_empty()) else {
+        eprintln!("Error: --tool <name> is required");
+        std::process::exit(1);
+    };
+    let model = model.unwrap_or_else(|| "unknown".to_string());
+
+    let mut patch = Vec::new();
+    if let Err(e) = std::io::stdin().read_to_end(&mut patch) {
+        eprintln!("Failed to read patch from stdin: {}", e);
+        std::process::exit(1);
+    }
+
+    let repo = match find_repository(&[]) {
+        Ok(repo) => repo,
+        Err(e) => {
+            crate::error::exit_with(&format!("Failed to find repository: {}", e), &e);
+        }
+    };
+
+    match apply_ai_patch(&repo, &patch, &tool, &model) {
+        Ok(files) => {
+            eprintln!("Applied patch to {} file(s) as {}", files.len(), tool);
+            crate::observability::spawn_background_flush();
+        }
+        Err(e) => {
+            crate::error::exit_with(&format!("Failed to apply patch: {}", e), &e);
+        }
+    }
+}
+
+/// Apply `patch` to the working tree and attribute its changes to `tool`.
+///
+/// Returns the files the patch touched, relative to the repository root.
+pub fn apply_ai_patch(
+    repo: &Repository,
+    patch: &[u8],
+    tool: &str,
+    model: &str,
+) -> Result<Vec<String>, GitAiError> {
+    if patch.iter().all(u8::is_ascii_whitespace) {
+        return Err(GitAiError::Generic("No patch given on stdin".to_string()));
+    }
+    let files = patch_files(repo, patch)?;
+    if files.is_empty() {
+        return Err(GitAiError::Generic("Patch touches no files".to_string()));
+    }
+
+    if let Err(e) = reconcile_unfinalized_commits(repo) {
+        debug_log(&format!("Failed to reconcile unfinalized commits: {}", e));
+    }
+    let author = author_name(repo);
+    checkpoint::run(
+        repo,
+        &author,
+        CheckpointKind::Human,
+        false,
+        false,
+        true,
+        Some(run_result(
+            tool,
+            model,
+            CheckpointKind::Human,
+            files.clone(),
+        )),
+        false,
+    )?;
+
+    git_apply(repo, patch, false)?;
+    let recorded = checkpoint::run(
+        repo,
+        &author,
+        CheckpointKind::AiAgent,
+        false,
+        false,
+        true,
+        Some(run_result(
+            tool,
+            model,
+            CheckpointKind::AiAgent,
+            files.clone(),
+        )),
+        false,
+    );
+    if let Err(e) = recorded {
+        if let Err(revert_err) = git_apply(repo, patch, true) {
+            debug_log(&format!("Failed to reverse patch: {}", revert_err));
+        }
+        return Err(e);
+    }
+    Ok(files)
+}
+
+/// Paths a patch touches, checking on the way that it applies cleanly.
+fn patch_files(repo: &Repository, patch: &[u8]) -> Result<Vec<String>, GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.extend(["apply", "--check", "--numstat", "-z"].map(String::from));
+    let output = exec_git_stdin(&args, patch)?;
+    Ok(parse_numstat_z(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn git_apply(repo: &Repository, patch: &[u8], reverse: bool) -> Result<(), GitAiError> {
+    let mut args = repo.global_args_for_exec();
+    args.push("apply".to_string());
+    if reverse {
+        args.push("--reverse".to_string());
+    }
+    exec_git_stdin(&args, patch).map(|_| ())
+}
+
+/// `git apply --numstat -z` prints `added\tdeleted\tpath\0`, or for renames
+/// `added\tdeleted\t\0old\0new\0`; both sides of a rename are touched.
+fn parse_numstat_z(output: &str) -> Vec<String> {
+    let mut files = Vec::new();
+    let mut fields = output.split('\0');
+    while let Some(record) = fields.next() {
+        let mut parts = record.splitn(3, '\t');
+        let (Some(_), Some(_), Some(path)) = (parts.next(), parts.next(), parts.next()) else {
+            continue;
+        };
+        if path.is_empty() {
+            files.extend(fields.next().map(str::to_string));
+            files.extend(fields.next().map(str::to_string));
+        } else {
+            files.push(path.to_string());
+        }
+    }
+    files.retain(|f| !f.is_empty());
+    files.dedup();
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_numstat_z() {
+        let output = [
+            "2\t0\tsrc/lib.rs",
+            "-\t-\tlogo.png",
+            "1\t1\t",
+            "old.txt",
+            "new.txt",
+            "",
+        ]
+        .join("\0");
+        assert_eq!(
+            parse_numstat_z(&output),
+            vec!["src/lib.rs", "logo.png", "old.txt", "new.txt"]
+        );
+        assert!(parse_numstat_z("").is_empty());
+    }
+}
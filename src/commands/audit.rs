@@ -0,0 +1,340 @@
+//! `git-ai audit export [--range <base>[..<head>]] [--output <file>] [--sign]`: the
+//! audit trail of a range (see `authorship::audit`), as evidence for SOC 2 or ISO 27001
+//! reviews of AI-assisted changes.
+//!
+//! The range defaults to that of `ci-gate`. Records go to stdout, or are appended to
+//! `--output`, whose chain is checked first and continued. `--sign` ends the export
+//! with a seal signed like a git commit: with `ssh-keygen -Y sign` (namespace
+//! `git-ai-audit`) when `gpg.format` is `ssh`, with gpg otherwise, using
+//! `user.signingkey`.
+//!
+//! `git-ai audit verify <file>` checks the chain of an export and exits 1 if broken.
+
+use crate::authorship::audit::{
+    self, AUDIT_FORMAT_VERSION, AuditChain, AuditEvent, AuditRecord, Reclassification,
+};
+use crate::authorship::policy::{self as file_policy, POLICY_FILE, Policy};
+use crate::authorship::push_policy::evaluate_commits;
+use crate::commands::ci_gate::RequestRange;
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::refs::get_authorship;
+use crate::git::repository::Repository;
+use crate::repo_url::normalize_repo_url;
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Namespace of the SSH signatures of seals
+pub const SIGNATURE_NAMESPACE: &str = "git-ai-audit";
+
+pub fn handle_audit(args: &[String]) {
+    match args.first().map(String::as_str) {
+        Some("export") => handle_export(&args[1..]),
+        Some("verify") if args.len() == 2 => handle_verify(&args[1]),
+        _ => {
+            eprintln!(
+                "Usage: git-ai audit export [--range <base>[..<head>]] [--output <file>] [--sign]"
+            );
+            eprintln!("       git-ai audit verify <file>");
+            std::process::exit(1);
+        }
+    }
+}
+
+fn handle_export(args: &[String]) {
+    let mut range = None;
+    let mut output = None;
+    let mut sign = false;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--range" if i + 1 < args.len() => {
+                range = Some(args[i + 1].clone());
+                i += 1;
+            }
+            "--output" | "-o" if i + 1 < args.len() => {
+                output = Some(args[i + 1].clone());
+                i += 1;
+            }
+            "--sign" => sign = true,
+            _ => {
+                eprintln!(
+                    "Usage: git-ai audit export [--range <base>[..<head>]] [--output <file>] [--sign]"
+                );
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    let result = (|| {
+        let repo = find_repository(&Vec::<String>::new())?;
+        let previous = match &output {
+            Some(path) => match std::fs::read_to_string(path) {
+                Ok(source) => audit::verify(&source)
+                    .map_err(|e| GitAiError::Generic(format!("{}: {}", path, e)))?,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+                Err(e) => return Err(e.into()),
+            },
+            None => Vec::new(),
+        };
+        let (chain, exported) = export(&repo, range.as_deref(), &previous, sign)?;
+        let mut lines = chain.into_lines().join("\n");
+        lines.push('\n');
+        match &output {
+            Some(path) => {
+                std::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)?
+                    .write_all(lines.as_bytes())?;
+                eprintln!(
+                    "git-ai: recorded {} commit(s) in {}",
+                    exported,
+                    path.as_str()
+                );
+            }
+            None => print!("{}", lines),
+        }
+        Ok::<_, GitAiError>(())
+    })();
+    if let Err(e) = result {
+        crate::error::exit_with(&format!("git-ai: audit export failed: {}", e), &e);
+    }
+}
+
+fn handle_verify(path: &str) {
+    let result = std::fs::read_to_string(path)
+        .map_err(GitAiError::from)
+        .and_then(|source| audit::verify(&source));
+    match result {
+        Ok(records) => {
+            let seals = records
+                .iter()
+                .filter(|r| matches!(r.event, AuditEvent::Seal { .. }))
+                .count();
+            eprintln!(
+                "git-ai: {}: {} record(s) intact, {} seal(s)",
+                path,
+                records.len(),
+                seals
+            );
+        }
+        Err(e) => crate::error::exit_with(&format!("git-ai: {}: {}", path, e), &e),
+    }
+}
+
+/// The records of the commits of `range` that `previous` does not record yet, and how
+/// many commits that is
+fn export(
+    repo: &Repository,
+    range: Option<&str>,
+    previous: &[AuditRecord],
+    sign: bool,
+) -> Result<(AuditChain, usize), GitAiError> {
+    let request = RequestRange::resolve(repo, range)?;
+    let recorded: HashSet<&str> = previous
+        .iter()
+        .filter_map(|r| match &r.event {
+            AuditEvent::Attribution { commit, .. } => Some(commit.as_str()),
+            _ => None,
+        })
+        .collect();
+    let commits: Vec<String> = request
+        .commits
+        .iter()
+        .filter(|sha| !recorded.contains(sha.as_str()))
+        .cloned()
+        .collect();
+
+    let now = now_secs();
+    let mut chain = AuditChain::after(previous);
+    chain.push(
+        now,
+        AuditEvent::Header {
+            format: "git-ai-audit".to_string(),
+            version: AUDIT_FORMAT_VERSION,
+            generator: format!("git-ai {}", env!("CARGO_PKG_VERSION")),
+            repository: repository_name(repo),
+            range: format!("{}..{}", request.merge_base, request.head),
+        },
+    )?;
+
+    // The policy a change is checked against is that of its target, as in `ci-gate`
+    let mut violations = evaluate_commits(repo, Config::get().push_policy(), &commits)?;
+    let policy = Policy::load_at(repo, &request.merge_base)?;
+    if let Some(policy) = &policy {
+        violations.extend(policy.evaluate_commits(repo, &commits)?);
+    }
+    let checked_against = if policy.is_some() {
+        format!("push_policy and {}", POLICY_FILE)
+    } else {
+        "push_policy".to_string()
+    };
+    let mut violations_by_commit: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for violation in violations {
+        violations_by_commit
+            .entry(violation.commit_sha)
+            .or_default()
+            .push(violation.reason);
+    }
+
+    let mut reclassifications: BTreeMap<String, Vec<Reclassification>> = BTreeMap::new();
+    for reclassification in audit::read_reclassifications(&repo.storage)? {
+        reclassifications
+            .entry(reclassification.base_commit.clone())
+            .or_default()
+            .push(reclassification);
+    }
+
+    for sha in &commits {
+        let info = repo.git(&["log", "-1", "--format=%ct%x00%P%x00%an <%ae>%x00%s", sha])?;
+        let mut fields = info.trim_end_matches('\n').splitn(4, '\0');
+        let committed_at = fields.next().unwrap_or_default().parse().unwrap_or(0);
+        let parent = fields.next().unwrap_or_default().split(' ').next();
+        let author = fields.next().unwrap_or_default().to_string();
+        let subject = fields.next().unwrap_or_default().to_string();
+
+        if let Some(reviewed) = parent.and_then(|p| reclassifications.remove(p)) {
+            for mut reclassification in reviewed {
+                reclassification.commit = Some(sha.clone());
+                chain.push(
+                    reclassification.reviewed_at,
+                    AuditEvent::Reclassification(reclassification),
+                )?;
+            }
+        }
+
+        let log = get_authorship(repo, sha);
+        let ai_lines = log.as_ref().map(file_policy::ai_lines_by_file);
+        chain.push(
+            committed_at,
+            AuditEvent::Attribution {
+                commit: sha.clone(),
+                subject,
+                author,
+                attributed: log.is_some(),
+                ai_lines: ai_lines
+                    .iter()
+                    .flat_map(|files| files.values())
+                    .flat_map(|by_prompt| by_prompt.values())
+                    .sum(),
+                tools: log.as_ref().map(audit::tool_lines).unwrap_or_default(),
+                files: ai_lines
+                    .iter()
+                    .flat_map(|files| files.keys().cloned())
+                    .collect(),
+                human_checked_files: log
+                    .iter()
+                    .flat_map(|log| log.metadata.human_checked_files.iter().cloned())
+                    .collect(),
+            },
+        )?;
+
+        let violations = violations_by_commit.remove(sha).unwrap_or_default();
+        chain.push(
+            now,
+            AuditEvent::PolicyEvaluation {
+                commit: sha.clone(),
+                checked_against: checked_against.clone(),
+                passed: violations.is_empty(),
+                violations,
+            },
+        )?;
+    }
+
+    if sign {
+        let (format, signature) = sign_payload(repo, chain.head())?;
+        chain.push(now, AuditEvent::Seal { format, signature })?;
+    }
+    Ok((chain, commits.len()))
+}
+
+/// The normalized URL of the default remote, or the path of the working tree
+fn repository_name(repo: &Repository) -> String {
+    let url = (|| {
+        let remote = repo.get_default_remote().ok()??;
+        let (_, url) = repo
+            .remotes_with_urls()
+            .ok()?
+            .into_iter()
+            .find(|(name, _)| *name == remote)?;
+        normalize_repo_url(&url).ok()
+    })();
+    url.unwrap_or_else(|| {
+        repo.workdir()
+            .map(|w| w.display().to_string())
+            .unwrap_or_default()
+    })
+}
+
+/// A detached signature of `payload` with the git signing key, and its format
+fn sign_payload(repo: &Repository, payload: &str) -> Result<(String, String), GitAiError> {
+    let format = repo
+        .config_get_str("gpg.format")?
+        .unwrap_or_else(|| "openpgp".to_string());
+    let key = repo.config_get_str("user.signingkey")?;
+    let mut command = match format.as_str() {
+        "ssh" => {
+            let Some(key) = key else {
+                return Err(GitAiError::Generic(
+                    "--sign with gpg.format=ssh needs user.signingkey".to_string(),
+                ));
+            };
+            let key = match key.strip_prefix("~/").zip(dirs::home_dir()) {
+                Some((rest, home)) => home.join(rest).display().to_string(),
+                None => key,
+            };
+            let program = repo
+                .config_get_str("gpg.ssh.program")?
+                .unwrap_or_else(|| "ssh-keygen".to_string());
+            let mut command = Command::new(program);
+            command.args(["-Y", "sign", "-n", SIGNATURE_NAMESPACE, "-f", &key]);
+            command
+        }
+        "openpgp" => {
+            let program = repo
+                .config_get_str("gpg.program")?
+                .unwrap_or_else(|| "gpg".to_string());
+            let mut command = Command::new(program);
+            command.args(["--detach-sign", "--armor"]);
+            if let Some(key) = key {
+                command.args(["--local-user", &key]);
+            }
+            command
+        }
+        other => {
+            return Err(GitAiError::Generic(format!(
+                "--sign does not support gpg.format={}",
+                other
+            )));
+        }
+    };
+
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(payload.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        return Err(GitAiError::Generic(format!(
+            "signing failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok((format, String::from_utf8(output.stdout)?))
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
@@ -0,0 +1,182 @@
+use crate::api::network;
+use crate::api::{ApiClient, ApiContext};
+use crate::auth::types::StoredCredentials;
+use crate::auth::{self, CredentialStore, profiles};
+use serde_json::json;
+
+pub fn handle_auth(args: &[String]) {
+    let args = match profiles::take_flag(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    match args.first().map(String::as_str) {
+        Some("status") => {
+            let json = match &args[1..] {
+                [] => false,
+                [flag] if flag == "--json" => true,
+                _ => {
+                    print_auth_help();
+                    std::process::exit(1);
+                }
+            };
+            let logged_in = print_status(json);
+            if !logged_in {
+                std::process::exit(1);
+            }
+        }
+        Some("--help" | "-h" | "help") => print_auth_help(),
+        _ => {
+            print_auth_help();
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Print the active profile's login; returns whether it is logged in
+fn print_status(json: bool) -> bool {
+    let profile = profiles::active();
+    let api_base_url = profiles::api_base_url();
+    let binding = profiles::binding();
+    let binding_error = profiles::check_binding(&api_base_url).err();
+    let store = CredentialStore::new();
+    // GIT_AI_TOKEN is used instead of the stored credentials, like every API call does
+    let env_token = auth::env_token();
+    let creds = match &env_token {
+        Some(token) => Some(StoredCredentials::service_token(token)),
+        None => store.load().ok().flatten(),
+    };
+    let source = match &creds {
+        _ if env_token.is_some() => Some(auth::TOKEN_ENV),
+        Some(creds) if creds.service => Some("service token"),
+        Some(_) => Some("oauth"),
+        None => None,
+    };
+    let expiry = |expires_at: fn(&StoredCredentials) -> i64| {
+        creds.as_ref().filter(|c| !c.service).map(expires_at)
+    };
+    let logged_in = creds
+        .as_ref()
+        .is_some_and(|creds| !creds.is_refresh_token_expired());
+
+    // Only ask the server who we are when there are credentials to ask with
+    let identity = if !logged_in {
+        Err("not logged in".to_string())
+    } else if network::offline() {
+        Err("offline mode".to_string())
+    } else {
+        ApiClient::new(ApiContext::new(None))
+            .user_info()
+            .map_err(|e| e.to_string())
+    };
+
+    if json {
+        let identity = identity.as_ref().ok();
+        println!(
+            "{}",
+            json!({
+                "profile": profile.as_deref().unwrap_or(profiles::DEFAULT_PROFILE),
+                "api_base_url": api_base_url,
+                "credential_store": store.backend_name(),
+                "binding": binding.is_bound().then(|| json!({
+                    "profile": binding.profile,
+                    "api_base_url": binding.api_base_url,
+                })),
+                "binding_error": binding_error,
+                "logged_in": logged_in,
+                "token_source": source.filter(|_| logged_in),
+                "email": identity.and_then(|user| user.email.clone()),
+                "name": identity.and_then(|user| user.name.clone()),
+                "org": identity.and_then(|user| user.org.clone()),
+                "access_token_expires_at": expiry(|c| c.access_token_expires_at),
+                "refresh_token_expires_at": expiry(|c| c.refresh_token_expires_at),
+            })
+        );
+        return logged_in;
+    }
+
+    println!(
+        "Profile:       {}",
+        profile.as_deref().unwrap_or(profiles::DEFAULT_PROFILE)
+    );
+    println!("API:           {}", api_base_url);
+    println!("Storage:       {}", store.backend_name());
+    if binding.is_bound() {
+        let bound: Vec<String> = [
+            binding
+                .profile
+                .as_ref()
+                .map(|name| format!("profile '{}'", name)),
+            binding.api_base_url.clone(),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        println!("Repository:    bound to {}", bound.join(", "));
+    }
+    if let Some(e) = &binding_error {
+        println!("Warning:       {}; uploads from here are refused", e);
+    }
+    let Some(creds) = creds.filter(|_| logged_in) else {
+        println!("Logged in:     no (run 'git-ai login')");
+        return false;
+    };
+    match source {
+        Some("oauth") | None => println!("Logged in:     yes"),
+        Some(source) => println!("Logged in:     yes ({})", source),
+    }
+    match identity {
+        Ok(user) => {
+            let who = match (user.email, user.name) {
+                (Some(email), Some(name)) => format!("{} ({})", name, email),
+                (Some(who), None) | (None, Some(who)) => who,
+                (None, None) => "unknown".to_string(),
+            };
+            println!("User:          {}", who);
+            println!(
+                "Organization:  {}",
+                user.org.as_deref().unwrap_or("(personal)")
+            );
+        }
+        Err(e) => println!("User:          unavailable ({})", e),
+    }
+    if creds.service {
+        return true;
+    }
+    println!(
+        "Access token:  {}",
+        describe_expiry(creds.access_token_expires_at)
+    );
+    println!(
+        "Refresh token: {}",
+        describe_expiry(creds.refresh_token_expires_at)
+    );
+    true
+}
+
+fn describe_expiry(expires_at: i64) -> String {
+    let Some(time) = chrono::DateTime::from_timestamp(expires_at, 0) else {
+        return "unknown expiry".to_string();
+    };
+    let formatted = time.format("%Y-%m-%d %H:%M UTC");
+    if expires_at <= chrono::Utc::now().timestamp() {
+        format!("expired {} (refreshed on next use)", formatted)
+    } else {
+        format!("expires {}", formatted)
+    }
+}
+
+fn print_auth_help() {
+    eprintln!("git-ai auth - Inspect the stored login");
+    eprintln!();
+    eprintln!("Usage: git-ai auth status [--json] [--profile <name>]");
+    eprintln!();
+    eprintln!("Shows the active profile, its API, where credentials are stored, the");
+    eprintln!("profile or API the repository is bound to (git config git-ai.profile /");
+    eprintln!("git-ai.apiBaseUrl), the logged-in user and organization, and when the");
+    eprintln!("tokens expire. Exits 1");
+    eprintln!("when not logged in. A GIT_AI_TOKEN service token takes precedence over");
+    eprintln!("the stored login.");
+}
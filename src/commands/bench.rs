@@ -0,0 +1,623 @@
+//! `git-ai bench`: timings of the attribution engine on reproducible scenarios, to
+//! catch performance regressions before a release and to profile a repository.
+//!
+//! The synthetic scenarios generate their inputs from a seed (`--seed`, 42 by
+//! default), so a run on one machine is comparable with the next:
+//! - `large-file-churn`: successive edits to one large file
+//! - `many-small-files`: one edit to each of many small files
+//! - `deep-history`: parsing and aggregating the authorship logs of a long history
+//!
+//! `repo-history` aggregates the stats of the last `--commits` commits of the current
+//! repository instead; it only runs when named.
+//!
+//! Measurements follow criterion: each scenario runs `--warmup` times untimed, then
+//! `--samples` timed runs, reported by their median with the spread around it. Each
+//! run also yields a checksum of its result, which must not change between runs with
+//! the same seed. `--save-baseline <file>` writes the measurements; `--baseline
+//! <file>` compares with them and exits with 1 when a median is more than
+//! `--threshold` percent (10 by default) slower.
+
+use crate::authorship::attribution_tracker::{
+    AttributionTracker, attributions_to_line_attributions,
+};
+use crate::authorship::authorship_log::{LineRange, PromptRecord};
+use crate::authorship::authorship_log_serialization::{AttestationEntry, AuthorshipLog};
+use crate::authorship::stats::{stats_for_commits_stats, stats_from_authorship_log};
+use crate::authorship::working_log::{AgentId, CheckpointKind};
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::{Repository, exec_git};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::time::Instant;
+
+const DEFAULT_SEED: u64 = 42;
+const DEFAULT_SAMPLES: usize = 10;
+const DEFAULT_WARMUP: usize = 1;
+const DEFAULT_COMMITS: usize = 200;
+const DEFAULT_THRESHOLD: f64 = 10.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scenario {
+    LargeFileChurn,
+    ManySmallFiles,
+    DeepHistory,
+    RepoHistory,
+}
+
+impl Scenario {
+    /// The scenarios run when none is named
+    pub const DEFAULT: [Scenario; 3] = [
+        Scenario::LargeFileChurn,
+        Scenario::ManySmallFiles,
+        Scenario::DeepHistory,
+    ];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "large-file-churn" => Some(Scenario::LargeFileChurn),
+            "many-small-files" => Some(Scenario::ManySmallFiles),
+            "deep-history" => Some(Scenario::DeepHistory),
+            "repo-history" => Some(Scenario::RepoHistory),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Scenario::LargeFileChurn => "large-file-churn",
+            Scenario::ManySmallFiles => "many-small-files",
+            Scenario::DeepHistory => "deep-history",
+            Scenario::RepoHistory => "repo-history",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BenchOptions {
+    pub seed: u64,
+    pub samples: usize,
+    pub warmup: usize,
+    /// Shrink the synthetic scenarios tenfold, for a quick check
+    pub quick: bool,
+    /// Commits aggregated by `repo-history`
+    pub commits: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        BenchOptions {
+            seed: DEFAULT_SEED,
+            samples: DEFAULT_SAMPLES,
+            warmup: DEFAULT_WARMUP,
+            quick: false,
+            commits: DEFAULT_COMMITS,
+        }
+    }
+}
+
+impl BenchOptions {
+    fn size(&self, full: usize) -> usize {
+        if self.quick { (full / 10).max(1) } else { full }
+    }
+}
+
+/// Timings of one scenario, in nanoseconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Measurement {
+    pub scenario: String,
+    pub samples: usize,
+    pub median_ns: u64,
+    pub mean_ns: u64,
+    pub min_ns: u64,
+    pub max_ns: u64,
+    /// Median absolute deviation from the median
+    pub mad_ns: u64,
+    pub checksum: u64,
+}
+
+impl Measurement {
+    fn from_samples(scenario: Scenario, mut samples: Vec<u64>, checksum: u64) -> Self {
+        samples.sort_unstable();
+        let median_ns = median(&samples);
+        let mut deviations: Vec<u64> = samples.iter().map(|s| s.abs_diff(median_ns)).collect();
+        deviations.sort_unstable();
+        Measurement {
+            scenario: scenario.name().to_string(),
+            samples: samples.len(),
+            median_ns,
+            mean_ns: (samples.iter().sum::<u64>())
+                .checked_div(samples.len() as u64)
+                .unwrap_or(0),
+            min_ns: samples.first().copied().unwrap_or(0),
+            max_ns: samples.last().copied().unwrap_or(0),
+            mad_ns: median(&deviations),
+            checksum,
+        }
+    }
+}
+
+fn median(sorted: &[u64]) -> u64 {
+    match sorted.len() {
+        0 => 0,
+        n if n % 2 == 1 => sorted[n / 2],
+        n => (sorted[n / 2 - 1] + sorted[n / 2]) / 2,
+    }
+}
+
+/// A scenario's inputs, generated before anything is timed
+enum Workload {
+    /// Successive versions of one file
+    Versions(Vec<String>),
+    /// Old and new contents of each file
+    Files(Vec<(String, String)>),
+    /// Serialized authorship logs
+    Notes(Vec<String>),
+    Commits(Box<Repository>, Vec<String>),
+}
+
+pub fn handle_bench(args: &[String]) {
+    let usage = "Usage: git-ai bench [<scenario>...] [--samples <n>] [--warmup <n>] [--seed <n>] [--quick] [--commits <n>] [--json] [--save-baseline <file>] [--baseline <file> [--threshold <percent>]]";
+    let mut options = BenchOptions::default();
+    let mut scenarios = Vec::new();
+    let mut json = false;
+    let mut save_baseline = None;
+    let mut baseline = None;
+    let mut threshold = DEFAULT_THRESHOLD;
+
+    let number = |name: &str, value: &str| -> u64 {
+        value.parse().unwrap_or_else(|_| {
+            eprintln!("Invalid {}: {}", name, value);
+            std::process::exit(1);
+        })
+    };
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--samples" if i + 1 < args.len() => {
+                options.samples = (number("--samples", &args[i + 1]) as usize).max(1);
+                i += 1;
+            }
+            "--warmup" if i + 1 < args.len() => {
+                options.warmup = number("--warmup", &args[i + 1]) as usize;
+                i += 1;
+            }
+            "--seed" if i + 1 < args.len() => {
+                options.seed = number("--seed", &args[i + 1]);
+                i += 1;
+            }
+            "--commits" if i + 1 < args.len() => {
+                options.commits = number("--commits", &args[i + 1]) as usize;
+                i += 1;
+            }
+            "--threshold" if i + 1 < args.len() => {
+                threshold = args[i + 1].parse().unwrap_or_else(|_| {
+                    eprintln!("Invalid --threshold: {}", args[i + 1]);
+                    std::process::exit(1);
+                });
+                i += 1;
+            }
+            "--save-baseline" if i + 1 < args.len() => {
+                save_baseline = Some(args[i + 1].clone());
+                i += 1;
+            }
+            "--baseline" if i + 1 < args.len() => {
+                baseline = Some(args[i + 1].clone());
+                i += 1;
+            }
+            "--quick" => options.quick = true,
+            "--json" => json = true,
+            arg if !arg.starts_with('-') => match Scenario::parse(arg) {
+                Some(scenario) => scenarios.push(scenario),
+                None => {
+                    eprintln!(
+                        "Unknown scenario: {} (use large-file-churn, many-small-files, deep-history or repo-history)",
+                        arg
+                    );
+                    std::process::exit(1);
+                }
+            },
+            _ => {
+                eprintln!("{}", usage);
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+    if scenarios.is_empty() {
+        scenarios = Scenario::DEFAULT.to_vec();
+    }
+
+    let result = run_bench(&scenarios, &options, |measurement| {
+        if !json {
+            println!("{}", format_measurement(measurement));
+        }
+    })
+    .and_then(|measurements| {
+        if let Some(path) = &save_baseline {
+            std::fs::write(path, serde_json::to_string_pretty(&measurements)?)?;
+        }
+        let baseline = match &baseline {
+            Some(path) => Some(serde_json::from_str::<Vec<Measurement>>(
+                &std::fs::read_to_string(path)?,
+            )?),
+            None => None,
+        };
+        Ok((measurements, baseline))
+    });
+    let (measurements, baseline) = match result {
+        Ok(result) => result,
+        Err(e) => crate::error::exit_with(&format!("Benchmark failed: {}", e), &e),
+    };
+
+    let comparisons = baseline
+        .map(|baseline| compare(&measurements, &baseline, threshold))
+        .unwrap_or_default();
+    if json {
+        let output = serde_json::json!({
+            "measurements": measurements,
+            "comparisons": comparisons,
+        });
+        println!("{}", output);
+    } else if !comparisons.is_empty() {
+        println!();
+        for comparison in &comparisons {
+            println!(
+                "{:<18} {:+.1}% vs baseline{}",
+                comparison.scenario,
+                comparison.change_percent,
+                if comparison.regressed {
+                    "  REGRESSED"
+                } else {
+                    ""
+                }
+            );
+        }
+    }
+    let regressed: Vec<&str> = comparisons
+        .iter()
+        .filter(|c| c.regressed)
+        .map(|c| c.scenario.as_str())
+        .collect();
+    if !regressed.is_empty() {
+        eprintln!(
+            "More than {}% slower than the baseline: {}",
+            threshold,
+            regressed.join(", ")
+        );
+        std::process::exit(1);
+    }
+}
+
+/// Measure each of `scenarios`, calling `report` as each one finishes
+pub fn run_bench(
+    scenarios: &[Scenario],
+    options: &BenchOptions,
+    mut report: impl FnMut(&Measurement),
+) -> Result<Vec<Measurement>, GitAiError> {
+    let mut measurements = Vec::new();
+    for scenario in scenarios {
+        let workload = prepare(*scenario, options)?;
+        for _ in 0..options.warmup {
+            run(&workload)?;
+        }
+        let mut samples = Vec::with_capacity(options.samples);
+        let mut checksum = None;
+        for _ in 0..options.samples {
+            let start = Instant::now();
+            let sum = run(&workload)?;
+            samples.push(start.elapsed().as_nanos() as u64);
+            if checksum.is_some_and(|previous| previous != sum) {
+                return Err(GitAiError::Generic(format!(
+                    "{} gave different results between runs",
+                    scenario.name()
+                )));
+            }
+            checksum = Some(sum);
+        }
+        let measurement =
+            Measurement::from_samples(*scenario, samples, checksum.unwrap_or_default());
+        report(&measurement);
+        measurements.push(measurement);
+    }
+    Ok(measurements)
+}
+
+/// A small deterministic generator (SplitMix64), so inputs only depend on the seed
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n.max(1) as u64) as usize
+    }
+
+    fn line(&mut self) -> String {
+        let indent = "    ".repeat(self.below(3));
+        format!(
+            "{}let value_{} = compute({}, {});",
+            indent,
+            self.below(10_000),
+            self.below(100),
+            self.below(100)
+        )
+    }
+}
+
+/// `lines` with `edits` lines inserted, deleted or replaced at random
+fn churn(rng: &mut Rng, lines: &[String], edits: usize) -> Vec<String> {
+    let mut lines = lines.to_vec();
+    for _ in 0..edits {
+        let at = rng.below(lines.len() + 1);
+        match rng.below(3) {
+            0 => lines.insert(at, rng.line()),
+            1 if at < lines.len() => {
+                lines.remove(at);
+            }
+            _ if at < lines.len() => lines[at] = rng.line(),
+            _ => lines.push(rng.line()),
+        }
+    }
+    lines
+}
+
+fn to_content(lines: &[String]) -> String {
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
+
+fn prepare(scenario: Scenario, options: &BenchOptions) -> Result<Workload, GitAiError> {
+    let mut rng = Rng(options.seed);
+    Ok(match scenario {
+        Scenario::LargeFileChurn => {
+            let mut lines: Vec<String> = (0..options.size(10_000)).map(|_| rng.line()).collect();
+            let mut versions = vec![to_content(&lines)];
+            for _ in 0..options.size(20) {
+                lines = churn(&mut rng, &lines, 50);
+                versions.push(to_content(&lines));
+            }
+            Workload::Versions(versions)
+        }
+        Scenario::ManySmallFiles => {
+            let files = (0..options.size(2_000))
+                .map(|_| {
+                    let lines: Vec<String> = (0..20).map(|_| rng.line()).collect();
+                    let edited = churn(&mut rng, &lines, 3);
+                    (to_content(&lines), to_content(&edited))
+                })
+                .collect();
+            Workload::Files(files)
+        }
+        Scenario::DeepHistory => {
+            let notes = (0..options.size(5_000))
+                .map(|commit| synthetic_note(&mut rng, commit))
+                .collect::<Result<_, _>>()?;
+            Workload::Notes(notes)
+        }
+        Scenario::RepoHistory => {
+            let repo = find_repository(&Vec::<String>::new())?;
+            let mut args = repo.global_args_for_exec();
+            args.extend([
+                "rev-list".to_string(),
+                format!("--max-count={}", options.commits),
+                "HEAD".to_string(),
+            ]);
+            let output = String::from_utf8(exec_git(&args)?.stdout)?;
+            let shas = output.lines().map(str::to_string).collect();
+            Workload::Commits(Box::new(repo), shas)
+        }
+    })
+}
+
+/// The authorship log of a commit touching a few files with a few prompts
+fn synthetic_note(rng: &mut Rng, commit: usize) -> Result<String, GitAiError> {
+    let mut log = AuthorshipLog::new();
+    for prompt in 0..1 + rng.below(3) {
+        let hash = format!("{:016x}", rng.next());
+        log.metadata.prompts.insert(
+            hash.clone(),
+            PromptRecord {
+                agent_id: AgentId {
+                    tool: ["cursor", "claude", "codex"][prompt % 3].to_string(),
+                    id: format!("session-{}-{}", commit, prompt),
+                    model: "model".to_string(),
+                },
+                human_author: Some("dev".to_string()),
+                messages: Vec::new(),
+                total_additions: rng.below(200) as u32,
+                total_deletions: rng.below(50) as u32,
+                accepted_lines: rng.below(100) as u32,
+                overriden_lines: rng.below(10) as u32,
+                messages_url: None,
+            },
+        );
+        for _ in 0..1 + rng.below(4) {
+            let file = format!("src/module_{}/file_{}.rs", rng.below(20), rng.below(50));
+            let start = 1 + rng.below(500) as u32;
+            let ranges = vec![
+                LineRange::Range(start, start + rng.below(40) as u32),
+                LineRange::Single(start + 60),
+            ];
+            log.get_or_create_file(&file)
+                .add_entry(AttestationEntry::new(hash.clone(), ranges));
+        }
+    }
+    log.serialize_to_string()
+        .map_err(|e| GitAiError::Generic(format!("Failed to serialize note: {}", e)))
+}
+
+/// Run a workload once; returns a checksum of what it computed
+fn run(workload: &Workload) -> Result<u64, GitAiError> {
+    let human = CheckpointKind::Human.to_str();
+    let tracker = AttributionTracker::new();
+    match workload {
+        Workload::Versions(versions) => {
+            let mut attributions =
+                tracker.attribute_unattributed_ranges(&versions[0], &[], &human, 1);
+            for (i, pair) in versions.windows(2).enumerate() {
+                let author = if i % 2 == 0 {
+                    "ai-session"
+                } else {
+                    human.as_str()
+                };
+                attributions = tracker.update_attributions(
+                    &pair[0],
+                    &pair[1],
+                    &attributions,
+                    author,
+                    i as u128 + 2,
+                )?;
+            }
+            let content = versions.last().map(String::as_str).unwrap_or_default();
+            Ok(attributions_to_line_attributions(&attributions, content).len() as u64)
+        }
+        Workload::Files(files) => {
+            let mut sum = 0;
+            for (old, new) in files {
+                let attributions = tracker.attribute_unattributed_ranges(old, &[], &human, 1);
+                let attributions =
+                    tracker.update_attributions(old, new, &attributions, "ai-session", 2)?;
+                sum += attributions_to_line_attributions(&attributions, new).len() as u64;
+            }
+            Ok(sum)
+        }
+        Workload::Notes(notes) => {
+            let mut sum = 0;
+            for note in notes {
+                let log = AuthorshipLog::deserialize_from_string(note)
+                    .map_err(|e| GitAiError::Generic(format!("Failed to parse note: {}", e)))?;
+                let stats = stats_from_authorship_log(Some(&log), 0, 0, 0, &BTreeMap::new());
+                sum += (stats.total_ai_additions + stats.mixed_additions) as u64;
+            }
+            Ok(sum)
+        }
+        Workload::Commits(repo, shas) => {
+            let stats = stats_for_commits_stats(repo, shas, &[])?;
+            Ok(stats
+                .iter()
+                .map(|s| (s.ai_additions + s.human_additions) as u64)
+                .sum())
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Comparison {
+    pub scenario: String,
+    /// Change of the median from the baseline, in percent
+    pub change_percent: f64,
+    pub regressed: bool,
+}
+
+/// How each measurement compares with the same scenario in `baseline`
+pub fn compare(
+    measurements: &[Measurement],
+    baseline: &[Measurement],
+    threshold: f64,
+) -> Vec<Comparison> {
+    measurements
+        .iter()
+        .filter_map(|measurement| {
+            let base = baseline
+                .iter()
+                .find(|b| b.scenario == measurement.scenario && b.median_ns > 0)?;
+            let change_percent =
+                (measurement.median_ns as f64 / base.median_ns as f64 - 1.0) * 100.0;
+            Some(Comparison {
+                scenario: measurement.scenario.clone(),
+                change_percent,
+                regressed: change_percent > threshold,
+            })
+        })
+        .collect()
+}
+
+fn format_duration(ns: u64) -> String {
+    match ns {
+        0..1_000 => format!("{} ns", ns),
+        1_000..1_000_000 => format!("{:.1} µs", ns as f64 / 1e3),
+        1_000_000..1_000_000_000 => format!("{:.1} ms", ns as f64 / 1e6),
+        _ => format!("{:.2} s", ns as f64 / 1e9),
+    }
+}
+
+fn format_measurement(m: &Measurement) -> String {
+    format!(
+        "{:<18} median {:>9}  ±{:>9}  (min {}, max {}, {} samples)",
+        m.scenario,
+        format_duration(m.median_ns),
+        format_duration(m.mad_ns),
+        format_duration(m.min_ns),
+        format_duration(m.max_ns),
+        m.samples
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quick() -> BenchOptions {
+        BenchOptions {
+            samples: 2,
+            warmup: 0,
+            quick: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_scenarios_are_reproducible() {
+        let first = run_bench(&Scenario::DEFAULT, &quick(), |_| {}).unwrap();
+        let second = run_bench(&Scenario::DEFAULT, &quick(), |_| {}).unwrap();
+        for (a, b) in first.iter().zip(&second) {
+            assert_eq!(a.checksum, b.checksum, "{}", a.scenario);
+            assert!(a.checksum > 0, "{}", a.scenario);
+        }
+
+        let other_seed = BenchOptions { seed: 7, ..quick() };
+        let third = run_bench(&[Scenario::LargeFileChurn], &other_seed, |_| {}).unwrap();
+        assert_ne!(third[0].checksum, first[0].checksum);
+    }
+
+    #[test]
+    fn test_measurement_from_samples() {
+        let m = Measurement::from_samples(Scenario::DeepHistory, vec![30, 10, 20, 100], 1);
+        assert_eq!(m.median_ns, 25);
+        assert_eq!(m.mean_ns, 40);
+        assert_eq!((m.min_ns, m.max_ns), (10, 100));
+        // Deviations 5, 5, 15, 75
+        assert_eq!(m.mad_ns, 10);
+    }
+
+    #[test]
+    fn test_compare_with_baseline() {
+        let measurement = |scenario: Scenario, median_ns| Measurement {
+            median_ns,
+            ..Measurement::from_samples(scenario, vec![median_ns], 0)
+        };
+        let baseline = vec![
+            measurement(Scenario::LargeFileChurn, 100),
+            measurement(Scenario::DeepHistory, 100),
+        ];
+        let current = vec![
+            measurement(Scenario::LargeFileChurn, 105),
+            measurement(Scenario::DeepHistory, 150),
+            measurement(Scenario::ManySmallFiles, 10),
+        ];
+        let comparisons = compare(&current, &baseline, 10.0);
+        assert_eq!(comparisons.len(), 2);
+        assert!(!comparisons[0].regressed);
+        assert!(comparisons[1].regressed);
+        assert!((comparisons[1].change_percent - 50.0).abs() < 1e-9);
+    }
+}
@@ -4,19 +4,23 @@ use crate::authorship::attribution_tracker::{
 use crate::authorship::authorship_log::PromptRecord;
 use crate::authorship::authorship_log_serialization::generate_short_hash;
 use crate::authorship::imara_diff_utils::{LineChangeTag, compute_line_changes};
+use crate::authorship::path_config::PathConfig;
 use crate::authorship::working_log::CheckpointKind;
 use crate::authorship::working_log::{Checkpoint, WorkingLogEntry};
 use crate::commands::blame::{GitAiBlameOptions, OLDEST_AI_BLAME_DATE};
 use crate::commands::checkpoint_agent::agent_presets::AgentRunResult;
+use crate::commands::checkpoint_agent::agent_registry;
 use crate::config::Config;
 use crate::error::GitAiError;
-use crate::git::repo_storage::{PersistedWorkingLog, RepoStorage};
+use crate::git::fsmonitor::{FsmonitorChanges, query_fsmonitor};
+use crate::git::repo_storage::{PersistedWorkingLog, RepoStorage, content_sha256};
 use crate::git::repository::Repository;
+use crate::git::stat_cache::{FileStat, StatCache};
 use crate::git::status::{EntryKind, StatusCode};
 use crate::utils::{debug_log, normalize_to_posix};
-use futures::stream::{self, StreamExt};
 use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
@@ -84,6 +88,12 @@ pub fn run(
     let checkpoint_start = Instant::now();
     debug_log("[BENCHMARK] Starting checkpoint run");
 
+    // Agents declared in config get their canonical name and default model
+    let agent_run_result = agent_run_result.map(|mut result| {
+        agent_registry::resolve_agent_id(&mut result.agent_id, Config::get().custom_agents());
+        result
+    });
+
     // Robustly handle zero-commit repos
     let base_commit = match repo.head() {
         Ok(head) => match head.target() {
@@ -216,6 +226,21 @@ pub fn run(
         pathspec_start.elapsed()
     ));
 
+    // With an fsmonitor hook, files checkpointed before and untouched since the last
+    // query are known to be unchanged and need not be read and diffed again
+    let previous_token = working_log.read_fsmonitor_token();
+    let fsmonitor_query = query_fsmonitor(repo, previous_token.as_deref());
+    let fsmonitor_changes = match &fsmonitor_query {
+        Some(query) if !reset && previous_token.is_some() => Some(&query.changes),
+        _ => None,
+    };
+    // Without one, the stat data of the files the last checkpoint read tells the same
+    let previous_stat_cache = if reset {
+        None
+    } else {
+        working_log.read_stat_cache()
+    };
+
     let files_start = Instant::now();
     let files = get_all_tracked_files(
         repo,
@@ -223,6 +248,8 @@ pub fn run(
         &working_log,
         pathspec_filter,
         is_pre_commit,
+        fsmonitor_changes,
+        previous_stat_cache.as_ref(),
     )?;
     debug_log(&format!(
         "[BENCHMARK] get_all_tracked_files found {} files, took {:?}",
@@ -230,6 +257,11 @@ pub fn run(
         files_start.elapsed()
     ));
 
+    // Read and hash the files in the background while the checkpoints and the base
+    // commit's blobs are read
+    let mut stat_cache = StatCache::start();
+    let file_reads = spawn_file_reads(&working_log, &files);
+
     let read_checkpoints_start = Instant::now();
     let mut checkpoints = if reset {
         // If reset flag is set, start with an empty working log
@@ -296,9 +328,26 @@ pub fn run(
         return Ok((0, files.len(), checkpoints.len()));
     }
 
+    if let Some(query) = &fsmonitor_query
+        && let Err(e) = working_log.write_fsmonitor_token(&query.token)
+    {
+        debug_log(&format!("Failed to save fsmonitor token: {}", e));
+    }
+
+    // The base commit's version of the files no checkpoint has seen yet, read in one batch
+    let base_read_start = Instant::now();
+    let base_blobs = read_base_blobs(repo, &base_commit, &files, &checkpoints)?;
+    debug_log(&format!(
+        "[BENCHMARK] Reading {} files from the base commit took {:?}",
+        base_blobs.len(),
+        base_read_start.elapsed()
+    ));
+
     // Save current file states and get content hashes
     let save_states_start = Instant::now();
-    let file_content_hashes = save_current_file_states(&working_log, &files)?;
+    let file_reads = smol::block_on(file_reads);
+    let file_content_hashes =
+        save_current_file_states(&working_log, file_reads, &checkpoints, &base_blobs)?;
     debug_log(&format!(
         "[BENCHMARK] save_current_file_states for {} files took {:?}",
         files.len(),
@@ -333,6 +382,7 @@ pub fn run(
         &files,
         &file_content_hashes,
         &checkpoints,
+        &base_blobs,
         agent_run_result.as_ref(),
         ts,
     ))?;
@@ -428,6 +478,28 @@ pub fn run(
         }
     }
 
+    // Stat data of the files just read, plus that of files skipped as unchanged
+    if let Some(previous) = previous_stat_cache {
+        stat_cache.files = previous.files;
+    }
+    for (file, blob_sha) in &file_content_hashes {
+        let is_dirty = working_log
+            .dirty_files
+            .as_ref()
+            .is_some_and(|dirty| dirty.contains_key(file));
+        let stat = FileStat::of(
+            Path::new(&working_log.to_repo_absolute_path(file)),
+            blob_sha,
+        );
+        match stat {
+            Some(stat) if !is_dirty => stat_cache.files.insert(file.clone(), stat),
+            _ => stat_cache.files.remove(file),
+        };
+    }
+    if let Err(e) = working_log.write_stat_cache(&stat_cache) {
+        debug_log(&format!("Failed to save file stat data: {}", e));
+    }
+
     let agent_tool = if kind != CheckpointKind::Human
         && let Some(agent_run_result) = &agent_run_result
     {
@@ -509,6 +581,16 @@ fn get_status_of_files(
         status_start.elapsed()
     ));
 
+    // HEAD's version of deleted files, to tell whether they were text, read in one batch
+    let deleted: Vec<String> = statuses
+        .iter()
+        .filter(|entry| {
+            entry.staged == StatusCode::Deleted || entry.unstaged == StatusCode::Deleted
+        })
+        .map(|entry| entry.path.clone())
+        .collect();
+    let deleted_in_head = repo.blobs_at_paths("HEAD", &deleted).unwrap_or_default();
+
     for entry in statuses {
         // Skip ignored files
         if entry.kind == EntryKind::Ignored {
@@ -531,7 +613,10 @@ fn get_status_of_files(
                 entry.staged == StatusCode::Deleted || entry.unstaged == StatusCode::Deleted;
 
             let is_text = if is_deleted {
-                is_text_file_in_head(repo, &entry.path)
+                // Consider a file text if it contains no null bytes
+                deleted_in_head
+                    .get(&entry.path)
+                    .is_some_and(|blob| !blob.contains(&0))
             } else {
                 is_text_file(working_log, &entry.path)
             };
@@ -553,6 +638,8 @@ fn get_all_tracked_files(
     working_log: &PersistedWorkingLog,
     edited_filepaths: Option<&Vec<String>>,
     is_pre_commit: bool,
+    fsmonitor_changes: Option<&FsmonitorChanges>,
+    stat_cache: Option<&StatCache>,
 ) -> Result<Vec<String>, GitAiError> {
     let mut files: HashSet<String> = edited_filepaths
         .map(|paths| paths.iter().cloned().collect())
@@ -597,6 +684,9 @@ fn get_all_tracked_files(
     ));
 
     let checkpoints_read_start = Instant::now();
+    let mut checkpointed_files: HashSet<String> = HashSet::new();
+    // Content of each checkpointed file as of its latest entry
+    let mut latest_blobs: HashMap<String, String> = HashMap::new();
     if let Ok(working_log_data) = working_log.read_all_checkpoints() {
         for checkpoint in &working_log_data {
             for entry in &checkpoint.entries {
@@ -610,6 +700,8 @@ fn get_all_tracked_files(
                     ));
                     continue;
                 }
+                checkpointed_files.insert(normalized_path.clone());
+                latest_blobs.insert(normalized_path.clone(), entry.blob_sha.clone());
                 if !files.contains(&normalized_path) {
                     // Check if it's a text file before adding
                     if is_text_file(working_log, &normalized_path) {
@@ -624,6 +716,23 @@ fn get_all_tracked_files(
         checkpoints_read_start.elapsed()
     ));
 
+    // A checkpointed file the fsmonitor hook hasn't seen change since the last checkpoint
+    // still matches its latest entry, so re-reading it could only yield an empty entry
+    if let Some(changes) = fsmonitor_changes {
+        files.retain(|file| !checkpointed_files.contains(file) || changes.may_have_changed(file));
+    }
+    // Likewise for one whose stat data is what it was when its latest entry was read
+    if let Some(cache) = stat_cache {
+        let dirty_files = working_log.dirty_files.as_ref();
+        files.retain(|file| {
+            dirty_files.is_some_and(|dirty| dirty.contains_key(file))
+                || !latest_blobs.get(file).is_some_and(|blob| {
+                    let path = working_log.to_repo_absolute_path(file);
+                    cache.unchanged(file, Path::new(&path), blob)
+                })
+        });
+    }
+
     let has_ai_checkpoints = if let Ok(working_log_data) = working_log.read_all_checkpoints() {
         working_log_data.iter().any(|checkpoint| {
             checkpoint.kind == CheckpointKind::AiAgent || checkpoint.kind == CheckpointKind::AiTab
@@ -666,84 +775,135 @@ fn get_all_tracked_files(
         }
     }
 
+    // Files `.git-ai/paths.toml` ignores are never attributed
+    let paths = PathConfig::for_repo(repo);
+    results_for_tracked_files.retain(|file| !paths.ignores(file));
+
     Ok(results_for_tracked_files)
 }
 
-fn save_current_file_states(
-    working_log: &PersistedWorkingLog,
+/// Object id and content of the blob of each of `files` in `base_commit`, for the files
+/// no previous checkpoint has an entry for
+fn read_base_blobs(
+    repo: &Repository,
+    base_commit: &str,
     files: &[String],
-) -> Result<HashMap<String, String>, GitAiError> {
-    let _read_start = Instant::now();
-
-    // Extract only the data we need (no cloning the entire working_log)
-    let blobs_dir = working_log.dir.join("blobs");
-    let repo_workdir = working_log.repo_workdir.clone();
-    let dirty_files = working_log.dirty_files.clone();
-
-    // Process files concurrently with a semaphore limiting to 8 at a time
-    let file_content_hashes = smol::block_on(async {
-        let semaphore = Arc::new(smol::lock::Semaphore::new(8));
-        let blobs_dir = Arc::new(blobs_dir);
-        let repo_workdir = Arc::new(repo_workdir);
-        let dirty_files = Arc::new(dirty_files);
-
-        let futures = files.iter().map(|file_path| {
-            let file_path = file_path.clone();
-            let blobs_dir = Arc::clone(&blobs_dir);
-            let repo_workdir = Arc::clone(&repo_workdir);
-            let dirty_files = Arc::clone(&dirty_files);
-            let semaphore = Arc::clone(&semaphore);
-
-            async move {
-                // Acquire semaphore permit
-                let _permit = semaphore.acquire().await;
-
-                // Read file content - check dirty_files first, then filesystem
-                let content = if let Some(ref dirty_map) = *dirty_files {
-                    dirty_map.get(&file_path).cloned()
-                } else {
-                    None
-                }
-                .unwrap_or_else(|| {
-                    // Construct absolute path
-                    let abs_path = if std::path::Path::new(&file_path).is_absolute() {
-                        file_path.clone()
-                    } else {
-                        repo_workdir.join(&file_path).to_string_lossy().to_string()
-                    };
-                    // Read from filesystem
-                    std::fs::read_to_string(&abs_path).unwrap_or_default()
-                });
-
-                // Create SHA256 hash of the content
-                let mut hasher = Sha256::new();
-                hasher.update(content.as_bytes());
-                let sha = format!("{:x}", hasher.finalize());
-
-                // Ensure blobs directory exists
-                std::fs::create_dir_all(&*blobs_dir)?;
+    previous_checkpoints: &[Checkpoint],
+) -> Result<HashMap<String, (String, String)>, GitAiError> {
+    if base_commit == "initial" {
+        return Ok(HashMap::new());
+    }
+    let checkpointed: HashSet<&str> = previous_checkpoints
+        .iter()
+        .flat_map(|checkpoint| checkpoint.entries.iter().map(|entry| entry.file.as_str()))
+        .collect();
+    let new_files: Vec<String> = files
+        .iter()
+        .filter(|file| !checkpointed.contains(file.as_str()) && !Config::get().skips_snapshot(file))
+        .cloned()
+        .collect();
+    Ok(repo
+        .blob_ids_and_contents_at_paths(base_commit, &new_files)?
+        .into_iter()
+        .map(|(path, (oid, blob))| (path, (oid, String::from_utf8_lossy(&blob).to_string())))
+        .collect())
+}
 
-                // Write content to blob file
-                let blob_path = blobs_dir.join(&sha);
-                std::fs::write(blob_path, content)?;
+/// Files checkpoints read and hash at a time
+const MAX_CONCURRENT_FILE_READS: usize = 8;
 
-                Ok::<(String, String), GitAiError>((file_path, sha))
-            }
-        });
+/// Read and hash the current content of `files` on the blocking thread pool, so that
+/// reads and hashing overlap with each other and with what the caller does until it
+/// awaits the result. Yields `(path, content, sha)` in no particular order.
+fn spawn_file_reads(
+    working_log: &PersistedWorkingLog,
+    files: &[String],
+) -> smol::Task<Vec<(String, String, String)>> {
+    let working_log = Arc::new(working_log.clone());
+    let files = files.to_vec();
+    smol::spawn(async move {
+        let semaphore = Arc::new(smol::lock::Semaphore::new(MAX_CONCURRENT_FILE_READS));
+        let tasks: Vec<_> = files
+            .into_iter()
+            .map(|file_path| {
+                let working_log = Arc::clone(&working_log);
+                let semaphore = Arc::clone(&semaphore);
+                smol::spawn(async move {
+                    let _permit = semaphore.acquire().await;
+                    smol::unblock(move || {
+                        // Read file content - check dirty_files first, then filesystem
+                        let content = working_log
+                            .read_current_file_content(&file_path)
+                            .unwrap_or_default();
+                        let sha = content_sha256(&content);
+                        (file_path, content, sha)
+                    })
+                    .await
+                })
+            })
+            .collect();
+        futures::future::join_all(tasks).await
+    })
+}
 
-        // Collect results from all concurrent operations
-        let results: Vec<Result<(String, String), GitAiError>> =
-            stream::iter(futures).buffer_unordered(8).collect().await;
+/// Store a snapshot of each file read by [`spawn_file_reads`] that the working log
+/// doesn't have yet, delta-encoding and compressing them on the blocking thread pool.
+/// Returns the content hash of every file.
+fn save_current_file_states(
+    working_log: &PersistedWorkingLog,
+    file_reads: Vec<(String, String, String)>,
+    previous_checkpoints: &[Checkpoint],
+    base_blobs: &HashMap<String, (String, String)>,
+) -> Result<HashMap<String, String>, GitAiError> {
+    // Most recent snapshot of each path, used as the delta base for its next snapshot
+    let mut previous_blob_shas: HashMap<String, String> = HashMap::new();
+    for checkpoint in previous_checkpoints {
+        for entry in &checkpoint.entries {
+            previous_blob_shas.insert(entry.file.clone(), entry.blob_sha.clone());
+        }
+    }
 
-        // Convert results into HashMap
-        let mut file_content_hashes = HashMap::new();
-        for result in results {
-            let (file_path, content_hash) = result?;
-            file_content_hashes.insert(file_path, content_hash);
+    let mut file_content_hashes = HashMap::new();
+    let mut to_persist = Vec::new();
+    for (file_path, content, sha) in file_reads {
+        // Unchanged files reuse their existing blob, and skipped files are only hashed,
+        // to tell whether they changed
+        if !Config::get().skips_snapshot(&file_path) && !working_log.has_file_version(&sha) {
+            let previous = previous_blob_shas.remove(&file_path);
+            let base = match previous {
+                None => base_blobs.get(&file_path).cloned(),
+                Some(_) => None,
+            };
+            to_persist.push((content, previous, base));
         }
+        file_content_hashes.insert(file_path, sha);
+    }
 
-        Ok::<HashMap<String, String>, GitAiError>(file_content_hashes)
-    })?;
+    smol::block_on(async {
+        let semaphore = Arc::new(smol::lock::Semaphore::new(MAX_CONCURRENT_FILE_READS));
+        let working_log = Arc::new(working_log.clone());
+        let tasks: Vec<_> = to_persist
+            .into_iter()
+            .map(|(content, previous, base)| {
+                let working_log = Arc::clone(&working_log);
+                let semaphore = Arc::clone(&semaphore);
+                smol::spawn(async move {
+                    let _permit = semaphore.acquire().await;
+                    // Files without a snapshot yet are stored against their base commit blob
+                    smol::unblock(move || match base {
+                        Some((oid, base_content)) => working_log
+                            .persist_file_version_with_git_base(&content, &oid, &base_content),
+                        None => working_log
+                            .persist_file_version_with_base(&content, previous.as_deref()),
+                    })
+                    .await
+                })
+            })
+            .collect();
+        futures::future::join_all(tasks).await
+    })
+    .into_iter()
+    .collect::<Result<Vec<_>, GitAiError>>()?;
 
     Ok(file_content_hashes)
 }
@@ -758,7 +918,7 @@ fn get_checkpoint_entry_for_file(
     file_content_hash: String,
     author_id: Arc<String>,
     head_commit_sha: Arc<Option<String>>,
-    head_tree_id: Arc<Option<String>>,
+    base_blobs: Arc<HashMap<String, (String, String)>>,
     initial_attributions: Arc<HashMap<String, Vec<LineAttribution>>>,
     ts: u128,
 ) -> Result<Option<(WorkingLogEntry, FileLineStats)>, GitAiError> {
@@ -769,6 +929,26 @@ fn get_checkpoint_entry_for_file(
         .read_current_file_content(&file_path)
         .unwrap_or_default();
 
+    if Config::get().skips_snapshot(&file_path) {
+        let latest_blob_sha = previous_checkpoints.iter().rev().find_map(|checkpoint| {
+            checkpoint
+                .entries
+                .iter()
+                .find(|e| e.file == file_path)
+                .map(|entry| entry.blob_sha.as_str())
+        });
+        if latest_blob_sha == Some(file_content_hash.as_str()) {
+            return Ok(None);
+        }
+        return Ok(Some(make_coarse_entry_for_file(
+            &file_path,
+            &file_content_hash,
+            author_id.as_ref(),
+            &current_content,
+            ts,
+        )));
+    }
+
     // Try to get previous state from checkpoints first
     let from_checkpoint = previous_checkpoints.iter().rev().find_map(|checkpoint| {
         checkpoint
@@ -798,26 +978,10 @@ fn get_checkpoint_entry_for_file(
     } else {
         // File doesn't exist in any previous checkpoint - need to initialize from git + INITIAL
         // Get previous content from HEAD tree
-        let previous_content = if let Some(tree_id) = head_tree_id.as_ref().as_ref() {
-            let head_tree = repo.find_tree(tree_id.clone()).ok();
-            if let Some(tree) = head_tree {
-                match tree.get_path(std::path::Path::new(&file_path)) {
-                    Ok(entry) => {
-                        if let Ok(blob) = repo.find_blob(entry.id()) {
-                            let blob_content = blob.content().unwrap_or_default();
-                            String::from_utf8_lossy(&blob_content).to_string()
-                        } else {
-                            String::new()
-                        }
-                    }
-                    Err(_) => String::new(),
-                }
-            } else {
-                String::new()
-            }
-        } else {
-            String::new()
-        };
+        let previous_content = base_blobs
+            .get(&file_path)
+            .map(|(_, content)| content.clone())
+            .unwrap_or_default();
 
         // Skip if no changes, UNLESS we have INITIAL attributions for this file
         // (in which case we need to create an entry to record those attributions)
@@ -940,6 +1104,7 @@ fn get_checkpoint_entry_for_file(
     }
 
     let (entry, stats) = make_entry_for_file(
+        &repo,
         &file_path,
         &file_content_hash,
         author_id.as_ref(),
@@ -964,6 +1129,7 @@ async fn get_checkpoint_entries(
     files: &[String],
     file_content_hashes: &HashMap<String, String>,
     previous_checkpoints: &[Checkpoint],
+    base_blobs: &HashMap<String, (String, String)>,
     agent_run_result: Option<&AgentRunResult>,
     ts: u128,
 ) -> Result<(Vec<WorkingLogEntry>, Vec<FileLineStats>), GitAiError> {
@@ -1001,10 +1167,6 @@ async fn get_checkpoint_entries(
         .and_then(|h| h.target().ok())
         .and_then(|oid| repo.find_commit(oid).ok());
     let head_commit_sha = head_commit.as_ref().map(|c| c.id().to_string());
-    let head_tree_id = head_commit
-        .as_ref()
-        .and_then(|c| c.tree().ok())
-        .map(|t| t.id().to_string());
 
     const MAX_CONCURRENT: usize = 30;
 
@@ -1017,7 +1179,7 @@ async fn get_checkpoint_entries(
     // Move other repeated allocations outside the loop
     let author_id = Arc::new(author_id);
     let head_commit_sha = Arc::new(head_commit_sha);
-    let head_tree_id = Arc::new(head_tree_id);
+    let base_blobs = Arc::new(base_blobs.clone());
     let initial_attributions = Arc::new(initial_attributions);
 
     // Spawn tasks for each file
@@ -1031,7 +1193,7 @@ async fn get_checkpoint_entries(
         let previous_checkpoints = Arc::clone(&previous_checkpoints);
         let author_id = Arc::clone(&author_id);
         let head_commit_sha = Arc::clone(&head_commit_sha);
-        let head_tree_id = Arc::clone(&head_tree_id);
+        let base_blobs = Arc::clone(&base_blobs);
         let blob_sha = file_content_hashes
             .get(&file_path)
             .cloned()
@@ -1054,7 +1216,7 @@ async fn get_checkpoint_entries(
                     blob_sha,
                     author_id.clone(),
                     head_commit_sha.clone(),
-                    head_tree_id.clone(),
+                    base_blobs.clone(),
                     initial_attributions.clone(),
                     ts,
                 )
@@ -1107,7 +1269,9 @@ async fn get_checkpoint_entries(
     Ok((entries, file_stats))
 }
 
+#[allow(clippy::too_many_arguments)]
 fn make_entry_for_file(
+    repo: &Repository,
     file_path: &str,
     blob_sha: &str,
     author_id: &str,
@@ -1116,7 +1280,7 @@ fn make_entry_for_file(
     content: &str,
     ts: u128,
 ) -> Result<(WorkingLogEntry, FileLineStats), GitAiError> {
-    let tracker = AttributionTracker::new();
+    let tracker = AttributionTracker::new().with_diff_cache(repo.storage.diff_cache());
 
     let fill_start = Instant::now();
     let filled_in_prev_attributions = tracker.attribute_unattributed_ranges(
@@ -1179,6 +1343,39 @@ fn make_entry_for_file(
     Ok((entry, line_stats))
 }
 
+/// Entry of a file matching `snapshot_skip_patterns`: every line goes to the author of
+/// the checkpoint that last changed the file, without a snapshot or a diff
+fn make_coarse_entry_for_file(
+    file_path: &str,
+    blob_sha: &str,
+    author_id: &str,
+    content: &str,
+    ts: u128,
+) -> (WorkingLogEntry, FileLineStats) {
+    let attributions = if content.is_empty() {
+        Vec::new()
+    } else {
+        vec![Attribution::new(
+            0,
+            content.len(),
+            author_id.to_string(),
+            ts,
+        )]
+    };
+    let line_attributions =
+        crate::authorship::attribution_tracker::attributions_to_line_attributions(
+            &attributions,
+            content,
+        );
+    let entry = WorkingLogEntry::new(
+        file_path.to_string(),
+        blob_sha.to_string(),
+        attributions,
+        line_attributions,
+    );
+    (entry, FileLineStats::default())
+}
+
 /// Compute line statistics for a single file by diffing previous and current content
 fn compute_file_line_stats(previous_content: &str, current_content: &str) -> FileLineStats {
     let mut stats = FileLineStats::default();
@@ -1256,40 +1453,6 @@ fn is_text_file(working_log: &PersistedWorkingLog, path: &str) -> bool {
         .unwrap_or(false)
 }
 
-fn is_text_file_in_head(repo: &Repository, path: &str) -> bool {
-    // For deleted files, check if they were text files in HEAD
-    let head_commit = match repo
-        .head()
-        .ok()
-        .and_then(|h| h.target().ok())
-        .and_then(|oid| repo.find_commit(oid).ok())
-    {
-        Some(commit) => commit,
-        None => return false,
-    };
-
-    let head_tree = match head_commit.tree().ok() {
-        Some(tree) => tree,
-        None => return false,
-    };
-
-    match head_tree.get_path(std::path::Path::new(path)) {
-        Ok(entry) => {
-            if let Ok(blob) = repo.find_blob(entry.id()) {
-                // Consider a file text if it contains no null bytes
-                let blob_content = match blob.content() {
-                    Ok(content) => content,
-                    Err(_) => return false,
-                };
-                !blob_content.contains(&0)
-            } else {
-                false
-            }
-        }
-        Err(_) => false,
-    }
-}
-
 /// Upsert a checkpoint prompt to the internal database
 fn upsert_checkpoint_prompt_to_db(
     checkpoint: &Checkpoint,
@@ -703,12 +703,14 @@ impl AgentCheckpointPreset for CursorPreset {
             })?
             .to_string();
 
-        // Extract model from hook input (Cursor provides this directly)
-        let model = hook_data
+        // Extract model from hook input (Cursor provides this directly). Older Cursor
+        // builds and "Auto" mode leave it out; the composer's bubbles are used instead.
+        let hook_model = hook_data
             .get("model")
             .and_then(|v| v.as_str())
-            .map(|s| s.to_string())
-            .unwrap_or_else(|| "unknown".to_string());
+            .filter(|s| Self::is_known_model(s))
+            .map(|s| s.to_string());
+        let model = hook_model.clone().unwrap_or_else(|| "unknown".to_string());
 
         // Validate hook_event_name
         if hook_event_name != "beforeSubmitPrompt" && hook_event_name != "afterFileEdit" {
@@ -767,44 +769,50 @@ impl AgentCheckpointPreset for CursorPreset {
 
         // Locate Cursor storage
         let global_db = Self::cursor_global_database_path()?;
-        if !global_db.exists() {
-            return Err(GitAiError::PresetError(format!(
-                "Cursor global state database not found at {:?}. \
-                Make sure Cursor is installed and has been used at least once. \
-                Expected location: {:?}",
-                global_db, global_db,
-            )));
-        }
 
-        // Fetch the composer data and extract transcript (model is now from hook input, not DB)
-        let transcript = match Self::fetch_composer_payload(&global_db, &conversation_id) {
-            Ok(payload) => Self::transcript_data_from_composer_payload(
-                &payload,
-                &global_db,
-                &conversation_id,
-            )?
-            .map(|(transcript, _db_model)| transcript)
-            .unwrap_or_else(|| {
-                // Return empty transcript as default
-                // There's a race condition causing new threads to sometimes not show up.
-                // We refresh and grab all the messages in post-commit so we're ok with returning an empty (placeholder) transcript here and not throwing
-                eprintln!(
-                    "[Warning] Could not extract transcript from Cursor composer. Retrying at commit."
-                );
-                AiTranscript::new()
-            }),
-            Err(GitAiError::PresetError(msg))
-                if msg == "No conversation data found in database" =>
-            {
-                // Gracefully continue when the conversation hasn't been written yet due to Cursor race conditions
-                eprintln!(
-                    "[Warning] No conversation data found in Cursor DB for this thread. Proceeding and will re-sync at commit."
-                );
-                AiTranscript::new()
+        // Fetch the composer data and extract transcript. The edit itself came from the
+        // agent, so it is attributed even when the local state can't be read yet.
+        let (transcript, db_model) = if !global_db.exists() {
+            eprintln!(
+                "[Warning] Cursor global state database not found at {:?}. Proceeding and will re-sync at commit.",
+                global_db
+            );
+            (AiTranscript::new(), None)
+        } else {
+            match Self::fetch_composer_payload(&global_db, &conversation_id) {
+                Ok(payload) => Self::transcript_data_from_composer_payload(
+                    &payload,
+                    &global_db,
+                    &conversation_id,
+                )?
+                .map(|(transcript, db_model)| (transcript, Some(db_model)))
+                .unwrap_or_else(|| {
+                    // Return empty transcript as default
+                    // There's a race condition causing new threads to sometimes not show up.
+                    // We refresh and grab all the messages in post-commit so we're ok with returning an empty (placeholder) transcript here and not throwing
+                    eprintln!(
+                        "[Warning] Could not extract transcript from Cursor composer. Retrying at commit."
+                    );
+                    (AiTranscript::new(), None)
+                }),
+                Err(GitAiError::PresetError(msg))
+                    if msg == "No conversation data found in database" =>
+                {
+                    // Gracefully continue when the conversation hasn't been written yet due to Cursor race conditions
+                    eprintln!(
+                        "[Warning] No conversation data found in Cursor DB for this thread. Proceeding and will re-sync at commit."
+                    );
+                    (AiTranscript::new(), None)
+                }
+                Err(e) => return Err(e),
             }
-            Err(e) => return Err(e),
         };
 
+        // The hook's model wins; the database only fills in when the hook omitted it
+        let model = hook_model
+            .or(db_model.filter(|m| Self::is_known_model(m)))
+            .unwrap_or(model);
+
         let edited_filepaths = if !file_path.is_empty() {
             Some(vec![file_path.to_string()])
         } else {
@@ -843,6 +851,14 @@ impl AgentCheckpointPreset for CursorPreset {
 }
 
 impl CursorPreset {
+    /// Whether a model name reported by Cursor identifies an actual model.
+    pub fn is_known_model(model: &str) -> bool {
+        let model = model.trim();
+        !model.is_empty()
+            && !model.eq_ignore_ascii_case("unknown")
+            && !model.eq_ignore_ascii_case("default")
+    }
+
     /// Normalize Windows paths that Cursor sends in Unix-style format.
     ///
     /// On Windows, Cursor sometimes sends paths like `/c:/Users/...` instead of `C:\Users\...`.
@@ -0,0 +1,381 @@
+//! Agents declared by the user under `custom_agents` in the git-ai config, so in-house
+//! tools without a preset still get attributed to themselves, and the built-in agents
+//! recognizable from the environment they run commands in.
+//!
+//! A custom agent is checkpointed with `git-ai checkpoint <name> [files...]`, or by a
+//! plain `git-ai checkpoint` run from a process where one of its `detect_env`
+//! variables is set. Agent ids recorded under its name by any other integration
+//! (`agent-v1`, `wrap`, the daemon) pick up its canonical name and default model.
+//!
+//! With the `agent_detection` feature flag, a plain `git-ai checkpoint` that matches no
+//! custom agent is also checked against the marker variables and process names of
+//! known agents before falling back to a human checkpoint.
+//!
+//! A custom agent can also serve as a machine identity: with
+//! `GIT_AI_MACHINE_IDENTITY=<name>` set, as on a CI runner that commits as a bot,
+//! everything that would have been checkpointed as human, the pre-commit
+//! checkpoint included, is attributed to that agent instead of to whoever's
+//! credentials are on the machine.
+
+use crate::authorship::working_log::AgentId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Names the custom agent a machine's unattributed changes belong to
+pub const MACHINE_IDENTITY_ENV: &str = "GIT_AI_MACHINE_IDENTITY";
+
+/// Parent processes inspected when looking for a known agent
+const MAX_ANCESTORS: usize = 16;
+
+/// How a built-in agent shows up in the environment of the commands it runs.
+struct KnownAgent {
+    tool: &'static str,
+    /// Variables set for the agent's commands; a trailing `*` matches a prefix
+    env_markers: &'static [&'static str],
+    model_env: &'static [&'static str],
+    session_env: &'static [&'static str],
+    process_names: &'static [&'static str],
+}
+
+const KNOWN_AGENTS: &[KnownAgent] = &[
+    KnownAgent {
+        tool: "claude",
+        env_markers: &["CLAUDECODE"],
+        model_env: &["ANTHROPIC_MODEL"],
+        session_env: &["CLAUDE_CODE_SESSION_ID"],
+        process_names: &["claude"],
+    },
+    KnownAgent {
+        tool: "cursor",
+        env_markers: &["CURSOR_TRACE_ID", "CURSOR_AGENT"],
+        model_env: &[],
+        session_env: &["CURSOR_TRACE_ID"],
+        process_names: &["cursor", "cursor-agent"],
+    },
+    KnownAgent {
+        tool: "aider",
+        env_markers: &["AIDER_*"],
+        model_env: &["AIDER_MODEL"],
+        session_env: &[],
+        process_names: &["aider"],
+    },
+    KnownAgent {
+        tool: "codex",
+        env_markers: &["CODEX_SANDBOX", "CODEX_SANDBOX_NETWORK_DISABLED"],
+        model_env: &[],
+        session_env: &[],
+        process_names: &["codex"],
+    },
+    KnownAgent {
+        tool: "gemini",
+        env_markers: &["GEMINI_CLI"],
+        model_env: &["GEMINI_MODEL"],
+        session_env: &[],
+        process_names: &["gemini"],
+    },
+    KnownAgent {
+        tool: "opencode",
+        env_markers: &["OPENCODE"],
+        model_env: &[],
+        session_env: &[],
+        process_names: &["opencode"],
+    },
+    KnownAgent {
+        tool: "droid",
+        env_markers: &[],
+        model_env: &[],
+        session_env: &[],
+        process_names: &["droid"],
+    },
+];
+
+/// An agent inferred from the environment of a checkpoint without a preset.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct DetectedAgent {
+    pub tool: String,
+    pub model: Option<String>,
+    pub session_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomAgent {
+    /// Tool name recorded in agent ids
+    pub name: String,
+    /// Name shown in `git-ai status`; defaults to `name`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    /// Environment variables whose presence means the agent is driving the checkpoint
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub detect_env: Vec<String>,
+    /// Model recorded when the integration doesn't report one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_model: Option<String>,
+}
+
+pub fn find_agent<'a>(agents: &'a [CustomAgent], tool: &str) -> Option<&'a CustomAgent> {
+    agents
+        .iter()
+        .find(|agent| agent.name.eq_ignore_ascii_case(tool))
+}
+
+/// First agent with one of its `detect_env` variables set to a non-empty value.
+pub fn detect_agent(
+    agents: &[CustomAgent],
+    env: impl Fn(&str) -> Option<String>,
+) -> Option<&CustomAgent> {
+    agents.iter().find(|agent| {
+        agent
+            .detect_env
+            .iter()
+            .any(|var| env(var).is_some_and(|value| !value.is_empty()))
+    })
+}
+
+/// The custom agent `GIT_AI_MACHINE_IDENTITY` names, per `env`; a name that isn't
+/// registered is ignored with a warning.
+pub fn machine_identity(
+    agents: &[CustomAgent],
+    env: impl Fn(&str) -> Option<String>,
+) -> Option<&CustomAgent> {
+    let name = env(MACHINE_IDENTITY_ENV).filter(|name| !name.trim().is_empty())?;
+    let agent = find_agent(agents, name.trim());
+    if agent.is_none() {
+        eprintln!(
+            "Warning: Ignoring {}: no custom_agents entry is named '{}'",
+            MACHINE_IDENTITY_ENV, name
+        );
+    }
+    agent
+}
+
+/// Canonicalize the tool name of `agent_id` and fill in the agent's default model.
+pub fn resolve_agent_id(agent_id: &mut AgentId, agents: &[CustomAgent]) {
+    let Some(agent) = find_agent(agents, &agent_id.tool) else {
+        return;
+    };
+    agent_id.tool = agent.name.clone();
+    if let Some(default_model) = &agent.default_model
+        && (agent_id.model.is_empty() || agent_id.model == "unknown")
+    {
+        agent_id.model = default_model.clone();
+    }
+}
+
+/// Display name of `tool`, if it is a custom agent that declares one.
+pub fn display_name<'a>(agents: &'a [CustomAgent], tool: &str) -> Option<&'a str> {
+    find_agent(agents, tool).and_then(|agent| agent.display_name.as_deref())
+}
+
+/// Known agent whose markers are set in `vars`, or failing that whose process is
+/// among `ancestors` (nearest parent first).
+pub fn detect_known_agent(
+    vars: &HashMap<String, String>,
+    ancestors: &[String],
+) -> Option<DetectedAgent> {
+    let is_set = |name: &str| vars.get(name).is_some_and(|value| !value.is_empty());
+    let has_marker = |marker: &str| match marker.strip_suffix('*') {
+        Some(prefix) => vars
+            .iter()
+            .any(|(name, value)| name.starts_with(prefix) && !value.is_empty()),
+        None => is_set(marker),
+    };
+    let first_set = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| vars.get(*name).filter(|value| !value.is_empty()).cloned())
+    };
+
+    let agent = KNOWN_AGENTS
+        .iter()
+        .find(|agent| agent.env_markers.iter().any(|marker| has_marker(marker)))
+        .or_else(|| {
+            ancestors.iter().find_map(|process| {
+                KNOWN_AGENTS.iter().find(|agent| {
+                    agent
+                        .process_names
+                        .iter()
+                        .any(|name| process.eq_ignore_ascii_case(name))
+                })
+            })
+        })?;
+    Some(DetectedAgent {
+        tool: agent.tool.to_string(),
+        model: first_set(agent.model_env),
+        session_id: first_set(agent.session_env),
+    })
+}
+
+/// Names of the parent processes of this one, nearest first.
+#[cfg(unix)]
+pub fn process_ancestry() -> Vec<String> {
+    let mut names = Vec::new();
+    let mut pid = std::os::unix::process::parent_id();
+    while pid > 1 && names.len() < MAX_ANCESTORS {
+        let Some((name, parent)) = process_info(pid) else {
+            break;
+        };
+        names.push(name);
+        pid = parent;
+    }
+    names
+}
+
+#[cfg(not(unix))]
+pub fn process_ancestry() -> Vec<String> {
+    Vec::new()
+}
+
+/// Name and parent pid of `pid`, from procfs where available and `ps` elsewhere.
+#[cfg(unix)]
+fn process_info(pid: u32) -> Option<(String, u32)> {
+    if let Ok(stat) = std::fs::read_to_string(format!("/proc/{}/stat", pid)) {
+        // "<pid> (<comm>) <state> <ppid> ..."; comm may itself contain parentheses
+        let open = stat.find('(')?;
+        let close = stat.rfind(')')?;
+        let name = stat[open + 1..close].to_string();
+        let parent = stat[close + 1..].split_whitespace().nth(1)?.parse().ok()?;
+        return Some((name, parent));
+    }
+
+    let output = std::process::Command::new("ps")
+        .args(["-o", "ppid=", "-o", "comm=", "-p", &pid.to_string()])
+        .output()
+        .ok()?;
+    let output = String::from_utf8_lossy(&output.stdout);
+    let (parent, command) = output.trim().split_once(char::is_whitespace)?;
+    let name = command
+        .trim()
+        .rsplit('/')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    Some((name, parent.trim().parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn agents() -> Vec<CustomAgent> {
+        vec![
+            CustomAgent {
+                name: "acme-bot".to_string(),
+                display_name: Some("Acme Bot".to_string()),
+                detect_env: vec!["ACME_BOT_SESSION".to_string()],
+                default_model: Some("acme-large".to_string()),
+            },
+            CustomAgent {
+                name: "helper".to_string(),
+                ..Default::default()
+            },
+        ]
+    }
+
+    #[test]
+    fn test_resolve_agent_id() {
+        let agents = agents();
+        let mut agent_id = AgentId {
+            tool: "Acme-Bot".to_string(),
+            id: "s1".to_string(),
+            model: "unknown".to_string(),
+        };
+        resolve_agent_id(&mut agent_id, &agents);
+        assert_eq!(agent_id.tool, "acme-bot");
+        assert_eq!(agent_id.model, "acme-large");
+
+        // A reported model wins over the default
+        agent_id.model = "acme-small".to_string();
+        resolve_agent_id(&mut agent_id, &agents);
+        assert_eq!(agent_id.model, "acme-small");
+
+        let mut other = AgentId {
+            tool: "claude".to_string(),
+            id: "s2".to_string(),
+            model: "unknown".to_string(),
+        };
+        resolve_agent_id(&mut other, &agents);
+        assert_eq!(other.tool, "claude");
+        assert_eq!(other.model, "unknown");
+    }
+
+    #[test]
+    fn test_detect_agent() {
+        let agents = agents();
+        let detected = detect_agent(&agents, |var| {
+            (var == "ACME_BOT_SESSION").then(|| "abc".to_string())
+        });
+        assert_eq!(detected.map(|a| a.name.as_str()), Some("acme-bot"));
+        assert!(detect_agent(&agents, |_| Some(String::new())).is_none());
+        assert!(detect_agent(&agents, |_| None).is_none());
+        assert_eq!(display_name(&agents, "acme-bot"), Some("Acme Bot"));
+        assert_eq!(display_name(&agents, "helper"), None);
+    }
+
+    #[test]
+    fn test_machine_identity() {
+        let agents = agents();
+        let env = |value: &'static str| {
+            move |var: &str| (var == MACHINE_IDENTITY_ENV).then(|| value.to_string())
+        };
+        assert_eq!(
+            machine_identity(&agents, env("Helper")).map(|a| a.name.as_str()),
+            Some("helper")
+        );
+        assert!(machine_identity(&agents, env("unregistered-bot")).is_none());
+        assert!(machine_identity(&agents, env("")).is_none());
+        assert!(machine_identity(&agents, |_| None).is_none());
+    }
+
+    fn vars(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_detect_known_agent() {
+        let detected = detect_known_agent(
+            &vars(&[
+                ("CLAUDECODE", "1"),
+                ("ANTHROPIC_MODEL", "claude-sonnet-4-5"),
+                ("CLAUDE_CODE_SESSION_ID", "abc"),
+            ]),
+            &[],
+        );
+        assert_eq!(
+            detected,
+            Some(DetectedAgent {
+                tool: "claude".to_string(),
+                model: Some("claude-sonnet-4-5".to_string()),
+                session_id: Some("abc".to_string()),
+            })
+        );
+
+        let detected = detect_known_agent(&vars(&[("AIDER_MODEL", "gpt-4o")]), &[]).unwrap();
+        assert_eq!(detected.tool, "aider");
+        assert_eq!(detected.model.as_deref(), Some("gpt-4o"));
+
+        // Process names are only consulted without a marker, nearest parent first
+        let ancestors = [
+            "bash".to_string(),
+            "codex".to_string(),
+            "claude".to_string(),
+        ];
+        let detected = detect_known_agent(&vars(&[]), &ancestors).unwrap();
+        assert_eq!(detected.tool, "codex");
+        assert_eq!(detected.model, None);
+
+        assert_eq!(
+            detect_known_agent(&vars(&[("CLAUDECODE", "")]), &["zsh".to_string()]),
+            None
+        );
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_process_ancestry() {
+        // The test harness always has a parent
+        assert!(!process_ancestry().is_empty());
+    }
+}
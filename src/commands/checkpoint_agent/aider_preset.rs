@@ -0,0 +1,378 @@
+use crate::{
+    authorship::{
+        transcript::{AiTranscript, Message},
+        working_log::{AgentId, CheckpointKind},
+    },
+    commands::checkpoint_agent::agent_presets::{
+        AgentCheckpointFlags, AgentCheckpointPreset, AgentRunResult,
+    },
+    error::GitAiError,
+    git::repository::Repository,
+};
+use serde::Deserialize;
+use std::path::Path;
+
+/// Files aider writes to the root of the repo it is working in
+pub const AIDER_CHAT_HISTORY_FILE: &str = ".aider.chat.history.md";
+pub const AIDER_INPUT_HISTORY_FILE: &str = ".aider.input.history";
+
+/// Suffix aider appends to the author name of commits it makes (`--attribute-author`)
+const AIDER_AUTHOR_SUFFIX: &str = " (aider)";
+/// Prefix of aider commit messages with `--attribute-commit-message-author`
+const AIDER_MESSAGE_PREFIX: &str = "aider: ";
+
+/// Checkpoint for files aider just edited.
+///
+/// Aider has no hook API; its `lint-cmd` runs after every edit with the edited file
+/// names appended, which `git-ai checkpoint aider <files>` turns into an AI checkpoint.
+pub struct AiderPreset;
+
+#[derive(Debug, Deserialize)]
+struct AiderHookInput {
+    cwd: String,
+    #[serde(default)]
+    edited_filepaths: Vec<String>,
+}
+
+impl AgentCheckpointPreset for AiderPreset {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        let hook_input_json = flags.hook_input.ok_or_else(|| {
+            GitAiError::PresetError("hook_input is required for aider preset".to_string())
+        })?;
+        let hook_input: AiderHookInput = serde_json::from_str(&hook_input_json)
+            .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
+
+        let session = AiderSession::load(Path::new(&hook_input.cwd));
+        let edited_filepaths =
+            (!hook_input.edited_filepaths.is_empty()).then_some(hook_input.edited_filepaths);
+
+        Ok(AgentRunResult {
+            agent_id: session.agent_id(None),
+            agent_metadata: None,
+            checkpoint_kind: CheckpointKind::AiAgent,
+            transcript: Some(session.transcript),
+            repo_working_dir: Some(hook_input.cwd),
+            edited_filepaths,
+            will_edit_filepaths: None,
+            dirty_files: None,
+        })
+    }
+}
+
+/// What an aider commit says about itself.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AiderCommit {
+    /// Model named in the `Co-authored-by: aider (<model>)` trailer
+    pub model: Option<String>,
+}
+
+/// Recognize a commit aider made for its own edits.
+///
+/// Aider marks them with ` (aider)` after the author name, an `aider: ` message prefix,
+/// or a `Co-authored-by: aider (<model>) <...>` trailer, depending on its
+/// `--attribute-*` settings. Commits of the user's dirty files only get the committer
+/// name suffix, so the committer is deliberately not looked at.
+pub fn detect_aider_commit(author_name: Option<&str>, message: &str) -> Option<AiderCommit> {
+    let model = message.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        if !key.trim().eq_ignore_ascii_case("co-authored-by") {
+            return None;
+        }
+        let rest = value.trim().strip_prefix("aider (")?;
+        let model = rest.split_once(')')?.0.trim();
+        Some(model.to_string())
+    });
+    if model.is_some() {
+        return Some(AiderCommit { model });
+    }
+
+    let by_author = author_name.is_some_and(|name| name.trim_end().ends_with(AIDER_AUTHOR_SUFFIX));
+    let by_message = message.starts_with(AIDER_MESSAGE_PREFIX);
+    (by_author || by_message).then_some(AiderCommit { model: None })
+}
+
+/// The AI checkpoint to take before a `git commit` that aider runs for its own edits.
+///
+/// Aider commits straight after applying an edit, so nothing else gets a chance to
+/// checkpoint it. It passes the message with `-m` and the edited files after `--`.
+pub fn agent_run_for_commit(
+    repo: &Repository,
+    author: &str,
+    command_args: &[String],
+) -> Option<AgentRunResult> {
+    let author_name = author.split(" <").next();
+    let message = commit_message_from_args(command_args);
+    let commit = detect_aider_commit(author_name, &message)?;
+
+    let workdir = repo.workdir().ok()?;
+    let session = AiderSession::load(&workdir);
+    let edited_filepaths: Vec<String> = command_args
+        .iter()
+        .skip_while(|arg| arg.as_str() != "--")
+        .skip(1)
+        .cloned()
+        .collect();
+
+    Some(AgentRunResult {
+        agent_id: session.agent_id(commit.model.as_deref()),
+        agent_metadata: None,
+        checkpoint_kind: CheckpointKind::AiAgent,
+        transcript: Some(session.transcript),
+        repo_working_dir: Some(workdir.to_string_lossy().to_string()),
+        edited_filepaths: (!edited_filepaths.is_empty()).then_some(edited_filepaths),
+        will_edit_filepaths: None,
+        dirty_files: None,
+    })
+}
+
+/// The message given with `-m`/`--message`; multiple values become paragraphs.
+fn commit_message_from_args(args: &[String]) -> String {
+    let mut paragraphs = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].as_str();
+        if arg == "--" {
+            break;
+        }
+        if (arg == "-m" || arg == "--message") && i + 1 < args.len() {
+            paragraphs.push(args[i + 1].clone());
+            i += 2;
+            continue;
+        }
+        if let Some(value) = arg.strip_prefix("--message=") {
+            paragraphs.push(value.to_string());
+        } else if let Some(value) = arg.strip_prefix("-m")
+            && !value.is_empty()
+        {
+            paragraphs.push(value.to_string());
+        }
+        i += 1;
+    }
+    paragraphs.join("\n\n")
+}
+
+/// The latest aider session recorded in a repo's history files.
+#[derive(Debug, Default)]
+pub struct AiderSession {
+    /// Timestamp from `# aider chat started at ...`, used as the session id
+    pub started_at: Option<String>,
+    pub model: Option<String>,
+    pub transcript: AiTranscript,
+}
+
+impl AiderSession {
+    /// Read the latest session from `.aider.chat.history.md`, falling back to the last
+    /// prompts in `.aider.input.history` when aider was run without a chat history file.
+    pub fn load(repo_root: &Path) -> Self {
+        if let Ok(history) = std::fs::read_to_string(repo_root.join(AIDER_CHAT_HISTORY_FILE)) {
+            return Self::from_chat_history(&history);
+        }
+        match std::fs::read_to_string(repo_root.join(AIDER_INPUT_HISTORY_FILE)) {
+            Ok(input) => Self::from_input_history(&input),
+            Err(_) => Self::default(),
+        }
+    }
+
+    /// Agent id for this session; a model from the commit trailer wins over the one
+    /// in the chat history.
+    pub fn agent_id(&self, commit_model: Option<&str>) -> AgentId {
+        AgentId {
+            tool: "aider".to_string(),
+            id: self
+                .started_at
+                .clone()
+                .unwrap_or_else(|| "aider-session".to_string()),
+            model: commit_model
+                .map(str::to_string)
+                .or_else(|| self.model.clone())
+                .unwrap_or_else(|| "unknown".to_string()),
+        }
+    }
+
+    /// Parse the last session of an `.aider.chat.history.md` file.
+    ///
+    /// User prompts are the `#### ` lines, aider's own output the `> ` lines, and
+    /// everything else is the model's reply.
+    pub fn from_chat_history(history: &str) -> Self {
+        let session_start = history
+            .rfind("# aider chat started at ")
+            .map(|idx| &history[idx..])
+            .unwrap_or(history);
+
+        let mut session = AiderSession::default();
+        let mut user = Vec::new();
+        let mut assistant = Vec::new();
+        for line in session_start.lines() {
+            if let Some(started) = line.strip_prefix("# aider chat started at ") {
+                session.started_at = Some(started.trim().to_string());
+            } else if let Some(prompt) = line.strip_prefix("####") {
+                flush_message(&mut session.transcript, &mut assistant, false);
+                user.push(prompt.strip_prefix(' ').unwrap_or(prompt).to_string());
+            } else if let Some(note) = line.strip_prefix('>') {
+                flush_message(&mut session.transcript, &mut user, true);
+                flush_message(&mut session.transcript, &mut assistant, false);
+                let note = note.trim();
+                let model = ["Main model: ", "Model: "]
+                    .iter()
+                    .find_map(|prefix| note.strip_prefix(prefix));
+                if let Some(model) = model {
+                    let name = model.split(" with ").next().unwrap_or(model).trim();
+                    session.model = Some(name.to_string());
+                }
+            } else {
+                flush_message(&mut session.transcript, &mut user, true);
+                assistant.push(line.to_string());
+            }
+        }
+        flush_message(&mut session.transcript, &mut user, true);
+        flush_message(&mut session.transcript, &mut assistant, false);
+        session
+    }
+
+    /// Parse the prompts after the last timestamp of an `.aider.input.history` file.
+    ///
+    /// Entries are a `# <timestamp>` line followed by `+`-prefixed prompt lines.
+    pub fn from_input_history(input: &str) -> Self {
+        let mut session = AiderSession::default();
+        let mut entries: Vec<(String, Vec<String>)> = Vec::new();
+        for line in input.lines() {
+            if let Some(timestamp) = line.strip_prefix("# ") {
+                entries.push((timestamp.trim().to_string(), Vec::new()));
+            } else if let Some(text) = line.strip_prefix('+')
+                && let Some((_, lines)) = entries.last_mut()
+            {
+                lines.push(text.to_string());
+            }
+        }
+        if let Some((timestamp, _)) = entries.first() {
+            session.started_at = Some(timestamp.clone());
+        }
+        for (timestamp, lines) in entries {
+            let text = lines.join("\n");
+            if !text.trim().is_empty() && !text.starts_with('/') {
+                session
+                    .transcript
+                    .add_message(Message::user(text, Some(timestamp)));
+            }
+        }
+        session
+    }
+}
+
+fn flush_message(transcript: &mut AiTranscript, lines: &mut Vec<String>, is_user: bool) {
+    let text = lines.join("\n").trim().to_string();
+    lines.clear();
+    if text.is_empty() {
+        return;
+    }
+    transcript.add_message(if is_user {
+        Message::user(text, None)
+    } else {
+        Message::assistant(text, None)
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_aider_commit() {
+        let trailer = "feat: Add greeting\n\nCo-authored-by: aider (openrouter/anthropic/claude-sonnet-4) <aider@aider.chat>\n";
+        assert_eq!(
+            detect_aider_commit(Some("Jane Doe"), trailer),
+            Some(AiderCommit {
+                model: Some("openrouter/anthropic/claude-sonnet-4".to_string())
+            })
+        );
+        assert_eq!(
+            detect_aider_commit(Some("Jane Doe (aider)"), "feat: Add greeting"),
+            Some(AiderCommit { model: None })
+        );
+        assert!(detect_aider_commit(None, "aider: feat: Add greeting").is_some());
+        assert!(detect_aider_commit(Some("Jane Doe"), "Fix aider config").is_none());
+    }
+
+    #[test]
+    fn test_commit_message_from_args() {
+        let args: Vec<String> = [
+            "-m",
+            "feat: Add greeting",
+            "--no-verify",
+            "-mBody",
+            "--",
+            "-m",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        assert_eq!(
+            commit_message_from_args(&args),
+            "feat: Add greeting\n\nBody"
+        );
+    }
+
+    #[test]
+    fn test_parse_aider_chat_history() {
+        let history = "\
+# aider chat started at 2025-01-01 09:00:00
+
+> Model: gpt-4o with diff edit format
+
+#### old session prompt
+
+# aider chat started at 2025-03-04 10:11:12
+
+> /usr/local/bin/aider
+> Main model: claude-3-5-sonnet-20241022 with diff edit format, infinite output
+> Git repo: .git with 3 files
+
+#### add a greet function
+#### and call it from main
+
+Here is the change:
+
+greet.py
+```python
+def greet():
+    print(\"hi\")
+```
+
+> Applied edit to greet.py
+";
+        let session = AiderSession::from_chat_history(history);
+        assert_eq!(session.started_at.as_deref(), Some("2025-03-04 10:11:12"));
+        assert_eq!(session.model.as_deref(), Some("claude-3-5-sonnet-20241022"));
+        let messages = session.transcript.messages();
+        assert_eq!(messages.len(), 2);
+        assert_eq!(
+            messages[0],
+            Message::user(
+                "add a greet function\nand call it from main".to_string(),
+                None
+            )
+        );
+        assert!(
+            matches!(&messages[1], Message::Assistant { text, .. } if text.contains("def greet()"))
+        );
+
+        let agent_id = session.agent_id(Some("gpt-4.1"));
+        assert_eq!(agent_id.tool, "aider");
+        assert_eq!(agent_id.model, "gpt-4.1");
+    }
+
+    #[test]
+    fn test_parse_aider_input_history() {
+        let input = "\n# 2025-03-04 10:11:12.123456\n+add a greet function\n\n# 2025-03-04 10:12:00.000000\n+/run pytest\n\n# 2025-03-04 10:13:00.000000\n+now add tests\n+for greet\n";
+        let session = AiderSession::from_input_history(input);
+        assert_eq!(
+            session.started_at.as_deref(),
+            Some("2025-03-04 10:11:12.123456")
+        );
+        let messages = session.transcript.messages();
+        assert_eq!(messages.len(), 2);
+        assert!(
+            matches!(&messages[1], Message::User { text, .. } if text == "now add tests\nfor greet")
+        );
+    }
+}
@@ -0,0 +1,140 @@
+//! Shared support for CLI agents whose only integration points are an end-of-turn
+//! notification and the session logs they write (e.g. Codex CLI).
+//!
+//! Such agents can't tell us which files they are about to touch, so they checkpoint
+//! once per turn: everything that changed since the previous checkpoint is attributed
+//! to them, with the transcript read back from their session log.
+
+use crate::authorship::transcript::AiTranscript;
+use crate::authorship::working_log::{AgentId, CheckpointKind};
+use crate::commands::checkpoint_agent::agent_presets::AgentRunResult;
+use crate::error::GitAiError;
+use crate::git::find_repository_in_path;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Upper bound on session logs inspected when looking for the one of a directory
+const MAX_SESSIONS_SCANNED: usize = 200;
+
+/// A session read back from an agent's log.
+#[derive(Debug, Clone)]
+pub struct CliAgentSession {
+    pub session_id: String,
+    /// Directory the agent was started in
+    pub cwd: Option<PathBuf>,
+    pub model: Option<String>,
+    pub transcript: AiTranscript,
+    pub path: PathBuf,
+}
+
+pub trait SessionLogAgent {
+    /// Tool name recorded in the agent id
+    fn tool(&self) -> &str;
+
+    /// Root directory holding the agent's session logs
+    fn sessions_dir(&self) -> PathBuf;
+
+    /// Parse one session log
+    fn read_session(&self, path: &Path) -> Result<CliAgentSession, GitAiError>;
+}
+
+/// Most recent session of `agent` started in `cwd`, or the one with `session_id`.
+///
+/// Logs are searched newest first by file name and modification time, which both
+/// sort chronologically for the agents this is used with.
+pub fn find_session(
+    agent: &dyn SessionLogAgent,
+    cwd: &Path,
+    session_id: Option<&str>,
+) -> Option<CliAgentSession> {
+    let mut logs = Vec::new();
+    collect_logs(&agent.sessions_dir(), &mut logs);
+    logs.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| b.0.cmp(&a.0)));
+
+    let cwd = canonical(cwd);
+    logs.into_iter()
+        .take(MAX_SESSIONS_SCANNED)
+        .filter(|(path, _)| session_id.is_none_or(|id| path.to_string_lossy().contains(id)))
+        .filter_map(|(path, _)| agent.read_session(&path).ok())
+        .find(|session| {
+            session_id.is_some_and(|id| session.session_id == id)
+                || session
+                    .cwd
+                    .as_deref()
+                    .is_some_and(|session_cwd| canonical(session_cwd) == cwd)
+        })
+}
+
+/// End-of-turn AI checkpoint for `session`.
+///
+/// When the session log could not be found the checkpoint is still taken, so the
+/// agent's edits don't end up looking human.
+pub fn end_of_turn_run(
+    agent: &dyn SessionLogAgent,
+    session: Option<CliAgentSession>,
+    cwd: &Path,
+) -> AgentRunResult {
+    let (id, model, transcript, metadata) = match session {
+        Some(session) => (
+            session.session_id,
+            session.model,
+            session.transcript,
+            Some(HashMap::from([(
+                "session_path".to_string(),
+                session.path.to_string_lossy().to_string(),
+            )])),
+        ),
+        None => (
+            format!("{}-session", agent.tool()),
+            None,
+            AiTranscript::new(),
+            None,
+        ),
+    };
+
+    AgentRunResult {
+        agent_id: AgentId {
+            tool: agent.tool().to_string(),
+            id,
+            model: model.unwrap_or_else(|| "unknown".to_string()),
+        },
+        agent_metadata: metadata,
+        checkpoint_kind: CheckpointKind::AiAgent,
+        transcript: Some(transcript),
+        repo_working_dir: Some(cwd.to_string_lossy().to_string()),
+        edited_filepaths: changed_files(cwd),
+        will_edit_filepaths: None,
+        dirty_files: None,
+    }
+}
+
+/// Changed and untracked files of the repo containing `cwd`, relative to its root.
+fn changed_files(cwd: &Path) -> Option<Vec<String>> {
+    let repo = find_repository_in_path(&cwd.to_string_lossy()).ok()?;
+    let mut files: Vec<String> = repo.get_worktree_filenames().ok()?.into_iter().collect();
+    files.sort();
+    Some(files)
+}
+
+fn collect_logs(dir: &Path, logs: &mut Vec<(PathBuf, std::time::SystemTime)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            collect_logs(&path, logs);
+        } else if path.extension().is_some_and(|ext| ext == "jsonl") {
+            let modified = metadata.modified().unwrap_or(std::time::UNIX_EPOCH);
+            logs.push((path, modified));
+        }
+    }
+}
+
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
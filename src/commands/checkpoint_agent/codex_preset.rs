@@ -0,0 +1,207 @@
+use crate::{
+    authorship::transcript::{AiTranscript, Message},
+    commands::checkpoint_agent::{
+        agent_presets::{AgentCheckpointFlags, AgentCheckpointPreset, AgentRunResult},
+        cli_agent::{CliAgentSession, SessionLogAgent, end_of_turn_run, find_session},
+    },
+    error::GitAiError,
+    mdm::utils::home_dir,
+};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Codex CLI, checkpointed from its `notify` program.
+///
+/// Codex runs `notify` after every agent turn with a JSON payload appended as the last
+/// argument; the transcript and model come from its rollout logs under
+/// `$CODEX_HOME/sessions/YYYY/MM/DD/rollout-*.jsonl`.
+pub struct CodexPreset;
+
+impl CodexPreset {
+    pub fn codex_home() -> PathBuf {
+        std::env::var("CODEX_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| home_dir().join(".codex"))
+    }
+
+    /// Parse a rollout log: a `session_meta` line, `turn_context` lines carrying the
+    /// model, and `response_item` lines for messages and tool calls.
+    pub fn session_from_rollout(path: &Path) -> Result<CliAgentSession, GitAiError> {
+        let content = std::fs::read_to_string(path)?;
+        let mut session = CliAgentSession {
+            session_id: path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default(),
+            cwd: None,
+            model: None,
+            transcript: AiTranscript::new(),
+            path: path.to_path_buf(),
+        };
+
+        for line in content.lines().filter(|l| !l.trim().is_empty()) {
+            let Ok(entry) = serde_json::from_str::<Value>(line) else {
+                continue;
+            };
+            let timestamp = entry
+                .get("timestamp")
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            let payload = entry.get("payload").unwrap_or(&Value::Null);
+            match entry.get("type").and_then(|v| v.as_str()) {
+                Some("session_meta") => {
+                    if let Some(id) = payload.get("id").and_then(|v| v.as_str()) {
+                        session.session_id = id.to_string();
+                    }
+                    if let Some(cwd) = payload.get("cwd").and_then(|v| v.as_str()) {
+                        session.cwd = Some(PathBuf::from(cwd));
+                    }
+                }
+                Some("turn_context") => {
+                    if let Some(model) = payload.get("model").and_then(|v| v.as_str()) {
+                        session.model = Some(model.to_string());
+                    }
+                    if session.cwd.is_none()
+                        && let Some(cwd) = payload.get("cwd").and_then(|v| v.as_str())
+                    {
+                        session.cwd = Some(PathBuf::from(cwd));
+                    }
+                }
+                Some("response_item") => {
+                    if let Some(message) = Self::message_from_item(payload, timestamp) {
+                        session.transcript.add_message(message);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(session)
+    }
+
+    fn message_from_item(item: &Value, timestamp: Option<String>) -> Option<Message> {
+        match item.get("type").and_then(|v| v.as_str())? {
+            "message" => {
+                let text = item
+                    .get("content")
+                    .and_then(|v| v.as_array())?
+                    .iter()
+                    .filter_map(|part| part.get("text").and_then(|t| t.as_str()))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                // Codex injects its environment context as user messages
+                if text.trim().is_empty() || text.trim_start().starts_with("<environment_context>")
+                {
+                    return None;
+                }
+                match item.get("role").and_then(|v| v.as_str())? {
+                    "user" => Some(Message::user(text, timestamp)),
+                    "assistant" => Some(Message::assistant(text, timestamp)),
+                    _ => None,
+                }
+            }
+            "function_call" | "custom_tool_call" => {
+                let name = item.get("name").and_then(|v| v.as_str())?.to_string();
+                let raw_input = item.get("arguments").or_else(|| item.get("input"));
+                let input = match raw_input {
+                    Some(Value::String(s)) => {
+                        serde_json::from_str(s).unwrap_or_else(|_| Value::String(s.clone()))
+                    }
+                    Some(other) => other.clone(),
+                    None => Value::Null,
+                };
+                Some(Message::ToolUse {
+                    name,
+                    input,
+                    timestamp,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+impl SessionLogAgent for CodexPreset {
+    fn tool(&self) -> &str {
+        "codex"
+    }
+
+    fn sessions_dir(&self) -> PathBuf {
+        Self::codex_home().join("sessions")
+    }
+
+    fn read_session(&self, path: &Path) -> Result<CliAgentSession, GitAiError> {
+        Self::session_from_rollout(path)
+    }
+}
+
+impl AgentCheckpointPreset for CodexPreset {
+    fn run(&self, flags: AgentCheckpointFlags) -> Result<AgentRunResult, GitAiError> {
+        let hook_input_json = flags.hook_input.ok_or_else(|| {
+            GitAiError::PresetError("hook_input is required for Codex preset".to_string())
+        })?;
+        let notification: Value = serde_json::from_str(&hook_input_json)
+            .map_err(|e| GitAiError::PresetError(format!("Invalid JSON in hook_input: {}", e)))?;
+
+        let event = notification.get("type").and_then(|v| v.as_str());
+        if event != Some("agent-turn-complete") {
+            return Err(GitAiError::PresetError(format!(
+                "Unsupported Codex notification: {}. Expected 'agent-turn-complete'",
+                event.unwrap_or("<missing>")
+            )));
+        }
+
+        let cwd = notification
+            .get("cwd")
+            .and_then(|v| v.as_str())
+            .map(PathBuf::from)
+            .or_else(|| std::env::current_dir().ok())
+            .ok_or_else(|| GitAiError::PresetError("Could not determine cwd".to_string()))?;
+        let thread_id = notification.get("thread-id").and_then(|v| v.as_str());
+
+        let session = find_session(self, &cwd, thread_id);
+        if session.is_none() {
+            eprintln!("[Warning] No Codex session log found for {}", cwd.display());
+        }
+        Ok(end_of_turn_run(self, session, &cwd))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_from_rollout() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("rollout-2025-09-01T10-00-00-abc.jsonl");
+        let lines = [
+            r#"{"timestamp":"2025-09-01T10:00:00Z","type":"session_meta","payload":{"id":"0199-abc","cwd":"/work/repo","cli_version":"0.36.0"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:01Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"<environment_context>\n  <cwd>/work/repo</cwd>\n</environment_context>"}]}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:01Z","type":"turn_context","payload":{"cwd":"/work/repo","model":"gpt-5-codex"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:02Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"add a greet function"}]}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:03Z","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"ls\"]}","call_id":"call_1"}}"#,
+            r#"{"timestamp":"2025-09-01T10:00:04Z","type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"Added greet()."}]}}"#,
+            "not json",
+        ];
+        std::fs::write(&path, lines.join("\n")).unwrap();
+
+        let session = CodexPreset::session_from_rollout(&path).unwrap();
+        assert_eq!(session.session_id, "0199-abc");
+        assert_eq!(session.cwd, Some(PathBuf::from("/work/repo")));
+        assert_eq!(session.model.as_deref(), Some("gpt-5-codex"));
+        let messages = session.transcript.messages();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(
+            messages[0],
+            Message::user(
+                "add a greet function".to_string(),
+                Some("2025-09-01T10:00:02Z".to_string())
+            )
+        );
+        assert!(matches!(&messages[1], Message::ToolUse { name, input, .. }
+            if name == "shell" && input["command"][0] == "ls"));
+        assert!(
+            matches!(&messages[2], Message::Assistant { text, .. } if text == "Added greet().")
+        );
+    }
+}
@@ -0,0 +1,347 @@
+//! Detectors deciding which agent is behind a `git-ai checkpoint` run without a preset.
+//!
+//! Built-in detectors cover the machine identity in `GIT_AI_MACHINE_IDENTITY`, the
+//! `custom_agents` registry and, with the
+//! `agent_detection` feature flag, the known agents of [`agent_registry`]. Third
+//! parties can add their own without forking by installing an executable named
+//! `git-ai-detector-<name>`, either on `PATH` or in `~/.git-ai/detectors/`.
+//!
+//! An external detector inherits git-ai's environment, receives on stdin
+//!
+//! ```json
+//! {"working_dir": "/path/to/repo", "ancestors": ["zsh", "acme-agent"]}
+//! ```
+//!
+//! and prints `{"tool": "acme", "model": "acme-large", "session_id": "abc"}` (only
+//! `tool` is required) when it recognizes its agent. Printing nothing, `null`, or
+//! exiting non-zero means no match. Detectors that don't answer within
+//! [`EXTERNAL_TIMEOUT`] are killed and skipped.
+//!
+//! [`agent_registry`]: super::agent_registry
+
+use crate::commands::checkpoint_agent::agent_registry::{
+    self, CustomAgent, DetectedAgent, process_ancestry,
+};
+use crate::mdm::utils::home_dir;
+use crate::utils::debug_log;
+use serde_json::json;
+use std::cell::OnceCell;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// Prefix of external detector executables
+pub const EXTERNAL_PREFIX: &str = "git-ai-detector-";
+
+/// How long an external detector may take before it is skipped
+pub const EXTERNAL_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// What detectors get to look at.
+pub struct DetectionContext {
+    pub working_dir: String,
+    pub env: HashMap<String, String>,
+    ancestors: OnceCell<Vec<String>>,
+}
+
+impl DetectionContext {
+    pub fn new(working_dir: &str, env: HashMap<String, String>) -> Self {
+        DetectionContext {
+            working_dir: working_dir.to_string(),
+            env,
+            ancestors: OnceCell::new(),
+        }
+    }
+
+    /// Context of the current process.
+    pub fn current(working_dir: &str) -> Self {
+        Self::new(working_dir, std::env::vars().collect())
+    }
+
+    #[cfg(test)]
+    fn with_ancestors(mut self, ancestors: Vec<String>) -> Self {
+        self.ancestors = OnceCell::from(ancestors);
+        self
+    }
+
+    /// Names of the parent processes, nearest first; read once, on first use.
+    pub fn ancestors(&self) -> &[String] {
+        self.ancestors.get_or_init(process_ancestry)
+    }
+
+    fn env_value(&self, var: &str) -> Option<String> {
+        self.env.get(var).filter(|value| !value.is_empty()).cloned()
+    }
+}
+
+pub trait Detector {
+    /// Name shown in debug logs
+    fn name(&self) -> &str;
+
+    fn detect(&self, context: &DetectionContext) -> Option<DetectedAgent>;
+}
+
+/// The custom agent named by `GIT_AI_MACHINE_IDENTITY`.
+pub struct MachineIdentityDetector<'a> {
+    pub agents: &'a [CustomAgent],
+}
+
+impl Detector for MachineIdentityDetector<'_> {
+    fn name(&self) -> &str {
+        "machine_identity"
+    }
+
+    fn detect(&self, context: &DetectionContext) -> Option<DetectedAgent> {
+        let agent = agent_registry::machine_identity(self.agents, |var| context.env_value(var))?;
+        Some(DetectedAgent {
+            tool: agent.name.clone(),
+            model: None,
+            session_id: agent
+                .detect_env
+                .iter()
+                .find_map(|var| context.env_value(var)),
+        })
+    }
+}
+
+/// Agents from the `custom_agents` config whose `detect_env` variables are set.
+pub struct CustomAgentDetector<'a> {
+    pub agents: &'a [CustomAgent],
+}
+
+impl Detector for CustomAgentDetector<'_> {
+    fn name(&self) -> &str {
+        "custom_agents"
+    }
+
+    fn detect(&self, context: &DetectionContext) -> Option<DetectedAgent> {
+        let agent = agent_registry::detect_agent(self.agents, |var| context.env_value(var))?;
+        Some(DetectedAgent {
+            tool: agent.name.clone(),
+            model: None,
+            // The detection variable usually identifies the agent's session
+            session_id: agent
+                .detect_env
+                .iter()
+                .find_map(|var| context.env_value(var)),
+        })
+    }
+}
+
+/// Built-in agents recognized by their marker variables and process names.
+pub struct KnownAgentDetector;
+
+impl Detector for KnownAgentDetector {
+    fn name(&self) -> &str {
+        "known_agents"
+    }
+
+    fn detect(&self, context: &DetectionContext) -> Option<DetectedAgent> {
+        agent_registry::detect_known_agent(&context.env, context.ancestors())
+    }
+}
+
+/// A `git-ai-detector-*` executable.
+pub struct ExternalDetector {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+impl Detector for ExternalDetector {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn detect(&self, context: &DetectionContext) -> Option<DetectedAgent> {
+        let mut child = Command::new(&self.path)
+            .current_dir(&context.working_dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| debug_log(&format!("detector {} failed to start: {}", self.name, e)))
+            .ok()?;
+
+        let input = json!({
+            "working_dir": context.working_dir,
+            "ancestors": context.ancestors(),
+        });
+        if let Some(mut stdin) = child.stdin.take() {
+            // A detector that doesn't read its input is fine
+            let _ = writeln!(stdin, "{}", input);
+        }
+
+        let deadline = Instant::now() + EXTERNAL_TIMEOUT;
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) if Instant::now() < deadline => {
+                    std::thread::sleep(Duration::from_millis(10))
+                }
+                _ => {
+                    debug_log(&format!("detector {} timed out", self.name));
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return None;
+                }
+            }
+        };
+        if !status.success() {
+            return None;
+        }
+
+        let mut output = String::new();
+        child.stdout.take()?.read_to_string(&mut output).ok()?;
+        parse_external_output(&output)
+    }
+}
+
+fn parse_external_output(output: &str) -> Option<DetectedAgent> {
+    let output = output.trim();
+    if output.is_empty() {
+        return None;
+    }
+    match serde_json::from_str::<Option<DetectedAgent>>(output) {
+        Ok(agent) => agent.filter(|agent| !agent.tool.trim().is_empty()),
+        Err(e) => {
+            debug_log(&format!("ignoring malformed detector output: {}", e));
+            None
+        }
+    }
+}
+
+/// External detectors in `~/.git-ai/detectors/` and on `PATH`, by name; the
+/// detectors directory wins over `PATH`.
+pub fn discover_external() -> Vec<ExternalDetector> {
+    let mut dirs = vec![home_dir().join(".git-ai").join("detectors")];
+    if let Some(path) = std::env::var_os("PATH") {
+        dirs.extend(std::env::split_paths(&path));
+    }
+
+    let mut found = BTreeMap::new();
+    for dir in dirs {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let Some(name) = file_name.strip_prefix(EXTERNAL_PREFIX) else {
+                continue;
+            };
+            let name = name.trim_end_matches(".exe").to_string();
+            if !name.is_empty() && is_executable(&entry.path()) {
+                // Like command lookup, the first directory to have it wins
+                found.entry(name).or_insert_with(|| entry.path());
+            }
+        }
+    }
+    found
+        .into_iter()
+        .map(|(name, path)| ExternalDetector { name, path })
+        .collect()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &std::path::Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path)
+        .is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &std::path::Path) -> bool {
+    path.is_file()
+}
+
+/// Detectors in the order they are consulted: the machine identity, custom agents,
+/// external detectors, then known agents when `known_agents` is set.
+pub fn detectors<'a>(agents: &'a [CustomAgent], known_agents: bool) -> Vec<Box<dyn Detector + 'a>> {
+    let mut detectors: Vec<Box<dyn Detector + 'a>> = vec![
+        Box::new(MachineIdentityDetector { agents }),
+        Box::new(CustomAgentDetector { agents }),
+    ];
+    for external in discover_external() {
+        detectors.push(Box::new(external));
+    }
+    if known_agents {
+        detectors.push(Box::new(KnownAgentDetector));
+    }
+    detectors
+}
+
+/// First agent any of `detectors` recognizes.
+pub fn detect(
+    detectors: &[Box<dyn Detector + '_>],
+    context: &DetectionContext,
+) -> Option<DetectedAgent> {
+    detectors.iter().find_map(|detector| {
+        let agent = detector.detect(context)?;
+        debug_log(&format!(
+            "detector {} matched {}",
+            detector.name(),
+            agent.tool
+        ));
+        Some(agent)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context(pairs: &[(&str, &str)], ancestors: &[&str]) -> DetectionContext {
+        DetectionContext::new(
+            ".",
+            pairs
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect(),
+        )
+        .with_ancestors(ancestors.iter().map(|a| a.to_string()).collect())
+    }
+
+    #[test]
+    fn test_detectors_run_in_order() {
+        let agents = vec![CustomAgent {
+            name: "acme-bot".to_string(),
+            detect_env: vec!["ACME_SESSION".to_string()],
+            ..Default::default()
+        }];
+        let detectors: Vec<Box<dyn Detector>> = vec![
+            Box::new(MachineIdentityDetector { agents: &agents }),
+            Box::new(CustomAgentDetector { agents: &agents }),
+            Box::new(KnownAgentDetector),
+        ];
+
+        let both = context(&[("ACME_SESSION", "s1"), ("CLAUDECODE", "1")], &[]);
+        let agent = detect(&detectors, &both).unwrap();
+        assert_eq!(agent.tool, "acme-bot");
+        assert_eq!(agent.session_id.as_deref(), Some("s1"));
+
+        let machine = context(&[("GIT_AI_MACHINE_IDENTITY", "acme-bot")], &["aider"]);
+        let agent = detect(&detectors, &machine).unwrap();
+        assert_eq!(agent.tool, "acme-bot");
+        assert_eq!(agent.session_id, None);
+
+        let known = context(&[], &["bash", "aider"]);
+        assert_eq!(detect(&detectors, &known).unwrap().tool, "aider");
+        assert!(detect(&detectors, &context(&[], &["bash"])).is_none());
+    }
+
+    #[test]
+    fn test_parse_external_output() {
+        assert_eq!(
+            parse_external_output(r#"{"tool": "acme", "session_id": "abc"}"#),
+            Some(DetectedAgent {
+                tool: "acme".to_string(),
+                model: None,
+                session_id: Some("abc".to_string()),
+            })
+        );
+        assert_eq!(parse_external_output(""), None);
+        assert_eq!(parse_external_output("null"), None);
+        assert_eq!(parse_external_output(r#"{"tool": ""}"#), None);
+        assert_eq!(parse_external_output("not json"), None);
+    }
+}
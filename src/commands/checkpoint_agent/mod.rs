@@ -1,3 +1,8 @@
 pub mod agent_presets;
+pub mod agent_registry;
 pub mod agent_v1_preset;
+pub mod aider_preset;
+pub mod cli_agent;
+pub mod codex_preset;
+pub mod detectors;
 pub mod opencode_preset;
@@ -0,0 +1,344 @@
+//! `git-ai ci-gate`: the push policy, checked in CI over the commits of a pull or
+//! merge request instead of in a hook.
+//!
+//! Commits are checked against the configured `push_policy` and against the
+//! `.git-ai/policy.toml` of the merge base, so a request cannot relax the policy it is
+//! checked against. `--approved-by <login>` (once per approving reviewer, as read from
+//! the forge's review API) acknowledges every commit for rules that require a review from
+//! a `CODEOWNERS` owner; a plain login stands for `@login`.
+//!
+//! The range defaults to the request's target branch (`GITHUB_BASE_REF` or
+//! `CI_MERGE_REQUEST_TARGET_BRANCH_NAME`, on `origin`) up to HEAD. Violations are
+//! reported on stderr and fail the job, exactly as `pre-receive` would reject them.
+//!
+//! `--annotations` also prints the AI-heavy hunks of the change on stdout, as a JSON
+//! array of `{path, start_line, end_line, annotation_level, title, message}` objects.
+//! Those are the fields of a GitHub check-run annotation, and map one-to-one onto the
+//! inline comments of most other review APIs. A hunk is annotated when at least
+//! `--min-ai-percent` (default 50) of its added lines are AI-authored; its level is
+//! `notice`, `warning` from 90%, and `failure` in a `protected_paths` file.
+
+use crate::authorship::policy::{POLICY_FILE, Policy};
+use crate::authorship::push_policy::{PolicyViolation, evaluate_commits};
+use crate::commands::diff::{
+    Attribution, DiffLineKey, LineSide, get_diff_with_line_numbers, overlay_diff_attributions,
+};
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::Repository;
+use glob::Pattern;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+/// Share of a hunk's added lines that must be AI-authored for it to be annotated
+const DEFAULT_MIN_AI_PERCENT: u32 = 50;
+
+/// Share from which an annotated hunk is a warning rather than a notice
+const WARNING_AI_PERCENT: u32 = 90;
+
+/// CI variables naming the branch a pull or merge request targets
+const BASE_BRANCH_ENVS: &[&str] = &["GITHUB_BASE_REF", "CI_MERGE_REQUEST_TARGET_BRANCH_NAME"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnotationLevel {
+    Notice,
+    Warning,
+    Failure,
+}
+
+/// An AI-heavy hunk, as a GitHub check-run annotation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReviewAnnotation {
+    pub path: String,
+    pub start_line: u32,
+    pub end_line: u32,
+    pub annotation_level: AnnotationLevel,
+    pub title: String,
+    pub message: String,
+}
+
+pub fn handle_ci_gate(args: &[String]) {
+    let usage = "Usage: git-ai ci-gate [<base>[..<head>]] [--annotations] [--min-ai-percent <n>] [--approved-by <login>]...";
+    let mut range = None;
+    let mut annotations = false;
+    let mut min_ai_percent = DEFAULT_MIN_AI_PERCENT;
+    let mut approved_by = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--annotations" => annotations = true,
+            "--min-ai-percent" if i + 1 < args.len() => {
+                min_ai_percent = match args[i + 1].parse::<u32>() {
+                    Ok(percent) if percent <= 100 => percent,
+                    _ => {
+                        eprintln!("Invalid --min-ai-percent: {}", args[i + 1]);
+                        std::process::exit(1);
+                    }
+                };
+                i += 1;
+            }
+            "--approved-by" if i + 1 < args.len() => {
+                let reviewer = &args[i + 1];
+                approved_by.push(if reviewer.contains('@') {
+                    reviewer.clone()
+                } else {
+                    format!("@{}", reviewer)
+                });
+                i += 1;
+            }
+            arg if !arg.starts_with('-') && range.is_none() => range = Some(arg.to_string()),
+            _ => {
+                eprintln!("{}", usage);
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    let result = (|| {
+        let repo = find_repository(&Vec::<String>::new())?;
+        let RequestRange {
+            merge_base,
+            head,
+            commits,
+        } = RequestRange::resolve(&repo, range.as_deref())?;
+        let policy = Config::get().push_policy();
+        let mut violations = evaluate_commits(&repo, policy, &commits)?;
+        let mut file_policy = Policy::load_at(&repo, &merge_base)?;
+        if let Some(file_policy) = &mut file_policy {
+            file_policy.approved_by = approved_by.clone();
+            violations.extend(file_policy.evaluate_commits(&repo, &commits)?);
+        }
+        let checked_against = if file_policy.is_some() {
+            format!("push_policy and {}", POLICY_FILE)
+        } else {
+            "push_policy".to_string()
+        };
+        let found = if annotations {
+            let protected: Vec<Pattern> = policy
+                .protected_paths
+                .iter()
+                .filter_map(|p| Pattern::new(p).ok())
+                .collect();
+            ai_hunk_annotations(&repo, &merge_base, &head, min_ai_percent, &protected)?
+        } else {
+            Vec::new()
+        };
+        Ok::<(usize, String, Vec<PolicyViolation>, Vec<ReviewAnnotation>), GitAiError>((
+            commits.len(),
+            checked_against,
+            violations,
+            found,
+        ))
+    })();
+
+    match result {
+        Ok((checked, checked_against, violations, found)) => {
+            if annotations {
+                println!("{}", serde_json::to_string_pretty(&found).unwrap());
+            }
+            if violations.is_empty() {
+                eprintln!("git-ai: {} commit(s) pass {}", checked, checked_against);
+                return;
+            }
+            eprintln!(
+                "git-ai: ci-gate failed, commits violate {}",
+                checked_against
+            );
+            for v in &violations {
+                eprintln!(
+                    "  {} {}: {}",
+                    &v.commit_sha[..7.min(v.commit_sha.len())],
+                    v.subject,
+                    v.reason
+                );
+            }
+            std::process::exit(1);
+        }
+        Err(e) => {
+            crate::error::exit_with(&format!("git-ai: ci-gate failed: {}", e), &e);
+        }
+    }
+}
+
+/// The commits a pull or merge request adds to its target branch
+pub struct RequestRange {
+    pub merge_base: String,
+    pub head: String,
+    /// Oldest first
+    pub commits: Vec<String>,
+}
+
+impl RequestRange {
+    /// `range` as given to `ci-gate`, defaulting to the CI request's target branch
+    pub fn resolve(repo: &Repository, range: Option<&str>) -> Result<Self, GitAiError> {
+        let (base, head) = resolve_range(range)?;
+        let merge_base = repo.git(&["merge-base", &base, &head])?.trim().to_string();
+        let commits = repo
+            .git(&[
+                "rev-list",
+                "--reverse",
+                &format!("{}..{}", merge_base, head),
+            ])?
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(str::to_string)
+            .collect();
+        Ok(RequestRange {
+            merge_base,
+            head,
+            commits,
+        })
+    }
+}
+
+/// `<base>..<head>`, `<base>` (up to HEAD), or the CI request's target branch
+fn resolve_range(range: Option<&str>) -> Result<(String, String), GitAiError> {
+    if let Some(range) = range {
+        return Ok(match range.split_once("..") {
+            Some((base, head)) => (base.to_string(), head.to_string()),
+            None => (range.to_string(), "HEAD".to_string()),
+        });
+    }
+    let base = BASE_BRANCH_ENVS
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|branch| !branch.is_empty()))
+        .map(|branch| format!("origin/{}", branch))
+        .unwrap_or_else(|| "origin/HEAD".to_string());
+    Ok((base, "HEAD".to_string()))
+}
+
+/// Annotations for the hunks of `from..to` that are at least `min_ai_percent` AI-authored
+pub fn ai_hunk_annotations(
+    repo: &Repository,
+    from: &str,
+    to: &str,
+    min_ai_percent: u32,
+    protected: &[Pattern],
+) -> Result<Vec<ReviewAnnotation>, GitAiError> {
+    let hunks = get_diff_with_line_numbers(repo, from, to)?;
+    let attributions: HashMap<DiffLineKey, Attribution> =
+        overlay_diff_attributions(repo, from, to, &hunks)?;
+    Ok(hunks
+        .iter()
+        .filter_map(|hunk| {
+            let lines: Vec<(u32, Option<&str>)> = hunk
+                .added_lines
+                .iter()
+                .map(|&line| {
+                    let key = DiffLineKey {
+                        file: hunk.file_path.clone(),
+                        line,
+                        side: LineSide::New,
+                    };
+                    let tool = match attributions.get(&key) {
+                        Some(Attribution::Ai(tool)) => Some(tool.as_str()),
+                        _ => None,
+                    };
+                    (line, tool)
+                })
+                .collect();
+            let is_protected = protected.iter().any(|p| p.matches(&hunk.file_path));
+            hunk_annotation(&hunk.file_path, &lines, min_ai_percent, is_protected)
+        })
+        .collect())
+}
+
+/// The annotation for one hunk's added lines, each with its AI tool if it has one
+fn hunk_annotation(
+    path: &str,
+    lines: &[(u32, Option<&str>)],
+    min_ai_percent: u32,
+    protected: bool,
+) -> Option<ReviewAnnotation> {
+    let start_line = lines.iter().map(|(line, _)| *line).min()?;
+    let end_line = lines.iter().map(|(line, _)| *line).max()?;
+    let tools: BTreeSet<&str> = lines.iter().filter_map(|(_, tool)| *tool).collect();
+    let ai_lines = lines.iter().filter(|(_, tool)| tool.is_some()).count();
+    if ai_lines == 0 {
+        return None;
+    }
+    let percent = (ai_lines * 100 / lines.len()) as u32;
+    if percent < min_ai_percent && !protected {
+        return None;
+    }
+
+    let annotation_level = if protected {
+        AnnotationLevel::Failure
+    } else if percent >= WARNING_AI_PERCENT {
+        AnnotationLevel::Warning
+    } else {
+        AnnotationLevel::Notice
+    };
+    let mut message = format!(
+        "{} of {} added lines ({}%) are AI-authored ({}).",
+        ai_lines,
+        lines.len(),
+        percent,
+        tools.into_iter().collect::<Vec<_>>().join(", ")
+    );
+    if protected {
+        message.push_str(" This path is protected by push_policy.");
+    }
+    Some(ReviewAnnotation {
+        path: path.to_string(),
+        start_line,
+        end_line,
+        annotation_level,
+        title: format!("{}% AI-authored", percent),
+        message,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hunk_annotation_severity() {
+        let mostly_ai = [(3, Some("claude")), (4, Some("cursor")), (5, None)];
+        let annotation = hunk_annotation("src/lib.rs", &mostly_ai, 50, false).unwrap();
+        assert_eq!(annotation.start_line, 3);
+        assert_eq!(annotation.end_line, 5);
+        assert_eq!(annotation.annotation_level, AnnotationLevel::Notice);
+        assert_eq!(annotation.title, "66% AI-authored");
+        assert_eq!(
+            annotation.message,
+            "2 of 3 added lines (66%) are AI-authored (claude, cursor)."
+        );
+
+        let all_ai = [(1, Some("claude")), (2, Some("claude"))];
+        assert_eq!(
+            hunk_annotation("a", &all_ai, 50, false)
+                .unwrap()
+                .annotation_level,
+            AnnotationLevel::Warning
+        );
+
+        let little_ai = [(1, Some("claude")), (2, None), (3, None)];
+        assert_eq!(hunk_annotation("a", &little_ai, 50, false), None);
+        assert_eq!(
+            hunk_annotation("a", &little_ai, 50, true)
+                .unwrap()
+                .annotation_level,
+            AnnotationLevel::Failure
+        );
+
+        assert_eq!(hunk_annotation("a", &[(1, None)], 0, true), None);
+        assert_eq!(hunk_annotation("a", &[], 0, false), None);
+    }
+
+    #[test]
+    fn test_resolve_range() {
+        assert_eq!(
+            resolve_range(Some("main..feature")).unwrap(),
+            ("main".to_string(), "feature".to_string())
+        );
+        assert_eq!(
+            resolve_range(Some("main")).unwrap(),
+            ("main".to_string(), "HEAD".to_string())
+        );
+    }
+}
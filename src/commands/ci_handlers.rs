@@ -68,14 +68,18 @@ fn handle_ci_github(args: &[String]) {
                             print_ci_result(&result, "GitHub CI");
                         }
                         Err(e) => {
-                            eprintln!("Error running GitHub CI context: {}", e);
-                            std::process::exit(1);
+                            crate::error::exit_with(
+                                &format!("Error running GitHub CI context: {}", e),
+                                &e,
+                            );
                         }
                     }
                     if !no_cleanup {
                         if let Err(e) = ci_context.teardown() {
-                            eprintln!("Error tearing down GitHub CI context: {}", e);
-                            std::process::exit(1);
+                            crate::error::exit_with(
+                                &format!("Error tearing down GitHub CI context: {}", e),
+                                &e,
+                            );
                         }
                         debug_log("GitHub CI context teared down");
                     } else {
@@ -84,8 +88,7 @@ fn handle_ci_github(args: &[String]) {
                     std::process::exit(0);
                 }
                 Err(e) => {
-                    eprintln!("Failed to get GitHub CI context: {}", e);
-                    std::process::exit(1);
+                    crate::error::exit_with(&format!("Failed to get GitHub CI context: {}", e), &e);
                 }
                 Ok(None) => {
                     eprintln!("No GitHub CI context found");
@@ -99,8 +102,10 @@ fn handle_ci_github(args: &[String]) {
                 std::process::exit(0);
             }
             Err(e) => {
-                eprintln!("Failed to install GitHub CI workflow: {}", e);
-                std::process::exit(1);
+                crate::error::exit_with(
+                    &format!("Failed to install GitHub CI workflow: {}", e),
+                    &e,
+                );
             }
         },
         other => {
@@ -128,14 +133,18 @@ fn handle_ci_gitlab(args: &[String]) {
                             print_ci_result(&result, "GitLab CI");
                         }
                         Err(e) => {
-                            eprintln!("Error running GitLab CI context: {}", e);
-                            std::process::exit(1);
+                            crate::error::exit_with(
+                                &format!("Error running GitLab CI context: {}", e),
+                                &e,
+                            );
                         }
                     }
                     if !no_cleanup {
                         if let Err(e) = ci_context.teardown() {
-                            eprintln!("Error tearing down GitLab CI context: {}", e);
-                            std::process::exit(1);
+                            crate::error::exit_with(
+                                &format!("Error tearing down GitLab CI context: {}", e),
+                                &e,
+                            );
                         }
                         debug_log("GitLab CI context teared down");
                     } else {
@@ -144,8 +153,7 @@ fn handle_ci_gitlab(args: &[String]) {
                     std::process::exit(0);
                 }
                 Err(e) => {
-                    eprintln!("Failed to get GitLab CI context: {}", e);
-                    std::process::exit(1);
+                    crate::error::exit_with(&format!("Failed to get GitLab CI context: {}", e), &e);
                 }
                 Ok(None) => {
                     // No matching MR found - this is not an error, just nothing to do
@@ -193,8 +201,10 @@ fn handle_ci_local(args: &[String]) {
     let repo = match find_repository_in_path(".") {
         Ok(r) => r,
         Err(e) => {
-            eprintln!("Failed to open repository in current directory: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(
+                &format!("Failed to open repository in current directory: {}", e),
+                &e,
+            );
         }
     };
 
@@ -262,8 +272,7 @@ fn handle_ci_local(args: &[String]) {
                     print_ci_result(&result, "Local CI (merge)");
                 }
                 Err(e) => {
-                    eprintln!("Error running local CI: {}", e);
-                    std::process::exit(1);
+                    crate::error::exit_with(&format!("Error running local CI: {}", e), &e);
                 }
             }
             std::process::exit(0);
@@ -110,6 +110,27 @@ fn print_config_help() {
     eprintln!("  include_prompts_in_repositories  Repos to include for prompt storage (array)");
     eprintln!("  default_prompt_storage       Fallback storage mode for non-included repos");
     eprintln!("  quiet                        Suppress chart output after commits (bool)");
+    eprintln!("  commit_trailers              Append AI trailers in prepare-commit-msg (bool)");
+    eprintln!(
+        "  commit_summary               Comment pending attribution in the commit editor (bool)"
+    );
+    eprintln!("  async_post_commit            Finalize commit authorship in the background (bool)");
+    eprintln!("  push_policy                  Policies enforced by the pre-push hook (object)");
+    eprintln!(
+        "  org_sync                     Upload aggregated org metrics on a schedule (object)"
+    );
+    eprintln!("  custom_agents                In-house agents to attribute edits to (array)");
+    eprintln!("                               GIT_AI_MACHINE_IDENTITY=<name> makes one own every");
+    eprintln!("                               unattributed change, e.g. on a bot's CI runner");
+    eprintln!(
+        "  model_aliases                Model ids or globs mapped to a stats family (object)"
+    );
+    eprintln!("  language_overrides           Path globs mapped to a stats language (object)");
+    eprintln!("  snapshot_skip_patterns       Generated files checkpoints attribute whole (array)");
+    eprintln!("  credential_store             Where login credentials are kept (keyring/file)");
+    eprintln!("  auth_profiles                Named logins and the repos that use them (object)");
+    eprintln!("  ca_bundle                    PEM file of extra trusted root certificates");
+    eprintln!("  network                      Allow network calls; false for offline mode (bool)");
     eprintln!();
     eprintln!("Repository Patterns:");
     eprintln!("  For exclude/allow/exclude_prompts_in_repositories, you can provide:");
@@ -125,6 +146,23 @@ fn print_config_help() {
     eprintln!("  git-ai config --add exclude_repositories \"temp/*\"");
     eprintln!("  git-ai config --add allow_repositories ~/projects/my-repo");
     eprintln!("  git-ai config --add feature_flags.my_flag true");
+    eprintln!("  git-ai config set push_policy '{{\"max_ai_percent\": 80}}'");
+    eprintln!("  git-ai config set org_sync '{{\"team\": \"payments\", \"interval_hours\": 24}}'");
+    eprintln!(
+        "  git-ai config --add custom_agents '{{\"name\": \"acme-bot\", \"detect_env\": [\"ACME_BOT\"]}}'"
+    );
+    eprintln!("  git-ai config --add model_aliases '{{\"acme-*\": \"acme\"}}'");
+    eprintln!("  git-ai config --add language_overrides '{{\"tests/**\": \"Rust tests\"}}'");
+    eprintln!("  git-ai config --add snapshot_skip_patterns \"generated/**\"");
+    eprintln!(
+        "  git-ai config --add bot_authors '{{\"pattern\": \"*@agents.acme.dev\", \"tool\": \"acme-bot\"}}'"
+    );
+    eprintln!(
+        "  git-ai config --add auth_profiles '{{\"acme\": {{\"repositories\": [\"https://github.com/acme/*\"]}}}}'"
+    );
+    eprintln!(
+        "  git-ai config --add auth_profiles '{{\"acme\": {{\"oidc\": {{\"issuer\": \"https://sso.acme.dev\", \"client_id\": \"git-ai\"}}}}}}'"
+    );
     eprintln!("  git-ai config unset exclude_repositories");
     eprintln!();
     std::process::exit(0);
@@ -302,7 +340,72 @@ fn show_all_config() -> Result<(), String> {
         );
     }
 
+    if let Some(store) = runtime_config.credential_store() {
+        effective_config.insert(
+            "credential_store".to_string(),
+            Value::String(store.to_string()),
+        );
+    }
+
+    if let Some(value) = runtime_config.ca_bundle() {
+        effective_config.insert("ca_bundle".to_string(), Value::String(value.to_string()));
+    }
+
     effective_config.insert("quiet".to_string(), Value::Bool(runtime_config.is_quiet()));
+    effective_config.insert(
+        "network".to_string(),
+        Value::Bool(runtime_config.network_enabled()),
+    );
+    effective_config.insert(
+        "commit_trailers".to_string(),
+        Value::Bool(runtime_config.commit_trailers_enabled()),
+    );
+    effective_config.insert(
+        "commit_summary".to_string(),
+        Value::Bool(runtime_config.commit_summary_enabled()),
+    );
+    effective_config.insert(
+        "async_post_commit".to_string(),
+        Value::Bool(runtime_config.async_post_commit_enabled()),
+    );
+    effective_config.insert(
+        "push_policy".to_string(),
+        serde_json::to_value(runtime_config.push_policy())
+            .unwrap_or_else(|_| Value::Object(serde_json::Map::new())),
+    );
+    effective_config.insert(
+        "org_sync".to_string(),
+        serde_json::to_value(runtime_config.org_sync()).unwrap_or(Value::Null),
+    );
+    effective_config.insert(
+        "custom_agents".to_string(),
+        serde_json::to_value(runtime_config.custom_agents())
+            .unwrap_or_else(|_| Value::Array(vec![])),
+    );
+    effective_config.insert(
+        "model_aliases".to_string(),
+        serde_json::to_value(runtime_config.model_aliases())
+            .unwrap_or_else(|_| Value::Object(serde_json::Map::new())),
+    );
+    effective_config.insert(
+        "language_overrides".to_string(),
+        serde_json::to_value(runtime_config.language_overrides())
+            .unwrap_or_else(|_| Value::Object(serde_json::Map::new())),
+    );
+    effective_config.insert(
+        "bot_authors".to_string(),
+        serde_json::to_value(runtime_config.bot_authors()).unwrap_or_else(|_| Value::Array(vec![])),
+    );
+    effective_config.insert(
+        "snapshot_skip_patterns".to_string(),
+        serde_json::to_value(runtime_config.snapshot_skip_patterns())
+            .unwrap_or_else(|_| Value::Array(vec![])),
+    );
+    effective_config.insert(
+        "auth_profiles".to_string(),
+        serde_json::to_value(runtime_config.auth_profiles())
+            .unwrap_or_else(|_| Value::Object(serde_json::Map::new())),
+    );
 
     // Feature flags - show effective flags with defaults applied
     let flags_value = serde_json::to_value(runtime_config.get_feature_flags())
@@ -391,7 +494,36 @@ fn get_config_value(key: &str) -> Result<(), String> {
                     Value::Null
                 }
             }
+            "credential_store" => runtime_config
+                .credential_store()
+                .map(|store| Value::String(store.to_string()))
+                .unwrap_or(Value::Null),
+            "ca_bundle" => runtime_config
+                .ca_bundle()
+                .map(|value| Value::String(value.to_string()))
+                .unwrap_or(Value::Null),
             "quiet" => Value::Bool(runtime_config.is_quiet()),
+            "network" => Value::Bool(runtime_config.network_enabled()),
+            "commit_trailers" => Value::Bool(runtime_config.commit_trailers_enabled()),
+            "commit_summary" => Value::Bool(runtime_config.commit_summary_enabled()),
+            "async_post_commit" => Value::Bool(runtime_config.async_post_commit_enabled()),
+            "push_policy" => serde_json::to_value(runtime_config.push_policy())
+                .unwrap_or_else(|_| Value::Object(serde_json::Map::new())),
+            "org_sync" => serde_json::to_value(runtime_config.org_sync()).unwrap_or(Value::Null),
+            "custom_agents" => serde_json::to_value(runtime_config.custom_agents())
+                .unwrap_or_else(|_| Value::Array(vec![])),
+            "model_aliases" => serde_json::to_value(runtime_config.model_aliases())
+                .unwrap_or_else(|_| Value::Object(serde_json::Map::new())),
+            "language_overrides" => serde_json::to_value(runtime_config.language_overrides())
+                .unwrap_or_else(|_| Value::Object(serde_json::Map::new())),
+            "bot_authors" => serde_json::to_value(runtime_config.bot_authors())
+                .unwrap_or_else(|_| Value::Array(vec![])),
+            "snapshot_skip_patterns" => {
+                serde_json::to_value(runtime_config.snapshot_skip_patterns())
+                    .unwrap_or_else(|_| Value::Array(vec![]))
+            }
+            "auth_profiles" => serde_json::to_value(runtime_config.auth_profiles())
+                .unwrap_or_else(|_| Value::Object(serde_json::Map::new())),
             _ => return Err(format!("Unknown config key: {}", key)),
         };
 
@@ -547,12 +679,206 @@ fn set_config_value(key: &str, value: &str, add_mode: bool) -> Result<(), String
                 crate::config::save_file_config(&file_config)?;
                 eprintln!("[default_prompt_storage]: {}", value);
             }
+            "credential_store" => {
+                if value != "keyring" && value != "file" {
+                    return Err(format!(
+                        "Invalid credential_store value '{}'. Expected 'keyring' or 'file'",
+                        value
+                    ));
+                }
+                file_config.credential_store = Some(value.to_string());
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[credential_store]: {}", value);
+            }
+            "ca_bundle" => {
+                if !std::path::Path::new(value).is_file() {
+                    return Err(format!("ca_bundle file not found: {}", value));
+                }
+                file_config.ca_bundle = Some(value.to_string());
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[ca_bundle]: {}", value);
+            }
             "quiet" => {
                 let bool_value = parse_bool(value)?;
                 file_config.quiet = Some(bool_value);
                 crate::config::save_file_config(&file_config)?;
                 eprintln!("[quiet]: {}", bool_value);
             }
+            "network" => {
+                let bool_value = parse_bool(value)?;
+                file_config.network = Some(bool_value);
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[network]: {}", bool_value);
+            }
+            "commit_trailers" => {
+                let bool_value = parse_bool(value)?;
+                file_config.commit_trailers = Some(bool_value);
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[commit_trailers]: {}", bool_value);
+            }
+            "commit_summary" => {
+                let bool_value = parse_bool(value)?;
+                file_config.commit_summary = Some(bool_value);
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[commit_summary]: {}", bool_value);
+            }
+            "async_post_commit" => {
+                let bool_value = parse_bool(value)?;
+                file_config.async_post_commit = Some(bool_value);
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[async_post_commit]: {}", bool_value);
+            }
+            "push_policy" => {
+                if add_mode {
+                    return Err("Cannot use --add with push_policy".to_string());
+                }
+                let policy: crate::authorship::push_policy::PushPolicy =
+                    serde_json::from_str(value)
+                        .map_err(|e| format!("Invalid JSON for push_policy: {}", e))?;
+                file_config.push_policy = Some(policy);
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[push_policy]: {}", value);
+            }
+            "org_sync" => {
+                if add_mode {
+                    return Err("Cannot use --add with org_sync".to_string());
+                }
+                let org_sync: crate::authorship::org_metrics::OrgSyncConfig =
+                    serde_json::from_str(value)
+                        .map_err(|e| format!("Invalid JSON for org_sync: {}", e))?;
+                file_config.org_sync = Some(org_sync);
+                crate::config::save_file_config(&file_config)?;
+                eprintln!("[org_sync]: {}", value);
+            }
+            "custom_agents" => {
+                if add_mode {
+                    // Upsert a single agent by name
+                    let agent: crate::commands::checkpoint_agent::agent_registry::CustomAgent =
+                        serde_json::from_str(value)
+                            .map_err(|e| format!("Invalid JSON for custom agent: {}", e))?;
+                    if agent.name.trim().is_empty() {
+                        return Err("Custom agent requires a name".to_string());
+                    }
+                    let agents = file_config.custom_agents.get_or_insert_with(Vec::new);
+                    agents.retain(|existing| !existing.name.eq_ignore_ascii_case(&agent.name));
+                    eprintln!("+ [custom_agents]: {}", agent.name);
+                    agents.push(agent);
+                } else {
+                    let agents: Vec<
+                        crate::commands::checkpoint_agent::agent_registry::CustomAgent,
+                    > = serde_json::from_str(value)
+                        .map_err(|e| format!("Invalid JSON for custom_agents: {}", e))?;
+                    eprintln!("[custom_agents]: {}", value);
+                    file_config.custom_agents = Some(agents);
+                }
+                crate::config::save_file_config(&file_config)?;
+            }
+            "model_aliases" => {
+                let aliases: std::collections::BTreeMap<String, String> =
+                    serde_json::from_str(value)
+                        .map_err(|e| format!("Invalid JSON for model_aliases: {}", e))?;
+                if add_mode {
+                    // Merge into the existing aliases
+                    let existing = file_config
+                        .model_aliases
+                        .get_or_insert_with(Default::default);
+                    for (from, family) in aliases {
+                        eprintln!("+ [model_aliases]: {} -> {}", from, family);
+                        existing.insert(from, family);
+                    }
+                } else {
+                    eprintln!("[model_aliases]: {}", value);
+                    file_config.model_aliases = Some(aliases);
+                }
+                crate::config::save_file_config(&file_config)?;
+            }
+            "language_overrides" => {
+                let overrides: std::collections::BTreeMap<String, String> =
+                    serde_json::from_str(value)
+                        .map_err(|e| format!("Invalid JSON for language_overrides: {}", e))?;
+                if add_mode {
+                    // Merge into the existing overrides
+                    let existing = file_config
+                        .language_overrides
+                        .get_or_insert_with(Default::default);
+                    for (pattern, language) in overrides {
+                        eprintln!("+ [language_overrides]: {} -> {}", pattern, language);
+                        existing.insert(pattern, language);
+                    }
+                } else {
+                    eprintln!("[language_overrides]: {}", value);
+                    file_config.language_overrides = Some(overrides);
+                }
+                crate::config::save_file_config(&file_config)?;
+            }
+            "auth_profiles" => {
+                let profiles: std::collections::BTreeMap<
+                    String,
+                    crate::auth::profiles::AuthProfile,
+                > = serde_json::from_str(value)
+                    .map_err(|e| format!("Invalid JSON for auth_profiles: {}", e))?;
+                for name in profiles.keys() {
+                    crate::auth::profiles::validate_name(name)?;
+                }
+                if add_mode {
+                    // Merge into the existing profiles, replacing ones with the same name
+                    let existing = file_config
+                        .auth_profiles
+                        .get_or_insert_with(Default::default);
+                    for (name, profile) in profiles {
+                        eprintln!("+ [auth_profiles]: {}", name);
+                        existing.insert(name, profile);
+                    }
+                } else {
+                    eprintln!("[auth_profiles]: {}", value);
+                    file_config.auth_profiles = Some(profiles);
+                }
+                crate::config::save_file_config(&file_config)?;
+            }
+            "bot_authors" => {
+                if add_mode {
+                    // Upsert a single bot by pattern
+                    let bot: crate::authorship::bot_authors::BotAuthor =
+                        serde_json::from_str(value)
+                            .map_err(|e| format!("Invalid JSON for bot author: {}", e))?;
+                    if bot.pattern.trim().is_empty() || bot.tool.trim().is_empty() {
+                        return Err("Bot author requires a pattern and a tool".to_string());
+                    }
+                    let bots = file_config.bot_authors.get_or_insert_with(Vec::new);
+                    bots.retain(|existing| existing.pattern != bot.pattern);
+                    eprintln!("+ [bot_authors]: {} -> {}", bot.pattern, bot.tool);
+                    bots.push(bot);
+                } else {
+                    let bots: Vec<crate::authorship::bot_authors::BotAuthor> =
+                        serde_json::from_str(value)
+                            .map_err(|e| format!("Invalid JSON for bot_authors: {}", e))?;
+                    eprintln!("[bot_authors]: {}", value);
+                    file_config.bot_authors = Some(bots);
+                }
+                crate::config::save_file_config(&file_config)?;
+            }
+            "snapshot_skip_patterns" => {
+                if add_mode {
+                    // Start from the effective patterns, so adding one keeps the defaults
+                    let patterns = file_config.snapshot_skip_patterns.get_or_insert_with(|| {
+                        crate::config::Config::get()
+                            .snapshot_skip_patterns()
+                            .into_iter()
+                            .map(str::to_string)
+                            .collect()
+                    });
+                    if !patterns.iter().any(|existing| existing == value) {
+                        patterns.push(value.to_string());
+                        eprintln!("+ [snapshot_skip_patterns]: {}", value);
+                    }
+                } else {
+                    let patterns: Vec<String> =
+                        serde_json::from_str(value).unwrap_or_else(|_| vec![value.to_string()]);
+                    eprintln!("[snapshot_skip_patterns]: {}", patterns.join(", "));
+                    file_config.snapshot_skip_patterns = Some(patterns);
+                }
+                crate::config::save_file_config(&file_config)?;
+            }
             _ => return Err(format!("Unknown config key: {}", key)),
         }
 
@@ -717,6 +1043,20 @@ fn unset_config_value(key: &str) -> Result<(), String> {
                     eprintln!("- [default_prompt_storage]: {}", v);
                 }
             }
+            "credential_store" => {
+                let old_value = file_config.credential_store.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [credential_store]: {}", v);
+                }
+            }
+            "ca_bundle" => {
+                let old_value = file_config.ca_bundle.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [ca_bundle]: {}", v);
+                }
+            }
             "quiet" => {
                 let old_value = file_config.quiet.take();
                 crate::config::save_file_config(&file_config)?;
@@ -724,6 +1064,82 @@ fn unset_config_value(key: &str) -> Result<(), String> {
                     eprintln!("- [quiet]: {}", v);
                 }
             }
+            "network" => {
+                let old_value = file_config.network.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [network]: {}", v);
+                }
+            }
+            "commit_trailers" => {
+                let old_value = file_config.commit_trailers.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [commit_trailers]: {}", v);
+                }
+            }
+            "commit_summary" => {
+                let old_value = file_config.commit_summary.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [commit_summary]: {}", v);
+                }
+            }
+            "async_post_commit" => {
+                let old_value = file_config.async_post_commit.take();
+                crate::config::save_file_config(&file_config)?;
+                if let Some(v) = old_value {
+                    eprintln!("- [async_post_commit]: {}", v);
+                }
+            }
+            "push_policy" => {
+                if file_config.push_policy.take().is_some() {
+                    crate::config::save_file_config(&file_config)?;
+                    eprintln!("- [push_policy]");
+                }
+            }
+            "org_sync" => {
+                if file_config.org_sync.take().is_some() {
+                    crate::config::save_file_config(&file_config)?;
+                    eprintln!("- [org_sync]");
+                }
+            }
+            "custom_agents" => {
+                if file_config.custom_agents.take().is_some() {
+                    crate::config::save_file_config(&file_config)?;
+                    eprintln!("- [custom_agents]");
+                }
+            }
+            "model_aliases" => {
+                if file_config.model_aliases.take().is_some() {
+                    crate::config::save_file_config(&file_config)?;
+                    eprintln!("- [model_aliases]");
+                }
+            }
+            "language_overrides" => {
+                if file_config.language_overrides.take().is_some() {
+                    crate::config::save_file_config(&file_config)?;
+                    eprintln!("- [language_overrides]");
+                }
+            }
+            "auth_profiles" => {
+                if file_config.auth_profiles.take().is_some() {
+                    crate::config::save_file_config(&file_config)?;
+                    eprintln!("- [auth_profiles]");
+                }
+            }
+            "bot_authors" => {
+                if file_config.bot_authors.take().is_some() {
+                    crate::config::save_file_config(&file_config)?;
+                    eprintln!("- [bot_authors]");
+                }
+            }
+            "snapshot_skip_patterns" => {
+                if file_config.snapshot_skip_patterns.take().is_some() {
+                    crate::config::save_file_config(&file_config)?;
+                    eprintln!("- [snapshot_skip_patterns]");
+                }
+            }
             _ => return Err(format!("Unknown config key: {}", key)),
         }
 
@@ -0,0 +1,1583 @@
+//! `git-ai daemon`: a long-running process answering JSON-RPC 2.0 requests on a local
+//! Unix socket, so editor plugins and agent integrations don't spawn git-ai for every
+//! edit and share one warm set of opened repositories.
+//!
+//! Requests and responses are single-line JSON objects. Methods:
+//! - `checkpoint` `{repo, kind?, tool?, model?, session_id?, files?}`
+//! - `status` `{repo}`: the same summary as `git-ai status --json`
+//! - `blame` `{repo, file, start_line?, end_line?}`: per-line authors
+//! - `edit` `{repo, file, text, contents?, timestamp_ms?}`: an insertion made in an
+//!   editor buffer, checked for pasted AI output when `paste_detection` is enabled
+//! - `precompute` `{}`: precompute now, returning `{repos, files}` newly blamed
+//! - `ping`, `shutdown`
+//!
+//! Tools that only need to report their edits can skip JSON-RPC and write one message
+//! per line to the agent socket (`~/.git-ai/agent.sock`):
+//!
+//! ```json
+//! {"tool": "acme-bot", "model": "acme-large", "files": ["src/a.rs", {"path": "src/b.rs", "ranges": [[10, 24]]}]}
+//! ```
+//!
+//! Paths may be absolute or relative to `repo`, which defaults to the repository of the
+//! first absolute path. `model`, `session_id` and `kind` (`ai_agent`, `ai_tab`) are
+//! optional. `ranges` are 1-based inclusive line ranges; they narrow nothing yet, since
+//! lines are credited from what changed in the file since its last checkpoint. Each
+//! message is answered with `{"ok": true, "files_edited": n}` or
+//! `{"ok": false, "error": "..."}`.
+//!
+//! With `--http <port>`, `POST /checkpoint` on `127.0.0.1:<port>` takes the same
+//! params as the `checkpoint` method as its JSON body, for clients that can't open a
+//! Unix socket. Requests must carry `Authorization: Bearer <token>`, where the token
+//! is read from `~/.git-ai/daemon.token` (created on first start, readable only by
+//! the user). Responses are the method's result, or `{"error": {"code", "message"}}`
+//! with a 4xx/5xx status.
+//!
+//! The HTTP listener also answers `GET /metrics`, with the same token, in the
+//! Prometheus text format: surviving AI lines at HEAD by tool, lines and AI share by
+//! top-level directory, and checkpoint counts, for every repository the daemon has
+//! opened and those named with `--metrics-repo` (e.g. a mirror). Line metrics are
+//! recomputed only when a repository's HEAD or authorship notes move.
+//!
+//! When no request has come in for a couple of seconds, the daemon precomputes for
+//! the repositories it has opened: it refreshes the status summary of the working
+//! log, which `git-ai status` reads too, and blames the files of the working log and
+//! of the last commits, so `blame` requests for them are answered from memory until
+//! the file, HEAD, the authorship notes or the checkpoints change. The `precompute`
+//! method runs a pass right away; `--no-precompute` turns idle passes off.
+
+use crate::authorship::paste_detection::{
+    CONFIDENCE_KEY, LOW_CONFIDENCE, PASTE_TOOL, PasteDetector,
+};
+use crate::authorship::reconcile::reconcile_unfinalized_commits;
+use crate::authorship::working_log::CheckpointKind;
+use crate::commands::blame::GitAiBlameOptions;
+use crate::commands::checkpoint;
+use crate::commands::report::{self, LineAuthor};
+use crate::commands::status::{StatusOutput, collect_status};
+use crate::commands::wrap::{author_name, run_result};
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::find_repository_in_path;
+use crate::git::ref_state::ref_stamp;
+use crate::git::repository::Repository;
+use crate::mdm::utils::home_dir;
+use crate::utils::debug_log;
+use serde::Deserialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub(crate) const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+pub(crate) const METHOD_NOT_FOUND: i64 = -32601;
+pub(crate) const INVALID_PARAMS: i64 = -32602;
+/// Any failure while serving a well-formed request
+const SERVER_ERROR: i64 = -32000;
+pub(crate) const REPOSITORY_NOT_ALLOWED: i64 = -32001;
+
+pub fn default_socket_path() -> PathBuf {
+    home_dir().join(".git-ai").join("daemon.sock")
+}
+
+pub fn default_agent_socket_path() -> PathBuf {
+    home_dir().join(".git-ai").join("agent.sock")
+}
+
+pub fn default_http_token_path() -> PathBuf {
+    home_dir().join(".git-ai").join("daemon.token")
+}
+
+/// Largest `POST /checkpoint` body accepted
+const MAX_HTTP_BODY: usize = 1024 * 1024;
+
+/// Prometheus text exposition format
+const METRICS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// How long the daemon goes without requests before it precomputes
+const PRECOMPUTE_IDLE: Duration = Duration::from_secs(2);
+
+/// Commits whose files are blamed ahead of time, newest first
+const PRECOMPUTE_RECENT_COMMITS: usize = 10;
+
+pub fn handle_daemon(args: &[String]) {
+    let mut socket_path = default_socket_path();
+    let mut agent_socket_path = default_agent_socket_path();
+    let mut http_port = None;
+    let mut http_token_path = default_http_token_path();
+    let mut metrics_repos = Vec::new();
+    let mut precompute = true;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--socket" if i + 1 < args.len() => {
+                socket_path = PathBuf::from(&args[i + 1]);
+                i += 1;
+            }
+            "--agent-socket" if i + 1 < args.len() => {
+                agent_socket_path = PathBuf::from(&args[i + 1]);
+                i += 1;
+            }
+            "--http" if i + 1 < args.len() => {
+                http_port = Some(parse_port(&args[i + 1]));
+                i += 1;
+            }
+            "--http-token-file" if i + 1 < args.len() => {
+                http_token_path = PathBuf::from(&args[i + 1]);
+                i += 1;
+            }
+            "--metrics-repo" if i + 1 < args.len() => {
+                metrics_repos.push(args[i + 1].clone());
+                i += 1;
+            }
+            "--no-precompute" => precompute = false,
+            arg if arg.starts_with("--socket=") => {
+                socket_path = PathBuf::from(&arg["--socket=".len()..]);
+            }
+            arg if arg.starts_with("--agent-socket=") => {
+                agent_socket_path = PathBuf::from(&arg["--agent-socket=".len()..]);
+            }
+            arg if arg.starts_with("--http=") => {
+                http_port = Some(parse_port(&arg["--http=".len()..]));
+            }
+            arg => {
+                eprintln!("Unknown daemon argument: {}", arg);
+                print_usage();
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    let http = http_port.map(|port| (port, http_token_path));
+    let daemon = Daemon {
+        metrics_repos,
+        precompute,
+        ..Default::default()
+    };
+    if let Err(e) = serve(daemon, socket_path, agent_socket_path, http) {
+        crate::error::exit_with(&format!("Daemon failed: {}", e), &e);
+    }
+}
+
+fn print_usage() {
+    eprintln!(
+        "Usage: git-ai daemon [--socket <path>] [--agent-socket <path>] [--http <port> [--http-token-file <path>] [--metrics-repo <path>...]] [--no-precompute]"
+    );
+}
+
+fn parse_port(value: &str) -> u16 {
+    value.parse().unwrap_or_else(|_| {
+        eprintln!("Invalid --http port: {}", value);
+        print_usage();
+        std::process::exit(1);
+    })
+}
+
+#[cfg(unix)]
+fn serve(
+    daemon: Daemon,
+    socket_path: PathBuf,
+    agent_socket_path: PathBuf,
+    http: Option<(u16, PathBuf)>,
+) -> Result<(), GitAiError> {
+    use std::sync::Arc;
+
+    let listener = bind(&socket_path)?;
+    let agent_listener = bind(&agent_socket_path)?;
+    eprintln!(
+        "git-ai daemon listening on {} (agents: {})",
+        socket_path.display(),
+        agent_socket_path.display()
+    );
+
+    let daemon = Arc::new(daemon);
+    if let Some((port, token_path)) = http {
+        let token = load_or_create_token(&token_path)?;
+        let http_listener = std::net::TcpListener::bind(("127.0.0.1", port))?;
+        eprintln!(
+            "git-ai daemon serving POST /checkpoint and GET /metrics on http://{} (token: {})",
+            http_listener.local_addr()?,
+            token_path.display()
+        );
+        let daemon = Arc::clone(&daemon);
+        std::thread::spawn(move || accept_http(http_listener, daemon, token));
+    }
+    if daemon.precompute {
+        let daemon = Arc::clone(&daemon);
+        std::thread::spawn(move || {
+            loop {
+                std::thread::sleep(PRECOMPUTE_IDLE);
+                if daemon.is_idle() {
+                    daemon.precompute(true);
+                }
+            }
+        });
+    }
+    let sockets = [socket_path, agent_socket_path];
+    {
+        let daemon = Arc::clone(&daemon);
+        let sockets = sockets.clone();
+        std::thread::spawn(move || {
+            accept(agent_listener, &sockets, move |line| {
+                (Some(daemon.handle_agent_message(line)), false)
+            })
+        });
+    }
+    accept(listener, &sockets, move |line| daemon.handle_line(line));
+    Ok(())
+}
+
+/// Listen on `path`, replacing a socket left behind by a daemon that died.
+#[cfg(unix)]
+fn bind(path: &std::path::Path) -> Result<std::os::unix::net::UnixListener, GitAiError> {
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if path.exists() {
+        if UnixStream::connect(path).is_ok() {
+            return Err(GitAiError::Generic(format!(
+                "a daemon is already listening on {}",
+                path.display()
+            )));
+        }
+        // Left behind by a daemon that didn't shut down cleanly
+        std::fs::remove_file(path)?;
+    }
+
+    let listener = UnixListener::bind(path)?;
+    // Checkpoints are recorded as whoever runs the daemon, so keep other users out
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(listener)
+}
+
+/// Answer each line of every connection to `listener` with `handle` on its own thread;
+/// removes `sockets` and exits once `handle` asks for a shutdown.
+#[cfg(unix)]
+fn accept<F>(listener: std::os::unix::net::UnixListener, sockets: &[PathBuf], handle: F)
+where
+    F: Fn(&str) -> (Option<Value>, bool) + Send + Sync + 'static,
+{
+    use std::io::{BufRead, BufReader, Write};
+    use std::sync::Arc;
+
+    let handle = Arc::new(handle);
+    for stream in listener.incoming() {
+        let stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                debug_log(&format!("daemon: failed to accept connection: {}", e));
+                continue;
+            }
+        };
+        let handle = Arc::clone(&handle);
+        let sockets = sockets.to_vec();
+        std::thread::spawn(move || {
+            let Ok(mut writer) = stream.try_clone() else {
+                return;
+            };
+            for line in BufReader::new(stream).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                let (response, shutdown) = handle(&line);
+                if let Some(response) = response
+                    && writeln!(writer, "{}", response).is_err()
+                {
+                    break;
+                }
+                if shutdown {
+                    for socket in &sockets {
+                        let _ = std::fs::remove_file(socket);
+                    }
+                    std::process::exit(0);
+                }
+            }
+        });
+    }
+}
+
+/// The bearer token HTTP clients must send, generated on first use.
+#[cfg(unix)]
+fn load_or_create_token(path: &std::path::Path) -> Result<String, GitAiError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if let Ok(token) = std::fs::read_to_string(path)
+        && !token.trim().is_empty()
+    {
+        return Ok(token.trim().to_string());
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let token = uuid::Uuid::new_v4().simple().to_string();
+    std::fs::write(path, format!("{}\n", token))?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+    Ok(token)
+}
+
+/// Answer each HTTP connection to `listener` on its own thread, one request per
+/// connection.
+#[cfg(unix)]
+fn accept_http(listener: std::net::TcpListener, daemon: std::sync::Arc<Daemon>, token: String) {
+    use std::io::Write;
+    use std::sync::Arc;
+
+    let token = Arc::new(token);
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                debug_log(&format!("daemon: failed to accept HTTP connection: {}", e));
+                continue;
+            }
+        };
+        let daemon = Arc::clone(&daemon);
+        let token = Arc::clone(&token);
+        std::thread::spawn(move || {
+            let (status, content_type, body) = match read_http_request(&stream) {
+                Ok(request) if request.path == "/metrics" => {
+                    match daemon.handle_metrics(&token, &request) {
+                        Ok(metrics) => (200, METRICS_CONTENT_TYPE, metrics),
+                        Err(error) => {
+                            let (status, body) = http_error(error);
+                            (status, "application/json", body.to_string())
+                        }
+                    }
+                }
+                Ok(request) => {
+                    let (status, body) = daemon.handle_http(&token, request);
+                    (status, "application/json", body.to_string())
+                }
+                Err(error) => {
+                    let (status, body) = http_error(error);
+                    (status, "application/json", body.to_string())
+                }
+            };
+            let _ = write!(
+                stream,
+                "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                http_reason(status),
+                content_type,
+                body.len(),
+                body
+            );
+        });
+    }
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+/// Read a request line, headers and a `Content-Length` body.
+#[cfg(unix)]
+fn read_http_request(stream: &std::net::TcpStream) -> Result<HttpRequest, (u16, String)> {
+    use std::io::{BufRead, BufReader, Read};
+
+    let bad_request = |message: &str| (400, message.to_string());
+    let mut reader = BufReader::new(stream);
+    let mut line = String::new();
+    reader
+        .read_line(&mut line)
+        .map_err(|_| bad_request("Unreadable request"))?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        return Err(bad_request("Malformed request line"));
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    let mut authorization = None;
+    let mut content_length = 0;
+    loop {
+        line.clear();
+        reader
+            .read_line(&mut line)
+            .map_err(|_| bad_request("Unreadable headers"))?;
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        let Some((name, value)) = header.split_once(':') else {
+            return Err(bad_request("Malformed header"));
+        };
+        let value = value.trim();
+        if name.eq_ignore_ascii_case("authorization") {
+            authorization = Some(value.to_string());
+        } else if name.eq_ignore_ascii_case("content-length") {
+            content_length = value
+                .parse()
+                .map_err(|_| bad_request("Invalid Content-Length"))?;
+        }
+    }
+    if content_length > MAX_HTTP_BODY {
+        return Err((413, "Request body too large".to_string()));
+    }
+
+    let mut body = vec![0; content_length];
+    reader
+        .read_exact(&mut body)
+        .map_err(|_| bad_request("Truncated body"))?;
+    Ok(HttpRequest {
+        method,
+        path,
+        authorization,
+        body,
+    })
+}
+
+fn http_error((status, message): (u16, String)) -> (u16, Value) {
+    (
+        status,
+        json!({"error": {"code": status, "message": message}}),
+    )
+}
+
+fn http_reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        413 => "Payload Too Large",
+        _ => "Internal Server Error",
+    }
+}
+
+/// Compare without returning early, so response times don't leak the token.
+fn tokens_match(given: &str, expected: &str) -> bool {
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+#[cfg(not(unix))]
+fn serve(
+    _daemon: Daemon,
+    _socket_path: PathBuf,
+    _agent_socket_path: PathBuf,
+    _http: Option<(u16, PathBuf)>,
+) -> Result<(), GitAiError> {
+    Err(GitAiError::Generic(
+        "git-ai daemon is only supported on Unix platforms".to_string(),
+    ))
+}
+
+#[derive(Debug)]
+pub(crate) struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    pub(crate) fn new(code: i64, message: impl Into<String>) -> Self {
+        RpcError {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// `{"code", "message"}`, as nested under `error` in responses
+    pub(crate) fn to_json(&self) -> Value {
+        json!({"code": self.code, "message": self.message})
+    }
+}
+
+impl From<GitAiError> for RpcError {
+    fn from(e: GitAiError) -> Self {
+        RpcError::new(SERVER_ERROR, e.to_string())
+    }
+}
+
+#[derive(Deserialize)]
+struct RepoParams {
+    repo: String,
+}
+
+#[derive(Deserialize)]
+pub(crate) struct CheckpointParams {
+    repo: String,
+    kind: Option<String>,
+    tool: Option<String>,
+    model: Option<String>,
+    session_id: Option<String>,
+    /// Paths relative to the repository root; defaults to every changed file
+    files: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct BlameParams {
+    repo: String,
+    file: String,
+    start_line: Option<u32>,
+    end_line: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct AgentMessage {
+    repo: Option<String>,
+    tool: String,
+    model: Option<String>,
+    session_id: Option<String>,
+    kind: Option<String>,
+    files: Vec<AgentFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AgentFile {
+    Path(String),
+    Ranges {
+        path: String,
+        #[serde(default)]
+        #[allow(dead_code)]
+        ranges: Vec<(u32, u32)>,
+    },
+}
+
+impl AgentFile {
+    fn path(&self) -> &str {
+        match self {
+            AgentFile::Path(path) | AgentFile::Ranges { path, .. } => path,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct EditParams {
+    repo: String,
+    file: String,
+    /// Text the edit inserted
+    text: String,
+    /// Buffer contents after the edit; defaults to the file on disk
+    contents: Option<String>,
+    /// When the edit happened, in milliseconds since the epoch
+    timestamp_ms: Option<u128>,
+}
+
+#[derive(Default)]
+struct Daemon {
+    /// Opened repositories by the path clients address them with. Requests hold this
+    /// lock while they run, so checkpoints of concurrent clients never interleave.
+    repos: Mutex<HashMap<String, Repository>>,
+    pastes: Mutex<PasteDetector>,
+    /// Repositories `/metrics` reports on even before a client opens them
+    metrics_repos: Vec<String>,
+    /// Line metrics by repository path, with the refs they were computed at
+    line_metrics: Mutex<HashMap<String, (String, LineMetrics)>>,
+    checkpoints_recorded: AtomicU64,
+    /// Whether to precompute while idle
+    precompute: bool,
+    /// When the last request came in, in milliseconds since the epoch
+    last_request_ms: AtomicU64,
+    /// Blame results by repository path and file, with the state they were computed at
+    blames: Mutex<HashMap<(String, String), (String, Value)>>,
+    /// Files to precompute by repository path, with the refs and checkpoints size
+    /// they were listed at
+    candidates: Mutex<HashMap<String, (String, Vec<String>)>>,
+    /// HEAD by git dir, with the ref stamp it was resolved at
+    heads: Mutex<HashMap<PathBuf, (String, String)>>,
+}
+
+impl Daemon {
+    /// Answer one request line; returns the response (none for notifications) and
+    /// whether the daemon should exit.
+    fn handle_line(&self, line: &str) -> (Option<Value>, bool) {
+        if line.trim().is_empty() {
+            return (None, false);
+        }
+        self.touch();
+        let request: Value = match serde_json::from_str(line) {
+            Ok(request) => request,
+            Err(e) => {
+                let error = RpcError::new(PARSE_ERROR, format!("Parse error: {}", e));
+                return (Some(error_response(Value::Null, error)), false);
+            }
+        };
+        let id = request.get("id").cloned();
+        let Some(method) = request.get("method").and_then(|m| m.as_str()) else {
+            let error = RpcError::new(INVALID_REQUEST, "Request has no method");
+            return (
+                Some(error_response(id.unwrap_or(Value::Null), error)),
+                false,
+            );
+        };
+        let params = request.get("params").cloned().unwrap_or(json!({}));
+
+        let shutdown = method == "shutdown";
+        let result = self.dispatch(method, params);
+        let response = id.map(|id| match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(error) => error_response(id, error),
+        });
+        (response, shutdown)
+    }
+
+    /// Answer one line written to the agent socket.
+    fn handle_agent_message(&self, line: &str) -> Value {
+        self.touch();
+        let result = serde_json::from_str::<AgentMessage>(line)
+            .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid message: {}", e)))
+            .and_then(|message| self.agent_checkpoint(message));
+        match result {
+            Ok(files_edited) => json!({"ok": true, "files_edited": files_edited}),
+            Err(error) => json!({"ok": false, "error": error.message}),
+        }
+    }
+
+    fn agent_checkpoint(&self, message: AgentMessage) -> Result<usize, RpcError> {
+        if message.kind.as_deref() == Some("human") {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                "The agent socket only records AI edits",
+            ));
+        }
+        let repo_path = match message.repo {
+            Some(repo) => repo,
+            None => message
+                .files
+                .iter()
+                .map(|file| std::path::Path::new(file.path()))
+                .find(|path| path.is_absolute())
+                .and_then(|path| path.parent())
+                .map(|dir| dir.to_string_lossy().to_string())
+                .ok_or_else(|| {
+                    RpcError::new(INVALID_PARAMS, "repo is required for relative paths")
+                })?,
+        };
+
+        let result = self.with_repo(&repo_path, true, |repo| {
+            let workdir = repo.workdir()?;
+            let files = message
+                .files
+                .iter()
+                .map(|file| {
+                    let path = std::path::Path::new(file.path());
+                    path.strip_prefix(&workdir)
+                        .unwrap_or(path)
+                        .to_string_lossy()
+                        .to_string()
+                })
+                .collect();
+            self.checkpoints_recorded.fetch_add(1, Ordering::Relaxed);
+            checkpoint(
+                repo,
+                CheckpointParams {
+                    repo: repo_path.clone(),
+                    kind: message.kind,
+                    tool: Some(message.tool),
+                    model: message.model,
+                    session_id: message.session_id,
+                    files: Some(files),
+                },
+            )
+        })?;
+        Ok(result["files_edited"].as_u64().unwrap_or_default() as usize)
+    }
+
+    /// Answer one HTTP request; returns the status and JSON body.
+    fn handle_http(&self, token: &str, request: HttpRequest) -> (u16, Value) {
+        self.touch();
+        if request.path != "/checkpoint" {
+            return http_error((404, format!("No such endpoint: {}", request.path)));
+        }
+        if request.method != "POST" {
+            return http_error((405, "Use POST /checkpoint".to_string()));
+        }
+        let authorized = request
+            .authorization
+            .as_deref()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|given| tokens_match(given.trim(), token));
+        if !authorized {
+            return http_error((401, "Missing or invalid bearer token".to_string()));
+        }
+
+        let result = serde_json::from_slice(&request.body)
+            .map_err(|e| RpcError::new(PARSE_ERROR, format!("Parse error: {}", e)))
+            .and_then(|params| self.dispatch("checkpoint", params));
+        match result {
+            Ok(result) => (200, result),
+            Err(error) => {
+                let status = match error.code {
+                    PARSE_ERROR | INVALID_PARAMS => 400,
+                    REPOSITORY_NOT_ALLOWED => 403,
+                    _ => 500,
+                };
+                (status, json!({"error": error.to_json()}))
+            }
+        }
+    }
+
+    fn dispatch(&self, method: &str, params: Value) -> Result<Value, RpcError> {
+        match method {
+            "ping" => Ok(json!("pong")),
+            "shutdown" => {
+                // Wait for in-flight requests before the process exits
+                let _repos = self.repos.lock().unwrap_or_else(|e| e.into_inner());
+                Ok(Value::Null)
+            }
+            "checkpoint" => {
+                let params: CheckpointParams = parse_params(params)?;
+                let repo_path = params.repo.clone();
+                self.with_repo(&repo_path, true, |repo| {
+                    self.checkpoints_recorded.fetch_add(1, Ordering::Relaxed);
+                    checkpoint(repo, params)
+                })
+            }
+            "status" => {
+                let params: RepoParams = parse_params(params)?;
+                self.with_repo(&params.repo, true, |repo| {
+                    let output = collect_status(repo, true)?.unwrap_or_else(StatusOutput::default);
+                    Ok(serde_json::to_value(output).map_err(GitAiError::from)?)
+                })
+            }
+            "blame" => {
+                let params: BlameParams = parse_params(params)?;
+                let repo_path = params.repo.clone();
+                self.with_repo(&repo_path, false, |repo| self.cached_blame(repo, params))
+            }
+            "precompute" => {
+                let (repos, files) = self.precompute(false);
+                Ok(json!({"repos": repos, "files": files}))
+            }
+            "edit" => {
+                let params: EditParams = parse_params(params)?;
+                if !Config::get().get_feature_flags().paste_detection {
+                    return Ok(json!({"flagged": false}));
+                }
+                let repo_path = params.repo.clone();
+                self.with_repo(&repo_path, true, |repo| {
+                    let mut pastes = self.pastes.lock().unwrap_or_else(|e| e.into_inner());
+                    edit(repo, &mut pastes, params)
+                })
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unknown method: {}", method),
+            )),
+        }
+    }
+
+    /// Answer `GET /metrics` with the Prometheus metrics of every known repository.
+    fn handle_metrics(&self, token: &str, request: &HttpRequest) -> Result<String, (u16, String)> {
+        if request.method != "GET" {
+            return Err((405, "Use GET /metrics".to_string()));
+        }
+        let authorized = request
+            .authorization
+            .as_deref()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .is_some_and(|given| tokens_match(given.trim(), token));
+        if !authorized {
+            return Err((401, "Missing or invalid bearer token".to_string()));
+        }
+
+        let mut paths: Vec<String> = self.metrics_repos.clone();
+        {
+            let repos = self.repos.lock().unwrap_or_else(|e| e.into_inner());
+            paths.extend(repos.keys().cloned());
+        }
+        paths.sort();
+        paths.dedup();
+
+        let mut samples = Vec::new();
+        for path in paths {
+            let result = self.with_repo(&path, false, |repo| Ok(self.repo_metrics(repo)?));
+            match result {
+                Ok(metrics) => samples.push(metrics),
+                Err(error) => debug_log(&format!(
+                    "daemon: no metrics for {}: {}",
+                    path, error.message
+                )),
+            }
+        }
+        Ok(render_metrics(
+            &samples,
+            self.checkpoints_recorded.load(Ordering::Relaxed),
+        ))
+    }
+
+    fn repo_metrics(&self, repo: &Repository) -> Result<RepoMetrics, GitAiError> {
+        let workdir = repo.workdir()?.to_string_lossy().to_string();
+        let (head, refs) = self.head_and_refs(repo)?;
+        let checkpoints = repo
+            .storage
+            .working_log_for_base_commit(&head)
+            .read_all_checkpoints()?
+            .len();
+
+        let mut cache = self.line_metrics.lock().unwrap_or_else(|e| e.into_inner());
+        let lines = match cache.get(&workdir) {
+            Some((cached_refs, lines)) if *cached_refs == refs => lines.clone(),
+            _ => {
+                let mut lines = LineMetrics::default();
+                report::for_each_file(repo, &head, |file| {
+                    lines.add(&file);
+                    Ok(())
+                })?;
+                cache.insert(workdir.clone(), (refs, lines.clone()));
+                lines
+            }
+        };
+        Ok(RepoMetrics {
+            repo: workdir,
+            lines,
+            checkpoints,
+        })
+    }
+
+    fn touch(&self) {
+        self.last_request_ms.store(now_ms(), Ordering::Relaxed);
+    }
+
+    fn is_idle(&self) -> bool {
+        let last = self.last_request_ms.load(Ordering::Relaxed);
+        now_ms().saturating_sub(last) >= PRECOMPUTE_IDLE.as_millis() as u64
+    }
+
+    /// Refresh the status summaries and blame the likely next files of every opened
+    /// repository; returns how many repositories and files were computed. An idle
+    /// pass stops at the first request, which would otherwise wait on it.
+    fn precompute(&self, idle: bool) -> (usize, usize) {
+        let paths: Vec<String> = {
+            let repos = self.repos.lock().unwrap_or_else(|e| e.into_inner());
+            repos.keys().cloned().collect()
+        };
+        let mut repos = 0;
+        let mut files = 0;
+        for path in paths {
+            let candidates = self.with_repo(&path, false, |repo| {
+                let (head, refs) = self.head_and_refs(repo)?;
+                let summary = repo
+                    .storage
+                    .working_log_for_base_commit(&head)
+                    .status_summary()?;
+                let listed_at = format!("{}:{}", refs, summary.checkpoints_bytes);
+                let mut candidates = self.candidates.lock().unwrap_or_else(|e| e.into_inner());
+                match candidates.get(&path) {
+                    Some((at, files)) if *at == listed_at => Ok(files.clone()),
+                    _ => {
+                        let files = precompute_candidates(repo, &head)?;
+                        candidates.insert(path.clone(), (listed_at, files.clone()));
+                        Ok(files)
+                    }
+                }
+            });
+            let candidates = match candidates {
+                Ok(candidates) => candidates,
+                Err(error) => {
+                    debug_log(&format!(
+                        "daemon: not precomputing {}: {}",
+                        path, error.message
+                    ));
+                    continue;
+                }
+            };
+            repos += 1;
+            for file in candidates {
+                if idle && !self.is_idle() {
+                    return (repos, files);
+                }
+                let params = BlameParams {
+                    repo: path.clone(),
+                    file,
+                    start_line: None,
+                    end_line: None,
+                };
+                let computed = self.with_repo(&path, false, |repo| {
+                    let fresh = self
+                        .blame_state(repo, &params.file)?
+                        .is_some_and(|state| !self.has_blame(&path, &params.file, &state));
+                    if fresh {
+                        self.cached_blame(repo, params)?;
+                    }
+                    Ok(fresh)
+                });
+                match computed {
+                    Ok(true) => files += 1,
+                    Ok(false) => {}
+                    Err(error) => debug_log(&format!(
+                        "daemon: not precomputing blame in {}: {}",
+                        path, error.message
+                    )),
+                }
+            }
+        }
+        (repos, files)
+    }
+
+    fn has_blame(&self, repo_path: &str, file: &str, state: &str) -> bool {
+        let blames = self.blames.lock().unwrap_or_else(|e| e.into_inner());
+        blames
+            .get(&(repo_path.to_string(), file.to_string()))
+            .is_some_and(|(cached, _)| cached == state)
+    }
+
+    /// `blame`, answered from the blame of the whole file when it is still current
+    fn cached_blame(&self, repo: &Repository, params: BlameParams) -> Result<Value, RpcError> {
+        let range = match (params.start_line, params.end_line) {
+            (Some(start), Some(end)) if start >= 1 && start <= end => Some((start, end)),
+            (None, None) => None,
+            // Left to blame to reject
+            _ => return blame(repo, params),
+        };
+        let Some(state) = self.blame_state(repo, &params.file)? else {
+            return blame(repo, params);
+        };
+        let key = (params.repo.clone(), params.file.clone());
+        let cached = {
+            let blames = self.blames.lock().unwrap_or_else(|e| e.into_inner());
+            blames
+                .get(&key)
+                .filter(|(cached, _)| *cached == state)
+                .map(|(_, result)| result.clone())
+        };
+        let whole = match cached {
+            Some(result) => result,
+            None => {
+                let result = blame(
+                    repo,
+                    BlameParams {
+                        start_line: None,
+                        end_line: None,
+                        ..params
+                    },
+                )?;
+                let mut blames = self.blames.lock().unwrap_or_else(|e| e.into_inner());
+                blames.insert(key, (state, result.clone()));
+                result
+            }
+        };
+        Ok(match range {
+            Some((start, end)) => blame_lines(&whole, start, end),
+            None => whole,
+        })
+    }
+
+    /// HEAD and the key of what was computed at it: the ref stamp, which also moves
+    /// with the authorship notes, or HEAD itself when there is no stamp. HEAD is only
+    /// resolved again once the stamp moves.
+    fn head_and_refs(&self, repo: &Repository) -> Result<(String, String), GitAiError> {
+        let Some(stamp) = ref_stamp(repo) else {
+            let head = repo.head()?.target()?;
+            return Ok((head.clone(), head));
+        };
+        let mut heads = self.heads.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((cached_stamp, head)) = heads.get(repo.path())
+            && *cached_stamp == stamp
+        {
+            return Ok((head.clone(), stamp));
+        }
+        let head = repo.head()?.target()?;
+        heads.insert(repo.path().to_path_buf(), (stamp.clone(), head.clone()));
+        Ok((head, stamp))
+    }
+
+    /// What the blame of `file` depends on: the refs, the checkpoints and the file
+    /// itself. `None` for a file that isn't there.
+    fn blame_state(&self, repo: &Repository, file: &str) -> Result<Option<String>, GitAiError> {
+        let Ok(metadata) = std::fs::metadata(repo.workdir()?.join(file)) else {
+            return Ok(None);
+        };
+        let (head, refs) = self.head_and_refs(repo)?;
+        let checkpoints = std::fs::metadata(
+            repo.storage
+                .working_log_for_base_commit(&head)
+                .dir
+                .join("checkpoints.jsonl"),
+        )
+        .map_or(0, |m| m.len());
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_nanos());
+        Ok(Some(format!(
+            "{}:{}:{}:{}",
+            refs,
+            checkpoints,
+            metadata.len(),
+            modified
+        )))
+    }
+
+    fn with_repo<T>(
+        &self,
+        path: &str,
+        checkpoints: bool,
+        f: impl FnOnce(&Repository) -> Result<T, RpcError>,
+    ) -> Result<T, RpcError> {
+        let mut repos = self.repos.lock().unwrap_or_else(|e| e.into_inner());
+        if !repos.contains_key(path) {
+            let repo = find_repository_in_path(path)?;
+            repos.insert(path.to_string(), repo);
+        }
+        let repo = &repos[path];
+        if checkpoints && !Config::get().is_allowed_repository(&Some(repo.clone())) {
+            return Err(RpcError::new(
+                REPOSITORY_NOT_ALLOWED,
+                "Repository is excluded or not in allow_repositories list",
+            ));
+        }
+        f(repo)
+    }
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+/// Files worth blaming ahead of time: those of the working log, then those changed
+/// by the last commits
+fn precompute_candidates(repo: &Repository, head: &str) -> Result<Vec<String>, GitAiError> {
+    let mut files: Vec<String> = repo
+        .storage
+        .working_log_for_base_commit(head)
+        .all_touched_files()?
+        .into_iter()
+        .collect();
+    files.sort();
+
+    let mut args = repo.global_args_for_exec();
+    args.extend([
+        "log".to_string(),
+        format!("-{}", PRECOMPUTE_RECENT_COMMITS),
+        "--name-only".to_string(),
+        "--format=".to_string(),
+        head.to_string(),
+    ]);
+    let output = crate::git::repository::exec_git(&args)?;
+    for file in String::from_utf8(output.stdout)?.lines() {
+        if !file.is_empty() && !files.iter().any(|f| f == file) {
+            files.push(file.to_string());
+        }
+    }
+
+    let workdir = repo.workdir()?;
+    files.retain(|file| workdir.join(file).is_file());
+    Ok(files)
+}
+
+/// The part of a whole-file `blame` result for lines `start..=end`
+fn blame_lines(whole: &Value, start: u32, end: u32) -> Value {
+    let lines: Vec<Value> = whole["lines"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|line| {
+            line["line"]
+                .as_u64()
+                .is_some_and(|n| n >= start as u64 && n <= end as u64)
+        })
+        .cloned()
+        .collect();
+    let prompts: serde_json::Map<String, Value> = whole["prompts"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(hash, _)| lines.iter().any(|line| line["prompt_id"] == hash.as_str()))
+        .map(|(hash, prompt)| (hash.clone(), prompt.clone()))
+        .collect();
+    json!({"lines": lines, "prompts": prompts})
+}
+
+/// Line counts of a repository's HEAD
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct LineMetrics {
+    /// Surviving AI lines by tool
+    ai_lines_by_tool: BTreeMap<String, u64>,
+    /// AI and total lines by top-level directory (`.` for files at the root)
+    lines_by_directory: BTreeMap<String, (u64, u64)>,
+}
+
+impl LineMetrics {
+    #[cfg(test)]
+    fn from_files(files: &[report::FileReport]) -> Self {
+        let mut metrics = LineMetrics::default();
+        for file in files {
+            metrics.add(file);
+        }
+        metrics
+    }
+
+    fn add(&mut self, file: &report::FileReport) {
+        let directory = file.path.split_once('/').map_or(".", |(dir, _)| dir);
+        let counts = self
+            .lines_by_directory
+            .entry(directory.to_string())
+            .or_default();
+        for (_, author) in &file.lines {
+            counts.1 += 1;
+            if let LineAuthor::Ai(tool) = author {
+                counts.0 += 1;
+                *self.ai_lines_by_tool.entry(tool.clone()).or_default() += 1;
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct RepoMetrics {
+    repo: String,
+    lines: LineMetrics,
+    /// Checkpoints recorded since HEAD's commit
+    checkpoints: usize,
+}
+
+fn render_metrics(repos: &[RepoMetrics], checkpoints_recorded: u64) -> String {
+    let mut out = String::new();
+    let mut family = |name: &str, kind: &str, help: &str, samples: Vec<(String, String)>| {
+        out.push_str(&format!(
+            "# HELP {} {}\n# TYPE {} {}\n",
+            name, help, name, kind
+        ));
+        for (labels, value) in samples {
+            out.push_str(&format!("{}{} {}\n", name, labels, value));
+        }
+    };
+    let labels = |pairs: &[(&str, &str)]| {
+        let pairs: Vec<String> = pairs
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+            .collect();
+        format!("{{{}}}", pairs.join(","))
+    };
+
+    family(
+        "git_ai_surviving_ai_lines",
+        "gauge",
+        "Lines at HEAD written by AI, by tool",
+        repos
+            .iter()
+            .flat_map(|r| {
+                r.lines.ai_lines_by_tool.iter().map(|(tool, lines)| {
+                    (
+                        labels(&[("repo", &r.repo), ("tool", tool)]),
+                        lines.to_string(),
+                    )
+                })
+            })
+            .collect(),
+    );
+    family(
+        "git_ai_lines",
+        "gauge",
+        "Lines at HEAD, by top-level directory",
+        repos
+            .iter()
+            .flat_map(|r| {
+                r.lines.lines_by_directory.iter().map(|(dir, (_, total))| {
+                    (
+                        labels(&[("repo", &r.repo), ("directory", dir)]),
+                        total.to_string(),
+                    )
+                })
+            })
+            .collect(),
+    );
+    family(
+        "git_ai_ai_share",
+        "gauge",
+        "Share (0-1) of the lines at HEAD written by AI, by top-level directory",
+        repos
+            .iter()
+            .flat_map(|r| {
+                r.lines.lines_by_directory.iter().map(|(dir, (ai, total))| {
+                    let share = if *total == 0 {
+                        0.0
+                    } else {
+                        *ai as f64 / *total as f64
+                    };
+                    (
+                        labels(&[("repo", &r.repo), ("directory", dir)]),
+                        format!("{:.4}", share),
+                    )
+                })
+            })
+            .collect(),
+    );
+    family(
+        "git_ai_pending_checkpoints",
+        "gauge",
+        "Checkpoints recorded since HEAD was committed",
+        repos
+            .iter()
+            .map(|r| (labels(&[("repo", &r.repo)]), r.checkpoints.to_string()))
+            .collect(),
+    );
+    family(
+        "git_ai_daemon_checkpoints_total",
+        "counter",
+        "Checkpoints recorded through this daemon since it started",
+        vec![(String::new(), checkpoints_recorded.to_string())],
+    );
+    out
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+pub(crate) fn parse_params<T: DeserializeOwned>(params: Value) -> Result<T, RpcError> {
+    serde_json::from_value(params)
+        .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid params: {}", e)))
+}
+
+pub(crate) fn error_response(id: Value, error: RpcError) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": error.to_json(),
+    })
+}
+
+pub(crate) fn checkpoint(repo: &Repository, params: CheckpointParams) -> Result<Value, RpcError> {
+    let kind = match params.kind.as_deref().unwrap_or("ai_agent") {
+        "human" => CheckpointKind::Human,
+        "ai_agent" => CheckpointKind::AiAgent,
+        "ai_tab" => CheckpointKind::AiTab,
+        other => {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                format!("Unknown checkpoint kind: {}", other),
+            ));
+        }
+    };
+
+    if let Err(e) = reconcile_unfinalized_commits(repo) {
+        debug_log(&format!("Failed to reconcile unfinalized commits: {}", e));
+    }
+
+    let agent_run_result = if kind == CheckpointKind::Human && params.files.is_none() {
+        None
+    } else {
+        let tool = match (kind, params.tool) {
+            (_, Some(tool)) if !tool.trim().is_empty() => tool,
+            (CheckpointKind::Human, _) => "human".to_string(),
+            _ => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    "tool is required for AI checkpoints",
+                ));
+            }
+        };
+        let files = match params.files {
+            Some(files) => files,
+            None => {
+                let mut files: Vec<String> = repo.get_worktree_filenames()?.into_iter().collect();
+                files.sort();
+                files
+            }
+        };
+        let model = params.model.unwrap_or_else(|| "unknown".to_string());
+        let mut result = run_result(&tool, &model, kind, files);
+        if let Some(session_id) = params.session_id {
+            result.agent_id.id = session_id;
+        }
+        Some(result)
+    };
+
+    let (_, files_edited, _) = checkpoint::run(
+        repo,
+        &author_name(repo),
+        kind,
+        false,
+        false,
+        true,
+        agent_run_result,
+        false,
+    )?;
+    if kind != CheckpointKind::Human {
+        crate::observability::spawn_background_flush();
+    }
+    Ok(json!({"files_edited": files_edited}))
+}
+
+/// Checkpoint an edit that looks pasted as low-confidence AI, after checkpointing the
+/// rest of the buffer as human.
+fn edit(
+    repo: &Repository,
+    pastes: &mut PasteDetector,
+    params: EditParams,
+) -> Result<Value, RpcError> {
+    let now_ms = params.timestamp_ms.unwrap_or_else(|| {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or_default()
+    });
+    let key = format!("{}:{}", params.repo, params.file);
+    if !pastes.observe(&key, &params.text, now_ms) {
+        return Ok(json!({"flagged": false}));
+    }
+
+    let contents = match params.contents {
+        Some(contents) => contents,
+        None => {
+            std::fs::read_to_string(repo.workdir()?.join(&params.file)).map_err(GitAiError::from)?
+        }
+    };
+    if !contents.contains(&params.text) {
+        // The buffer moved on before we heard about the edit
+        return Ok(json!({"flagged": true, "recorded": false}));
+    }
+
+    if let Err(e) = reconcile_unfinalized_commits(repo) {
+        debug_log(&format!("Failed to reconcile unfinalized commits: {}", e));
+    }
+
+    let files = vec![params.file.clone()];
+    let mut human = run_result("human", "unknown", CheckpointKind::Human, files.clone());
+    human.dirty_files = Some(HashMap::from([(
+        params.file.clone(),
+        contents.replacen(&params.text, "", 1),
+    )]));
+    checkpoint::run(
+        repo,
+        &author_name(repo),
+        CheckpointKind::Human,
+        false,
+        false,
+        true,
+        Some(human),
+        false,
+    )?;
+
+    let mut paste = run_result(PASTE_TOOL, "unknown", CheckpointKind::AiAgent, files);
+    paste.dirty_files = Some(HashMap::from([(params.file.clone(), contents)]));
+    paste.agent_metadata = Some(HashMap::from([(
+        CONFIDENCE_KEY.to_string(),
+        LOW_CONFIDENCE.to_string(),
+    )]));
+    let id = paste.agent_id.id.clone();
+    checkpoint::run(
+        repo,
+        &author_name(repo),
+        CheckpointKind::AiAgent,
+        false,
+        false,
+        true,
+        Some(paste),
+        false,
+    )?;
+    Ok(json!({"flagged": true, "recorded": true, "id": id}))
+}
+
+fn blame(repo: &Repository, params: BlameParams) -> Result<Value, RpcError> {
+    let mut options = GitAiBlameOptions::default();
+    #[allow(clippy::field_reassign_with_default)]
+    {
+        options.no_output = true;
+        options.use_prompt_hashes_as_names = true;
+    }
+    match (params.start_line, params.end_line) {
+        (Some(start), Some(end)) if start >= 1 && start <= end => {
+            options.line_ranges = vec![(start, end)];
+        }
+        (None, None) => {}
+        _ => {
+            return Err(RpcError::new(
+                INVALID_PARAMS,
+                "start_line and end_line must be given together, with 1 <= start_line <= end_line",
+            ));
+        }
+    }
+
+    let (line_authors, prompt_records) = repo.blame(&params.file, &options)?;
+
+    let line_numbers: std::collections::BTreeSet<u32> = line_authors.keys().copied().collect();
+    let lines: Vec<Value> = line_numbers
+        .into_iter()
+        .map(|line| {
+            let author = &line_authors[&line];
+            match prompt_records.get(author) {
+                Some(prompt) => json!({
+                    "line": line,
+                    "author": prompt.agent_id.tool,
+                    "prompt_id": author,
+                }),
+                None => json!({"line": line, "author": author}),
+            }
+        })
+        .collect();
+    let prompts: BTreeMap<&String, Value> = prompt_records
+        .iter()
+        .map(|(hash, prompt)| {
+            (
+                hash,
+                json!({
+                    "tool": prompt.agent_id.tool,
+                    "model": prompt.agent_id.model,
+                    "id": prompt.agent_id.id,
+                    "human_author": prompt.human_author,
+                }),
+            )
+        })
+        .collect();
+    Ok(json!({"lines": lines, "prompts": prompts}))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_handle_line_protocol_errors() {
+        let daemon = Daemon::default();
+
+        let (response, shutdown) =
+            daemon.handle_line(r#"{"jsonrpc":"2.0","id":1,"method":"ping"}"#);
+        assert_eq!(
+            response.unwrap(),
+            json!({"jsonrpc": "2.0", "id": 1, "result": "pong"})
+        );
+        assert!(!shutdown);
+
+        let (response, _) = daemon.handle_line("{not json");
+        let response = response.unwrap();
+        assert_eq!(response["id"], Value::Null);
+        assert_eq!(response["error"]["code"], PARSE_ERROR);
+
+        let (response, _) = daemon.handle_line(r#"{"jsonrpc":"2.0","id":"a","method":"nope"}"#);
+        assert_eq!(response.unwrap()["error"]["code"], METHOD_NOT_FOUND);
+
+        let (response, _) =
+            daemon.handle_line(r#"{"jsonrpc":"2.0","id":2,"method":"blame","params":{}}"#);
+        assert_eq!(response.unwrap()["error"]["code"], INVALID_PARAMS);
+
+        // Notifications get no response
+        let (response, _) = daemon.handle_line(r#"{"jsonrpc":"2.0","method":"ping"}"#);
+        assert!(response.is_none());
+        assert_eq!(daemon.handle_line("  ").0, None);
+
+        let (_, shutdown) = daemon.handle_line(r#"{"jsonrpc":"2.0","id":3,"method":"shutdown"}"#);
+        assert!(shutdown);
+    }
+
+    #[test]
+    fn test_handle_http_rejects_before_checkpointing() {
+        let daemon = Daemon::default();
+        let request =
+            |method: &str, path: &str, authorization: Option<&str>, body: &str| HttpRequest {
+                method: method.to_string(),
+                path: path.to_string(),
+                authorization: authorization.map(str::to_string),
+                body: body.as_bytes().to_vec(),
+            };
+
+        let (status, _) = daemon.handle_http("secret", request("POST", "/status", None, ""));
+        assert_eq!(status, 404);
+        let (status, _) = daemon.handle_http("secret", request("GET", "/checkpoint", None, ""));
+        assert_eq!(status, 405);
+        let (status, _) = daemon.handle_http("secret", request("POST", "/checkpoint", None, "{}"));
+        assert_eq!(status, 401);
+        let (status, _) = daemon.handle_http(
+            "secret",
+            request("POST", "/checkpoint", Some("Bearer secreT"), "{}"),
+        );
+        assert_eq!(status, 401);
+        let (status, body) = daemon.handle_http(
+            "secret",
+            request("POST", "/checkpoint", Some("Bearer secret"), "{nope"),
+        );
+        assert_eq!(status, 400);
+        assert_eq!(body["error"]["code"], PARSE_ERROR);
+        let (status, body) = daemon.handle_http(
+            "secret",
+            request("POST", "/checkpoint", Some("Bearer secret"), "{}"),
+        );
+        assert_eq!(status, 400);
+        assert_eq!(body["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_handle_metrics_requires_token() {
+        let daemon = Daemon::default();
+        let request = |method: &str, authorization: Option<&str>| HttpRequest {
+            method: method.to_string(),
+            path: "/metrics".to_string(),
+            authorization: authorization.map(str::to_string),
+            body: Vec::new(),
+        };
+        assert_eq!(
+            daemon
+                .handle_metrics("secret", &request("POST", None))
+                .unwrap_err()
+                .0,
+            405
+        );
+        assert_eq!(
+            daemon
+                .handle_metrics("secret", &request("GET", None))
+                .unwrap_err()
+                .0,
+            401
+        );
+        let metrics = daemon
+            .handle_metrics("secret", &request("GET", Some("Bearer secret")))
+            .unwrap();
+        assert!(metrics.contains("git_ai_daemon_checkpoints_total 0\n"));
+    }
+
+    #[test]
+    fn test_render_metrics() {
+        let file = |path: &str, authors: Vec<LineAuthor>| report::FileReport {
+            path: path.to_string(),
+            lines: authors.into_iter().map(|a| (String::new(), a)).collect(),
+        };
+        let claude = || LineAuthor::Ai("claude".to_string());
+        let lines = LineMetrics::from_files(&[
+            file("src/a.rs", vec![claude(), LineAuthor::Human]),
+            file("src/b/c.rs", vec![claude()]),
+            file("README.md", vec![LineAuthor::Human]),
+        ]);
+        let metrics = render_metrics(
+            &[RepoMetrics {
+                repo: "/srv/\"mirror\"".to_string(),
+                lines,
+                checkpoints: 3,
+            }],
+            7,
+        );
+        let repo = r#"repo="/srv/\"mirror\"""#;
+        assert!(metrics.contains("# TYPE git_ai_surviving_ai_lines gauge\n"));
+        assert!(metrics.contains(&format!(
+            "git_ai_surviving_ai_lines{{{},tool=\"claude\"}} 2\n",
+            repo
+        )));
+        assert!(metrics.contains(&format!("git_ai_lines{{{},directory=\"src\"}} 3\n", repo)));
+        assert!(metrics.contains(&format!(
+            "git_ai_ai_share{{{},directory=\"src\"}} 0.6667\n",
+            repo
+        )));
+        assert!(metrics.contains(&format!(
+            "git_ai_ai_share{{{},directory=\".\"}} 0.0000\n",
+            repo
+        )));
+        assert!(metrics.contains(&format!("git_ai_pending_checkpoints{{{}}} 3\n", repo)));
+        assert!(metrics.contains(
+            "# TYPE git_ai_daemon_checkpoints_total counter\ngit_ai_daemon_checkpoints_total 7\n"
+        ));
+    }
+}
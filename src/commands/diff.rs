@@ -1,4 +1,5 @@
 use crate::authorship::authorship_log::{LineRange, PromptRecord};
+use crate::authorship::path_config::PathConfig;
 use crate::commands::blame::GitAiBlameOptions;
 use crate::error::GitAiError;
 use crate::git::repository::{Repository, exec_git};
@@ -197,7 +198,8 @@ fn resolve_commit(repo: &Repository, rev: &str) -> Result<String, GitAiError> {
     Ok(sha)
 }
 
-fn resolve_parent(repo: &Repository, commit: &str) -> Result<String, GitAiError> {
+/// Parent of `commit`, or the empty tree for a root commit
+pub fn resolve_parent(repo: &Repository, commit: &str) -> Result<String, GitAiError> {
     let parent_rev = format!("{}^", commit);
 
     // Try to resolve parent
@@ -354,6 +356,33 @@ fn parse_hunk_line(line: &str, file_path: &str) -> Result<Option<DiffHunk>, GitA
 // Attribution Overlay
 // ============================================================================
 
+/// AI-authored and added lines of `from..to`, by file
+pub fn ai_lines_by_file(
+    repo: &Repository,
+    from: &str,
+    to: &str,
+) -> Result<BTreeMap<String, (u32, u32)>, GitAiError> {
+    let hunks = get_diff_with_line_numbers(repo, from, to)?;
+    let attributions = overlay_diff_attributions(repo, from, to, &hunks)?;
+    let paths = PathConfig::for_repo(repo);
+    let mut files: BTreeMap<String, (u32, u32)> = BTreeMap::new();
+    for hunk in hunks.iter().filter(|h| !paths.ignores(&h.file_path)) {
+        for &line in &hunk.added_lines {
+            let key = DiffLineKey {
+                file: hunk.file_path.clone(),
+                line,
+                side: LineSide::New,
+            };
+            let counts = files.entry(hunk.file_path.clone()).or_default();
+            counts.1 += 1;
+            if matches!(attributions.get(&key), Some(Attribution::Ai(_))) {
+                counts.0 += 1;
+            }
+        }
+    }
+    Ok(files)
+}
+
 pub fn overlay_diff_attributions(
     repo: &Repository,
     from_commit: &str,
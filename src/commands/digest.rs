@@ -0,0 +1,406 @@
+//! `git-ai digest`: how AI authorship changed over the last week or month, for
+//! posting to a chat channel or team wiki from a cron job.
+//!
+//! The digest compares the period ending now with the one before it: the AI share of
+//! the added lines and its change, the directories with the most AI lines, and the AI
+//! tools used this period that the previous one didn't use. Periods are rolling (the
+//! last 7 or 30 days) and commits fall in them by commit date. The Markdown output
+//! sticks to bold text and bullet lists, which chat apps render too; `--output html`
+//! is a standalone page.
+
+use crate::authorship::stats::stats_for_commits_stats;
+use crate::commands::diff::{ai_lines_by_file, resolve_parent};
+use crate::commands::report::escape;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::Repository;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use std::collections::BTreeMap;
+
+/// How many of the most AI-touched directories are listed
+const TOP_AREAS: usize = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Week,
+    Month,
+}
+
+impl Period {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "week" => Some(Period::Week),
+            "month" => Some(Period::Month),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Period::Week => "week",
+            Period::Month => "month",
+        }
+    }
+
+    fn length(&self) -> Duration {
+        match self {
+            Period::Week => Duration::days(7),
+            Period::Month => Duration::days(30),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigestFormat {
+    Markdown,
+    Html,
+}
+
+/// Authorship of the commits of one period
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeriodStats {
+    pub commits: usize,
+    pub added_lines: u32,
+    pub ai_lines: u32,
+    /// AI lines by tool
+    pub tools: BTreeMap<String, u32>,
+    /// AI-authored and added lines, by directory
+    pub areas: BTreeMap<String, (u32, u32)>,
+}
+
+impl PeriodStats {
+    /// Share (0-100) of the added lines written by AI
+    pub fn ai_percent(&self) -> u32 {
+        (self.ai_lines * 100)
+            .checked_div(self.added_lines)
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Digest {
+    pub period: Period,
+    pub end: DateTime<Utc>,
+    pub current: PeriodStats,
+    pub previous: PeriodStats,
+}
+
+impl Digest {
+    /// Tools with AI lines this period and none the previous one
+    pub fn new_tools(&self) -> Vec<&str> {
+        self.current
+            .tools
+            .keys()
+            .filter(|tool| !self.previous.tools.contains_key(*tool))
+            .map(String::as_str)
+            .collect()
+    }
+}
+
+pub fn handle_digest(args: &[String]) {
+    let usage = "Usage: git-ai digest [--period week|month] [--output md|html]";
+    let mut period = Period::Week;
+    let mut format = DigestFormat::Markdown;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--period" if i + 1 < args.len() => {
+                period = match Period::parse(&args[i + 1]) {
+                    Some(period) => period,
+                    None => {
+                        eprintln!("Invalid --period: {} (use week or month)", args[i + 1]);
+                        std::process::exit(1);
+                    }
+                };
+                i += 1;
+            }
+            "--output" if i + 1 < args.len() => {
+                format = match args[i + 1].as_str() {
+                    "md" | "markdown" => DigestFormat::Markdown,
+                    "html" => DigestFormat::Html,
+                    other => {
+                        eprintln!("Invalid --output: {} (use md or html)", other);
+                        std::process::exit(1);
+                    }
+                };
+                i += 1;
+            }
+            _ => {
+                eprintln!("{}", usage);
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    let result = find_repository(&Vec::<String>::new())
+        .and_then(|repo| collect_digest(&repo, period, Utc::now()));
+    match result {
+        Ok(digest) => match format {
+            DigestFormat::Markdown => print!("{}", render_markdown(&digest)),
+            DigestFormat::Html => print!("{}", render_html(&digest)),
+        },
+        Err(e) => {
+            crate::error::exit_with(&format!("Failed to generate digest: {}", e), &e);
+        }
+    }
+}
+
+/// The period of `period`'s length ending at `end`, and the one before it
+pub fn collect_digest(
+    repo: &Repository,
+    period: Period,
+    end: DateTime<Utc>,
+) -> Result<Digest, GitAiError> {
+    let start = end - period.length();
+    Ok(Digest {
+        period,
+        end,
+        current: collect_period(repo, start, end)?,
+        previous: collect_period(repo, start - period.length(), start)?,
+    })
+}
+
+fn collect_period(
+    repo: &Repository,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> Result<PeriodStats, GitAiError> {
+    let since = format!(
+        "--since={}",
+        (start + Duration::seconds(1)).to_rfc3339_opts(SecondsFormat::Secs, true)
+    );
+    let until = format!("--until={}", end.to_rfc3339_opts(SecondsFormat::Secs, true));
+    let log = repo.git(&["log", "--no-merges", "--format=%H", &since, &until, "HEAD"])?;
+
+    let shas: Vec<String> = log
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect();
+
+    let mut stats = PeriodStats::default();
+    for (sha, commit) in shas.iter().zip(stats_for_commits_stats(repo, &shas, &[])?) {
+        stats.commits += 1;
+        stats.added_lines += commit.git_diff_added_lines;
+        stats.ai_lines += commit.ai_additions.min(commit.git_diff_added_lines);
+        for (key, tool_stats) in &commit.tool_model_breakdown {
+            if tool_stats.ai_additions > 0 {
+                let tool = key.split_once("::").map_or(key.as_str(), |(tool, _)| tool);
+                *stats.tools.entry(tool.to_string()).or_default() += tool_stats.ai_additions;
+            }
+        }
+        if commit.ai_additions > 0 {
+            let parent = resolve_parent(repo, sha)?;
+            for (path, (ai, added)) in ai_lines_by_file(repo, &parent, sha)? {
+                let area = stats.areas.entry(area_of(&path)).or_default();
+                area.0 += ai;
+                area.1 += added;
+            }
+        }
+    }
+    Ok(stats)
+}
+
+/// The directory a file is grouped under, with a trailing `/`
+fn area_of(path: &str) -> String {
+    match path.rsplit_once('/') {
+        Some((dir, _)) => format!("{}/", dir),
+        None => "./".to_string(),
+    }
+}
+
+/// The parts of a digest, as plain text both renderings format
+struct Sections {
+    title: String,
+    share: String,
+    commits: String,
+    areas: Vec<String>,
+    new_tools: Option<String>,
+}
+
+fn sections(digest: &Digest) -> Sections {
+    let period = digest.period.name();
+    let current = &digest.current;
+    let previous = &digest.previous;
+
+    let mut share = format!(
+        "{}% of {} added lines written by AI",
+        current.ai_percent(),
+        current.added_lines
+    );
+    if previous.added_lines == 0 {
+        share.push_str(&format!(" (no lines added the previous {})", period));
+    } else {
+        let delta = current.ai_percent() as i64 - previous.ai_percent() as i64;
+        let change = match delta {
+            0 => "no change".to_string(),
+            delta => format!("{:+} points", delta),
+        };
+        share.push_str(&format!(
+            " (previous {}: {}%, {})",
+            period,
+            previous.ai_percent(),
+            change
+        ));
+    }
+
+    let mut areas: Vec<(&String, &(u32, u32))> = current
+        .areas
+        .iter()
+        .filter(|(_, (ai, _))| *ai > 0)
+        .collect();
+    areas.sort_by_key(|(_, (ai, _))| std::cmp::Reverse(*ai));
+    let areas = areas
+        .into_iter()
+        .take(TOP_AREAS)
+        .map(|(area, (ai, added))| {
+            format!(
+                "{}: {} AI lines ({}%)",
+                area,
+                ai,
+                (ai * 100).checked_div(*added).unwrap_or(0)
+            )
+        })
+        .collect();
+
+    let new_tools = digest.new_tools();
+    Sections {
+        title: format!(
+            "git-ai digest: {} ending {}",
+            period,
+            digest.end.format("%Y-%m-%d")
+        ),
+        share,
+        commits: format!(
+            "{} ({} the previous {})",
+            current.commits, previous.commits, period
+        ),
+        areas,
+        new_tools: (!new_tools.is_empty()).then(|| new_tools.join(", ")),
+    }
+}
+
+pub fn render_markdown(digest: &Digest) -> String {
+    let sections = sections(digest);
+    let mut output = format!("**{}**\n\n", sections.title);
+    output.push_str(&format!("**AI share:** {}\n", sections.share));
+    output.push_str(&format!("**Commits:** {}\n", sections.commits));
+    if !sections.areas.is_empty() {
+        output.push_str("\n**Most AI-touched areas:**\n");
+        for area in &sections.areas {
+            output.push_str(&format!("- {}\n", area));
+        }
+    }
+    if let Some(tools) = &sections.new_tools {
+        output.push_str(&format!("\n**New tools:** {}\n", tools));
+    }
+    output
+}
+
+pub fn render_html(digest: &Digest) -> String {
+    let sections = sections(digest);
+    let mut body = format!(
+        "<h1>{}</h1>\n<p><strong>AI share:</strong> {}</p>\n<p><strong>Commits:</strong> {}</p>\n",
+        escape(&sections.title),
+        escape(&sections.share),
+        escape(&sections.commits)
+    );
+    if !sections.areas.is_empty() {
+        body.push_str("<h2>Most AI-touched areas</h2>\n<ul>\n");
+        for area in &sections.areas {
+            body.push_str(&format!("<li>{}</li>\n", escape(area)));
+        }
+        body.push_str("</ul>\n");
+    }
+    if let Some(tools) = &sections.new_tools {
+        body.push_str(&format!(
+            "<p><strong>New tools:</strong> {}</p>\n",
+            escape(tools)
+        ));
+    }
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(&sections.title),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn digest() -> Digest {
+        let current = PeriodStats {
+            commits: 4,
+            added_lines: 200,
+            ai_lines: 90,
+            tools: [("claude".to_string(), 60), ("cursor".to_string(), 30)]
+                .into_iter()
+                .collect(),
+            areas: [
+                ("src/auth/".to_string(), (50, 60)),
+                ("./".to_string(), (0, 10)),
+                ("tests/".to_string(), (40, 130)),
+            ]
+            .into_iter()
+            .collect(),
+        };
+        let previous = PeriodStats {
+            commits: 3,
+            added_lines: 100,
+            ai_lines: 30,
+            tools: [("claude".to_string(), 30)].into_iter().collect(),
+            areas: BTreeMap::new(),
+        };
+        Digest {
+            period: Period::Week,
+            end: Utc.with_ymd_and_hms(2026, 10, 14, 9, 0, 0).unwrap(),
+            current,
+            previous,
+        }
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        assert_eq!(
+            render_markdown(&digest()),
+            "**git-ai digest: week ending 2026-10-14**
+
+**AI share:** 45% of 200 added lines written by AI (previous week: 30%, +15 points)
+**Commits:** 4 (3 the previous week)
+
+**Most AI-touched areas:**
+- src/auth/: 50 AI lines (83%)
+- tests/: 40 AI lines (30%)
+
+**New tools:** cursor
+"
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_without_previous_period() {
+        let mut digest = digest();
+        digest.previous = PeriodStats::default();
+        let markdown = render_markdown(&digest);
+        assert!(markdown.contains("lines written by AI (no lines added the previous week)"));
+        assert!(markdown.contains("**New tools:** claude, cursor"));
+    }
+
+    #[test]
+    fn test_render_html_escapes() {
+        let mut digest = digest();
+        digest.current.tools.insert("<x>".to_string(), 1);
+        let html = render_html(&digest);
+        assert!(html.contains("<li>src/auth/: 50 AI lines (83%)</li>"));
+        assert!(html.contains("<strong>New tools:</strong> &lt;x&gt;, cursor"));
+    }
+
+    #[test]
+    fn test_area_of() {
+        assert_eq!(area_of("src/auth/login.rs"), "src/auth/");
+        assert_eq!(area_of("README.md"), "./");
+    }
+}
@@ -0,0 +1,310 @@
+//! `git-ai editor-api`: the backend of the VS Code extension. Every mode prints one
+//! JSON object on stdout carrying `api_version`; fields are only ever added within a
+//! version. Failures print `{"api_version", "error": {"code", "message"}}` and exit 1.
+//!
+//! - `--ranges-for-file <path>`: AI-authored line ranges of the file as it is on disk,
+//!   committed and not
+//! - `--pending-summary`: what the next commit would be credited with
+//! - `--record-edit`: checkpoint the edit described on stdin, with the params of the
+//!   daemon's `checkpoint` method (`repo` defaults to the current directory)
+//! - `--subscribe [--since <cursor>] [--timeout <secs>]`: long-poll until HEAD moves or
+//!   a checkpoint is recorded, then print the new cursor
+//!
+//! The other modes return the `cursor` of the state they describe, so the extension
+//! can subscribe from it without missing changes made in between.
+
+use crate::authorship::paste_detection;
+use crate::authorship::virtual_attribution::VirtualAttributions;
+use crate::authorship::working_log::{AgentId, CheckpointKind};
+use crate::commands::blame::GitAiBlameOptions;
+use crate::commands::checkpoint;
+use crate::commands::daemon::{
+    CheckpointParams, INVALID_PARAMS, REPOSITORY_NOT_ALLOWED, RpcError, parse_params,
+};
+use crate::commands::status::{StatusOutput, collect_status};
+use crate::commands::wrap::author_name;
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::find_repository_in_path;
+use crate::git::repository::Repository;
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+pub const API_VERSION: u32 = 1;
+
+const DEFAULT_SUBSCRIBE_TIMEOUT: Duration = Duration::from_secs(60);
+const SUBSCRIBE_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+pub fn handle_editor_api(args: &[String]) {
+    let mut value = match run(args) {
+        Ok(value) => value,
+        Err(error) => {
+            println!(
+                "{}",
+                json!({"api_version": API_VERSION, "error": error.to_json()})
+            );
+            std::process::exit(1);
+        }
+    };
+    value["api_version"] = json!(API_VERSION);
+    println!("{}", value);
+}
+
+fn run(args: &[String]) -> Result<Value, RpcError> {
+    let usage = || {
+        RpcError::new(
+            INVALID_PARAMS,
+            "Usage: git-ai editor-api --ranges-for-file <path> | --pending-summary | --record-edit | --subscribe [--since <cursor>] [--timeout <secs>]",
+        )
+    };
+    let Some(mode) = args.first().map(String::as_str) else {
+        return Err(usage());
+    };
+    let (path, options) = match mode {
+        "--ranges-for-file" => match args.get(1) {
+            Some(path) => (Some(path.as_str()), &args[2..]),
+            None => return Err(usage()),
+        },
+        _ => (None, &args[1..]),
+    };
+
+    let mut since = None;
+    let mut timeout = DEFAULT_SUBSCRIBE_TIMEOUT;
+    let mut i = 0;
+    while i < options.len() {
+        match options[i].as_str() {
+            "--since" if mode == "--subscribe" && i + 1 < options.len() => {
+                since = Some(options[i + 1].clone());
+                i += 1;
+            }
+            "--timeout" if mode == "--subscribe" && i + 1 < options.len() => {
+                let secs = options[i + 1].parse().map_err(|_| {
+                    RpcError::new(
+                        INVALID_PARAMS,
+                        format!("Invalid timeout: {}", options[i + 1]),
+                    )
+                })?;
+                timeout = Duration::from_secs(secs);
+                i += 1;
+            }
+            arg => {
+                return Err(RpcError::new(
+                    INVALID_PARAMS,
+                    format!("Unknown editor-api argument: {}", arg),
+                ));
+            }
+        }
+        i += 1;
+    }
+
+    let current_dir = std::env::current_dir()
+        .map_err(GitAiError::from)?
+        .to_string_lossy()
+        .to_string();
+    match (mode, path) {
+        ("--ranges-for-file", Some(path)) => ranges_for_file(path),
+        ("--pending-summary", _) => pending_summary(&find_repository_in_path(&current_dir)?),
+        ("--record-edit", _) => {
+            let input = std::io::read_to_string(std::io::stdin()).map_err(GitAiError::from)?;
+            record_edit(&current_dir, &input)
+        }
+        ("--subscribe", _) => subscribe(&find_repository_in_path(&current_dir)?, since, timeout),
+        _ => Err(usage()),
+    }
+}
+
+/// Cheap fingerprint of the attribution state: HEAD and the working log's checkpoints.
+pub fn cursor(repo: &Repository) -> Result<String, GitAiError> {
+    let head = repo.head()?.target()?;
+    let working_log = repo.storage.working_log_for_base_commit(&head);
+    let (len, modified) = std::fs::metadata(working_log.dir.join("checkpoints.jsonl"))
+        .map(|meta| {
+            let modified = meta
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map(|since_epoch| since_epoch.as_nanos())
+                .unwrap_or_default();
+            (meta.len(), modified)
+        })
+        .unwrap_or_default();
+    Ok(format!("{}:{}:{}", head, len, modified))
+}
+
+/// Checkpoint uncommitted changes as human, so the working log describes the disk.
+fn checkpoint_working_tree(repo: &Repository) {
+    let _ = checkpoint::run(
+        repo,
+        &author_name(repo),
+        CheckpointKind::Human,
+        false,
+        false,
+        true,
+        None,
+        false,
+    );
+}
+
+fn ranges_for_file(path: &str) -> Result<Value, RpcError> {
+    let absolute = std::path::absolute(path).map_err(GitAiError::from)?;
+    let canonical = absolute.canonicalize().map_err(GitAiError::from)?;
+    let dir = canonical.parent().unwrap_or(Path::new("/"));
+    let repo = find_repository_in_path(&dir.to_string_lossy())?;
+    let relative = canonical
+        .strip_prefix(repo.canonical_workdir())
+        .map_err(|_| GitAiError::Generic(format!("{} is outside the repository", path)))?
+        .to_string_lossy()
+        .to_string();
+
+    checkpoint_working_tree(&repo);
+    let head = repo.head()?.target()?;
+
+    // Committed attribution; fails for files HEAD doesn't have
+    let mut options = GitAiBlameOptions::default();
+    #[allow(clippy::field_reassign_with_default)]
+    {
+        options.no_output = true;
+        options.use_prompt_hashes_as_names = true;
+    }
+    let (blamed_authors, committed_prompts) = repo.blame(&relative, &options).unwrap_or_default();
+
+    // Checkpoints of the file since HEAD cover all of its lines, committed ones included
+    let working = VirtualAttributions::from_just_working_log(repo.clone(), head, None)?;
+    let line_authors: BTreeMap<u32, String> = match working.get_line_attributions(&relative) {
+        Some(attributions) => attributions
+            .iter()
+            .flat_map(|attr| {
+                (attr.start_line..=attr.end_line).map(|line| (line, attr.author_id.clone()))
+            })
+            .collect(),
+        None => blamed_authors.into_iter().collect(),
+    };
+
+    let mut agents: HashMap<&str, (&AgentId, bool)> = HashMap::new();
+    for (id, records) in working.prompts() {
+        if let Some(record) = records.values().next() {
+            agents.insert(id, (&record.agent_id, false));
+        }
+    }
+    for (id, record) in &committed_prompts {
+        agents.insert(id, (&record.agent_id, true));
+    }
+
+    let ranges: Vec<Value> = group_ranges(&line_authors)
+        .into_iter()
+        .filter_map(|(start_line, end_line, author)| {
+            let (agent, committed) = agents.get(author.as_str())?;
+            Some(json!({
+                "start_line": start_line,
+                "end_line": end_line,
+                "tool": agent.tool,
+                "model": agent.model,
+                "prompt_id": author,
+                "committed": committed,
+            }))
+        })
+        .collect();
+    Ok(json!({"file": relative, "cursor": cursor(&repo)?, "ranges": ranges}))
+}
+
+/// Runs of consecutive lines with the same author, as `(start, end, author)`.
+fn group_ranges(line_authors: &BTreeMap<u32, String>) -> Vec<(u32, u32, String)> {
+    let mut ranges: Vec<(u32, u32, String)> = Vec::new();
+    for (&line, author) in line_authors {
+        match ranges.last_mut() {
+            Some((_, end, last)) if *end + 1 == line && last == author => *end = line,
+            _ => ranges.push((line, line, author.clone())),
+        }
+    }
+    ranges
+}
+
+fn pending_summary(repo: &Repository) -> Result<Value, RpcError> {
+    let stats = collect_status(repo, true)?
+        .unwrap_or_else(StatusOutput::default)
+        .stats;
+    let head = repo.head()?.target()?;
+    let working_log = repo.storage.working_log_for_base_commit(&head);
+    let checkpoints = working_log.read_all_checkpoints()?.len();
+    let pending_review = paste_detection::pending_checkpoints(&working_log)?.len();
+    Ok(json!({
+        "head": head,
+        "cursor": cursor(repo)?,
+        "checkpoints": checkpoints,
+        "pending_review": pending_review,
+        "stats": stats,
+    }))
+}
+
+fn record_edit(current_dir: &str, input: &str) -> Result<Value, RpcError> {
+    let mut params: Value = serde_json::from_str(input)
+        .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid edit: {}", e)))?;
+    if !params.is_object() {
+        return Err(RpcError::new(
+            INVALID_PARAMS,
+            "The edit must be a JSON object",
+        ));
+    }
+    if params.get("repo").is_none() {
+        params["repo"] = json!(current_dir);
+    }
+    let repo_path = params["repo"].as_str().unwrap_or(current_dir).to_string();
+    let params: CheckpointParams = parse_params(params)?;
+
+    let repo = find_repository_in_path(&repo_path)?;
+    if !Config::get().is_allowed_repository(&Some(repo.clone())) {
+        return Err(RpcError::new(
+            REPOSITORY_NOT_ALLOWED,
+            "Repository is excluded or not in allow_repositories list",
+        ));
+    }
+    let mut result = crate::commands::daemon::checkpoint(&repo, params)?;
+    result["cursor"] = json!(cursor(&repo)?);
+    Ok(result)
+}
+
+fn subscribe(
+    repo: &Repository,
+    since: Option<String>,
+    timeout: Duration,
+) -> Result<Value, RpcError> {
+    let since = match since {
+        Some(since) => since,
+        None => cursor(repo)?,
+    };
+    let deadline = Instant::now() + timeout;
+    loop {
+        let current = cursor(repo)?;
+        if current != since {
+            return Ok(json!({"event": "changed", "cursor": current}));
+        }
+        if Instant::now() >= deadline {
+            return Ok(json!({"event": "timeout", "cursor": current}));
+        }
+        std::thread::sleep(SUBSCRIBE_POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_ranges() {
+        let authors: BTreeMap<u32, String> = [(1, "a"), (2, "a"), (3, "human"), (4, "a"), (6, "a")]
+            .into_iter()
+            .map(|(line, author)| (line, author.to_string()))
+            .collect();
+        assert_eq!(
+            group_ranges(&authors),
+            vec![
+                (1, 2, "a".to_string()),
+                (3, 3, "human".to_string()),
+                (4, 4, "a".to_string()),
+                (6, 6, "a".to_string()),
+            ]
+        );
+    }
+}
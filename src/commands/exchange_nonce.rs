@@ -4,23 +4,67 @@
 //! OAuth credentials. It reads INSTALL_NONCE and API_BASE from environment
 //! variables and stores credentials in ~/.git-ai/internal/credentials.
 //!
+//! API_BASE must be git-ai's own API or one configured with `api_base_url` (globally
+//! or in an auth profile), so a tampered install page can't make git-ai log in to
+//! someone else's server. Transient failures are retried with backoff, and every
+//! exchange is recorded in ~/.git-ai/internal/auth-audit.jsonl.
+//!
 //! On failure, exits with code 1 silently so the install script can fall back
 //! to running `git-ai login`. Errors are recorded server-side for debugging.
+//! `--print-only` shows what would be exchanged, without contacting the server.
 
-use crate::auth::CredentialStore;
 use crate::auth::client::OAuthClient;
+use crate::auth::{CredentialStore, profiles};
+use crate::config::{self, Config};
+use glob::Pattern;
+use serde_json::json;
+use std::io::Write;
+use std::time::Duration;
+use url::Url;
+
+/// API bases a nonce may always be exchanged with
+#[cfg(not(debug_assertions))]
+const TRUSTED_API_BASES: &[&str] = &["https://usegitai.com", "https://*.usegitai.com"];
+#[cfg(debug_assertions)]
+const TRUSTED_API_BASES: &[&str] = &[
+    "https://usegitai.com",
+    "https://*.usegitai.com",
+    "http://localhost:*",
+    "http://127.0.0.1:*",
+];
+
+/// Waits before each retry of a failed exchange
+const RETRY_DELAYS: &[Duration] = &[
+    Duration::from_secs(1),
+    Duration::from_secs(2),
+    Duration::from_secs(4),
+];
 
 /// Handle the exchange-nonce command (internal - called by install scripts)
 ///
 /// Exits with code 1 on failure (silently) so install script can run `git-ai login`.
 /// Exits with code 0 on success.
-pub fn handle_exchange_nonce(_args: &[String]) {
+pub fn handle_exchange_nonce(args: &[String]) {
+    let print_only = match args {
+        [] => false,
+        [flag] if flag == "--print-only" => true,
+        _ => {
+            eprintln!("Usage: git-ai exchange-nonce [--print-only]");
+            std::process::exit(1);
+        }
+    };
+
     // Read from environment variables (injected by install script)
     let nonce = std::env::var("INSTALL_NONCE")
         .ok()
         .filter(|s| !s.is_empty());
     let api_base = std::env::var("API_BASE").ok().filter(|s| !s.is_empty());
 
+    if print_only {
+        print_exchange(nonce.as_deref(), api_base.as_deref());
+        return;
+    }
+
     // If no nonce provided, silently exit success (not an error - just means no auto-login)
     let Some(nonce) = nonce else {
         return;
@@ -33,17 +77,21 @@ pub fn handle_exchange_nonce(_args: &[String]) {
 
     // Perform the exchange - exit with failure code on error (silently)
     // The error is already recorded server-side, so no need to print anything
-    if exchange_nonce(&nonce, &api_base).is_err() {
+    let result = exchange_nonce(&nonce, &api_base);
+    audit(&api_base, &result);
+    if result.is_err() {
         std::process::exit(1);
     }
 }
 
 fn exchange_nonce(nonce: &str, api_base: &str) -> Result<(), String> {
+    trusted_pattern(api_base)?;
+
     // Create OAuth client with custom base URL
     let client = OAuthClient::with_base_url(api_base)?;
 
     // Exchange the nonce for credentials
-    let credentials = client.exchange_install_nonce(nonce)?;
+    let credentials = client.exchange_install_nonce(nonce, RETRY_DELAYS)?;
 
     // Store credentials
     let store = CredentialStore::new();
@@ -52,3 +100,128 @@ fn exchange_nonce(nonce: &str, api_base: &str) -> Result<(), String> {
     eprintln!("\x1b[32m✓ Logged in automatically\x1b[0m");
     Ok(())
 }
+
+/// API bases configured by the user, who presumably trusts them
+fn configured_api_bases() -> Vec<String> {
+    let config = Config::get();
+    std::iter::once(config.api_base_url().to_string())
+        .chain(
+            config
+                .auth_profiles()
+                .values()
+                .filter_map(|profile| profile.api_base_url.clone()),
+        )
+        .collect()
+}
+
+/// The allowlist entry `api_base` matches, compared by origin
+fn trusted_pattern(api_base: &str) -> Result<String, String> {
+    let origin = |url: &str| {
+        Url::parse(url)
+            .ok()
+            .filter(|url| url.username().is_empty() && url.password().is_none())
+            .map(|url| url.origin().ascii_serialization())
+    };
+    let Some(requested) = origin(api_base) else {
+        return Err(format!("Invalid API_BASE: {}", api_base));
+    };
+    let trusted = TRUSTED_API_BASES
+        .iter()
+        .find(|pattern| Pattern::new(pattern).is_ok_and(|pattern| pattern.matches(&requested)));
+    if let Some(pattern) = trusted {
+        return Ok(pattern.to_string());
+    }
+    configured_api_bases()
+        .into_iter()
+        .find(|base| origin(base).as_deref() == Some(requested.as_str()))
+        .ok_or_else(|| format!("API_BASE {} is not a trusted git-ai API", api_base))
+}
+
+/// Append where credentials came from, and whether they were stored, to the audit log
+fn audit(api_base: &str, result: &Result<(), String>) {
+    let Some(path) = config::internal_dir_path().map(|dir| dir.join("auth-audit.jsonl")) else {
+        return;
+    };
+    let line = json!({
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "source": "install_nonce",
+        "api_base": api_base,
+        "profile": profiles::active().as_deref().unwrap_or(profiles::DEFAULT_PROFILE),
+        "credential_store": CredentialStore::new().backend_name(),
+        "result": match result {
+            Ok(()) => "stored".to_string(),
+            Err(e) => e.clone(),
+        },
+    });
+    let _ = std::fs::create_dir_all(path.parent().unwrap_or(&path));
+    if let Ok(mut file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+    {
+        let _ = writeln!(file, "{}", line);
+    }
+}
+
+fn print_exchange(nonce: Option<&str>, api_base: Option<&str>) {
+    let Some(nonce) = nonce else {
+        println!("INSTALL_NONCE is not set; nothing would be exchanged.");
+        return;
+    };
+    println!("Nonce:            {}", redact(nonce));
+    let Some(api_base) = api_base else {
+        println!("API base:         (API_BASE is not set; the exchange would fail)");
+        return;
+    };
+    println!("API base:         {}", api_base);
+    match trusted_pattern(api_base) {
+        Ok(pattern) => println!("Trusted:          yes ({})", pattern),
+        Err(e) => println!("Trusted:          no ({})", e),
+    }
+    match OAuthClient::with_base_url(api_base).and_then(|client| client.token_endpoint()) {
+        Ok(endpoint) => println!("Token endpoint:   {}", endpoint),
+        Err(e) => println!("Token endpoint:   invalid ({})", e),
+    }
+    println!(
+        "Profile:          {}",
+        profiles::active()
+            .as_deref()
+            .unwrap_or(profiles::DEFAULT_PROFILE)
+    );
+    println!(
+        "Credential store: {}",
+        CredentialStore::new().backend_name()
+    );
+}
+
+/// Enough of a nonce to tell two apart, not enough to use it
+fn redact(secret: &str) -> String {
+    let shown: String = secret.chars().take(4).collect();
+    format!("{}… ({} characters)", shown, secret.chars().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trusted_pattern() {
+        assert_eq!(
+            trusted_pattern("https://usegitai.com").as_deref(),
+            Ok("https://usegitai.com")
+        );
+        assert_eq!(
+            trusted_pattern("https://eu.usegitai.com/").as_deref(),
+            Ok("https://*.usegitai.com")
+        );
+        assert!(trusted_pattern("https://usegitai.com.evil.dev").is_err());
+        assert!(trusted_pattern("https://evil.dev/usegitai.com").is_err());
+        assert!(trusted_pattern("https://usegitai.com@evil.dev").is_err());
+        assert!(trusted_pattern("not a url").is_err());
+    }
+
+    #[test]
+    fn test_redact() {
+        assert_eq!(redact("abcdef123456"), "abcd… (12 characters)");
+    }
+}
@@ -0,0 +1,367 @@
+//! `git-ai export`: attribution data in formats other tools can load.
+//!
+//! `--timeseries` writes CSV with one row per (bucket, author, tool): the lines the
+//! author's commits of that day, week or month added, and how many of those lines
+//! still survive at the exported revision. Human lines have the tool `human`. Survival
+//! is found by blaming the revision, and a surviving line keeps the tool its commit's
+//! authorship note credited it to.
+//!
+//! `--anonymize` replaces the authors with stable pseudonyms (see
+//! `authorship::anonymize`); buckets are already coarser than any commit's time.
+//!
+//! Commits are read from `git log` as it runs and aggregated a batch at a time, and
+//! the commits of a long history move to disk (see [`SpillMap`]), so memory stays
+//! bounded however many years are exported.
+
+use crate::authorship::anonymize::Anonymizer;
+use crate::authorship::authorship_log_serialization::AuthorshipLog;
+use crate::authorship::spill::SpillMap;
+use crate::authorship::stats::stats_for_commits_stats;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::refs::get_authorship;
+use crate::git::repository::{Repository, exec_git_lines};
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Tool of lines no AI was credited with
+const HUMAN_TOOL: &str = "human";
+
+/// Commits whose stats are computed together
+const BATCH_SIZE: usize = 256;
+
+/// Authorship logs kept while crediting the surviving lines
+const MAX_CACHED_LOGS: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bucket {
+    Day,
+    Week,
+    Month,
+}
+
+impl Bucket {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "day" => Some(Bucket::Day),
+            "week" => Some(Bucket::Week),
+            "month" => Some(Bucket::Month),
+            _ => None,
+        }
+    }
+
+    /// The bucket `date` falls in, named by its first day (weeks start on Monday)
+    pub fn start(&self, date: NaiveDate) -> String {
+        match self {
+            Bucket::Day => date.to_string(),
+            Bucket::Week => {
+                (date - Duration::days(date.weekday().num_days_from_monday() as i64)).to_string()
+            }
+            Bucket::Month => format!("{:04}-{:02}", date.year(), date.month()),
+        }
+    }
+}
+
+/// Lines added and still surviving
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineCounts {
+    pub added: u32,
+    pub surviving: u32,
+}
+
+pub type Timeseries = BTreeMap<(String, String, String), LineCounts>;
+
+#[derive(Clone, Serialize, Deserialize)]
+struct CommitInfo {
+    bucket: String,
+    author: String,
+}
+
+pub fn handle_export(args: &[String]) {
+    let usage = "Usage: git-ai export --timeseries [--bucket day|week|month] [--since <date>] [--anonymize] [<rev>]";
+    let mut timeseries = false;
+    let mut anonymize = false;
+    let mut bucket = Bucket::Week;
+    let mut since = None;
+    let mut rev = "HEAD".to_string();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--timeseries" => timeseries = true,
+            "--anonymize" => anonymize = true,
+            "--bucket" if i + 1 < args.len() => {
+                bucket = match Bucket::parse(&args[i + 1]) {
+                    Some(bucket) => bucket,
+                    None => {
+                        eprintln!("Invalid --bucket: {} (use day, week or month)", args[i + 1]);
+                        std::process::exit(1);
+                    }
+                };
+                i += 1;
+            }
+            "--since" if i + 1 < args.len() => {
+                since = Some(args[i + 1].clone());
+                i += 1;
+            }
+            arg if !arg.starts_with('-') => rev = arg.to_string(),
+            _ => {
+                eprintln!("{}", usage);
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+    if !timeseries {
+        eprintln!("{}", usage);
+        std::process::exit(1);
+    }
+
+    let result = find_repository(&Vec::<String>::new())
+        .and_then(|repo| collect_timeseries(&repo, &rev, since.as_deref(), bucket))
+        .and_then(|rows| match anonymize {
+            true => Ok(anonymized(rows, &Anonymizer::load()?)),
+            false => Ok(rows),
+        });
+    match result {
+        Ok(rows) => print!("{}", format_csv(&rows)),
+        Err(e) => {
+            crate::error::exit_with(&format!("Export failed: {}", e), &e);
+        }
+    }
+}
+
+pub fn collect_timeseries(
+    repo: &Repository,
+    rev: &str,
+    since: Option<&str>,
+    bucket: Bucket,
+) -> Result<Timeseries, GitAiError> {
+    let mut log_args = repo.global_args_for_exec();
+    log_args.extend([
+        "log".to_string(),
+        "--no-merges".to_string(),
+        "--format=%H%x09%aI%x09%an".to_string(),
+    ]);
+    log_args.extend(since.map(|date| format!("--since={}", date)));
+    log_args.push(rev.to_string());
+
+    let mut rows = Timeseries::new();
+    let mut commits: SpillMap<CommitInfo> = SpillMap::new();
+    let mut batch: Vec<(String, CommitInfo)> = Vec::with_capacity(BATCH_SIZE);
+    for line in exec_git_lines(&log_args)? {
+        let line = line?;
+        let mut fields = line.splitn(3, '\t');
+        let (Some(sha), Some(date), Some(author)) = (fields.next(), fields.next(), fields.next())
+        else {
+            continue;
+        };
+        let Ok(date) = DateTime::<FixedOffset>::parse_from_rfc3339(date) else {
+            continue;
+        };
+        let info = CommitInfo {
+            bucket: bucket.start(date.date_naive()),
+            author: author.to_string(),
+        };
+        batch.push((sha.to_string(), info));
+        if batch.len() == BATCH_SIZE {
+            add_commits(repo, &mut rows, &mut commits, std::mem::take(&mut batch))?;
+        }
+    }
+    add_commits(repo, &mut rows, &mut commits, batch)?;
+
+    let mut logs: HashMap<String, Option<AuthorshipLog>> = HashMap::new();
+    for path in repo.git(&["ls-tree", "-r", "--name-only", rev])?.lines() {
+        // Blame fails for binary files and the like, which have no lines to count
+        let Ok(porcelain) = repo.git(&["blame", "--line-porcelain", rev, "--", path]) else {
+            continue;
+        };
+        for (sha, orig_path, orig_line) in parse_line_porcelain(&porcelain) {
+            let Some(info) = commits.get(&sha)? else {
+                continue;
+            };
+            if logs.len() >= MAX_CACHED_LOGS && !logs.contains_key(&sha) {
+                logs.clear();
+            }
+            let log = logs
+                .entry(sha.clone())
+                .or_insert_with(|| get_authorship(repo, &sha));
+            let tool = log
+                .as_ref()
+                .and_then(|log| line_tool(log, &orig_path, orig_line))
+                .unwrap_or(HUMAN_TOOL);
+            rows.entry((info.bucket.clone(), info.author.clone(), tool.to_string()))
+                .or_default()
+                .surviving += 1;
+        }
+    }
+    Ok(rows)
+}
+
+/// `rows` with their authors replaced by pseudonyms
+pub fn anonymized(rows: Timeseries, anonymizer: &Anonymizer) -> Timeseries {
+    let mut anonymized = Timeseries::new();
+    for ((bucket, author, tool), counts) in rows {
+        let row = anonymized
+            .entry((bucket, anonymizer.author(&author), tool))
+            .or_default();
+        row.added += counts.added;
+        row.surviving += counts.surviving;
+    }
+    anonymized
+}
+
+/// Add the lines `batch`'s commits added to `rows`, and remember the commits
+fn add_commits(
+    repo: &Repository,
+    rows: &mut Timeseries,
+    commits: &mut SpillMap<CommitInfo>,
+    batch: Vec<(String, CommitInfo)>,
+) -> Result<(), GitAiError> {
+    let shas: Vec<String> = batch.iter().map(|(sha, _)| sha.clone()).collect();
+    for ((sha, info), stats) in batch
+        .into_iter()
+        .zip(stats_for_commits_stats(repo, &shas, &[])?)
+    {
+        let mut add = |tool: &str, lines: u32| {
+            if lines > 0 {
+                rows.entry((info.bucket.clone(), info.author.clone(), tool.to_string()))
+                    .or_default()
+                    .added += lines;
+            }
+        };
+        add(HUMAN_TOOL, stats.human_additions);
+        for (key, tool_stats) in &stats.tool_model_breakdown {
+            add(tool_of(key), tool_stats.ai_additions);
+        }
+        commits.insert(sha, info)?;
+    }
+    Ok(())
+}
+
+/// Tool of a `tool::model` breakdown key
+fn tool_of(key: &str) -> &str {
+    key.split_once("::").map_or(key, |(tool, _)| tool)
+}
+
+/// The AI tool `log` credits with a line of its commit's version of `path`
+fn line_tool<'a>(log: &'a AuthorshipLog, path: &str, line: u32) -> Option<&'a str> {
+    let attestation = log.attestations.iter().find(|a| a.file_path == path)?;
+    let entry = attestation
+        .entries
+        .iter()
+        .find(|entry| entry.line_ranges.iter().any(|range| range.contains(line)))?;
+    Some(
+        log.metadata
+            .prompts
+            .get(&entry.hash)?
+            .agent_id
+            .tool
+            .as_str(),
+    )
+}
+
+/// `(commit, path in that commit, line in that commit)` of each line `git blame
+/// --line-porcelain` describes
+fn parse_line_porcelain(porcelain: &str) -> Vec<(String, String, u32)> {
+    let mut lines = Vec::new();
+    let mut current: Option<(String, u32)> = None;
+    for line in porcelain.lines() {
+        if line.starts_with('\t') {
+            continue;
+        }
+        if let Some(path) = line.strip_prefix("filename ") {
+            if let Some((sha, orig_line)) = current.take() {
+                lines.push((sha, path.to_string(), orig_line));
+            }
+            continue;
+        }
+        let mut fields = line.split(' ');
+        if let (Some(sha), Some(orig_line)) = (fields.next(), fields.next())
+            && sha.len() == 40
+            && sha.bytes().all(|b| b.is_ascii_hexdigit())
+            && let Ok(orig_line) = orig_line.parse()
+        {
+            current = Some((sha.to_string(), orig_line));
+        }
+    }
+    lines
+}
+
+pub fn format_csv(rows: &Timeseries) -> String {
+    let mut csv = String::from("bucket,author,tool,added_lines,surviving_lines\n");
+    for ((bucket, author, tool), counts) in rows {
+        csv.push_str(&format!(
+            "{},{},{},{},{}\n",
+            csv_field(bucket),
+            csv_field(author),
+            csv_field(tool),
+            counts.added,
+            counts.surviving
+        ));
+    }
+    csv
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_start() {
+        let date = NaiveDate::from_ymd_opt(2026, 10, 14).unwrap();
+        assert_eq!(Bucket::Day.start(date), "2026-10-14");
+        assert_eq!(Bucket::Week.start(date), "2026-10-12");
+        assert_eq!(Bucket::Month.start(date), "2026-10");
+        assert_eq!(Bucket::parse("year"), None);
+    }
+
+    #[test]
+    fn test_parse_line_porcelain() {
+        let sha_a = "a".repeat(40);
+        let sha_b = "b".repeat(40);
+        let porcelain = format!(
+            "{a} 1 1 2\nauthor Ada\nsummary first\nfilename src/old.rs\n\tfn one() {{}}\n\
+             {a} 2 2\nauthor Ada\nsummary first\nfilename src/old.rs\n\tfn two() {{}}\n\
+             {b} 7 3 1\nauthor Bob\nsummary second\nfilename src/new.rs\n\tfilename x\n",
+            a = sha_a,
+            b = sha_b
+        );
+        assert_eq!(
+            parse_line_porcelain(&porcelain),
+            vec![
+                (sha_a.clone(), "src/old.rs".to_string(), 1),
+                (sha_a, "src/old.rs".to_string(), 2),
+                (sha_b, "src/new.rs".to_string(), 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_csv() {
+        let mut rows = Timeseries::new();
+        rows.insert(
+            (
+                "2026-10-12".to_string(),
+                "Doe, Jane".to_string(),
+                "claude".to_string(),
+            ),
+            LineCounts {
+                added: 10,
+                surviving: 7,
+            },
+        );
+        assert_eq!(
+            format_csv(&rows),
+            "bucket,author,tool,added_lines,surviving_lines\n2026-10-12,\"Doe, Jane\",claude,10,7\n"
+        );
+    }
+}
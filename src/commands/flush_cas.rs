@@ -5,6 +5,9 @@ use std::collections::HashMap;
 
 /// Spawn a background process to flush CAS objects to the server
 pub fn spawn_background_cas_flush() {
+    if crate::api::network::offline() {
+        return;
+    }
     use std::process::Command;
 
     if let Ok(exe) = crate::utils::current_git_ai_exe() {
@@ -36,8 +39,7 @@ pub fn handle_flush_cas(_args: &[String]) {
     let db = match InternalDatabase::global() {
         Ok(db) => db,
         Err(e) => {
-            eprintln!("Failed to access database: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Failed to access database: {}", e), &e);
         }
     };
 
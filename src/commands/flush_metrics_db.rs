@@ -11,6 +11,9 @@ const MAX_BATCH_SIZE: usize = 250;
 
 /// Spawn a background process to flush metrics DB
 pub fn spawn_background_metrics_db_flush() {
+    if crate::api::network::offline() {
+        return;
+    }
     use std::process::Command;
 
     if let Ok(exe) = crate::utils::current_git_ai_exe() {
@@ -1,6 +1,9 @@
+use crate::authorship::async_finalize::finalize_pending_commits;
 use crate::authorship::internal_db::InternalDatabase;
+use crate::authorship::languages::{self, LanguageLines};
 use crate::authorship::range_authorship;
-use crate::authorship::stats::stats_command;
+use crate::authorship::reconcile::reconcile_unfinalized_commits;
+use crate::authorship::stats::{self, stats_command, stream_commit_stats};
 use crate::authorship::working_log::{AgentId, CheckpointKind};
 use crate::commands;
 use crate::commands::checkpoint_agent::agent_presets::{
@@ -8,14 +11,18 @@ use crate::commands::checkpoint_agent::agent_presets::{
     ContinueCliPreset, CursorPreset, DroidPreset, GeminiPreset, GithubCopilotPreset,
 };
 use crate::commands::checkpoint_agent::agent_v1_preset::AgentV1Preset;
+use crate::commands::checkpoint_agent::aider_preset::AiderPreset;
+use crate::commands::checkpoint_agent::codex_preset::CodexPreset;
 use crate::commands::checkpoint_agent::opencode_preset::OpenCodePreset;
+use crate::commands::checkpoint_agent::{agent_registry, detectors};
 use crate::config;
 use crate::git::find_repository;
 use crate::git::find_repository_in_path;
-use crate::git::repository::{CommitRange, group_files_by_repository};
+use crate::git::repository::{CommitRange, Repository, group_files_by_repository};
 use crate::observability::wrapper_performance_targets::log_performance_for_checkpoint;
 use crate::observability::{self, log_message};
-use crate::utils::is_interactive_terminal;
+use crate::utils::{debug_log, is_interactive_terminal};
+use std::collections::BTreeMap;
 use std::env;
 use std::io::IsTerminal;
 use std::io::Read;
@@ -27,6 +34,58 @@ pub fn handle_git_ai(args: &[String]) {
         return;
     }
 
+    // `git-ai --profile <name> <command>` runs the command as that profile
+    if args[0] == "--profile" || args[0].starts_with("--profile=") {
+        let flag_len = if args[0] == "--profile" { 2 } else { 1 };
+        let (flag, rest) = args.split_at(flag_len.min(args.len()));
+        if let Err(e) = crate::auth::profiles::take_flag(flag) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return handle_git_ai(rest);
+    }
+
+    // `git-ai --log-level <level> <command>` takes precedence over GIT_AI_LOG
+    if args[0] == "--log-level" || args[0].starts_with("--log-level=") {
+        let (name, rest) = match args[0].strip_prefix("--log-level=") {
+            Some(name) => (Some(name), &args[1..]),
+            None => (
+                args.get(1).map(String::as_str),
+                args.get(2..).unwrap_or(&[]),
+            ),
+        };
+        match name.and_then(crate::logging::Level::parse) {
+            Some(level) => crate::logging::set_level(level),
+            None => {
+                eprintln!("--log-level requires one of: off, error, warn, info, debug, trace");
+                std::process::exit(1);
+            }
+        }
+        return handle_git_ai(rest);
+    }
+
+    // `git-ai --error-format json <command>` reports the error it fails with as JSON
+    if args[0] == "--error-format" || args[0].starts_with("--error-format=") {
+        let (name, rest) = match args[0].strip_prefix("--error-format=") {
+            Some(name) => (Some(name), &args[1..]),
+            None => (
+                args.get(1).map(String::as_str),
+                args.get(2..).unwrap_or(&[]),
+            ),
+        };
+        match name.and_then(crate::error::ErrorFormat::parse) {
+            Some(format) => crate::error::set_error_format(format),
+            None => {
+                eprintln!("--error-format requires one of: human, json");
+                std::process::exit(1);
+            }
+        }
+        return handle_git_ai(rest);
+    }
+    crate::logging::set_context(&format!("git-ai {}", args[0]));
+
+    crate::api::network::init_ca_bundle();
+
     let current_dir = env::current_dir().unwrap().to_string_lossy().to_string();
     let repository_option = find_repository_in_path(&current_dir).ok();
 
@@ -34,9 +93,35 @@ pub fn handle_git_ai(args: &[String]) {
 
     let allowed_repository = config.is_allowed_repository(&repository_option);
 
+    if crate::api::network::offline() {
+        match args[0].as_str() {
+            // Showing what would be exchanged needs no network
+            "exchange-nonce" if args[1..] == ["--print-only"] => {}
+            // Background syncs and installer steps quietly do nothing
+            "flush-logs" | "flush-cas" | "flush-metrics-db" | "exchange-nonce" => {
+                eprintln!(
+                    "{}: skipping {}",
+                    crate::api::network::OFFLINE_NOTE,
+                    args[0]
+                );
+                return;
+            }
+            "login" | "share" | "upgrade" => {
+                eprintln!(
+                    "{}: {} needs the network",
+                    crate::api::network::OFFLINE_NOTE,
+                    args[0]
+                );
+                std::process::exit(1);
+            }
+            _ => {}
+        }
+    }
+
     // Start DB warmup early for commands that need database access
     match args[0].as_str() {
-        "checkpoint" | "show-prompt" | "share" | "sync-prompts" | "flush-cas" => {
+        "checkpoint" | "show-prompt" | "share" | "sync-prompts" | "flush-cas"
+        | "finalize-commits" | "daemon" => {
             InternalDatabase::warmup();
         }
         _ => {}
@@ -93,6 +178,30 @@ pub fn handle_git_ai(args: &[String]) {
                 log_message("diff", "info", None)
             }
         }
+        "manifest" => {
+            commands::manifest::handle_manifest(&args[1..]);
+        }
+        "export" => {
+            commands::export::handle_export(&args[1..]);
+        }
+        "summary" => {
+            commands::summary::handle_summary(&args[1..]);
+        }
+        "bench" => {
+            commands::bench::handle_bench(&args[1..]);
+        }
+        "digest" => {
+            commands::digest::handle_digest(&args[1..]);
+        }
+        "graph" => {
+            commands::graph::handle_graph(&args[1..]);
+        }
+        "shortlog" => {
+            commands::shortlog::handle_shortlog(&args[1..]);
+        }
+        "report" => {
+            commands::report::handle_report(&args[1..]);
+        }
         "git-path" => {
             let config = config::Config::get();
             println!("{}", config.git_cmd());
@@ -105,8 +214,7 @@ pub fn handle_git_ai(args: &[String]) {
                 }
             }
             Err(e) => {
-                eprintln!("Install hooks failed: {}", e);
-                std::process::exit(1);
+                crate::error::exit_with(&format!("Install hooks failed: {}", e), &e);
             }
         },
         "uninstall-hooks" => match commands::install_hooks::run_uninstall(&args[1..]) {
@@ -116,16 +224,66 @@ pub fn handle_git_ai(args: &[String]) {
                 }
             }
             Err(e) => {
-                eprintln!("Uninstall hooks failed: {}", e);
-                std::process::exit(1);
+                crate::error::exit_with(&format!("Uninstall hooks failed: {}", e), &e);
             }
         },
+        "integrate" => {
+            if let Err(e) = commands::integrate::run(&args[1..]) {
+                crate::error::exit_with(&format!("Integrate failed: {}", e), &e);
+            }
+        }
+        "apply-ai-patch" => {
+            if !allowed_repository {
+                eprintln!(
+                    "Refusing to apply patch because repository is excluded or not in allow_repositories list"
+                );
+                std::process::exit(1);
+            }
+            commands::apply_ai_patch::handle_apply_ai_patch(&args[1..]);
+        }
+        "wrap" => {
+            // The wrapped command still runs in excluded repositories, just untracked
+            commands::wrap::handle_wrap(&args[1..], allowed_repository);
+        }
+        "daemon" => {
+            commands::daemon::handle_daemon(&args[1..]);
+        }
+        "lsp" => {
+            commands::lsp::handle_lsp(&args[1..]);
+        }
+        "editor-api" => {
+            commands::editor_api::handle_editor_api(&args[1..]);
+        }
+        "review-pending" => {
+            commands::review_pending::handle_review_pending(&args[1..]);
+        }
+        "hook" => {
+            commands::git_hooks::handle_hook(&args[1..]);
+        }
+        "pre-receive" => {
+            commands::git_hooks::handle_pre_receive(&args[1..]);
+        }
+        "import" => {
+            commands::import::handle_import(&args[1..]);
+        }
+        "ingest" => {
+            commands::ingest::handle_ingest(&args[1..]);
+        }
         "squash-authorship" => {
             commands::squash_authorship::handle_squash_authorship(&args[1..]);
         }
         "ci" => {
             commands::ci_handlers::handle_ci(&args[1..]);
         }
+        "ci-gate" => {
+            commands::ci_gate::handle_ci_gate(&args[1..]);
+        }
+        "policy" => {
+            commands::policy::handle_policy(&args[1..]);
+        }
+        "audit" => {
+            commands::audit::handle_audit(&args[1..]);
+        }
         "upgrade" => {
             commands::upgrade::run_with_args(&args[1..]);
         }
@@ -135,6 +293,17 @@ pub fn handle_git_ai(args: &[String]) {
         "flush-cas" => {
             commands::flush_cas::handle_flush_cas(&args[1..]);
         }
+        "finalize-commits" => {
+            // Spawned after commits when async_post_commit is enabled
+            match find_repository(&[]) {
+                Ok(repo) => {
+                    if let Err(e) = finalize_pending_commits(&repo, false) {
+                        debug_log(&format!("Background finalization failed: {}", e));
+                    }
+                }
+                Err(e) => debug_log(&format!("finalize-commits: {}", e)),
+            }
+        }
         "flush-metrics-db" => {
             commands::flush_metrics_db::handle_flush_metrics_db(&args[1..]);
         }
@@ -144,6 +313,9 @@ pub fn handle_git_ai(args: &[String]) {
         "logout" => {
             commands::logout::handle_logout(&args[1..]);
         }
+        "auth" => {
+            commands::auth::handle_auth(&args[1..]);
+        }
         "exchange-nonce" => {
             commands::exchange_nonce::handle_exchange_nonce(&args[1..]);
         }
@@ -176,25 +348,84 @@ pub fn handle_git_ai(args: &[String]) {
 fn print_help() {
     eprintln!("git-ai - git proxy with AI authorship tracking");
     eprintln!();
-    eprintln!("Usage: git-ai <command> [args...]");
+    eprintln!(
+        "Usage: git-ai [--profile <name>] [--log-level <level>] [--error-format <format>] <command> [args...]"
+    );
+    eprintln!();
+    eprintln!(
+        "  --profile <name>   Use a named auth profile (also GIT_AI_PROFILE or auth_profiles)"
+    );
+    eprintln!(
+        "                     Bind a repository with 'git config git-ai.profile <name>' and/or"
+    );
+    eprintln!(
+        "                     'git config git-ai.apiBaseUrl <url>' to refuse uploads elsewhere"
+    );
+    eprintln!("  --log-level <level>  off, error, warn, info, debug or trace (also GIT_AI_LOG)");
+    eprintln!(
+        "                     GIT_AI_LOG_FILE=<path> also appends the records to a file, hooks included"
+    );
+    eprintln!(
+        "  --error-format <format>  human or json (also GIT_AI_ERROR_FORMAT); json prints the"
+    );
+    eprintln!(
+        "                     error to stderr as {{\"error\":{{\"code\",\"message\"}}}} with a stable code"
+    );
     eprintln!();
     eprintln!("Commands:");
     eprintln!("  checkpoint         Checkpoint working changes and attribute author");
-    eprintln!("    Presets: claude, continue-cli, cursor, gemini, github-copilot, ai_tab, mock_ai");
+    eprintln!(
+        "    Presets: claude, codex, continue-cli, cursor, gemini, github-copilot, aider, ai_tab, mock_ai"
+    );
     eprintln!(
         "    --hook-input <json|stdin>   JSON payload required by presets, or 'stdin' to read from stdin"
     );
     eprintln!("    --show-working-log          Display current working log");
     eprintln!("    --reset                     Reset working log");
+    eprintln!("    aider [files...]            Files aider edited (run as aider's lint-cmd)");
     eprintln!("    mock_ai [pathspecs...]      Test preset accepting optional file pathspecs");
+    eprintln!("    <custom agent> [files...]   Agent declared under custom_agents in config");
+    eprintln!(
+        "    (no preset)                 Agent detected from the environment, otherwise human"
+    );
     eprintln!("  blame <file>       Git blame with AI authorship overlay");
     eprintln!("  diff <commit|range>  Show diff with AI authorship annotations");
     eprintln!("    <commit>              Diff from commit's parent to commit");
     eprintln!("    <commit1>..<commit2>  Diff between two commits");
+    eprintln!("  manifest [rev]     Print a provenance manifest of the files with AI content");
+    eprintln!("    --format spdx-like    SPDX-shaped JSON (the default)");
+    eprintln!("    --all-files           Also list files without AI content");
+    eprintln!("  export [rev]       Export attribution data for other tools");
+    eprintln!(
+        "    --timeseries          CSV of added and surviving lines per bucket, author, tool"
+    );
+    eprintln!("    --bucket <day|week|month>  Size of the time buckets (default: week)");
+    eprintln!("    --since <date>        Only commits since <date>");
+    eprintln!("    --anonymize           Replace authors with stable pseudonyms");
+    eprintln!("  summary            Markdown summary of a pull request's AI authorship");
+    eprintln!("    --range <base>..<head>  Commits to summarize (default: as for ci-gate)");
+    eprintln!("    --markdown            Output Markdown (the default)");
+    eprintln!("  digest             Compare AI authorship of this period with the previous one");
+    eprintln!("    --period <week|month>  Length of the periods (default: week)");
+    eprintln!("    --output <md|html>    Output Markdown (the default) or an HTML page");
+    eprintln!("  graph [rev]        SVG calendar of the last year's human and AI lines per day");
+    eprintln!("    -o, --output <file>   File to write the SVG to (default: stdout)");
+    eprintln!("  shortlog [range]   Commits, human and AI lines, and top tools per author");
+    eprintln!("  report [rev]       Write an HTML report of AI authorship at a revision");
+    eprintln!("                        Per-directory treemaps linking to shaded per-file views");
+    eprintln!("    --output <dir>        Directory to write to (default: git-ai-report)");
+    eprintln!("    --by-language         Add a table of AI share by language to the index");
+    eprintln!("    --anonymize           Hash file paths and leave out their contents");
     eprintln!("  stats [commit]     Show AI authorship statistics for a commit");
     eprintln!("    --json                 Output in JSON format");
+    eprintln!("    --jsonl                Stream one JSON object per commit of the range,");
+    eprintln!("                           or of HEAD's history");
+    eprintln!("    --since <date>         With --jsonl, only commits since <date>");
+    eprintln!("    --by-language          Group added lines by language (see language_overrides)");
     eprintln!("  status             Show uncommitted AI authorship status (debug)");
     eprintln!("    --json                 Output in JSON format");
+    eprintln!("    --exact                Recompute the attributions instead of using the");
+    eprintln!("                           summary kept by checkpoints");
     eprintln!("  show <rev|range>   Display authorship logs for a revision or range");
     eprintln!("  show-prompt <id>   Display a prompt record by its ID");
     eprintln!("    --commit <rev>        Look in a specific commit only");
@@ -209,6 +440,9 @@ fn print_help() {
         "                          Formats: '1d', '2h', '1w', Unix timestamp, ISO8601, YYYY-MM-DD"
     );
     eprintln!("    --workdir <path>      Only sync prompts from specific repository");
+    eprintln!("    --org                 Upload aggregated org metrics instead (see org_sync)");
+    eprintln!("    --preview             With --org, print the payload without uploading it");
+    eprintln!("    --anonymize           With --org, report repositories and paths as pseudonyms");
     eprintln!("  config             View and manage git-ai configuration");
     eprintln!("                        Show all config as formatted JSON");
     eprintln!("    <key>                 Show specific config value (supports dot notation)");
@@ -216,9 +450,72 @@ fn print_help() {
     eprintln!("    --add <key> <value>   Add to array or upsert into object");
     eprintln!("    unset <key>           Remove config value (reverts to default)");
     eprintln!("  install-hooks      Install git hooks for AI authorship tracking");
+    eprintln!("    --mode=<chain|standalone>  Chain existing repo hooks (default) or replace them");
+    eprintln!("    --global              Install git hooks for every repo via core.hooksPath");
+    eprintln!("    --template            With --global, use init.templateDir (new repos only)");
     eprintln!("  uninstall-hooks    Remove git-ai hooks from all detected tools");
+    eprintln!("    --global              Remove the machine-wide git hooks");
+    eprintln!("    --purge-data          Also delete this repo's local attribution data (.git/ai)");
+    eprintln!("  integrate <agent>  Set up one coding agent's hooks (e.g. claude-code)");
+    eprintln!("    --dry-run             Show the changes without writing them");
+    eprintln!("    --uninstall           Remove that agent's hooks instead");
+    eprintln!("  wrap -- <command...>  Run an agent and attribute what it changes to it");
+    eprintln!("    --tool <name>         Agent name recorded for the changes (required)");
+    eprintln!("    --model <name>        Model recorded for the changes");
+    eprintln!("  apply-ai-patch     Apply a unified diff from stdin as an agent's edit");
+    eprintln!("    --tool <name>         Agent name recorded for the changes (required)");
+    eprintln!("    --model <name>        Model recorded for the changes");
+    eprintln!("  daemon             Serve checkpoint, status and blame requests as JSON-RPC");
+    eprintln!(
+        "    --socket <path>       Unix socket to listen on (default: ~/.git-ai/daemon.sock)"
+    );
+    eprintln!(
+        "    --agent-socket <path> Socket for plain edit reports (default: ~/.git-ai/agent.sock)"
+    );
+    eprintln!(
+        "    --http <port>         Also serve POST /checkpoint and GET /metrics on 127.0.0.1:<port>"
+    );
+    eprintln!(
+        "    --http-token-file <path>  Bearer token for HTTP clients (default: ~/.git-ai/daemon.token)"
+    );
+    eprintln!("    --metrics-repo <path>  Also report this repository on GET /metrics");
+    eprintln!("    --no-precompute       Don't precompute status and blame while idle");
+    eprintln!("  bench [scenario...]  Time the attribution engine on reproducible scenarios");
+    eprintln!("    --samples <n>         Timed runs per scenario (default: 10)");
+    eprintln!("    --quick               Shrink the synthetic scenarios tenfold");
+    eprintln!("    --save-baseline <file>  Write the measurements to <file>");
+    eprintln!("    --baseline <file>     Exit with 1 if a scenario got slower than in <file>");
+    eprintln!("    --threshold <percent>  Slowdown allowed by --baseline (default: 10)");
+    eprintln!("  lsp                Language server showing AI attribution as inlay hints");
+    eprintln!("  editor-api         JSON backend for editor extensions");
+    eprintln!("    --ranges-for-file <path>  AI-authored line ranges of a file");
+    eprintln!("    --pending-summary         What the next commit would be credited with");
+    eprintln!("    --record-edit             Checkpoint the edit described on stdin");
+    eprintln!("    --subscribe [--since <cursor>] [--timeout <secs>]  Wait for a change");
+    eprintln!("  review-pending     List edits flagged as probably pasted AI output");
+    eprintln!("    confirm <id>... | --all  Keep them attributed to AI");
+    eprintln!("    dismiss <id>... | --all  Attribute them to you instead");
+    eprintln!("  hook <name> [args...]  Entry point for git hooks (e.g. prepare-commit-msg)");
+    eprintln!("  pre-receive        Server-side hook: reject pushes that violate push_policy");
+    eprintln!("    --require-attribution  Also reject commits with no note or AI trailers");
+    eprintln!("  import [range]     Synthesize authorship from AI commit trailers");
+    eprintln!("    --dry-run             Show what would be imported without writing notes");
+    eprintln!("    --jsonl               Stream one JSON object per imported commit");
+    eprintln!("  ingest --provider <p> <payload.json>  Attribute commits a cloud agent pushed");
     eprintln!("  ci                 Continuous integration utilities");
     eprintln!("    github                 GitHub CI helpers");
+    eprintln!("  ci-gate [<base>..<head>]  Check a pull request's commits against push_policy");
+    eprintln!("                        Base defaults to the CI target branch on origin");
+    eprintln!("    --annotations         Print AI-heavy hunks as JSON review annotations");
+    eprintln!("    --min-ai-percent <n>  AI share of a hunk's lines to annotate it (default: 50)");
+    eprintln!("    --approved-by <login>  A reviewer who approved the request (repeatable)");
+    eprintln!("  policy check [<base>..<head>]  Check commits against .git-ai/policy.toml");
+    eprintln!("  audit export       Hash-chained JSONL audit trail of a range's attribution,");
+    eprintln!("                        policy evaluations and review-pending reclassifications");
+    eprintln!("    --range <base>..<head>  Commits to export (default: as for ci-gate)");
+    eprintln!("    --output <file>       Append to <file>, continuing its chain");
+    eprintln!("    --sign                End with a seal signed with the git signing key");
+    eprintln!("  audit verify <file>  Check the chain of an exported audit trail");
     eprintln!("  squash-authorship  Generate authorship log for squashed commits");
     eprintln!(
         "    <base_branch> <new_sha> <old_sha>  Required: base branch, new commit SHA, old commit SHA"
@@ -237,7 +534,17 @@ fn print_help() {
     eprintln!("    next                  Get next prompt as JSON (iterator pattern)");
     eprintln!("    reset                 Reset iteration pointer to start");
     eprintln!("  login              Authenticate with Git AI");
-    eprintln!("  logout             Clear stored credentials");
+    eprintln!(
+        "    --device               Only print the URL and code, to finish in another browser"
+    );
+    eprintln!("    --token <key>          Store a service token for CI instead ('-' reads stdin);");
+    eprintln!("                           GIT_AI_TOKEN=<key> works without storing it");
+    eprintln!("    --profile <name>       Log in to a named profile (default: the active one)");
+    eprintln!("  logout             Revoke and clear stored credentials");
+    eprintln!("    --profile <name>       Log out of a named profile (default: the active one)");
+    eprintln!("    --all-profiles         Log out of every profile");
+    eprintln!("  auth status        Show the logged-in user, organization, and token expiry");
+    eprintln!("    --json                 Output in JSON format");
     eprintln!("  version, -v, --version     Print the git-ai version");
     eprintln!("  help, -h, --help           Show this help message");
     eprintln!();
@@ -441,6 +748,50 @@ fn handle_checkpoint(args: &[String]) {
                     }
                 }
             }
+            "codex" => {
+                match CodexPreset.run(AgentCheckpointFlags {
+                    hook_input: hook_input.clone(),
+                }) {
+                    Ok(agent_run) => {
+                        if agent_run.repo_working_dir.is_some() {
+                            repository_working_dir = agent_run.repo_working_dir.clone().unwrap();
+                        }
+                        agent_run_result = Some(agent_run);
+                    }
+                    Err(e) => {
+                        eprintln!("Codex preset error: {}", e);
+                        std::process::exit(0);
+                    }
+                }
+            }
+            "aider" => {
+                // Invoked as aider's lint-cmd, which appends the edited files
+                let hook_input = hook_input.clone().or_else(|| {
+                    let edited_filepaths: Vec<&String> = args[1..]
+                        .iter()
+                        .filter(|arg| !arg.starts_with("--"))
+                        .collect();
+                    Some(
+                        serde_json::json!({
+                            "cwd": repository_working_dir,
+                            "edited_filepaths": edited_filepaths,
+                        })
+                        .to_string(),
+                    )
+                });
+                match AiderPreset.run(AgentCheckpointFlags { hook_input }) {
+                    Ok(agent_run) => {
+                        if agent_run.repo_working_dir.is_some() {
+                            repository_working_dir = agent_run.repo_working_dir.clone().unwrap();
+                        }
+                        agent_run_result = Some(agent_run);
+                    }
+                    Err(e) => {
+                        eprintln!("Aider preset error: {}", e);
+                        std::process::exit(0);
+                    }
+                }
+            }
             "mock_ai" => {
                 let mock_agent_id = format!(
                     "ai-thread-{}",
@@ -488,6 +839,12 @@ fn handle_checkpoint(args: &[String]) {
         }
     }
 
+    // Checkpoints without a preset may still come from an agent declared in config,
+    // or one recognizable from the environment
+    if agent_run_result.is_none() && !reset && !show_working_log {
+        agent_run_result = detected_agent_run(args, &repository_working_dir);
+    }
+
     let final_working_dir = agent_run_result
         .as_ref()
         .and_then(|r| r.repo_working_dir.clone())
@@ -597,6 +954,10 @@ fn handle_checkpoint(args: &[String]) {
                     }
                 };
 
+                if let Err(e) = reconcile_unfinalized_commits(&repo) {
+                    debug_log(&format!("Failed to reconcile unfinalized commits: {}", e));
+                }
+
                 // Create a modified agent_run_result with only this repo's files
                 let repo_agent_result = agent_run_result.as_ref().map(|r| {
                     let mut modified = r.clone();
@@ -717,11 +1078,17 @@ fn handle_checkpoint(args: &[String]) {
     let default_user_name = match repo.config_get_str("user.name") {
         Ok(Some(name)) if !name.trim().is_empty() => name,
         _ => {
-            eprintln!("Warning: git user.name not configured. Using 'unknown' as author.");
+            crate::logging::warn(&crate::i18n::tr("hook.no_user_name"));
             "unknown".to_string()
         }
     };
 
+    // Checkpoints must land on top of any commit made behind our back, not in the
+    // working log that commit already consumed
+    if let Err(e) = reconcile_unfinalized_commits(&repo) {
+        debug_log(&format!("Failed to reconcile unfinalized commits: {}", e));
+    }
+
     let checkpoint_start = std::time::Instant::now();
     let agent_tool = agent_run_result.as_ref().map(|r| r.agent_id.tool.clone());
     let checkpoint_result = commands::checkpoint::run(
@@ -774,8 +1141,7 @@ fn handle_ai_blame(args: &[String]) {
     let repo = match find_repository_in_path(&current_dir) {
         Ok(repo) => repo,
         Err(e) => {
-            eprintln!("Failed to find repository: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Failed to find repository: {}", e), &e);
         }
     };
 
@@ -783,8 +1149,7 @@ fn handle_ai_blame(args: &[String]) {
     let (file_path, mut options) = match commands::blame::parse_blame_args(args) {
         Ok(result) => result,
         Err(e) => {
-            eprintln!("Failed to parse blame arguments: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Failed to parse blame arguments: {}", e), &e);
         }
     };
 
@@ -833,8 +1198,7 @@ fn handle_ai_blame(args: &[String]) {
     }
 
     if let Err(e) = repo.blame(&file_path, &options) {
-        eprintln!("Blame failed: {}", e);
-        std::process::exit(1);
+        crate::error::exit_with(&format!("Blame failed: {}", e), &e);
     }
 }
 
@@ -846,14 +1210,12 @@ fn handle_ai_diff(args: &[String]) {
     let repo = match find_repository_in_path(&current_dir) {
         Ok(repo) => repo,
         Err(e) => {
-            eprintln!("Failed to find repository: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Failed to find repository: {}", e), &e);
         }
     };
 
     if let Err(e) = commands::diff::handle_diff(&repo, args) {
-        eprintln!("Diff failed: {}", e);
-        std::process::exit(1);
+        crate::error::exit_with(&format!("Diff failed: {}", e), &e);
     }
 }
 
@@ -862,15 +1224,18 @@ fn handle_stats(args: &[String]) {
     let repo = match find_repository(&Vec::<String>::new()) {
         Ok(repo) => repo,
         Err(e) => {
-            eprintln!("Failed to find repository: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Failed to find repository: {}", e), &e);
         }
     };
     // Parse stats-specific arguments
     let mut json_output = false;
+    let mut jsonl = false;
+    let mut since: Option<String> = None;
     let mut commit_sha = None;
+    let mut range_arg: Option<String> = None;
     let mut commit_range: Option<CommitRange> = None;
     let mut ignore_patterns: Vec<String> = Vec::new();
+    let mut by_language = false;
 
     let mut i = 0;
     while i < args.len() {
@@ -879,6 +1244,22 @@ fn handle_stats(args: &[String]) {
                 json_output = true;
                 i += 1;
             }
+            "--by-language" => {
+                by_language = true;
+                i += 1;
+            }
+            "--jsonl" => {
+                jsonl = true;
+                i += 1;
+            }
+            "--since" => {
+                let Some(date) = args.get(i + 1) else {
+                    eprintln!("--since requires a date");
+                    std::process::exit(1);
+                };
+                since = Some(date.clone());
+                i += 2;
+            }
             "--ignore" => {
                 // Collect all arguments after --ignore until we hit another flag or commit SHA
                 // This supports shell glob expansion: `--ignore *.lock` expands to `--ignore Cargo.lock package.lock`
@@ -912,6 +1293,7 @@ fn handle_stats(args: &[String]) {
                     let arg = &args[i];
                     // Check if this is a commit range (contains "..")
                     if arg.contains("..") {
+                        range_arg = Some(arg.clone());
                         let parts: Vec<&str> = arg.split("..").collect();
                         if parts.len() == 2 {
                             match CommitRange::new_infer_refname(
@@ -925,8 +1307,10 @@ fn handle_stats(args: &[String]) {
                                     commit_range = Some(range);
                                 }
                                 Err(e) => {
-                                    eprintln!("Failed to create commit range: {}", e);
-                                    std::process::exit(1);
+                                    crate::error::exit_with(
+                                        &format!("Failed to create commit range: {}", e),
+                                        &e,
+                                    );
                                 }
                             }
                         } else {
@@ -945,6 +1329,44 @@ fn handle_stats(args: &[String]) {
         }
     }
 
+    if since.is_some() && !jsonl {
+        eprintln!("--since is only supported with --jsonl");
+        std::process::exit(1);
+    }
+    if by_language {
+        if jsonl {
+            eprintln!("--by-language can't be combined with --jsonl");
+            std::process::exit(1);
+        }
+        let result = language_stats(&repo, range_arg.as_deref(), commit_sha.as_deref());
+        match result {
+            Ok(languages) if json_output => {
+                println!("{}", serde_json::to_string(&languages).unwrap());
+            }
+            Ok(languages) => print!("{}", stats::write_language_stats_to_terminal(&languages)),
+            Err(e) => {
+                crate::error::exit_with(&format!("Stats failed: {}", e), &e);
+            }
+        }
+        return;
+    }
+    if jsonl {
+        // A range, one commit, or the history of HEAD
+        let mut rev_args: Vec<String> = since
+            .iter()
+            .map(|date| format!("--since={}", date))
+            .collect();
+        match (range_arg, commit_sha) {
+            (Some(range), _) => rev_args.push(range),
+            (None, Some(sha)) => rev_args.extend(["-1".to_string(), sha]),
+            (None, None) => rev_args.push("HEAD".to_string()),
+        }
+        if let Err(e) = stream_commit_stats(&repo, &rev_args, &ignore_patterns) {
+            crate::error::exit_with(&format!("Stats failed: {}", e), &e);
+        }
+        return;
+    }
+
     // Handle commit range if detected
     if let Some(range) = commit_range {
         match range_authorship::range_authorship(range, false, &ignore_patterns) {
@@ -957,8 +1379,7 @@ fn handle_stats(args: &[String]) {
                 }
             }
             Err(e) => {
-                eprintln!("Range authorship failed: {}", e);
-                std::process::exit(1);
+                crate::error::exit_with(&format!("Range authorship failed: {}", e), &e);
             }
         }
         return;
@@ -977,6 +1398,87 @@ fn handle_stats(args: &[String]) {
     }
 }
 
+/// AI-authored and added lines of a range or commit (HEAD by default), by language
+fn language_stats(
+    repo: &Repository,
+    range: Option<&str>,
+    commit: Option<&str>,
+) -> Result<BTreeMap<String, LanguageLines>, crate::error::GitAiError> {
+    let (from, to) = match range.and_then(|range| range.split_once("..")) {
+        Some((from, to)) => (from.to_string(), to.to_string()),
+        None => {
+            let to = repo
+                .git(&["rev-parse", "--verify", commit.unwrap_or("HEAD")])?
+                .trim()
+                .to_string();
+            (commands::diff::resolve_parent(repo, &to)?, to)
+        }
+    };
+    let files = commands::diff::ai_lines_by_file(repo, &from, &to)?;
+    Ok(languages::group_by_language(
+        files
+            .iter()
+            .map(|(path, (ai, added))| (path.as_str(), *ai, *added)),
+        config::Config::get().language_overrides(),
+    ))
+}
+
+/// AI checkpoint for a custom agent named by the first argument, or for an agent
+/// detected from the environment when no preset is given. Without explicit files,
+/// every changed or untracked file is attributed to it.
+fn detected_agent_run(args: &[String], working_dir: &str) -> Option<AgentRunResult> {
+    let config = config::Config::get();
+    let agents = config.custom_agents();
+    let env_value = |var: &String| env::var(var).ok().filter(|value| !value.is_empty());
+
+    let (agent, paths) = match args.first() {
+        Some(name) if !name.starts_with("--") => {
+            let agent = agent_registry::find_agent(agents, name)?;
+            let paths = args[1..]
+                .iter()
+                .filter(|arg| !arg.starts_with("--"))
+                .cloned()
+                .collect::<Vec<_>>();
+            // The detection variable usually identifies the agent's session
+            let session_id = agent.detect_env.iter().find_map(env_value);
+            ((agent.name.clone(), None, session_id), paths)
+        }
+        _ => {
+            let detectors =
+                detectors::detectors(agents, config.get_feature_flags().agent_detection);
+            let agent = detectors::detect(
+                &detectors,
+                &detectors::DetectionContext::current(working_dir),
+            )?;
+            ((agent.tool, agent.model, agent.session_id), Vec::new())
+        }
+    };
+    let (tool, model, session_id) = agent;
+
+    let files = if paths.is_empty() {
+        let repo = find_repository_in_path(working_dir).ok()?;
+        let mut files: Vec<String> = repo.get_worktree_filenames().ok()?.into_iter().collect();
+        files.sort();
+        files
+    } else {
+        paths
+    };
+    let mut result = commands::wrap::run_result(
+        &tool,
+        model.as_deref().unwrap_or("unknown"),
+        CheckpointKind::AiAgent,
+        files,
+    );
+    if let Some(session_id) = session_id {
+        result.agent_id.id = session_id;
+    }
+    debug_log(&format!(
+        "Attributing checkpoint to detected agent {}",
+        result.agent_id.tool
+    ));
+    Some(result)
+}
+
 fn get_all_files_for_mock_ai(working_dir: &str) -> Vec<String> {
     // Find the git repository
     let repo = match find_repository_in_path(working_dir) {
@@ -1012,30 +1514,32 @@ fn handle_show_transcript(args: &[String]) {
         "claude" => match ClaudePreset::transcript_and_model_from_claude_code_jsonl(path_or_id) {
             Ok((transcript, model)) => Ok((transcript, model)),
             Err(e) => {
-                eprintln!("Error loading Claude transcript: {}", e);
-                std::process::exit(1);
+                crate::error::exit_with(&format!("Error loading Claude transcript: {}", e), &e);
             }
         },
         "gemini" => match GeminiPreset::transcript_and_model_from_gemini_json(path_or_id) {
             Ok((transcript, model)) => Ok((transcript, model)),
             Err(e) => {
-                eprintln!("Error loading Gemini transcript: {}", e);
-                std::process::exit(1);
+                crate::error::exit_with(&format!("Error loading Gemini transcript: {}", e), &e);
             }
         },
         "continue-cli" => match ContinueCliPreset::transcript_from_continue_json(path_or_id) {
             Ok(transcript) => Ok((transcript, None)),
             Err(e) => {
-                eprintln!("Error loading Continue CLI transcript: {}", e);
-                std::process::exit(1);
+                crate::error::exit_with(
+                    &format!("Error loading Continue CLI transcript: {}", e),
+                    &e,
+                );
             }
         },
         "github-copilot" => {
             match GithubCopilotPreset::transcript_and_model_from_copilot_session_json(path_or_id) {
                 Ok((transcript, model, _file_paths)) => Ok((transcript, model)),
                 Err(e) => {
-                    eprintln!("Error loading GitHub Copilot transcript: {}", e);
-                    std::process::exit(1);
+                    crate::error::exit_with(
+                        &format!("Error loading GitHub Copilot transcript: {}", e),
+                        &e,
+                    );
                 }
             }
         }
@@ -1046,8 +1550,7 @@ fn handle_show_transcript(args: &[String]) {
                 std::process::exit(1);
             }
             Err(e) => {
-                eprintln!("Error loading Cursor transcript: {}", e);
-                std::process::exit(1);
+                crate::error::exit_with(&format!("Error loading Cursor transcript: {}", e), &e);
             }
         },
         _ => {
@@ -1078,8 +1581,7 @@ fn handle_show_transcript(args: &[String]) {
             println!("{}", transcript_json);
         }
         Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Error: {}", e), &e);
         }
     }
 }
@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 
+use crate::authorship::reconcile::reconcile_unfinalized_commits;
 use crate::authorship::virtual_attribution::VirtualAttributions;
 use crate::commands::hooks::checkout_hooks;
 use crate::commands::hooks::cherry_pick_hooks;
@@ -38,6 +39,10 @@ use std::time::Instant;
 #[cfg(unix)]
 static CHILD_PGID: AtomicI32 = AtomicI32::new(0);
 
+/// Set on git processes spawned by the proxy so repo hooks (`git-ai hook ...`) can tell
+/// that the proxy will already handle the operation in its post-command hooks.
+pub const PROXY_ACTIVE_ENV: &str = "GIT_AI_PROXY_ACTIVE";
+
 /// Error type for hook panics
 #[derive(Debug)]
 struct HookPanicError(String);
@@ -92,6 +97,9 @@ pub struct CommandHooksContext {
     /// VirtualAttributions captured before a pull --rebase --autostash operation.
     /// Used to preserve uncommitted AI attributions that git's internal stash would lose.
     pub stashed_va: Option<VirtualAttributions>,
+    /// Remote name and its remote-tracking tips before a fetch/pull, used to find newly
+    /// fetched commits whose trailers can be imported.
+    pub pre_fetch_remote_tips: Option<(String, Vec<String>)>,
 }
 
 pub fn handle_git(args: &[String]) {
@@ -104,6 +112,10 @@ pub fn handle_git(args: &[String]) {
     }
 
     let mut parsed_args = parse_git_cli_args(args);
+    crate::logging::set_context(&format!(
+        "git {}",
+        parsed_args.command.as_deref().unwrap_or_default()
+    ));
 
     let mut repository_option = find_repository(&parsed_args.global_args).ok();
 
@@ -138,6 +150,7 @@ pub fn handle_git(args: &[String]) {
             stash_sha: None,
             push_authorship_handle: None,
             stashed_va: None,
+            pre_fetch_remote_tips: None,
         };
 
         let repository = repository_option.as_mut().unwrap();
@@ -309,6 +322,11 @@ fn run_pre_command_hooks(
     repository: &mut Repository,
 ) {
     let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        // Finalize commits made without git-ai since the last invocation (GUI clients)
+        if let Err(e) = reconcile_unfinalized_commits(repository) {
+            debug_log(&format!("Failed to reconcile unfinalized commits: {}", e));
+        }
+
         // Pre-command hooks
         match parsed_args.command.as_deref() {
             Some("commit") => {
@@ -336,6 +354,8 @@ fn run_pre_command_hooks(
             Some("fetch") => {
                 command_hooks_context.fetch_authorship_handle =
                     fetch_hooks::fetch_pull_pre_command_hook(parsed_args, repository);
+                command_hooks_context.pre_fetch_remote_tips =
+                    fetch_hooks::capture_remote_tips(parsed_args, repository);
             }
             Some("pull") => {
                 fetch_hooks::pull_pre_command_hook(parsed_args, repository, command_hooks_context);
@@ -347,6 +367,9 @@ fn run_pre_command_hooks(
                     stash_hooks::pre_stash_hook(parsed_args, repository, command_hooks_context);
                 }
             }
+            Some("merge") => {
+                merge_hooks::pre_merge_hook(repository);
+            }
             Some("checkout") => {
                 checkout_hooks::pre_checkout_hook(parsed_args, repository, command_hooks_context);
             }
@@ -495,6 +518,7 @@ fn proxy_to_git(args: &[String], exit_on_completion: bool) -> std::process::Exit
 
             let mut cmd = Command::new(config::Config::get().git_cmd());
             cmd.args(args);
+            cmd.env(PROXY_ACTIVE_ENV, "1");
             unsafe {
                 let setpgid_flag = should_setpgid;
                 cmd.pre_exec(move || {
@@ -515,6 +539,7 @@ fn proxy_to_git(args: &[String], exit_on_completion: bool) -> std::process::Exit
         {
             let mut cmd = Command::new(config::Config::get().git_cmd());
             cmd.args(args);
+            cmd.env(PROXY_ACTIVE_ENV, "1");
 
             #[cfg(windows)]
             {
@@ -562,13 +587,13 @@ fn proxy_to_git(args: &[String], exit_on_completion: bool) -> std::process::Exit
                             uninstall_forwarding_handlers();
                         }
                     }
-                    eprintln!("Failed to wait for git process: {}", e);
+                    crate::logging::error(&format!("Failed to wait for git process: {}", e));
                     std::process::exit(1);
                 }
             }
         }
         Err(e) => {
-            eprintln!("Failed to execute git command: {}", e);
+            crate::logging::error(&format!("Failed to execute git command: {}", e));
             std::process::exit(1);
         }
     }
@@ -585,13 +610,13 @@ fn proxy_to_git(args: &[String], exit_on_completion: bool) -> std::process::Exit
                     return status;
                 }
                 Err(e) => {
-                    eprintln!("Failed to wait for git process: {}", e);
+                    crate::logging::error(&format!("Failed to wait for git process: {}", e));
                     std::process::exit(1);
                 }
             }
         }
         Err(e) => {
-            eprintln!("Failed to execute git command: {}", e);
+            crate::logging::error(&format!("Failed to execute git command: {}", e));
             std::process::exit(1);
         }
     }
@@ -0,0 +1,544 @@
+//! Entry points for real git hooks (`.git/hooks/*` or `core.hooksPath`).
+//!
+//! Invoked as `git-ai hook <hook-name> [hook args...]`. Hooks must never block the
+//! git operation they run inside, so failures are logged and the process exits 0.
+//!
+//! History-rewriting hooks mirror what the proxy does in its post-command hooks, so
+//! they are skipped when git was spawned by the proxy (see [`PROXY_ACTIVE_ENV`]).
+
+use crate::authorship::commit_trailers::{
+    append_trailers, build_trailers, insert_summary_comment, pending_line_summary,
+    pending_trailer_summary, summary_comment,
+};
+use crate::authorship::policy::Policy;
+use crate::authorship::push_policy::{
+    commits_to_push, commits_to_receive, evaluate_commits, format_rejection_report,
+    format_violation_report, parse_pushed_refs, parse_received_refs,
+};
+use crate::authorship::rebase_authorship::reconstruct_working_log_after_reset;
+use crate::commands::git_handlers::PROXY_ACTIVE_ENV;
+use crate::commands::hooks::checkout_hooks::prune_attributions_for_clean_files;
+use crate::commands::hooks::commit_hooks::get_commit_default_author;
+use crate::commands::hooks::merge_hooks::reconcile_working_log_after_merge;
+use crate::commands::hooks::reset_hooks::is_ancestor;
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::ref_state::RefRecord;
+use crate::git::refs::get_reference_as_authorship_log_v3;
+use crate::git::repository::Repository;
+use crate::git::repository::{exec_git, from_bare_repository};
+use crate::git::rewrite_log::{MergeSquashEvent, RebaseCompleteEvent, RewriteLogEvent};
+use crate::i18n::tr_args;
+use crate::mdm::git_hooks::{
+    GitHookAction, HOOK_SCRIPT_ENV, HOOK_VERSION_ENV, refresh_git_hooks, shim_is_compatible,
+};
+use crate::mdm::utils::get_current_binary_path;
+use crate::utils::debug_log;
+use std::io::Read;
+use std::path::Path;
+
+pub fn handle_hook(args: &[String]) {
+    let Some(hook_name) = args.first() else {
+        eprintln!("Error: hook requires a hook name");
+        eprintln!("Usage: git-ai hook <hook-name> [args...]");
+        std::process::exit(1);
+    };
+    let hook_args = &args[1..];
+    crate::logging::set_context(&format!("hook {}", hook_name));
+
+    check_shim_version();
+
+    let result = match hook_name.as_str() {
+        "prepare-commit-msg" | "commit-msg" => handle_commit_msg_hook(hook_name, hook_args),
+        "post-rewrite" if !proxy_active() => handle_post_rewrite_hook(hook_args),
+        "post-merge" if !proxy_active() => handle_post_merge_hook(hook_args),
+        "post-checkout" if !proxy_active() => handle_post_checkout_hook(hook_args),
+        "pre-push" => handle_pre_push_hook(hook_args),
+        "pre-receive" => {
+            handle_pre_receive(hook_args);
+            Ok(())
+        }
+        "reference-transaction" => handle_reference_transaction_hook(hook_args),
+        _ => {
+            debug_log(&format!("Ignoring unsupported git hook: {}", hook_name));
+            Ok(())
+        }
+    };
+
+    if let Err(e) = result {
+        crate::logging::info(&format!("git hook {} failed: {}", hook_name, e));
+    }
+}
+
+/// Shims record the git-ai version that wrote them. After an upgrade that changes the
+/// shim layout, rewrite them in place so later hooks run the current form; if that is
+/// not possible, tell the user how to fix it instead of failing obscurely.
+fn check_shim_version() {
+    let Ok(shim_version) = std::env::var(HOOK_VERSION_ENV) else {
+        return;
+    };
+    let cli_version = env!("CARGO_PKG_VERSION");
+    if shim_is_compatible(&shim_version, cli_version) {
+        return;
+    }
+
+    let refreshed = std::env::var(HOOK_SCRIPT_ENV)
+        .map_err(|e| GitAiError::Generic(e.to_string()))
+        .and_then(|script| {
+            let hooks_dir = Path::new(&script)
+                .parent()
+                .ok_or_else(|| GitAiError::Generic(format!("Invalid hook path: {}", script)))?
+                .to_path_buf();
+            refresh_git_hooks(&hooks_dir, &get_current_binary_path()?)
+        });
+    match refreshed {
+        Ok(changes) => {
+            let updated = changes
+                .iter()
+                .filter(|c| c.action != GitHookAction::Unchanged)
+                .count();
+            eprintln!(
+                "{}",
+                tr_args(
+                    "hook.shims_updated",
+                    &[
+                        ("count", &updated.to_string()),
+                        ("old", &shim_version),
+                        ("new", cli_version),
+                    ],
+                )
+            );
+        }
+        Err(e) => {
+            debug_log(&format!("Failed to refresh git hooks: {}", e));
+            eprintln!(
+                "{}",
+                tr_args(
+                    "hook.shims_outdated",
+                    &[("old", &shim_version), ("new", cli_version)],
+                )
+            );
+        }
+    }
+}
+
+/// `prepare-commit-msg <file> [source [sha]]` and `commit-msg <file>`.
+///
+/// Both hooks append the same (idempotent) trailers: prepare-commit-msg covers `-m`/`-F`,
+/// and commit-msg covers messages written in the editor.
+fn handle_commit_msg_hook(hook_name: &str, hook_args: &[String]) -> Result<(), GitAiError> {
+    let config = Config::get();
+    let source = hook_args.get(1).map(String::as_str);
+    // Comments are only stripped from messages that go through the editor
+    let add_summary = config.commit_summary_enabled()
+        && hook_name == "prepare-commit-msg"
+        && matches!(source, None | Some("template"));
+    if !config.commit_trailers_enabled() && !add_summary {
+        return Ok(());
+    }
+
+    let Some(message_file) = hook_args.first() else {
+        return Err(GitAiError::Generic(format!(
+            "{} requires the commit message file",
+            hook_name
+        )));
+    };
+
+    // Merge and squash messages are generated by git and describe someone else's work
+    if matches!(source, Some("merge") | Some("squash")) {
+        return Ok(());
+    }
+
+    let repo = find_repository(&[])?;
+    let message = std::fs::read_to_string(message_file)?;
+    let mut updated = message.clone();
+
+    if config.commit_trailers_enabled() {
+        updated = append_trailers(&updated, &build_trailers(&pending_trailer_summary(&repo)?));
+    }
+    if add_summary && let Some(comment) = summary_comment(&pending_line_summary(&repo)?) {
+        updated = insert_summary_comment(&updated, &comment);
+    }
+
+    if updated != message {
+        std::fs::write(message_file, updated)?;
+    }
+    Ok(())
+}
+
+fn proxy_active() -> bool {
+    std::env::var(PROXY_ACTIVE_ENV).is_ok_and(|v| v == "1")
+}
+
+/// Parse post-rewrite stdin: one `<old-sha> <new-sha> [extra]` line per rewritten commit.
+fn parse_rewrite_mappings(input: &str) -> Vec<(String, String)> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some((parts.next()?.to_string(), parts.next()?.to_string()))
+        })
+        .collect()
+}
+
+/// `post-rewrite <amend|rebase>`, with the old→new commit mapping on stdin.
+///
+/// Carries authorship logs (and the uncommitted working log) over to the rewritten
+/// commits, the same way the proxy does after `commit --amend` and `rebase`.
+fn handle_post_rewrite_hook(hook_args: &[String]) -> Result<(), GitAiError> {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    let mappings = parse_rewrite_mappings(&input);
+    if mappings.is_empty() {
+        return Ok(());
+    }
+
+    let mut repo = find_repository(&[])?;
+    let commit_author = get_commit_default_author(&repo, &[]);
+
+    match hook_args.first().map(String::as_str) {
+        Some("amend") => {
+            for (original, amended) in mappings {
+                if get_reference_as_authorship_log_v3(&repo, &amended).is_ok() {
+                    continue;
+                }
+                repo.handle_rewrite_log_event(
+                    RewriteLogEvent::commit_amend(original, amended),
+                    commit_author.clone(),
+                    true,
+                    true,
+                );
+            }
+        }
+        Some("rebase") => {
+            // Squash/fixup map several original commits onto one new commit
+            let mut original_commits: Vec<String> = Vec::new();
+            let mut new_commits: Vec<String> = Vec::new();
+            for (original, new) in mappings {
+                if !original_commits.contains(&original) {
+                    original_commits.push(original);
+                }
+                if !new_commits.contains(&new) {
+                    new_commits.push(new);
+                }
+            }
+            let original_head = original_commits.last().cloned().unwrap_or_default();
+            let new_head = new_commits.last().cloned().unwrap_or_default();
+
+            repo.handle_rewrite_log_event(
+                RewriteLogEvent::rebase_complete(RebaseCompleteEvent::new(
+                    original_head.clone(),
+                    new_head.clone(),
+                    false,
+                    original_commits,
+                    new_commits,
+                )),
+                commit_author,
+                true,
+                true,
+            );
+
+            // Autostashed changes come back on top of the rewritten branch
+            let _ = repo.storage.rename_working_log(&original_head, &new_head);
+        }
+        other => {
+            debug_log(&format!("post-rewrite: unknown rewrite kind {:?}", other));
+        }
+    }
+    Ok(())
+}
+
+/// Source commit of a `merge --squash`: git lists the squashed commits in
+/// `SQUASH_MSG`, newest first.
+fn squash_source_from_message(squash_msg: &str) -> Option<String> {
+    squash_msg
+        .lines()
+        .find_map(|line| line.strip_prefix("commit "))
+        .map(|sha| sha.trim().to_string())
+}
+
+/// `post-merge <is-squash>`, run after `git merge` and after the merge step of `git pull`.
+fn handle_post_merge_hook(hook_args: &[String]) -> Result<(), GitAiError> {
+    let mut repo = find_repository(&[])?;
+    let new_head = repo.head()?.target()?;
+
+    if hook_args.first().map(String::as_str) == Some("1") {
+        // HEAD doesn't move on --squash; the squashed changes are staged for the next commit
+        let squash_msg_path = repo.path().join("SQUASH_MSG");
+        let Some(source_head) = std::fs::read_to_string(&squash_msg_path)
+            .ok()
+            .and_then(|msg| squash_source_from_message(&msg))
+        else {
+            debug_log("post-merge: no SQUASH_MSG, skipping squash handling");
+            return Ok(());
+        };
+        let base_branch = repo.head()?.name().unwrap_or("HEAD").to_string();
+        let commit_author = get_commit_default_author(&repo, &[]);
+        repo.handle_rewrite_log_event(
+            RewriteLogEvent::merge_squash(MergeSquashEvent::new(
+                source_head.clone(),
+                source_head,
+                base_branch,
+                new_head,
+            )),
+            commit_author,
+            true,
+            true,
+        );
+        return Ok(());
+    }
+
+    // merge and pull both record the pre-merge HEAD in ORIG_HEAD
+    let old_head = repo.git(&["rev-parse", "--verify", "ORIG_HEAD"])?;
+    reconcile_working_log_after_merge(&repo, old_head.trim(), &new_head);
+    Ok(())
+}
+
+/// `post-checkout <prev-head> <new-head> <is-branch-checkout>`, also run by `git switch`
+/// and after `git clone`.
+fn handle_post_checkout_hook(hook_args: &[String]) -> Result<(), GitAiError> {
+    let (Some(old_head), Some(new_head)) = (hook_args.first(), hook_args.get(1)) else {
+        return Err(GitAiError::Generic(
+            "post-checkout requires the previous and new HEAD".to_string(),
+        ));
+    };
+    let repo = find_repository(&[])?;
+
+    // Local changes are only carried across a branch switch when the files are identical
+    // in both commits, so pending attributions stay valid under the new base commit
+    if old_head != new_head {
+        repo.storage.rename_working_log(old_head, new_head)?;
+    }
+    prune_attributions_for_clean_files(&repo, new_head);
+    Ok(())
+}
+
+/// `pre-push <remote-name> <url>`, with the refs being pushed on stdin.
+///
+/// This is the one hook allowed to fail: a policy violation exits 1 so git aborts the
+/// push. Errors while evaluating are only logged, like every other hook.
+fn handle_pre_push_hook(hook_args: &[String]) -> Result<(), GitAiError> {
+    let config_policy = Config::get().push_policy();
+    let repo = find_repository(&[])?;
+    let file_policy = Policy::load(&repo)?.unwrap_or_default();
+    if config_policy.is_empty() && file_policy.rules.is_empty() {
+        return Ok(());
+    }
+
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    let refs = parse_pushed_refs(&input);
+    let remote = hook_args.first().map(String::as_str).unwrap_or("origin");
+
+    let commits = commits_to_push(&repo, remote, &refs)?;
+    let mut violations = evaluate_commits(&repo, config_policy, &commits)?;
+    violations.extend(file_policy.evaluate_commits(&repo, &commits)?);
+    if !violations.is_empty() {
+        eprint!("{}", format_violation_report(&violations));
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// `git-ai pre-receive [--require-attribution]`, installed as a server's `pre-receive`
+/// hook (also reachable as `git-ai hook pre-receive`).
+///
+/// Checks the commits a push would add against the configured `push_policy` and the
+/// `.git-ai/policy.toml` of the default branch. Unlike the client hooks this fails
+/// closed: a violation or an error rejects the whole push.
+pub fn handle_pre_receive(args: &[String]) {
+    let mut policy = Config::get().push_policy().clone();
+    if args.iter().any(|a| a == "--require-attribution") {
+        policy.require_attribution = true;
+    }
+
+    let result = (|| {
+        let repo = find_receiving_repository()?;
+        let file_policy = if repo.revparse_single("HEAD").is_ok() {
+            Policy::load_at(&repo, "HEAD")?.unwrap_or_default()
+        } else {
+            Policy::default()
+        };
+        if policy.is_empty() && file_policy.rules.is_empty() {
+            return Ok::<_, GitAiError>(Vec::new());
+        }
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input)?;
+        let commits = commits_to_receive(&repo, &parse_received_refs(&input))?;
+        let mut violations = evaluate_commits(&repo, &policy, &commits)?;
+        violations.extend(file_policy.evaluate_commits(&repo, &commits)?);
+        Ok(violations)
+    })();
+    match result {
+        Ok(violations) if violations.is_empty() => {}
+        Ok(violations) => {
+            eprint!("{}", format_rejection_report(&violations));
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!(
+                "{}",
+                tr_args("push.check_failed", &[("error", &e.to_string())])
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Server repositories are usually bare, which `find_repository` does not handle.
+fn find_receiving_repository() -> Result<Repository, GitAiError> {
+    if let Ok(repo) = find_repository(&[]) {
+        return Ok(repo);
+    }
+    let output = exec_git(&["rev-parse".to_string(), "--git-dir".to_string()])?;
+    let git_dir = std::path::PathBuf::from(String::from_utf8(output.stdout)?.trim());
+    from_bare_repository(&git_dir.canonicalize()?)
+}
+
+/// `reference-transaction <state>`, with `<old> <new> <ref>` lines on stdin.
+///
+/// Records moves of HEAD and the authorship notes for the caches keyed by
+/// [`ref_stamp`](crate::git::ref_state::ref_stamp), then catches HEAD moving without
+/// porcelain that has a hook of its own (`git update-ref`, GUI "undo commit", plain
+/// `git reset`). Commits, merges, amends and rebases are left to their own hooks; only
+/// the remaining moves are repaired here.
+fn handle_reference_transaction_hook(hook_args: &[String]) -> Result<(), GitAiError> {
+    if hook_args.first().map(String::as_str) != Some("committed") {
+        return Ok(());
+    }
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    let moved: Vec<(&str, &str)> = input
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let (_old, new, refname) = (parts.next()?, parts.next()?, parts.next()?);
+            Some((new, refname))
+        })
+        .collect();
+    if moved.is_empty() {
+        return Ok(());
+    }
+
+    let repo = find_repository(&[])?;
+    let head_ref = repo
+        .head()
+        .ok()
+        .map(|h| h.name().unwrap_or("HEAD").to_string());
+    RefRecord::record_updates(repo.common_dir(), head_ref.as_deref(), &moved)?;
+
+    let updates = parse_ref_updates(&input);
+    if updates.is_empty() || proxy_active() {
+        return Ok(());
+    }
+    if repo.path().join("rebase-merge").exists()
+        || repo.path().join("rebase-apply").exists()
+        || repo.path().join("sequencer").exists()
+    {
+        return Ok(());
+    }
+
+    let mut seen: Vec<(&str, &str)> = Vec::new();
+    for (old, new, refname) in updates {
+        let moves_head = refname == "HEAD" || head_ref.as_deref() == Some(refname);
+        if !moves_head || seen.contains(&(old, new)) {
+            continue;
+        }
+        seen.push((old, new));
+        repair_working_log_after_ref_move(&repo, old, new)?;
+    }
+    Ok(())
+}
+
+/// Updates of existing refs to another commit (creations and deletions are skipped)
+fn parse_ref_updates(input: &str) -> Vec<(&str, &str, &str)> {
+    input
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let (old, new, refname) = (parts.next()?, parts.next()?, parts.next()?);
+            let is_zero = |sha: &str| sha.chars().all(|c| c == '0');
+            (old != new && !is_zero(old) && !is_zero(new)).then_some((old, new, refname))
+        })
+        .collect()
+}
+
+fn repair_working_log_after_ref_move(
+    repo: &Repository,
+    old: &str,
+    new: &str,
+) -> Result<(), GitAiError> {
+    // Moving forward is a commit, fast-forward or pull; the working log for `old`
+    // is consumed when that commit's authorship is finalized
+    if is_ancestor(repo, old, new) {
+        return Ok(());
+    }
+
+    // Moving back (update-ref HEAD HEAD~n, reset): the undone commits' AI lines are
+    // uncommitted again, so rebuild the working log on the new base like a soft reset.
+    // If the working tree was reset too, the rebuilt entries for clean files drop out.
+    if is_ancestor(repo, new, old) {
+        debug_log(&format!(
+            "HEAD moved back {} -> {}, reconstructing working log",
+            old, new
+        ));
+        let author = get_commit_default_author(repo, &[]);
+        reconstruct_working_log_after_reset(repo, new, old, &author, None)?;
+        prune_attributions_for_clean_files(repo, new);
+        return Ok(());
+    }
+
+    // Same parents as before: an amend, handled by post-rewrite
+    let parents = |sha: &str| repo.git(&["rev-parse", &format!("{}^@", sha)]).ok();
+    if parents(old) == parents(new) {
+        return Ok(());
+    }
+
+    // Unrelated history: the pending checkpoints no longer describe changes against HEAD
+    if repo.storage.working_logs.join(old).exists() {
+        debug_log(&format!(
+            "HEAD moved to unrelated commit {} -> {}, dropping working log",
+            old, new
+        ));
+        repo.storage.delete_working_log_for_base_commit(old)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_ref_updates() {
+        let zero = "0000000000000000000000000000000000000000";
+        let input = format!(
+            "aaa bbb refs/heads/main\n{z} ccc refs/heads/new\nddd {z} refs/heads/gone\neee eee HEAD\n",
+            z = zero
+        );
+        assert_eq!(
+            parse_ref_updates(&input),
+            vec![("aaa", "bbb", "refs/heads/main")]
+        );
+    }
+
+    #[test]
+    fn test_squash_source_from_message() {
+        let msg = "Squashed commit of the following:\n\ncommit abc123\nAuthor: A <a@x>\n\n    Two\n\ncommit def456\n";
+        assert_eq!(squash_source_from_message(msg), Some("abc123".to_string()));
+        assert_eq!(squash_source_from_message("nothing here\n"), None);
+    }
+
+    #[test]
+    fn test_parse_rewrite_mappings() {
+        let input = "aaa111 bbb222\nccc333 ddd444 extra-info\n\nmalformed\n";
+        assert_eq!(
+            parse_rewrite_mappings(input),
+            vec![
+                ("aaa111".to_string(), "bbb222".to_string()),
+                ("ccc333".to_string(), "ddd444".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,285 @@
+//! `git-ai graph`: a contribution calendar of the last year as an SVG, in the style of
+//! GitHub's, with every day's cell split between the lines humans and AI added.
+//!
+//! A day's cell is shaded by how many lines its commits added, on four levels relative
+//! to the busiest day. The bottom of the cell, in proportion to the AI share of those
+//! lines, takes the AI colour; the rest stays human green. Days are the commits'
+//! author dates, in the timezone each commit was made in.
+
+use crate::authorship::stats::stats_for_commits_stats;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::Repository;
+use chrono::{DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate};
+use std::collections::BTreeMap;
+
+const CELL: i64 = 11;
+const STEP: i64 = 13;
+const LEFT: i64 = 30;
+const TOP: i64 = 20;
+const WEEKS: i64 = 53;
+
+const EMPTY_COLOR: &str = "#ebedf0";
+const HUMAN_COLORS: [&str; 4] = ["#9be9a8", "#40c463", "#30a14e", "#216e39"];
+const AI_COLORS: [&str; 4] = ["#d8b4fe", "#a855f7", "#7e22ce", "#581c87"];
+
+/// Lines the commits of a day added
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DayLines {
+    pub human: u32,
+    pub ai: u32,
+}
+
+impl DayLines {
+    pub fn total(&self) -> u32 {
+        self.human + self.ai
+    }
+}
+
+pub fn handle_graph(args: &[String]) {
+    let usage = "Usage: git-ai graph [-o <file.svg>] [<rev>]";
+    let mut output = None;
+    let mut rev = "HEAD".to_string();
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "-o" | "--output" if i + 1 < args.len() => {
+                output = Some(args[i + 1].clone());
+                i += 1;
+            }
+            arg if !arg.starts_with('-') => rev = arg.to_string(),
+            _ => {
+                eprintln!("{}", usage);
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    let end = Local::now().date_naive();
+    let result = find_repository(&Vec::<String>::new())
+        .and_then(|repo| collect_days(&repo, &rev, first_day(end)))
+        .map(|days| render_svg(&days, end));
+    match (result, output) {
+        (Ok(svg), None) => print!("{}", svg),
+        (Ok(svg), Some(path)) => {
+            if let Err(e) = std::fs::write(&path, svg) {
+                eprintln!("Failed to write {}: {}", path, e);
+                std::process::exit(1);
+            }
+            println!("Wrote contribution graph to {}", path);
+        }
+        (Err(e), _) => {
+            crate::error::exit_with(&format!("Failed to generate graph: {}", e), &e);
+        }
+    }
+}
+
+/// The Sunday starting the first week of a calendar ending on `end`
+fn first_day(end: NaiveDate) -> NaiveDate {
+    let start = end - Duration::weeks(WEEKS - 1);
+    start - Duration::days(start.weekday().num_days_from_sunday() as i64)
+}
+
+/// Human and AI lines added by the commits of `rev` authored since `since`, by day
+pub fn collect_days(
+    repo: &Repository,
+    rev: &str,
+    since: NaiveDate,
+) -> Result<BTreeMap<NaiveDate, DayLines>, GitAiError> {
+    let since = format!("--since={}", since);
+    let log = repo.git(&["log", "--no-merges", "--format=%H%x09%aI", &since, rev])?;
+
+    let commits: Vec<(String, DateTime<FixedOffset>)> = log
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .filter_map(|(sha, date)| {
+            let date = DateTime::<FixedOffset>::parse_from_rfc3339(date).ok()?;
+            Some((sha.to_string(), date))
+        })
+        .collect();
+    let shas: Vec<String> = commits.iter().map(|(sha, _)| sha.clone()).collect();
+
+    let mut days: BTreeMap<NaiveDate, DayLines> = BTreeMap::new();
+    for ((_, date), stats) in commits
+        .iter()
+        .zip(stats_for_commits_stats(repo, &shas, &[])?)
+    {
+        let ai = stats.ai_additions.min(stats.git_diff_added_lines);
+        let day = days.entry(date.date_naive()).or_default();
+        day.ai += ai;
+        day.human += stats.git_diff_added_lines - ai;
+    }
+    Ok(days)
+}
+
+/// The calendar of the year ending on `end`
+pub fn render_svg(days: &BTreeMap<NaiveDate, DayLines>, end: NaiveDate) -> String {
+    let start = first_day(end);
+    let busiest = days
+        .iter()
+        .filter(|(day, _)| **day >= start && **day <= end)
+        .map(|(_, lines)| lines.total())
+        .max()
+        .unwrap_or(0);
+    let width = LEFT + WEEKS * STEP;
+    let height = TOP + 7 * STEP + 24;
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"9\" fill=\"#57606a\">\n",
+        w = width,
+        h = height
+    );
+    for (row, label) in [(1, "Mon"), (3, "Wed"), (5, "Fri")] {
+        svg.push_str(&format!(
+            "<text x=\"0\" y=\"{}\">{}</text>\n",
+            TOP + row * STEP + 9,
+            label
+        ));
+    }
+
+    let mut month = None;
+    let mut day = start;
+    while day <= end {
+        let offset = (day - start).num_days();
+        let x = LEFT + offset / 7 * STEP;
+        let y = TOP + day.weekday().num_days_from_sunday() as i64 * STEP;
+        if day.weekday().num_days_from_sunday() == 0 && month != Some(day.month()) {
+            // A month is labelled above the first week that starts in it
+            if month.is_some() || day.day() <= 7 {
+                svg.push_str(&format!(
+                    "<text x=\"{}\" y=\"{}\">{}</text>\n",
+                    x,
+                    TOP - 6,
+                    day.format("%b")
+                ));
+            }
+            month = Some(day.month());
+        }
+        svg.push_str(&cell(day, x, y, days.get(&day).copied(), busiest));
+        day += Duration::days(1);
+    }
+
+    let legend_y = TOP + 7 * STEP + 8;
+    svg.push_str(&format!(
+        "<rect x=\"{x}\" y=\"{y}\" width=\"{c}\" height=\"{c}\" rx=\"2\" fill=\"{}\"/><text x=\"{}\" y=\"{}\">human</text>\n",
+        HUMAN_COLORS[2],
+        LEFT + STEP + 2,
+        legend_y + 9,
+        x = LEFT,
+        y = legend_y,
+        c = CELL
+    ));
+    svg.push_str(&format!(
+        "<rect x=\"{x}\" y=\"{y}\" width=\"{c}\" height=\"{c}\" rx=\"2\" fill=\"{}\"/><text x=\"{}\" y=\"{}\">AI</text>\n",
+        AI_COLORS[2],
+        LEFT + 5 * STEP + 2,
+        legend_y + 9,
+        x = LEFT + 4 * STEP,
+        y = legend_y,
+        c = CELL
+    ));
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// One day's cell: the human colour, with the AI share of its lines at the bottom
+fn cell(day: NaiveDate, x: i64, y: i64, lines: Option<DayLines>, busiest: u32) -> String {
+    let lines = lines.unwrap_or_default();
+    let title = format!(
+        "<title>{}: {} human, {} AI lines</title>",
+        day, lines.human, lines.ai
+    );
+    if lines.total() == 0 {
+        return format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{c}\" height=\"{c}\" rx=\"2\" fill=\"{}\">{}</rect>\n",
+            x,
+            y,
+            EMPTY_COLOR,
+            title,
+            c = CELL
+        );
+    }
+    let level = level(lines.total(), busiest);
+    let ai_height = (CELL * lines.ai as i64 + lines.total() as i64 / 2) / lines.total() as i64;
+    let mut cell = format!(
+        "<g>{}<rect x=\"{}\" y=\"{}\" width=\"{c}\" height=\"{c}\" rx=\"2\" fill=\"{}\"/>",
+        title,
+        x,
+        y,
+        HUMAN_COLORS[level],
+        c = CELL
+    );
+    if ai_height > 0 {
+        cell.push_str(&format!(
+            "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"2\" fill=\"{}\"/>",
+            x,
+            y + CELL - ai_height,
+            CELL,
+            ai_height,
+            AI_COLORS[level]
+        ));
+    }
+    cell.push_str("</g>\n");
+    cell
+}
+
+/// Shade (0-3) of a day with `total` lines, relative to the busiest day
+fn level(total: u32, busiest: u32) -> usize {
+    if busiest == 0 {
+        return 0;
+    }
+    ((total as u64 * 4).div_ceil(busiest as u64) as usize).clamp(1, 4) - 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_first_day_is_a_sunday_a_year_back() {
+        let start = first_day(date(2026, 10, 14));
+        assert_eq!(start, date(2025, 10, 12));
+        assert_eq!(start.weekday().num_days_from_sunday(), 0);
+    }
+
+    #[test]
+    fn test_level() {
+        assert_eq!(level(1, 100), 0);
+        assert_eq!(level(25, 100), 0);
+        assert_eq!(level(26, 100), 1);
+        assert_eq!(level(100, 100), 3);
+    }
+
+    #[test]
+    fn test_render_svg_splits_cells() {
+        let end = date(2026, 10, 14);
+        let days: BTreeMap<NaiveDate, DayLines> = [
+            (date(2026, 10, 13), DayLines { human: 1, ai: 3 }),
+            (date(2026, 10, 12), DayLines { human: 4, ai: 0 }),
+        ]
+        .into_iter()
+        .collect();
+        let svg = render_svg(&days, end);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+        assert_eq!(svg.matches("<title>").count(), 53 * 7 - 3);
+        // Tuesday of the last week, 8 of its 11 pixels AI
+        assert!(svg.contains(&format!(
+            "<g><title>2026-10-13: 1 human, 3 AI lines</title><rect x=\"{x}\" y=\"46\" width=\"11\" height=\"11\" rx=\"2\" fill=\"{}\"/><rect x=\"{x}\" y=\"49\" width=\"11\" height=\"8\" rx=\"2\" fill=\"{}\"/></g>",
+            HUMAN_COLORS[3],
+            AI_COLORS[3],
+            x = LEFT + 52 * STEP
+        )));
+        assert!(svg.contains(&format!(
+            "<title>2026-10-12: 4 human, 0 AI lines</title><rect x=\"{}\" y=\"33\" width=\"11\" height=\"11\" rx=\"2\" fill=\"{}\"/></g>",
+            LEFT + 52 * STEP,
+            HUMAN_COLORS[3]
+        )));
+        assert!(svg.contains("<title>2026-10-14: 0 human, 0 AI lines</title>"));
+        assert!(svg.contains(">Oct</text>"));
+    }
+}
@@ -150,7 +150,11 @@ pub fn post_checkout_hook(
 }
 
 /// Remove attributions for specific files from working log (pathspec checkout case).
-fn remove_attributions_for_pathspecs(repository: &Repository, head: &str, pathspecs: &[String]) {
+pub(crate) fn remove_attributions_for_pathspecs(
+    repository: &Repository,
+    head: &str,
+    pathspecs: &[String],
+) {
     let working_log = repository.storage.working_log_for_base_commit(head);
 
     // Filter INITIAL attributions
@@ -179,6 +183,38 @@ fn remove_attributions_for_pathspecs(repository: &Repository, head: &str, pathsp
     }
 }
 
+/// Drop attributions for working-log files that no longer differ from HEAD.
+///
+/// Used when the exact set of checked-out paths isn't known (e.g. from the
+/// post-checkout git hook): any file that is clean again was reset by the checkout.
+pub(crate) fn prune_attributions_for_clean_files(repository: &Repository, head: &str) {
+    let working_log = repository.storage.working_log_for_base_commit(head);
+    let mut tracked_files = working_log.all_touched_files().unwrap_or_default();
+    tracked_files.extend(working_log.read_initial_attributions().files.into_keys());
+    if tracked_files.is_empty() {
+        return;
+    }
+
+    let dirty_files = match repository.get_staged_and_unstaged_filenames() {
+        Ok(files) => files,
+        Err(e) => {
+            debug_log(&format!("Failed to read status for pruning: {}", e));
+            return;
+        }
+    };
+    let clean_files: Vec<String> = tracked_files
+        .into_iter()
+        .filter(|file| !dirty_files.contains(file))
+        .collect();
+    if !clean_files.is_empty() {
+        debug_log(&format!(
+            "Dropping attributions for files reset by checkout: {:?}",
+            clean_files
+        ));
+        remove_attributions_for_pathspecs(repository, head, &clean_files);
+    }
+}
+
 fn matches_any_pathspec(file: &str, pathspecs: &[String]) -> bool {
     pathspecs.iter().any(|p| file == p || file.starts_with(p))
 }
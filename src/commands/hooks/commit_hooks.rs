@@ -1,8 +1,15 @@
+use crate::authorship::async_finalize::{
+    FinalizeIntent, record_intent, spawn_background_finalizer,
+};
 use crate::authorship::pre_commit;
+use crate::authorship::working_log::CheckpointKind;
+use crate::commands::checkpoint_agent::aider_preset;
 use crate::commands::git_handlers::CommandHooksContext;
+use crate::config::Config;
 use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run};
 use crate::git::repository::Repository;
 use crate::git::rewrite_log::RewriteLogEvent;
+use crate::i18n::{tr, tr_args};
 use crate::utils::debug_log;
 
 pub fn commit_pre_command_hook(
@@ -18,17 +25,35 @@ pub fn commit_pre_command_hook(
 
     let default_author = get_commit_default_author(repository, &parsed_args.command_args);
 
+    // Aider commits its edits as soon as it makes them; record them as AI first
+    if let Some(agent_run) =
+        aider_preset::agent_run_for_commit(repository, &default_author, &parsed_args.command_args)
+        && let Err(e) = crate::commands::checkpoint::run(
+            repository,
+            &default_author,
+            CheckpointKind::AiAgent,
+            false,
+            false,
+            true,
+            Some(agent_run),
+            false,
+        )
+    {
+        debug_log(&format!("Failed to checkpoint aider commit: {}", e));
+    }
+
     // Run pre-commit logic
     if let Err(e) = pre_commit::pre_commit(repository, default_author.clone()) {
         if e.to_string()
             .contains("Cannot run checkpoint on bare repositories")
         {
-            eprintln!(
-                "Cannot run checkpoint on bare repositories (skipping git-ai pre-commit hook)"
-            );
+            crate::logging::warn(&tr("hook.bare_repository"));
             return false;
         }
-        eprintln!("Pre-commit failed: {}", e);
+        crate::logging::error(&tr_args(
+            "hook.pre_commit_failed",
+            &[("error", &e.to_string())],
+        ));
         std::process::exit(1);
     }
     true
@@ -85,6 +110,24 @@ pub fn commit_post_command_hook(
                 true,
             );
         }
+    } else if Config::get().async_post_commit_enabled() {
+        // Keep the commit fast: log the event now, build the note in the background
+        let new_sha = new_sha.unwrap();
+        repository.handle_rewrite_log_event(
+            RewriteLogEvent::commit(original_commit.clone(), new_sha.clone()),
+            commit_author.clone(),
+            supress_output,
+            false,
+        );
+        let intent = FinalizeIntent {
+            base_commit: original_commit,
+            commit_sha: new_sha,
+            human_author: commit_author,
+        };
+        match record_intent(repository, &intent) {
+            Ok(()) => spawn_background_finalizer(repository),
+            Err(e) => debug_log(&format!("Failed to queue commit finalization: {}", e)),
+        }
     } else {
         repository.handle_rewrite_log_event(
             RewriteLogEvent::commit(original_commit, new_sha.unwrap()),
@@ -170,7 +213,7 @@ pub fn get_commit_default_author(repo: &Repository, args: &[String]) -> String {
         (Some(name), None) => name,
         (None, Some(email)) => email,
         (None, None) => {
-            eprintln!("Warning: No author information found. Using 'unknown' as author.");
+            crate::logging::warn(&tr("hook.no_author"));
             "unknown".to_string()
         }
     }
@@ -1,7 +1,10 @@
+use crate::authorship::history_import::import_commits;
 use crate::authorship::virtual_attribution::{VirtualAttributions, restore_stashed_va};
 use crate::commands::git_handlers::CommandHooksContext;
 use crate::commands::hooks::commit_hooks::get_commit_default_author;
+use crate::commands::hooks::merge_hooks::reconcile_working_log_after_merge;
 use crate::commands::hooks::rebase_hooks::build_rebase_commit_mappings;
+use crate::commands::sync_prompts;
 use crate::commands::upgrade;
 use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run};
 use crate::git::repository::{Repository, exec_git, find_repository};
@@ -14,6 +17,7 @@ pub fn fetch_pull_pre_command_hook(
     repository: &Repository,
 ) -> Option<std::thread::JoinHandle<()>> {
     upgrade::maybe_schedule_background_update_check();
+    sync_prompts::maybe_schedule_org_sync();
 
     // Early return for dry-run
     if is_dry_run(&parsed_args.command_args) {
@@ -62,6 +66,7 @@ pub fn pull_pre_command_hook(
     // Start the background authorship fetch (same as regular fetch)
     command_hooks_context.fetch_authorship_handle =
         fetch_pull_pre_command_hook(parsed_args, repository);
+    command_hooks_context.pre_fetch_remote_tips = capture_remote_tips(parsed_args, repository);
 
     // Capture HEAD before pull to detect changes
     repository.require_pre_command_head();
@@ -115,10 +120,62 @@ pub fn pull_pre_command_hook(
     }
 }
 
+/// Snapshot the remote-tracking refs of the fetched remote before the fetch runs.
+pub fn capture_remote_tips(
+    parsed_args: &ParsedGitInvocation,
+    repository: &Repository,
+) -> Option<(String, Vec<String>)> {
+    if is_dry_run(&parsed_args.command_args) {
+        return None;
+    }
+    let remote = fetch_remote_from_args(repository, parsed_args).ok()?;
+    let refs_prefix = format!("refs/remotes/{}/", remote);
+    let tips = repository
+        .git(&["for-each-ref", "--format=%(objectname)", &refs_prefix])
+        .ok()?
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect();
+    Some((remote, tips))
+}
+
+/// Synthesize authorship for newly fetched commits that carry AI trailers but arrived
+/// without notes. Runs after the notes fetch so real attribution always wins.
+fn import_trailers_for_fetched_commits(
+    repository: &Repository,
+    command_hooks_context: &mut CommandHooksContext,
+) {
+    let Some((remote, old_tips)) = command_hooks_context.pre_fetch_remote_tips.take() else {
+        return;
+    };
+
+    let mut rev_args = vec![
+        format!("--max-count={}", MAX_FETCH_IMPORT_COMMITS),
+        format!("--remotes={}", remote),
+    ];
+    if !old_tips.is_empty() {
+        rev_args.push("--not".to_string());
+        rev_args.extend(old_tips);
+    }
+
+    match import_commits(repository, &rev_args, false, |_, _| {}) {
+        Ok(summary) if !summary.imported.is_empty() => debug_log(&format!(
+            "Imported trailer-based authorship for {} fetched commits",
+            summary.imported.len()
+        )),
+        Ok(_) => {}
+        Err(e) => debug_log(&format!("trailer import after fetch failed: {}", e)),
+    }
+}
+
+/// Upper bound on commits scanned for trailers after a single fetch.
+const MAX_FETCH_IMPORT_COMMITS: usize = 1000;
+
 pub fn fetch_pull_post_command_hook(
-    _repository: &Repository,
+    repository: &Repository,
     _parsed_args: &ParsedGitInvocation,
-    _exit_status: std::process::ExitStatus,
+    exit_status: std::process::ExitStatus,
     command_hooks_context: &mut CommandHooksContext,
 ) {
     // Always wait for the authorship fetch thread to complete if it was started,
@@ -127,6 +184,10 @@ pub fn fetch_pull_post_command_hook(
     if let Some(handle) = command_hooks_context.fetch_authorship_handle.take() {
         let _ = handle.join();
     }
+
+    if exit_status.success() {
+        import_trailers_for_fetched_commits(repository, command_hooks_context);
+    }
 }
 
 /// Post-command hook for git pull.
@@ -149,6 +210,8 @@ pub fn pull_post_command_hook(
         return;
     }
 
+    import_trailers_for_fetched_commits(repository, command_hooks_context);
+
     // Get old HEAD from pre-command capture
     let old_head = match &repository.pre_command_base_commit {
         Some(sha) => sha.clone(),
@@ -185,6 +248,8 @@ pub fn pull_post_command_hook(
     let config = get_pull_rebase_autostash_config(parsed_args, repository);
     if config.is_rebase {
         process_completed_pull_rebase(repository, &old_head, &new_head);
+    } else {
+        reconcile_working_log_after_merge(repository, &old_head, &new_head);
     }
 }
 
@@ -1,12 +1,18 @@
 use crate::{
+    commands::hooks::checkout_hooks::remove_attributions_for_pathspecs,
     commands::hooks::commit_hooks::get_commit_default_author,
     git::{
         cli_parser::{ParsedGitInvocation, is_dry_run},
         repository::Repository,
         rewrite_log::{MergeSquashEvent, RewriteLogEvent},
     },
+    utils::debug_log,
 };
 
+pub fn pre_merge_hook(repository: &mut Repository) {
+    repository.require_pre_command_head();
+}
+
 pub fn post_merge_hook(
     parsed_args: &ParsedGitInvocation,
     exit_status: std::process::ExitStatus,
@@ -45,5 +51,48 @@ pub fn post_merge_hook(
             false,
             true,
         );
+    } else if exit_status.success() && !is_dry_run(&parsed_args.command_args) {
+        let old_head = repository.pre_command_base_commit.clone();
+        let new_head = repository.head().ok().and_then(|h| h.target().ok());
+        if let (Some(old_head), Some(new_head)) = (old_head, new_head) {
+            reconcile_working_log_after_merge(repository, &old_head, &new_head);
+        }
+    }
+}
+
+/// Move pending (uncommitted) attributions from the pre-merge HEAD onto the new HEAD.
+///
+/// Git refuses to merge into files with local changes, so the dirty files the working
+/// log describes are untouched by the merge and their attributions stay valid. Entries
+/// for files the merge did change are stale (their changes were discarded or committed
+/// before merging) and are dropped. Covers both fast-forwards and merge commits.
+pub fn reconcile_working_log_after_merge(repository: &Repository, old_head: &str, new_head: &str) {
+    if old_head == new_head {
+        return;
+    }
+    if !repository.storage.working_logs.join(old_head).exists() {
+        return;
+    }
+
+    debug_log(&format!(
+        "Reconciling working log after merge: {} -> {}",
+        old_head, new_head
+    ));
+    let _ = repository.storage.rename_working_log(old_head, new_head);
+
+    let merged_files: Vec<String> =
+        match repository.git(&["diff", "--name-only", old_head, new_head]) {
+            Ok(output) => output
+                .lines()
+                .filter(|l| !l.is_empty())
+                .map(|l| l.to_string())
+                .collect(),
+            Err(e) => {
+                debug_log(&format!("Failed to list merged files: {}", e));
+                return;
+            }
+        };
+    if !merged_files.is_empty() {
+        remove_attributions_for_pathspecs(repository, new_head, &merged_files);
     }
 }
@@ -1,4 +1,5 @@
 use crate::commands::git_handlers::CommandHooksContext;
+use crate::commands::sync_prompts;
 use crate::commands::upgrade;
 use crate::git::cli_parser::{ParsedGitInvocation, is_dry_run};
 use crate::git::repository::{Repository, find_repository};
@@ -10,6 +11,7 @@ pub fn push_pre_command_hook(
     repository: &Repository,
 ) -> Option<std::thread::JoinHandle<()>> {
     upgrade::maybe_schedule_background_update_check();
+    sync_prompts::maybe_schedule_org_sync();
 
     // Early returns for cases where we shouldn't push authorship notes
     if is_dry_run(&parsed_args.command_args)
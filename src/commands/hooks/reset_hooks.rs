@@ -355,7 +355,7 @@ fn resolve_tree_ish_to_commit(
 }
 
 /// Check if 'ancestor' is an ancestor of 'descendant'
-fn is_ancestor(repository: &Repository, ancestor: &str, descendant: &str) -> bool {
+pub(crate) fn is_ancestor(repository: &Repository, ancestor: &str, descendant: &str) -> bool {
     let mut args = repository.global_args_for_exec();
     args.push("merge-base".to_string());
     args.push("--is-ancestor".to_string());
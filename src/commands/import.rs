@@ -0,0 +1,108 @@
+use crate::authorship::commit_trailers::TrailerSummary;
+use crate::authorship::history_import::import_commits;
+use crate::git::find_repository;
+use serde_json::json;
+
+pub fn handle_import(args: &[String]) {
+    let mut dry_run = false;
+    let mut jsonl = false;
+    let mut rev_args: Vec<String> = Vec::new();
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--dry-run" => {
+                dry_run = true;
+            }
+            "--jsonl" => {
+                jsonl = true;
+            }
+            "--help" | "-h" => {
+                print_import_help();
+                std::process::exit(0);
+            }
+            other => {
+                rev_args.push(other.to_string());
+            }
+        }
+        i += 1;
+    }
+
+    if rev_args.is_empty() {
+        rev_args.push("HEAD".to_string());
+    }
+
+    let repo = match find_repository(&Vec::<String>::new()) {
+        Ok(repo) => repo,
+        Err(e) => {
+            crate::error::exit_with(&format!("Failed to find repository: {}", e), &e);
+        }
+    };
+
+    // Each commit is printed as soon as it's imported, so long histories stream
+    let print_commit = |sha: &str, trailer_summary: &TrailerSummary| {
+        let tools: Vec<&str> = trailer_summary.tools.iter().map(String::as_str).collect();
+        if jsonl {
+            println!(
+                "{}",
+                json!({
+                    "type": "commit",
+                    "commit": sha,
+                    "ai_percent": trailer_summary.ai_percent,
+                    "tools": tools,
+                    "dry_run": dry_run,
+                })
+            );
+        } else {
+            println!(
+                "{} {}% AI ({})",
+                &sha[..7.min(sha.len())],
+                trailer_summary.ai_percent,
+                tools.join(",")
+            );
+        }
+    };
+
+    match import_commits(&repo, &rev_args, dry_run, print_commit) {
+        Ok(summary) => {
+            if jsonl {
+                println!(
+                    "{}",
+                    json!({
+                        "type": "summary",
+                        "scanned": summary.scanned,
+                        "imported": summary.imported.len(),
+                        "already_attributed": summary.already_attributed,
+                        "dry_run": dry_run,
+                    })
+                );
+                return;
+            }
+            let verb = if dry_run { "Would import" } else { "Imported" };
+            eprintln!(
+                "{} {} of {} commits ({} already attributed)",
+                verb,
+                summary.imported.len(),
+                summary.scanned,
+                summary.already_attributed
+            );
+        }
+        Err(e) => {
+            crate::error::exit_with(&format!("Import failed: {}", e), &e);
+        }
+    }
+}
+
+fn print_import_help() {
+    eprintln!("git-ai import - Synthesize authorship for commits without git-ai notes");
+    eprintln!();
+    eprintln!("Usage: git-ai import [<revision-range>...] [--dry-run] [--jsonl]");
+    eprintln!();
+    eprintln!("Reads AI-Assisted / AI-Tools commit trailers and writes a coarse");
+    eprintln!("authorship log for each matching commit that has no attribution yet.");
+    eprintln!("Defaults to the history of HEAD.");
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --dry-run    List commits that would be imported without writing notes");
+    eprintln!("  --jsonl      Print a JSON object per commit as it's imported, then a summary");
+}
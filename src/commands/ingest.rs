@@ -0,0 +1,97 @@
+use crate::authorship::agent_ingest::{PROVIDERS, ingest, parse_payload};
+use crate::error::GitAiError;
+use crate::git::find_repository;
+
+pub fn handle_ingest(args: &[String]) {
+    let mut provider = None;
+    let mut payload_path = None;
+    let mut dry_run = false;
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--provider" if i + 1 < args.len() => {
+                provider = Some(args[i + 1].clone());
+                i += 1;
+            }
+            arg if arg.starts_with("--provider=") => {
+                provider = Some(arg["--provider=".len()..].to_string());
+            }
+            "--dry-run" => {
+                dry_run = true;
+            }
+            "--help" | "-h" => {
+                print_ingest_help();
+                std::process::exit(0);
+            }
+            arg if payload_path.is_none() && (arg == "-" || !arg.starts_with('-')) => {
+                payload_path = Some(arg.to_string());
+            }
+            arg => {
+                eprintln!("Unknown ingest argument: {}", arg);
+                print_ingest_help();
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    let (Some(provider), Some(payload_path)) = (provider, payload_path) else {
+        print_ingest_help();
+        std::process::exit(1);
+    };
+
+    if let Err(e) = run(&provider, &payload_path, dry_run) {
+        crate::error::exit_with(&format!("Ingest failed: {}", e), &e);
+    }
+}
+
+fn run(provider: &str, payload_path: &str, dry_run: bool) -> Result<(), GitAiError> {
+    let contents = if payload_path == "-" {
+        std::io::read_to_string(std::io::stdin())?
+    } else {
+        std::fs::read_to_string(payload_path)?
+    };
+    let payload: serde_json::Value = serde_json::from_str(&contents)?;
+    let work = parse_payload(provider, &payload)?;
+
+    let repo = find_repository(&Vec::<String>::new())?;
+    let summary = ingest(&repo, &work, dry_run)?;
+
+    for sha in &summary.attributed {
+        println!("{} {}", &sha[..7.min(sha.len())], work.agent_id.tool);
+    }
+    for sha in &summary.missing {
+        eprintln!("{} not found; fetch it and ingest again", sha);
+    }
+    let verb = if dry_run {
+        "Would attribute"
+    } else {
+        "Attributed"
+    };
+    eprintln!(
+        "{} {} commit(s) to {} ({} already attributed, {} missing)",
+        verb,
+        summary.attributed.len(),
+        work.agent_id.tool,
+        summary.already_attributed.len(),
+        summary.missing.len()
+    );
+    Ok(())
+}
+
+fn print_ingest_help() {
+    let providers: Vec<&str> = PROVIDERS.iter().map(|(name, _)| *name).collect();
+    eprintln!("git-ai ingest - Attribute commits a cloud agent pushed");
+    eprintln!();
+    eprintln!("Usage: git-ai ingest --provider <provider> <payload.json|-> [--dry-run]");
+    eprintln!();
+    eprintln!("Reads a webhook body or session export naming the agent's commits or");
+    eprintln!("branch, and attributes every line those commits add to the agent.");
+    eprintln!("The commits must already be fetched; commits with notes are left alone.");
+    eprintln!();
+    eprintln!("Providers: {}", providers.join(", "));
+    eprintln!();
+    eprintln!("Options:");
+    eprintln!("  --dry-run    List commits that would be attributed without writing notes");
+}
@@ -1,8 +1,13 @@
 use crate::commands::flush_metrics_db::spawn_background_metrics_db_flush;
 use crate::error::GitAiError;
+use crate::git::find_repository;
 use crate::mdm::agents::get_all_installers;
 use crate::mdm::git_client_installer::GitClientInstallerParams;
 use crate::mdm::git_clients::get_all_git_client_installers;
+use crate::mdm::git_hooks::{
+    GitHookAction, GitHookChange, GitHookMode, GitHookScope, GlobalHookTarget, install_git_hooks,
+    install_global_git_hooks, resolve_hooks_dir, uninstall_git_hooks, uninstall_global_git_hooks,
+};
 use crate::mdm::hook_installer::HookInstallerParams;
 use crate::mdm::skills_installer;
 use crate::mdm::spinner::{Spinner, print_diff};
@@ -106,6 +111,8 @@ pub fn run(args: &[String]) -> Result<HashMap<String, String>, GitAiError> {
     // Parse flags
     let mut dry_run = false;
     let mut verbose = false;
+    let mut mode = GitHookMode::default();
+    let global = parse_global_target(args);
     for arg in args {
         if arg == "--dry-run" || arg == "--dry-run=true" {
             dry_run = true;
@@ -113,6 +120,14 @@ pub fn run(args: &[String]) -> Result<HashMap<String, String>, GitAiError> {
         if arg == "--verbose" || arg == "-v" {
             verbose = true;
         }
+        if let Some(value) = arg.strip_prefix("--mode=") {
+            mode = GitHookMode::parse(value).ok_or_else(|| {
+                GitAiError::Generic(format!(
+                    "Invalid --mode '{}'. Expected 'chain' or 'standalone'",
+                    value
+                ))
+            })?;
+        }
     }
 
     // Get absolute path to the current binary
@@ -120,7 +135,7 @@ pub fn run(args: &[String]) -> Result<HashMap<String, String>, GitAiError> {
     let params = HookInstallerParams { binary_path };
 
     // Run async operations with smol and convert result
-    let statuses = smol::block_on(async_run_install(&params, dry_run, verbose))?;
+    let statuses = smol::block_on(async_run_install(&params, mode, global, dry_run, verbose))?;
 
     // Spawn background processes to flush metrics
     crate::observability::spawn_background_flush();
@@ -134,6 +149,7 @@ pub fn run_uninstall(args: &[String]) -> Result<HashMap<String, String>, GitAiEr
     // Parse flags
     let mut dry_run = false;
     let mut verbose = false;
+    let mut purge_data = false;
     for arg in args {
         if arg == "--dry-run" || arg == "--dry-run=true" {
             dry_run = true;
@@ -141,6 +157,9 @@ pub fn run_uninstall(args: &[String]) -> Result<HashMap<String, String>, GitAiEr
         if arg == "--verbose" || arg == "-v" {
             verbose = true;
         }
+        if arg == "--purge-data" {
+            purge_data = true;
+        }
     }
 
     // Get absolute path to the current binary
@@ -148,12 +167,17 @@ pub fn run_uninstall(args: &[String]) -> Result<HashMap<String, String>, GitAiEr
     let params = HookInstallerParams { binary_path };
 
     // Run async operations with smol and convert result
-    let statuses = smol::block_on(async_run_uninstall(&params, dry_run, verbose))?;
+    let global = parse_global_target(args);
+    let statuses = smol::block_on(async_run_uninstall(
+        &params, global, purge_data, dry_run, verbose,
+    ))?;
     Ok(to_hashmap(statuses))
 }
 
 async fn async_run_install(
     params: &HookInstallerParams,
+    mode: GitHookMode,
+    global: Option<GlobalHookTarget>,
     dry_run: bool,
     verbose: bool,
 ) -> Result<HashMap<String, InstallStatus>, GitAiError> {
@@ -173,7 +197,7 @@ async fn async_run_install(
 
     // Ensure git symlinks for Fork compatibility
     if let Err(e) = crate::mdm::ensure_git_symlinks() {
-        eprintln!("Warning: Failed to create git symlinks: {}", e);
+        crate::logging::warn(&format!("Failed to create git symlinks: {}", e));
     }
 
     // === Coding Agents ===
@@ -377,6 +401,78 @@ async fn async_run_install(
         }
     }
 
+    // === Git Hooks (current repository, or machine-wide with --global) ===
+    let repo_hooks_dir = if global.is_none() {
+        current_repo_hooks_dir()
+    } else {
+        None
+    };
+    if global.is_some() || repo_hooks_dir.is_some() {
+        println!("\n\x1b[1mGit Hooks\x1b[0m");
+        any_checked = true;
+
+        let spinner = Spinner::new("git hooks: checking");
+        spinner.start();
+        let result = match (global, &repo_hooks_dir) {
+            (Some(target), _) => {
+                install_global_git_hooks(target, &params.binary_path, mode, dry_run).map(
+                    |install| {
+                        (
+                            install.changes,
+                            install.config_change,
+                            Some(install.hooks_dir),
+                        )
+                    },
+                )
+            }
+            (None, Some(hooks_dir)) => install_git_hooks(
+                hooks_dir,
+                &params.binary_path,
+                mode,
+                GitHookScope::Repo,
+                dry_run,
+            )
+            .map(|changes| (changes, None, None)),
+            (None, None) => Ok((Vec::new(), None, None)),
+        };
+        match result {
+            Ok((changes, config_change, global_dir)) => {
+                let mut changed = report_git_hook_changes(&spinner, &changes, dry_run, verbose);
+                if let Some(config_change) = config_change {
+                    println!("  git config --global {}", config_change);
+                    changed = true;
+                }
+                if let Some(global_dir) = global_dir {
+                    println!("  Shared hooks directory: {}", global_dir.display());
+                }
+                has_changes |= changed;
+                let status = if changed {
+                    InstallStatus::Installed
+                } else {
+                    InstallStatus::AlreadyInstalled
+                };
+                statuses.insert("git-hooks".to_string(), status);
+                detailed_results.push((
+                    "git-hooks".to_string(),
+                    if changed {
+                        InstallResult::installed()
+                    } else {
+                        InstallResult::already_installed()
+                    },
+                ));
+            }
+            Err(e) => {
+                spinner.error("git hooks: Failed to install");
+                eprintln!("  Error: {}", e);
+                statuses.insert("git-hooks".to_string(), InstallStatus::Failed);
+                detailed_results.push((
+                    "git-hooks".to_string(),
+                    InstallResult::failed(e.to_string()),
+                ));
+            }
+        }
+    }
+
     if !any_checked {
         println!("No compatible IDEs or agent configurations detected. Nothing to install.");
     } else if has_changes && dry_run {
@@ -416,6 +512,8 @@ fn emit_install_hooks_metrics(results: &[(String, InstallResult)]) {
 
 async fn async_run_uninstall(
     params: &HookInstallerParams,
+    global: Option<GlobalHookTarget>,
+    purge_data: bool,
     dry_run: bool,
     verbose: bool,
 ) -> Result<HashMap<String, InstallStatus>, GitAiError> {
@@ -587,6 +685,92 @@ async fn async_run_uninstall(
         }
     }
 
+    // === Git Hooks (current repository, or machine-wide with --global) ===
+    let repo_hooks_dir = if global.is_none() {
+        current_repo_hooks_dir()
+    } else {
+        None
+    };
+    if global.is_some() || repo_hooks_dir.is_some() {
+        println!("\n\x1b[1mGit Hooks\x1b[0m");
+        let spinner = Spinner::new("git hooks: removing");
+        spinner.start();
+        let result = match (global, &repo_hooks_dir) {
+            (Some(target), _) => uninstall_global_git_hooks(target, dry_run).map(|uninstall| {
+                uninstall
+                    .map(|u| (u.changes, u.config_change))
+                    .unwrap_or_default()
+            }),
+            (None, Some(hooks_dir)) => {
+                uninstall_git_hooks(hooks_dir, dry_run).map(|changes| (changes, None))
+            }
+            (None, None) => Ok((Vec::new(), None)),
+        };
+        match result {
+            Ok((changes, None)) if changes.is_empty() => {
+                spinner.success("git hooks: No hooks to remove");
+            }
+            Ok((changes, config_change)) => {
+                any_checked = true;
+                has_changes = true;
+                report_git_hook_changes(&spinner, &changes, dry_run, verbose);
+                if let Some(config_change) = config_change {
+                    println!("  git config --global {}", config_change);
+                }
+                statuses.insert("git-hooks".to_string(), InstallStatus::Installed);
+            }
+            Err(e) => {
+                any_checked = true;
+                spinner.error("git hooks: Failed to remove hooks");
+                eprintln!("  Error: {}", e);
+                statuses.insert("git-hooks".to_string(), InstallStatus::Failed);
+            }
+        }
+    }
+
+    // === Local attribution data (current repository, --purge-data only) ===
+    if purge_data && let Ok(repo) = find_repository(&[]) {
+        println!("\n\x1b[1mLocal Data\x1b[0m");
+        let spinner = Spinner::new("local data: removing");
+        spinner.start();
+        let existing: Vec<std::path::PathBuf> = repo
+            .storage
+            .data_dirs()
+            .into_iter()
+            .filter(|dir| dir.exists())
+            .collect();
+        let result = if dry_run {
+            Ok(existing)
+        } else {
+            repo.storage.purge()
+        };
+        match result {
+            Ok(dirs) if dirs.is_empty() => {
+                spinner.success("local data: Nothing to remove");
+            }
+            Ok(dirs) => {
+                any_checked = true;
+                has_changes = true;
+                if dry_run {
+                    spinner.pending("local data: Pending removal");
+                } else {
+                    spinner.success("local data: Removed");
+                }
+                for dir in dirs {
+                    println!("  {}", dir.display());
+                }
+                println!("  Authorship notes (refs/notes/ai) were kept");
+                statuses.insert("local-data".to_string(), InstallStatus::Installed);
+            }
+            Err(e) => {
+                any_checked = true;
+                spinner.error("local data: Failed to remove");
+                eprintln!("  Error: {}", e);
+                statuses.insert("local-data".to_string(), InstallStatus::Failed);
+            }
+        }
+    }
+
     if !any_checked {
         println!("No git-ai hooks found to uninstall.");
     } else if has_changes && dry_run {
@@ -599,3 +783,70 @@ async fn async_run_uninstall(
 
     Ok(statuses)
 }
+
+/// `--global` installs via `core.hooksPath`; `--global --template` via `init.templateDir`
+fn parse_global_target(args: &[String]) -> Option<GlobalHookTarget> {
+    if !args.iter().any(|a| a == "--global") {
+        return None;
+    }
+    if args.iter().any(|a| a == "--template") {
+        Some(GlobalHookTarget::TemplateDir)
+    } else {
+        Some(GlobalHookTarget::HooksPath)
+    }
+}
+
+/// Hooks directory of the repository the command runs in, if any
+fn current_repo_hooks_dir() -> Option<std::path::PathBuf> {
+    let repo = find_repository(&[]).ok()?;
+    resolve_hooks_dir(&repo).ok()
+}
+
+/// Print one line per changed git hook. Returns whether anything changed.
+fn report_git_hook_changes(
+    spinner: &Spinner,
+    changes: &[GitHookChange],
+    dry_run: bool,
+    verbose: bool,
+) -> bool {
+    let changed: Vec<&GitHookChange> = changes
+        .iter()
+        .filter(|c| c.action != GitHookAction::Unchanged)
+        .collect();
+    if changed.is_empty() {
+        spinner.success("git hooks: Hooks already up to date");
+        return false;
+    }
+
+    let summary = if dry_run {
+        "git hooks: Pending updates"
+    } else {
+        "git hooks: Hooks updated"
+    };
+    if dry_run {
+        spinner.pending(summary);
+    } else {
+        spinner.success(summary);
+    }
+
+    for change in changed {
+        let detail = match (change.action, change.existing) {
+            (GitHookAction::Installed, Some(kind)) => {
+                format!("installed, chaining existing {} hook", kind.name())
+            }
+            (GitHookAction::Restored, Some(kind)) => {
+                format!("removed, restored original {} hook", kind.name())
+            }
+            (GitHookAction::Installed, None) => "installed".to_string(),
+            (GitHookAction::Updated, _) => "updated".to_string(),
+            (GitHookAction::Removed, _) | (GitHookAction::Restored, None) => "removed".to_string(),
+            (GitHookAction::Unchanged, _) => continue,
+        };
+        println!("  {}: {}", change.hook, detail);
+        if verbose && let Some(diff) = &change.diff {
+            println!();
+            print_diff(diff);
+        }
+    }
+    true
+}
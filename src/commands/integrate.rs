@@ -0,0 +1,98 @@
+//! `git-ai integrate <agent>`: set up a single coding agent, even one that
+//! `install-hooks` did not detect yet (e.g. installed after git-ai).
+
+use crate::error::GitAiError;
+use crate::mdm::agents::get_all_installers;
+use crate::mdm::hook_installer::HookInstallerParams;
+use crate::mdm::spinner::print_diff;
+use crate::mdm::utils::get_current_binary_path;
+
+pub fn run(args: &[String]) -> Result<(), GitAiError> {
+    let dry_run = args.iter().any(|a| a == "--dry-run");
+    let uninstall = args.iter().any(|a| a == "--uninstall");
+    let verbose = args.iter().any(|a| a == "--verbose" || a == "-v");
+    let installers = get_all_installers();
+    let available = || {
+        installers
+            .iter()
+            .map(|i| i.id().to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+
+    let Some(agent) = args.iter().find(|a| !a.starts_with('-')) else {
+        return Err(GitAiError::Generic(format!(
+            "integrate requires an agent. Available: {}",
+            available()
+        )));
+    };
+    let Some(installer) = installers.iter().find(|i| i.id() == agent) else {
+        return Err(GitAiError::Generic(format!(
+            "Unknown agent '{}'. Available: {}",
+            agent,
+            available()
+        )));
+    };
+
+    let params = HookInstallerParams {
+        binary_path: get_current_binary_path()?,
+    };
+    let name = installer.name();
+
+    // Version checks still apply; "not installed" does not, since the user asked for it
+    let check = installer.check_hooks(&params)?;
+    if !check.tool_installed && !uninstall {
+        println!(
+            "{} was not detected; writing its configuration anyway.",
+            name
+        );
+    }
+
+    let mut changed = false;
+    if installer.uses_config_hooks() {
+        let diff = if uninstall {
+            installer.uninstall_hooks(&params, dry_run)?
+        } else {
+            installer.install_hooks(&params, dry_run)?
+        };
+        if let Some(diff) = diff {
+            changed = true;
+            if dry_run || verbose {
+                print_diff(&diff);
+            }
+        }
+    }
+
+    let extras: Vec<(bool, String, Option<String>)> = if uninstall {
+        installer
+            .uninstall_extras(&params, dry_run)?
+            .into_iter()
+            .map(|r| (r.changed, r.message, r.diff))
+            .collect()
+    } else {
+        installer
+            .install_extras(&params, dry_run)?
+            .into_iter()
+            .map(|r| (r.changed, r.message, r.diff))
+            .collect()
+    };
+    for (extra_changed, message, diff) in extras {
+        changed |= extra_changed;
+        println!("{}", message);
+        if let Some(diff) = diff
+            && (dry_run || verbose)
+        {
+            print_diff(&diff);
+        }
+    }
+
+    let outcome = match (changed, dry_run, uninstall) {
+        (false, _, false) => "already up to date",
+        (false, _, true) => "nothing to remove",
+        (true, true, _) => "pending changes (dry run)",
+        (true, false, false) => "hooks installed",
+        (true, false, true) => "hooks removed",
+    };
+    println!("{}: {}", name, outcome);
+    Ok(())
+}
@@ -1,31 +1,151 @@
-use crate::auth::{CredentialStore, OAuthClient};
+use crate::auth::types::StoredCredentials;
+use crate::auth::{CredentialStore, OAuthClient, profiles};
 use crate::commands::flush_metrics_db::spawn_background_metrics_db_flush;
 use crate::metrics::db::MetricsDatabase;
 
 /// Handle the `git-ai login` command
-pub fn handle_login(_args: &[String]) {
+pub fn handle_login(args: &[String]) {
+    let args = match profiles::take_flag(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let usage = || {
+        eprintln!("Usage: git-ai login [--device | --token <key>] [--profile <name>]");
+        std::process::exit(1);
+    };
+    let mut device = false;
+    let mut token = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--device" => device = true,
+            "--token" => match args.get(i + 1) {
+                Some(key) => {
+                    token = Some(key.clone());
+                    i += 1;
+                }
+                None => {
+                    eprintln!("--token requires a key, or '-' to read it from stdin");
+                    usage();
+                }
+            },
+            arg if arg.starts_with("--token=") => token = Some(arg["--token=".len()..].to_string()),
+            arg => {
+                eprintln!("Unknown login argument: {}", arg);
+                usage();
+            }
+        }
+        i += 1;
+    }
+    // Over SSH or in a container there is no browser to open here
+    let device = device || no_local_browser(|var| std::env::var(var).ok());
     let store = CredentialStore::new();
+    let profile_note = match profiles::active() {
+        Some(name) => format!(" to profile '{}'", name),
+        None => String::new(),
+    };
+
+    if let Some(token) = token {
+        store_service_token(&store, &token, &profile_note);
+        return;
+    }
 
     // Check if already logged in
     if let Ok(Some(creds)) = store.load()
         && !creds.is_refresh_token_expired()
     {
-        eprintln!("Already logged in. Use 'git-ai logout' to log out first.");
+        eprintln!(
+            "Already logged in{}. Use 'git-ai logout' to log out first.",
+            profile_note
+        );
         std::process::exit(0);
     }
 
     let client = OAuthClient::new();
+    let result = if client.supports_browser_login() && !device {
+        browser_login(&client)
+    } else {
+        device_login(&client, device)
+    };
 
-    // Start device flow
-    eprintln!("Starting device authorization...\n");
+    match result {
+        Ok(creds) => {
+            // Store credentials
+            if let Err(e) = store.store(&creds) {
+                eprintln!("\nWarning: Failed to store credentials: {}", e);
+                eprintln!("You may need to log in again next time.");
+            }
 
-    let auth_response = match client.start_device_flow() {
-        Ok(response) => response,
+            eprintln!("\nSuccessfully logged in{}!", profile_note);
+
+            // Check if there's queued metrics data to sync
+            if let Ok(db) = MetricsDatabase::global()
+                && let Ok(db_lock) = db.lock()
+                && let Ok(count) = db_lock.count()
+                && count > 0
+            {
+                // Spawn background metrics flush now that we're logged in
+                spawn_background_metrics_db_flush();
+                // Inform the user
+                eprintln!("Syncing your Git AI dashboard in the background...");
+            }
+        }
         Err(e) => {
-            eprintln!("Failed to start authorization: {}", e);
+            eprintln!("\nAuthorization failed: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Store a service token, replacing whatever credentials the profile had. `-` reads
+/// the token from stdin, which keeps it out of the shell history.
+fn store_service_token(store: &CredentialStore, token: &str, profile_note: &str) {
+    let token = if token == "-" {
+        let mut input = String::new();
+        if let Err(e) = std::io::stdin().read_line(&mut input) {
+            eprintln!("Failed to read the token from stdin: {}", e);
             std::process::exit(1);
         }
+        input.trim().to_string()
+    } else {
+        token.trim().to_string()
     };
+    if token.is_empty() {
+        eprintln!("The token is empty");
+        std::process::exit(1);
+    }
+    if let Err(e) = store.store(&StoredCredentials::service_token(&token)) {
+        eprintln!("Failed to store the token: {}", e);
+        std::process::exit(1);
+    }
+    eprintln!("Stored the service token{}.", profile_note);
+}
+
+/// Log in at the profile's identity provider in this machine's browser
+fn browser_login(client: &OAuthClient) -> Result<StoredCredentials, String> {
+    eprintln!("Starting browser authorization...\n");
+    client.authorize_in_browser(|url| {
+        eprintln!("Log in in your browser. If it doesn't open, visit:");
+        eprintln!("  {}", url);
+        eprintln!();
+        if open_browser(url).is_err() {
+            eprintln!("  (Could not open browser automatically)");
+            eprintln!();
+        }
+        eprintln!("Waiting for authorization...");
+    })
+}
+
+fn device_login(client: &OAuthClient, device: bool) -> Result<StoredCredentials, String> {
+    // Start device flow
+    eprintln!("Starting device authorization...\n");
+
+    let auth_response = client
+        .start_device_flow()
+        .map_err(|e| format!("Failed to start authorization: {}", e))?;
 
     // Build the display URL
     let display_url = auth_response
@@ -35,7 +155,11 @@ pub fn handle_login(_args: &[String]) {
 
     // Display instructions
     eprintln!("To authorize this device:");
-    eprintln!("  1. Open this URL in your browser:");
+    if device {
+        eprintln!("  1. On any device with a browser, open this URL:");
+    } else {
+        eprintln!("  1. Open this URL in your browser:");
+    }
     eprintln!("     {}", display_url);
     eprintln!();
     eprintln!("  2. Enter this code when prompted:");
@@ -43,7 +167,7 @@ pub fn handle_login(_args: &[String]) {
     eprintln!();
 
     // Try to open browser automatically
-    if open_browser(display_url).is_err() {
+    if !device && open_browser(display_url).is_err() {
         eprintln!("  (Could not open browser automatically)");
         eprintln!();
     }
@@ -51,37 +175,20 @@ pub fn handle_login(_args: &[String]) {
     eprintln!("Waiting for authorization...");
 
     // Poll for token
-    match client.poll_for_token(
+    client.poll_for_token(
         &auth_response.device_code,
         auth_response.interval,
         auth_response.expires_in,
-    ) {
-        Ok(creds) => {
-            // Store credentials
-            if let Err(e) = store.store(&creds) {
-                eprintln!("\nWarning: Failed to store credentials: {}", e);
-                eprintln!("You may need to log in again next time.");
-            }
-
-            eprintln!("\nSuccessfully logged in!");
+    )
+}
 
-            // Check if there's queued metrics data to sync
-            if let Ok(db) = MetricsDatabase::global()
-                && let Ok(db_lock) = db.lock()
-                && let Ok(count) = db_lock.count()
-                && count > 0
-            {
-                // Spawn background metrics flush now that we're logged in
-                spawn_background_metrics_db_flush();
-                // Inform the user
-                eprintln!("Syncing your Git AI dashboard in the background...");
-            }
-        }
-        Err(e) => {
-            eprintln!("\nAuthorization failed: {}", e);
-            std::process::exit(1);
-        }
+/// Whether this machine looks headless: an SSH session, or Linux without a display.
+fn no_local_browser(env: impl Fn(&str) -> Option<String>) -> bool {
+    let set = |var: &str| env(var).is_some_and(|value| !value.is_empty());
+    if set("SSH_CONNECTION") || set("SSH_TTY") {
+        return true;
     }
+    cfg!(target_os = "linux") && !set("DISPLAY") && !set("WAYLAND_DISPLAY")
 }
 
 /// Attempt to open a URL in the system's default browser
@@ -112,3 +219,26 @@ fn open_browser(url: &str) -> Result<(), String> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_local_browser() {
+        let env = |pairs: &'static [(&'static str, &'static str)]| {
+            move |var: &str| {
+                pairs
+                    .iter()
+                    .find(|(name, _)| *name == var)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+        assert!(no_local_browser(env(&[
+            ("SSH_CONNECTION", "10.0.0.1 22 10.0.0.2 22"),
+            ("DISPLAY", ":0"),
+        ])));
+        assert!(!no_local_browser(env(&[("DISPLAY", ":0")])));
+        assert_eq!(no_local_browser(env(&[])), cfg!(target_os = "linux"));
+    }
+}
@@ -1,25 +1,92 @@
-use crate::auth::CredentialStore;
+use crate::auth::types::StoredCredentials;
+use crate::auth::{CredentialStore, OAuthClient, profiles};
 
 /// Handle the `git-ai logout` command
-pub fn handle_logout(_args: &[String]) {
-    let store = CredentialStore::new();
-
-    // Check if currently logged in
-    match store.load() {
-        Ok(Some(_)) => {
-            // Clear credentials
-            if let Err(e) = store.clear() {
-                eprintln!("Failed to clear credentials: {}", e);
+pub fn handle_logout(args: &[String]) {
+    let args = match profiles::take_flag(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+    };
+    let mut all_profiles = false;
+    for arg in &args {
+        match arg.as_str() {
+            "--all-profiles" => all_profiles = true,
+            arg => {
+                eprintln!("Unknown logout argument: {}", arg);
+                eprintln!("Usage: git-ai logout [--all-profiles | --profile <name>]");
                 std::process::exit(1);
             }
-            eprintln!("Successfully logged out.");
-        }
-        Ok(None) => {
-            eprintln!("Not currently logged in.");
         }
-        Err(e) => {
-            eprintln!("Error checking credentials: {}", e);
-            std::process::exit(1);
+    }
+
+    let targets = if all_profiles {
+        profiles::known()
+    } else {
+        vec![profiles::active()]
+    };
+
+    let mut failed = false;
+    let mut logged_out = 0;
+    for profile in &targets {
+        let profile_note = match profile {
+            Some(name) => format!(" (profile '{}')", name),
+            None => String::new(),
+        };
+        match logout(profile.as_deref()) {
+            Ok(true) => {
+                logged_out += 1;
+                eprintln!("Successfully logged out{}.", profile_note);
+            }
+            // Profiles that were never used aren't worth a line each
+            Ok(false) if all_profiles => {}
+            Ok(false) => eprintln!("Not currently logged in{}.", profile_note),
+            Err(e) => {
+                failed = true;
+                eprintln!("Failed to log out{}: {}", profile_note, e);
+            }
         }
     }
+    if all_profiles && logged_out == 0 && !failed {
+        eprintln!("Not currently logged in to any profile.");
+    }
+    if failed {
+        std::process::exit(1);
+    }
+}
+
+/// Revoke and remove a profile's credentials; false if there were none
+fn logout(profile: Option<&str>) -> Result<bool, String> {
+    let store = CredentialStore::for_profile(profile);
+    let Some(creds) = store
+        .load()
+        .map_err(|e| format!("Error checking credentials: {}", e))?
+    else {
+        return Ok(false);
+    };
+
+    // Local credentials go regardless: being unable to reach the server must not
+    // keep anyone logged in
+    if let Err(e) = revoke(profile, &creds) {
+        crate::logging::warn(&format!("{}. The tokens stay valid until they expire.", e));
+    }
+    store
+        .clear()
+        .map_err(|e| format!("Failed to clear credentials: {}", e))?;
+    Ok(true)
+}
+
+fn revoke(profile: Option<&str>, creds: &StoredCredentials) -> Result<(), String> {
+    // Service tokens are managed on the dashboard, not through OAuth
+    if creds.service || creds.is_refresh_token_expired() {
+        return Ok(());
+    }
+    let client = OAuthClient::for_profile(profile)?;
+    client.revoke_token(&creds.refresh_token, "refresh_token")?;
+    if !creds.is_access_token_expired(0) {
+        client.revoke_token(&creds.access_token, "access_token")?;
+    }
+    Ok(())
 }
@@ -0,0 +1,427 @@
+//! `git-ai lsp`: a minimal Language Server on stdio that shows AI attribution as
+//! inlay hints and code lenses, so any LSP-capable editor can display it without a
+//! bespoke plugin.
+//!
+//! Documents are synced in full and blamed with the editor's buffer contents, so
+//! hints stay on the right lines while the file is being edited.
+
+use crate::commands::blame::GitAiBlameOptions;
+use crate::commands::daemon::{INVALID_PARAMS, METHOD_NOT_FOUND, RpcError, error_response};
+use crate::commands::status::format_time_ago;
+use crate::error::GitAiError;
+use crate::git::find_repository_in_path;
+use crate::git::repository::Repository;
+use crate::utils::debug_log;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// An AI-authored line; `line` is 0-based as in LSP positions.
+#[derive(Debug, Clone, PartialEq)]
+struct LineHint {
+    line: u32,
+    label: String,
+}
+
+#[derive(Default)]
+struct Document {
+    text: String,
+    /// Computed on the first request after each change
+    hints: Option<Vec<LineHint>>,
+}
+
+#[derive(Default)]
+struct LspServer {
+    documents: HashMap<String, Document>,
+    /// Opened repositories by workdir
+    repos: HashMap<PathBuf, Repository>,
+    shutdown_requested: bool,
+}
+
+pub fn handle_lsp(args: &[String]) {
+    if let Some(arg) = args.iter().find(|arg| arg.as_str() != "--stdio") {
+        eprintln!("Unknown lsp argument: {}", arg);
+        eprintln!("Usage: git-ai lsp [--stdio]");
+        std::process::exit(1);
+    }
+
+    let mut server = LspServer::default();
+    let mut reader = BufReader::new(std::io::stdin().lock());
+    let mut stdout = std::io::stdout().lock();
+    loop {
+        let message = match read_message(&mut reader) {
+            Ok(Some(message)) => message,
+            // The client went away without `exit`
+            Ok(None) => std::process::exit(1),
+            Err(e) => {
+                debug_log(&format!("lsp: failed to read message: {}", e));
+                std::process::exit(1);
+            }
+        };
+        if message.get("method").and_then(|m| m.as_str()) == Some("exit") {
+            std::process::exit(if server.shutdown_requested { 0 } else { 1 });
+        }
+        if let Some(response) = server.handle_message(&message)
+            && write_message(&mut stdout, &response).is_err()
+        {
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Read one `Content-Length` framed message; `None` at end of input.
+fn read_message(reader: &mut impl BufRead) -> Result<Option<Value>, GitAiError> {
+    let mut content_length = None;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            if content_length.is_some() {
+                break;
+            }
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value.trim().parse::<usize>().ok();
+        }
+    }
+
+    let mut body = vec![0; content_length.unwrap_or_default()];
+    reader.read_exact(&mut body)?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+fn write_message(writer: &mut impl Write, message: &Value) -> std::io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+impl LspServer {
+    /// Handle a request or notification; returns the response for requests.
+    fn handle_message(&mut self, message: &Value) -> Option<Value> {
+        let method = message.get("method").and_then(|m| m.as_str())?;
+        let params = message.get("params").unwrap_or(&Value::Null);
+        let Some(id) = message.get("id").cloned() else {
+            self.handle_notification(method, params);
+            return None;
+        };
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": {"openClose": true, "change": 1, "save": true},
+                    "inlayHintProvider": true,
+                    "codeLensProvider": {"resolveProvider": false},
+                },
+                "serverInfo": {"name": "git-ai", "version": env!("CARGO_PKG_VERSION")},
+            })),
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Ok(Value::Null)
+            }
+            "textDocument/inlayHint" => self.inlay_hints(params),
+            "textDocument/codeLens" => self.code_lenses(params),
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("Unsupported method: {}", method),
+            )),
+        };
+        Some(match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": id, "result": result}),
+            Err(error) => error_response(id, error),
+        })
+    }
+
+    fn handle_notification(&mut self, method: &str, params: &Value) {
+        let Some(uri) = params["textDocument"]["uri"].as_str() else {
+            return;
+        };
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents.insert(
+                    uri.to_string(),
+                    Document {
+                        text: text.to_string(),
+                        hints: None,
+                    },
+                );
+            }
+            "textDocument/didChange" => {
+                // Full sync: the last change holds the whole document
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                if let (Some(document), Some(text)) = (self.documents.get_mut(uri), text) {
+                    document.text = text.to_string();
+                    document.hints = None;
+                }
+            }
+            // A save often comes with a commit or checkpoint changing the attribution
+            "textDocument/didSave" => {
+                if let Some(document) = self.documents.get_mut(uri) {
+                    document.hints = None;
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+            }
+            _ => {}
+        }
+    }
+
+    fn inlay_hints(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let uri = document_uri(params)?;
+        let (start, end) = requested_lines(params);
+        let hints = self.hints_for(&uri)?;
+        let text = &self.documents[&uri].text;
+        let lines: Vec<&str> = text.lines().collect();
+
+        let items: Vec<Value> = hints
+            .iter()
+            .filter(|hint| hint.line >= start && hint.line <= end)
+            .map(|hint| {
+                let character = lines
+                    .get(hint.line as usize)
+                    .map(|line| line.encode_utf16().count())
+                    .unwrap_or_default();
+                json!({
+                    "position": {"line": hint.line, "character": character},
+                    "label": hint.label,
+                    "paddingLeft": true,
+                })
+            })
+            .collect();
+        Ok(json!(items))
+    }
+
+    fn code_lenses(&mut self, params: &Value) -> Result<Value, RpcError> {
+        let uri = document_uri(params)?;
+        let hints = self.hints_for(&uri)?;
+
+        // One lens above each run of consecutive lines with the same attribution
+        let mut lenses = Vec::new();
+        let mut previous: Option<&LineHint> = None;
+        for hint in &hints {
+            let continues_run =
+                previous.is_some_and(|p| p.line + 1 == hint.line && p.label == hint.label);
+            if !continues_run {
+                let position = json!({"line": hint.line, "character": 0});
+                lenses.push(json!({
+                    "range": {"start": position, "end": position},
+                    "command": {"title": hint.label, "command": ""},
+                }));
+            }
+            previous = Some(hint);
+        }
+        Ok(json!(lenses))
+    }
+
+    fn hints_for(&mut self, uri: &str) -> Result<Vec<LineHint>, RpcError> {
+        let path = url::Url::parse(uri)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Not a file URI: {}", uri)))?;
+
+        if !self.documents.contains_key(uri) {
+            // Clients may ask about files they never opened
+            let text = std::fs::read_to_string(&path).unwrap_or_default();
+            self.documents
+                .insert(uri.to_string(), Document { text, hints: None });
+        }
+        if let Some(hints) = &self.documents[uri].hints {
+            return Ok(hints.clone());
+        }
+
+        let text = self.documents[uri].text.clone();
+        let hints = match self.repo_for(&path) {
+            Some(repo) => ai_line_hints(repo, &path, &text).unwrap_or_else(|e| {
+                debug_log(&format!("lsp: blame failed for {}: {}", path.display(), e));
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        if let Some(document) = self.documents.get_mut(uri) {
+            document.hints = Some(hints.clone());
+        }
+        Ok(hints)
+    }
+
+    fn repo_for(&mut self, path: &Path) -> Option<&Repository> {
+        let dir = path.parent()?;
+        if let Some(workdir) = self
+            .repos
+            .keys()
+            .filter(|workdir| dir.starts_with(workdir))
+            .max_by_key(|workdir| workdir.as_os_str().len())
+            .cloned()
+        {
+            return self.repos.get(&workdir);
+        }
+        let repo = find_repository_in_path(&dir.to_string_lossy()).ok()?;
+        let workdir = repo.workdir().ok()?;
+        Some(self.repos.entry(workdir).or_insert(repo))
+    }
+}
+
+fn document_uri(params: &Value) -> Result<String, RpcError> {
+    params["textDocument"]["uri"]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| RpcError::new(INVALID_PARAMS, "textDocument.uri is required"))
+}
+
+/// 0-based inclusive line range of a request, the whole document when absent.
+fn requested_lines(params: &Value) -> (u32, u32) {
+    let line = |pos: &str| params["range"][pos]["line"].as_u64().map(|l| l as u32);
+    (line("start").unwrap_or(0), line("end").unwrap_or(u32::MAX))
+}
+
+/// Hints for the AI-authored lines of `text`, the editor's contents of `path`.
+fn ai_line_hints(repo: &Repository, path: &Path, text: &str) -> Result<Vec<LineHint>, GitAiError> {
+    let total_lines = text.lines().count() as u32;
+    if total_lines == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut options = GitAiBlameOptions::default();
+    #[allow(clippy::field_reassign_with_default)]
+    {
+        options.no_output = true;
+        options.use_prompt_hashes_as_names = true;
+        options.contents_data = Some(text.as_bytes().to_vec());
+    }
+    let file = path.to_string_lossy();
+    let (line_authors, prompt_records) = repo.blame(&file, &options)?;
+    if prompt_records.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Native blame again for the time each line was written
+    let canonical = path.canonicalize()?;
+    let relative = canonical
+        .strip_prefix(repo.canonical_workdir())
+        .map_err(|_| GitAiError::Generic(format!("{} is outside the repository", path.display())))?
+        .to_string_lossy()
+        .to_string();
+    let mut authored_at = HashMap::new();
+    for hunk in repo.blame_hunks(&relative, 1, total_lines, &options)? {
+        for line in hunk.range.0..=hunk.range.1 {
+            authored_at.insert(line, hunk.author_time);
+        }
+    }
+
+    let mut hints: Vec<LineHint> = line_authors
+        .iter()
+        .filter_map(|(line, author)| {
+            let prompt = prompt_records.get(author)?;
+            let agent = &prompt.agent_id;
+            let name = if agent.model.is_empty() || agent.model == "unknown" {
+                &agent.tool
+            } else {
+                &agent.model
+            };
+            let label = match authored_at.get(line) {
+                Some(time) => format!("AI: {}, {}", name, format_time_ago(*time as u64)),
+                None => format!("AI: {}", name),
+            };
+            Some(LineHint {
+                line: line - 1,
+                label,
+            })
+        })
+        .collect();
+    hints.sort_by_key(|hint| hint.line);
+    Ok(hints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn framed(body: &str) -> String {
+        format!("Content-Length: {}\r\n\r\n{}", body.len(), body)
+    }
+
+    #[test]
+    fn test_read_message_framing() {
+        let input = format!(
+            "{}{}",
+            framed(r#"{"jsonrpc":"2.0","id":1,"method":"initialize"}"#),
+            framed(r#"{"jsonrpc":"2.0","method":"exit"}"#)
+        );
+        let mut reader = std::io::Cursor::new(input.into_bytes());
+        let first = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(first["method"], "initialize");
+        let second = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(second["method"], "exit");
+        assert!(read_message(&mut reader).unwrap().is_none());
+
+        let mut out = Vec::new();
+        write_message(&mut out, &json!({"id": 1})).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), framed(r#"{"id":1}"#));
+    }
+
+    #[test]
+    fn test_code_lenses_group_runs() {
+        let mut server = LspServer::default();
+        let uri = "file:///tmp/example.rs";
+        let hint = |line: u32, label: &str| LineHint {
+            line,
+            label: label.to_string(),
+        };
+        server.documents.insert(
+            uri.to_string(),
+            Document {
+                text: "a\nb\nc\nd\ne\n".to_string(),
+                hints: Some(vec![
+                    hint(0, "AI: gpt-5, 1 days ago"),
+                    hint(1, "AI: gpt-5, 1 days ago"),
+                    hint(3, "AI: gpt-5, 1 days ago"),
+                    hint(4, "AI: claude, 2 days ago"),
+                ]),
+            },
+        );
+
+        let params = json!({"textDocument": {"uri": uri}});
+        let lenses = server.code_lenses(&params).unwrap();
+        let starts: Vec<u64> = lenses
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|lens| lens["range"]["start"]["line"].as_u64().unwrap())
+            .collect();
+        assert_eq!(starts, vec![0, 3, 4]);
+
+        let params = json!({
+            "textDocument": {"uri": uri},
+            "range": {"start": {"line": 1, "character": 0}, "end": {"line": 3, "character": 0}},
+        });
+        let hints = server.inlay_hints(&params).unwrap();
+        let hints = hints.as_array().unwrap();
+        assert_eq!(hints.len(), 2);
+        assert_eq!(hints[0]["position"], json!({"line": 1, "character": 1}));
+    }
+
+    #[test]
+    fn test_unknown_request_and_notifications() {
+        let mut server = LspServer::default();
+        let response = server
+            .handle_message(&json!({"jsonrpc": "2.0", "id": 7, "method": "textDocument/hover"}))
+            .unwrap();
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+        assert!(
+            server
+                .handle_message(&json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}))
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,257 @@
+//! `git-ai manifest`: a machine-readable disclosure of the AI-generated content of a
+//! commit, for procurement and compliance reviews.
+//!
+//! The `spdx-like` format borrows the shape of an SPDX 2.3 JSON document: a creation
+//! section, then one entry per file with its SHA-256 checksum, here listing the AI
+//! tools and models its lines are credited to and their share. Files without AI lines
+//! are left out unless `--all-files` is given. The creation time is the commit's, so
+//! the manifest of a commit is the same whenever it is generated.
+
+use crate::commands::blame::GitAiBlameOptions;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::Repository;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+
+const MANIFEST_VERSION: &str = "git-ai-provenance-1.0";
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub manifest_version: String,
+    #[serde(rename = "SPDXID")]
+    pub spdx_id: String,
+    pub name: String,
+    pub creation_info: CreationInfo,
+    pub commit: String,
+    pub summary: ManifestSummary,
+    pub files: Vec<ManifestFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreationInfo {
+    pub created: String,
+    pub creators: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestSummary {
+    pub files: usize,
+    pub files_with_ai_content: usize,
+    pub lines: u32,
+    pub ai_lines: u32,
+    pub ai_percent: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestFile {
+    #[serde(rename = "SPDXID")]
+    pub spdx_id: String,
+    pub file_name: String,
+    pub checksums: Vec<Checksum>,
+    pub lines: u32,
+    pub ai_lines: u32,
+    pub ai_percent: f64,
+    pub ai_contributors: Vec<AiContributor>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Checksum {
+    pub algorithm: String,
+    pub checksum_value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiContributor {
+    pub tool: String,
+    pub model: String,
+    pub lines: u32,
+    pub percent: f64,
+}
+
+pub fn handle_manifest(args: &[String]) {
+    let usage = "Usage: git-ai manifest [<rev>] [--format spdx-like] [--all-files]";
+    let mut rev = "HEAD".to_string();
+    let mut all_files = false;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--format" if i + 1 < args.len() => {
+                if args[i + 1] != "spdx-like" {
+                    eprintln!(
+                        "Unknown manifest format: {} (supported: spdx-like)",
+                        args[i + 1]
+                    );
+                    std::process::exit(1);
+                }
+                i += 1;
+            }
+            "--format=spdx-like" => {}
+            "--all-files" => all_files = true,
+            arg if !arg.starts_with('-') => rev = arg.to_string(),
+            _ => {
+                eprintln!("{}", usage);
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    let result = find_repository(&Vec::<String>::new())
+        .and_then(|repo| build_manifest(&repo, &rev, all_files));
+    match result {
+        Ok(manifest) => println!("{}", serde_json::to_string_pretty(&manifest).unwrap()),
+        Err(e) => {
+            crate::error::exit_with(&format!("Failed to build manifest: {}", e), &e);
+        }
+    }
+}
+
+pub fn build_manifest(
+    repo: &Repository,
+    rev: &str,
+    all_files: bool,
+) -> Result<Manifest, GitAiError> {
+    let commit = repo
+        .git(&["rev-parse", "--verify", &format!("{}^{{commit}}", rev)])?
+        .trim()
+        .to_string();
+    let created = repo
+        .git(&["log", "-1", "--format=%cI", &commit])?
+        .trim()
+        .to_string();
+
+    let mut summary = ManifestSummary::default();
+    let mut files = Vec::new();
+    for path in repo
+        .git(&["ls-tree", "-r", "--name-only", &commit])?
+        .lines()
+    {
+        let Ok(contents) = repo.git(&["show", &format!("{}:{}", commit, path)]) else {
+            continue;
+        };
+        if contents.contains('\0') {
+            continue;
+        }
+        let lines = contents.lines().count() as u32;
+
+        let mut options = GitAiBlameOptions::default();
+        #[allow(clippy::field_reassign_with_default)]
+        {
+            options.newest_commit = Some(commit.clone());
+            options.no_output = true;
+            options.use_prompt_hashes_as_names = true;
+        }
+        let (line_authors, prompt_records) = if lines > 0 {
+            repo.blame(path, &options).unwrap_or_default()
+        } else {
+            Default::default()
+        };
+        let mut by_agent: BTreeMap<(String, String), u32> = BTreeMap::new();
+        for author in line_authors.values() {
+            if let Some(record) = prompt_records.get(author) {
+                *by_agent
+                    .entry((record.agent_id.tool.clone(), record.agent_id.model.clone()))
+                    .or_default() += 1;
+            }
+        }
+        let ai_lines: u32 = by_agent.values().sum();
+
+        summary.files += 1;
+        summary.lines += lines;
+        summary.ai_lines += ai_lines;
+        if ai_lines > 0 {
+            summary.files_with_ai_content += 1;
+        } else if !all_files {
+            continue;
+        }
+        let mut ai_contributors: Vec<AiContributor> = by_agent
+            .into_iter()
+            .map(|((tool, model), count)| AiContributor {
+                tool,
+                model,
+                lines: count,
+                percent: percent(count, lines),
+            })
+            .collect();
+        ai_contributors.sort_by_key(|contributor| std::cmp::Reverse(contributor.lines));
+        files.push(ManifestFile {
+            spdx_id: spdx_ref(path),
+            file_name: format!("./{}", path),
+            checksums: vec![Checksum {
+                algorithm: "SHA256".to_string(),
+                checksum_value: format!("{:x}", Sha256::digest(contents.as_bytes())),
+            }],
+            lines,
+            ai_lines,
+            ai_percent: percent(ai_lines, lines),
+            ai_contributors,
+        });
+    }
+    summary.ai_percent = percent(summary.ai_lines, summary.lines);
+
+    Ok(Manifest {
+        manifest_version: MANIFEST_VERSION.to_string(),
+        spdx_id: "SPDXRef-DOCUMENT".to_string(),
+        name: format!("git-ai-provenance-{}", &commit[..12.min(commit.len())]),
+        creation_info: CreationInfo {
+            created,
+            creators: vec![format!("Tool: git-ai-{}", env!("CARGO_PKG_VERSION"))],
+        },
+        commit,
+        summary,
+        files,
+    })
+}
+
+/// Share of `part` in `total` as a percentage, to one decimal
+fn percent(part: u32, total: u32) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    (part as f64 * 1000.0 / total as f64).round() / 10.0
+}
+
+/// An SPDX element identifier for `path`: letters, digits, `.` and `-` only
+fn spdx_ref(path: &str) -> String {
+    let id: String = path
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("SPDXRef-File-{}", id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percent() {
+        assert_eq!(percent(1, 3), 33.3);
+        assert_eq!(percent(2, 3), 66.7);
+        assert_eq!(percent(0, 0), 0.0);
+        assert_eq!(percent(5, 5), 100.0);
+    }
+
+    #[test]
+    fn test_spdx_ref() {
+        assert_eq!(spdx_ref("src/lib.rs"), "SPDXRef-File-src-lib.rs");
+        assert_eq!(
+            spdx_ref("docs/read me_1.md"),
+            "SPDXRef-File-docs-read-me-1.md"
+        );
+    }
+}
@@ -1,27 +1,49 @@
+pub mod apply_ai_patch;
+pub mod audit;
+pub mod auth;
+pub mod bench;
 pub mod blame;
 pub mod checkpoint;
 pub mod checkpoint_agent;
+pub mod ci_gate;
 pub mod ci_handlers;
 pub mod config;
+pub mod daemon;
 pub mod diff;
+pub mod digest;
+pub mod editor_api;
 pub mod exchange_nonce;
+pub mod export;
 pub mod flush_cas;
 pub mod flush_logs;
 pub mod flush_metrics_db;
 pub mod git_ai_handlers;
 pub mod git_handlers;
+pub mod git_hooks;
+pub mod graph;
 pub mod hooks;
+pub mod import;
+pub mod ingest;
 pub mod install_hooks;
+pub mod integrate;
 pub mod login;
 pub mod logout;
+pub mod lsp;
+pub mod manifest;
 pub mod personal_dashboard;
+pub mod policy;
 pub mod prompt_picker;
 pub mod prompts_db;
+pub mod report;
+pub mod review_pending;
 pub mod share;
 pub mod share_tui;
+pub mod shortlog;
 pub mod show;
 pub mod show_prompt;
 pub mod squash_authorship;
 pub mod status;
+pub mod summary;
 pub mod sync_prompts;
 pub mod upgrade;
+pub mod wrap;
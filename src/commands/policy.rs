@@ -0,0 +1,67 @@
+//! `git-ai policy check [<base>[..<head>]]`: the commits of a change checked against
+//! the `.git-ai/policy.toml` of the working tree, to try out a policy before the hooks
+//! and `ci-gate` enforce it.
+//!
+//! The range defaults to that of `ci-gate`. Violations are listed on stderr and exit 1.
+
+use crate::authorship::policy::{POLICY_FILE, Policy};
+use crate::commands::ci_gate::RequestRange;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+
+pub fn handle_policy(args: &[String]) {
+    let usage = "Usage: git-ai policy check [<base>[..<head>]]";
+    if args.first().map(String::as_str) != Some("check") || args.len() > 2 {
+        eprintln!("{}", usage);
+        std::process::exit(1);
+    }
+    let range = args.get(1).cloned();
+    if range.as_deref().is_some_and(|r| r.starts_with('-')) {
+        eprintln!("{}", usage);
+        std::process::exit(1);
+    }
+
+    let result = (|| {
+        let repo = find_repository(&Vec::<String>::new())?;
+        let Some(policy) = Policy::load(&repo)? else {
+            return Ok(None);
+        };
+        let request = RequestRange::resolve(&repo, range.as_deref())?;
+        let violations = policy.evaluate_commits(&repo, &request.commits)?;
+        Ok::<_, GitAiError>(Some((
+            policy.rules.len(),
+            request.commits.len(),
+            violations,
+        )))
+    })();
+
+    match result {
+        Ok(None) => eprintln!("git-ai: no {} in this repository", POLICY_FILE),
+        Ok(Some((rules, checked, violations))) => {
+            if violations.is_empty() {
+                eprintln!(
+                    "git-ai: {} commit(s) pass {} rule(s) of {}",
+                    checked, rules, POLICY_FILE
+                );
+                return;
+            }
+            eprintln!(
+                "git-ai: {} violation(s) of {}",
+                violations.len(),
+                POLICY_FILE
+            );
+            for v in &violations {
+                eprintln!(
+                    "  {} {}: {}",
+                    &v.commit_sha[..7.min(v.commit_sha.len())],
+                    v.subject,
+                    v.reason
+                );
+            }
+            std::process::exit(1);
+        }
+        Err(e) => {
+            crate::error::exit_with(&format!("git-ai: policy check failed: {}", e), &e);
+        }
+    }
+}
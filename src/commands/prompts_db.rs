@@ -143,8 +143,7 @@ fn handle_populate(args: &[String]) {
     let since_timestamp = match parse_since_arg(&since_str) {
         Ok(ts) => ts,
         Err(e) => {
-            eprintln!("Error parsing --since: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Error parsing --since: {}", e), &e);
         }
     };
 
@@ -258,8 +257,7 @@ fn handle_exec(args: &[String]) {
     let conn = match open_prompts_db() {
         Ok(c) => c,
         Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Error: {}", e), &e);
         }
     };
 
@@ -353,8 +351,7 @@ fn handle_list(args: &[String]) {
     let conn = match open_prompts_db() {
         Ok(c) => c,
         Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Error: {}", e), &e);
         }
     };
 
@@ -416,8 +413,7 @@ fn handle_next(_args: &[String]) {
     let conn = match open_prompts_db() {
         Ok(c) => c,
         Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Error: {}", e), &e);
         }
     };
 
@@ -496,8 +492,7 @@ fn handle_reset(_args: &[String]) {
     let conn = match open_prompts_db() {
         Ok(c) => c,
         Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Error: {}", e), &e);
         }
     };
 
@@ -521,8 +516,7 @@ fn handle_count(_args: &[String]) {
     let conn = match open_prompts_db() {
         Ok(c) => c,
         Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Error: {}", e), &e);
         }
     };
 
@@ -0,0 +1,469 @@
+//! `git-ai report`: a static HTML report of who wrote the files of a commit.
+//!
+//! `index.html` is a treemap per directory, with one tile per file sized by its line
+//! count and shaded by its AI share: human, AI, or mixed. Each tile links to the
+//! file's page under `files/`, which renders its source with every line's background
+//! shaded by its author: human, or one colour per AI tool. The report has no scripts
+//! or external assets, so it can be published as a CI artifact as is.
+//!
+//! `--by-language` adds a table of the AI share of each language to the index.
+//!
+//! `--anonymize` replaces each path with a stable pseudonym that keeps only its
+//! extension (see `authorship::anonymize`), so all files share one treemap, and leaves
+//! the lines of the file pages blank: only their shading is kept.
+//!
+//! Each file's page is written as soon as its file is blamed and only its line counts
+//! are kept for the index, so memory doesn't grow with the size of the tree.
+
+use crate::authorship::anonymize::Anonymizer;
+use crate::authorship::languages::{LanguageLines, group_by_language, sorted_by_lines};
+use crate::commands::blame::GitAiBlameOptions;
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::Repository;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+const DEFAULT_OUTPUT_DIR: &str = "git-ai-report";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineAuthor {
+    Human,
+    Ai(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct FileReport {
+    pub path: String,
+    pub lines: Vec<(String, LineAuthor)>,
+}
+
+impl FileReport {
+    pub fn ai_lines(&self) -> usize {
+        self.lines
+            .iter()
+            .filter(|(_, author)| matches!(author, LineAuthor::Ai(_)))
+            .count()
+    }
+
+    /// Share (0-100) of the file's lines written by AI
+    pub fn ai_percent(&self) -> u32 {
+        self.totals().ai_percent()
+    }
+
+    pub fn totals(&self) -> FileTotals {
+        FileTotals {
+            path: self.path.clone(),
+            lines: self.lines.len(),
+            ai_lines: self.ai_lines(),
+        }
+    }
+}
+
+/// Line counts of a file, all the index needs of it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileTotals {
+    pub path: String,
+    pub lines: usize,
+    pub ai_lines: usize,
+}
+
+impl FileTotals {
+    /// Share (0-100) of the file's lines written by AI
+    pub fn ai_percent(&self) -> u32 {
+        (self.ai_lines * 100).checked_div(self.lines).unwrap_or(0) as u32
+    }
+}
+
+pub fn handle_report(args: &[String]) {
+    let mut output = PathBuf::from(DEFAULT_OUTPUT_DIR);
+    let mut rev = "HEAD".to_string();
+    let mut by_language = false;
+    let mut anonymize = false;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--output" | "-o" if i + 1 < args.len() => {
+                output = PathBuf::from(&args[i + 1]);
+                i += 1;
+            }
+            "--by-language" => by_language = true,
+            "--anonymize" => anonymize = true,
+            arg if !arg.starts_with('-') => rev = arg.to_string(),
+            _ => {
+                eprintln!(
+                    "Usage: git-ai report [<rev>] [--output <dir>] [--by-language] [--anonymize]"
+                );
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    let result = (|| {
+        let repo = find_repository(&Vec::<String>::new())?;
+        let anonymizer = anonymize.then(Anonymizer::load).transpose()?;
+        write_report(&repo, &output, &rev, by_language, anonymizer.as_ref())
+    })();
+    match result {
+        Ok(count) => println!(
+            "Wrote a report of {} files to {}",
+            count,
+            output.join("index.html").display()
+        ),
+        Err(e) => {
+            crate::error::exit_with(&format!("Failed to generate report: {}", e), &e);
+        }
+    }
+}
+
+/// Call `f` with every text file of `rev`, with the author of each line, one file
+/// at a time
+pub fn for_each_file(
+    repo: &Repository,
+    rev: &str,
+    mut f: impl FnMut(FileReport) -> Result<(), GitAiError>,
+) -> Result<(), GitAiError> {
+    let commit = repo
+        .git(&["rev-parse", "--verify", rev])?
+        .trim()
+        .to_string();
+    for path in repo
+        .git(&["ls-tree", "-r", "--name-only", &commit])?
+        .lines()
+    {
+        // Binary files don't decode and have no lines worth showing
+        let Ok(contents) = repo.git(&["show", &format!("{}:{}", commit, path)]) else {
+            continue;
+        };
+        if contents.is_empty() || contents.contains('\0') {
+            continue;
+        }
+
+        let mut options = GitAiBlameOptions::default();
+        #[allow(clippy::field_reassign_with_default)]
+        {
+            options.newest_commit = Some(commit.clone());
+            options.no_output = true;
+        }
+        let (line_authors, prompt_records) = repo.blame(path, &options).unwrap_or_default();
+        let ai_tools: BTreeSet<&str> = prompt_records
+            .values()
+            .map(|record| record.agent_id.tool.as_str())
+            .collect();
+        let lines = contents
+            .lines()
+            .enumerate()
+            .map(|(index, text)| {
+                let author = match line_authors.get(&(index as u32 + 1)) {
+                    Some(author) if ai_tools.contains(author.as_str()) => {
+                        LineAuthor::Ai(author.clone())
+                    }
+                    _ => LineAuthor::Human,
+                };
+                (text.to_string(), author)
+            })
+            .collect();
+        f(FileReport {
+            path: path.to_string(),
+            lines,
+        })?;
+    }
+    Ok(())
+}
+
+/// AI-authored and total lines of `files`, by language
+fn file_languages(files: &[FileTotals]) -> BTreeMap<String, LanguageLines> {
+    group_by_language(
+        files
+            .iter()
+            .map(|file| (file.path.as_str(), file.ai_lines as u32, file.lines as u32)),
+        Config::get().language_overrides(),
+    )
+}
+
+/// Write the report of `rev` to `output`. Returns the number of files in it.
+fn write_report(
+    repo: &Repository,
+    output: &Path,
+    rev: &str,
+    by_language: bool,
+    anonymizer: Option<&Anonymizer>,
+) -> Result<usize, GitAiError> {
+    std::fs::create_dir_all(output)?;
+    let mut files = Vec::new();
+    for_each_file(repo, rev, |mut file| {
+        if let Some(anonymizer) = anonymizer {
+            file.path = anonymizer.path(&file.path);
+            for (text, _) in file.lines.iter_mut() {
+                text.clear();
+            }
+        }
+        let page = output.join(file_page(&file.path));
+        if let Some(parent) = page.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(page, render_file(&file))?;
+        files.push(file.totals());
+        Ok(())
+    })?;
+    let languages = by_language.then(|| file_languages(&files));
+    std::fs::write(
+        output.join("index.html"),
+        render_index(rev, &files, languages.as_ref()),
+    )?;
+    Ok(files.len())
+}
+
+/// Page of a file, relative to the report's directory
+fn file_page(path: &str) -> String {
+    format!("files/{}.html", path)
+}
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2em;color:#222}\
+a{color:inherit}\
+.legend span{display:inline-block;padding:0 .5em;margin-right:.5em;border-radius:3px}\
+.treemap{display:flex;flex-wrap:wrap;gap:2px;margin-bottom:2em}\
+.tile{flex-basis:6em;min-height:3em;padding:.3em;font-size:.8em;overflow:hidden;text-decoration:none;border-radius:2px}\
+.human{background:#e8eef7}\
+.ai{background:#f7c4c4}\
+.mixed{background:#f7e2b8}\
+table.source{border-collapse:collapse;font-family:ui-monospace,monospace;font-size:.85em}\
+table.source td{padding:0 .6em;white-space:pre}\
+td.num{color:#888;text-align:right;user-select:none}\
+td.who{color:#555;font-size:.85em}\
+table.languages{border-collapse:collapse;margin-bottom:2em}\
+table.languages td,table.languages th{padding:.2em .8em;text-align:right}\
+table.languages td:first-child,table.languages th:first-child{text-align:left}";
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape(title),
+        STYLE,
+        body
+    )
+}
+
+fn render_index(
+    rev: &str,
+    files: &[FileTotals],
+    languages: Option<&BTreeMap<String, LanguageLines>>,
+) -> String {
+    let mut by_dir: BTreeMap<&str, Vec<&FileTotals>> = BTreeMap::new();
+    for file in files {
+        let dir = file.path.rsplit_once('/').map_or(".", |(dir, _)| dir);
+        by_dir.entry(dir).or_default().push(file);
+    }
+    let total: usize = files.iter().map(|file| file.lines).sum();
+    let ai: usize = files.iter().map(|file| file.ai_lines).sum();
+
+    let mut body = format!(
+        "<h1>AI authorship of {}</h1>\n<p>{} of {} lines ({}%) written by AI.</p>\n",
+        escape(rev),
+        ai,
+        total,
+        (ai * 100).checked_div(total).unwrap_or(0)
+    );
+    if let Some(languages) = languages {
+        body.push_str("<h2>By language</h2>\n<table class=\"languages\">\n<tr><th>Language</th><th>Lines</th><th>AI lines</th><th>AI share</th></tr>\n");
+        for (language, lines) in sorted_by_lines(languages) {
+            body.push_str(&format!(
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}%</td></tr>\n",
+                escape(language),
+                lines.total_lines,
+                lines.ai_lines,
+                lines.ai_percent()
+            ));
+        }
+        body.push_str("</table>\n");
+    }
+    body.push_str("<p class=\"legend\"><span class=\"human\">human</span><span class=\"mixed\">mixed</span><span class=\"ai\">AI</span></p>\n");
+    for (dir, files) in by_dir {
+        let lines: usize = files.iter().map(|file| file.lines).sum();
+        let ai: usize = files.iter().map(|file| file.ai_lines).sum();
+        body.push_str(&format!(
+            "<h2>{}/</h2>\n<p>{} of {} lines by AI</p>\n<div class=\"treemap\">\n",
+            escape(dir),
+            ai,
+            lines
+        ));
+        for file in files {
+            let name = file.path.rsplit('/').next().unwrap_or(&file.path);
+            body.push_str(&format!(
+                "<a class=\"tile {}\" style=\"flex-grow:{}\" href=\"{}\" title=\"{}: {}% AI\">{}<br>{}%</a>\n",
+                tile_class(file),
+                file.lines.max(1),
+                escape(&file_page(&file.path)),
+                escape(&file.path),
+                file.ai_percent(),
+                escape(name),
+                file.ai_percent()
+            ));
+        }
+        body.push_str("</div>\n");
+    }
+    page(&format!("git-ai report: {}", rev), &body)
+}
+
+fn tile_class(file: &FileTotals) -> &'static str {
+    match file.ai_lines {
+        0 => "human",
+        ai if ai == file.lines => "ai",
+        _ => "mixed",
+    }
+}
+
+fn render_file(file: &FileReport) -> String {
+    let tools: BTreeSet<&str> = file
+        .lines
+        .iter()
+        .filter_map(|(_, author)| match author {
+            LineAuthor::Ai(tool) => Some(tool.as_str()),
+            LineAuthor::Human => None,
+        })
+        .collect();
+    let depth = file.path.matches('/').count() + 1;
+
+    let mut body = format!(
+        "<p><a href=\"{}index.html\">&larr; all files</a></p>\n<h1>{}</h1>\n<p>{} of {} lines ({}%) written by AI.</p>\n<p class=\"legend\"><span class=\"human\">human</span>",
+        "../".repeat(depth),
+        escape(&file.path),
+        file.ai_lines(),
+        file.lines.len(),
+        file.ai_percent()
+    );
+    for tool in &tools {
+        body.push_str(&format!(
+            "<span style=\"background:{}\">{}</span>",
+            tool_color(tool),
+            escape(tool)
+        ));
+    }
+    body.push_str("</p>\n<table class=\"source\">\n");
+    for (index, (text, author)) in file.lines.iter().enumerate() {
+        let (style, who) = match author {
+            LineAuthor::Human => (String::new(), ""),
+            LineAuthor::Ai(tool) => (
+                format!(" style=\"background:{}\"", tool_color(tool)),
+                tool.as_str(),
+            ),
+        };
+        body.push_str(&format!(
+            "<tr{}><td class=\"num\">{}</td><td class=\"who\">{}</td><td>{}</td></tr>\n",
+            style,
+            index + 1,
+            escape(who),
+            escape(text)
+        ));
+    }
+    body.push_str("</table>\n");
+    page(&file.path, &body)
+}
+
+/// A light background colour that stays the same for a tool across pages and runs
+fn tool_color(tool: &str) -> String {
+    let hue = tool.bytes().fold(0u32, |hash, byte| {
+        hash.wrapping_mul(31).wrapping_add(byte as u32)
+    }) % 360;
+    format!("hsl({}, 70%, 85%)", hue)
+}
+
+/// `text` with the characters HTML gives a meaning escaped
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(path: &str, authors: &[Option<&str>]) -> FileReport {
+        FileReport {
+            path: path.to_string(),
+            lines: authors
+                .iter()
+                .enumerate()
+                .map(|(i, tool)| {
+                    let author = match tool {
+                        Some(tool) => LineAuthor::Ai(tool.to_string()),
+                        None => LineAuthor::Human,
+                    };
+                    (format!("line <{}>", i + 1), author)
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_tile_class() {
+        assert_eq!(tile_class(&file("a", &[None, None]).totals()), "human");
+        assert_eq!(
+            tile_class(&file("a", &[Some("claude"), None]).totals()),
+            "mixed"
+        );
+        assert_eq!(tile_class(&file("a", &[Some("claude")]).totals()), "ai");
+    }
+
+    #[test]
+    fn test_render_file_shades_lines_by_tool() {
+        let html = render_file(&file("src/lib.rs", &[Some("claude"), None]));
+        assert!(html.contains(&format!(
+            "<tr style=\"background:{}\"><td class=\"num\">1</td><td class=\"who\">claude</td><td>line &lt;1&gt;</td></tr>",
+            tool_color("claude")
+        )));
+        assert!(html.contains(
+            "<tr><td class=\"num\">2</td><td class=\"who\"></td><td>line &lt;2&gt;</td></tr>"
+        ));
+        assert!(html.contains("href=\"../../index.html\""));
+    }
+
+    #[test]
+    fn test_render_index_links_files() {
+        let files = [
+            file("src/lib.rs", &[Some("claude"), None]).totals(),
+            file("README.md", &[None]).totals(),
+        ];
+        let html = render_index("HEAD", &files, None);
+        assert!(html.contains("<h2>src/</h2>"));
+        assert!(html.contains("<h2>./</h2>"));
+        assert!(
+            html.contains(
+                "class=\"tile mixed\" style=\"flex-grow:2\" href=\"files/src/lib.rs.html\""
+            )
+        );
+        assert!(html.contains("1 of 3 lines (33%) written by AI."));
+        assert!(!html.contains("By language"));
+    }
+
+    #[test]
+    fn test_render_index_by_language() {
+        let files = [
+            file("src/lib.rs", &[Some("claude"), None]).totals(),
+            file("src/main.rs", &[Some("claude"), Some("claude")]).totals(),
+            file("README.md", &[None]).totals(),
+        ];
+        let languages = group_by_language(
+            files
+                .iter()
+                .map(|file| (file.path.as_str(), file.ai_lines as u32, file.lines as u32)),
+            &BTreeMap::new(),
+        );
+        let html = render_index("HEAD", &files, Some(&languages));
+        assert!(html.contains(
+            "<tr><td>Rust</td><td>4</td><td>3</td><td>75%</td></tr>\n<tr><td>Markdown</td><td>1</td><td>0</td><td>0%</td></tr>"
+        ));
+    }
+}
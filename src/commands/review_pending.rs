@@ -0,0 +1,161 @@
+//! `git-ai review-pending`: confirm or dismiss the edits paste detection recorded as
+//! low-confidence AI since the last commit. Each decision is kept in the audit log, for
+//! `git-ai audit export`.
+
+use crate::authorship::audit::{self, Reclassification};
+use crate::authorship::paste_detection;
+use crate::commands::status::format_time_ago;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repo_storage::PersistedWorkingLog;
+use crate::git::repository::Repository;
+use serde::Serialize;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Serialize)]
+struct PendingInfo {
+    id: String,
+    files: Vec<String>,
+    additions: u32,
+    time_ago: String,
+}
+
+pub fn handle_review_pending(args: &[String]) {
+    if let Err(e) = run(args) {
+        crate::error::exit_with(&format!("Error: {}", e), &e);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), GitAiError> {
+    let repo = find_repository(&[])?;
+    let head_sha = repo.head()?.target()?;
+    let working_log = repo.storage.working_log_for_base_commit(&head_sha);
+
+    match args.first().map(String::as_str) {
+        Some(action @ ("confirm" | "dismiss")) => {
+            let ids = &args[1..];
+            if ids.is_empty() {
+                return Err(GitAiError::Generic(format!(
+                    "Usage: git-ai review-pending {} <id>... | --all",
+                    action
+                )));
+            }
+            resolve(&repo, &head_sha, &working_log, action == "confirm", ids)
+        }
+        None | Some("--json") => list(&working_log, !args.is_empty()),
+        Some(arg) => Err(GitAiError::Generic(format!(
+            "Unknown review-pending argument: {}",
+            arg
+        ))),
+    }
+}
+
+fn list(working_log: &PersistedWorkingLog, json: bool) -> Result<(), GitAiError> {
+    let pending: Vec<PendingInfo> = paste_detection::pending_checkpoints(working_log)?
+        .into_iter()
+        .filter_map(|checkpoint| {
+            Some(PendingInfo {
+                id: checkpoint.agent_id?.id,
+                files: checkpoint.entries.into_iter().map(|e| e.file).collect(),
+                additions: checkpoint.line_stats.additions,
+                time_ago: format_time_ago(checkpoint.timestamp),
+            })
+        })
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string(&pending)?);
+        return Ok(());
+    }
+    if pending.is_empty() {
+        eprintln!("No pending edits to review");
+        return Ok(());
+    }
+    for info in &pending {
+        println!(
+            "{}  +{} lines  {}  {}",
+            info.id,
+            info.additions,
+            info.files.join(", "),
+            info.time_ago
+        );
+    }
+    eprintln!();
+    eprintln!("Keep with `git-ai review-pending confirm <id>`, hand back to you with `dismiss`");
+    Ok(())
+}
+
+fn resolve(
+    repo: &Repository,
+    head_sha: &str,
+    working_log: &PersistedWorkingLog,
+    keep: bool,
+    ids: &[String],
+) -> Result<(), GitAiError> {
+    let pending = paste_detection::pending_checkpoints(working_log)?;
+    let ids: Vec<String> = if ids.iter().any(|id| id == "--all") {
+        pending
+            .iter()
+            .filter_map(|checkpoint| Some(checkpoint.agent_id.as_ref()?.id.clone()))
+            .collect()
+    } else {
+        ids.to_vec()
+    };
+    let user = [
+        repo.config_get_str("user.name")?,
+        repo.config_get_str("user.email")?
+            .map(|e| format!("<{}>", e)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<_>>()
+    .join(" ");
+
+    for id in &ids {
+        let reviewed = pending
+            .iter()
+            .filter(|c| c.agent_id.as_ref().is_some_and(|a| &a.id == id));
+        let mut reclassification = Reclassification {
+            reviewed_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs() as i64)
+                .unwrap_or(0),
+            action: if keep { "confirm" } else { "dismiss" }.to_string(),
+            session: id.clone(),
+            tool: String::new(),
+            model: String::new(),
+            files: Vec::new(),
+            lines: 0,
+            base_commit: head_sha.to_string(),
+            user: user.clone(),
+            commit: None,
+        };
+        for checkpoint in reviewed {
+            if let Some(agent_id) = &checkpoint.agent_id {
+                reclassification.tool = agent_id.tool.clone();
+                reclassification.model = agent_id.model.clone();
+            }
+            reclassification
+                .files
+                .extend(checkpoint.entries.iter().map(|e| e.file.clone()));
+            reclassification.lines += checkpoint.line_stats.additions;
+        }
+        reclassification.files.sort();
+        reclassification.files.dedup();
+
+        let found = if keep {
+            paste_detection::confirm(working_log, id)?
+        } else {
+            paste_detection::dismiss(working_log, id)?
+        };
+        if !found {
+            return Err(GitAiError::Generic(format!(
+                "No pending edit with id {}",
+                id
+            )));
+        }
+        audit::record_reclassification(&repo.storage, &reclassification)?;
+        eprintln!("{} {}", if keep { "Confirmed" } else { "Dismissed" }, id);
+    }
+    Ok(())
+}
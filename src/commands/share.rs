@@ -45,8 +45,7 @@ fn handle_share_cli(parsed: ParsedArgs) {
         match find_prompt_with_db_fallback(&parsed.prompt_id, repo.as_ref()) {
             Ok((sha, prompt)) => (sha, prompt),
             Err(e) => {
-                eprintln!("Error: {}", e);
-                std::process::exit(1);
+                crate::error::exit_with(&format!("Error: {}", e), &e);
             }
         };
 
@@ -72,8 +71,7 @@ fn handle_share_cli(parsed: ParsedArgs) {
             println!("{}", response.url);
         }
         Err(e) => {
-            eprintln!("Failed to create bundle: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Failed to create bundle: {}", e), &e);
         }
     }
 }
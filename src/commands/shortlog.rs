@@ -0,0 +1,155 @@
+//! `git-ai shortlog`: `git shortlog -sn` with the lines each author's commits added,
+//! split between human and AI, and the AI tools they used most.
+//!
+//! Authors are listed with the most commits first. Merges are left out, as their
+//! lines were counted in the commits they merge.
+
+use crate::authorship::stats::stats_for_commits_stats;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::Repository;
+use std::collections::BTreeMap;
+
+/// How many of an author's tools are listed
+const TOP_TOOLS: usize = 3;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuthorSummary {
+    pub commits: usize,
+    pub human_lines: u32,
+    pub ai_lines: u32,
+    /// AI lines by tool
+    pub tools: BTreeMap<String, u32>,
+}
+
+impl AuthorSummary {
+    /// The tools with the most AI lines, most first
+    pub fn dominant_tools(&self) -> Vec<&str> {
+        let mut tools: Vec<(&String, &u32)> = self.tools.iter().collect();
+        tools.sort_by_key(|(_, lines)| std::cmp::Reverse(**lines));
+        tools
+            .into_iter()
+            .take(TOP_TOOLS)
+            .map(|(tool, _)| tool.as_str())
+            .collect()
+    }
+}
+
+pub fn handle_shortlog(args: &[String]) {
+    let mut range = None;
+    for arg in args {
+        if arg.starts_with('-') || range.is_some() {
+            eprintln!("Usage: git-ai shortlog [<range>]");
+            std::process::exit(1);
+        }
+        range = Some(arg.clone());
+    }
+
+    let result = find_repository(&Vec::<String>::new())
+        .and_then(|repo| collect_shortlog(&repo, range.as_deref().unwrap_or("HEAD")));
+    match result {
+        Ok(authors) => print!("{}", format_shortlog(&authors)),
+        Err(e) => {
+            crate::error::exit_with(&format!("Shortlog failed: {}", e), &e);
+        }
+    }
+}
+
+/// Commits and added lines of the commits of `range`, by author name
+pub fn collect_shortlog(
+    repo: &Repository,
+    range: &str,
+) -> Result<BTreeMap<String, AuthorSummary>, GitAiError> {
+    let log = repo.git(&["log", "--no-merges", "--format=%H%x09%an", range])?;
+    let commits: Vec<(String, &str)> = log
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(sha, author)| (sha.to_string(), author))
+        .collect();
+    let shas: Vec<String> = commits.iter().map(|(sha, _)| sha.clone()).collect();
+
+    let mut authors: BTreeMap<String, AuthorSummary> = BTreeMap::new();
+    for ((_, author), stats) in commits
+        .iter()
+        .zip(stats_for_commits_stats(repo, &shas, &[])?)
+    {
+        let ai = stats.ai_additions.min(stats.git_diff_added_lines);
+        let summary = authors.entry(author.to_string()).or_default();
+        summary.commits += 1;
+        summary.ai_lines += ai;
+        summary.human_lines += stats.git_diff_added_lines - ai;
+        for (key, tool_stats) in &stats.tool_model_breakdown {
+            if tool_stats.ai_additions > 0 {
+                let tool = key.split_once("::").map_or(key.as_str(), |(tool, _)| tool);
+                *summary.tools.entry(tool.to_string()).or_default() += tool_stats.ai_additions;
+            }
+        }
+    }
+    Ok(authors)
+}
+
+/// One line per author, most commits first, like `git shortlog -sn`
+pub fn format_shortlog(authors: &BTreeMap<String, AuthorSummary>) -> String {
+    let mut sorted: Vec<(&String, &AuthorSummary)> = authors.iter().collect();
+    sorted.sort_by_key(|(_, summary)| std::cmp::Reverse(summary.commits));
+    let width = sorted
+        .iter()
+        .map(|(author, _)| author.chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut output = String::new();
+    for (author, summary) in sorted {
+        let line = format!(
+            "{:>6}\t{:<width$}  {:>6} human  {:>6} AI  {}",
+            summary.commits,
+            author,
+            summary.human_lines,
+            summary.ai_lines,
+            summary.dominant_tools().join(", "),
+            width = width
+        );
+        output.push_str(line.trim_end());
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_shortlog() {
+        let mut authors = BTreeMap::new();
+        authors.insert(
+            "Ada".to_string(),
+            AuthorSummary {
+                commits: 1,
+                human_lines: 12,
+                ai_lines: 0,
+                tools: BTreeMap::new(),
+            },
+        );
+        authors.insert(
+            "Grace Hopper".to_string(),
+            AuthorSummary {
+                commits: 3,
+                human_lines: 40,
+                ai_lines: 120,
+                tools: [
+                    ("aider".to_string(), 5),
+                    ("claude".to_string(), 80),
+                    ("codex".to_string(), 10),
+                    ("cursor".to_string(), 25),
+                ]
+                .into_iter()
+                .collect(),
+            },
+        );
+        assert_eq!(
+            format_shortlog(&authors),
+            "     3\tGrace Hopper      40 human     120 AI  claude, cursor, codex\n     1\tAda               12 human       0 AI\n"
+        );
+    }
+}
@@ -19,14 +19,12 @@ pub fn handle_show(args: &[String]) {
     let repo = match find_repository(&Vec::<String>::new()) {
         Ok(repo) => repo,
         Err(e) => {
-            eprintln!("Failed to find repository: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Failed to find repository: {}", e), &e);
         }
     };
 
     if let Err(e) = show_authorship(&repo, &args[0]) {
-        eprintln!("Failed to show authorship: {}", e);
-        std::process::exit(1);
+        crate::error::exit_with(&format!("Failed to show authorship: {}", e), &e);
     }
 }
 
@@ -19,8 +19,7 @@ pub fn handle_show_prompt(args: &[String]) {
     let repo = match find_repository(&Vec::<String>::new()) {
         Ok(repo) => repo,
         Err(e) => {
-            eprintln!("Failed to find repository: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Failed to find repository: {}", e), &e);
         }
     };
 
@@ -43,8 +42,7 @@ pub fn handle_show_prompt(args: &[String]) {
             );
         }
         Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Error: {}", e), &e);
         }
     }
 }
@@ -71,8 +71,7 @@ pub fn handle_squash_authorship(args: &[String]) {
     let repo = match find_repository_in_path(".") {
         Ok(repo) => repo,
         Err(e) => {
-            eprintln!("Failed to find repository: {}", e);
-            std::process::exit(1);
+            crate::error::exit_with(&format!("Failed to find repository: {}", e), &e);
         }
     };
 
@@ -85,7 +84,6 @@ pub fn handle_squash_authorship(args: &[String]) {
         &new_sha,     // merge_commit_sha - the new commit
         false,        // suppress_output
     ) {
-        eprintln!("Squash authorship failed: {}", e);
-        std::process::exit(1);
+        crate::error::exit_with(&format!("Squash authorship failed: {}", e), &e);
     }
 }
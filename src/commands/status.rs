@@ -1,11 +1,16 @@
+use crate::authorship::async_finalize::finalize_pending_commits;
 use crate::authorship::stats::{CommitStats, write_stats_to_terminal};
+use crate::authorship::status_summary::StatusSummary;
 use crate::authorship::virtual_attribution::VirtualAttributions;
 use crate::authorship::working_log::CheckpointKind;
 use crate::commands::checkpoint;
+use crate::commands::checkpoint_agent::agent_registry;
+use crate::config::Config;
 use crate::error::GitAiError;
 use crate::git::find_repository;
 use crate::git::repo_storage::InitialAttributions;
 use crate::git::repository::Repository;
+use crate::i18n::{tr, tr_args};
 use serde::Serialize;
 use std::collections::HashSet;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -19,39 +24,114 @@ struct CheckpointInfo {
     is_human: bool,
 }
 
-#[derive(Serialize)]
-struct StatusOutput {
-    stats: CommitStats,
+#[derive(Serialize, Default)]
+pub(crate) struct StatusOutput {
+    pub(crate) stats: CommitStats,
     checkpoints: Vec<CheckpointInfo>,
 }
 
 pub fn handle_status(args: &[String]) {
     let mut json_output = false;
+    let mut exact = false;
 
     let mut i = 0;
     while i < args.len() {
-        if args[i].as_str() == "--json" {
-            json_output = true;
+        match args[i].as_str() {
+            "--json" => json_output = true,
+            "--exact" => exact = true,
+            _ => {}
         }
         i += 1;
     }
 
-    if let Err(e) = run_status(json_output) {
-        eprintln!("Error: {}", e);
-        std::process::exit(1);
+    if let Err(e) = run_status(json_output, exact) {
+        crate::error::exit_with(&tr_args("error", &[("error", &e.to_string())]), &e);
     }
 }
 
-fn run_status(json: bool) -> Result<(), GitAiError> {
+fn run_status(json: bool, exact: bool) -> Result<(), GitAiError> {
     let repo = find_repository(&[])?;
 
+    let Some(output) = collect_status(&repo, exact)? else {
+        if json {
+            let json_str = serde_json::to_string(&StatusOutput::default())?;
+            println!("{}", json_str);
+        } else {
+            let head_sha = repo.head()?.target()?;
+            eprintln!(
+                "{}",
+                tr_args("status.no_checkpoints", &[("sha", &head_sha[..7])])
+            );
+            eprintln!();
+
+            eprintln!("{}", tr("status.install_hooks_hint"));
+            eprintln!();
+            eprintln!("  git-ai install-hooks");
+            eprintln!();
+        }
+        return Ok(());
+    };
+
+    if json {
+        let json_str = serde_json::to_string(&output)?;
+        println!("{}", json_str);
+        return Ok(());
+    }
+
+    let StatusOutput {
+        stats,
+        checkpoints: checkpoint_infos,
+    } = output;
+
+    write_stats_to_terminal(&stats, true);
+
+    println!();
+    for cp in &checkpoint_infos {
+        let add_str = if cp.additions > 0 {
+            format!("+{}", cp.additions)
+        } else {
+            "0".to_string()
+        };
+        let del_str = if cp.deletions > 0 {
+            format!("-{}", cp.deletions)
+        } else {
+            "0".to_string()
+        };
+
+        let line = format!(
+            "{:<14} {:>5}  {:>5}  {}",
+            cp.time_ago, add_str, del_str, cp.tool_model
+        );
+
+        if cp.is_human {
+            println!("\x1b[90m{}\x1b[0m", line);
+        } else {
+            println!("{}", line);
+        }
+    }
+
+    Ok(())
+}
+
+/// Checkpoint the working tree and summarize what is pending for the next commit;
+/// `None` when nothing has been checkpointed since HEAD.
+///
+/// Unless `exact`, the numbers come from the summary the checkpoints keep up to date,
+/// without rebuilding the attributions of the working tree.
+pub(crate) fn collect_status(
+    repo: &Repository,
+    exact: bool,
+) -> Result<Option<StatusOutput>, GitAiError> {
+    // Commits still being finalized in the background would show up as pending
+    finalize_pending_commits(repo, true)?;
+
     let default_user_name = match repo.config_get_str("user.name") {
         Ok(Some(name)) if !name.trim().is_empty() => name,
         _ => "unknown".to_string(),
     };
 
     let _ = checkpoint::run(
-        &repo,
+        repo,
         &default_user_name,
         CheckpointKind::Human,
         false,
@@ -65,56 +145,35 @@ fn run_status(json: bool) -> Result<(), GitAiError> {
     let head_sha = head.target()?;
 
     let working_log = repo.storage.working_log_for_base_commit(&head_sha);
-    let checkpoints = working_log.read_all_checkpoints()?;
-
-    if checkpoints.is_empty() {
-        if json {
-            let output = StatusOutput {
-                stats: CommitStats::default(),
-                checkpoints: vec![],
-            };
-            let json_str = serde_json::to_string(&output)?;
-            println!("{}", json_str);
-        } else {
-            eprintln!(
-                "No checkpoints recorded since last commit ({})",
-                &head_sha[..7]
-            );
-            eprintln!();
-
-            eprintln!(
-                "If you've made AI edits recently and don't see them here, you might need to install hooks:"
-            );
-            eprintln!();
-            eprintln!("  git-ai install-hooks");
-            eprintln!();
-        }
-        return Ok(());
+    if !exact {
+        return summarize_status(repo, &working_log.status_summary()?, &default_user_name);
     }
 
+    // Stream the checkpoints: only their headline numbers and files are kept
     let mut checkpoint_infos = Vec::new();
-
-    for checkpoint in checkpoints.iter().rev() {
-        let (additions, deletions) = (
-            checkpoint.line_stats.additions,
-            checkpoint.line_stats.deletions,
-        );
-
-        let tool_model = checkpoint
-            .agent_id
-            .as_ref()
-            .map(|a| format!("{} {}", capitalize(&a.tool), &a.model))
-            .unwrap_or_else(|| default_user_name.clone());
-
-        let is_human = checkpoint.kind == CheckpointKind::Human;
+    let mut pathspecs: HashSet<String> = HashSet::new();
+    for checkpoint in working_log.checkpoints()? {
+        let checkpoint = checkpoint?;
+        let agent = checkpoint.agent_id.as_ref();
         checkpoint_infos.push(CheckpointInfo {
             time_ago: format_time_ago(checkpoint.timestamp),
-            additions,
-            deletions,
-            tool_model,
-            is_human,
+            additions: checkpoint.line_stats.additions,
+            deletions: checkpoint.line_stats.deletions,
+            tool_model: tool_model(
+                agent.map(|a| a.tool.as_str()),
+                agent.map(|a| a.model.as_str()),
+                &default_user_name,
+            ),
+            is_human: checkpoint.kind == CheckpointKind::Human,
         });
+        pathspecs.extend(checkpoint.entries.into_iter().map(|e| e.file));
+    }
+
+    if checkpoint_infos.is_empty() {
+        return Ok(None);
     }
+    // Newest first
+    checkpoint_infos.reverse();
 
     let working_va = VirtualAttributions::from_just_working_log(
         repo.clone(),
@@ -122,20 +181,15 @@ fn run_status(json: bool) -> Result<(), GitAiError> {
         Some(default_user_name.clone()),
     )?;
 
-    let pathspecs: HashSet<String> = checkpoints
-        .iter()
-        .flat_map(|cp| cp.entries.iter().map(|e| e.file.clone()))
-        .collect();
-
     let (authorship_log, initial) = working_va.to_authorship_log_and_initial_working_log(
-        &repo,
+        repo,
         &head_sha,
         &head_sha,
         Some(&pathspecs),
     )?;
 
     // Get actual git diff stats between HEAD and working directory (like post_commit does)
-    let (total_additions, total_deletions) = get_working_dir_diff_stats(&repo, Some(&pathspecs))?;
+    let (total_additions, total_deletions) = get_working_dir_diff_stats(repo, Some(&pathspecs))?;
 
     // For status (uncommitted changes), the AI attributions are in `initial` (uncommitted),
     // not in authorship_log.attestations (which is for committed changes).
@@ -149,47 +203,67 @@ fn run_status(json: bool) -> Result<(), GitAiError> {
         ai_accepted,
     );
 
-    if json {
-        let output = StatusOutput {
-            stats,
-            checkpoints: checkpoint_infos,
-        };
-        let json_str = serde_json::to_string(&output)?;
-        println!("{}", json_str);
-        return Ok(());
+    Ok(Some(StatusOutput {
+        stats,
+        checkpoints: checkpoint_infos,
+    }))
+}
+
+/// The status of the working log from its summary
+fn summarize_status(
+    repo: &Repository,
+    summary: &StatusSummary,
+    default_user_name: &str,
+) -> Result<Option<StatusOutput>, GitAiError> {
+    if summary.checkpoints.is_empty() {
+        return Ok(None);
     }
 
-    write_stats_to_terminal(&stats, true);
+    let checkpoints = summary
+        .checkpoints
+        .iter()
+        .rev()
+        .map(|cp| CheckpointInfo {
+            time_ago: format_time_ago(cp.timestamp),
+            additions: cp.additions,
+            deletions: cp.deletions,
+            tool_model: tool_model(cp.tool.as_deref(), cp.model.as_deref(), default_user_name),
+            is_human: cp.is_human,
+        })
+        .collect();
 
-    println!();
-    for cp in &checkpoint_infos {
-        let add_str = if cp.additions > 0 {
-            format!("+{}", cp.additions)
-        } else {
-            "0".to_string()
-        };
-        let del_str = if cp.deletions > 0 {
-            format!("-{}", cp.deletions)
-        } else {
-            "0".to_string()
-        };
+    let pathspecs: HashSet<String> = summary.ai_lines.keys().cloned().collect();
+    let (total_additions, total_deletions) = get_working_dir_diff_stats(repo, Some(&pathspecs))?;
+    // A file put back as it was at HEAD still has its AI lines in the summary
+    let ai_accepted = summary.ai_accepted().min(total_additions);
 
-        let line = format!(
-            "{:<14} {:>5}  {:>5}  {}",
-            cp.time_ago, add_str, del_str, cp.tool_model
-        );
+    let mut stats = stats_from_authorship_log_with_override(
+        None,
+        total_additions,
+        total_deletions,
+        ai_accepted,
+    );
+    stats.total_ai_additions = summary.total_ai_additions;
+    stats.total_ai_deletions = summary.total_ai_deletions;
+    stats.time_waiting_for_ai = summary.time_waiting_for_ai();
 
-        if cp.is_human {
-            println!("\x1b[90m{}\x1b[0m", line);
-        } else {
-            println!("{}", line);
+    Ok(Some(StatusOutput { stats, checkpoints }))
+}
+
+/// How a checkpoint's author is shown: the agent and its model, or the user
+fn tool_model(tool: Option<&str>, model: Option<&str>, default_user_name: &str) -> String {
+    match tool {
+        Some(tool) => {
+            let name = agent_registry::display_name(Config::get().custom_agents(), tool)
+                .map(str::to_string)
+                .unwrap_or_else(|| capitalize(tool));
+            format!("{} {}", name, model.unwrap_or_default())
         }
+        None => default_user_name.to_string(),
     }
-
-    Ok(())
 }
 
-fn format_time_ago(timestamp: u64) -> String {
+pub(crate) fn format_time_ago(timestamp: u64) -> String {
     let now = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
@@ -269,7 +343,7 @@ fn get_working_dir_diff_stats(
 }
 
 /// Count AI-attributed lines from InitialAttributions (uncommitted changes)
-fn count_ai_lines_from_initial(initial: &InitialAttributions) -> u32 {
+pub(crate) fn count_ai_lines_from_initial(initial: &InitialAttributions) -> u32 {
     let mut ai_lines = 0u32;
 
     for line_attrs in initial.files.values() {
@@ -0,0 +1,252 @@
+//! `git-ai summary`: a short Markdown summary of a pull request's AI authorship, for
+//! reviewers. It is meant to be prepended to the PR description by a CI step, so it
+//! stays under a screenful: the AI share, the tools and models used, the files with
+//! the most AI lines, and the commits `push_policy` would flag.
+//!
+//! The range is given with `--range` and defaults to that of `ci-gate`.
+
+use crate::authorship::push_policy::{PolicyViolation, evaluate_commits};
+use crate::authorship::range_authorship::range_authorship;
+use crate::authorship::stats::CommitStats;
+use crate::commands::ci_gate::RequestRange;
+use crate::commands::diff::ai_lines_by_file;
+use crate::config::Config;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::{CommitRange, Repository};
+use std::collections::BTreeMap;
+
+/// How many of the most AI-touched files are listed
+const TOP_FILES: usize = 5;
+
+/// What a summary is made of
+#[derive(Debug, Clone, Default)]
+pub struct PrSummary {
+    pub commits: usize,
+    pub commits_with_authorship: usize,
+    pub stats: CommitStats,
+    /// AI-authored and added lines, by file
+    pub files: BTreeMap<String, (u32, u32)>,
+    pub violations: Vec<PolicyViolation>,
+}
+
+pub fn handle_summary(args: &[String]) {
+    let usage = "Usage: git-ai summary [--range <base>..<head>] [--markdown]";
+    let mut range = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--range" if i + 1 < args.len() => {
+                range = Some(args[i + 1].clone());
+                i += 1;
+            }
+            // The one format there is, accepted so scripts can be explicit
+            "--markdown" => {}
+            _ => {
+                eprintln!("{}", usage);
+                std::process::exit(1);
+            }
+        }
+        i += 1;
+    }
+
+    let result = find_repository(&Vec::<String>::new())
+        .and_then(|repo| collect_summary(&repo, range.as_deref()));
+    match result {
+        Ok(summary) => print!("{}", render_markdown(&summary)),
+        Err(e) => {
+            crate::error::exit_with(&format!("Failed to summarize: {}", e), &e);
+        }
+    }
+}
+
+pub fn collect_summary(repo: &Repository, range: Option<&str>) -> Result<PrSummary, GitAiError> {
+    let request = RequestRange::resolve(repo, range)?;
+    if request.commits.is_empty() {
+        return Ok(PrSummary::default());
+    }
+
+    let commit_range = CommitRange::new_infer_refname(
+        repo,
+        request.merge_base.clone(),
+        request.head.clone(),
+        None,
+    )?;
+    let range_stats = range_authorship(commit_range, false, &[])?;
+
+    let files = ai_lines_by_file(repo, &request.merge_base, &request.head)?;
+
+    Ok(PrSummary {
+        commits: request.commits.len(),
+        commits_with_authorship: range_stats.authorship_stats.commits_with_authorship,
+        stats: range_stats.range_stats,
+        files,
+        violations: evaluate_commits(repo, Config::get().push_policy(), &request.commits)?,
+    })
+}
+
+pub fn render_markdown(summary: &PrSummary) -> String {
+    let mut output = String::from("### AI authorship\n\n");
+    if summary.commits == 0 {
+        output.push_str("No commits to summarize.\n");
+        return output;
+    }
+
+    let added = summary.stats.git_diff_added_lines;
+    let ai = summary.stats.ai_additions.min(added);
+    output.push_str(&format!(
+        "**{}%** of the {} lines added by {} {} were written by AI",
+        (ai * 100).checked_div(added).unwrap_or(0),
+        added,
+        summary.commits,
+        if summary.commits == 1 {
+            "commit"
+        } else {
+            "commits"
+        }
+    ));
+    if summary.commits_with_authorship < summary.commits {
+        output.push_str(&format!(
+            " ({} of them have no git-ai attribution)",
+            summary.commits - summary.commits_with_authorship
+        ));
+    }
+    output.push_str(".\n");
+
+    let mut tools: Vec<(&String, u32)> = summary
+        .stats
+        .tool_model_breakdown
+        .iter()
+        .map(|(key, stats)| (key, stats.ai_additions))
+        .filter(|(_, lines)| *lines > 0)
+        .collect();
+    tools.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+    if !tools.is_empty() {
+        let listed: Vec<String> = tools
+            .iter()
+            .map(|(key, lines)| {
+                let name = match key.split_once("::") {
+                    Some((tool, model)) if !model.is_empty() && model != "unknown" => {
+                        format!("{} ({})", tool, model)
+                    }
+                    Some((tool, _)) => tool.to_string(),
+                    None => key.to_string(),
+                };
+                format!("{}: {} lines", name, lines)
+            })
+            .collect();
+        output.push_str(&format!("\n**Tools:** {}\n", listed.join(", ")));
+    }
+
+    let mut files: Vec<(&String, &(u32, u32))> = summary
+        .files
+        .iter()
+        .filter(|(_, (ai, _))| *ai > 0)
+        .collect();
+    files.sort_by(|a, b| (b.1).0.cmp(&(a.1).0).then(a.0.cmp(b.0)));
+    if !files.is_empty() {
+        output.push_str("\n| File | AI lines | AI share |\n|---|---:|---:|\n");
+        for (path, (ai, added)) in files.iter().take(TOP_FILES) {
+            output.push_str(&format!(
+                "| `{}` | {} of {} | {}% |\n",
+                path,
+                ai,
+                added,
+                (ai * 100).checked_div(*added).unwrap_or(0)
+            ));
+        }
+        if files.len() > TOP_FILES {
+            output.push_str(&format!(
+                "\n…and {} more files with AI lines.\n",
+                files.len() - TOP_FILES
+            ));
+        }
+    }
+
+    if summary.violations.is_empty() {
+        output.push_str("\n**Policy:** no `push_policy` flags.\n");
+    } else {
+        output.push_str("\n**Policy flags:**\n\n");
+        for v in &summary.violations {
+            output.push_str(&format!(
+                "- `{}` {}: {}\n",
+                &v.commit_sha[..7.min(v.commit_sha.len())],
+                v.subject,
+                v.reason
+            ));
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authorship::stats::ToolModelHeadlineStats;
+
+    #[test]
+    fn test_render_markdown() {
+        let mut stats = CommitStats {
+            ai_additions: 30,
+            git_diff_added_lines: 40,
+            ..Default::default()
+        };
+        stats.tool_model_breakdown.insert(
+            "claude::claude-sonnet-4".to_string(),
+            ToolModelHeadlineStats {
+                ai_additions: 25,
+                ..Default::default()
+            },
+        );
+        stats.tool_model_breakdown.insert(
+            "cursor::unknown".to_string(),
+            ToolModelHeadlineStats {
+                ai_additions: 5,
+                ..Default::default()
+            },
+        );
+        let summary = PrSummary {
+            commits: 2,
+            commits_with_authorship: 1,
+            stats,
+            files: [
+                ("src/auth.rs".to_string(), (28, 30)),
+                ("src/lib.rs".to_string(), (2, 5)),
+                ("README.md".to_string(), (0, 5)),
+            ]
+            .into_iter()
+            .collect(),
+            violations: vec![PolicyViolation {
+                commit_sha: "abcdef123456".to_string(),
+                subject: "Add login".to_string(),
+                reason: "AI-authored lines in protected path src/auth.rs".to_string(),
+            }],
+        };
+        assert_eq!(
+            render_markdown(&summary),
+            "### AI authorship
+
+**75%** of the 40 lines added by 2 commits were written by AI (1 of them have no git-ai attribution).
+
+**Tools:** claude (claude-sonnet-4): 25 lines, cursor: 5 lines
+
+| File | AI lines | AI share |
+|---|---:|---:|
+| `src/auth.rs` | 28 of 30 | 93% |
+| `src/lib.rs` | 2 of 5 | 40% |
+
+**Policy flags:**
+
+- `abcdef1` Add login: AI-authored lines in protected path src/auth.rs
+"
+        );
+    }
+
+    #[test]
+    fn test_render_markdown_without_commits() {
+        assert_eq!(
+            render_markdown(&PrSummary::default()),
+            "### AI authorship\n\nNo commits to summarize.\n"
+        );
+    }
+}
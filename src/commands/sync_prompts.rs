@@ -1,15 +1,34 @@
+use crate::api::{ApiClient, ApiContext};
+use crate::authorship::anonymize::Anonymizer;
 use crate::authorship::internal_db::{InternalDatabase, PromptDbRecord};
+use crate::authorship::org_metrics::{OrgSyncConfig, aggregate, anonymized};
 use crate::authorship::prompt_utils::{PromptUpdateResult, update_prompt_from_tool};
+use crate::config::Config;
 use crate::error::GitAiError;
 use crate::observability::log_error;
 use chrono::{DateTime, NaiveDate};
+use serde::{Deserialize, Serialize};
 use std::cmp::min;
 use std::collections::HashMap;
+use std::process::{Command, Stdio};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+/// Prompts read from the database per query when aggregating
+const ORG_PAGE_SIZE: usize = 10_000;
+
+/// When `sync-prompts --org` last uploaded, in `~/.git-ai/internal/org_sync`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct OrgSyncState {
+    last_synced_at: i64,
+}
+
 pub fn handle_sync_prompts(args: &[String]) {
     let mut since: Option<String> = None;
     let mut workdir: Option<String> = None;
+    let mut org = false;
+    let mut preview = false;
+    let mut if_due = false;
+    let mut anonymize = false;
 
     // Parse arguments
     let mut i = 0;
@@ -31,9 +50,16 @@ pub fn handle_sync_prompts(args: &[String]) {
                 i += 1;
                 workdir = Some(args[i].clone());
             }
+            "--org" => org = true,
+            "--preview" => preview = true,
+            "--anonymize" => anonymize = true,
+            // Used by hooks scheduling the upload in the background
+            "--if-due" => if_due = true,
             _ => {
                 eprintln!("Error: Unknown argument: {}", args[i]);
-                eprintln!("Usage: git-ai sync-prompts [--since <time>] [--workdir <path>]");
+                eprintln!(
+                    "Usage: git-ai sync-prompts [--since <time>] [--workdir <path>] [--org [--preview] [--anonymize]]"
+                );
                 std::process::exit(1);
             }
         }
@@ -45,18 +71,23 @@ pub fn handle_sync_prompts(args: &[String]) {
         match parse_since_arg(&since_str) {
             Ok(ts) => Some(ts),
             Err(e) => {
-                eprintln!("Error parsing --since: {}", e);
-                std::process::exit(1);
+                crate::error::exit_with(&format!("Error parsing --since: {}", e), &e);
             }
         }
     } else {
         None
     };
 
+    if org {
+        if let Err(e) = sync_org_metrics(since_timestamp, preview, if_due, anonymize) {
+            crate::error::exit_with(&format!("Org sync failed: {}", e), &e);
+        }
+        return;
+    }
+
     // Run sync
     if let Err(e) = sync_prompts(since_timestamp, workdir.as_deref()) {
-        eprintln!("Sync failed: {}", e);
-        std::process::exit(1);
+        crate::error::exit_with(&format!("Sync failed: {}", e), &e);
     }
 }
 
@@ -173,6 +204,133 @@ fn sync_prompts(since_timestamp: Option<i64>, workdir: Option<&str>) -> Result<(
     Ok(())
 }
 
+/// Upload (or with `preview`, print) the aggregated metrics of the prompts updated
+/// since `since`, by default since the last upload; under pseudonyms with `anonymize`
+/// or when the config asks for it
+fn sync_org_metrics(
+    since: Option<i64>,
+    preview: bool,
+    if_due: bool,
+    anonymize: bool,
+) -> Result<(), GitAiError> {
+    let configured = Config::get().org_sync().cloned();
+    if configured.is_none() && !preview {
+        return Err(GitAiError::Generic(
+            "org_sync is not configured, see `git-ai config set org_sync`".to_string(),
+        ));
+    }
+    let mut config = configured.clone().unwrap_or(OrgSyncConfig {
+        team: None,
+        interval_hours: 24,
+        include_paths: false,
+        anonymize: false,
+    });
+    config.anonymize |= anonymize;
+
+    let now = now_secs();
+    let state = read_org_sync_state();
+    if if_due && !org_sync_due(state.as_ref(), &config, now) {
+        return Ok(());
+    }
+    let since = since
+        .or(state.map(|s| s.last_synced_at))
+        .unwrap_or(now - config.interval_hours as i64 * 3600);
+
+    let prompts = {
+        let db = InternalDatabase::global()?;
+        let db_lock = db
+            .lock()
+            .map_err(|e| GitAiError::Generic(format!("Failed to lock database: {}", e)))?;
+        let mut prompts = Vec::new();
+        loop {
+            let page = db_lock.list_prompts(None, Some(since), ORG_PAGE_SIZE, prompts.len())?;
+            let done = page.len() < ORG_PAGE_SIZE;
+            prompts.extend(page);
+            if done {
+                break;
+            }
+        }
+        prompts
+    };
+    let mut metrics = aggregate(&prompts, &config, since, now + 1);
+    if config.anonymize {
+        metrics = anonymized(metrics, &Anonymizer::load()?);
+    }
+
+    if preview {
+        if configured.is_none() {
+            eprintln!("org_sync is not configured: nothing is uploaded until it is");
+        }
+        println!("{}", serde_json::to_string_pretty(&metrics)?);
+        return Ok(());
+    }
+
+    crate::api::network::ensure_online().map_err(GitAiError::Generic)?;
+    let context = ApiContext::new(None);
+    let using_default_api = context.base_url == crate::config::DEFAULT_API_BASE_URL;
+    let client = ApiClient::new(context);
+    if using_default_api && !client.is_logged_in() {
+        return Err(GitAiError::Generic(
+            "Not logged in, run `git-ai login` first".to_string(),
+        ));
+    }
+    client.upload_org_metrics(&metrics)?;
+    write_org_sync_state(&OrgSyncState {
+        last_synced_at: now,
+    });
+    eprintln!(
+        "✓ Uploaded org metrics for {} repositories",
+        metrics.repos.len()
+    );
+    Ok(())
+}
+
+/// Start a background upload when org sync is configured and one is due
+pub fn maybe_schedule_org_sync() {
+    let Some(config) = Config::get().org_sync() else {
+        return;
+    };
+    if crate::api::network::offline()
+        || !org_sync_due(read_org_sync_state().as_ref(), config, now_secs())
+    {
+        return;
+    }
+    if let Ok(exe) = crate::utils::current_git_ai_exe() {
+        let _ = Command::new(exe)
+            .args(["sync-prompts", "--org", "--if-due"])
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+    }
+}
+
+fn org_sync_due(state: Option<&OrgSyncState>, config: &OrgSyncConfig, now: i64) -> bool {
+    state.is_none_or(|s| now - s.last_synced_at >= config.interval_hours as i64 * 3600)
+}
+
+fn read_org_sync_state() -> Option<OrgSyncState> {
+    let path = crate::config::org_sync_state_path()?;
+    serde_json::from_slice(&std::fs::read(path).ok()?).ok()
+}
+
+fn write_org_sync_state(state: &OrgSyncState) {
+    if let Some(path) = crate::config::org_sync_state_path() {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        if let Ok(json) = serde_json::to_vec(state) {
+            let _ = std::fs::write(path, json);
+        }
+    }
+}
+
+fn now_secs() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
 fn deduplicate_by_agent_id(prompts: &[PromptDbRecord]) -> Vec<PromptDbRecord> {
     let mut latest_by_agent: HashMap<String, PromptDbRecord> = HashMap::new();
 
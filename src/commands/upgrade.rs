@@ -1,4 +1,5 @@
 use crate::api::client::ApiContext;
+use crate::api::network;
 use crate::config::{self, UpdateChannel};
 use crate::observability::log_message;
 use serde::{Deserialize, Serialize};
@@ -217,6 +218,7 @@ fn fetch_and_verify_checksums(
 ) -> Result<HashMap<String, String>, String> {
     let endpoint = format!("/worker/releases/{}/download/SHA256SUMS", channel);
 
+    network::ensure_online()?;
     let response = ApiContext::http_get(&format!("{}{}", api_base_url, endpoint))
         .with_timeout(30)
         .send()
@@ -257,6 +259,7 @@ fn fetch_and_verify_install_script(
 
     let endpoint = format!("/worker/releases/{}/download/{}", channel, script_name);
 
+    network::ensure_online()?;
     let response = ApiContext::http_get(&format!("{}{}", api_base_url, endpoint))
         .with_timeout(30)
         .send()
@@ -706,6 +709,9 @@ pub fn maybe_schedule_background_update_check() {
 }
 
 fn spawn_background_upgrade_process() -> bool {
+    if crate::api::network::offline() {
+        return false;
+    }
     match crate::utils::current_git_ai_exe() {
         Ok(exe) => {
             let mut cmd = Command::new(exe);
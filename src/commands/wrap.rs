@@ -0,0 +1,298 @@
+//! `git-ai wrap`: attribute whatever a command changes to an agent we have no
+//! integration for.
+//!
+//! Uncommitted edits present before the command are checkpointed as human first, then
+//! every file whose contents differ after the command exits gets an AI checkpoint.
+
+use crate::authorship::reconcile::reconcile_unfinalized_commits;
+use crate::authorship::working_log::{AgentId, CheckpointKind};
+use crate::commands::checkpoint;
+use crate::commands::checkpoint_agent::agent_presets::AgentRunResult;
+use crate::error::GitAiError;
+use crate::git::find_repository;
+use crate::git::repository::Repository;
+use crate::utils::debug_log;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, PartialEq, Eq)]
+struct WrapArgs {
+    tool: String,
+    model: String,
+    command: Vec<String>,
+}
+
+fn parse_args(args: &[String]) -> Result<WrapArgs, String> {
+    let mut tool = None;
+    let mut model = None;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--" => {
+                i += 1;
+                break;
+            }
+            "--tool" | "--model" if i + 1 < args.len() => {
+                let value = args[i + 1].clone();
+                if args[i] == "--tool" {
+                    tool = Some(value);
+                } else {
+                    model = Some(value);
+                }
+                i += 2;
+            }
+            arg => {
+                if let Some(value) = arg.strip_prefix("--tool=") {
+                    tool = Some(value.to_string());
+                } else if let Some(value) = arg.strip_prefix("--model=") {
+                    model = Some(value.to_string());
+                } else if arg.starts_with('-') {
+                    return Err(format!("Unknown option: {}", arg));
+                } else {
+                    // The command may also follow the options without `--`
+                    break;
+                }
+                i += 1;
+            }
+        }
+    }
+
+    let tool = tool
+        .filter(|t| !t.trim().is_empty())
+        .ok_or("--tool <name> is required")?;
+    let command = args[i..].to_vec();
+    if command.is_empty() {
+        return Err("No command given to wrap".to_string());
+    }
+    Ok(WrapArgs {
+        tool,
+        model: model.unwrap_or_else(|| "unknown".to_string()),
+        command,
+    })
+}
+
+pub fn handle_wrap(args: &[String], track_changes: bool) {
+    let wrap_args = match parse_args(args) {
+        Ok(wrap_args) => wrap_args,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            eprintln!("Usage: git-ai wrap --tool <name> [--model <name>] -- <command...>");
+            std::process::exit(1);
+        }
+    };
+
+    let repo = match find_repository(&[]) {
+        Ok(repo) => repo,
+        Err(e) => {
+            crate::error::exit_with(&format!("Failed to find repository: {}", e), &e);
+        }
+    };
+
+    let before = if track_changes {
+        match snapshot(&repo, &wrap_args) {
+            Ok(before) => Some(before),
+            Err(e) => {
+                eprintln!("git-ai: not tracking this run: {}", e);
+                None
+            }
+        }
+    } else {
+        eprintln!(
+            "Skipping checkpoints because repository is excluded or not in allow_repositories list"
+        );
+        None
+    };
+
+    let status = Command::new(&wrap_args.command[0])
+        .args(&wrap_args.command[1..])
+        .status();
+    let exit_code = match status {
+        Ok(status) => status.code().unwrap_or(1),
+        Err(e) => {
+            eprintln!("Failed to run {}: {}", wrap_args.command[0], e);
+            127
+        }
+    };
+
+    if let Some(before) = before {
+        match record_changes(&repo, &wrap_args, &before) {
+            Ok(0) => {}
+            Ok(count) => eprintln!("git-ai: attributed {} file(s) to {}", count, wrap_args.tool),
+            Err(e) => eprintln!("git-ai: failed to checkpoint changes: {}", e),
+        }
+    }
+    std::process::exit(exit_code);
+}
+
+/// Checkpoint the edits already in the tree as human and hash the files they touch.
+fn snapshot(
+    repo: &Repository,
+    wrap_args: &WrapArgs,
+) -> Result<HashMap<String, Option<String>>, GitAiError> {
+    if let Err(e) = reconcile_unfinalized_commits(repo) {
+        debug_log(&format!("Failed to reconcile unfinalized commits: {}", e));
+    }
+
+    let hashes = worktree_hashes(repo)?;
+    if !hashes.is_empty() {
+        let human = run_result(
+            &wrap_args.tool,
+            &wrap_args.model,
+            CheckpointKind::Human,
+            hashes.keys().cloned().collect(),
+        );
+        checkpoint::run(
+            repo,
+            &author_name(repo),
+            CheckpointKind::Human,
+            false,
+            false,
+            true,
+            Some(human),
+            false,
+        )?;
+    }
+    Ok(hashes)
+}
+
+/// Record an AI checkpoint for every file the command changed; returns how many.
+fn record_changes(
+    repo: &Repository,
+    wrap_args: &WrapArgs,
+    before: &HashMap<String, Option<String>>,
+) -> Result<usize, GitAiError> {
+    let after = worktree_hashes(repo)?;
+    let mut changed: Vec<String> = after
+        .iter()
+        .filter(|(path, hash)| before.get(*path) != Some(hash))
+        .map(|(path, _)| path.clone())
+        .collect();
+    // Files that were dirty before and got restored by the command
+    changed.extend(
+        before
+            .keys()
+            .filter(|path| !after.contains_key(*path))
+            .cloned(),
+    );
+    if changed.is_empty() {
+        return Ok(0);
+    }
+    changed.sort();
+
+    let count = changed.len();
+    let ai = run_result(
+        &wrap_args.tool,
+        &wrap_args.model,
+        CheckpointKind::AiAgent,
+        changed,
+    );
+    checkpoint::run(
+        repo,
+        &author_name(repo),
+        CheckpointKind::AiAgent,
+        false,
+        false,
+        true,
+        Some(ai),
+        false,
+    )?;
+    crate::observability::spawn_background_flush();
+    Ok(count)
+}
+
+/// Checkpoint input attributing `files` to `tool`; human ones are scoped the same way.
+pub(crate) fn run_result(
+    tool: &str,
+    model: &str,
+    kind: CheckpointKind,
+    files: Vec<String>,
+) -> AgentRunResult {
+    let (edited_filepaths, will_edit_filepaths) = if kind == CheckpointKind::Human {
+        (None, Some(files))
+    } else {
+        (Some(files), None)
+    };
+    AgentRunResult {
+        agent_id: AgentId {
+            tool: tool.to_string(),
+            id: format!(
+                "{}-{}",
+                tool,
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_nanos())
+                    .unwrap_or_default()
+            ),
+            model: model.to_string(),
+        },
+        agent_metadata: None,
+        checkpoint_kind: kind,
+        transcript: None,
+        repo_working_dir: None,
+        edited_filepaths,
+        will_edit_filepaths,
+        dirty_files: None,
+    }
+}
+
+/// Content hash of every changed or untracked file; `None` for deleted ones.
+fn worktree_hashes(repo: &Repository) -> Result<HashMap<String, Option<String>>, GitAiError> {
+    let workdir = repo.workdir()?;
+    Ok(repo
+        .get_worktree_filenames()?
+        .into_iter()
+        .map(|path| {
+            let hash = std::fs::read(workdir.join(&path))
+                .ok()
+                .map(|bytes| format!("{:x}", Sha256::digest(&bytes)));
+            (path, hash)
+        })
+        .collect())
+}
+
+pub(crate) fn author_name(repo: &Repository) -> String {
+    match repo.config_get_str("user.name") {
+        Ok(Some(name)) if !name.trim().is_empty() => name,
+        _ => "unknown".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_wrap_args() {
+        let parsed = parse_args(&strings(&[
+            "--tool",
+            "devin",
+            "--model=gpt-5",
+            "--",
+            "run",
+            "-x",
+        ]))
+        .unwrap();
+        assert_eq!(
+            parsed,
+            WrapArgs {
+                tool: "devin".to_string(),
+                model: "gpt-5".to_string(),
+                command: strings(&["run", "-x"]),
+            }
+        );
+
+        let parsed = parse_args(&strings(&["--tool=devin", "run"])).unwrap();
+        assert_eq!(parsed.model, "unknown");
+        assert_eq!(parsed.command, strings(&["run"]));
+
+        assert!(parse_args(&strings(&["--", "run"])).is_err());
+        assert!(parse_args(&strings(&["--tool", "devin", "--"])).is_err());
+        assert!(parse_args(&strings(&["--tool", "devin", "--bogus", "run"])).is_err());
+    }
+}
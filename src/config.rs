@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -7,6 +8,11 @@ use uuid::Uuid;
 use glob::Pattern;
 use serde::{Deserialize, Serialize};
 
+use crate::auth::profiles::AuthProfile;
+use crate::authorship::bot_authors::BotAuthor;
+use crate::authorship::org_metrics::OrgSyncConfig;
+use crate::authorship::push_policy::PushPolicy;
+use crate::commands::checkpoint_agent::agent_registry::CustomAgent;
 use crate::feature_flags::FeatureFlags;
 use crate::git::repository::Repository;
 
@@ -73,6 +79,20 @@ pub struct Config {
     default_prompt_storage: Option<String>,
     api_key: Option<String>,
     quiet: bool,
+    commit_trailers: bool,
+    commit_summary: bool,
+    push_policy: PushPolicy,
+    org_sync: Option<OrgSyncConfig>,
+    async_post_commit: bool,
+    custom_agents: Vec<CustomAgent>,
+    model_aliases: BTreeMap<String, String>,
+    language_overrides: BTreeMap<String, String>,
+    bot_authors: Vec<BotAuthor>,
+    snapshot_skip_patterns: Vec<Pattern>,
+    credential_store: Option<String>,
+    auth_profiles: BTreeMap<String, AuthProfile>,
+    ca_bundle: Option<String>,
+    network: bool,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
@@ -139,6 +159,34 @@ pub struct FileConfig {
     pub api_key: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub quiet: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_trailers: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_summary: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub push_policy: Option<PushPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org_sync: Option<OrgSyncConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub async_post_commit: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_agents: Option<Vec<CustomAgent>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_aliases: Option<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_overrides: Option<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bot_authors: Option<Vec<BotAuthor>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_skip_patterns: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub credential_store: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth_profiles: Option<BTreeMap<String, AuthProfile>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<bool>,
 }
 
 static CONFIG: OnceLock<Config> = OnceLock::new();
@@ -161,6 +209,26 @@ pub struct ConfigPatch {
     pub disable_auto_updates: Option<bool>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub prompt_storage: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_trailers: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit_summary: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub async_post_commit: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub push_policy: Option<PushPolicy>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub org_sync: Option<OrgSyncConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_agents: Option<Vec<CustomAgent>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_aliases: Option<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language_overrides: Option<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bot_authors: Option<Vec<BotAuthor>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub snapshot_skip_patterns: Option<Vec<String>>,
 }
 
 impl Config {
@@ -375,6 +443,91 @@ impl Config {
         self.quiet
     }
 
+    /// Returns true if AI trailers should be appended to commit messages
+    pub fn commit_trailers_enabled(&self) -> bool {
+        self.commit_trailers
+    }
+
+    /// Returns true if a commented attribution summary should be added to commit messages
+    pub fn commit_summary_enabled(&self) -> bool {
+        self.commit_summary
+    }
+
+    /// Policies the pre-push hook enforces on pushed commits
+    pub fn push_policy(&self) -> &PushPolicy {
+        &self.push_policy
+    }
+
+    /// Aggregated metrics uploaded by `sync-prompts --org`, when configured
+    pub fn org_sync(&self) -> Option<&OrgSyncConfig> {
+        self.org_sync.as_ref()
+    }
+
+    /// Returns true if commit authorship is finalized in a background process
+    pub fn async_post_commit_enabled(&self) -> bool {
+        self.async_post_commit
+    }
+
+    /// Agents declared by the user for tools git-ai has no preset for
+    pub fn custom_agents(&self) -> &[CustomAgent] {
+        &self.custom_agents
+    }
+
+    /// Model identifiers or globs mapped to the family stats group them under
+    pub fn model_aliases(&self) -> &BTreeMap<String, String> {
+        &self.model_aliases
+    }
+
+    /// Path globs mapped to the language stats group their files under
+    pub fn language_overrides(&self) -> &BTreeMap<String, String> {
+        &self.language_overrides
+    }
+
+    /// Bot identities whose commits are attributed to an agent, before the built-in ones
+    pub fn bot_authors(&self) -> &[BotAuthor] {
+        &self.bot_authors
+    }
+
+    pub fn snapshot_skip_patterns(&self) -> Vec<&str> {
+        self.snapshot_skip_patterns
+            .iter()
+            .map(Pattern::as_str)
+            .collect()
+    }
+
+    /// Whether checkpoints skip snapshotting and diffing `path` (relative to the repository
+    /// root), attributing the whole file to whoever last changed it instead. Patterns
+    /// match the path or any trailing part of it, so `dist/**` also covers `web/dist/`.
+    pub fn skips_snapshot(&self, path: &str) -> bool {
+        let mut suffixes =
+            std::iter::once(path).chain(path.match_indices('/').map(|(i, _)| &path[i + 1..]));
+        suffixes.any(|suffix| {
+            self.snapshot_skip_patterns
+                .iter()
+                .any(|p| p.matches(suffix))
+        })
+    }
+
+    /// Where login credentials are kept: `Some("keyring")`, `Some("file")`, or `None` to
+    /// let the `auth_keyring` feature flag decide
+    pub fn credential_store(&self) -> Option<&str> {
+        self.credential_store.as_deref()
+    }
+
+    pub fn auth_profiles(&self) -> &BTreeMap<String, AuthProfile> {
+        &self.auth_profiles
+    }
+
+    /// PEM file of root certificates trusted on top of the system and built-in ones
+    pub fn ca_bundle(&self) -> Option<&str> {
+        self.ca_bundle.as_deref()
+    }
+
+    /// False in offline mode, where no command may make network calls
+    pub fn network_enabled(&self) -> bool {
+        self.network
+    }
+
     /// Override feature flags for testing purposes.
     /// Only available when the `test-support` feature is enabled or in test mode.
     /// Must be `pub` to work with integration tests in the `tests/` directory.
@@ -418,6 +571,40 @@ impl Config {
     }
 }
 
+/// Generated files agents rewrite wholesale, whose line-level attribution isn't worth
+/// a snapshot and a diff per checkpoint
+const DEFAULT_SNAPSHOT_SKIP_PATTERNS: &[&str] = &[
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lockb",
+    "Cargo.lock",
+    "Gemfile.lock",
+    "poetry.lock",
+    "composer.lock",
+    "go.sum",
+    "dist/**",
+    "*.min.js",
+    "*.min.css",
+];
+
+fn compile_snapshot_skip_patterns(patterns: Vec<String>) -> Vec<Pattern> {
+    patterns
+        .into_iter()
+        .filter_map(|pattern_str| {
+            Pattern::new(&pattern_str)
+                .map_err(|e| {
+                    crate::logging::warn(&format!(
+                        "Invalid glob pattern in snapshot_skip_patterns '{}': {}",
+                        pattern_str, e
+                    ));
+                })
+                .ok()
+        })
+        .collect()
+}
+
 fn build_config() -> Config {
     let file_cfg = load_file_config();
     let exclude_prompts_in_repositories = file_cfg
@@ -572,6 +759,119 @@ fn build_config() -> Config {
     // Get quiet setting (defaults to false)
     let quiet = file_cfg.as_ref().and_then(|c| c.quiet).unwrap_or(false);
 
+    // Get commit_trailers setting (opt-in, defaults to false)
+    let commit_trailers = file_cfg
+        .as_ref()
+        .and_then(|c| c.commit_trailers)
+        .unwrap_or(false);
+
+    // Get commit_summary setting (opt-in, defaults to false)
+    let commit_summary = file_cfg
+        .as_ref()
+        .and_then(|c| c.commit_summary)
+        .unwrap_or(false);
+
+    // Get push_policy (no policies unless configured)
+    let push_policy = file_cfg
+        .as_ref()
+        .and_then(|c| c.push_policy.clone())
+        .unwrap_or_default();
+
+    let org_sync = file_cfg.as_ref().and_then(|c| c.org_sync.clone());
+
+    // Get async_post_commit setting (opt-in, defaults to false)
+    let async_post_commit = file_cfg
+        .as_ref()
+        .and_then(|c| c.async_post_commit)
+        .unwrap_or(false);
+
+    // Get custom_agents, dropping entries without a name
+    let custom_agents = file_cfg
+        .as_ref()
+        .and_then(|c| c.custom_agents.clone())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|agent| {
+            let valid = !agent.name.trim().is_empty();
+            if !valid {
+                crate::logging::warn("Ignoring custom_agents entry without a name");
+            }
+            valid
+        })
+        .collect();
+
+    // Get model_aliases (built-in normalization only unless configured)
+    let model_aliases = file_cfg
+        .as_ref()
+        .and_then(|c| c.model_aliases.clone())
+        .unwrap_or_default();
+
+    // Get language_overrides (extension-based detection only unless configured)
+    let language_overrides = file_cfg
+        .as_ref()
+        .and_then(|c| c.language_overrides.clone())
+        .unwrap_or_default();
+
+    // Get bot_authors (built-in bots only unless configured)
+    let bot_authors = file_cfg
+        .as_ref()
+        .and_then(|c| c.bot_authors.clone())
+        .unwrap_or_default();
+
+    // Get snapshot_skip_patterns (lockfiles and build output unless configured)
+    let snapshot_skip_patterns = compile_snapshot_skip_patterns(
+        file_cfg
+            .as_ref()
+            .and_then(|c| c.snapshot_skip_patterns.clone())
+            .unwrap_or_else(|| {
+                DEFAULT_SNAPSHOT_SKIP_PATTERNS
+                    .iter()
+                    .map(|p| p.to_string())
+                    .collect()
+            }),
+    );
+
+    // Get credential_store (the auth_keyring feature flag decides unless configured)
+    // Valid values: "keyring", "file"
+    let credential_store = file_cfg
+        .as_ref()
+        .and_then(|c| c.credential_store.clone())
+        .and_then(|s| {
+            if matches!(s.as_str(), "keyring" | "file") {
+                Some(s)
+            } else {
+                crate::logging::warn(&format!("Invalid credential_store value '{}', ignoring", s));
+                None
+            }
+        });
+
+    // Get auth_profiles, dropping entries whose name can't be used
+    let auth_profiles = file_cfg
+        .as_ref()
+        .and_then(|c| c.auth_profiles.clone())
+        .unwrap_or_default()
+        .into_iter()
+        .filter(
+            |(name, _)| match crate::auth::profiles::validate_name(name) {
+                Ok(()) => true,
+                Err(e) => {
+                    crate::logging::warn(&format!("Ignoring auth_profiles entry: {}", e));
+                    false
+                }
+            },
+        )
+        .collect();
+
+    // Get ca_bundle (PEM file of extra root certificates, e.g. a corporate CA)
+    let ca_bundle = file_cfg
+        .as_ref()
+        .and_then(|c| c.ca_bundle.clone())
+        .filter(|path| !path.is_empty());
+
+    // Get network (on unless disabled; GIT_AI_OFFLINE=1 forces it off)
+    let network = !env::var("GIT_AI_OFFLINE").is_ok_and(|v| v == "1" || v == "true")
+        && file_cfg.as_ref().and_then(|c| c.network).unwrap_or(true);
+
     #[cfg(any(test, feature = "test-support"))]
     {
         let mut config = Config {
@@ -591,6 +891,20 @@ fn build_config() -> Config {
             default_prompt_storage,
             api_key,
             quiet,
+            commit_trailers,
+            commit_summary,
+            push_policy,
+            org_sync,
+            async_post_commit,
+            custom_agents,
+            model_aliases,
+            language_overrides,
+            bot_authors,
+            snapshot_skip_patterns,
+            credential_store,
+            auth_profiles,
+            ca_bundle,
+            network,
         };
         apply_test_config_patch(&mut config);
         config
@@ -614,6 +928,20 @@ fn build_config() -> Config {
         default_prompt_storage,
         api_key,
         quiet,
+        commit_trailers,
+        commit_summary,
+        push_policy,
+        org_sync,
+        async_post_commit,
+        custom_agents,
+        model_aliases,
+        language_overrides,
+        bot_authors,
+        snapshot_skip_patterns,
+        credential_store,
+        auth_profiles,
+        ca_bundle,
+        network,
     }
 }
 
@@ -754,7 +1082,7 @@ pub fn get_or_create_distinct_id() -> String {
 
             // Write the new ID to file
             if let Err(e) = fs::write(&id_path, &new_id) {
-                eprintln!("Warning: Failed to write distinct_id file: {}", e);
+                crate::logging::warn(&format!("Failed to write distinct_id file: {}", e));
             }
 
             new_id
@@ -767,6 +1095,16 @@ pub fn update_check_path() -> Option<PathBuf> {
     internal_dir_path().map(|dir| dir.join("update_check"))
 }
 
+/// Returns the path to the org sync state file (~/.git-ai/internal/org_sync)
+pub fn org_sync_state_path() -> Option<PathBuf> {
+    internal_dir_path().map(|dir| dir.join("org_sync"))
+}
+
+/// Returns the path to the key of anonymized output (~/.git-ai/internal/anonymize_key)
+pub fn anonymize_key_path() -> Option<PathBuf> {
+    internal_dir_path().map(|dir| dir.join("anonymize_key"))
+}
+
 /// Load the raw file config
 pub fn load_file_config_public() -> Result<FileConfig, String> {
     let path =
@@ -840,6 +1178,36 @@ fn apply_test_config_patch(config: &mut Config) {
         if let Some(disable_auto_updates) = patch.disable_auto_updates {
             config.disable_auto_updates = disable_auto_updates;
         }
+        if let Some(commit_trailers) = patch.commit_trailers {
+            config.commit_trailers = commit_trailers;
+        }
+        if let Some(commit_summary) = patch.commit_summary {
+            config.commit_summary = commit_summary;
+        }
+        if let Some(async_post_commit) = patch.async_post_commit {
+            config.async_post_commit = async_post_commit;
+        }
+        if let Some(push_policy) = patch.push_policy {
+            config.push_policy = push_policy;
+        }
+        if let Some(org_sync) = patch.org_sync {
+            config.org_sync = Some(org_sync);
+        }
+        if let Some(custom_agents) = patch.custom_agents {
+            config.custom_agents = custom_agents;
+        }
+        if let Some(model_aliases) = patch.model_aliases {
+            config.model_aliases = model_aliases;
+        }
+        if let Some(language_overrides) = patch.language_overrides {
+            config.language_overrides = language_overrides;
+        }
+        if let Some(bot_authors) = patch.bot_authors {
+            config.bot_authors = bot_authors;
+        }
+        if let Some(patterns) = patch.snapshot_skip_patterns {
+            config.snapshot_skip_patterns = compile_snapshot_skip_patterns(patterns);
+        }
         if let Some(prompt_storage) = patch.prompt_storage {
             // Validate the value
             if matches!(prompt_storage.as_str(), "default" | "notes" | "local") {
@@ -885,6 +1253,20 @@ mod tests {
             default_prompt_storage: None,
             api_key: None,
             quiet: false,
+            commit_trailers: false,
+            commit_summary: false,
+            push_policy: PushPolicy::default(),
+            org_sync: None,
+            async_post_commit: false,
+            custom_agents: vec![],
+            model_aliases: BTreeMap::new(),
+            language_overrides: BTreeMap::new(),
+            bot_authors: vec![],
+            snapshot_skip_patterns: vec![],
+            credential_store: None,
+            auth_profiles: BTreeMap::new(),
+            ca_bundle: None,
+            network: true,
         }
     }
 
@@ -992,6 +1374,20 @@ mod tests {
             default_prompt_storage: None,
             api_key: None,
             quiet: false,
+            commit_trailers: false,
+            commit_summary: false,
+            push_policy: PushPolicy::default(),
+            org_sync: None,
+            async_post_commit: false,
+            custom_agents: vec![],
+            model_aliases: BTreeMap::new(),
+            language_overrides: BTreeMap::new(),
+            bot_authors: vec![],
+            snapshot_skip_patterns: vec![],
+            credential_store: None,
+            auth_profiles: BTreeMap::new(),
+            ca_bundle: None,
+            network: true,
         }
     }
 
@@ -1108,6 +1504,20 @@ mod tests {
             default_prompt_storage: default_prompt_storage.map(|s| s.to_string()),
             api_key: None,
             quiet: false,
+            commit_trailers: false,
+            commit_summary: false,
+            push_policy: PushPolicy::default(),
+            org_sync: None,
+            async_post_commit: false,
+            custom_agents: vec![],
+            model_aliases: BTreeMap::new(),
+            language_overrides: BTreeMap::new(),
+            bot_authors: vec![],
+            snapshot_skip_patterns: vec![],
+            credential_store: None,
+            auth_profiles: BTreeMap::new(),
+            ca_bundle: None,
+            network: true,
         }
     }
 
@@ -1292,6 +1702,25 @@ mod tests {
         assert_eq!(channel.as_str(), "enterprise-next");
     }
 
+    #[test]
+    fn test_skips_snapshot_matches_any_trailing_part_of_the_path() {
+        let mut config = create_test_config(vec![], vec![]);
+        config.snapshot_skip_patterns = compile_snapshot_skip_patterns(
+            DEFAULT_SNAPSHOT_SKIP_PATTERNS
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+        );
+        assert!(config.skips_snapshot("package-lock.json"));
+        assert!(config.skips_snapshot("web/package-lock.json"));
+        assert!(config.skips_snapshot("dist/app.js"));
+        assert!(config.skips_snapshot("web/dist/assets/app.js"));
+        assert!(config.skips_snapshot("static/vendor.min.js"));
+        assert!(!config.skips_snapshot("src/dist.rs"));
+        assert!(!config.skips_snapshot("package-lock.json.md"));
+        assert!(!config.skips_snapshot("src/main.rs"));
+    }
+
     #[test]
     fn test_quiet_default_is_false() {
         let config = create_test_config(vec![], vec![]);
@@ -1,4 +1,5 @@
 use std::fmt;
+use std::sync::OnceLock;
 
 #[derive(Debug)]
 pub enum GitAiError {
@@ -18,9 +19,100 @@ pub enum GitAiError {
     FromUtf8Error(std::string::FromUtf8Error),
     PresetError(String),
     SqliteError(rusqlite::Error),
+    /// The current directory (or `-C` path) is not inside a git repository
+    NotARepository(String),
+    /// A working log file could not be parsed
+    CorruptWorkingLog(String),
+    /// The stored credentials are missing or no longer accepted
+    AuthExpired(String),
     Generic(String),
 }
 
+impl GitAiError {
+    /// A stable identifier of the kind of error, for tools that wrap git-ai.
+    ///
+    /// Unlike the message, which may be translated or reworded, codes never change
+    /// once released.
+    pub fn code(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "test-support")]
+            GitAiError::GitError(_) => "git_error",
+            GitAiError::IoError(_) => "io_error",
+            GitAiError::GitCliError { .. } => "git_failed",
+            GitAiError::GixError(_) => "git_error",
+            GitAiError::JsonError(_) => "invalid_json",
+            GitAiError::Utf8Error(_) | GitAiError::FromUtf8Error(_) => "invalid_utf8",
+            GitAiError::PresetError(_) => "invalid_agent_input",
+            GitAiError::SqliteError(_) => "database_error",
+            GitAiError::NotARepository(_) => "not_a_repository",
+            GitAiError::CorruptWorkingLog(_) => "corrupt_working_log",
+            GitAiError::AuthExpired(_) => "auth_expired",
+            GitAiError::Generic(_) => "error",
+        }
+    }
+
+    /// The error as the JSON object printed by `--error-format json`
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut error = serde_json::json!({
+            "code": self.code(),
+            "message": self.to_string(),
+        });
+        if let GitAiError::GitCliError {
+            code: Some(exit_code),
+            ..
+        } = self
+        {
+            error["exit_code"] = serde_json::json!(exit_code);
+        }
+        serde_json::json!({ "error": error })
+    }
+}
+
+/// How commands report the error they exit with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorFormat {
+    Human,
+    Json,
+}
+
+impl ErrorFormat {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "human" | "text" => Some(ErrorFormat::Human),
+            "json" => Some(ErrorFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+pub const ERROR_FORMAT_ENV: &str = "GIT_AI_ERROR_FORMAT";
+
+static ERROR_FORMAT: OnceLock<ErrorFormat> = OnceLock::new();
+
+/// Report errors as `format` for the rest of the process, over `GIT_AI_ERROR_FORMAT`
+pub fn set_error_format(format: ErrorFormat) {
+    let _ = ERROR_FORMAT.set(format);
+}
+
+pub fn error_format() -> ErrorFormat {
+    *ERROR_FORMAT.get_or_init(|| {
+        std::env::var(ERROR_FORMAT_ENV)
+            .ok()
+            .and_then(|name| ErrorFormat::parse(&name))
+            .unwrap_or(ErrorFormat::Human)
+    })
+}
+
+/// Print `error` and exit with status 1. `message` is what people see; with
+/// `--error-format json` stderr gets one JSON line with the error's code instead.
+pub fn exit_with(message: &str, error: &GitAiError) -> ! {
+    match error_format() {
+        ErrorFormat::Human => eprintln!("{}", message),
+        ErrorFormat::Json => eprintln!("{}", error.to_json()),
+    }
+    std::process::exit(1);
+}
+
 impl fmt::Display for GitAiError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -42,6 +134,9 @@ impl fmt::Display for GitAiError {
             GitAiError::FromUtf8Error(e) => write!(f, "From UTF-8 error: {}", e),
             GitAiError::PresetError(e) => write!(f, "{}", e),
             GitAiError::SqliteError(e) => write!(f, "SQLite error: {}", e),
+            GitAiError::NotARepository(e) => write!(f, "{}", e),
+            GitAiError::CorruptWorkingLog(e) => write!(f, "Corrupt working log: {}", e),
+            GitAiError::AuthExpired(e) => write!(f, "{}", e),
             GitAiError::Generic(e) => write!(f, "Generic error: {}", e),
             GitAiError::GixError(e) => write!(f, "Gix error: {}", e),
         }
@@ -105,8 +200,41 @@ impl Clone for GitAiError {
             GitAiError::FromUtf8Error(e) => GitAiError::FromUtf8Error(e.clone()),
             GitAiError::PresetError(s) => GitAiError::PresetError(s.clone()),
             GitAiError::SqliteError(e) => GitAiError::Generic(format!("SQLite error: {}", e)),
+            GitAiError::NotARepository(s) => GitAiError::NotARepository(s.clone()),
+            GitAiError::CorruptWorkingLog(s) => GitAiError::CorruptWorkingLog(s.clone()),
+            GitAiError::AuthExpired(s) => GitAiError::AuthExpired(s.clone()),
             GitAiError::Generic(s) => GitAiError::Generic(s.clone()),
             GitAiError::GixError(e) => GitAiError::Generic(format!("Gix error: {}", e)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_json() {
+        let error = GitAiError::GitCliError {
+            code: Some(128),
+            stderr: "fatal: bad revision".to_string(),
+            args: vec!["log".to_string()],
+        };
+        assert_eq!(
+            error.to_json(),
+            serde_json::json!({
+                "error": {
+                    "code": "git_failed",
+                    "message": "Git CLI (log) failed with exit code 128: fatal: bad revision",
+                    "exit_code": 128,
+                }
+            })
+        );
+        assert_eq!(
+            GitAiError::CorruptWorkingLog("line 3".to_string()).to_json()["error"]["code"],
+            "corrupt_working_log"
+        );
+        assert_eq!(ErrorFormat::parse("JSON"), Some(ErrorFormat::Json));
+        assert_eq!(ErrorFormat::parse("xml"), None);
+    }
+}
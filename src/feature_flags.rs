@@ -55,6 +55,8 @@ define_feature_flags!(
     rewrite_stash: rewrite_stash, debug = true, release = false,
     inter_commit_move: checkpoint_inter_commit_move, debug = false, release = false,
     auth_keyring: auth_keyring, debug = false, release = false,
+    agent_detection: agent_detection, debug = false, release = true,
+    paste_detection: paste_detection, debug = false, release = false,
 );
 
 impl FeatureFlags {
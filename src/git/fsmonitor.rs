@@ -0,0 +1,161 @@
+//! Change detection through a `core.fsmonitor` hook (e.g. Watchman's
+//! `fsmonitor-watchman`), using git's hook protocol version 2.
+//!
+//! The hook is run as `<hook> 2 <token>` and prints a new token followed by the paths
+//! changed since `<token>`, all NUL-terminated. Checkpoints keep the token next to the
+//! working log so the next checkpoint only re-reads files touched in between.
+//!
+//! The builtin daemon (`core.fsmonitor = true`) has no client command to ask it for
+//! changes; git already uses it for the `git status` calls we make.
+
+use crate::git::repository::Repository;
+use crate::utils::debug_log;
+use std::process::Command;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FsmonitorChanges {
+    /// The hook could not tell (first query, reset, overflow): assume everything changed
+    All,
+    /// Changed paths relative to the workdir; entries ending in `/` are directories
+    Paths(Vec<String>),
+}
+
+impl FsmonitorChanges {
+    pub fn may_have_changed(&self, path: &str) -> bool {
+        match self {
+            FsmonitorChanges::All => true,
+            FsmonitorChanges::Paths(paths) => paths.iter().any(|changed| {
+                changed == path || (changed.ends_with('/') && path.starts_with(changed.as_str()))
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FsmonitorQuery {
+    /// Token to pass to the next query
+    pub token: String,
+    pub changes: FsmonitorChanges,
+}
+
+/// The configured fsmonitor hook command, if `core.fsmonitor` names one.
+pub fn fsmonitor_hook(repo: &Repository) -> Option<String> {
+    let value = repo.config_get_str("core.fsmonitor").ok().flatten()?;
+    let value = value.trim();
+    let is_bool = matches!(
+        value.to_ascii_lowercase().as_str(),
+        "" | "true" | "false" | "yes" | "no" | "on" | "off" | "1" | "0"
+    );
+    (!is_bool).then(|| value.to_string())
+}
+
+/// Ask the fsmonitor hook what changed since `since_token`.
+///
+/// Returns `None` when no hook is configured or it failed, in which case callers
+/// fall back to their full scan.
+pub fn query_fsmonitor(repo: &Repository, since_token: Option<&str>) -> Option<FsmonitorQuery> {
+    let hook = fsmonitor_hook(repo)?;
+    let workdir = repo.workdir().ok()?;
+
+    // git runs the hook through the shell from the top of the worktree
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(format!("{} \"$@\"", hook))
+        .arg("fsmonitor")
+        .arg("2")
+        .arg(since_token.unwrap_or(""))
+        .current_dir(&workdir)
+        .output();
+    match output {
+        Ok(output) if output.status.success() => {
+            let query = parse_fsmonitor_output(&output.stdout);
+            if query.is_none() {
+                debug_log("fsmonitor hook returned no token");
+            }
+            query
+        }
+        Ok(output) => {
+            debug_log(&format!("fsmonitor hook exited with {}", output.status));
+            None
+        }
+        Err(e) => {
+            debug_log(&format!("Failed to run fsmonitor hook: {}", e));
+            None
+        }
+    }
+}
+
+fn parse_fsmonitor_output(stdout: &[u8]) -> Option<FsmonitorQuery> {
+    let mut fields = stdout
+        .split(|b| *b == 0)
+        .map(|field| String::from_utf8_lossy(field).to_string());
+    let token = fields.next().filter(|t| !t.is_empty())?;
+
+    let mut paths = Vec::new();
+    for path in fields.filter(|p| !p.is_empty()) {
+        // A lone "/" is the hook's way of saying it lost track
+        if path == "/" {
+            return Some(FsmonitorQuery {
+                token,
+                changes: FsmonitorChanges::All,
+            });
+        }
+        paths.push(path);
+    }
+    Some(FsmonitorQuery {
+        token,
+        changes: FsmonitorChanges::Paths(paths),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_parse_fsmonitor_output() {
+        let query = parse_fsmonitor_output(b"c:1234\0src/lib.rs\0docs/\0").unwrap();
+        assert_eq!(query.token, "c:1234");
+        assert!(query.changes.may_have_changed("src/lib.rs"));
+        assert!(query.changes.may_have_changed("docs/guide.md"));
+        assert!(!query.changes.may_have_changed("src/main.rs"));
+
+        let all = parse_fsmonitor_output(b"c:1235\0/\0").unwrap();
+        assert_eq!(all.changes, FsmonitorChanges::All);
+
+        assert!(parse_fsmonitor_output(b"").is_none());
+    }
+
+    #[test]
+    fn test_query_fsmonitor_runs_configured_hook() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        assert_eq!(query_fsmonitor(repo, None), None);
+
+        let workdir = repo.workdir().unwrap();
+        let hook = workdir.join("fsmonitor-hook");
+        std::fs::write(
+            &hook,
+            "#!/bin/sh\nprintf 'next-token\\0'\n[ \"$2\" = prev ] && printf 'a.txt\\0' || printf '/\\0'\n",
+        )
+        .unwrap();
+        tmp_repo
+            .repo()
+            .config()
+            .unwrap()
+            .set_str("core.fsmonitor", "sh ./fsmonitor-hook")
+            .unwrap();
+        let repo = crate::git::find_repository_in_path(workdir.to_str().unwrap()).unwrap();
+
+        let first = query_fsmonitor(&repo, None).unwrap();
+        assert_eq!(first.token, "next-token");
+        assert_eq!(first.changes, FsmonitorChanges::All);
+
+        let next = query_fsmonitor(&repo, Some("prev")).unwrap();
+        assert_eq!(
+            next.changes,
+            FsmonitorChanges::Paths(vec!["a.txt".to_string()])
+        );
+    }
+}
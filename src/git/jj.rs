@@ -0,0 +1,47 @@
+//! Jujutsu (`jj`) repositories colocated with git.
+//!
+//! jj writes commits straight into the git object store and never runs git hooks. In a
+//! colocated repo it exports its refs after every operation and keeps git's HEAD
+//! (detached) on the parent of the working-copy commit, so `jj commit`/`jj new` show
+//! up as HEAD moving onto a commit git-ai has not seen, and `jj describe` as HEAD being
+//! replaced by a rewrite with the same tree. Both are caught up by
+//! `authorship::reconcile` on the next checkpoint.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Whether `workdir` holds a jj repo that shares its git repo (`jj git init --colocate`).
+pub fn is_colocated_jj_repo(workdir: &Path) -> bool {
+    workdir.join(".jj").join("repo").is_dir() && workdir.join(".git").exists()
+}
+
+/// The email jj records as author, which need not match git's `user.email`.
+pub fn jj_user_email(workdir: &Path) -> Option<String> {
+    let output = Command::new("jj")
+        .args(["config", "get", "user.email"])
+        .current_dir(workdir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let email = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!email.is_empty()).then_some(email)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_colocated_jj_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(!is_colocated_jj_repo(dir.path()));
+
+        std::fs::create_dir_all(dir.path().join(".jj").join("repo")).unwrap();
+        assert!(!is_colocated_jj_repo(dir.path()));
+
+        std::fs::create_dir(dir.path().join(".git")).unwrap();
+        assert!(is_colocated_jj_repo(dir.path()));
+    }
+}
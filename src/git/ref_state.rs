@@ -0,0 +1,152 @@
+//! When HEAD and the authorship notes last moved, known without running git.
+//!
+//! Caches computed at a commit (blame, line metrics) go stale when HEAD moves, and also
+//! when `refs/notes/ai` does: the note of a new commit is written after HEAD already
+//! points to it, and fetches bring in notes for commits already checked out. Resolving
+//! both refs costs a git process per lookup, so caches are keyed by a stamp instead:
+//! the contents of `HEAD`, the sizes and mtimes of the HEAD and notes reflogs, which git
+//! appends to on every update (including updates of the branch HEAD points to), and
+//! the generation of the record the `reference-transaction` hook keeps in
+//! `ai/ref_state.json`, which covers repositories where reflogs are turned off.
+//!
+//! Without reflogs or the hook there is nothing to vouch for the refs, and callers
+//! resolve them on every lookup as before.
+
+use crate::error::GitAiError;
+use crate::git::repository::Repository;
+use crate::mdm::utils::write_atomic;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::UNIX_EPOCH;
+
+pub const REF_STATE_FILE: &str = "ref_state.json";
+
+pub const NOTES_REF: &str = "refs/notes/ai";
+
+/// Refs the `reference-transaction` hook saw move last, in the common git dir
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RefRecord {
+    /// Bumped by every transaction that moves a watched ref
+    pub generation: u64,
+    /// Latest value of each watched ref (all zeros once deleted)
+    pub refs: BTreeMap<String, String>,
+}
+
+impl RefRecord {
+    pub fn read(common_dir: &Path) -> Option<RefRecord> {
+        let raw = fs::read_to_string(record_path(common_dir)).ok()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    /// Record the `(new, refname)` updates of a committed transaction that touch HEAD,
+    /// the branch it points to, or the notes. Returns whether the record changed.
+    pub fn record_updates(
+        common_dir: &Path,
+        head_ref: Option<&str>,
+        updates: &[(&str, &str)],
+    ) -> Result<bool, GitAiError> {
+        let watched: Vec<_> = updates
+            .iter()
+            .filter(|(_, refname)| {
+                *refname == "HEAD" || *refname == NOTES_REF || Some(*refname) == head_ref
+            })
+            .collect();
+        if watched.is_empty() {
+            return Ok(false);
+        }
+        let mut record = Self::read(common_dir).unwrap_or_default();
+        record.generation += 1;
+        for (new, refname) in watched {
+            record.refs.insert(refname.to_string(), new.to_string());
+        }
+        let path = record_path(common_dir);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        write_atomic(&path, serde_json::to_string(&record)?.as_bytes())?;
+        Ok(true)
+    }
+}
+
+fn record_path(common_dir: &Path) -> std::path::PathBuf {
+    common_dir.join("ai").join(REF_STATE_FILE)
+}
+
+/// A token that changes whenever HEAD or the authorship notes move, or None when
+/// neither a reflog of HEAD nor the hook's record can tell
+pub fn ref_stamp(repo: &Repository) -> Option<String> {
+    let head = fs::read_to_string(repo.path().join("HEAD")).ok()?;
+    let head_log = file_stamp(&repo.path().join("logs").join("HEAD"));
+    let record = RefRecord::read(repo.common_dir());
+    if head_log.is_none() && record.is_none() {
+        return None;
+    }
+    let notes_log = file_stamp(&repo.common_dir().join("logs").join(NOTES_REF));
+    Some(format!(
+        "{}|{}|{}|{}",
+        head.trim(),
+        head_log.unwrap_or_default(),
+        notes_log.unwrap_or_default(),
+        record.map_or(0, |r| r.generation)
+    ))
+}
+
+fn file_stamp(path: &Path) -> Option<String> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos());
+    Some(format!("{}:{}", metadata.len(), modified))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_ref_stamp_moves_with_head_and_notes() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        tmp_repo.write_file("a.txt", "one\n", true).unwrap();
+        tmp_repo.commit_with_message("first").unwrap();
+        let repo = tmp_repo.gitai_repo();
+
+        let first = ref_stamp(repo).unwrap();
+        assert_eq!(ref_stamp(repo), Some(first.clone()));
+
+        repo.git(&["notes", "--ref=ai", "add", "-f", "-m", "note", "HEAD"])
+            .unwrap();
+        let noted = ref_stamp(repo).unwrap();
+        assert_ne!(noted, first);
+
+        tmp_repo.write_file("a.txt", "one\ntwo\n", true).unwrap();
+        tmp_repo.commit_with_message("second").unwrap();
+        assert_ne!(ref_stamp(repo).unwrap(), noted);
+    }
+
+    #[test]
+    fn test_record_updates_only_counts_watched_refs() {
+        let dir = tempfile::tempdir().unwrap();
+        let sha = "a".repeat(40);
+        let updates = [(sha.as_str(), "refs/heads/other")];
+        assert!(!RefRecord::record_updates(dir.path(), Some("refs/heads/main"), &updates).unwrap());
+        assert_eq!(RefRecord::read(dir.path()), None);
+
+        let updates = [
+            (sha.as_str(), "refs/heads/main"),
+            (sha.as_str(), NOTES_REF),
+            (sha.as_str(), "refs/tags/v1"),
+        ];
+        assert!(RefRecord::record_updates(dir.path(), Some("refs/heads/main"), &updates).unwrap());
+        let record = RefRecord::read(dir.path()).unwrap();
+        assert_eq!(record.generation, 1);
+        assert_eq!(
+            record.refs.keys().collect::<Vec<_>>(),
+            vec!["refs/heads/main", NOTES_REF]
+        );
+    }
+}
@@ -5,6 +5,7 @@ use crate::git::repository::{Repository, exec_git, exec_git_stdin};
 use crate::utils::debug_log;
 use serde_json;
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 // Modern refspecs without force to enable proper merging
 pub const AI_AUTHORSHIP_REFNAME: &str = "ai";
@@ -91,15 +92,15 @@ pub fn get_commits_with_notes_from_list(
     }
 
     // Build the result Vec
+    let authorship_logs = get_authorships_concurrent(repo, commit_shas);
     let mut result = Vec::new();
-    for sha in commit_shas {
+    for (sha, authorship_log) in commit_shas.iter().zip(authorship_logs) {
         let git_author = commit_authors
             .get(sha)
             .cloned()
             .unwrap_or_else(|| "Unknown".to_string());
 
-        // Check if this commit has a note by trying to show it
-        if let Some(authorship_log) = get_authorship(repo, sha) {
+        if let Some(authorship_log) = authorship_log {
             result.push(CommitAuthorship::Log {
                 sha: sha.clone(),
                 git_author,
@@ -116,6 +117,32 @@ pub fn get_commits_with_notes_from_list(
     Ok(result)
 }
 
+/// The authorship logs of `commit_shas`, in the same order, read concurrently using
+/// the MAX_CONCURRENT pattern: each one is a `git notes show`.
+fn get_authorships_concurrent(
+    repo: &Repository,
+    commit_shas: &[String],
+) -> Vec<Option<AuthorshipLog>> {
+    const MAX_CONCURRENT: usize = 30;
+
+    smol::block_on(async {
+        let semaphore = Arc::new(smol::lock::Semaphore::new(MAX_CONCURRENT));
+        let tasks: Vec<_> = commit_shas
+            .iter()
+            .map(|sha| {
+                let sha = sha.clone();
+                let repo = repo.clone();
+                let semaphore = Arc::clone(&semaphore);
+                smol::spawn(async move {
+                    let _permit = semaphore.acquire().await;
+                    smol::unblock(move || get_authorship(&repo, &sha)).await
+                })
+            })
+            .collect();
+        futures::future::join_all(tasks).await
+    })
+}
+
 // Show an authorship note and return its JSON content if found, or None if it doesn't exist.
 pub fn show_authorship_note(repo: &Repository, commit_sha: &str) -> Option<String> {
     let mut args = repo.global_args_for_exec();
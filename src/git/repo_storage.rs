@@ -1,14 +1,23 @@
 use crate::authorship::attribution_tracker::LineAttribution;
 use crate::authorship::authorship_log::PromptRecord;
 use crate::authorship::authorship_log_serialization::generate_short_hash;
+use crate::authorship::diff_cache::DiffCache;
+use crate::authorship::imara_diff_utils::{DiffOp, capture_diff_slices};
+use crate::authorship::status_summary::{SUMMARY_FILE, StatusSummary};
 use crate::authorship::working_log::{CHECKPOINT_API_VERSION, Checkpoint, CheckpointKind};
 use crate::error::GitAiError;
+use crate::git::repository::{exec_git, resolve_common_dir};
 use crate::git::rewrite_log::{RewriteLogEvent, append_event_to_file};
-use crate::utils::{debug_log, normalize_to_posix};
+use crate::git::stat_cache::{STAT_CACHE_FILE, StatCache};
+use crate::utils::{debug_log, normalize_to_posix, read_text_lossy, with_file_bytes};
+use flate2::Compression;
+use flate2::read::ZlibDecoder;
+use flate2::write::ZlibEncoder;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::path::{Path, PathBuf};
 
 /// Initial attributions data structure stored in the INITIAL file
@@ -26,14 +35,29 @@ pub struct RepoStorage {
     pub repo_workdir: PathBuf,
     pub working_logs: PathBuf,
     pub rewrite_log: PathBuf,
+    /// Manual reclassifications, kept for `git-ai audit export`
+    pub audit_log: PathBuf,
     pub logs: PathBuf,
 }
 
 impl RepoStorage {
     pub fn for_repo_path(repo_path: &Path, repo_workdir: &Path) -> RepoStorage {
+        let common_dir = resolve_common_dir(repo_path);
+        Self::for_worktree(repo_path, &common_dir, repo_workdir)
+    }
+
+    /// Storage for one worktree of a repository.
+    ///
+    /// Working logs are keyed by base commit and describe uncommitted changes, so they
+    /// stay under the worktree's own git dir: two worktrees on the same commit must not
+    /// share one. The rewrite log records rewrites of shared history, and the audit log
+    /// reclassifications of what gets committed to it; both live in the common dir so
+    /// every worktree appends to the same files.
+    pub fn for_worktree(repo_path: &Path, common_dir: &Path, repo_workdir: &Path) -> RepoStorage {
         let ai_dir = repo_path.join("ai");
         let working_logs_dir = ai_dir.join("working_logs");
-        let rewrite_log_file = ai_dir.join("rewrite_log");
+        let rewrite_log_file = common_dir.join("ai").join("rewrite_log");
+        let audit_log_file = common_dir.join("ai").join("audit_log");
         let logs_dir = ai_dir.join("logs");
 
         let config = RepoStorage {
@@ -41,6 +65,7 @@ impl RepoStorage {
             repo_workdir: repo_workdir.to_path_buf(),
             working_logs: working_logs_dir,
             rewrite_log: rewrite_log_file,
+            audit_log: audit_log_file,
             logs: logs_dir,
         };
 
@@ -52,6 +77,9 @@ impl RepoStorage {
         let ai_dir = self.repo_path.join("ai");
 
         fs::create_dir_all(ai_dir)?;
+        if let Some(rewrite_log_dir) = self.rewrite_log.parent() {
+            fs::create_dir_all(rewrite_log_dir)?;
+        }
 
         // Create working_logs directory
         fs::create_dir_all(&self.working_logs)?;
@@ -66,6 +94,56 @@ impl RepoStorage {
         Ok(())
     }
 
+    /// The diff cache of the attribution tracker, shared by all worktrees since its
+    /// entries only depend on file contents
+    pub fn diff_cache(&self) -> DiffCache {
+        let shared = self
+            .rewrite_log
+            .parent()
+            .map_or_else(|| self.repo_path.join("ai"), Path::to_path_buf);
+        DiffCache::new(shared.join("diff_cache"))
+    }
+
+    /// Directories holding this repository's local git-ai data (working logs, rewrite
+    /// log, logs). More than one in a linked worktree, where the rewrite log is shared.
+    pub fn data_dirs(&self) -> Vec<PathBuf> {
+        let mut dirs = vec![self.repo_path.join("ai")];
+        if let Some(shared) = self.rewrite_log.parent()
+            && !dirs.iter().any(|d| d == shared)
+        {
+            dirs.push(shared.to_path_buf());
+        }
+        dirs
+    }
+
+    /// Delete all local git-ai data for this repository. Authorship notes are refs and
+    /// are left alone. Returns the directories that were removed.
+    pub fn purge(&self) -> Result<Vec<PathBuf>, GitAiError> {
+        let mut removed = Vec::new();
+        for dir in self.data_dirs() {
+            if dir.exists() {
+                fs::remove_dir_all(&dir)?;
+                removed.push(dir);
+            }
+        }
+        Ok(removed)
+    }
+
+    /* Reconciliation state */
+
+    /// HEAD as of the last reconciliation pass (see `authorship::reconcile`)
+    pub fn read_last_known_head(&self) -> Option<String> {
+        fs::read_to_string(self.repo_path.join("ai").join("last_known_head"))
+            .ok()
+            .map(|sha| sha.trim().to_string())
+            .filter(|sha| !sha.is_empty())
+    }
+
+    pub fn write_last_known_head(&self, sha: &str) -> Result<(), GitAiError> {
+        fs::write(self.repo_path.join("ai").join("last_known_head"), sha)?;
+        Ok(())
+    }
+
     /* Working Log Persistance */
 
     pub fn working_log_for_base_commit(&self, sha: &str) -> PersistedWorkingLog {
@@ -146,6 +224,84 @@ impl RepoStorage {
     }
 }
 
+/// Iterator over the checkpoints of a working log, from [`PersistedWorkingLog::checkpoints`]
+pub struct CheckpointReader {
+    path: PathBuf,
+    lines: Option<std::io::Lines<BufReader<fs::File>>>,
+    line_number: usize,
+    old_to_new_hash: HashMap<String, String>,
+}
+
+impl Iterator for CheckpointReader {
+    type Item = Result<Checkpoint, GitAiError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.as_mut()?.next()? {
+                Ok(line) => line,
+                Err(e) => return Some(Err(e.into())),
+            };
+            self.line_number += 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let mut checkpoint: Checkpoint = match serde_json::from_str(&line) {
+                Ok(checkpoint) => checkpoint,
+                Err(e) => {
+                    return Some(Err(GitAiError::CorruptWorkingLog(format!(
+                        "{} line {}: {}",
+                        self.path.display(),
+                        self.line_number,
+                        e
+                    ))));
+                }
+            };
+
+            if checkpoint.api_version != CHECKPOINT_API_VERSION {
+                debug_log(&format!(
+                    "unsupported checkpoint api version: {} (silently skipping checkpoint)",
+                    checkpoint.api_version
+                ));
+                continue;
+            }
+
+            migrate_prompt_hashes(&mut checkpoint, &self.old_to_new_hash);
+            return Some(Ok(checkpoint));
+        }
+    }
+}
+
+/// Step 2: Replace 7-char author_ids in the checkpoint's attributions and line_attributions
+fn migrate_prompt_hashes(checkpoint: &mut Checkpoint, old_to_new_hash: &HashMap<String, String>) {
+    for entry in &mut checkpoint.entries {
+        // Replace author_ids in attributions
+        for attr in &mut entry.attributions {
+            if attr.author_id.len() == 7
+                && let Some(new_hash) = old_to_new_hash.get(&attr.author_id)
+            {
+                attr.author_id = new_hash.clone();
+            }
+        }
+
+        // Replace author_ids in line_attributions
+        for line_attr in &mut entry.line_attributions {
+            if line_attr.author_id.len() == 7
+                && let Some(new_hash) = old_to_new_hash.get(&line_attr.author_id)
+            {
+                line_attr.author_id = new_hash.clone();
+            }
+            // Also migrate the overrode field if it contains a 7-char hash
+            if let Some(ref overrode_id) = line_attr.overrode
+                && overrode_id.len() == 7
+                && let Some(new_hash) = old_to_new_hash.get(overrode_id)
+            {
+                line_attr.overrode = Some(new_hash.clone());
+            }
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct PersistedWorkingLog {
     pub dir: PathBuf,
@@ -203,34 +359,191 @@ impl PersistedWorkingLog {
         // Clear checkpoints by truncating the JSONL file
         let checkpoints_file = self.dir.join("checkpoints.jsonl");
         fs::write(&checkpoints_file, "")?;
+        self.remove_status_summary()?;
+
+        // Nothing is known about file states any more
+        let token_file = self.dir.join("fsmonitor_token");
+        if token_file.exists() {
+            fs::remove_file(&token_file)?;
+        }
+        let stat_cache_file = self.dir.join(STAT_CACHE_FILE);
+        if stat_cache_file.exists() {
+            fs::remove_file(&stat_cache_file)?;
+        }
 
         Ok(())
     }
 
+    /* fsmonitor token */
+
+    /// Token of the fsmonitor query made by the last checkpoint
+    pub fn read_fsmonitor_token(&self) -> Option<String> {
+        fs::read_to_string(self.dir.join("fsmonitor_token"))
+            .ok()
+            .map(|token| token.trim_end_matches('\n').to_string())
+            .filter(|token| !token.is_empty())
+    }
+
+    pub fn write_fsmonitor_token(&self, token: &str) -> Result<(), GitAiError> {
+        fs::write(self.dir.join("fsmonitor_token"), token)?;
+        Ok(())
+    }
+
+    /* stat cache */
+
+    /// Stat data of the files the last checkpoint read
+    pub fn read_stat_cache(&self) -> Option<StatCache> {
+        let raw = fs::read_to_string(self.dir.join(STAT_CACHE_FILE)).ok()?;
+        serde_json::from_str(&raw).ok()
+    }
+
+    pub fn write_stat_cache(&self, cache: &StatCache) -> Result<(), GitAiError> {
+        write_blob_atomically(
+            &self.dir.join(STAT_CACHE_FILE),
+            &serde_json::to_string(cache)?,
+        )
+    }
+
     /* blob storage */
+
+    /// Read a snapshot by its content hash, transparently resolving delta-encoded blobs.
     pub fn get_file_version(&self, sha: &str) -> Result<String, GitAiError> {
-        let blob_path = self.dir.join("blobs").join(sha);
-        Ok(fs::read_to_string(blob_path)?)
+        let blobs_dir = self.dir.join("blobs");
+        let full_path = blobs_dir.join(sha);
+        if full_path.exists() {
+            return with_file_bytes(&full_path, decode_full_blob)?;
+        }
+
+        let delta_path = blobs_dir.join(format!("{}.delta", sha));
+        if !delta_path.exists() {
+            // Preserve the original not-found error for callers
+            return Ok(fs::read_to_string(full_path)?);
+        }
+
+        let delta: BlobDelta = serde_json::from_str(&fs::read_to_string(delta_path)?)?;
+        if delta.depth > MAX_DELTA_CHAIN_DEPTH {
+            return Err(GitAiError::Generic(format!(
+                "Delta chain for blob {} exceeds maximum depth",
+                sha
+            )));
+        }
+        let base = if delta.git_base {
+            self.read_git_blob(&delta.base)?
+        } else {
+            self.get_file_version(&delta.base)?
+        };
+        Ok(delta.apply(&base))
+    }
+
+    /// Content of a blob of the repository, as a snapshot delta base
+    fn read_git_blob(&self, oid: &str) -> Result<String, GitAiError> {
+        let args = [
+            "-C".to_string(),
+            self.repo_workdir.to_string_lossy().to_string(),
+            "cat-file".to_string(),
+            "blob".to_string(),
+            oid.to_string(),
+        ];
+        let output = exec_git(&args)?;
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+
+    /// Returns true if a snapshot with this content hash is already stored (full or delta).
+    pub fn has_file_version(&self, sha: &str) -> bool {
+        let blobs_dir = self.dir.join("blobs");
+        blobs_dir.join(sha).exists() || blobs_dir.join(format!("{}.delta", sha)).exists()
     }
 
     #[allow(dead_code)]
     pub fn persist_file_version(&self, content: &str) -> Result<String, GitAiError> {
-        // Create SHA256 hash of the content
-        let mut hasher = Sha256::new();
-        hasher.update(content.as_bytes());
-        let sha = format!("{:x}", hasher.finalize());
+        self.persist_file_version_with_base(content, None)
+    }
 
-        // Ensure blobs directory exists
-        let blobs_dir = self.dir.join("blobs");
-        fs::create_dir_all(&blobs_dir)?;
+    /// Store a snapshot content-addressed by its SHA256. Identical content is only written
+    /// once. When `base_sha` names the previous snapshot of the same path, the blob is
+    /// stored as a line delta against it if that is meaningfully smaller than the content.
+    pub fn persist_file_version_with_base(
+        &self,
+        content: &str,
+        base_sha: Option<&str>,
+    ) -> Result<String, GitAiError> {
+        let sha = content_sha256(content);
+        if self.has_file_version(&sha) {
+            return Ok(sha);
+        }
 
-        // Write content to blob file
-        let blob_path = blobs_dir.join(&sha);
-        fs::write(blob_path, content)?;
+        fs::create_dir_all(self.dir.join("blobs"))?;
 
+        let delta = base_sha
+            .filter(|base_sha| *base_sha != sha)
+            .and_then(|base_sha| self.try_build_delta(content, base_sha));
+        self.write_file_version(&sha, content, delta)?;
         Ok(sha)
     }
 
+    /// Store the first snapshot of a path, as a line delta against the path's blob
+    /// `git_oid` in the base commit (whose content is `git_content`) if that is
+    /// meaningfully smaller than the content. The base commit stays reachable for as
+    /// long as its working log is in use, so the blob doesn't need a copy of its own.
+    pub fn persist_file_version_with_git_base(
+        &self,
+        content: &str,
+        git_oid: &str,
+        git_content: &str,
+    ) -> Result<String, GitAiError> {
+        let sha = content_sha256(content);
+        if self.has_file_version(&sha) {
+            return Ok(sha);
+        }
+
+        fs::create_dir_all(self.dir.join("blobs"))?;
+        let mut delta = BlobDelta::compute(git_oid, 1, git_content, content);
+        delta.git_base = true;
+        self.write_file_version(&sha, content, Some(delta))?;
+        Ok(sha)
+    }
+
+    /// Write the snapshot `sha` as `delta` if that's less than half the size of the
+    /// content, and in full (compressed if large enough) otherwise
+    fn write_file_version(
+        &self,
+        sha: &str,
+        content: &str,
+        delta: Option<BlobDelta>,
+    ) -> Result<(), GitAiError> {
+        let blobs_dir = self.dir.join("blobs");
+        if let Some(delta) = delta {
+            let serialized = serde_json::to_string(&delta)?;
+            if serialized.len() < content.len() / 2 {
+                return write_blob_atomically(
+                    &blobs_dir.join(format!("{}.delta", sha)),
+                    &serialized,
+                );
+            }
+        }
+        write_blob_atomically(&blobs_dir.join(sha), encode_full_blob(content))
+    }
+
+    fn try_build_delta(&self, content: &str, base_sha: &str) -> Option<BlobDelta> {
+        let depth = self.delta_depth(base_sha)?;
+        if depth >= MAX_DELTA_CHAIN_DEPTH {
+            return None;
+        }
+        let base = self.get_file_version(base_sha).ok()?;
+        Some(BlobDelta::compute(base_sha, depth + 1, &base, content))
+    }
+
+    /// Number of deltas that must be applied to reconstruct `sha` (0 for a full blob).
+    fn delta_depth(&self, sha: &str) -> Option<u32> {
+        let blobs_dir = self.dir.join("blobs");
+        if blobs_dir.join(sha).exists() {
+            return Some(0);
+        }
+        let raw = fs::read_to_string(blobs_dir.join(format!("{}.delta", sha))).ok()?;
+        let delta: BlobDelta = serde_json::from_str(&raw).ok()?;
+        Some(delta.depth)
+    }
+
     pub fn to_repo_absolute_path(&self, file_path: &str) -> String {
         if Path::new(file_path).is_absolute() {
             return file_path.to_string();
@@ -304,16 +617,17 @@ impl PersistedWorkingLog {
         let file_path = self.to_repo_absolute_path(file_path);
 
         // Fall back to reading from filesystem
-        match fs::read(&file_path) {
-            Ok(bytes) => Ok(String::from_utf8_lossy(&bytes).to_string()),
-            Err(_) => Ok(String::new()),
-        }
+        Ok(read_text_lossy(Path::new(&file_path)).unwrap_or_default())
     }
 
     /* append checkpoint */
     pub fn append_checkpoint(&self, checkpoint: &Checkpoint) -> Result<(), GitAiError> {
         // Read existing checkpoints
         let mut checkpoints = self.read_all_checkpoints().unwrap_or_default();
+        let mut summary = self
+            .current_status_summary()
+            .unwrap_or_else(|| StatusSummary::from_checkpoints(&checkpoints));
+        summary.apply(checkpoint);
 
         // Create a copy, potentially without transcript to reduce storage size.
         // Transcripts are refetched in update_prompts_to_latest() before post-commit
@@ -365,84 +679,64 @@ impl PersistedWorkingLog {
         self.prune_old_char_attributions(&mut checkpoints);
 
         // Write all checkpoints back
-        self.write_all_checkpoints(&checkpoints)
-    }
-
-    pub fn read_all_checkpoints(&self) -> Result<Vec<Checkpoint>, GitAiError> {
-        let checkpoints_file = self.dir.join("checkpoints.jsonl");
+        self.write_all_checkpoints(&checkpoints)?;
 
-        if !checkpoints_file.exists() {
-            return Ok(Vec::new());
+        // The summary only speeds up status, so failing to write it isn't an error
+        summary.checkpoints_bytes = self.checkpoints_bytes();
+        if let Err(e) = self.write_status_summary(&summary) {
+            debug_log(&format!("Failed to write status summary: {}", e));
         }
+        Ok(())
+    }
 
-        let content = fs::read_to_string(&checkpoints_file)?;
-        let mut checkpoints = Vec::new();
-
-        // Parse JSONL file - each line is a separate JSON object
-        for line in content.lines() {
-            if line.trim().is_empty() {
-                continue;
-            }
-
-            let checkpoint: Checkpoint = serde_json::from_str(line)
-                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-
-            if checkpoint.api_version != CHECKPOINT_API_VERSION {
-                debug_log(&format!(
-                    "unsupported checkpoint api version: {} (silently skipping checkpoint)",
-                    checkpoint.api_version
-                ));
-                continue;
-            }
+    pub fn read_all_checkpoints(&self) -> Result<Vec<Checkpoint>, GitAiError> {
+        self.checkpoints()?.collect()
+    }
 
-            checkpoints.push(checkpoint);
+    /// The checkpoints of the working log one at a time, oldest first: the same ones
+    /// `read_all_checkpoints` returns, without holding them all in memory.
+    ///
+    /// Migrating 7-char prompt hashes needs every agent of the log, so the file is
+    /// read twice, the first time only for the agents.
+    pub fn checkpoints(&self) -> Result<CheckpointReader, GitAiError> {
+        let path = self.dir.join("checkpoints.jsonl");
+        if !path.exists() {
+            return Ok(CheckpointReader {
+                path,
+                lines: None,
+                line_number: 0,
+                old_to_new_hash: HashMap::new(),
+            });
         }
 
-        // Migrate 7-char prompt hashes to 16-char hashes
         // Step 1: Build mapping from old 7-char hash to new 16-char hash
+        #[derive(Deserialize)]
+        struct AgentOnly {
+            #[serde(default)]
+            api_version: String,
+            agent_id: Option<crate::authorship::working_log::AgentId>,
+        }
         let mut old_to_new_hash: HashMap<String, String> = HashMap::new();
-
-        for checkpoint in &checkpoints {
-            if let Some(agent_id) = &checkpoint.agent_id {
+        for line in BufReader::new(fs::File::open(&path)?).lines() {
+            let line = line?;
+            // Lines that don't parse are reported on the second pass
+            if let Ok(AgentOnly {
+                api_version,
+                agent_id: Some(agent_id),
+            }) = serde_json::from_str(&line)
+                && api_version == CHECKPOINT_API_VERSION
+            {
                 let new_hash = generate_short_hash(&agent_id.id, &agent_id.tool);
-                let old_hash = new_hash[..7].to_string();
-                old_to_new_hash.insert(old_hash, new_hash);
-            }
-        }
-
-        // Step 2: Replace 7-char author_ids in all checkpoints' attributions and line_attributions
-        let mut migrated_checkpoints = Vec::new();
-        for mut checkpoint in checkpoints {
-            for entry in &mut checkpoint.entries {
-                // Replace author_ids in attributions
-                for attr in &mut entry.attributions {
-                    if attr.author_id.len() == 7
-                        && let Some(new_hash) = old_to_new_hash.get(&attr.author_id)
-                    {
-                        attr.author_id = new_hash.clone();
-                    }
-                }
-
-                // Replace author_ids in line_attributions
-                for line_attr in &mut entry.line_attributions {
-                    if line_attr.author_id.len() == 7
-                        && let Some(new_hash) = old_to_new_hash.get(&line_attr.author_id)
-                    {
-                        line_attr.author_id = new_hash.clone();
-                    }
-                    // Also migrate the overrode field if it contains a 7-char hash
-                    if let Some(ref overrode_id) = line_attr.overrode
-                        && overrode_id.len() == 7
-                        && let Some(new_hash) = old_to_new_hash.get(overrode_id)
-                    {
-                        line_attr.overrode = Some(new_hash.clone());
-                    }
-                }
+                old_to_new_hash.insert(new_hash[..7].to_string(), new_hash);
             }
-            migrated_checkpoints.push(checkpoint);
         }
 
-        Ok(migrated_checkpoints)
+        Ok(CheckpointReader {
+            lines: Some(BufReader::new(fs::File::open(&path)?).lines()),
+            path,
+            line_number: 0,
+            old_to_new_hash,
+        })
     }
 
     /// Remove char-level attributions from all but the most recent checkpoint per file.
@@ -495,7 +789,55 @@ impl PersistedWorkingLog {
             fs::write(&checkpoints_file, "")?;
         }
 
-        Ok(())
+        // The checkpoints may not be the ones the summary was computed from
+        self.remove_status_summary()
+    }
+
+    /* status summary */
+
+    /// The summary of the checkpoints for `git-ai status`, rebuilt from them when it is
+    /// missing or stale
+    pub fn status_summary(&self) -> Result<StatusSummary, GitAiError> {
+        if let Some(summary) = self.current_status_summary() {
+            return Ok(summary);
+        }
+        // Measured first: checkpoints appended while streaming leave the summary stale
+        let mut summary = StatusSummary {
+            checkpoints_bytes: self.checkpoints_bytes(),
+            ..Default::default()
+        };
+        for checkpoint in self.checkpoints()? {
+            summary.apply(&checkpoint?);
+        }
+        if let Err(e) = self.write_status_summary(&summary) {
+            debug_log(&format!("Failed to write status summary: {}", e));
+        }
+        Ok(summary)
+    }
+
+    fn checkpoints_bytes(&self) -> u64 {
+        fs::metadata(self.dir.join("checkpoints.jsonl")).map_or(0, |m| m.len())
+    }
+
+    /// The stored summary, if it was computed from the current checkpoints
+    fn current_status_summary(&self) -> Option<StatusSummary> {
+        let raw = fs::read_to_string(self.dir.join(SUMMARY_FILE)).ok()?;
+        let summary: StatusSummary = serde_json::from_str(&raw).ok()?;
+        (summary.checkpoints_bytes == self.checkpoints_bytes()).then_some(summary)
+    }
+
+    fn write_status_summary(&self, summary: &StatusSummary) -> Result<(), GitAiError> {
+        write_blob_atomically(
+            &self.dir.join(SUMMARY_FILE),
+            &serde_json::to_string(summary)?,
+        )
+    }
+
+    fn remove_status_summary(&self) -> Result<(), GitAiError> {
+        match fs::remove_file(self.dir.join(SUMMARY_FILE)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
     }
 
     pub fn all_touched_files(&self) -> Result<HashSet<String>, GitAiError> {
@@ -590,6 +932,132 @@ impl PersistedWorkingLog {
     }
 }
 
+/// Longest chain of deltas allowed before a snapshot is stored in full again.
+const MAX_DELTA_CHAIN_DEPTH: u32 = 16;
+
+/// Hash that names a snapshot of `content`
+pub fn content_sha256(content: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(content.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Full snapshots at least this large are stored zlib-compressed when that saves space
+const COMPRESS_MIN_BYTES: usize = 1024;
+
+/// A full snapshot as stored: compressed when large enough and worth it. No UTF-8 text
+/// starts with an ASCII byte followed by a continuation byte, so the zlib header
+/// (`0x78 0x9c`) tells compressed blobs apart from plain ones without a marker.
+fn encode_full_blob(content: &str) -> Vec<u8> {
+    if content.len() >= COMPRESS_MIN_BYTES {
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        if encoder.write_all(content.as_bytes()).is_ok()
+            && let Ok(compressed) = encoder.finish()
+            && compressed.len() < content.len()
+        {
+            return compressed;
+        }
+    }
+    content.as_bytes().to_vec()
+}
+
+fn decode_full_blob(bytes: &[u8]) -> Result<String, GitAiError> {
+    if bytes.len() >= 2 && bytes[0] == 0x78 && bytes[1] >= 0x80 {
+        let mut content = String::new();
+        ZlibDecoder::new(bytes).read_to_string(&mut content)?;
+        return Ok(content);
+    }
+    Ok(std::str::from_utf8(bytes)?.to_string())
+}
+
+/// Write via a temp file + rename so a partially written blob is never mistaken for a
+/// complete one by the skip-if-exists check.
+fn write_blob_atomically(path: &Path, content: impl AsRef<[u8]>) -> Result<(), GitAiError> {
+    let tmp_path = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+    fs::write(&tmp_path, content)?;
+    if let Err(e) = fs::rename(&tmp_path, path) {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// A line-level delta from a base snapshot to a new one.
+#[derive(Debug, Serialize, Deserialize)]
+struct BlobDelta {
+    base: String,
+    /// `base` is a blob of the repository rather than a snapshot
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    git_base: bool,
+    depth: u32,
+    ops: Vec<DeltaOp>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum DeltaOp {
+    /// Copy `len` lines from the base starting at line `start`.
+    Copy { start: usize, len: usize },
+    /// Insert literal text.
+    Insert(String),
+}
+
+impl BlobDelta {
+    fn compute(base_sha: &str, depth: u32, base: &str, content: &str) -> Self {
+        let old_lines: Vec<&str> = base.split_inclusive('\n').collect();
+        let new_lines: Vec<&str> = content.split_inclusive('\n').collect();
+
+        let mut ops: Vec<DeltaOp> = Vec::new();
+
+        for op in capture_diff_slices(&old_lines, &new_lines) {
+            match op {
+                DiffOp::Equal { old_index, len, .. } => ops.push(DeltaOp::Copy {
+                    start: old_index,
+                    len,
+                }),
+                DiffOp::Delete { .. } => {}
+                DiffOp::Insert {
+                    new_index, new_len, ..
+                }
+                | DiffOp::Replace {
+                    new_index, new_len, ..
+                } => {
+                    let text = new_lines[new_index..new_index + new_len].concat();
+                    if let Some(DeltaOp::Insert(prev)) = ops.last_mut() {
+                        prev.push_str(&text);
+                    } else {
+                        ops.push(DeltaOp::Insert(text));
+                    }
+                }
+            }
+        }
+
+        BlobDelta {
+            base: base_sha.to_string(),
+            git_base: false,
+            depth,
+            ops,
+        }
+    }
+
+    fn apply(&self, base: &str) -> String {
+        let base_lines: Vec<&str> = base.split_inclusive('\n').collect();
+        let mut out = String::with_capacity(base.len());
+        for op in &self.ops {
+            match op {
+                DeltaOp::Copy { start, len } => {
+                    let end = (start + len).min(base_lines.len());
+                    for line in &base_lines[(*start).min(end)..end] {
+                        out.push_str(line);
+                    }
+                }
+                DeltaOp::Insert(text) => out.push_str(text),
+            }
+        }
+        out
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -632,6 +1100,22 @@ mod tests {
         assert_eq!(content, "", "rewrite_log should be empty by default");
     }
 
+    #[test]
+    fn test_purge_removes_local_data() {
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        let repo_storage =
+            RepoStorage::for_repo_path(tmp_repo.repo().path(), tmp_repo.repo().workdir().unwrap());
+        repo_storage.working_log_for_base_commit("abc123");
+
+        let ai_dir = tmp_repo.repo().path().join("ai");
+        assert_eq!(repo_storage.data_dirs(), vec![ai_dir.clone()]);
+
+        let removed = repo_storage.purge().unwrap();
+        assert_eq!(removed, vec![ai_dir.clone()]);
+        assert!(!ai_dir.exists());
+        assert!(repo_storage.purge().unwrap().is_empty());
+    }
+
     #[test]
     fn test_ensure_config_directory_handles_existing_files() {
         // Create a temporary repository
@@ -709,6 +1193,113 @@ mod tests {
         assert_eq!(sha, sha2, "Same content should produce same SHA");
     }
 
+    #[test]
+    fn test_persisted_working_log_blob_delta_against_previous_snapshot() {
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        let repo_storage =
+            RepoStorage::for_repo_path(tmp_repo.repo().path(), tmp_repo.repo().workdir().unwrap());
+        let working_log = repo_storage.working_log_for_base_commit("test-commit-sha");
+
+        let base: String = (0..200).map(|i| format!("line {}\n", i)).collect();
+        let base_sha = working_log
+            .persist_file_version_with_base(&base, None)
+            .expect("Failed to persist base version");
+
+        let mut edited = base.replace("line 100\n", "line 100 edited\n");
+        edited.push_str("no trailing newline");
+        let edited_sha = working_log
+            .persist_file_version_with_base(&edited, Some(&base_sha))
+            .expect("Failed to persist edited version");
+
+        let blobs_dir = working_log.dir.join("blobs");
+        assert!(blobs_dir.join(&base_sha).is_file());
+        assert!(
+            !blobs_dir.join(&edited_sha).exists(),
+            "Small edits should not store a full copy"
+        );
+        assert!(blobs_dir.join(format!("{}.delta", edited_sha)).is_file());
+        assert_eq!(working_log.get_file_version(&edited_sha).unwrap(), edited);
+
+        // Re-persisting identical content is a no-op that returns the same hash
+        let again = working_log
+            .persist_file_version_with_base(&edited, Some(&base_sha))
+            .unwrap();
+        assert_eq!(again, edited_sha);
+        assert_eq!(fs::read_dir(&blobs_dir).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_persisted_working_log_compresses_large_snapshots() {
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        let repo_storage =
+            RepoStorage::for_repo_path(tmp_repo.repo().path(), tmp_repo.repo().workdir().unwrap());
+        let working_log = repo_storage.working_log_for_base_commit("test-commit-sha");
+
+        let large: String = (0..500).map(|i| format!("let x{} = {};\n", i, i)).collect();
+        let sha = working_log.persist_file_version(&large).unwrap();
+        let stored = fs::read(working_log.dir.join("blobs").join(&sha)).unwrap();
+        assert!(stored.len() < large.len() / 2);
+        assert_eq!(working_log.get_file_version(&sha).unwrap(), large);
+
+        // Small snapshots, and text that happens to start with an `x`, are kept as is
+        let small = "x = 1\n";
+        let sha = working_log.persist_file_version(small).unwrap();
+        let stored = fs::read(working_log.dir.join("blobs").join(&sha)).unwrap();
+        assert_eq!(stored, small.as_bytes());
+        assert_eq!(working_log.get_file_version(&sha).unwrap(), small);
+    }
+
+    #[test]
+    fn test_persisted_working_log_first_snapshot_delta_against_git_blob() {
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        let base: String = (0..200).map(|i| format!("line {}\n", i)).collect();
+        fs::write(tmp_repo.path().join("big.txt"), &base).unwrap();
+        let repo = tmp_repo.gitai_repo();
+        repo.git(&["add", "big.txt"]).unwrap();
+        repo.git(&["commit", "-m", "Initial"]).unwrap();
+        let head = repo.head().unwrap().target().unwrap();
+        let oid = repo.git(&["rev-parse", "HEAD:big.txt"]).unwrap();
+
+        let working_log = repo.storage.working_log_for_base_commit(&head);
+        let edited = base.replace("line 7\n", "line 7 edited\n");
+        let sha = working_log
+            .persist_file_version_with_git_base(&edited, oid.trim(), &base)
+            .expect("Failed to persist edited version");
+
+        let blobs_dir = working_log.dir.join("blobs");
+        assert!(!blobs_dir.join(&sha).exists());
+        assert!(blobs_dir.join(format!("{}.delta", sha)).is_file());
+        assert_eq!(working_log.get_file_version(&sha).unwrap(), edited);
+
+        // Later snapshots chain onto it like any other
+        let next = edited.replace("line 9\n", "");
+        let next_sha = working_log
+            .persist_file_version_with_base(&next, Some(&sha))
+            .unwrap();
+        assert_eq!(working_log.delta_depth(&next_sha), Some(2));
+        assert_eq!(working_log.get_file_version(&next_sha).unwrap(), next);
+    }
+
+    #[test]
+    fn test_persisted_working_log_blob_delta_chain_is_bounded() {
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        let repo_storage =
+            RepoStorage::for_repo_path(tmp_repo.repo().path(), tmp_repo.repo().workdir().unwrap());
+        let working_log = repo_storage.working_log_for_base_commit("test-commit-sha");
+
+        let mut content: String = (0..200).map(|i| format!("line {}\n", i)).collect();
+        let mut prev_sha = working_log.persist_file_version(&content).unwrap();
+        for i in 0..(MAX_DELTA_CHAIN_DEPTH + 4) {
+            content.push_str(&format!("appended {}\n", i));
+            let sha = working_log
+                .persist_file_version_with_base(&content, Some(&prev_sha))
+                .unwrap();
+            assert_eq!(working_log.get_file_version(&sha).unwrap(), content);
+            assert!(working_log.delta_depth(&sha).unwrap() <= MAX_DELTA_CHAIN_DEPTH);
+            prev_sha = sha;
+        }
+    }
+
     #[test]
     fn test_persisted_working_log_checkpoint_storage() {
         use crate::authorship::working_log::CheckpointKind;
@@ -819,6 +1410,54 @@ mod tests {
         assert_eq!(checkpoints[0].api_version, CHECKPOINT_API_VERSION);
     }
 
+    #[test]
+    fn test_checkpoints_streams_until_corrupt_line() {
+        use crate::authorship::working_log::CheckpointKind;
+
+        let tmp_repo = TmpRepo::new().expect("Failed to create tmp repo");
+        let repo_storage =
+            RepoStorage::for_repo_path(tmp_repo.repo().path(), tmp_repo.repo().workdir().unwrap());
+        let working_log = repo_storage.working_log_for_base_commit("test-commit-sha");
+        assert_eq!(working_log.checkpoints().unwrap().count(), 0);
+
+        let lines: Vec<String> = ["first", "second"]
+            .iter()
+            .map(|author| {
+                serde_json::to_string(&Checkpoint::new(
+                    CheckpointKind::Human,
+                    String::new(),
+                    author.to_string(),
+                    vec![],
+                ))
+                .unwrap()
+            })
+            .collect();
+        let checkpoints_file = working_log.dir.join("checkpoints.jsonl");
+        fs::write(
+            &checkpoints_file,
+            format!("{}\n\n{}\n{{oops\n", lines[0], lines[1]),
+        )
+        .unwrap();
+
+        let mut checkpoints = working_log.checkpoints().unwrap();
+        assert_eq!(checkpoints.next().unwrap().unwrap().author, "first");
+        assert_eq!(checkpoints.next().unwrap().unwrap().author, "second");
+        match checkpoints.next() {
+            Some(Err(GitAiError::CorruptWorkingLog(message))) => {
+                assert!(
+                    message.contains("checkpoints.jsonl line 4: "),
+                    "{}",
+                    message
+                )
+            }
+            other => panic!(
+                "expected a corrupt line, got {:?}",
+                other.map(|c| c.is_ok())
+            ),
+        }
+        assert!(working_log.read_all_checkpoints().is_err());
+    }
+
     #[test]
     fn test_persisted_working_log_reset() {
         use crate::authorship::working_log::CheckpointKind;
@@ -852,6 +852,9 @@ impl<'a> Iterator for References<'a> {
 pub struct Repository {
     global_args: Vec<String>,
     git_dir: PathBuf,
+    /// Directory shared by all worktrees (objects, refs, config); equals `git_dir`
+    /// outside of linked worktrees
+    common_dir: PathBuf,
     pub storage: RepoStorage,
     pub pre_command_base_commit: Option<String>,
     pub pre_command_refname: Option<String>,
@@ -962,6 +965,11 @@ impl Repository {
         self.git_dir.as_path()
     }
 
+    // Returns the git directory shared by all worktrees of this repository.
+    pub fn common_dir(&self) -> &Path {
+        self.common_dir.as_path()
+    }
+
     // Get the path of the working directory for this repository.
     // If this repository is bare, then None is returned.
     pub fn workdir(&self) -> Result<PathBuf, GitAiError> {
@@ -1039,7 +1047,8 @@ impl Repository {
 
     /// Get the git config file for this repository and fallback to global config if not found.
     fn get_git_config_file(&self) -> Result<gix_config::File<'static>, GitAiError> {
-        match gix_config::File::from_git_dir(self.path().to_path_buf()) {
+        // Linked worktrees keep no config of their own; it lives in the common dir
+        match gix_config::File::from_git_dir(self.common_dir().to_path_buf()) {
             Ok(git_config_file) => Ok(git_config_file),
             Err(e) => match gix_config::File::from_globals() {
                 Ok(system_config) => Ok(system_config),
@@ -1586,6 +1595,7 @@ impl Repository {
     }
 
     // Lookup a reference to one of the objects in a repository.
+    #[allow(dead_code)]
     pub fn find_tree(&self, oid: String) -> Result<Tree<'_>, GitAiError> {
         let typ = self.object_type(&oid)?;
         if typ != "tree" {
@@ -1612,51 +1622,88 @@ impl Repository {
         Ok(output.stdout)
     }
 
-    /// Get content of all staged files concurrently
+    /// Get content of all staged files
     /// Returns a HashMap of file paths to their staged content as strings
     /// Skips files that fail to read or aren't valid UTF-8
     pub fn get_all_staged_files_content(
         &self,
         file_paths: &[String],
     ) -> Result<HashMap<String, String>, GitAiError> {
-        use futures::future::join_all;
-        use std::sync::Arc;
-
-        const MAX_CONCURRENT: usize = 30;
-
-        let repo_global_args = self.global_args_for_exec();
-        let semaphore = Arc::new(smol::lock::Semaphore::new(MAX_CONCURRENT));
+        // An empty treeish names the index
+        Ok(self
+            .blobs_at_paths("", file_paths)?
+            .into_iter()
+            .filter_map(|(path, content)| Some((path, String::from_utf8(content).ok()?)))
+            .collect())
+    }
+
+    /// Contents of the blobs at `paths` in `treeish` (a commit or tree, or "" for the
+    /// index), read by a single `git cat-file --batch` rather than a process per file.
+    /// Paths that don't exist in `treeish` or aren't blobs are left out.
+    pub fn blobs_at_paths(
+        &self,
+        treeish: &str,
+        paths: &[String],
+    ) -> Result<HashMap<String, Vec<u8>>, GitAiError> {
+        Ok(self
+            .blob_ids_and_contents_at_paths(treeish, paths)?
+            .into_iter()
+            .map(|(path, (_, content))| (path, content))
+            .collect())
+    }
+
+    /// Like [`Repository::blobs_at_paths`], with the object id of each blob
+    pub fn blob_ids_and_contents_at_paths(
+        &self,
+        treeish: &str,
+        paths: &[String],
+    ) -> Result<HashMap<String, (String, Vec<u8>)>, GitAiError> {
+        // cat-file reads one object name per line
+        let paths: Vec<&String> = paths.iter().filter(|path| !path.contains('\n')).collect();
+        if paths.is_empty() {
+            return Ok(HashMap::new());
+        }
 
-        let futures: Vec<_> = file_paths
+        let mut args = self.global_args_for_exec();
+        args.push("cat-file".to_string());
+        args.push("--batch=%(objectname) %(objecttype) %(objectsize)".to_string());
+        let stdin: String = paths
             .iter()
-            .map(|file_path| {
-                let mut args = repo_global_args.clone();
-                args.push("show".to_string());
-                args.push(format!(":{}", file_path));
-                let file_path = file_path.clone();
-                let semaphore = semaphore.clone();
-
-                async move {
-                    let _permit = semaphore.acquire().await;
-                    let result = exec_git(&args).and_then(|output| {
-                        String::from_utf8(output.stdout)
-                            .map_err(|e| GitAiError::Utf8Error(e.utf8_error()))
-                    });
-                    (file_path, result)
-                }
-            })
+            .map(|path| format!("{}:{}\n", treeish, path))
             .collect();
+        let output = exec_git_stdin(&args, stdin.as_bytes())?;
+
+        // Every object name gets a header, in order: "<oid> <type> <size>" followed by
+        // the content and a newline, or "<name> missing" (or "ambiguous") with no content
+        let data = output.stdout;
+        let mut blobs = HashMap::new();
+        let mut pos = 0;
+        for path in paths {
+            let Some(header_len) = data[pos..].iter().position(|&b| b == b'\n') else {
+                break;
+            };
+            let header = String::from_utf8_lossy(&data[pos..pos + header_len]).to_string();
+            pos += header_len + 1;
 
-        let results = smol::block_on(async { join_all(futures).await });
-
-        let mut staged_files = HashMap::new();
-        for (file_path, result) in results {
-            if let Ok(content) = result {
-                staged_files.insert(file_path, content);
+            let mut fields = header.splitn(3, ' ');
+            let (Some(oid), Some(object_type), Some(size)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let Ok(size) = size.parse::<usize>() else {
+                continue;
+            };
+            let content_end = (pos + size).min(data.len());
+            if object_type == "blob" {
+                blobs.insert(
+                    path.clone(),
+                    (oid.to_string(), data[pos..content_end].to_vec()),
+                );
             }
+            pos = content_end + 1;
         }
-
-        Ok(staged_files)
+        Ok(blobs)
     }
 
     /// List all files changed in a commit
@@ -1865,7 +1912,12 @@ pub fn find_repository(global_args: &[String]) -> Result<Repository, GitAiError>
     args.push("--git-dir".to_string());
     args.push("--show-toplevel".to_string());
 
-    let output = exec_git(&args)?;
+    let output = exec_git(&args).map_err(|e| match e {
+        GitAiError::GitCliError { ref stderr, .. } if stderr.contains("not a git repository") => {
+            GitAiError::NotARepository(stderr.trim().to_string())
+        }
+        e => e,
+    })?;
     let both_dirs = String::from_utf8(output.stdout)?;
 
     let both_dirs = both_dirs.trim();
@@ -1925,10 +1977,13 @@ pub fn find_repository(global_args: &[String]) -> Result<Repository, GitAiError>
         ))
     })?;
 
+    let common_dir = resolve_common_dir(&git_dir);
+
     Ok(Repository {
         global_args,
-        storage: RepoStorage::for_repo_path(&git_dir, &workdir),
+        storage: RepoStorage::for_worktree(&git_dir, &common_dir, &workdir),
         git_dir,
+        common_dir,
         pre_command_base_commit: None,
         pre_command_refname: None,
         pre_reset_target_commit: None,
@@ -1937,7 +1992,6 @@ pub fn find_repository(global_args: &[String]) -> Result<Repository, GitAiError>
     })
 }
 
-#[allow(dead_code)]
 pub fn from_bare_repository(git_dir: &Path) -> Result<Repository, GitAiError> {
     let workdir = git_dir
         .parent()
@@ -1951,6 +2005,7 @@ pub fn from_bare_repository(git_dir: &Path) -> Result<Repository, GitAiError> {
         global_args,
         storage: RepoStorage::for_repo_path(git_dir, &workdir),
         git_dir: git_dir.to_path_buf(),
+        common_dir: resolve_common_dir(git_dir),
         pre_command_base_commit: None,
         pre_command_refname: None,
         pre_reset_target_commit: None,
@@ -1959,6 +2014,47 @@ pub fn from_bare_repository(git_dir: &Path) -> Result<Repository, GitAiError> {
     })
 }
 
+/// Resolve the common git directory for `git_dir`.
+///
+/// A linked worktree's git dir (`<common>/worktrees/<name>`) contains a `commondir`
+/// file pointing back at the shared directory, either absolute or relative to itself.
+pub fn resolve_common_dir(git_dir: &Path) -> PathBuf {
+    let Ok(content) = std::fs::read_to_string(git_dir.join("commondir")) else {
+        return git_dir.to_path_buf();
+    };
+    let common = PathBuf::from(content.trim());
+    let common = if common.is_relative() {
+        git_dir.join(common)
+    } else {
+        common
+    };
+    common.canonicalize().unwrap_or(common)
+}
+
+/// Classify the target of a `.git` file (`gitdir: <path>`).
+///
+/// Returns true for submodules (`<super>/.git/modules/<name>`), false for linked
+/// worktrees (`<common>/worktrees/<name>`) and anything else.
+fn gitdir_file_is_submodule(content: &str) -> bool {
+    let Some(target) = content
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("gitdir:"))
+    else {
+        return false;
+    };
+    let target = Path::new(target.trim());
+    let parent_name = target
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|n| n.to_str());
+    if parent_name == Some("worktrees") {
+        return false;
+    }
+    target
+        .components()
+        .any(|c| c.as_os_str() == std::ffi::OsStr::new("modules"))
+}
+
 pub fn find_repository_in_path(path: &str) -> Result<Repository, GitAiError> {
     let global_args = vec!["-C".to_string(), path.to_string()];
     find_repository(&global_args)
@@ -2020,17 +2116,15 @@ pub fn find_repository_for_file(
         let git_path = dir.join(".git");
         if git_path.exists() {
             // Found a .git - but we need to check if this is a submodule
-            // Submodules have a .git file (not directory) that points to the parent's .git/modules
-            if git_path.is_file() {
-                // This is a submodule - read the file to check if it points to modules/
-                if let Ok(content) = std::fs::read_to_string(&git_path)
-                    && content.contains("gitdir:")
-                    && content.contains("/modules/")
-                {
-                    // This is a submodule, skip it and continue searching up
-                    current_dir = dir.parent();
-                    continue;
-                }
+            // Submodules have a .git file (not directory) that points to the parent's .git/modules,
+            // while linked worktrees point at <common>/worktrees/<name> and are real checkouts
+            if git_path.is_file()
+                && let Ok(content) = std::fs::read_to_string(&git_path)
+                && gitdir_file_is_submodule(&content)
+            {
+                // This is a submodule, skip it and continue searching up
+                current_dir = dir.parent();
+                continue;
             }
 
             // Found a real git repository, use find_repository_in_path
@@ -2121,6 +2215,95 @@ pub fn exec_git(args: &[String]) -> Result<Output, GitAiError> {
     Ok(output)
 }
 
+/// Run a git command and read its output one line at a time, for output that may not
+/// fit in memory (e.g. the log of a long history). A failing command ends the lines
+/// with its error.
+pub fn exec_git_lines(args: &[String]) -> Result<GitLines, GitAiError> {
+    let mut cmd = Command::new(config::Config::get().git_cmd());
+    cmd.args(args)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped());
+
+    #[cfg(windows)]
+    {
+        if !is_interactive_terminal() {
+            cmd.creation_flags(CREATE_NO_WINDOW);
+        }
+    }
+
+    let mut child = cmd.spawn().map_err(GitAiError::IoError)?;
+    let stdout = child.stdout.take().map(std::io::BufReader::new);
+    Ok(GitLines {
+        child: Some(child),
+        stdout,
+        args: args.to_vec(),
+    })
+}
+
+pub struct GitLines {
+    child: Option<std::process::Child>,
+    stdout: Option<std::io::BufReader<std::process::ChildStdout>>,
+    args: Vec<String>,
+}
+
+impl GitLines {
+    /// Wait for git once its output is read; its error if it failed
+    fn finish(&mut self) -> Option<GitAiError> {
+        let child = self.child.take()?;
+        let output = match child.wait_with_output() {
+            Ok(output) => output,
+            Err(e) => return Some(GitAiError::IoError(e)),
+        };
+        if output.status.success() {
+            return None;
+        }
+        Some(GitAiError::GitCliError {
+            code: output.status.code(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+            args: self.args.clone(),
+        })
+    }
+}
+
+impl Iterator for GitLines {
+    type Item = Result<String, GitAiError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use std::io::BufRead;
+
+        if let Some(stdout) = self.stdout.as_mut() {
+            let mut line = String::new();
+            match stdout.read_line(&mut line) {
+                Ok(0) => self.stdout = None,
+                Ok(_) => {
+                    let trimmed = line.trim_end_matches(['\n', '\r']).len();
+                    line.truncate(trimmed);
+                    return Some(Ok(line));
+                }
+                Err(e) => {
+                    self.stdout = None;
+                    if let Some(mut child) = self.child.take() {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                    }
+                    return Some(Err(GitAiError::IoError(e)));
+                }
+            }
+        }
+        self.finish().map(Err)
+    }
+}
+
+impl Drop for GitLines {
+    fn drop(&mut self) {
+        // Stopping early: git would block on a full pipe otherwise
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
 /// Helper to execute a git command with data provided on stdin
 pub fn exec_git_stdin(args: &[String], stdin_data: &[u8]) -> Result<Output, GitAiError> {
     // TODO Make sure to handle process signals, etc.
@@ -2442,6 +2625,61 @@ fn parse_hunk_header(line: &str) -> Option<(Vec<u32>, bool)> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::git::test_utils::TmpRepo;
+
+    #[test]
+    fn test_blobs_at_paths_reads_in_one_batch() {
+        let tmp_repo = TmpRepo::new().unwrap();
+        let repo = tmp_repo.gitai_repo();
+        std::fs::create_dir_all(tmp_repo.path().join("src")).unwrap();
+        std::fs::write(tmp_repo.path().join("src/a b.txt"), "one\ntwo\n").unwrap();
+        std::fs::write(tmp_repo.path().join("bin"), [0u8, 1, 2]).unwrap();
+        repo.git(&["add", "."]).unwrap();
+        repo.git(&["commit", "-m", "Initial"]).unwrap();
+        std::fs::write(tmp_repo.path().join("bin"), "staged").unwrap();
+        repo.git(&["add", "bin"]).unwrap();
+
+        let paths = ["src/a b.txt", "missing", "src", "bin"].map(String::from);
+        let blobs = repo.blobs_at_paths("HEAD", &paths).unwrap();
+        assert_eq!(blobs.len(), 2);
+        assert_eq!(blobs["src/a b.txt"], b"one\ntwo\n");
+        assert_eq!(blobs["bin"], [0u8, 1, 2]);
+
+        let staged = repo.get_all_staged_files_content(&paths).unwrap();
+        assert_eq!(staged.get("bin").map(String::as_str), Some("staged"));
+        assert!(!staged.contains_key("missing"));
+    }
+
+    #[test]
+    fn test_gitdir_file_is_submodule() {
+        assert!(gitdir_file_is_submodule(
+            "gitdir: ../.git/modules/vendor/lib\n"
+        ));
+        assert!(!gitdir_file_is_submodule(
+            "gitdir: /work/app/.git/worktrees/feature\n"
+        ));
+        // A worktree whose repository happens to live under a `modules` directory
+        assert!(!gitdir_file_is_submodule(
+            "gitdir: /src/modules/app/.git/worktrees/feature\n"
+        ));
+        assert!(!gitdir_file_is_submodule("not a gitdir file"));
+    }
+
+    #[test]
+    fn test_resolve_common_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let common = tmp.path().join(".git");
+        let worktree_git_dir = common.join("worktrees").join("feature");
+        std::fs::create_dir_all(&worktree_git_dir).unwrap();
+
+        assert_eq!(resolve_common_dir(&common), common);
+
+        std::fs::write(worktree_git_dir.join("commondir"), "../..\n").unwrap();
+        assert_eq!(
+            resolve_common_dir(&worktree_git_dir),
+            common.canonicalize().unwrap()
+        );
+    }
 
     #[test]
     fn test_parse_git_version_standard() {
@@ -0,0 +1,125 @@
+//! Stat-based change detection for checkpoints, the way git's index avoids rehashing.
+//!
+//! After a checkpoint, the mtime, size and inode of each file it read are kept next to
+//! the working log with the hash of the content that was read. The next checkpoint
+//! skips a checkpointed file whose stat data still matches and whose latest entry is
+//! still that content, so its cost follows the files that changed rather than every
+//! file checkpointed so far.
+//!
+//! As with git's "racily clean" entries, a file modified in the same second the stat
+//! data was taken (or later) could change again without its mtime moving, and is
+//! always read.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const STAT_CACHE_FILE: &str = "file_stats.json";
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatCache {
+    /// When the files were about to be read, in nanoseconds since the epoch
+    pub taken_ns: u128,
+    pub files: HashMap<String, FileStat>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileStat {
+    pub mtime_ns: u128,
+    pub size: u64,
+    pub ino: u64,
+    /// Content hash of the snapshot read along with this stat data
+    pub blob_sha: String,
+}
+
+impl FileStat {
+    pub fn of(path: &Path, blob_sha: &str) -> Option<FileStat> {
+        let metadata = std::fs::metadata(path).ok()?;
+        if !metadata.is_file() {
+            return None;
+        }
+        #[cfg(unix)]
+        let ino = std::os::unix::fs::MetadataExt::ino(&metadata);
+        #[cfg(not(unix))]
+        let ino = 0;
+        Some(FileStat {
+            mtime_ns: metadata
+                .modified()
+                .ok()?
+                .duration_since(UNIX_EPOCH)
+                .ok()?
+                .as_nanos(),
+            size: metadata.len(),
+            ino,
+            blob_sha: blob_sha.to_string(),
+        })
+    }
+
+    fn same_stat(&self, other: &FileStat) -> bool {
+        self.mtime_ns == other.mtime_ns && self.size == other.size && self.ino == other.ino
+    }
+}
+
+impl StatCache {
+    /// An empty cache for files about to be read
+    pub fn start() -> Self {
+        StatCache {
+            taken_ns: now_ns(),
+            files: HashMap::new(),
+        }
+    }
+
+    /// Whether the file at `path` (stored at `absolute_path`) is known to still be
+    /// `latest_blob_sha`, the content of its latest checkpoint entry
+    pub fn unchanged(&self, path: &str, absolute_path: &Path, latest_blob_sha: &str) -> bool {
+        let Some(cached) = self.files.get(path) else {
+            return false;
+        };
+        if cached.blob_sha != latest_blob_sha || self.is_racy(cached) {
+            return false;
+        }
+        FileStat::of(absolute_path, latest_blob_sha).is_some_and(|stat| stat.same_stat(cached))
+    }
+
+    /// Modified within the second the stat data was taken, at a granularity any
+    /// filesystem's mtime can tell apart
+    fn is_racy(&self, stat: &FileStat) -> bool {
+        const SECOND_NS: u128 = 1_000_000_000;
+        stat.mtime_ns / SECOND_NS >= self.taken_ns / SECOND_NS
+    }
+}
+
+fn now_ns() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unchanged_requires_matching_stat_and_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("a.txt");
+        std::fs::write(&path, "one\n").unwrap();
+
+        let mut cache = StatCache::start();
+        let stat = FileStat::of(&path, "sha1").unwrap();
+        cache.files.insert("a.txt".to_string(), stat.clone());
+        // Written in the second the stat data was taken: not trusted
+        assert!(!cache.unchanged("a.txt", &path, "sha1"));
+
+        cache.taken_ns = stat.mtime_ns + 2_000_000_000;
+        assert!(cache.unchanged("a.txt", &path, "sha1"));
+        // Another checkpoint recorded different content since
+        assert!(!cache.unchanged("a.txt", &path, "sha2"));
+        assert!(!cache.unchanged("b.txt", &path, "sha1"));
+
+        std::fs::write(&path, "one\ntwo\n").unwrap();
+        assert!(!cache.unchanged("a.txt", &path, "sha1"));
+    }
+}
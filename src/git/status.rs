@@ -82,6 +82,35 @@ impl Repository {
         Ok(filenames)
     }
 
+    // Staged, modified, deleted and untracked files, with untracked directories expanded
+    pub fn get_worktree_filenames(&self) -> Result<HashSet<String>, GitAiError> {
+        let mut args = self.global_args_for_exec();
+        args.push("ls-files".to_string());
+        args.push("--modified".to_string());
+        args.push("--others".to_string());
+        args.push("--exclude-standard".to_string());
+        args.push("-z".to_string());
+
+        let output = exec_git(&args)?;
+
+        if !output.status.success() {
+            return Err(GitAiError::Generic(format!(
+                "git ls-files exited with status {}",
+                output.status
+            )));
+        }
+
+        let mut filenames = self.get_staged_filenames()?;
+        filenames.extend(
+            output
+                .stdout
+                .split(|&b| b == 0)
+                .filter(|bytes| !bytes.is_empty())
+                .filter_map(|bytes| String::from_utf8(bytes.to_vec()).ok()),
+        );
+        Ok(filenames)
+    }
+
     // Get status for tracked files that changed
     pub fn get_staged_and_unstaged_filenames(&self) -> Result<HashSet<String>, GitAiError> {
         let mut args = self.global_args_for_exec();
@@ -0,0 +1,331 @@
+//! Translations of user-facing messages.
+//!
+//! `GIT_AI_LANG` picks the language by its code (`fr`, or a locale such as
+//! `fr_FR.UTF-8`, of which only the language counts). Messages are looked up by a
+//! stable key and fall back to English when the language has no translation for them,
+//! so the catalog can grow one message at a time. `{name}` placeholders are filled in
+//! by [`tr_args`].
+//!
+//! Only what people read is translated: JSON output, log files and error codes stay
+//! the same whatever the language, so scripts don't depend on it.
+
+use std::sync::OnceLock;
+
+pub const LANG_ENV: &str = "GIT_AI_LANG";
+
+struct Message {
+    key: &'static str,
+    en: &'static str,
+    translations: &'static [(&'static str, &'static str)],
+}
+
+const MESSAGES: &[Message] = &[
+    Message {
+        key: "error",
+        en: "Error: {error}",
+        translations: &[
+            ("de", "Fehler: {error}"),
+            ("es", "Error: {error}"),
+            ("fr", "Erreur : {error}"),
+        ],
+    },
+    Message {
+        key: "status.no_checkpoints",
+        en: "No checkpoints recorded since last commit ({sha})",
+        translations: &[
+            (
+                "de",
+                "Seit dem letzten Commit ({sha}) wurden keine Checkpoints erfasst",
+            ),
+            (
+                "es",
+                "No se han registrado checkpoints desde el último commit ({sha})",
+            ),
+            (
+                "fr",
+                "Aucun checkpoint enregistré depuis le dernier commit ({sha})",
+            ),
+        ],
+    },
+    Message {
+        key: "status.install_hooks_hint",
+        en: "If you've made AI edits recently and don't see them here, you might need to install hooks:",
+        translations: &[
+            (
+                "de",
+                "Wenn Sie kürzlich KI-Änderungen vorgenommen haben und sie hier nicht sehen, müssen Sie eventuell die Hooks installieren:",
+            ),
+            (
+                "es",
+                "Si has hecho cambios con IA recientemente y no aparecen aquí, puede que necesites instalar los hooks:",
+            ),
+            (
+                "fr",
+                "Si vous avez fait des modifications par IA récemment et ne les voyez pas ici, il faut peut-être installer les hooks :",
+            ),
+        ],
+    },
+    Message {
+        key: "hook.no_author",
+        en: "No author information found. Using 'unknown' as author.",
+        translations: &[
+            (
+                "de",
+                "Keine Autoreninformationen gefunden. 'unknown' wird als Autor verwendet.",
+            ),
+            (
+                "es",
+                "No se encontró información del autor. Se usará 'unknown' como autor.",
+            ),
+            (
+                "fr",
+                "Aucune information d'auteur trouvée. 'unknown' est utilisé comme auteur.",
+            ),
+        ],
+    },
+    Message {
+        key: "hook.no_user_name",
+        en: "git user.name not configured. Using 'unknown' as author.",
+        translations: &[
+            (
+                "de",
+                "git user.name ist nicht konfiguriert. 'unknown' wird als Autor verwendet.",
+            ),
+            (
+                "es",
+                "git user.name no está configurado. Se usará 'unknown' como autor.",
+            ),
+            (
+                "fr",
+                "git user.name n'est pas configuré. 'unknown' est utilisé comme auteur.",
+            ),
+        ],
+    },
+    Message {
+        key: "hook.bare_repository",
+        en: "Cannot run checkpoint on bare repositories (skipping git-ai pre-commit hook)",
+        translations: &[
+            (
+                "de",
+                "Checkpoints sind in Bare-Repositories nicht möglich (git-ai-pre-commit-Hook übersprungen)",
+            ),
+            (
+                "es",
+                "No se puede crear un checkpoint en repositorios bare (se omite el hook pre-commit de git-ai)",
+            ),
+            (
+                "fr",
+                "Impossible de faire un checkpoint dans un dépôt nu (hook pre-commit de git-ai ignoré)",
+            ),
+        ],
+    },
+    Message {
+        key: "hook.pre_commit_failed",
+        en: "Pre-commit failed: {error}",
+        translations: &[
+            ("de", "Pre-commit fehlgeschlagen: {error}"),
+            ("es", "Falló el pre-commit: {error}"),
+            ("fr", "Échec du pre-commit : {error}"),
+        ],
+    },
+    Message {
+        key: "hook.shims_updated",
+        en: "git-ai: updated {count} git hook(s) installed by git-ai {old} for {new}",
+        translations: &[
+            (
+                "de",
+                "git-ai: {count} von git-ai {old} installierte(r) Git-Hook(s) für {new} aktualisiert",
+            ),
+            (
+                "es",
+                "git-ai: se actualizaron {count} hook(s) de git instalados por git-ai {old} para {new}",
+            ),
+            (
+                "fr",
+                "git-ai : {count} hook(s) git installé(s) par git-ai {old} mis à jour pour {new}",
+            ),
+        ],
+    },
+    Message {
+        key: "hook.shims_outdated",
+        en: "git-ai: git hooks were installed by git-ai {old} but {new} is running; run `git-ai install-hooks` to update them",
+        translations: &[
+            (
+                "de",
+                "git-ai: Die Git-Hooks wurden von git-ai {old} installiert, es läuft aber {new}; führen Sie `git-ai install-hooks` aus, um sie zu aktualisieren",
+            ),
+            (
+                "es",
+                "git-ai: los hooks de git los instaló git-ai {old} pero se está ejecutando {new}; ejecuta `git-ai install-hooks` para actualizarlos",
+            ),
+            (
+                "fr",
+                "git-ai : les hooks git ont été installés par git-ai {old} mais {new} est en cours d'exécution ; lancez `git-ai install-hooks` pour les mettre à jour",
+            ),
+        ],
+    },
+    Message {
+        key: "push.blocked",
+        en: "git-ai: push blocked by policy",
+        translations: &[
+            ("de", "git-ai: Push durch Richtlinie blockiert"),
+            ("es", "git-ai: push bloqueado por la política"),
+            ("fr", "git-ai : push bloqué par la politique"),
+        ],
+    },
+    Message {
+        key: "push.blocked_hint",
+        en: "To push anyway, re-run with `git push --no-verify`.",
+        translations: &[
+            (
+                "de",
+                "Um trotzdem zu pushen, führen Sie `git push --no-verify` aus.",
+            ),
+            (
+                "es",
+                "Para hacer push de todos modos, vuelve a ejecutar con `git push --no-verify`.",
+            ),
+            (
+                "fr",
+                "Pour pousser quand même, relancez avec `git push --no-verify`.",
+            ),
+        ],
+    },
+    Message {
+        key: "push.rejected",
+        en: "git-ai: push rejected by server policy",
+        translations: &[
+            ("de", "git-ai: Push durch Serverrichtlinie abgelehnt"),
+            ("es", "git-ai: push rechazado por la política del servidor"),
+            ("fr", "git-ai : push refusé par la politique du serveur"),
+        ],
+    },
+    Message {
+        key: "push.rejected_hint",
+        en: "Fix the listed commits (e.g. `git commit --amend` or `git rebase -i`) and push again.",
+        translations: &[
+            (
+                "de",
+                "Korrigieren Sie die aufgeführten Commits (z. B. mit `git commit --amend` oder `git rebase -i`) und pushen Sie erneut.",
+            ),
+            (
+                "es",
+                "Corrige los commits indicados (p. ej. con `git commit --amend` o `git rebase -i`) y vuelve a hacer push.",
+            ),
+            (
+                "fr",
+                "Corrigez les commits listés (par ex. avec `git commit --amend` ou `git rebase -i`) puis poussez à nouveau.",
+            ),
+        ],
+    },
+    Message {
+        key: "push.check_failed",
+        en: "git-ai: push rejected, policy check failed: {error}",
+        translations: &[
+            (
+                "de",
+                "git-ai: Push abgelehnt, Richtlinienprüfung fehlgeschlagen: {error}",
+            ),
+            (
+                "es",
+                "git-ai: push rechazado, falló la comprobación de la política: {error}",
+            ),
+            (
+                "fr",
+                "git-ai : push refusé, la vérification de la politique a échoué : {error}",
+            ),
+        ],
+    },
+];
+
+/// The language messages are shown in, e.g. `fr`
+pub fn lang() -> &'static str {
+    static LANG: OnceLock<String> = OnceLock::new();
+    LANG.get_or_init(|| parse_lang(&std::env::var(LANG_ENV).unwrap_or_default()))
+}
+
+fn parse_lang(value: &str) -> String {
+    value
+        .split(['_', '-', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase()
+}
+
+/// The message `key` in the current language
+pub fn tr(key: &str) -> String {
+    tr_args(key, &[])
+}
+
+/// The message `key` in the current language, with its `{name}` placeholders filled in
+pub fn tr_args(key: &str, args: &[(&str, &str)]) -> String {
+    translate(lang(), key, args)
+}
+
+fn translate(lang: &str, key: &str, args: &[(&str, &str)]) -> String {
+    let Some(message) = MESSAGES.iter().find(|message| message.key == key) else {
+        // A missing key is a bug; showing it beats showing nothing
+        return key.to_string();
+    };
+    let template = message
+        .translations
+        .iter()
+        .find(|(code, _)| *code == lang)
+        .map_or(message.en, |(_, text)| text);
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::BTreeSet;
+
+    fn placeholders(text: &str) -> BTreeSet<&str> {
+        text.split('{')
+            .skip(1)
+            .filter_map(|part| part.split_once('}').map(|(name, _)| name))
+            .collect()
+    }
+
+    #[test]
+    fn test_translations_keep_placeholders() {
+        let mut keys = BTreeSet::new();
+        for message in MESSAGES {
+            assert!(keys.insert(message.key), "duplicate key {}", message.key);
+            for (code, text) in message.translations {
+                assert_eq!(
+                    placeholders(text),
+                    placeholders(message.en),
+                    "{} in {}",
+                    message.key,
+                    code
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_translate() {
+        assert_eq!(
+            translate("fr", "hook.pre_commit_failed", &[("error", "oops")]),
+            "Échec du pre-commit : oops"
+        );
+        assert_eq!(
+            translate("pt", "hook.pre_commit_failed", &[("error", "oops")]),
+            "Pre-commit failed: oops"
+        );
+        assert_eq!(translate("fr", "no.such.key", &[]), "no.such.key");
+    }
+
+    #[test]
+    fn test_parse_lang() {
+        assert_eq!(parse_lang("fr_FR.UTF-8"), "fr");
+        assert_eq!(parse_lang("DE"), "de");
+        assert_eq!(parse_lang("es-419"), "es");
+        assert_eq!(parse_lang(""), "");
+    }
+}
@@ -7,6 +7,8 @@ pub mod config;
 pub mod error;
 pub mod feature_flags;
 pub mod git;
+pub mod i18n;
+pub mod logging;
 pub mod mdm;
 pub mod metrics;
 pub mod observability;
@@ -0,0 +1,194 @@
+//! Leveled diagnostics for commands and hooks.
+//!
+//! The level is `--log-level <level>` for a `git-ai` command, otherwise `GIT_AI_LOG`
+//! (`off`, `error`, `warn`, `info`, `debug` or `trace`). Without either it is `debug`
+//! in debug builds or with `GIT_AI_DEBUG=1`, and `warn` otherwise, so `debug_log`
+//! behaves as it always has.
+//!
+//! Hooks run where nobody sees their stderr. With `GIT_AI_LOG_FILE=<path>`, every
+//! record at the selected level is also appended to that file, with a timestamp, the
+//! process id and the command or hook that wrote it. Hooks inherit the environment
+//! from git, so `GIT_AI_LOG=debug GIT_AI_LOG_FILE=/tmp/git-ai.log git commit` captures
+//! what the hooks of that commit did.
+
+use chrono::{SecondsFormat, Utc};
+use std::io::Write;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+pub const LOG_ENV: &str = "GIT_AI_LOG";
+pub const LOG_FILE_ENV: &str = "GIT_AI_LOG_FILE";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Off = 0,
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Trace = 5,
+}
+
+impl Level {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "off" => Some(Level::Off),
+            "error" => Some(Level::Error),
+            "warn" | "warning" => Some(Level::Warn),
+            "info" => Some(Level::Info),
+            "debug" => Some(Level::Debug),
+            "trace" => Some(Level::Trace),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Level::Off => "OFF",
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+
+    fn from_u8(value: u8) -> Self {
+        match value {
+            1 => Level::Error,
+            2 => Level::Warn,
+            3 => Level::Info,
+            4 => Level::Debug,
+            5 => Level::Trace,
+            _ => Level::Off,
+        }
+    }
+}
+
+/// The level as one more than its value, or 0 until it is first needed
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+static CONTEXT: RwLock<String> = RwLock::new(String::new());
+
+/// Use `level` for the rest of the process, over `GIT_AI_LOG`
+pub fn set_level(level: Level) {
+    LEVEL.store(level as u8 + 1, Ordering::Relaxed);
+}
+
+/// The current level
+pub fn level() -> Level {
+    match LEVEL.load(Ordering::Relaxed) {
+        0 => {
+            let level = std::env::var(LOG_ENV)
+                .ok()
+                .and_then(|name| Level::parse(&name))
+                .unwrap_or_else(default_level);
+            set_level(level);
+            level
+        }
+        stored => Level::from_u8(stored - 1),
+    }
+}
+
+fn default_level() -> Level {
+    let debug = std::env::var("GIT_AI_DEBUG").unwrap_or_default();
+    let performance = std::env::var("GIT_AI_DEBUG_PERFORMANCE").unwrap_or_default();
+    if (cfg!(debug_assertions) || debug == "1" || !performance.is_empty()) && debug != "0" {
+        Level::Debug
+    } else {
+        Level::Warn
+    }
+}
+
+/// Name the command or hook this process runs, for the records of the log file
+pub fn set_context(context: &str) {
+    if let Ok(mut current) = CONTEXT.write() {
+        *current = context.to_string();
+    }
+}
+
+pub fn enabled(level: Level) -> bool {
+    level != Level::Off && level <= self::level()
+}
+
+pub fn log(level: Level, msg: &str) {
+    if !enabled(level) {
+        return;
+    }
+    match level {
+        Level::Warn => eprintln!("Warning: {}", msg),
+        Level::Debug | Level::Trace => eprintln!("\x1b[1;33m[git-ai]\x1b[0m {}", msg),
+        _ => eprintln!("{}", msg),
+    }
+    if let Ok(path) = std::env::var(LOG_FILE_ENV)
+        && !path.is_empty()
+    {
+        append_to_file(&path, &format_record(level, msg));
+    }
+}
+
+pub fn error(msg: &str) {
+    log(Level::Error, msg);
+}
+
+pub fn warn(msg: &str) {
+    log(Level::Warn, msg);
+}
+
+pub fn info(msg: &str) {
+    log(Level::Info, msg);
+}
+
+fn format_record(level: Level, msg: &str) -> String {
+    let context = CONTEXT.read().map(|c| c.clone()).unwrap_or_default();
+    format!(
+        "{} [{}] {} {}: {}\n",
+        Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+        std::process::id(),
+        level.as_str(),
+        if context.is_empty() {
+            "git-ai"
+        } else {
+            &context
+        },
+        msg
+    )
+}
+
+fn append_to_file(path: &str, record: &str) {
+    // Losing a record is better than failing the command that logged it
+    if let Ok(mut file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+    {
+        let _ = file.write_all(record.as_bytes());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_levels() {
+        assert_eq!(Level::parse("DEBUG"), Some(Level::Debug));
+        assert_eq!(Level::parse("warning"), Some(Level::Warn));
+        assert_eq!(Level::parse("off"), Some(Level::Off));
+        assert_eq!(Level::parse("verbose"), None);
+        assert!(Level::Error < Level::Warn && Level::Debug < Level::Trace);
+    }
+
+    #[test]
+    fn test_format_record() {
+        set_context("hook pre-push");
+        let record = format_record(Level::Info, "pushed 2 notes");
+        assert!(
+            record.ends_with(&format!(
+                " [{}] INFO hook pre-push: pushed 2 notes\n",
+                std::process::id()
+            )),
+            "{}",
+            record
+        );
+    }
+}
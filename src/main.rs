@@ -7,6 +7,8 @@ mod config;
 mod error;
 mod feature_flags;
 mod git;
+mod i18n;
+mod logging;
 mod mdm;
 mod metrics;
 mod observability;
@@ -0,0 +1,145 @@
+use crate::error::GitAiError;
+use crate::mdm::hook_installer::{HookCheckResult, HookInstaller, HookInstallerParams};
+use crate::mdm::utils::{binary_exists, generate_diff, home_dir, write_atomic};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Aider appends the edited file names when it runs its lint command
+const AIDER_LINT_CMD: &str = "checkpoint aider";
+const AIDER_CONFIG_COMMENT: &str = "# git-ai: checkpoint the files aider edits";
+
+pub struct AiderInstaller;
+
+impl AiderInstaller {
+    fn config_path() -> PathBuf {
+        home_dir().join(".aider.conf.yml")
+    }
+
+    fn is_lint_cmd_line(line: &str) -> bool {
+        line.starts_with("lint-cmd:")
+    }
+
+    fn is_git_ai_line(line: &str) -> bool {
+        line == AIDER_CONFIG_COMMENT
+            || (Self::is_lint_cmd_line(line)
+                && line.contains("git-ai")
+                && line.contains(AIDER_LINT_CMD))
+    }
+
+    fn lint_cmd_line(binary_path: &Path) -> String {
+        format!("lint-cmd: \"{} {}\"", binary_path.display(), AIDER_LINT_CMD)
+    }
+}
+
+impl HookInstaller for AiderInstaller {
+    fn name(&self) -> &str {
+        "Aider"
+    }
+
+    fn id(&self) -> &str {
+        "aider"
+    }
+
+    fn check_hooks(&self, _params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
+        let config_path = Self::config_path();
+        if !binary_exists("aider") && !config_path.exists() {
+            return Ok(HookCheckResult {
+                tool_installed: false,
+                hooks_installed: false,
+                hooks_up_to_date: false,
+            });
+        }
+
+        let content = fs::read_to_string(&config_path).unwrap_or_default();
+        let has_hooks = content.lines().any(Self::is_git_ai_line);
+        Ok(HookCheckResult {
+            tool_installed: true,
+            hooks_installed: has_hooks,
+            hooks_up_to_date: has_hooks,
+        })
+    }
+
+    fn install_hooks(
+        &self,
+        params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let config_path = Self::config_path();
+        let existing_content = fs::read_to_string(&config_path).unwrap_or_default();
+        let desired = Self::lint_cmd_line(&params.binary_path);
+
+        if existing_content.lines().any(|line| line == desired) {
+            return Ok(None);
+        }
+        // lint-cmd replaces aider's built-in linters, so never take over one the user set
+        if existing_content
+            .lines()
+            .any(|line| Self::is_lint_cmd_line(line) && !Self::is_git_ai_line(line))
+        {
+            return Err(GitAiError::Generic(format!(
+                "{} already sets lint-cmd; add `git-ai {}` to it to checkpoint aider's edits",
+                config_path.display(),
+                AIDER_LINT_CMD
+            )));
+        }
+
+        let mut lines: Vec<&str> = existing_content
+            .lines()
+            .filter(|line| !Self::is_git_ai_line(line))
+            .collect();
+        lines.push(AIDER_CONFIG_COMMENT);
+        lines.push(&desired);
+        let new_content = format!("{}\n", lines.join("\n"));
+
+        let diff_output = generate_diff(&config_path, &existing_content, &new_content);
+        if !dry_run {
+            write_atomic(&config_path, new_content.as_bytes())?;
+        }
+        Ok(Some(diff_output))
+    }
+
+    fn uninstall_hooks(
+        &self,
+        _params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let config_path = Self::config_path();
+        let Ok(existing_content) = fs::read_to_string(&config_path) else {
+            return Ok(None);
+        };
+        if !existing_content.lines().any(Self::is_git_ai_line) {
+            return Ok(None);
+        }
+
+        let lines: Vec<&str> = existing_content
+            .lines()
+            .filter(|line| !Self::is_git_ai_line(line))
+            .collect();
+        let new_content = if lines.is_empty() {
+            String::new()
+        } else {
+            format!("{}\n", lines.join("\n"))
+        };
+
+        let diff_output = generate_diff(&config_path, &existing_content, &new_content);
+        if !dry_run {
+            write_atomic(&config_path, new_content.as_bytes())?;
+        }
+        Ok(Some(diff_output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aider_config_lines() {
+        let line = AiderInstaller::lint_cmd_line(Path::new("/usr/local/bin/git-ai"));
+        assert_eq!(line, "lint-cmd: \"/usr/local/bin/git-ai checkpoint aider\"");
+        assert!(AiderInstaller::is_git_ai_line(&line));
+        assert!(AiderInstaller::is_git_ai_line(AIDER_CONFIG_COMMENT));
+        assert!(!AiderInstaller::is_git_ai_line("lint-cmd: \"ruff check\""));
+        assert!(!AiderInstaller::is_git_ai_line("model: gpt-4o"));
+    }
+}
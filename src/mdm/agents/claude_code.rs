@@ -11,6 +11,16 @@ use std::path::PathBuf;
 // Command patterns for hooks
 const CLAUDE_PRE_TOOL_CMD: &str = "checkpoint claude --hook-input stdin";
 const CLAUDE_POST_TOOL_CMD: &str = "checkpoint claude --hook-input stdin";
+// End of turn: re-checkpoints files the agent already touched (e.g. later edits through
+// its Bash tool) and picks up the final transcript
+const CLAUDE_STOP_CMD: &str = "checkpoint claude --hook-input stdin";
+
+/// Hook events we register, with the tool matcher for each (`Stop` takes none)
+const CLAUDE_HOOK_EVENTS: &[(&str, Option<&str>)] = &[
+    ("PreToolUse", Some("Write|Edit|MultiEdit")),
+    ("PostToolUse", Some("Write|Edit|MultiEdit")),
+    ("Stop", None),
+];
 
 pub struct ClaudeCodeInstaller;
 
@@ -121,29 +131,21 @@ impl HookInstaller for ClaudeCodeInstaller {
             serde_json::from_str(&existing_content)?
         };
 
-        // Desired hooks - Claude Code doesn't need absolute paths, uses shell properly
-        let pre_tool_cmd = format!("git-ai {}", CLAUDE_PRE_TOOL_CMD);
-        let post_tool_cmd = format!("git-ai {}", CLAUDE_POST_TOOL_CMD);
-
-        let desired_hooks = json!({
-            "PreToolUse": {
-                "matcher": "Write|Edit|MultiEdit",
-                "desired_cmd": pre_tool_cmd,
-            },
-            "PostToolUse": {
-                "matcher": "Write|Edit|MultiEdit",
-                "desired_cmd": post_tool_cmd,
-            }
-        });
-
         // Merge desired into existing
         let mut merged = existing.clone();
         let mut hooks_obj = merged.get("hooks").cloned().unwrap_or_else(|| json!({}));
 
-        // Process both PreToolUse and PostToolUse
-        for hook_type in &["PreToolUse", "PostToolUse"] {
-            let desired_matcher = desired_hooks[hook_type]["matcher"].as_str().unwrap();
-            let desired_cmd = desired_hooks[hook_type]["desired_cmd"].as_str().unwrap();
+        for (hook_type, desired_matcher) in CLAUDE_HOOK_EVENTS {
+            // Claude Code doesn't need absolute paths, uses shell properly
+            let desired_cmd = format!(
+                "git-ai {}",
+                match *hook_type {
+                    "PreToolUse" => CLAUDE_PRE_TOOL_CMD,
+                    "PostToolUse" => CLAUDE_POST_TOOL_CMD,
+                    _ => CLAUDE_STOP_CMD,
+                }
+            );
+            let desired_cmd = desired_cmd.as_str();
 
             // Get or create the hooks array for this type
             let mut hook_type_array = hooks_obj
@@ -152,25 +154,23 @@ impl HookInstaller for ClaudeCodeInstaller {
                 .cloned()
                 .unwrap_or_default();
 
-            // Find existing matcher block for Write|Edit|MultiEdit
-            let mut found_matcher_idx: Option<usize> = None;
-            for (idx, item) in hook_type_array.iter().enumerate() {
-                if let Some(matcher) = item.get("matcher").and_then(|m| m.as_str())
-                    && matcher == desired_matcher
-                {
-                    found_matcher_idx = Some(idx);
-                    break;
+            // Find existing matcher block for Write|Edit|MultiEdit (or the unmatched block)
+            let found_matcher_idx = hook_type_array.iter().position(|item| {
+                let matcher = item.get("matcher").and_then(|m| m.as_str());
+                match desired_matcher {
+                    Some(desired) => matcher == Some(*desired),
+                    None => matcher.is_none_or(str::is_empty),
                 }
-            }
+            });
 
             let matcher_idx = match found_matcher_idx {
                 Some(idx) => idx,
                 None => {
                     // Create new matcher block
-                    hook_type_array.push(json!({
-                        "matcher": desired_matcher,
-                        "hooks": []
-                    }));
+                    hook_type_array.push(match desired_matcher {
+                        Some(matcher) => json!({ "matcher": matcher, "hooks": [] }),
+                        None => json!({ "hooks": [] }),
+                    });
                     hook_type_array.len() - 1
                 }
             };
@@ -289,8 +289,8 @@ impl HookInstaller for ClaudeCodeInstaller {
 
         let mut changed = false;
 
-        // Remove git-ai checkpoint commands from both PreToolUse and PostToolUse
-        for hook_type in &["PreToolUse", "PostToolUse"] {
+        // Remove git-ai checkpoint commands from every event we register
+        for (hook_type, _) in CLAUDE_HOOK_EVENTS {
             if let Some(hook_type_array) =
                 hooks_obj.get_mut(*hook_type).and_then(|v| v.as_array_mut())
             {
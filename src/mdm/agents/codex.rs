@@ -0,0 +1,175 @@
+use crate::commands::checkpoint_agent::codex_preset::CodexPreset;
+use crate::error::GitAiError;
+use crate::mdm::hook_installer::{HookCheckResult, HookInstaller, HookInstallerParams};
+use crate::mdm::utils::{binary_exists, generate_diff, write_atomic};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Codex appends the notification JSON as the last argument
+const CODEX_NOTIFY_ARGS: &[&str] = &["checkpoint", "codex", "--hook-input"];
+const CODEX_CONFIG_COMMENT: &str = "# git-ai: checkpoint after every Codex turn";
+
+pub struct CodexInstaller;
+
+impl CodexInstaller {
+    fn config_path() -> PathBuf {
+        CodexPreset::codex_home().join("config.toml")
+    }
+
+    fn is_notify_line(line: &str) -> bool {
+        line.split_once('=')
+            .is_some_and(|(key, _)| key.trim() == "notify")
+    }
+
+    fn is_git_ai_line(line: &str) -> bool {
+        line == CODEX_CONFIG_COMMENT
+            || (Self::is_notify_line(line) && line.contains("git-ai") && line.contains("\"codex\""))
+    }
+
+    fn notify_line(binary_path: &Path) -> String {
+        let args: Vec<String> = std::iter::once(binary_path.display().to_string())
+            .chain(CODEX_NOTIFY_ARGS.iter().map(|a| a.to_string()))
+            .map(|a| serde_json::to_string(&a).unwrap_or_default())
+            .collect();
+        format!("notify = [{}]", args.join(", "))
+    }
+}
+
+impl HookInstaller for CodexInstaller {
+    fn name(&self) -> &str {
+        "Codex"
+    }
+
+    fn id(&self) -> &str {
+        "codex"
+    }
+
+    fn check_hooks(&self, _params: &HookInstallerParams) -> Result<HookCheckResult, GitAiError> {
+        if !binary_exists("codex") && !CodexPreset::codex_home().exists() {
+            return Ok(HookCheckResult {
+                tool_installed: false,
+                hooks_installed: false,
+                hooks_up_to_date: false,
+            });
+        }
+
+        let content = fs::read_to_string(Self::config_path()).unwrap_or_default();
+        let has_hooks = content.lines().any(Self::is_git_ai_line);
+        Ok(HookCheckResult {
+            tool_installed: true,
+            hooks_installed: has_hooks,
+            hooks_up_to_date: has_hooks,
+        })
+    }
+
+    fn install_hooks(
+        &self,
+        params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let config_path = Self::config_path();
+        let existing_content = fs::read_to_string(&config_path).unwrap_or_default();
+        let desired = Self::notify_line(&params.binary_path);
+
+        if existing_content.lines().any(|line| line == desired) {
+            return Ok(None);
+        }
+        // Codex runs a single notify program
+        if existing_content
+            .lines()
+            .any(|line| Self::is_notify_line(line) && !Self::is_git_ai_line(line))
+        {
+            return Err(GitAiError::Generic(format!(
+                "{} already sets notify; call `git-ai {}` from it to checkpoint Codex turns",
+                config_path.display(),
+                CODEX_NOTIFY_ARGS.join(" ")
+            )));
+        }
+
+        // notify is a top-level key, so it has to come before the first [table]
+        let mut lines: Vec<&str> = existing_content
+            .lines()
+            .filter(|line| !Self::is_git_ai_line(line))
+            .collect();
+        let first_table = lines
+            .iter()
+            .position(|line| line.trim_start().starts_with('['))
+            .unwrap_or(lines.len());
+        let mut inserted = vec![CODEX_CONFIG_COMMENT, desired.as_str()];
+        if first_table < lines.len() {
+            inserted.push("");
+        }
+        lines.splice(first_table..first_table, inserted);
+        let new_content = format!("{}\n", lines.join("\n"));
+
+        if let Some(dir) = config_path.parent()
+            && !dry_run
+        {
+            fs::create_dir_all(dir)?;
+        }
+        let diff_output = generate_diff(&config_path, &existing_content, &new_content);
+        if !dry_run {
+            write_atomic(&config_path, new_content.as_bytes())?;
+        }
+        Ok(Some(diff_output))
+    }
+
+    fn uninstall_hooks(
+        &self,
+        _params: &HookInstallerParams,
+        dry_run: bool,
+    ) -> Result<Option<String>, GitAiError> {
+        let config_path = Self::config_path();
+        let Ok(existing_content) = fs::read_to_string(&config_path) else {
+            return Ok(None);
+        };
+        if !existing_content.lines().any(Self::is_git_ai_line) {
+            return Ok(None);
+        }
+
+        let mut lines: Vec<&str> = Vec::new();
+        let mut skip_blank = false;
+        for line in existing_content.lines() {
+            if Self::is_git_ai_line(line) {
+                skip_blank = true;
+                continue;
+            }
+            if skip_blank && line.is_empty() {
+                skip_blank = false;
+                continue;
+            }
+            skip_blank = false;
+            lines.push(line);
+        }
+        let new_content = if lines.is_empty() {
+            String::new()
+        } else {
+            format!("{}\n", lines.join("\n"))
+        };
+
+        let diff_output = generate_diff(&config_path, &existing_content, &new_content);
+        if !dry_run {
+            write_atomic(&config_path, new_content.as_bytes())?;
+        }
+        Ok(Some(diff_output))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codex_notify_line() {
+        let line = CodexInstaller::notify_line(Path::new("/usr/local/bin/git-ai"));
+        assert_eq!(
+            line,
+            r#"notify = ["/usr/local/bin/git-ai", "checkpoint", "codex", "--hook-input"]"#
+        );
+        assert!(CodexInstaller::is_git_ai_line(&line));
+        assert!(!CodexInstaller::is_git_ai_line(
+            r#"notify = ["notify-send"]"#
+        ));
+        assert!(!CodexInstaller::is_git_ai_line(r#"model = "gpt-5-codex""#));
+    }
+}
@@ -1,4 +1,6 @@
+mod aider;
 mod claude_code;
+mod codex;
 mod cursor;
 mod droid;
 mod gemini;
@@ -6,7 +8,9 @@ mod jetbrains;
 mod opencode;
 mod vscode;
 
+pub use aider::AiderInstaller;
 pub use claude_code::ClaudeCodeInstaller;
+pub use codex::CodexInstaller;
 pub use cursor::CursorInstaller;
 pub use droid::DroidInstaller;
 pub use gemini::GeminiInstaller;
@@ -26,5 +30,7 @@ pub fn get_all_installers() -> Vec<Box<dyn HookInstaller>> {
         Box::new(GeminiInstaller),
         Box::new(DroidInstaller),
         Box::new(JetBrainsInstaller),
+        Box::new(AiderInstaller),
+        Box::new(CodexInstaller),
     ]
 }
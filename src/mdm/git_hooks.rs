@@ -0,0 +1,795 @@
+use crate::config::git_ai_dir_path;
+use crate::error::GitAiError;
+use crate::git::repository::{Repository, exec_git};
+use crate::mdm::utils::{generate_diff, home_dir, parse_version, write_atomic};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Git hooks git-ai installs shims for (dispatched through `git-ai hook <name>`)
+pub const MANAGED_GIT_HOOKS: &[&str] = &[
+    "prepare-commit-msg",
+    "commit-msg",
+    "post-rewrite",
+    "post-merge",
+    "post-checkout",
+    "pre-push",
+    "reference-transaction",
+];
+
+/// First comment line of every shim; used to recognise hooks we own
+const SHIM_MARKER: &str = "# git-ai managed hook";
+
+/// Suffix for a pre-existing hook that a shim replaced (restored on uninstall)
+pub const ORIGINAL_HOOK_SUFFIX: &str = ".git-ai-orig";
+
+/// Set by shims for `git-ai hook`: the git-ai version that wrote the shim
+pub const HOOK_VERSION_ENV: &str = "GIT_AI_HOOK_VERSION";
+
+/// Set by shims for `git-ai hook`: path of the shim script itself (`$0`)
+pub const HOOK_SCRIPT_ENV: &str = "GIT_AI_HOOK_SCRIPT";
+
+/// How git-ai coexists with hooks that were already there
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GitHookMode {
+    /// Run the previous hook first (failing if it fails), then git-ai
+    #[default]
+    Chain,
+    /// Only run git-ai; the previous hook is kept aside for uninstall
+    Standalone,
+}
+
+impl GitHookMode {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "chain" => Some(GitHookMode::Chain),
+            "standalone" => Some(GitHookMode::Standalone),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GitHookMode::Chain => "chain",
+            GitHookMode::Standalone => "standalone",
+        }
+    }
+}
+
+/// Where a set of shims lives, which decides what else they have to chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitHookScope {
+    /// A repository's own hooks directory (or an init template copied into one)
+    Repo,
+    /// A machine-wide `core.hooksPath`; git then ignores `.git/hooks`, so the shims
+    /// run each repository's own hook themselves
+    Global,
+}
+
+impl GitHookScope {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "repo" => Some(GitHookScope::Repo),
+            "global" => Some(GitHookScope::Global),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GitHookScope::Repo => "repo",
+            GitHookScope::Global => "global",
+        }
+    }
+}
+
+/// Settings recorded in a shim's header comment
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShimHeader {
+    pub mode: GitHookMode,
+    pub scope: GitHookScope,
+    /// Missing in shims written before versions were recorded
+    pub version: Option<String>,
+}
+
+/// Machine-wide location git-ai installs hooks into with `install-hooks --global`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GlobalHookTarget {
+    /// `core.hooksPath`: applies to every existing and future repository
+    HooksPath,
+    /// `init.templateDir`: copied into repositories created by init/clone from now on
+    TemplateDir,
+}
+
+impl GlobalHookTarget {
+    pub fn config_key(&self) -> &'static str {
+        match self {
+            GlobalHookTarget::HooksPath => "core.hooksPath",
+            GlobalHookTarget::TemplateDir => "init.templateDir",
+        }
+    }
+
+    fn scope(&self) -> GitHookScope {
+        match self {
+            GlobalHookTarget::HooksPath => GitHookScope::Global,
+            GlobalHookTarget::TemplateDir => GitHookScope::Repo,
+        }
+    }
+
+    fn default_dir(&self) -> PathBuf {
+        let base = git_ai_dir_path().unwrap_or_else(|| home_dir().join(".git-ai"));
+        match self {
+            GlobalHookTarget::HooksPath => base.join("hooks"),
+            GlobalHookTarget::TemplateDir => base.join("git-template"),
+        }
+    }
+
+    /// Directory the shims go into for a configured (or default) value of `config_key`
+    fn hooks_dir(&self, configured: &Path) -> PathBuf {
+        match self {
+            GlobalHookTarget::HooksPath => configured.to_path_buf(),
+            GlobalHookTarget::TemplateDir => configured.join("hooks"),
+        }
+    }
+}
+
+/// Result of installing into or removing from a global hook location
+#[derive(Debug, Clone)]
+pub struct GlobalHookInstall {
+    pub hooks_dir: PathBuf,
+    pub changes: Vec<GitHookChange>,
+    /// Global git config that was (or would be) changed, as `key = value`
+    pub config_change: Option<String>,
+}
+
+/// Who owned a hook before git-ai chained onto it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExistingHookKind {
+    Husky,
+    PreCommit,
+    Lefthook,
+    Script,
+}
+
+impl ExistingHookKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ExistingHookKind::Husky => "husky",
+            ExistingHookKind::PreCommit => "pre-commit",
+            ExistingHookKind::Lefthook => "lefthook",
+            ExistingHookKind::Script => "script",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitHookAction {
+    Installed,
+    Updated,
+    Unchanged,
+    Removed,
+    Restored,
+}
+
+#[derive(Debug, Clone)]
+pub struct GitHookChange {
+    pub hook: String,
+    pub action: GitHookAction,
+    /// Hook that was chained (install) or put back (uninstall)
+    pub existing: Option<ExistingHookKind>,
+    pub diff: Option<String>,
+}
+
+/// Directory git runs hooks from, honouring `core.hooksPath`.
+pub fn resolve_hooks_dir(repo: &Repository) -> Result<PathBuf, GitAiError> {
+    let hooks_dir = repo.git(&["rev-parse", "--git-path", "hooks"])?;
+    let hooks_dir = PathBuf::from(hooks_dir.trim());
+    if hooks_dir.is_absolute() {
+        return Ok(hooks_dir);
+    }
+    // rev-parse --git-path is relative to the current directory
+    Ok(std::env::current_dir()?.join(hooks_dir))
+}
+
+pub fn is_git_ai_shim(content: &str) -> bool {
+    content
+        .lines()
+        .take(3)
+        .any(|line| line.starts_with(SHIM_MARKER))
+}
+
+/// Read the mode, scope and version a shim was installed with.
+pub fn parse_shim_header(content: &str) -> Option<ShimHeader> {
+    let line = content
+        .lines()
+        .take(3)
+        .find(|line| line.starts_with(SHIM_MARKER))?;
+    let fields = line.split_once('(')?.1.split_once(')')?.0;
+
+    let mut header = ShimHeader {
+        mode: GitHookMode::default(),
+        scope: GitHookScope::Repo,
+        version: None,
+    };
+    for field in fields.split(',') {
+        let Some((key, value)) = field.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "mode" => header.mode = GitHookMode::parse(value)?,
+            "scope" => header.scope = GitHookScope::parse(value)?,
+            "version" => header.version = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    Some(header)
+}
+
+/// Shims only change shape between minor releases, so same major.minor is compatible.
+pub fn shim_is_compatible(shim_version: &str, cli_version: &str) -> bool {
+    match (parse_version(shim_version), parse_version(cli_version)) {
+        (Some(shim), Some(cli)) => shim == cli,
+        _ => shim_version == cli_version,
+    }
+}
+
+/// Rewrite the shims in `hooks_dir` for the running binary, keeping the mode and scope
+/// they were installed with. Used by hooks to update themselves after an upgrade.
+pub fn refresh_git_hooks(
+    hooks_dir: &Path,
+    binary_path: &Path,
+) -> Result<Vec<GitHookChange>, GitAiError> {
+    let header = MANAGED_GIT_HOOKS
+        .iter()
+        .filter_map(|hook| fs::read_to_string(hooks_dir.join(hook)).ok())
+        .find_map(|content| parse_shim_header(&content))
+        .ok_or_else(|| {
+            GitAiError::Generic(format!("No git-ai hooks found in {}", hooks_dir.display()))
+        })?;
+    install_git_hooks(hooks_dir, binary_path, header.mode, header.scope, false)
+}
+
+/// Classify an existing hook by the hook manager that generated it.
+pub fn detect_existing_hook(hooks_dir: &Path, content: &str) -> ExistingHookKind {
+    let lower = content.to_lowercase();
+    let in_husky_dir = hooks_dir
+        .components()
+        .any(|c| c.as_os_str().to_string_lossy().contains(".husky"));
+    if in_husky_dir || lower.contains("husky") {
+        ExistingHookKind::Husky
+    } else if lower.contains("lefthook") {
+        ExistingHookKind::Lefthook
+    } else if lower.contains("pre-commit.com") || lower.contains("generated by pre-commit") {
+        ExistingHookKind::PreCommit
+    } else {
+        ExistingHookKind::Script
+    }
+}
+
+/// Hooks whose git-ai handler may reject the operation by exiting non-zero
+fn hook_can_block(hook: &str) -> bool {
+    matches!(hook, "pre-push" | "pre-receive")
+}
+
+/// Hooks that receive data on stdin, which must be replayed to both hooks when chaining
+fn hook_reads_stdin(hook: &str) -> bool {
+    matches!(
+        hook,
+        "pre-push" | "post-rewrite" | "reference-transaction" | "pre-receive" | "post-receive"
+    )
+}
+
+pub fn shim_content(
+    hook: &str,
+    binary_path: &Path,
+    mode: GitHookMode,
+    scope: GitHookScope,
+) -> String {
+    // Git for Windows runs hooks with sh, which expects forward slashes
+    let binary = binary_path.to_string_lossy().replace('\\', "/");
+    let mut script = format!(
+        "#!/bin/sh\n{} (mode: {}, scope: {}, version: {}). Reinstall with `git-ai install-hooks`.\n",
+        SHIM_MARKER,
+        mode.as_str(),
+        scope.as_str(),
+        env!("CARGO_PKG_VERSION")
+    );
+
+    let stdin_redirect = if hook_reads_stdin(hook) {
+        script.push_str("stdin_file=$(mktemp)\ncat > \"$stdin_file\"\n");
+        " < \"$stdin_file\""
+    } else {
+        ""
+    };
+
+    if mode == GitHookMode::Chain {
+        script.push_str(&format!(
+            "previous=\"$(dirname \"$0\")/{hook}{suffix}\"\n\
+             if [ -x \"$previous\" ]; then\n  \
+             \"$previous\" \"$@\"{stdin} || {{ status=$?; {cleanup}exit $status; }}\n\
+             fi\n",
+            hook = hook,
+            suffix = ORIGINAL_HOOK_SUFFIX,
+            stdin = stdin_redirect,
+            cleanup = if stdin_redirect.is_empty() {
+                ""
+            } else {
+                "rm -f \"$stdin_file\"; "
+            },
+        ));
+        if scope == GitHookScope::Global {
+            script.push_str(&format!(
+                "repo_hook=\"$(git rev-parse --git-common-dir 2>/dev/null)/hooks/{hook}\"\n\
+                 if [ -x \"$repo_hook\" ] && [ \"$repo_hook\" != \"$0\" ]; then\n  \
+                 \"$repo_hook\" \"$@\"{stdin} || {{ status=$?; {cleanup}exit $status; }}\n\
+                 fi\n",
+                hook = hook,
+                stdin = stdin_redirect,
+                cleanup = if stdin_redirect.is_empty() {
+                    ""
+                } else {
+                    "rm -f \"$stdin_file\"; "
+                },
+            ));
+        }
+    }
+
+    // Skipped rather than failing if git-ai has been removed from this machine
+    script.push_str(&format!(
+        "if [ -x \"{binary}\" ]; then\n  \
+         {version_env}={version} {script_env}=\"$0\" \"{binary}\" hook {hook} \"$@\"{stdin}\n\
+         fi\n",
+        version_env = HOOK_VERSION_ENV,
+        version = env!("CARGO_PKG_VERSION"),
+        script_env = HOOK_SCRIPT_ENV,
+        binary = binary,
+        hook = hook,
+        stdin = stdin_redirect
+    ));
+    let status = if hook_can_block(hook) {
+        script.push_str("status=$?\n");
+        "$status"
+    } else {
+        "0"
+    };
+    if !stdin_redirect.is_empty() {
+        script.push_str("rm -f \"$stdin_file\"\n");
+    }
+    script.push_str(&format!("exit {}\n", status));
+    script
+}
+
+fn make_executable(path: &Path) -> Result<(), GitAiError> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = fs::metadata(path)?.permissions();
+        perms.set_mode(0o755);
+        fs::set_permissions(path, perms)?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// Install git-ai shims for every managed hook in `hooks_dir`.
+///
+/// Existing hooks are never overwritten: they are moved aside to
+/// `<hook>.git-ai-orig` and, in chain mode, invoked by the shim.
+pub fn install_git_hooks(
+    hooks_dir: &Path,
+    binary_path: &Path,
+    mode: GitHookMode,
+    scope: GitHookScope,
+    dry_run: bool,
+) -> Result<Vec<GitHookChange>, GitAiError> {
+    if !dry_run {
+        fs::create_dir_all(hooks_dir)?;
+    }
+
+    let mut changes = Vec::new();
+    for hook in MANAGED_GIT_HOOKS {
+        let hook_path = hooks_dir.join(hook);
+        let original_path = hooks_dir.join(format!("{}{}", hook, ORIGINAL_HOOK_SUFFIX));
+        let desired = shim_content(hook, binary_path, mode, scope);
+
+        let current = fs::read_to_string(&hook_path).ok();
+        let (action, existing, old_content) = match &current {
+            Some(content) if is_git_ai_shim(content) => {
+                let existing = fs::read_to_string(&original_path)
+                    .ok()
+                    .map(|c| detect_existing_hook(hooks_dir, &c));
+                if *content == desired {
+                    (GitHookAction::Unchanged, existing, content.clone())
+                } else {
+                    (GitHookAction::Updated, existing, content.clone())
+                }
+            }
+            Some(content) => {
+                let existing = detect_existing_hook(hooks_dir, content);
+                if !dry_run {
+                    fs::rename(&hook_path, &original_path)?;
+                }
+                (GitHookAction::Installed, Some(existing), String::new())
+            }
+            None => (GitHookAction::Installed, None, String::new()),
+        };
+
+        let diff = if action == GitHookAction::Unchanged {
+            None
+        } else {
+            if !dry_run {
+                write_atomic(&hook_path, desired.as_bytes())?;
+                make_executable(&hook_path)?;
+            }
+            Some(generate_diff(&hook_path, &old_content, &desired))
+        };
+
+        changes.push(GitHookChange {
+            hook: hook.to_string(),
+            action,
+            existing,
+            diff,
+        });
+    }
+    Ok(changes)
+}
+
+/// Remove git-ai shims and restore any hooks they replaced.
+pub fn uninstall_git_hooks(
+    hooks_dir: &Path,
+    dry_run: bool,
+) -> Result<Vec<GitHookChange>, GitAiError> {
+    let mut changes = Vec::new();
+    for hook in MANAGED_GIT_HOOKS {
+        let hook_path = hooks_dir.join(hook);
+        let original_path = hooks_dir.join(format!("{}{}", hook, ORIGINAL_HOOK_SUFFIX));
+
+        let Ok(content) = fs::read_to_string(&hook_path) else {
+            continue;
+        };
+        if !is_git_ai_shim(&content) {
+            continue;
+        }
+
+        let restored = fs::read_to_string(&original_path).ok();
+        if !dry_run {
+            if restored.is_some() {
+                fs::rename(&original_path, &hook_path)?;
+            } else {
+                fs::remove_file(&hook_path)?;
+            }
+        }
+
+        changes.push(GitHookChange {
+            hook: hook.to_string(),
+            action: if restored.is_some() {
+                GitHookAction::Restored
+            } else {
+                GitHookAction::Removed
+            },
+            existing: restored
+                .as_deref()
+                .map(|c| detect_existing_hook(hooks_dir, c)),
+            diff: Some(generate_diff(
+                &hook_path,
+                &content,
+                restored.as_deref().unwrap_or(""),
+            )),
+        });
+    }
+    Ok(changes)
+}
+
+fn global_config_get(key: &str) -> Option<String> {
+    let args: Vec<String> = ["config", "--global", "--get", key]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+    let output = exec_git(&args).ok()?;
+    let value = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!value.is_empty()).then_some(value)
+}
+
+fn global_config_set(key: &str, value: Option<&str>) -> Result<(), GitAiError> {
+    let mut args: Vec<String> = vec!["config".to_string(), "--global".to_string()];
+    match value {
+        Some(value) => {
+            args.push(key.to_string());
+            args.push(value.to_string());
+        }
+        None => {
+            args.push("--unset".to_string());
+            args.push(key.to_string());
+        }
+    }
+    exec_git(&args)?;
+    Ok(())
+}
+
+/// git expands a leading `~/` in path-valued config
+fn expand_config_path(value: &str) -> PathBuf {
+    match value.strip_prefix("~/") {
+        Some(rest) => home_dir().join(rest),
+        None => PathBuf::from(value),
+    }
+}
+
+/// Install shims machine-wide. An already-configured hooks path or template directory
+/// is reused (its hooks are chained like any other); otherwise git-ai's own directory
+/// is created and registered in the global git config.
+pub fn install_global_git_hooks(
+    target: GlobalHookTarget,
+    binary_path: &Path,
+    mode: GitHookMode,
+    dry_run: bool,
+) -> Result<GlobalHookInstall, GitAiError> {
+    let key = target.config_key();
+    let (configured, config_change) = match global_config_get(key) {
+        Some(value) => (expand_config_path(&value), None),
+        None => {
+            let dir = target.default_dir();
+            let value = dir.to_string_lossy().replace('\\', "/");
+            if !dry_run {
+                global_config_set(key, Some(&value))?;
+            }
+            (dir, Some(format!("{} = {}", key, value)))
+        }
+    };
+
+    let hooks_dir = target.hooks_dir(&configured);
+    let changes = install_git_hooks(&hooks_dir, binary_path, mode, target.scope(), dry_run)?;
+    Ok(GlobalHookInstall {
+        hooks_dir,
+        changes,
+        config_change,
+    })
+}
+
+/// Remove machine-wide shims, and the global git config entry if git-ai created it.
+pub fn uninstall_global_git_hooks(
+    target: GlobalHookTarget,
+    dry_run: bool,
+) -> Result<Option<GlobalHookInstall>, GitAiError> {
+    let key = target.config_key();
+    let Some(value) = global_config_get(key) else {
+        return Ok(None);
+    };
+    let configured = expand_config_path(&value);
+    let hooks_dir = target.hooks_dir(&configured);
+    let changes = uninstall_git_hooks(&hooks_dir, dry_run)?;
+
+    let mut config_change = None;
+    if configured == target.default_dir() {
+        if !dry_run {
+            global_config_set(key, None)?;
+        }
+        config_change = Some(format!("{} (unset)", key));
+    }
+    Ok(Some(GlobalHookInstall {
+        hooks_dir,
+        changes,
+        config_change,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn binary() -> PathBuf {
+        PathBuf::from("/usr/local/bin/git-ai")
+    }
+
+    #[test]
+    fn test_install_into_empty_hooks_dir() {
+        let temp = TempDir::new().unwrap();
+        let hooks_dir = temp.path().join("hooks");
+
+        let changes = install_git_hooks(
+            &hooks_dir,
+            &binary(),
+            GitHookMode::Chain,
+            GitHookScope::Repo,
+            false,
+        )
+        .unwrap();
+        assert!(
+            changes
+                .iter()
+                .all(|c| c.action == GitHookAction::Installed && c.existing.is_none())
+        );
+        let content = fs::read_to_string(hooks_dir.join("prepare-commit-msg")).unwrap();
+        assert!(is_git_ai_shim(&content));
+        assert!(content.contains("\"/usr/local/bin/git-ai\" hook prepare-commit-msg \"$@\""));
+
+        // Second install is a no-op
+        let again = install_git_hooks(
+            &hooks_dir,
+            &binary(),
+            GitHookMode::Chain,
+            GitHookScope::Repo,
+            false,
+        )
+        .unwrap();
+        assert!(again.iter().all(|c| c.action == GitHookAction::Unchanged));
+    }
+
+    #[test]
+    fn test_shim_header_round_trip() {
+        let content = shim_content(
+            "post-merge",
+            &binary(),
+            GitHookMode::Standalone,
+            GitHookScope::Global,
+        );
+        let header = parse_shim_header(&content).unwrap();
+        assert_eq!(header.mode, GitHookMode::Standalone);
+        assert_eq!(header.scope, GitHookScope::Global);
+        assert_eq!(header.version.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+
+        // Shims from before versions were recorded
+        let legacy = "#!/bin/sh\n# git-ai managed hook (mode: chain). Reinstall with `git-ai install-hooks`.\n";
+        let header = parse_shim_header(legacy).unwrap();
+        assert_eq!(header.mode, GitHookMode::Chain);
+        assert_eq!(header.version, None);
+        assert!(parse_shim_header("#!/bin/sh\nexit 0\n").is_none());
+    }
+
+    #[test]
+    fn test_shim_is_compatible() {
+        assert!(shim_is_compatible("1.1.0", "1.1.7"));
+        assert!(!shim_is_compatible("1.0.9", "1.1.0"));
+        assert!(!shim_is_compatible("2.1.0", "1.1.0"));
+    }
+
+    #[test]
+    fn test_refresh_rewrites_outdated_shims_keeping_mode() {
+        let temp = TempDir::new().unwrap();
+        let hooks_dir = temp.path().join("hooks");
+        install_git_hooks(
+            &hooks_dir,
+            &binary(),
+            GitHookMode::Standalone,
+            GitHookScope::Repo,
+            false,
+        )
+        .unwrap();
+        let hook_path = hooks_dir.join("post-checkout");
+        let current = fs::read_to_string(&hook_path).unwrap();
+        let outdated = current.replace(env!("CARGO_PKG_VERSION"), "0.0.1");
+        fs::write(&hook_path, &outdated).unwrap();
+
+        let changes = refresh_git_hooks(&hooks_dir, &binary()).unwrap();
+        let change = changes.iter().find(|c| c.hook == "post-checkout").unwrap();
+        assert_eq!(change.action, GitHookAction::Updated);
+        assert_eq!(fs::read_to_string(&hook_path).unwrap(), current);
+    }
+
+    #[test]
+    fn test_install_chains_existing_hook_and_uninstall_restores_it() {
+        let temp = TempDir::new().unwrap();
+        let hooks_dir = temp.path().to_path_buf();
+        let original = "#!/bin/sh\n# lefthook generated\nlefthook run commit-msg \"$@\"\n";
+        fs::write(hooks_dir.join("commit-msg"), original).unwrap();
+
+        let changes = install_git_hooks(
+            &hooks_dir,
+            &binary(),
+            GitHookMode::Chain,
+            GitHookScope::Repo,
+            false,
+        )
+        .unwrap();
+        let commit_msg = changes.iter().find(|c| c.hook == "commit-msg").unwrap();
+        assert_eq!(commit_msg.existing, Some(ExistingHookKind::Lefthook));
+        assert_eq!(
+            fs::read_to_string(hooks_dir.join("commit-msg.git-ai-orig")).unwrap(),
+            original
+        );
+        let shim = fs::read_to_string(hooks_dir.join("commit-msg")).unwrap();
+        assert!(shim.contains("commit-msg.git-ai-orig"));
+
+        let removed = uninstall_git_hooks(&hooks_dir, false).unwrap();
+        assert_eq!(removed.len(), MANAGED_GIT_HOOKS.len());
+        assert_eq!(
+            fs::read_to_string(hooks_dir.join("commit-msg")).unwrap(),
+            original
+        );
+        assert!(!hooks_dir.join("commit-msg.git-ai-orig").exists());
+        assert!(!hooks_dir.join("prepare-commit-msg").exists());
+    }
+
+    #[test]
+    fn test_standalone_mode_does_not_call_previous_hook() {
+        let shim = shim_content(
+            "commit-msg",
+            &binary(),
+            GitHookMode::Standalone,
+            GitHookScope::Repo,
+        );
+        assert!(!shim.contains(ORIGINAL_HOOK_SUFFIX));
+        assert!(shim.contains("(mode: standalone, scope: repo,"));
+    }
+
+    #[test]
+    fn test_stdin_hooks_replay_input_to_both_hooks() {
+        let shim = shim_content(
+            "pre-push",
+            &binary(),
+            GitHookMode::Chain,
+            GitHookScope::Repo,
+        );
+        assert!(shim.contains("cat > \"$stdin_file\""));
+        assert!(shim.contains("\"$previous\" \"$@\" < \"$stdin_file\""));
+        assert!(shim.contains("hook pre-push \"$@\" < \"$stdin_file\""));
+    }
+
+    #[test]
+    fn test_blocking_hooks_propagate_exit_status() {
+        let shim = shim_content(
+            "pre-push",
+            &binary(),
+            GitHookMode::Chain,
+            GitHookScope::Repo,
+        );
+        assert!(shim.ends_with("status=$?\nrm -f \"$stdin_file\"\nexit $status\n"));
+        let shim = shim_content(
+            "post-merge",
+            &binary(),
+            GitHookMode::Chain,
+            GitHookScope::Repo,
+        );
+        assert!(shim.ends_with("exit 0\n"));
+    }
+
+    #[test]
+    fn test_global_scope_chains_repository_hooks() {
+        let repo_shim = shim_content(
+            "commit-msg",
+            &binary(),
+            GitHookMode::Chain,
+            GitHookScope::Repo,
+        );
+        assert!(!repo_shim.contains("--git-common-dir"));
+
+        let global_shim = shim_content(
+            "commit-msg",
+            &binary(),
+            GitHookMode::Chain,
+            GitHookScope::Global,
+        );
+        assert!(global_shim.contains(
+            "repo_hook=\"$(git rev-parse --git-common-dir 2>/dev/null)/hooks/commit-msg\""
+        ));
+        // Standalone mode runs only git-ai, even globally
+        let standalone = shim_content(
+            "commit-msg",
+            &binary(),
+            GitHookMode::Standalone,
+            GitHookScope::Global,
+        );
+        assert!(!standalone.contains("repo_hook"));
+    }
+
+    #[test]
+    fn test_detect_existing_hook() {
+        let dir = Path::new("/repo/.git/hooks");
+        assert_eq!(
+            detect_existing_hook(Path::new("/repo/.husky/_"), "#!/bin/sh\n. \"$0\""),
+            ExistingHookKind::Husky
+        );
+        assert_eq!(
+            detect_existing_hook(
+                dir,
+                "#!/usr/bin/env bash\n# File generated by pre-commit: https://pre-commit.com\n"
+            ),
+            ExistingHookKind::PreCommit
+        );
+        assert_eq!(
+            detect_existing_hook(dir, "#!/bin/sh\necho hi\n"),
+            ExistingHookKind::Script
+        );
+    }
+}
@@ -18,7 +18,8 @@ pub fn download_plugin_from_marketplace(
 
     debug_log(&format!("JetBrains: Downloading plugin from {}", url));
 
-    let response = minreq::get(&url)
+    crate::api::network::ensure_online().map_err(GitAiError::Generic)?;
+    let response = crate::api::network::with_proxy(minreq::get(&url), &url)
         .with_timeout(120) // 120 second timeout for plugin download
         .send()
         .map_err(|e| GitAiError::Generic(format!("Failed to download plugin: {}", e)))?;
@@ -2,6 +2,7 @@ pub mod agents;
 pub mod ensure_git_symlinks;
 pub mod git_client_installer;
 pub mod git_clients;
+pub mod git_hooks;
 pub mod hook_installer;
 pub mod jetbrains;
 pub mod skills_installer;
@@ -311,11 +311,13 @@ impl SentryClient {
 
         let body = serde_json::to_string(&event)?;
 
-        let response = minreq::post(&self.endpoint)
-            .with_header("X-Sentry-Auth", auth_header)
-            .with_header("Content-Type", "application/json")
-            .with_body(body)
-            .send()?;
+        crate::api::network::ensure_online()?;
+        let response =
+            crate::api::network::with_proxy(minreq::post(&self.endpoint), &self.endpoint)
+                .with_header("X-Sentry-Auth", auth_header)
+                .with_header("Content-Type", "application/json")
+                .with_body(body)
+                .send()?;
 
         let status = response.status_code;
         let event_id = serde_json::from_str::<Value>(response.as_str()?)
@@ -344,10 +346,12 @@ impl PostHogClient {
     fn send_event(&self, event: Value) -> Result<(), Box<dyn std::error::Error>> {
         let body = serde_json::to_string(&event)?;
 
-        let response = minreq::post(&self.endpoint)
-            .with_header("Content-Type", "application/json")
-            .with_body(body)
-            .send()?;
+        crate::api::network::ensure_online()?;
+        let response =
+            crate::api::network::with_proxy(minreq::post(&self.endpoint), &self.endpoint)
+                .with_header("Content-Type", "application/json")
+                .with_body(body)
+                .send()?;
 
         let status = response.status_code;
 
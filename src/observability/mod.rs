@@ -174,6 +174,9 @@ pub fn log_message(message: &str, level: &str, context: Option<serde_json::Value
 
 /// Spawn a background process to flush logs to Sentry
 pub fn spawn_background_flush() {
+    if crate::api::network::offline() {
+        return;
+    }
     // Skip flush in test builds to prevent race conditions during test cleanup.
     // Tests spawn git-ai as a subprocess which calls this function. If the background
     // flush process is still starting when TestRepo::drop() runs, file handles may
@@ -1,24 +1,11 @@
 use crate::error::GitAiError;
 use crate::git::diff_tree_to_tree::Diff;
 use std::io::IsTerminal;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-/// Check if debug logging is enabled via environment variable
-///
-/// This is checked once at module initialization to avoid repeated environment variable lookups.
-static DEBUG_ENABLED: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
 static DEBUG_PERFORMANCE_LEVEL: std::sync::OnceLock<u8> = std::sync::OnceLock::new();
 static IS_TERMINAL: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
 
-fn is_debug_enabled() -> bool {
-    *DEBUG_ENABLED.get_or_init(|| {
-        (cfg!(debug_assertions)
-            || std::env::var("GIT_AI_DEBUG").unwrap_or_default() == "1"
-            || std::env::var("GIT_AI_DEBUG_PERFORMANCE").unwrap_or_default() != "")
-            && std::env::var("GIT_AI_DEBUG").unwrap_or_default() != "0"
-    })
-}
-
 fn is_debug_performance_enabled() -> bool {
     debug_performance_level() >= 1
 }
@@ -46,16 +33,14 @@ pub fn debug_performance_log_structured(json: serde_json::Value) {
 
 /// Debug logging utility function
 ///
-/// Prints debug messages with a colored prefix when debug assertions are enabled or when
-/// the `GIT_AI_DEBUG` environment variable is set to "1".
+/// Logs `msg` at the debug level of [`crate::logging`], which is on in debug builds,
+/// with `GIT_AI_DEBUG=1`, or with `GIT_AI_LOG=debug`.
 ///
 /// # Arguments
 ///
 /// * `msg` - The debug message to print
 pub fn debug_log(msg: &str) {
-    if is_debug_enabled() {
-        eprintln!("\x1b[1;33m[git-ai]\x1b[0m {}", msg);
-    }
+    crate::logging::log(crate::logging::Level::Debug, msg);
 }
 
 /// Print a git diff in a readable format
@@ -234,10 +219,57 @@ pub fn unescape_git_path(path: &str) -> String {
     })
 }
 
+/// Files at least this large are memory-mapped rather than read into a buffer
+const MMAP_MIN_BYTES: u64 = 1 << 20;
+
+/// Run `f` on the bytes of the file at `path`. Large files are memory-mapped, so that
+/// decoding them (to text, or out of a compressed snapshot) allocates only the result
+/// rather than a copy of the file as well; anything that can't be mapped (a pipe, a
+/// filesystem without mmap support) is read into memory instead.
+///
+/// Like git's own mmap use, this assumes the file isn't truncated while `f` runs.
+pub fn with_file_bytes<T>(path: &Path, f: impl FnOnce(&[u8]) -> T) -> std::io::Result<T> {
+    let file = std::fs::File::open(path)?;
+    let metadata = file.metadata()?;
+    if metadata.is_file() && metadata.len() >= MMAP_MIN_BYTES {
+        // SAFETY: the mapping is read-only and dropped before returning
+        if let Ok(mmap) = unsafe { memmap2::Mmap::map(&file) } {
+            return Ok(f(&mmap));
+        }
+    }
+    let mut bytes = Vec::with_capacity(metadata.len() as usize);
+    std::io::Read::read_to_end(&mut &file, &mut bytes)?;
+    Ok(f(&bytes))
+}
+
+/// The contents of the file at `path` as text, with invalid UTF-8 replaced
+pub fn read_text_lossy(path: &Path) -> std::io::Result<String> {
+    with_file_bytes(path, |bytes| String::from_utf8_lossy(bytes).into_owned())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_with_file_bytes_maps_large_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let small = dir.path().join("small.txt");
+        std::fs::write(&small, b"caf\xc3\xa9 \xff\n").unwrap();
+        assert_eq!(read_text_lossy(&small).unwrap(), "café \u{fffd}\n");
+
+        let large = dir.path().join("large.txt");
+        let content = "line\n".repeat((MMAP_MIN_BYTES as usize / 5) + 1);
+        std::fs::write(&large, &content).unwrap();
+        assert_eq!(read_text_lossy(&large).unwrap(), content);
+        assert_eq!(
+            with_file_bytes(&large, |bytes| bytes.len()).unwrap(),
+            content.len()
+        );
+
+        assert!(read_text_lossy(&dir.path().join("missing.txt")).is_err());
+    }
+
     #[test]
     fn test_unescape_git_path_simple() {
         // Unquoted path - no change
@@ -0,0 +1,167 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+
+const CHAT_HISTORY: &str = "\
+# aider chat started at 2025-03-04 10:11:12
+
+> Main model: gpt-4o with diff edit format
+> Git repo: .git with 1 files
+
+#### add a greet function
+
+greet.py
+```python
+def greet():
+    print(\"hi\")
+```
+";
+
+fn note_for_head(repo: &TestRepo) -> serde_json::Value {
+    let note = repo
+        .git_og(&["notes", "--ref=ai", "show", "HEAD"])
+        .expect("HEAD should have an authorship note");
+    let json = &note[note.find("---").expect("note should have metadata") + 3..];
+    serde_json::from_str(json.trim()).unwrap()
+}
+
+/// Aider's auto-commits are attributed to aider even though no hook ran before them.
+#[test]
+fn test_aider_auto_commit_is_attributed() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("greet.py");
+    file.set_contents(lines!["# greetings"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    fs::write(repo.path().join(".aider.chat.history.md"), CHAT_HISTORY).unwrap();
+    let greet_path = repo.path().join("greet.py");
+    fs::write(
+        &greet_path,
+        "# greetings\ndef greet():\n    print(\"hi\")\n",
+    )
+    .unwrap();
+
+    // The way aider commits: add, then commit its files with an attribution trailer
+    let greet = greet_path.to_string_lossy().to_string();
+    repo.git(&["add", &greet]).unwrap();
+    repo.git_with_env(
+        &[
+            "commit",
+            "-m",
+            "feat: Add greet function\n\nCo-authored-by: aider (openai/gpt-4.1) <aider@aider.chat>",
+            "--no-verify",
+            "--",
+            &greet,
+        ],
+        &[("GIT_COMMITTER_NAME", "Test User (aider)")],
+        None,
+    )
+    .unwrap();
+
+    file.assert_lines_and_blame(lines![
+        "# greetings".human(),
+        "def greet():".ai(),
+        "    print(\"hi\")".ai(),
+    ]);
+
+    let note = note_for_head(&repo);
+    let prompt = note["prompts"]
+        .as_object()
+        .unwrap()
+        .values()
+        .next()
+        .unwrap();
+    assert_eq!(prompt["agent_id"]["tool"], "aider");
+    assert_eq!(prompt["agent_id"]["model"], "openai/gpt-4.1");
+    assert_eq!(prompt["agent_id"]["id"], "2025-03-04 10:11:12");
+}
+
+/// Commits of the user's own dirty files only carry aider's committer suffix.
+#[test]
+fn test_aider_dirty_commit_stays_human() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("notes.txt");
+    file.set_contents(lines!["first"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    fs::write(repo.path().join("notes.txt"), "first\nsecond\n").unwrap();
+    repo.git(&["add", "-A"]).unwrap();
+    repo.git_with_env(
+        &["commit", "-m", "docs: Update notes"],
+        &[("GIT_COMMITTER_NAME", "Test User (aider)")],
+        None,
+    )
+    .unwrap();
+
+    file.assert_lines_and_blame(lines!["first".human(), "second".human()]);
+}
+
+/// With auto-commits off, aider's lint-cmd checkpoints its edits for the later commit.
+#[test]
+fn test_aider_lint_cmd_checkpoint() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("greet.py");
+    file.set_contents(lines!["# greetings"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    fs::write(repo.path().join(".aider.chat.history.md"), CHAT_HISTORY).unwrap();
+    fs::write(
+        repo.path().join("greet.py"),
+        "# greetings\ndef greet():\n    print(\"hi\")\n",
+    )
+    .unwrap();
+    repo.git_ai(&["checkpoint", "aider", "greet.py"]).unwrap();
+
+    repo.git(&["add", "greet.py"]).unwrap();
+    repo.git(&["commit", "-m", "Add greet function"]).unwrap();
+
+    file.assert_lines_and_blame(lines![
+        "# greetings".human(),
+        "def greet():".ai(),
+        "    print(\"hi\")".ai(),
+    ]);
+    let note = note_for_head(&repo);
+    let prompt = note["prompts"]
+        .as_object()
+        .unwrap()
+        .values()
+        .next()
+        .unwrap();
+    assert_eq!(prompt["agent_id"]["model"], "gpt-4o");
+}
+
+#[test]
+fn test_integrate_aider_writes_lint_cmd() {
+    let repo = TestRepo::new();
+    let home = tempfile::tempdir().unwrap();
+    let home_str = home.path().to_str().unwrap();
+    let env = [("HOME", home_str)];
+    let config_path = home.path().join(".aider.conf.yml");
+    fs::write(&config_path, "model: gpt-4o\n").unwrap();
+
+    let output = repo.git_ai_with_env(&["integrate", "aider"], &env).unwrap();
+    assert!(output.contains("Aider: hooks installed"), "{}", output);
+    let config = fs::read_to_string(&config_path).unwrap();
+    assert!(config.starts_with("model: gpt-4o\n"), "{}", config);
+    let lint_cmd = config.lines().last().unwrap();
+    assert!(
+        lint_cmd.starts_with("lint-cmd: \"") && lint_cmd.ends_with("git-ai checkpoint aider\""),
+        "{}",
+        config
+    );
+
+    let output = repo
+        .git_ai_with_env(&["integrate", "aider", "--uninstall"], &env)
+        .unwrap();
+    assert!(output.contains("hooks removed"), "{}", output);
+    assert_eq!(fs::read_to_string(&config_path).unwrap(), "model: gpt-4o\n");
+
+    // A lint-cmd the user configured is left alone
+    fs::write(&config_path, "lint-cmd: \"ruff check\"\n").unwrap();
+    let err = repo
+        .git_ai_with_env(&["integrate", "aider"], &env)
+        .unwrap_err();
+    assert!(err.contains("already sets lint-cmd"), "{}", err);
+}
@@ -0,0 +1,80 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+
+const PATCH: &str = "\
+diff --git a/lib.txt b/lib.txt
+--- a/lib.txt
++++ b/lib.txt
+@@ -3,3 +3,4 @@ two
+ three
+ four
+ five
++six from agent
+diff --git a/new.txt b/new.txt
+new file mode 100644
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1 @@
++brand new
+";
+
+#[test]
+fn test_apply_ai_patch_attributes_exactly_the_patch() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.txt");
+    file.set_contents(lines!["one", "two", "three", "four", "five"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    // A human edit in the same file, never checkpointed
+    fs::write(
+        repo.path().join("lib.txt"),
+        "one by hand\ntwo\nthree\nfour\nfive\n",
+    )
+    .unwrap();
+
+    let output = repo
+        .git_ai_with_stdin(
+            &["apply-ai-patch", "--tool", "mock_ai", "--model", "gpt-4.1"],
+            PATCH.as_bytes(),
+        )
+        .unwrap();
+    assert!(output.contains("Applied patch to 2 file(s)"), "{}", output);
+
+    let commit = repo.stage_all_and_commit("Apply agent patch").unwrap();
+    file.assert_lines_and_blame(lines![
+        "one by hand".human(),
+        "two".human(),
+        "three".human(),
+        "four".human(),
+        "five".human(),
+        "six from agent".ai(),
+    ]);
+    repo.filename("new.txt")
+        .assert_lines_and_blame(lines!["brand new".ai()]);
+    let prompt = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .values()
+        .next()
+        .unwrap();
+    assert_eq!(prompt.agent_id.model, "gpt-4.1");
+}
+
+#[test]
+fn test_apply_ai_patch_rejects_patch_that_does_not_apply() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.txt");
+    file.set_contents(lines!["unrelated"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let err = repo
+        .git_ai_with_stdin(&["apply-ai-patch", "--tool", "mock_ai"], PATCH.as_bytes())
+        .unwrap_err();
+    assert!(err.contains("Failed to apply patch"), "{}", err);
+    assert_eq!(repo.read_file("lib.txt").unwrap(), "unrelated");
+    assert!(repo.read_file("new.txt").is_none());
+}
@@ -0,0 +1,41 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+/// With async_post_commit the commit returns before its note is written; `git-ai status`
+/// waits for the background finalizer so attribution is complete afterwards.
+#[test]
+fn test_async_post_commit_is_completed_by_status() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.async_post_commit = Some(true);
+    });
+
+    let mut file = repo.filename("test.txt");
+    file.set_contents(lines!["line 1"]);
+    repo.git(&["add", "-A"]).unwrap();
+    repo.git(&["commit", "-m", "Initial commit"]).unwrap();
+
+    file.set_contents(lines!["line 1", "ai line 2".ai()]);
+    repo.git(&["add", "-A"]).unwrap();
+    repo.git(&["commit", "-m", "Add AI line"]).unwrap();
+    let head = repo
+        .git_og(&["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string();
+
+    repo.git_ai(&["status"]).unwrap();
+
+    let note = repo.git_og(&["notes", "--ref=ai", "show", &head]).unwrap();
+    assert!(note.contains("test.txt"), "note: {}", note);
+    let queue = repo.path().join(".git").join("ai").join("pending_commits");
+    let queued: Vec<_> = std::fs::read_dir(&queue)
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .collect();
+    assert!(queued.is_empty(), "queue not drained: {:?}", queued);
+
+    file.assert_lines_and_blame(lines!["line 1".human(), "ai line 2".ai()]);
+}
@@ -0,0 +1,140 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use serde_json::Value;
+
+fn records(path: &std::path::Path) -> Vec<Value> {
+    std::fs::read_to_string(path)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+#[test]
+fn test_audit_export_appends_to_a_verifiable_chain() {
+    let repo = TestRepo::new();
+    let mut policy = repo.filename(".git-ai/policy.toml");
+    policy.set_contents(lines![
+        "[[rules]]",
+        "name = \"half\"",
+        "max_ai_percent = 50"
+    ]);
+    let base = repo.stage_all_and_commit("Add policy").unwrap().commit_sha;
+
+    let mut file = repo.filename("src/lib.rs");
+    file.set_contents(lines!["fn a() {}".ai(), "fn b() {}".ai()]);
+    let first = repo.stage_all_and_commit("Add a and b").unwrap().commit_sha;
+
+    let dir = tempfile::tempdir().unwrap();
+    let trail = dir.path().join("audit.jsonl");
+    let trail_arg = trail.to_str().unwrap();
+    let range = format!("{}..HEAD", base);
+    let output = repo
+        .git_ai(&["audit", "export", "--range", &range, "--output", trail_arg])
+        .unwrap();
+    assert!(output.contains("recorded 1 commit(s)"), "{}", output);
+
+    let exported = records(&trail);
+    let types: Vec<&str> = exported
+        .iter()
+        .map(|r| r["type"].as_str().unwrap())
+        .collect();
+    assert_eq!(types, ["header", "attribution", "policy_evaluation"]);
+    assert_eq!(exported[1]["commit"], first.as_str());
+    assert_eq!(exported[1]["ai_lines"], 2);
+    assert_eq!(exported[1]["tools"][0]["tool"], "mock_ai");
+    assert_eq!(exported[1]["files"][0], "src/lib.rs");
+    assert_eq!(exported[2]["passed"], false);
+    assert!(
+        exported[2]["violations"][0]
+            .as_str()
+            .unwrap()
+            .contains("rule `half`: 100% of the added lines are AI-authored"),
+        "{}",
+        exported[2]
+    );
+
+    file.set_contents(lines!["fn a() {}".ai(), "fn b() {}".ai(), "fn c() {}"]);
+    repo.stage_all_and_commit("Add c").unwrap();
+    repo.git_ai(&["audit", "export", "--range", &range, "--output", trail_arg])
+        .unwrap();
+    let exported = records(&trail);
+    assert_eq!(exported.len(), 6);
+    assert_eq!(exported[5]["seq"], 5);
+    assert_eq!(exported[5]["passed"], true);
+    assert_eq!(
+        exported
+            .iter()
+            .filter(|r| r["type"] == "attribution")
+            .count(),
+        2
+    );
+
+    let output = repo.git_ai(&["audit", "verify", trail_arg]).unwrap();
+    assert!(output.contains("6 record(s) intact"), "{}", output);
+
+    let source = std::fs::read_to_string(&trail).unwrap();
+    std::fs::write(&trail, source.replace("\"ai_lines\":2", "\"ai_lines\":0")).unwrap();
+    let err = repo.git_ai(&["audit", "verify", trail_arg]).unwrap_err();
+    assert!(
+        err.contains("audit trail broken at line 2: hash does not match the record"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_audit_export_seals_with_an_ssh_signature() {
+    let dir = tempfile::tempdir().unwrap();
+    let key = dir.path().join("id_ed25519");
+    let generated = std::process::Command::new("ssh-keygen")
+        .args(["-q", "-t", "ed25519", "-N", "", "-f"])
+        .arg(&key)
+        .status();
+    if !generated.is_ok_and(|status| status.success()) {
+        eprintln!("ssh-keygen is not available, skipping");
+        return;
+    }
+
+    let repo = TestRepo::new();
+    let mut file = repo.filename("a.txt");
+    file.set_contents(lines!["one"]);
+    repo.stage_all_and_commit("Add a").unwrap();
+    file.set_contents(lines!["one", "two".ai()]);
+    repo.stage_all_and_commit("Add two").unwrap();
+    repo.git(&["config", "gpg.format", "ssh"]).unwrap();
+    repo.git(&["config", "user.signingkey", key.to_str().unwrap()])
+        .unwrap();
+
+    let output = repo
+        .git_ai(&["audit", "export", "--range", "HEAD~1..HEAD", "--sign"])
+        .unwrap();
+    let exported: Vec<Value> = output
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    let seal = exported.last().unwrap();
+    assert_eq!(seal["type"], "seal");
+    assert_eq!(seal["format"], "ssh");
+
+    let signature = dir.path().join("seal.sig");
+    std::fs::write(&signature, seal["signature"].as_str().unwrap()).unwrap();
+    let mut check = std::process::Command::new("ssh-keygen")
+        .args(["-Y", "check-novalidate", "-n", "git-ai-audit", "-s"])
+        .arg(&signature)
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    use std::io::Write;
+    check
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(seal["prev_hash"].as_str().unwrap().as_bytes())
+        .unwrap();
+    assert!(check.wait().unwrap().success());
+}
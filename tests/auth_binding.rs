@@ -0,0 +1,40 @@
+#[macro_use]
+mod repos;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_repo_bound_to_profile_refuses_other_profiles() {
+    let repo = TestRepo::new();
+    repo.git(&["config", "git-ai.profile", "work"]).unwrap();
+    // A service token keeps the status command from failing for want of a login
+    let offline = [("GIT_AI_OFFLINE", "1"), ("GIT_AI_TOKEN", "test-token")];
+
+    // The binding picks the profile even when the environment names another
+    let output = repo
+        .git_ai_with_env(
+            &["auth", "status", "--json"],
+            &[
+                ("GIT_AI_OFFLINE", "1"),
+                ("GIT_AI_TOKEN", "test-token"),
+                ("GIT_AI_PROFILE", "personal"),
+            ],
+        )
+        .unwrap();
+    assert!(output.contains(r#""profile":"work""#), "{}", output);
+    assert!(
+        output.contains(r#""binding":{"api_base_url":null,"profile":"work"}"#),
+        "{}",
+        output
+    );
+    assert!(output.contains(r#""binding_error":null"#), "{}", output);
+
+    // Asking for another profile explicitly is flagged
+    let output = repo
+        .git_ai_with_env(&["--profile", "personal", "auth", "status"], &offline)
+        .unwrap();
+    assert!(
+        output.contains("bound to profile 'work' (git-ai.profile), not 'personal'"),
+        "{}",
+        output
+    );
+}
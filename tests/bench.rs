@@ -0,0 +1,51 @@
+mod repos;
+use repos::test_repo::TestRepo;
+use serde_json::Value;
+
+#[test]
+fn test_bench_fails_on_regression_against_baseline() {
+    let repo = TestRepo::new();
+    let dir = tempfile::tempdir().unwrap();
+    let baseline = dir.path().join("baseline.json");
+    let baseline_arg = baseline.to_str().unwrap();
+
+    let output = repo
+        .git_ai(&[
+            "bench",
+            "deep-history",
+            "--quick",
+            "--samples",
+            "1",
+            "--json",
+            "--save-baseline",
+            baseline_arg,
+        ])
+        .unwrap();
+    let line = output.lines().find(|l| l.starts_with('{')).unwrap();
+    let report: Value = serde_json::from_str(line).unwrap();
+    assert_eq!(report["measurements"][0]["scenario"], "deep-history");
+
+    // A baseline ten times faster than this machine can run
+    let mut saved: Value =
+        serde_json::from_str(&std::fs::read_to_string(&baseline).unwrap()).unwrap();
+    let median = saved[0]["median_ns"].as_u64().unwrap();
+    saved[0]["median_ns"] = (median / 10).max(1).into();
+    std::fs::write(&baseline, saved.to_string()).unwrap();
+
+    let err = repo
+        .git_ai(&[
+            "bench",
+            "deep-history",
+            "--quick",
+            "--samples",
+            "1",
+            "--baseline",
+            baseline_arg,
+        ])
+        .unwrap_err();
+    assert!(
+        err.contains("More than 10% slower than the baseline: deep-history"),
+        "{}",
+        err
+    );
+}
@@ -0,0 +1,52 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use serde_json::Value;
+
+/// The JSON array `--annotations` prints before the summary line on stderr
+fn annotations(output: &str) -> Vec<Value> {
+    let mut stream = serde_json::Deserializer::from_str(output).into_iter::<Value>();
+    match stream.next() {
+        Some(Ok(Value::Array(annotations))) => annotations,
+        other => panic!("no annotations in {:?}: {}", other, output),
+    }
+}
+
+#[test]
+fn test_ci_gate_annotates_ai_heavy_hunks() {
+    let mut repo = TestRepo::new();
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["readme"]);
+    let base = repo.stage_all_and_commit("Initial").unwrap().commit_sha;
+
+    let mut auth = repo.filename("src/auth.rs");
+    auth.set_contents(lines!["fn login() {}".ai(), "fn logout() {}".ai()]);
+    readme.set_contents(lines!["readme", "more docs".human()]);
+    repo.stage_all_and_commit("Add login").unwrap();
+
+    let range = format!("{}..HEAD", base);
+    let output = repo.git_ai(&["ci-gate", &range, "--annotations"]).unwrap();
+    assert!(
+        output.contains("1 commit(s) pass push_policy"),
+        "{}",
+        output
+    );
+    let found = annotations(&output);
+    assert_eq!(found.len(), 1, "{}", output);
+    assert_eq!(found[0]["path"], "src/auth.rs");
+    assert_eq!(found[0]["start_line"], 1);
+    assert_eq!(found[0]["end_line"], 2);
+    assert_eq!(found[0]["annotation_level"], "warning");
+    assert_eq!(found[0]["title"], "100% AI-authored");
+
+    repo.patch_git_ai_config(|patch| {
+        patch.push_policy = Some(git_ai::authorship::push_policy::PushPolicy {
+            protected_paths: vec!["src/auth*".to_string()],
+            ..Default::default()
+        });
+    });
+    let err = repo.git_ai(&["ci-gate", &range]).unwrap_err();
+    assert!(err.contains("ci-gate failed"), "{}", err);
+    assert!(err.contains("Add login: AI-authored lines in protected path src/auth.rs"));
+}
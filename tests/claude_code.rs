@@ -366,3 +366,79 @@ fn test_user_text_content_blocks_are_parsed_correctly() {
         "Second message should be Assistant"
     );
 }
+
+#[test]
+fn test_claude_preset_stop_hook_is_ai_checkpoint_with_model() {
+    let hook_input = r##"{
+        "cwd": "/Users/svarlamov/projects/testing-git",
+        "hook_event_name": "Stop",
+        "session_id": "23aad27c-175d-427f-ac5f-a6830b8e6e65",
+        "stop_hook_active": false,
+        "transcript_path": "tests/fixtures/example-claude-code.jsonl"
+    }"##;
+
+    let result = ClaudePreset
+        .run(AgentCheckpointFlags {
+            hook_input: Some(hook_input.to_string()),
+        })
+        .expect("Failed to run ClaudePreset");
+
+    assert_eq!(
+        result.checkpoint_kind,
+        git_ai::authorship::working_log::CheckpointKind::AiAgent
+    );
+    assert_eq!(result.agent_id.model, "claude-sonnet-4-20250514");
+    // No file list: only files already tracked in the working log are re-read
+    assert!(result.edited_filepaths.is_none());
+}
+
+#[test]
+fn test_integrate_claude_code_writes_settings() {
+    use repos::test_repo::TestRepo;
+
+    let repo = TestRepo::new();
+    let home = tempfile::tempdir().unwrap();
+    let home_str = home.path().to_str().unwrap();
+    let env = [("HOME", home_str)];
+
+    let output = repo
+        .git_ai_with_env(&["integrate", "claude-code"], &env)
+        .unwrap();
+    assert!(
+        output.contains("Claude Code: hooks installed"),
+        "{}",
+        output
+    );
+
+    let settings_path = home.path().join(".claude").join("settings.json");
+    let settings: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&settings_path).unwrap()).unwrap();
+    for event in ["PreToolUse", "PostToolUse", "Stop"] {
+        let command = settings["hooks"][event][0]["hooks"][0]["command"]
+            .as_str()
+            .unwrap_or_default();
+        assert_eq!(
+            command, "git-ai checkpoint claude --hook-input stdin",
+            "{}",
+            event
+        );
+    }
+    assert!(settings["hooks"]["Stop"][0].get("matcher").is_none());
+
+    let output = repo
+        .git_ai_with_env(&["integrate", "claude-code"], &env)
+        .unwrap();
+    assert!(output.contains("already up to date"), "{}", output);
+
+    let output = repo
+        .git_ai_with_env(&["integrate", "claude-code", "--uninstall"], &env)
+        .unwrap();
+    assert!(output.contains("hooks removed"), "{}", output);
+    let settings = fs::read_to_string(&settings_path).unwrap();
+    assert!(!settings.contains("git-ai checkpoint"), "{}", settings);
+
+    let err = repo
+        .git_ai_with_env(&["integrate", "nope"], &env)
+        .unwrap_err();
+    assert!(err.contains("Unknown agent 'nope'"), "{}", err);
+}
@@ -0,0 +1,113 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+
+fn write_rollout(codex_home: &std::path::Path, cwd: &str) {
+    let day = codex_home.join("sessions/2025/09/01");
+    fs::create_dir_all(&day).unwrap();
+    let lines = [
+        serde_json::json!({"timestamp": "2025-09-01T10:00:00Z", "type": "session_meta",
+            "payload": {"id": "0199-test-session", "cwd": cwd}}),
+        serde_json::json!({"timestamp": "2025-09-01T10:00:01Z", "type": "turn_context",
+            "payload": {"cwd": cwd, "model": "gpt-5-codex"}}),
+        serde_json::json!({"timestamp": "2025-09-01T10:00:02Z", "type": "response_item",
+            "payload": {"type": "message", "role": "user",
+                "content": [{"type": "input_text", "text": "add a greeting"}]}}),
+        serde_json::json!({"timestamp": "2025-09-01T10:00:03Z", "type": "response_item",
+            "payload": {"type": "message", "role": "assistant",
+                "content": [{"type": "output_text", "text": "Done."}]}}),
+    ];
+    let content: Vec<String> = lines.iter().map(|l| l.to_string()).collect();
+    fs::write(
+        day.join("rollout-2025-09-01T10-00-00-0199-test-session.jsonl"),
+        content.join("\n"),
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_codex_turn_checkpoint_is_attributed() {
+    let repo = TestRepo::new();
+    let codex_home = tempfile::tempdir().unwrap();
+    let cwd = repo.canonical_path().to_string_lossy().to_string();
+    write_rollout(codex_home.path(), &cwd);
+
+    let mut file = repo.filename("greet.txt");
+    file.set_contents(lines!["hello"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    fs::write(repo.path().join("greet.txt"), "hello\nhello from codex\n").unwrap();
+    let notification = serde_json::json!({
+        "type": "agent-turn-complete",
+        "turn-id": "1",
+        "cwd": cwd,
+        "input-messages": ["add a greeting"],
+        "last-assistant-message": "Done.",
+    })
+    .to_string();
+    let codex_home_str = codex_home.path().to_string_lossy().to_string();
+    repo.git_ai_with_env(
+        &["checkpoint", "codex", "--hook-input", &notification],
+        &[("CODEX_HOME", &codex_home_str)],
+    )
+    .unwrap();
+
+    let commit = repo.stage_all_and_commit("Add greeting").unwrap();
+    file.assert_lines_and_blame(lines!["hello".human(), "hello from codex".ai()]);
+
+    let prompt = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .values()
+        .next()
+        .expect("Should have a prompt record");
+    assert_eq!(prompt.agent_id.tool, "codex");
+    assert_eq!(prompt.agent_id.id, "0199-test-session");
+    assert_eq!(prompt.agent_id.model, "gpt-5-codex");
+    assert_eq!(prompt.messages.len(), 2);
+}
+
+#[test]
+fn test_integrate_codex_writes_notify() {
+    let repo = TestRepo::new();
+    let codex_home = tempfile::tempdir().unwrap();
+    let config_path = codex_home.path().join("config.toml");
+    fs::write(
+        &config_path,
+        "model = \"gpt-5-codex\"\n\n[mcp_servers.docs]\ncommand = \"docs\"\n",
+    )
+    .unwrap();
+    let codex_home_str = codex_home.path().to_string_lossy().to_string();
+    let env = [("CODEX_HOME", codex_home_str.as_str())];
+
+    let output = repo.git_ai_with_env(&["integrate", "codex"], &env).unwrap();
+    assert!(output.contains("Codex: hooks installed"), "{}", output);
+    let config = fs::read_to_string(&config_path).unwrap();
+    let notify = config
+        .lines()
+        .position(|l| l.starts_with("notify = ["))
+        .expect("notify should be set");
+    let table = config
+        .lines()
+        .position(|l| l.starts_with("[mcp_servers.docs]"))
+        .unwrap();
+    assert!(notify < table, "{}", config);
+    assert!(
+        config.contains(r#""checkpoint", "codex", "--hook-input"]"#),
+        "{}",
+        config
+    );
+
+    let output = repo.git_ai_with_env(&["integrate", "codex"], &env).unwrap();
+    assert!(output.contains("already up to date"), "{}", output);
+
+    repo.git_ai_with_env(&["integrate", "codex", "--uninstall"], &env)
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(&config_path).unwrap(),
+        "model = \"gpt-5-codex\"\n\n[mcp_servers.docs]\ncommand = \"docs\"\n"
+    );
+}
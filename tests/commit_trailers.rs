@@ -0,0 +1,136 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+
+fn run_prepare_commit_msg(repo: &TestRepo, message: &str) -> String {
+    let msg_path = repo.path().join(".git").join("COMMIT_EDITMSG");
+    fs::write(&msg_path, message).unwrap();
+    repo.git_ai(&[
+        "hook",
+        "prepare-commit-msg",
+        msg_path.to_str().unwrap(),
+        "message",
+    ])
+    .unwrap();
+    fs::read_to_string(&msg_path).unwrap()
+}
+
+#[test]
+fn test_prepare_commit_msg_appends_ai_trailers_when_enabled() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.commit_trailers = Some(true);
+    });
+
+    let mut file = repo.filename("test.txt");
+    file.set_contents(lines!["human 1", "human 2"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    file.set_contents(lines!["human 1", "human 2", "ai 1".ai(), "ai 2".ai()]);
+    repo.git(&["add", "-A"]).unwrap();
+
+    let message = run_prepare_commit_msg(&repo, "Add lines\n");
+    assert!(message.starts_with("Add lines\n\nAI-Assisted: "));
+    assert!(message.ends_with("%\nAI-Tools: mock_ai\n"));
+
+    // Running the hook again must not duplicate trailers
+    assert_eq!(run_prepare_commit_msg(&repo, &message), message);
+}
+
+#[test]
+fn test_prepare_commit_msg_is_noop_when_disabled() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+    file.set_contents(lines!["human 1"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    file.set_contents(lines!["human 1", "ai 1".ai()]);
+    repo.git(&["add", "-A"]).unwrap();
+
+    assert_eq!(run_prepare_commit_msg(&repo, "Add line\n"), "Add line\n");
+}
+
+#[test]
+fn test_fetch_imports_authorship_from_trailers() {
+    let (local, upstream) = TestRepo::new_with_remote();
+
+    // Another contributor, without git-ai, pushes a commit carrying AI trailers
+    let contributor_path =
+        std::env::temp_dir().join(format!("git-ai-trailer-contributor-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&contributor_path);
+    let run = |args: &[&str]| {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(&contributor_path)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).to_string()
+    };
+    std::process::Command::new("git")
+        .args([
+            "clone",
+            upstream.path().to_str().unwrap(),
+            contributor_path.to_str().unwrap(),
+        ])
+        .output()
+        .unwrap();
+    run(&["config", "user.name", "Contributor"]);
+    run(&["config", "user.email", "contributor@example.com"]);
+    fs::write(contributor_path.join("a.txt"), "one\ntwo\nthree\nfour\n").unwrap();
+    run(&["add", "a.txt"]);
+    run(&[
+        "commit",
+        "-m",
+        "Add a.txt\n\nAI-Assisted: 50%\nAI-Tools: claude",
+    ]);
+    run(&["push", "origin", "HEAD"]);
+    let sha = run(&["rev-parse", "HEAD"]).trim().to_string();
+
+    local.git(&["fetch", "origin"]).unwrap();
+
+    let note = local.git_og(&["notes", "--ref=ai", "show", &sha]).unwrap();
+    assert!(note.contains("a.txt"), "note: {}", note);
+    assert!(note.contains("\"tool\": \"claude\""), "note: {}", note);
+
+    let _ = fs::remove_dir_all(&contributor_path);
+}
+
+#[test]
+fn test_prepare_commit_msg_adds_pending_summary_comment() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.commit_summary = Some(true);
+    });
+
+    let mut file = repo.filename("test.txt");
+    file.set_contents(lines!["human 1"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    file.set_contents(lines!["human 1", "ai 1".ai(), "ai 2".ai()]);
+    repo.git(&["add", "-A"]).unwrap();
+
+    let msg_path = repo.path().join(".git").join("COMMIT_EDITMSG");
+    let template = "\n# Please enter the commit message for your changes.\n";
+    fs::write(&msg_path, template).unwrap();
+    repo.git_ai(&["hook", "prepare-commit-msg", msg_path.to_str().unwrap()])
+        .unwrap();
+    let message = fs::read_to_string(&msg_path).unwrap();
+
+    let summary_line = message
+        .lines()
+        .find(|l| l.starts_with("# AI changes pending:"))
+        .expect("summary comment should be added");
+    assert!(summary_line.contains("lines from mock_ai"));
+    assert!(message.ends_with("# Please enter the commit message for your changes.\n"));
+
+    // `-m` messages keep comment lines, so nothing is added there
+    assert_eq!(run_prepare_commit_msg(&repo, "Add lines\n"), "Add lines\n");
+}
@@ -572,3 +572,108 @@ fn test_cursor_e2e_with_resync() {
 
     // The temp directory and database will be automatically cleaned up when temp_dir goes out of scope
 }
+
+#[test]
+fn test_cursor_model_falls_back_to_database_when_hook_omits_it() {
+    use std::fs;
+
+    let repo = TestRepo::new();
+    let db_path = fixture_path("cursor_test.vscdb");
+    let db_path_str = db_path.to_string_lossy().to_string();
+
+    let file_path = repo.path().join("main.rs");
+    fs::write(&file_path, "fn main() {}\n").unwrap();
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    fs::write(&file_path, "fn main() {}\n// from Cursor\n").unwrap();
+    let hook_input = serde_json::json!({
+        "conversation_id": TEST_CONVERSATION_ID,
+        "workspace_roots": [repo.canonical_path().to_string_lossy().to_string()],
+        "hook_event_name": "afterFileEdit",
+        "file_path": file_path.to_string_lossy().to_string(),
+    })
+    .to_string();
+    repo.git_ai_with_env(
+        &["checkpoint", "cursor", "--hook-input", &hook_input],
+        &[("GIT_AI_CURSOR_GLOBAL_DB_PATH", &db_path_str)],
+    )
+    .unwrap();
+
+    let commit = repo.stage_all_and_commit("Add cursor edits").unwrap();
+    let prompt_record = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .values()
+        .next()
+        .expect("Should have a prompt record");
+    assert_eq!(prompt_record.agent_id.tool, "cursor");
+    assert_eq!(prompt_record.agent_id.model, "gpt-5");
+}
+
+#[test]
+fn test_cursor_edit_attributed_without_local_state_database() {
+    use std::fs;
+
+    let repo = TestRepo::new();
+    let missing_db = repo.path().join("missing-state.vscdb");
+    let missing_db_str = missing_db.to_string_lossy().to_string();
+
+    let file_path = repo.path().join("main.rs");
+    fs::write(&file_path, "fn main() {}\n").unwrap();
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    fs::write(&file_path, "fn main() {}\n// from Cursor\n").unwrap();
+    let hook_input = serde_json::json!({
+        "conversation_id": "conversation-without-db",
+        "workspace_roots": [repo.canonical_path().to_string_lossy().to_string()],
+        "hook_event_name": "afterFileEdit",
+        "file_path": file_path.to_string_lossy().to_string(),
+        "model": "claude-4-sonnet",
+    })
+    .to_string();
+    repo.git_ai_with_env(
+        &["checkpoint", "cursor", "--hook-input", &hook_input],
+        &[("GIT_AI_CURSOR_GLOBAL_DB_PATH", &missing_db_str)],
+    )
+    .unwrap();
+
+    repo.stage_all_and_commit("Add cursor edits").unwrap();
+    let mut file = repo.filename("main.rs");
+    file.assert_lines_and_blame(lines!["fn main() {}".human(), "// from Cursor".ai()]);
+}
+
+#[test]
+fn test_integrate_cursor_writes_hooks() {
+    use std::fs;
+
+    let repo = TestRepo::new();
+    let home = tempfile::tempdir().unwrap();
+    let home_str = home.path().to_str().unwrap();
+    let env = [("HOME", home_str)];
+
+    let output = repo
+        .git_ai_with_env(&["integrate", "cursor"], &env)
+        .unwrap();
+    assert!(output.contains("Cursor: hooks installed"), "{}", output);
+
+    let hooks_path = home.path().join(".cursor").join("hooks.json");
+    let hooks: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&hooks_path).unwrap()).unwrap();
+    for event in ["beforeSubmitPrompt", "afterFileEdit"] {
+        let command = hooks["hooks"][event][0]["command"]
+            .as_str()
+            .unwrap_or_default();
+        assert!(
+            command.ends_with("checkpoint cursor --hook-input stdin"),
+            "{}: {}",
+            event,
+            command
+        );
+    }
+
+    let output = repo
+        .git_ai_with_env(&["integrate", "cursor"], &env)
+        .unwrap();
+    assert!(output.contains("already up to date"), "{}", output);
+}
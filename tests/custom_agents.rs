@@ -0,0 +1,164 @@
+#[macro_use]
+mod repos;
+use git_ai::commands::checkpoint_agent::agent_registry::CustomAgent;
+use repos::test_repo::TestRepo;
+use std::fs;
+
+fn acme_repo() -> TestRepo {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.custom_agents = Some(vec![CustomAgent {
+            name: "acme-bot".to_string(),
+            display_name: Some("Acme Bot".to_string()),
+            detect_env: vec!["ACME_BOT_SESSION".to_string()],
+            default_model: Some("acme-large".to_string()),
+        }]);
+    });
+    let mut file = repo.filename("app.txt");
+    file.set_contents(lines!["base"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    repo
+}
+
+fn head_prompt(repo: &TestRepo) -> serde_json::Value {
+    let note = repo.git_og(&["notes", "--ref=ai", "show", "HEAD"]).unwrap();
+    let json = &note[note.find("---").unwrap() + 3..];
+    let note: serde_json::Value = serde_json::from_str(json.trim()).unwrap();
+    note["prompts"]
+        .as_object()
+        .unwrap()
+        .values()
+        .next()
+        .unwrap()
+        .clone()
+}
+
+#[test]
+fn test_checkpoint_named_custom_agent() {
+    let repo = acme_repo();
+    fs::write(repo.path().join("app.txt"), "base\nfrom acme\n").unwrap();
+    repo.git_ai(&["checkpoint", "Acme-Bot", "app.txt"]).unwrap();
+
+    let status = repo.git_ai(&["status"]).unwrap();
+    assert!(status.contains("Acme Bot acme-large"), "{}", status);
+
+    repo.stage_all_and_commit("Acme edit").unwrap();
+    let prompt = head_prompt(&repo);
+    assert_eq!(prompt["agent_id"]["tool"], "acme-bot");
+    assert_eq!(prompt["agent_id"]["model"], "acme-large");
+    assert_eq!(prompt["accepted_lines"], 1);
+}
+
+#[test]
+fn test_checkpoint_detects_custom_agent_from_env() {
+    let repo = acme_repo();
+    fs::write(repo.path().join("app.txt"), "base\nfrom acme\n").unwrap();
+    fs::write(repo.path().join("new.txt"), "new file\n").unwrap();
+    repo.git_ai_with_env(&["checkpoint"], &[("ACME_BOT_SESSION", "run-42")])
+        .unwrap();
+
+    repo.stage_all_and_commit("Acme edit").unwrap();
+    let prompt = head_prompt(&repo);
+    assert_eq!(prompt["agent_id"]["tool"], "acme-bot");
+    assert_eq!(prompt["agent_id"]["id"], "run-42");
+    assert_eq!(prompt["accepted_lines"], 2);
+
+    // Without the variable a plain checkpoint stays human
+    fs::write(repo.path().join("app.txt"), "base\nfrom acme\nby me\n").unwrap();
+    repo.git_ai(&["checkpoint"]).unwrap();
+    repo.stage_all_and_commit("My edit").unwrap();
+    let note = repo.git_og(&["notes", "--ref=ai", "show", "HEAD"]).unwrap();
+    assert!(!note.contains("acme-bot"), "{}", note);
+}
+
+#[test]
+fn test_machine_identity_owns_unattributed_commits() {
+    let repo = acme_repo();
+    let machine = [("GIT_AI_MACHINE_IDENTITY", "acme-bot")];
+    fs::write(repo.path().join("app.txt"), "base\nautofix\n").unwrap();
+    fs::write(repo.path().join("new.txt"), "generated\n").unwrap();
+    repo.git(&["add", "-A"]).unwrap();
+
+    // No checkpoint ran: the bot's commit is still credited to it, not to the git user
+    repo.commit_with_env("Autofix", &machine, None).unwrap();
+    let prompt = head_prompt(&repo);
+    assert_eq!(prompt["agent_id"]["tool"], "acme-bot");
+    assert_eq!(prompt["agent_id"]["model"], "acme-large");
+    assert_eq!(prompt["accepted_lines"], 2);
+
+    // Unregistered identities are ignored
+    fs::write(repo.path().join("app.txt"), "base\nautofix\nby me\n").unwrap();
+    repo.git(&["add", "-A"]).unwrap();
+    repo.commit_with_env("My edit", &[("GIT_AI_MACHINE_IDENTITY", "nobody")], None)
+        .unwrap();
+    let note = repo.git_og(&["notes", "--ref=ai", "show", "HEAD"]).unwrap();
+    assert!(
+        !note.contains("acme-bot") && !note.contains("nobody"),
+        "{}",
+        note
+    );
+}
+
+#[test]
+fn test_checkpoint_detects_known_agent_from_env() {
+    let repo = acme_repo();
+    fs::write(repo.path().join("app.txt"), "base\nfrom claude\n").unwrap();
+    repo.git_ai_with_env(
+        &["checkpoint"],
+        &[
+            ("GIT_AI_AGENT_DETECTION", "true"),
+            ("CLAUDECODE", "1"),
+            ("ANTHROPIC_MODEL", "claude-sonnet-4-5"),
+            ("CLAUDE_CODE_SESSION_ID", "session-7"),
+        ],
+    )
+    .unwrap();
+
+    repo.stage_all_and_commit("Claude edit").unwrap();
+    let prompt = head_prompt(&repo);
+    assert_eq!(prompt["agent_id"]["tool"], "claude");
+    assert_eq!(prompt["agent_id"]["model"], "claude-sonnet-4-5");
+    assert_eq!(prompt["agent_id"]["id"], "session-7");
+}
+
+#[cfg(unix)]
+#[test]
+fn test_checkpoint_consults_external_detectors() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let repo = acme_repo();
+    let bin_dir = tempfile::tempdir().unwrap();
+    let detector = bin_dir.path().join("git-ai-detector-acme");
+    fs::write(
+        &detector,
+        "#!/bin/sh\n\
+         input=$(cat)\n\
+         case \"$input\" in *working_dir*) ;; *) exit 1 ;; esac\n\
+         [ -n \"$ACME_CLOUD\" ] || exit 0\n\
+         echo '{\"tool\": \"acme-cloud\", \"model\": \"acme-xl\", \"session_id\": \"cloud-1\"}'\n",
+    )
+    .unwrap();
+    fs::set_permissions(&detector, fs::Permissions::from_mode(0o755)).unwrap();
+    let path = format!(
+        "{}:{}",
+        bin_dir.path().display(),
+        std::env::var("PATH").unwrap_or_default()
+    );
+
+    fs::write(repo.path().join("app.txt"), "base\nfrom the cloud\n").unwrap();
+    repo.git_ai_with_env(&["checkpoint"], &[("PATH", &path), ("ACME_CLOUD", "1")])
+        .unwrap();
+    repo.stage_all_and_commit("Cloud edit").unwrap();
+    let prompt = head_prompt(&repo);
+    assert_eq!(prompt["agent_id"]["tool"], "acme-cloud");
+    assert_eq!(prompt["agent_id"]["model"], "acme-xl");
+    assert_eq!(prompt["agent_id"]["id"], "cloud-1");
+
+    // A detector that prints nothing leaves the checkpoint human
+    fs::write(repo.path().join("app.txt"), "base\nfrom the cloud\nby me\n").unwrap();
+    repo.git_ai_with_env(&["checkpoint"], &[("PATH", &path)])
+        .unwrap();
+    repo.stage_all_and_commit("My edit").unwrap();
+    let note = repo.git_og(&["notes", "--ref=ai", "show", "HEAD"]).unwrap();
+    assert!(!note.contains("acme-cloud"), "{}", note);
+}
@@ -0,0 +1,451 @@
+#![cfg(unix)]
+
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::{TestRepo, get_binary_path};
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::process::{Child, Command};
+use std::thread::sleep;
+use std::time::Duration;
+
+fn agent_socket(socket: &Path) -> std::path::PathBuf {
+    socket.with_file_name("agent.sock")
+}
+
+struct Daemon {
+    child: Child,
+    reader: BufReader<UnixStream>,
+    writer: UnixStream,
+    next_id: u64,
+}
+
+impl Daemon {
+    fn start(repo: &TestRepo, socket: &Path, envs: &[(&str, &str)]) -> Self {
+        Self::start_with_args(repo, socket, &[], envs)
+    }
+
+    fn start_with_args(
+        repo: &TestRepo,
+        socket: &Path,
+        args: &[&str],
+        envs: &[(&str, &str)],
+    ) -> Self {
+        let mut child = Command::new(get_binary_path())
+            .args(["daemon", "--socket", socket.to_str().unwrap()])
+            .arg("--agent-socket")
+            .arg(agent_socket(socket))
+            .args(args)
+            .current_dir(repo.path())
+            .env("GIT_AI_TEST_DB_PATH", repo.test_db_path())
+            .envs(envs.iter().copied())
+            .spawn()
+            .expect("failed to spawn daemon");
+
+        for _ in 0..100 {
+            if let Ok(stream) = UnixStream::connect(socket) {
+                return Daemon {
+                    child,
+                    reader: BufReader::new(stream.try_clone().unwrap()),
+                    writer: stream,
+                    next_id: 0,
+                };
+            }
+            sleep(Duration::from_millis(50));
+        }
+        let _ = child.kill();
+        let _ = child.wait();
+        panic!("daemon never started listening");
+    }
+
+    fn call(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let request =
+            json!({"jsonrpc": "2.0", "id": self.next_id, "method": method, "params": params});
+        writeln!(self.writer, "{}", request).unwrap();
+        let mut line = String::new();
+        self.reader.read_line(&mut line).unwrap();
+        let response: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(response["id"], self.next_id);
+        response
+    }
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+fn test_daemon_checkpoint_status_and_blame() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("app.txt");
+    file.set_contents(lines!["human line"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let socket_dir = tempfile::tempdir().unwrap();
+    let socket = socket_dir.path().join("daemon.sock");
+    let mut daemon = Daemon::start(&repo, &socket, &[]);
+    let repo_path = repo.path().to_str().unwrap();
+
+    assert_eq!(daemon.call("ping", json!({}))["result"], "pong");
+
+    std::fs::write(repo.path().join("app.txt"), "human line\nagent line\n").unwrap();
+    let response = daemon.call(
+        "checkpoint",
+        json!({"repo": repo_path, "tool": "mock_ai", "model": "gpt-5", "files": ["app.txt"]}),
+    );
+    assert_eq!(response["result"]["files_edited"], 1, "{}", response);
+
+    let response = daemon.call("status", json!({"repo": repo_path}));
+    let checkpoints = response["result"]["checkpoints"].as_array().unwrap();
+    assert!(!checkpoints.is_empty(), "{}", response);
+    assert_eq!(checkpoints[0]["tool_model"], "Mock_ai gpt-5");
+
+    repo.stage_all_and_commit("Agent edit").unwrap();
+    file.assert_lines_and_blame(lines!["human line".human(), "agent line".ai()]);
+
+    let response = daemon.call(
+        "blame",
+        json!({"repo": repo_path, "file": "app.txt", "start_line": 2, "end_line": 2}),
+    );
+    let lines = response["result"]["lines"].as_array().unwrap();
+    assert_eq!(lines.len(), 1, "{}", response);
+    assert_eq!(lines[0]["line"], 2);
+    assert_eq!(lines[0]["author"], "mock_ai");
+    let prompt_id = lines[0]["prompt_id"].as_str().unwrap();
+    assert_eq!(response["result"]["prompts"][prompt_id]["model"], "gpt-5");
+
+    let response = daemon.call("checkpoint", json!({"repo": repo_path}));
+    assert_eq!(response["error"]["code"], -32602, "{}", response);
+
+    assert_eq!(daemon.call("shutdown", json!({}))["result"], Value::Null);
+    assert!(daemon.child.wait().unwrap().success());
+    assert!(!socket.exists());
+    assert!(!agent_socket(&socket).exists());
+}
+
+fn pasted_block(tag: &str) -> Vec<String> {
+    (1..=8)
+        .map(|i| format!("let {}_{} = compute_something({});", tag, i, i))
+        .collect()
+}
+
+#[test]
+fn test_daemon_flags_pastes_for_review() {
+    let repo = TestRepo::new();
+    let mut kept = repo.filename("kept.rs");
+    kept.set_contents(lines!["fn main() {}"]);
+    let mut dismissed = repo.filename("dismissed.rs");
+    dismissed.set_contents(lines!["fn main() {}"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let socket_dir = tempfile::tempdir().unwrap();
+    let socket = socket_dir.path().join("daemon.sock");
+    let mut daemon = Daemon::start(&repo, &socket, &[("GIT_AI_PASTE_DETECTION", "true")]);
+    let repo_path = repo.path().to_str().unwrap();
+
+    let mut ids = Vec::new();
+    for (file, tag) in [("kept.rs", "kept"), ("dismissed.rs", "gone")] {
+        let block = pasted_block(tag).join("\n") + "\n";
+        std::fs::write(
+            repo.path().join(file),
+            format!("fn main() {{}}\n// typed\n{}", block),
+        )
+        .unwrap();
+
+        // Typing right before the insertion makes it look written by hand
+        let response = daemon.call(
+            "edit",
+            json!({"repo": repo_path, "file": file, "text": "/", "timestamp_ms": 1_000}),
+        );
+        assert_eq!(response["result"]["flagged"], false, "{}", response);
+        let response = daemon.call(
+            "edit",
+            json!({"repo": repo_path, "file": file, "text": block, "timestamp_ms": 2_000}),
+        );
+        assert_eq!(response["result"]["flagged"], false, "{}", response);
+
+        let response = daemon.call(
+            "edit",
+            json!({"repo": repo_path, "file": file, "text": block, "timestamp_ms": 60_000}),
+        );
+        assert_eq!(response["result"]["recorded"], true, "{}", response);
+        ids.push(response["result"]["id"].as_str().unwrap().to_string());
+    }
+
+    let pending: Value =
+        serde_json::from_str(&repo.git_ai(&["review-pending", "--json"]).unwrap()).unwrap();
+    assert_eq!(pending.as_array().unwrap().len(), 2, "{}", pending);
+    assert_eq!(pending[0]["files"], json!(["kept.rs"]));
+
+    repo.git_ai(&["review-pending", "confirm", &ids[0]])
+        .unwrap();
+    repo.git_ai(&["review-pending", "dismiss", &ids[1]])
+        .unwrap();
+    assert!(
+        repo.git_ai(&["review-pending", "dismiss", &ids[1]])
+            .is_err()
+    );
+    let pending: Value =
+        serde_json::from_str(&repo.git_ai(&["review-pending", "--json"]).unwrap()).unwrap();
+    assert_eq!(pending, json!([]));
+
+    repo.stage_all_and_commit("Pasted code").unwrap();
+    let mut expected = vec!["fn main() {}".human(), "// typed".human()];
+    expected.extend(pasted_block("kept").into_iter().map(|line| line.ai()));
+    kept.assert_lines_and_blame(expected);
+    let mut expected = vec!["fn main() {}".human(), "// typed".human()];
+    expected.extend(pasted_block("gone").into_iter().map(|line| line.human()));
+    dismissed.assert_lines_and_blame(expected);
+
+    let trail = repo
+        .git_ai(&["audit", "export", "--range", "HEAD~1..HEAD"])
+        .unwrap();
+    let reclassifications: Vec<Value> = trail
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|record| record["type"] == "reclassification")
+        .collect();
+    assert_eq!(reclassifications.len(), 2, "{}", trail);
+    assert_eq!(reclassifications[0]["action"], "confirm");
+    assert_eq!(reclassifications[0]["files"], json!(["kept.rs"]));
+    assert_eq!(reclassifications[1]["action"], "dismiss");
+    assert_eq!(reclassifications[1]["session"], ids[1].as_str());
+}
+
+#[test]
+fn test_agent_socket_records_reported_edits() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("app.txt");
+    file.set_contents(lines!["human line"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let socket_dir = tempfile::tempdir().unwrap();
+    let socket = socket_dir.path().join("daemon.sock");
+    let _daemon = Daemon::start(&repo, &socket, &[]);
+    let agent = UnixStream::connect(agent_socket(&socket)).unwrap();
+    let mut reader = BufReader::new(agent.try_clone().unwrap());
+    let mut send = |message: Value| {
+        writeln!(&agent, "{}", message).unwrap();
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        serde_json::from_str::<Value>(&line).unwrap()
+    };
+
+    std::fs::write(repo.path().join("app.txt"), "human line\nagent line\n").unwrap();
+    let absolute = repo.path().join("app.txt");
+    let response = send(json!({
+        "tool": "mock_ai",
+        "model": "gpt-5",
+        "files": [{"path": absolute.to_str().unwrap(), "ranges": [[2, 2]]}],
+    }));
+    assert_eq!(response, json!({"ok": true, "files_edited": 1}));
+
+    let response = send(json!({"tool": "mock_ai", "files": ["app.txt"]}));
+    assert_eq!(response["ok"], false, "{}", response);
+    let response = send(json!({"files": ["app.txt"]}));
+    assert_eq!(response["ok"], false, "{}", response);
+
+    repo.stage_all_and_commit("Agent edit").unwrap();
+    file.assert_lines_and_blame(lines!["human line".human(), "agent line".ai()]);
+}
+
+fn post_checkpoint(port: u16, token: &str, body: &Value) -> (u16, Value) {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let body = body.to_string();
+    write!(
+        stream,
+        "POST /checkpoint HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        token,
+        body.len(),
+        body
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let status = response.split_whitespace().nth(1).unwrap().parse().unwrap();
+    let (_, body) = response.split_once("\r\n\r\n").unwrap();
+    (status, serde_json::from_str(body).unwrap())
+}
+
+#[test]
+fn test_http_checkpoint_requires_token() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("app.txt");
+    file.set_contents(lines!["human line"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let socket_dir = tempfile::tempdir().unwrap();
+    let socket = socket_dir.path().join("daemon.sock");
+    let token_path = socket_dir.path().join("daemon.token");
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let port_arg = port.to_string();
+    let _daemon = Daemon::start_with_args(
+        &repo,
+        &socket,
+        &[
+            "--http",
+            &port_arg,
+            "--http-token-file",
+            token_path.to_str().unwrap(),
+        ],
+        &[],
+    );
+    for _ in 0..100 {
+        if TcpStream::connect(("127.0.0.1", port)).is_ok() {
+            break;
+        }
+        sleep(Duration::from_millis(50));
+    }
+    let token = std::fs::read_to_string(&token_path).unwrap();
+    let token = token.trim();
+
+    std::fs::write(repo.path().join("app.txt"), "human line\nagent line\n").unwrap();
+    let params = json!({
+        "repo": repo.path().to_str().unwrap(),
+        "tool": "mock_ai",
+        "model": "gpt-5",
+        "files": ["app.txt"],
+    });
+    let (status, body) = post_checkpoint(port, "wrong", &params);
+    assert_eq!(status, 401, "{}", body);
+    let (status, body) = post_checkpoint(port, token, &params);
+    assert_eq!(status, 200, "{}", body);
+    assert_eq!(body, json!({"files_edited": 1}));
+
+    repo.stage_all_and_commit("Agent edit").unwrap();
+    file.assert_lines_and_blame(lines!["human line".human(), "agent line".ai()]);
+
+    let (status, _) = get_metrics(port, "wrong");
+    assert_eq!(status, 401);
+    let (status, metrics) = get_metrics(port, token);
+    assert_eq!(status, 200, "{}", metrics);
+    let workdir = repo.path().canonicalize().unwrap();
+    assert!(
+        metrics.contains(&format!(
+            "git_ai_surviving_ai_lines{{repo=\"{}\",tool=\"mock_ai\"}} 1\n",
+            workdir.display()
+        )),
+        "{}",
+        metrics
+    );
+    assert!(
+        metrics.contains("git_ai_daemon_checkpoints_total 1\n"),
+        "{}",
+        metrics
+    );
+}
+
+fn get_metrics(port: u16, token: &str) -> (u16, String) {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    write!(
+        stream,
+        "GET /metrics HTTP/1.1\r\nHost: localhost\r\nAuthorization: Bearer {}\r\n\r\n",
+        token
+    )
+    .unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    let status = response.split_whitespace().nth(1).unwrap().parse().unwrap();
+    let (_, body) = response.split_once("\r\n\r\n").unwrap();
+    (status, body.to_string())
+}
+
+#[test]
+fn test_daemon_precomputes_blame() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("app.txt");
+    file.set_contents(lines!["human line"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let socket_dir = tempfile::tempdir().unwrap();
+    let socket = socket_dir.path().join("daemon.sock");
+    let mut daemon = Daemon::start_with_args(&repo, &socket, &["--no-precompute"], &[]);
+    let repo_path = repo.path().to_str().unwrap();
+
+    std::fs::write(repo.path().join("app.txt"), "human line\nagent line\n").unwrap();
+    let response = daemon.call(
+        "checkpoint",
+        json!({"repo": repo_path, "tool": "mock_ai", "model": "gpt-5", "files": ["app.txt"]}),
+    );
+    assert_eq!(response["result"]["files_edited"], 1, "{}", response);
+    repo.stage_all_and_commit("Agent edit").unwrap();
+
+    let response = daemon.call("precompute", json!({}));
+    assert_eq!(response["result"], json!({"repos": 1, "files": 1}));
+    // Nothing changed since
+    let response = daemon.call("precompute", json!({}));
+    assert_eq!(response["result"], json!({"repos": 1, "files": 0}));
+
+    let response = daemon.call(
+        "blame",
+        json!({"repo": repo_path, "file": "app.txt", "start_line": 2, "end_line": 2}),
+    );
+    let lines = response["result"]["lines"].as_array().unwrap();
+    assert_eq!(lines.len(), 1, "{}", response);
+    assert_eq!(lines[0]["author"], "mock_ai");
+    let prompts = response["result"]["prompts"].as_object().unwrap();
+    assert_eq!(prompts.len(), 1, "{}", response);
+
+    // Editing the file makes its blame stale
+    std::fs::write(
+        repo.path().join("app.txt"),
+        "human line\nagent line\nmore\n",
+    )
+    .unwrap();
+    let response = daemon.call("precompute", json!({}));
+    assert_eq!(response["result"], json!({"repos": 1, "files": 1}));
+    let response = daemon.call("blame", json!({"repo": repo_path, "file": "app.txt"}));
+    assert_eq!(
+        response["result"]["lines"].as_array().unwrap().len(),
+        3,
+        "{}",
+        response
+    );
+}
+
+/// A precomputed blame is stale once the authorship notes move, even if HEAD didn't
+#[test]
+fn test_daemon_blame_follows_authorship_notes() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("app.txt");
+    file.set_contents(lines!["human line", "agent line".ai()]);
+    repo.stage_all_and_commit("Agent edit").unwrap();
+
+    let socket_dir = tempfile::tempdir().unwrap();
+    let socket = socket_dir.path().join("daemon.sock");
+    let mut daemon = Daemon::start_with_args(&repo, &socket, &["--no-precompute"], &[]);
+    let repo_path = repo.path().to_str().unwrap();
+    daemon.call("status", json!({"repo": repo_path}));
+
+    let response = daemon.call("precompute", json!({}));
+    assert_eq!(response["result"], json!({"repos": 1, "files": 1}));
+    let response = daemon.call("blame", json!({"repo": repo_path, "file": "app.txt"}));
+    assert_eq!(
+        response["result"]["lines"][1]["author"], "mock_ai",
+        "{}",
+        response
+    );
+
+    repo.git_og(&["notes", "--ref=ai", "remove", "HEAD"])
+        .unwrap();
+    let response = daemon.call("precompute", json!({}));
+    assert_eq!(response["result"], json!({"repos": 1, "files": 1}));
+    let response = daemon.call("blame", json!({"repo": repo_path, "file": "app.txt"}));
+    assert_ne!(
+        response["result"]["lines"][1]["author"], "mock_ai",
+        "{}",
+        response
+    );
+}
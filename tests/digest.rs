@@ -0,0 +1,55 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_digest_compares_with_previous_week() {
+    let repo = TestRepo::new();
+    let ten_days_ago = (chrono::Utc::now() - chrono::Duration::days(10)).to_rfc3339();
+    let dates = [
+        ("GIT_AUTHOR_DATE", ten_days_ago.as_str()),
+        ("GIT_COMMITTER_DATE", ten_days_ago.as_str()),
+    ];
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["readme".human(), "docs".human()]);
+    repo.git(&["add", "-A"]).unwrap();
+    repo.commit_with_env("Initial", &dates, None).unwrap();
+
+    let mut auth = repo.filename("src/auth/login.rs");
+    auth.set_contents(lines![
+        "fn login() {}".ai(),
+        "fn logout() {}".ai(),
+        "// reviewed".human()
+    ]);
+    readme.set_contents(lines!["readme".human(), "docs".human(), "more".human()]);
+    repo.stage_all_and_commit("Add login").unwrap();
+
+    let output = repo.git_ai(&["digest", "--period", "week"]).unwrap();
+    assert!(
+        output.contains(
+            "**AI share:** 40% of 5 added lines written by AI (previous week: 0%, +40 points)"
+        ),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("**Commits:** 1 (1 the previous week)"),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("- src/auth/: 2 AI lines (66%)"),
+        "{}",
+        output
+    );
+    assert!(output.contains("**New tools:** mock_ai"), "{}", output);
+
+    let html = repo.git_ai(&["digest", "--output", "html"]).unwrap();
+    assert!(
+        html.contains("<li>src/auth/: 2 AI lines (66%)</li>"),
+        "{}",
+        html
+    );
+    assert!(repo.git_ai(&["digest", "--period", "year"]).is_err());
+}
@@ -0,0 +1,93 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use serde_json::{Value, json};
+
+/// The JSON line of `output`, which also has whatever debug builds log to stderr
+fn parse(output: &str) -> Value {
+    let line = output.lines().next().unwrap_or_default();
+    serde_json::from_str(line).unwrap_or_else(|e| panic!("{}: {}", e, output))
+}
+
+fn editor_api(repo: &TestRepo, args: &[&str]) -> Value {
+    let mut full_args = vec!["editor-api"];
+    full_args.extend_from_slice(args);
+    parse(&repo.git_ai(&full_args).unwrap())
+}
+
+#[test]
+fn test_editor_api_record_edit_and_ranges() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("app.txt");
+    file.set_contents(lines!["human line"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let before = editor_api(&repo, &["--pending-summary"]);
+    assert_eq!(before["api_version"], 1);
+    let cursor = before["cursor"].as_str().unwrap().to_string();
+
+    std::fs::write(
+        repo.path().join("app.txt"),
+        "human line\nagent one\nagent two\n",
+    )
+    .unwrap();
+    let edit = json!({"tool": "mock_ai", "model": "gpt-5", "files": ["app.txt"]});
+    let output = repo
+        .git_ai_with_stdin(
+            &["editor-api", "--record-edit"],
+            edit.to_string().as_bytes(),
+        )
+        .unwrap();
+    let recorded = parse(&output);
+    assert_eq!(recorded["files_edited"], 1, "{}", recorded);
+
+    let changed = editor_api(
+        &repo,
+        &["--subscribe", "--since", &cursor, "--timeout", "5"],
+    );
+    assert_eq!(changed["event"], "changed");
+    let idle = editor_api(
+        &repo,
+        &[
+            "--subscribe",
+            "--since",
+            changed["cursor"].as_str().unwrap(),
+            "--timeout",
+            "0",
+        ],
+    );
+    assert_eq!(idle["event"], "timeout");
+
+    let path = repo.path().join("app.txt");
+    let ranges = editor_api(&repo, &["--ranges-for-file", path.to_str().unwrap()]);
+    assert_eq!(ranges["file"], "app.txt");
+    let ranges = ranges["ranges"].as_array().unwrap();
+    assert_eq!(ranges.len(), 1, "{:?}", ranges);
+    assert_eq!(ranges[0]["start_line"], 2);
+    assert_eq!(ranges[0]["end_line"], 3);
+    assert_eq!(ranges[0]["tool"], "mock_ai");
+    assert_eq!(ranges[0]["committed"], false);
+
+    let summary = editor_api(&repo, &["--pending-summary"]);
+    assert_eq!(summary["stats"]["ai_additions"], 2, "{}", summary);
+    assert_eq!(summary["pending_review"], 0);
+
+    repo.stage_all_and_commit("Agent edit").unwrap();
+    file.assert_lines_and_blame(lines![
+        "human line".human(),
+        "agent one".ai(),
+        "agent two".ai()
+    ]);
+    let ranges = editor_api(&repo, &["--ranges-for-file", "app.txt"]);
+    assert_eq!(ranges["ranges"][0]["start_line"], 2);
+    assert_eq!(ranges["ranges"][0]["committed"], true);
+}
+
+#[test]
+fn test_editor_api_reports_errors_as_json() {
+    let repo = TestRepo::new();
+    assert!(repo.git_ai(&["editor-api", "--nope"]).is_err());
+    let output = repo.git_ai_with_stdin(&["editor-api", "--record-edit"], b"[1]");
+    assert!(output.is_err());
+}
@@ -0,0 +1,47 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+/// The code of the JSON error on the last line of stderr, after any debug output
+fn error_code(stderr: &str) -> String {
+    let last_line = stderr.trim().lines().last().unwrap_or_default();
+    let error: serde_json::Value = serde_json::from_str(last_line)
+        .unwrap_or_else(|e| panic!("stderr is not JSON ({}): {}", e, stderr));
+    assert!(error["error"]["message"].is_string(), "{}", stderr);
+    error["error"]["code"].as_str().unwrap().to_string()
+}
+
+#[test]
+fn test_json_error_outside_repository() {
+    let repo = TestRepo::new();
+    let outside = tempfile::tempdir().unwrap();
+
+    let stderr = repo
+        .git_ai_from_working_dir(outside.path(), &["--error-format", "json", "status"])
+        .unwrap_err();
+    assert_eq!(error_code(&stderr), "not_a_repository");
+
+    // Without the flag the message stays for people
+    let stderr = repo
+        .git_ai_from_working_dir(outside.path(), &["status"])
+        .unwrap_err();
+    assert!(stderr.starts_with("Error: "), "{}", stderr);
+}
+
+#[test]
+fn test_json_error_corrupt_working_log() {
+    let repo = TestRepo::new();
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["readme".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    let working_log = repo.current_working_logs();
+    std::fs::create_dir_all(&working_log.dir).unwrap();
+    std::fs::write(working_log.dir.join("checkpoints.jsonl"), "{not json\n").unwrap();
+
+    let stderr = repo
+        .git_ai_with_env(&["status"], &[("GIT_AI_ERROR_FORMAT", "json")])
+        .unwrap_err();
+    assert_eq!(error_code(&stderr), "corrupt_working_log");
+}
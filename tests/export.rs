@@ -0,0 +1,76 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_export_timeseries_counts_added_and_surviving_lines() {
+    let repo = TestRepo::new();
+    let mut lib = repo.filename("src/lib.rs");
+    lib.set_contents(lines![
+        "fn human() {}".human(),
+        "fn ai_one() {}".ai(),
+        "fn ai_two() {}".ai()
+    ]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    // A human rewrites one of the AI lines
+    lib.set_contents(lines![
+        "fn human() {}".human(),
+        "fn ai_one() {}".ai(),
+        "fn rewritten() {}".human()
+    ]);
+    repo.stage_all_and_commit("Rewrite").unwrap();
+
+    let output = repo
+        .git_ai(&["export", "--timeseries", "--bucket", "month"])
+        .unwrap();
+    let rows: Vec<&str> = output
+        .lines()
+        .filter(|line| !line.contains("[git-ai]"))
+        .collect();
+    assert_eq!(rows[0], "bucket,author,tool,added_lines,surviving_lines");
+    let month = chrono::Utc::now().format("%Y-%m").to_string();
+    assert!(
+        rows.contains(&format!("{},Test User,mock_ai,2,1", month).as_str()),
+        "{}",
+        output
+    );
+    assert!(
+        rows.contains(&format!("{},Test User,human,2,2", month).as_str()),
+        "{}",
+        output
+    );
+}
+
+#[test]
+fn test_export_timeseries_anonymize_replaces_authors() {
+    let repo = TestRepo::new();
+    let mut lib = repo.filename("src/lib.rs");
+    lib.set_contents(lines!["fn human() {}".human(), "fn ai() {}".ai()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    let key = [("GIT_AI_ANONYMIZE_KEY", "shared-key")];
+    let export = |envs: &[(&str, &str)]| {
+        repo.git_ai_with_env(&["export", "--timeseries", "--anonymize"], envs)
+            .unwrap()
+            .lines()
+            .filter(|line| !line.contains("[git-ai]"))
+            .map(str::to_string)
+            .collect::<Vec<_>>()
+    };
+    let rows = export(&key);
+    assert_eq!(rows.len(), 3, "{:?}", rows);
+    assert!(
+        rows.iter().all(|row| !row.contains("Test User")),
+        "{:?}",
+        rows
+    );
+    let author = rows[1].split(',').nth(1).unwrap();
+    assert!(author.starts_with("author-"), "{:?}", rows);
+    assert_eq!(rows[2].split(',').nth(1).unwrap(), author);
+
+    assert_eq!(export(&key), rows);
+    let other = export(&[("GIT_AI_ANONYMIZE_KEY", "other-key")]);
+    assert_ne!(other[1].split(',').nth(1).unwrap(), author);
+}
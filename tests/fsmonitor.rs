@@ -0,0 +1,48 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+
+/// Install a fake fsmonitor hook that reports the paths listed in `.git/fsmonitor-changes`
+/// (or "everything" when the file is absent).
+fn install_fake_fsmonitor(repo: &TestRepo) {
+    let git_dir = repo.path().join(".git");
+    fs::write(
+        git_dir.join("fsmonitor.sh"),
+        "#!/bin/sh\nprintf 'token\\0'\nif [ -f .git/fsmonitor-changes ]; then\n  tr '\\n' '\\0' < .git/fsmonitor-changes\nelse\n  printf '/\\0'\nfi\n",
+    )
+    .unwrap();
+    repo.git_og(&["config", "core.fsmonitor", "sh .git/fsmonitor.sh"])
+        .unwrap();
+}
+
+/// Files the fsmonitor hook does not report are not re-read, and their pending
+/// attributions carry through to the commit unchanged.
+#[test]
+fn test_checkpoint_only_rereads_files_reported_by_fsmonitor() {
+    let repo = TestRepo::new();
+    let mut first = repo.filename("first.txt");
+    let mut second = repo.filename("second.txt");
+    first.set_contents(lines!["base"]);
+    second.set_contents(lines!["base"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    install_fake_fsmonitor(&repo);
+    fs::write(repo.path().join("first.txt"), "base\nai one\n").unwrap();
+    fs::write(repo.path().join("second.txt"), "base\nai two\n").unwrap();
+    repo.git_ai(&["checkpoint", "mock_ai"]).unwrap();
+
+    fs::write(repo.path().join("second.txt"), "base\nai two\nhuman\n").unwrap();
+    fs::write(repo.path().join(".git/fsmonitor-changes"), "second.txt\n").unwrap();
+    let output = repo.git_ai(&["checkpoint"]).unwrap();
+    assert!(
+        output.contains("changed 1 file(s)"),
+        "only second.txt should be considered: {}",
+        output
+    );
+
+    repo.stage_all_and_commit("Mixed commit").unwrap();
+    first.assert_lines_and_blame(lines!["base".human(), "ai one".ai()]);
+    second.assert_lines_and_blame(lines!["base".human(), "ai two".ai(), "human".human()]);
+}
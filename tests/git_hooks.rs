@@ -0,0 +1,294 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+fn head_sha(repo: &TestRepo) -> String {
+    repo.git_og(&["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string()
+}
+
+/// Amend with plain git (no proxy), then let the post-rewrite hook migrate authorship.
+#[test]
+fn test_post_rewrite_hook_migrates_authorship_after_amend() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+    file.set_contents(lines!["line 1", "line 2"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    let original = head_sha(&repo);
+
+    file.set_contents(lines!["line 1", "line 2", "ai line".ai()]);
+    repo.git_og(&["add", "-A"]).unwrap();
+    repo.git_og(&["commit", "--amend", "-m", "Initial commit (amended)"])
+        .unwrap();
+    let amended = head_sha(&repo);
+
+    let mapping = format!("{} {}\n", original, amended);
+    repo.git_ai_with_stdin(&["hook", "post-rewrite", "amend"], mapping.as_bytes())
+        .unwrap();
+
+    file.assert_lines_and_blame(lines!["line 1".human(), "line 2".human(), "ai line".ai()]);
+}
+
+#[test]
+fn test_post_rewrite_hook_migrates_authorship_after_rebase() {
+    let repo = TestRepo::new();
+    let mut base = repo.filename("base.txt");
+    base.set_contents(lines!["base"]);
+    repo.stage_all_and_commit("Base").unwrap();
+    let main_branch = repo.current_branch();
+
+    repo.git(&["checkout", "-b", "feature"]).unwrap();
+    let mut feature = repo.filename("feature.txt");
+    feature.set_contents(lines!["human", "ai feature".ai()]);
+    repo.stage_all_and_commit("Feature").unwrap();
+    let original = head_sha(&repo);
+
+    repo.git(&["checkout", &main_branch]).unwrap();
+    base.set_contents(lines!["base", "more base"]);
+    repo.stage_all_and_commit("Main moves on").unwrap();
+
+    repo.git_og(&["checkout", "feature"]).unwrap();
+    repo.git_og(&["rebase", &main_branch]).unwrap();
+    let rebased = head_sha(&repo);
+    assert_ne!(original, rebased);
+
+    let mapping = format!("{} {}\n", original, rebased);
+    repo.git_ai_with_stdin(&["hook", "post-rewrite", "rebase"], mapping.as_bytes())
+        .unwrap();
+
+    feature.assert_lines_and_blame(lines!["human".human(), "ai feature".ai()]);
+}
+
+#[test]
+fn test_post_merge_hook_rebases_pending_checkpoints_onto_new_head() {
+    let repo = TestRepo::new();
+    let mut notes = repo.filename("notes.txt");
+    notes.set_contents(lines!["notes"]);
+    let mut other = repo.filename("other.txt");
+    other.set_contents(lines!["other"]);
+    repo.stage_all_and_commit("Initial").unwrap();
+    let default_branch = repo.current_branch();
+
+    repo.git(&["checkout", "-b", "feature"]).unwrap();
+    other.set_contents(lines!["other", "from feature"]);
+    repo.stage_all_and_commit("Feature work").unwrap();
+    repo.git(&["checkout", &default_branch]).unwrap();
+
+    notes.set_contents(lines!["notes", "ai note".ai()]);
+    // A true merge needs a clean index; keep the AI edit in the working tree only
+    repo.git_og(&["reset", "-q"]).unwrap();
+    repo.git_og(&["merge", "--no-ff", "-m", "Merge feature", "feature"])
+        .unwrap();
+    repo.git_ai(&["hook", "post-merge", "0"]).unwrap();
+
+    repo.stage_all_and_commit("Commit AI note").unwrap();
+    notes.assert_lines_and_blame(lines!["notes".human(), "ai note".ai()]);
+}
+
+#[test]
+fn test_post_checkout_hook_moves_working_log_on_branch_switch() {
+    let repo = TestRepo::new();
+    let mut notes = repo.filename("notes.txt");
+    notes.set_contents(lines!["notes"]);
+    repo.stage_all_and_commit("Initial").unwrap();
+    let default_branch = repo.current_branch();
+
+    repo.git(&["checkout", "-b", "feature"]).unwrap();
+    let mut other = repo.filename("other.txt");
+    other.set_contents(lines!["other"]);
+    repo.stage_all_and_commit("Feature work").unwrap();
+    let feature_head = head_sha(&repo);
+    repo.git(&["checkout", &default_branch]).unwrap();
+    let default_head = head_sha(&repo);
+
+    notes.set_contents(lines!["notes", "ai note".ai()]);
+    repo.git_og(&["checkout", "feature"]).unwrap();
+    repo.git_ai(&["hook", "post-checkout", &default_head, &feature_head, "1"])
+        .unwrap();
+
+    repo.stage_all_and_commit("Commit AI note").unwrap();
+    notes.assert_lines_and_blame(lines!["notes".human(), "ai note".ai()]);
+}
+
+#[test]
+fn test_post_checkout_hook_drops_attribution_for_reset_files() {
+    let repo = TestRepo::new();
+    let mut notes = repo.filename("notes.txt");
+    notes.set_contents(lines!["notes"]);
+    repo.stage_all_and_commit("Initial").unwrap();
+    let head = head_sha(&repo);
+
+    notes.set_contents(lines!["notes", "ai note".ai()]);
+    repo.git_og(&["reset", "-q"]).unwrap();
+    repo.git_og(&["checkout", "--", "notes.txt"]).unwrap();
+    repo.git_ai(&["hook", "post-checkout", &head, &head, "0"])
+        .unwrap();
+
+    assert!(
+        repo.current_working_logs()
+            .all_touched_files()
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_pre_push_hook_blocks_ai_lines_in_protected_paths() {
+    let mut repo = TestRepo::new();
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["readme"]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    let mut auth = repo.filename("src/auth.rs");
+    auth.set_contents(lines!["fn login() {}".ai()]);
+    repo.stage_all_and_commit("Add login").unwrap();
+    let head = head_sha(&repo);
+    let zero = "0".repeat(40);
+    let stdin = format!("refs/heads/main {} refs/heads/main {}\n", head, zero);
+
+    // No policy configured: nothing is checked
+    repo.git_ai_with_stdin(&["hook", "pre-push", "origin", "url"], stdin.as_bytes())
+        .unwrap();
+
+    repo.patch_git_ai_config(|patch| {
+        patch.push_policy = Some(git_ai::authorship::push_policy::PushPolicy {
+            protected_paths: vec!["src/auth*".to_string()],
+            ..Default::default()
+        });
+    });
+    let err = repo
+        .git_ai_with_stdin(&["hook", "pre-push", "origin", "url"], stdin.as_bytes())
+        .unwrap_err();
+    assert!(err.contains("push blocked by policy"));
+    assert!(err.contains("Add login: AI-authored lines in protected path src/auth.rs"));
+    assert!(err.contains("--no-verify"));
+}
+
+/// A hook run through a shim from an older git-ai rewrites the shims for the running binary.
+#[test]
+fn test_outdated_shim_is_refreshed_by_hook() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+    file.set_contents(lines!["line 1"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    let head = head_sha(&repo);
+
+    let hooks_dir = repo.path().join(".git").join("hooks");
+    std::fs::create_dir_all(&hooks_dir).unwrap();
+    let shim_path = hooks_dir.join("post-checkout");
+    std::fs::write(
+        &shim_path,
+        "#!/bin/sh\n# git-ai managed hook (mode: chain, scope: repo, version: 0.0.1). Reinstall with `git-ai install-hooks`.\nexit 0\n",
+    )
+    .unwrap();
+
+    let output = repo
+        .git_ai_with_env(
+            &["hook", "post-checkout", &head, &head, "1"],
+            &[
+                ("GIT_AI_HOOK_VERSION", "0.0.1"),
+                ("GIT_AI_HOOK_SCRIPT", shim_path.to_str().unwrap()),
+            ],
+        )
+        .unwrap();
+    assert!(output.contains("updated"), "output: {}", output);
+
+    let refreshed = std::fs::read_to_string(&shim_path).unwrap();
+    assert!(refreshed.contains(&format!("version: {}", env!("CARGO_PKG_VERSION"))));
+    assert!(refreshed.contains("hook post-checkout"));
+    // The rest of the managed hooks are installed alongside it
+    assert!(hooks_dir.join("pre-push").exists());
+}
+
+/// Moving a branch back with plain `git update-ref` (as GUI clients do to undo a commit)
+/// puts the undone AI lines back into the working log.
+#[test]
+fn test_reference_transaction_hook_restores_attribution_after_undo() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+    file.set_contents(lines!["line 1"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    let base = head_sha(&repo);
+
+    file.set_contents(lines!["line 1", "ai line".ai()]);
+    repo.stage_all_and_commit("AI commit").unwrap();
+    let undone = head_sha(&repo);
+    let branch = format!("refs/heads/{}", repo.current_branch());
+
+    repo.git_og(&["update-ref", &branch, &base]).unwrap();
+    repo.git_ai_with_stdin(
+        &["hook", "reference-transaction", "committed"],
+        format!("{} {} {}\n", undone, base, branch).as_bytes(),
+    )
+    .unwrap();
+
+    // The move is recorded for caches keyed by the refs
+    let record: serde_json::Value = serde_json::from_str(
+        &std::fs::read_to_string(repo.path().join(".git/ai/ref_state.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(record["refs"][&branch], base.as_str());
+
+    repo.stage_all_and_commit("Redo AI commit").unwrap();
+    file.assert_lines_and_blame(lines!["line 1".human(), "ai line".ai()]);
+}
+
+/// `git-ai pre-receive` on the server rejects commits whose attribution has not been
+/// pushed and applies the push policy once it has.
+#[test]
+fn test_pre_receive_enforces_policy_on_server() {
+    let (mut local, upstream) = TestRepo::new_with_remote();
+    local.patch_git_ai_config(|patch| {
+        patch.push_policy = Some(git_ai::authorship::push_policy::PushPolicy {
+            protected_paths: vec!["src/auth*".to_string()],
+            require_attribution: true,
+            ..Default::default()
+        });
+    });
+    let patch_json = serde_json::to_string(local.config_patch.as_ref().unwrap()).unwrap();
+    let hook = upstream.path().join("hooks").join("pre-receive");
+    std::fs::create_dir_all(hook.parent().unwrap()).unwrap();
+    std::fs::write(
+        &hook,
+        format!(
+            "#!/bin/sh\nGIT_AI_TEST_CONFIG_PATCH='{}' GIT_AI_TEST_DB_PATH='{}' exec '{}' pre-receive\n",
+            patch_json,
+            upstream.test_db_path().display(),
+            repos::test_repo::get_binary_path().display()
+        ),
+    )
+    .unwrap();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&hook, std::fs::Permissions::from_mode(0o755)).unwrap();
+    }
+
+    let mut readme = local.filename("README.md");
+    readme.set_contents(lines!["readme"]);
+    local.stage_all_and_commit("Initial").unwrap();
+    let branch = format!("HEAD:refs/heads/{}", local.current_branch());
+
+    // The branch arrives without its note: nothing to attribute the commit to
+    let err = local.git_og(&["push", "origin", &branch]).unwrap_err();
+    assert!(err.contains("push rejected by server policy"), "{}", err);
+    assert!(err.contains("Initial: no git-ai attribution"), "{}", err);
+
+    local.git_og(&["push", "origin", "refs/notes/ai"]).unwrap();
+    local.git_og(&["push", "origin", &branch]).unwrap();
+
+    let mut auth = local.filename("src/auth.rs");
+    auth.set_contents(lines!["fn login() {}".ai()]);
+    local.stage_all_and_commit("Add login").unwrap();
+    local.git_og(&["push", "origin", "refs/notes/ai"]).unwrap();
+    let err = local.git_og(&["push", "origin", &branch]).unwrap_err();
+    assert!(
+        err.contains("Add login: AI-authored lines in protected path src/auth.rs"),
+        "{}",
+        err
+    );
+    assert!(!err.contains("--no-verify"), "{}", err);
+}
@@ -0,0 +1,38 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_graph_writes_calendar_svg() {
+    let repo = TestRepo::new();
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["readme".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+    let mut lib = repo.filename("lib.rs");
+    lib.set_contents(lines![
+        "fn a() {}".ai(),
+        "fn b() {}".ai(),
+        "fn c() {}".human()
+    ]);
+    repo.stage_all_and_commit("Add lib").unwrap();
+
+    let today = repo
+        .git(&["log", "-1", "--format=%ad", "--date=short"])
+        .unwrap()
+        .trim()
+        .to_string();
+    let path = repo.path().join("contrib.svg");
+    let output = repo
+        .git_ai(&["graph", "-o", path.to_str().unwrap()])
+        .unwrap();
+    assert!(output.contains("Wrote contribution graph to"), "{}", output);
+
+    let svg = std::fs::read_to_string(&path).unwrap();
+    assert!(svg.starts_with("<svg"), "{}", svg);
+    assert!(
+        svg.contains(&format!("<title>{}: 2 human, 2 AI lines</title>", today)),
+        "{}",
+        svg
+    );
+}
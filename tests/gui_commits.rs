@@ -0,0 +1,88 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+fn head_sha(repo: &TestRepo) -> String {
+    repo.git_og(&["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string()
+}
+
+/// Commits made with plain git (as a GUI client would) are finalized by the next
+/// git-ai invocation, each from the working log it consumed.
+#[test]
+fn test_commits_made_outside_git_ai_are_reconciled() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+    file.set_contents(lines!["line 1"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    file.set_contents(lines!["line 1", "ai line 2".ai()]);
+    repo.git_og(&["add", "-A"]).unwrap();
+    repo.git_og(&["commit", "-m", "First GUI commit"]).unwrap();
+    let first = head_sha(&repo);
+    assert!(repo.git_og(&["notes", "--ref=ai", "show", &first]).is_err());
+
+    // The next checkpoint finalizes the first commit before recording new edits
+    file.set_contents(lines!["line 1", "ai line 2".ai(), "ai line 3".ai()]);
+    repo.git_og(&["add", "-A"]).unwrap();
+    repo.git_og(&["commit", "-m", "Second GUI commit"]).unwrap();
+    let second = head_sha(&repo);
+
+    let note = repo.git_og(&["notes", "--ref=ai", "show", &first]).unwrap();
+    assert!(note.contains("test.txt"), "note: {}", note);
+    assert!(
+        repo.git_og(&["notes", "--ref=ai", "show", &second])
+            .is_err()
+    );
+
+    // ...and the next proxied git command finalizes the second
+    repo.git(&["status"]).unwrap();
+
+    repo.git_og(&["notes", "--ref=ai", "show", &second])
+        .unwrap();
+    file.assert_lines_and_blame(lines!["line 1".human(), "ai line 2".ai(), "ai line 3".ai()]);
+}
+
+/// Commits by someone else (e.g. fast-forwarded in from a GUI pull) are not given the
+/// local user's pending attributions.
+#[test]
+fn test_commits_by_other_authors_are_not_reconciled() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+    file.set_contents(lines!["line 1"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    file.set_contents(lines!["line 1", "ai line 2".ai()]);
+    repo.git_og(&["add", "-A"]).unwrap();
+    repo.git_og(&[
+        "-c",
+        "user.email=someone@example.com",
+        "commit",
+        "--author",
+        "Someone <someone@example.com>",
+        "-m",
+        "Not mine",
+    ])
+    .unwrap();
+    let sha = head_sha(&repo);
+
+    repo.git(&["status"]).unwrap();
+    assert!(repo.git_og(&["notes", "--ref=ai", "show", &sha]).is_err());
+}
+
+/// A commit of human-only changes stays unattributed.
+#[test]
+fn test_human_only_commits_are_left_alone() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+    file.set_contents(lines!["line 1"]);
+    repo.git_og(&["add", "-A"]).unwrap();
+    repo.git_og(&["commit", "-m", "Untracked commit"]).unwrap();
+    let sha = head_sha(&repo);
+
+    repo.git(&["status"]).unwrap();
+    assert!(repo.git_og(&["notes", "--ref=ai", "show", &sha]).is_err());
+}
@@ -0,0 +1,31 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_status_in_selected_language() {
+    let repo = TestRepo::new();
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["readme".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    let output = repo
+        .git_ai_with_env(&["status"], &[("GIT_AI_LANG", "fr_FR.UTF-8")])
+        .unwrap();
+    assert!(
+        output.contains("Aucun checkpoint enregistré depuis le dernier commit"),
+        "{}",
+        output
+    );
+
+    // Languages without a catalog fall back to English
+    let output = repo
+        .git_ai_with_env(&["status"], &[("GIT_AI_LANG", "pt")])
+        .unwrap();
+    assert!(
+        output.contains("No checkpoints recorded since last commit"),
+        "{}",
+        output
+    );
+}
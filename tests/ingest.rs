@@ -0,0 +1,106 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use serde_json::json;
+
+/// Commit like a remote agent would: plain git, so no checkpoint or note is recorded.
+fn agent_commit(repo: &TestRepo, file: &str, contents: &str, message: &str) -> String {
+    std::fs::write(repo.path().join(file), contents).unwrap();
+    repo.git_og(&["add", file]).unwrap();
+    repo.git_og(&["commit", "-m", message]).unwrap();
+    repo.git_og(&["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string()
+}
+
+#[test]
+fn test_ingest_attributes_commits_named_by_a_webhook() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("app.txt");
+    std::fs::write(repo.path().join("app.txt"), "human line\n").unwrap();
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let first = agent_commit(&repo, "app.txt", "human line\nagent one\n", "Agent 1");
+    let second = agent_commit(
+        &repo,
+        "app.txt",
+        "human line\nagent one\nagent two\n",
+        "Agent 2",
+    );
+
+    let payload = json!({
+        "ref": "refs/heads/copilot/fix-1",
+        "commits": [{"id": first}, {"id": "1234567890abcdef1234567890abcdef12345678"}],
+        "head_commit": {"id": second},
+        "session": {"id": "task-7", "model": "gpt-5"},
+    });
+    let payload_path = repo.path().join("payload.json");
+    std::fs::write(&payload_path, payload.to_string()).unwrap();
+    let payload_arg = payload_path.to_str().unwrap();
+
+    let output = repo
+        .git_ai(&["ingest", "--provider", "copilot", payload_arg, "--dry-run"])
+        .unwrap();
+    assert!(output.contains("Would attribute 2 commit(s)"), "{}", output);
+
+    let output = repo
+        .git_ai(&["ingest", "--provider", "copilot", payload_arg])
+        .unwrap();
+    assert!(
+        output
+            .contains("Attributed 2 commit(s) to github-copilot (0 already attributed, 1 missing)"),
+        "{}",
+        output
+    );
+    file.assert_lines_and_blame(lines![
+        "human line".human(),
+        "agent one".ai(),
+        "agent two".ai(),
+    ]);
+
+    let note = repo
+        .git_og(&["notes", "--ref=ai", "show", &second])
+        .unwrap();
+    assert!(note.contains("\"id\": \"task-7\""), "{}", note);
+    assert!(note.contains("\"model\": \"gpt-5\""), "{}", note);
+
+    let output = repo
+        .git_ai(&["ingest", "--provider", "copilot", payload_arg])
+        .unwrap();
+    assert!(output.contains("(2 already attributed"), "{}", output);
+}
+
+#[test]
+fn test_ingest_branch_session_export() {
+    let repo = TestRepo::new();
+    std::fs::write(repo.path().join("app.txt"), "human line\n").unwrap();
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    let base = repo
+        .git_og(&["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string();
+
+    repo.git_og(&["checkout", "-b", "devin/feature"]).unwrap();
+    agent_commit(&repo, "app.txt", "human line\nagent line\n", "Agent work");
+
+    let payload = json!({"session_id": "devin-1", "branch": "devin/feature", "base": base});
+    let output = repo
+        .git_ai_with_stdin(
+            &["ingest", "--provider", "devin", "-"],
+            payload.to_string().as_bytes(),
+        )
+        .unwrap();
+    assert!(
+        output.contains("Attributed 1 commit(s) to devin"),
+        "{}",
+        output
+    );
+
+    assert!(
+        repo.git_ai(&["ingest", "--provider", "nope", "payload.json"])
+            .is_err()
+    );
+}
@@ -0,0 +1,56 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+/// Create a commit the way jj does: objects written directly, HEAD detached onto it,
+/// no git hooks.
+fn jj_like_commit(repo: &TestRepo, parent: &str, message: &str) -> String {
+    repo.git_og(&["add", "-A"]).unwrap();
+    let tree = repo.git_og(&["write-tree"]).unwrap().trim().to_string();
+    let sha = repo
+        .git_og(&["commit-tree", &tree, "-p", parent, "-m", message])
+        .unwrap()
+        .trim()
+        .to_string();
+    repo.git_og(&["update-ref", "--no-deref", "HEAD", &sha])
+        .unwrap();
+    sha
+}
+
+fn note(repo: &TestRepo, sha: &str) -> Option<String> {
+    repo.git_og(&["notes", "--ref=ai", "show", sha]).ok()
+}
+
+#[test]
+fn test_jj_commits_and_rewrites_keep_attribution() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("test.txt");
+    file.set_contents(lines!["line 1"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    let initial = repo
+        .git_og(&["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string();
+
+    std::fs::create_dir_all(repo.path().join(".jj").join("repo")).unwrap();
+    std::fs::write(repo.path().join(".jj").join(".gitignore"), "/*\n").unwrap();
+
+    // `jj commit`: the agent's edit lands in a commit git-ai never saw
+    file.set_contents(lines!["line 1", "ai line 2".ai()]);
+    let committed = jj_like_commit(&repo, &initial, "Add line");
+    assert!(note(&repo, &committed).is_none());
+
+    repo.git_ai(&["checkpoint"]).unwrap();
+    let committed_note = note(&repo, &committed).expect("commit should be finalized");
+    assert!(committed_note.contains("test.txt"));
+
+    // `jj describe`: same tree and parent, new sha
+    let described = jj_like_commit(&repo, &initial, "Add line 2");
+    assert_ne!(described, committed);
+    repo.git_ai(&["checkpoint"]).unwrap();
+    assert!(note(&repo, &described).is_some());
+
+    file.assert_lines_and_blame(lines!["line 1".human(), "ai line 2".ai()]);
+}
@@ -0,0 +1,56 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_log_file_records_git_commands() {
+    let repo = TestRepo::new();
+    let log_dir = tempfile::tempdir().unwrap();
+    let log_path = log_dir.path().join("git-ai.log");
+    let log_file = log_path.to_str().unwrap();
+
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["readme".human()]);
+    repo.git(&["add", "-A"]).unwrap();
+    repo.commit_with_env(
+        "Initial",
+        &[("GIT_AI_LOG", "debug"), ("GIT_AI_LOG_FILE", log_file)],
+        None,
+    )
+    .unwrap();
+
+    let log = std::fs::read_to_string(&log_path).unwrap();
+    assert!(log.lines().count() > 0);
+    assert!(log.lines().all(|line| line.contains(" DEBUG ")), "{}", log);
+    assert!(log.contains(" DEBUG git commit: "), "{}", log);
+}
+
+#[test]
+fn test_log_level_flag() {
+    let repo = TestRepo::new();
+    let log_dir = tempfile::tempdir().unwrap();
+    let log_path = log_dir.path().join("git-ai.log");
+    let log_file = log_path.to_str().unwrap();
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["readme".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    repo.git_ai_with_env(
+        &["--log-level", "off", "status"],
+        &[("GIT_AI_LOG", "debug"), ("GIT_AI_LOG_FILE", log_file)],
+    )
+    .unwrap();
+    assert!(!log_path.exists());
+
+    repo.git_ai_with_env(
+        &["--log-level=debug", "status"],
+        &[("GIT_AI_LOG", "off"), ("GIT_AI_LOG_FILE", log_file)],
+    )
+    .unwrap();
+    let log = std::fs::read_to_string(&log_path).unwrap();
+    assert!(log.contains(" DEBUG git-ai status: "), "{}", log);
+
+    let err = repo.git_ai(&["--log-level", "loud", "status"]).unwrap_err();
+    assert!(err.contains("--log-level requires one of"), "{}", err);
+}
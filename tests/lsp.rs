@@ -0,0 +1,112 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::{TestRepo, get_binary_path};
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{ChildStdout, Command, Stdio};
+
+fn send(stdin: &mut impl Write, message: Value) {
+    let body = message.to_string();
+    write!(stdin, "Content-Length: {}\r\n\r\n{}", body.len(), body).unwrap();
+    stdin.flush().unwrap();
+}
+
+fn receive(stdout: &mut BufReader<ChildStdout>) -> Value {
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        stdout.read_line(&mut header).unwrap();
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some(value) = header.strip_prefix("Content-Length:") {
+            content_length = value.trim().parse().unwrap();
+        }
+    }
+    let mut body = vec![0; content_length];
+    stdout.read_exact(&mut body).unwrap();
+    serde_json::from_slice(&body).unwrap()
+}
+
+#[test]
+fn test_lsp_inlay_hints_and_code_lenses() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("lib.rs");
+    file.set_contents(lines!["fn human() {}", "fn agent() {}".ai()]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    file.assert_lines_and_blame(lines!["fn human() {}".human(), "fn agent() {}".ai()]);
+
+    let mut child = Command::new(get_binary_path())
+        .arg("lsp")
+        .current_dir(repo.path())
+        .env("GIT_AI_TEST_DB_PATH", repo.test_db_path())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = BufReader::new(child.stdout.take().unwrap());
+
+    send(
+        &mut stdin,
+        json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+    );
+    let response = receive(&mut stdout);
+    assert_eq!(
+        response["result"]["capabilities"]["inlayHintProvider"],
+        true
+    );
+    send(
+        &mut stdin,
+        json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}),
+    );
+
+    let uri = url::Url::from_file_path(repo.canonical_path().join("lib.rs"))
+        .unwrap()
+        .to_string();
+    // An unsaved line above shifts the committed ones down (set_contents adds no final newline)
+    send(
+        &mut stdin,
+        json!({"jsonrpc": "2.0", "method": "textDocument/didOpen", "params": {
+            "textDocument": {"uri": uri, "languageId": "rust", "version": 1,
+                "text": "// new\nfn human() {}\nfn agent() {}"}
+        }}),
+    );
+
+    send(
+        &mut stdin,
+        json!({"jsonrpc": "2.0", "id": 2, "method": "textDocument/inlayHint", "params": {
+            "textDocument": {"uri": uri},
+            "range": {"start": {"line": 0, "character": 0}, "end": {"line": 3, "character": 0}}
+        }}),
+    );
+    let response = receive(&mut stdout);
+    let hints = response["result"].as_array().unwrap();
+    assert_eq!(hints.len(), 1, "{}", response);
+    assert_eq!(hints[0]["position"], json!({"line": 2, "character": 13}));
+    let label = hints[0]["label"].as_str().unwrap();
+    assert!(label.starts_with("AI: mock_ai, "), "{}", label);
+    assert!(label.ends_with(" ago"), "{}", label);
+
+    send(
+        &mut stdin,
+        json!({"jsonrpc": "2.0", "id": 3, "method": "textDocument/codeLens", "params": {
+            "textDocument": {"uri": uri}
+        }}),
+    );
+    let response = receive(&mut stdout);
+    let lenses = response["result"].as_array().unwrap();
+    assert_eq!(lenses.len(), 1, "{}", response);
+    assert_eq!(lenses[0]["range"]["start"]["line"], 2);
+    assert_eq!(lenses[0]["command"]["title"], label);
+
+    send(
+        &mut stdin,
+        json!({"jsonrpc": "2.0", "id": 4, "method": "shutdown"}),
+    );
+    assert_eq!(receive(&mut stdout)["result"], Value::Null);
+    send(&mut stdin, json!({"jsonrpc": "2.0", "method": "exit"}));
+    assert!(child.wait().unwrap().success());
+}
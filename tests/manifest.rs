@@ -0,0 +1,38 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use serde_json::Value;
+
+#[test]
+fn test_manifest_lists_files_with_ai_content() {
+    let repo = TestRepo::new();
+    let mut lib = repo.filename("src/lib.rs");
+    lib.set_contents(lines!["fn human() {}".human(), "fn ai() {}".ai()]);
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["readme"]);
+    let commit = repo.stage_all_and_commit("Initial").unwrap().commit_sha;
+
+    let output = repo.git_ai(&["manifest", "--format", "spdx-like"]).unwrap();
+    let json_start = output.find('{').unwrap();
+    let manifest: Value = serde_json::Deserializer::from_str(&output[json_start..])
+        .into_iter::<Value>()
+        .next()
+        .unwrap()
+        .unwrap();
+    assert_eq!(manifest["commit"], commit);
+    assert_eq!(manifest["summary"]["files"], 2);
+    assert_eq!(manifest["summary"]["filesWithAiContent"], 1);
+    assert_eq!(manifest["summary"]["aiPercent"], 33.3);
+
+    let files = manifest["files"].as_array().unwrap();
+    assert_eq!(files.len(), 1, "{}", output);
+    assert_eq!(files[0]["fileName"], "./src/lib.rs");
+    assert_eq!(files[0]["aiLines"], 1);
+    assert_eq!(files[0]["aiPercent"], 50.0);
+    assert_eq!(files[0]["aiContributors"][0]["tool"], "mock_ai");
+    assert_eq!(files[0]["checksums"][0]["algorithm"], "SHA256");
+
+    let output = repo.git_ai(&["manifest", "--all-files"]).unwrap();
+    assert!(output.contains("\"./README.md\""), "{}", output);
+}
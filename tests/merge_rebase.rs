@@ -265,3 +265,26 @@ fn test_blame_after_merge_conflict_resolution() {
         "Line 10".human(),
     ]);
 }
+
+#[test]
+fn test_uncommitted_ai_changes_survive_fast_forward_merge() {
+    let repo = TestRepo::new();
+    let mut notes = repo.filename("notes.txt");
+    notes.set_contents(lines!["notes"]);
+    let mut other = repo.filename("other.txt");
+    other.set_contents(lines!["other"]);
+    repo.stage_all_and_commit("Initial").unwrap();
+    let default_branch = repo.current_branch();
+
+    repo.git(&["checkout", "-b", "feature"]).unwrap();
+    other.set_contents(lines!["other", "from feature"]);
+    repo.stage_all_and_commit("Feature work").unwrap();
+    repo.git(&["checkout", &default_branch]).unwrap();
+
+    // Pending AI work in a file the merge doesn't touch
+    notes.set_contents(lines!["notes", "ai note".ai()]);
+    repo.git(&["merge", "feature"]).unwrap();
+
+    repo.stage_all_and_commit("Commit AI note").unwrap();
+    notes.assert_lines_and_blame(lines!["notes".human(), "ai note".ai()]);
+}
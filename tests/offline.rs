@@ -0,0 +1,30 @@
+#[macro_use]
+mod repos;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_offline_mode_skips_network_commands() {
+    let repo = TestRepo::new();
+    let offline = [("GIT_AI_OFFLINE", "1")];
+
+    let err = repo
+        .git_ai_with_env(&["login", "--device"], &offline)
+        .expect_err("login needs the network");
+    assert!(err.contains("offline mode"), "{}", err);
+
+    let output = repo
+        .git_ai_with_env(&["exchange-nonce"], &offline)
+        .expect("exchange-nonce is a no-op offline");
+    assert!(output.contains("skipping exchange-nonce"), "{}", output);
+
+    // Local attribution keeps working
+    std::fs::write(repo.path().join("a.txt"), "line\n").unwrap();
+    repo.git_ai_with_env(&["checkpoint", "mock_ai"], &offline)
+        .unwrap();
+    repo.stage_all_and_commit("Offline commit").unwrap();
+    assert!(
+        repo.git_ai_with_env(&["stats", "--json"], &offline)
+            .unwrap()
+            .contains("ai_additions")
+    );
+}
@@ -0,0 +1,91 @@
+#[macro_use]
+mod repos;
+use git_ai::authorship::org_metrics::OrgSyncConfig;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use serde_json::Value;
+
+fn preview(repo: &TestRepo) -> Value {
+    let output = repo
+        .git_ai(&["sync-prompts", "--org", "--preview", "--since", "1d"])
+        .unwrap();
+    parse_payload(&output)
+}
+
+fn parse_payload(output: &str) -> Value {
+    let start = output
+        .find("\n{")
+        .map(|i| i + 1)
+        .or_else(|| output.starts_with('{').then_some(0))
+        .unwrap_or_else(|| panic!("no payload in {}", output));
+    serde_json::Deserializer::from_str(&output[start..])
+        .into_iter::<Value>()
+        .next()
+        .unwrap()
+        .unwrap()
+}
+
+#[test]
+fn test_org_preview_shows_only_aggregates() {
+    let mut repo = TestRepo::new();
+    let mut file = repo.filename("src/secret_project.rs");
+    file.set_contents(lines![
+        "fn a() {}".ai(),
+        "fn b() {}".ai(),
+        "fn c() {}".human()
+    ]);
+    repo.stage_all_and_commit("Add functions").unwrap();
+
+    let payload = preview(&repo);
+    let repos = payload["repos"].as_array().unwrap();
+    assert_eq!(repos.len(), 1, "{}", payload);
+    assert!(repos[0]["repo"].as_str().unwrap().starts_with("local:"));
+    let tools = repos[0]["tools"].as_array().unwrap();
+    assert_eq!(tools.len(), 1, "{}", payload);
+    assert_eq!(tools[0]["sessions"], 1);
+    assert_eq!(tools[0]["commits"], 1);
+    let text = payload.to_string();
+    assert!(!text.contains("secret_project"), "{}", text);
+    assert!(!text.contains(repo.path().to_str().unwrap()), "{}", text);
+
+    repo.patch_git_ai_config(|patch| {
+        patch.org_sync = Some(OrgSyncConfig {
+            team: Some("payments".to_string()),
+            interval_hours: 24,
+            include_paths: true,
+            anonymize: false,
+        });
+    });
+    let payload = preview(&repo);
+    assert_eq!(payload["team"], "payments");
+    assert_eq!(payload["repos"][0]["files"]["src/secret_project.rs"], 2);
+
+    let output = repo
+        .git_ai_with_env(
+            &[
+                "sync-prompts",
+                "--org",
+                "--preview",
+                "--anonymize",
+                "--since",
+                "1d",
+            ],
+            &[("GIT_AI_ANONYMIZE_KEY", "shared-key")],
+        )
+        .unwrap();
+    assert!(!output.contains("secret_project"), "{}", output);
+    let anonymized = parse_payload(&output);
+    assert!(
+        anonymized["repos"][0]["repo"]
+            .as_str()
+            .unwrap()
+            .starts_with("repo-")
+    );
+    let files = anonymized["repos"][0]["files"].as_object().unwrap();
+    assert!(
+        files
+            .keys()
+            .all(|file| file.starts_with("file-") && file.ends_with(".rs"))
+    );
+    assert_eq!(anonymized["since"].as_i64().unwrap() % 86400, 0);
+}
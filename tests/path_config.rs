@@ -0,0 +1,70 @@
+#[macro_use]
+mod repos;
+use git_ai::authorship::stats::CommitStats;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_ignored_paths_are_left_out_of_attribution_and_stats() {
+    let repo = TestRepo::new();
+    let mut paths = repo.filename(".git-ai/paths.toml");
+    paths.set_contents(lines!["[paths]", "\"vendor/\" = \"ignore\""]);
+    repo.stage_all_and_commit("Add path settings").unwrap();
+
+    let mut vendored = repo.filename("vendor/lib.js");
+    vendored.set_contents(lines![
+        "export const a = 1;".ai(),
+        "export const b = 2;".ai()
+    ]);
+    let mut app = repo.filename("src/app.js");
+    app.set_contents(lines!["import { a } from '../vendor/lib.js';".ai()]);
+    let commit = repo.stage_all_and_commit("Add app").unwrap();
+
+    let files: Vec<&str> = commit
+        .authorship_log
+        .attestations
+        .iter()
+        .map(|a| a.file_path.as_str())
+        .collect();
+    assert_eq!(files, ["src/app.js"]);
+
+    let raw = repo.git_ai(&["stats", "--json"]).unwrap();
+    let json = &raw[raw.find('{').unwrap()..=raw.rfind('}').unwrap()];
+    let stats: CommitStats = serde_json::from_str(json).unwrap();
+    assert_eq!(stats.git_diff_added_lines, 1);
+    assert_eq!(stats.ai_accepted, 1);
+}
+
+#[test]
+fn test_policy_check_follows_path_settings() {
+    let repo = TestRepo::new();
+    let mut policy = repo.filename(".git-ai/policy.toml");
+    policy.set_contents(lines![
+        "[[rules]]",
+        "name = \"mostly human\"",
+        "max_ai_percent = 50"
+    ]);
+    let mut paths = repo.filename(".git-ai/paths.toml");
+    paths.set_contents(lines!["[paths]", "\"tests/**\" = \"relaxed\""]);
+    let base = repo.stage_all_and_commit("Add policy").unwrap().commit_sha;
+
+    let mut test = repo.filename("tests/login_test.rs");
+    test.set_contents(lines![
+        "fn test_login() {}".ai(),
+        "fn test_logout() {}".ai()
+    ]);
+    repo.stage_all_and_commit("Add login tests").unwrap();
+
+    let range = format!("{}..HEAD", base);
+    let output = repo.git_ai(&["policy", "check", &range]).unwrap();
+    assert!(output.contains("1 commit(s) pass"), "{}", output);
+
+    paths.set_contents(lines!["[paths]", "\"tests/**\" = \"strict\""]);
+    repo.stage_all_and_commit("Check tests strictly").unwrap();
+    let err = repo.git_ai(&["policy", "check", &range]).unwrap_err();
+    assert!(
+        err.contains("Add login tests: rule `mostly human`: 100% of the added lines"),
+        "{}",
+        err
+    );
+}
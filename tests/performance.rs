@@ -21,6 +21,8 @@ fn setup() {
         rewrite_stash: true,
         inter_commit_move: true,
         auth_keyring: false,
+        agent_detection: false,
+        paste_detection: false,
     };
 
     git_ai::config::Config::set_test_feature_flags(test_flags.clone());
@@ -593,7 +595,7 @@ pub fn find_random_files_with_options(
     }
 
     // Sort by size descending and take top N
-    file_sizes.sort_by(|a, b| b.1.cmp(&a.1));
+    file_sizes.sort_by_key(|f| std::cmp::Reverse(f.1));
     let large_files: Vec<String> = file_sizes
         .into_iter()
         .take(options.large_file_count)
@@ -0,0 +1,103 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_policy_check_reports_rule_violations() {
+    let repo = TestRepo::new();
+    let mut policy = repo.filename(".git-ai/policy.toml");
+    policy.set_contents(lines![
+        "[[rules]]",
+        "name = \"auth\"",
+        "paths = [\"src/auth/**\"]",
+        "max_ai_percent = 50",
+        "require_human_checkpoint = true",
+    ]);
+    let base = repo.stage_all_and_commit("Add policy").unwrap().commit_sha;
+
+    let range = format!("{}..HEAD", base);
+    let output = repo.git_ai(&["policy", "check", &range]).unwrap();
+    assert!(output.contains("0 commit(s) pass 1 rule(s)"), "{}", output);
+
+    let mut docs = repo.filename("docs/login.md");
+    docs.set_contents(lines!["# Login".ai(), "Sign in first".ai()]);
+    repo.stage_all_and_commit("Document login").unwrap();
+    let output = repo.git_ai(&["policy", "check", &range]).unwrap();
+    assert!(output.contains("1 commit(s) pass"), "{}", output);
+
+    let mut auth = repo.filename("src/auth/login.rs");
+    auth.set_contents(lines!["fn login() {}".ai(), "fn check() {}".ai()]);
+    repo.stage_all_and_commit("Add login").unwrap();
+
+    let err = repo.git_ai(&["policy", "check", &range]).unwrap_err();
+    assert!(err.contains("2 violation(s)"), "{}", err);
+    assert!(
+        err.contains("Add login: rule `auth`: 100% of the added lines are AI-authored, more than max_ai_percent (50%)"),
+        "{}",
+        err
+    );
+    assert!(
+        err.contains("Add login: rule `auth`: AI-authored lines in src/auth/login.rs without a human checkpoint after them"),
+        "{}",
+        err
+    );
+
+    let err = repo.git_ai(&["ci-gate", &range]).unwrap_err();
+    assert!(
+        err.contains("commits violate push_policy and .git-ai/policy.toml"),
+        "{}",
+        err
+    );
+    assert!(err.contains("rule `auth`"), "{}", err);
+}
+
+#[test]
+fn test_policy_check_rejects_an_invalid_policy() {
+    let repo = TestRepo::new();
+    let mut policy = repo.filename(".git-ai/policy.toml");
+    policy.set_contents(lines!["[[rules]]", "max_ai_percent = \"lots\""]);
+    repo.stage_all_and_commit("Add policy").unwrap();
+
+    let err = repo.git_ai(&["policy", "check", "HEAD"]).unwrap_err();
+    assert!(
+        err.contains(".git-ai/policy.toml: rule 1: `max_ai_percent`"),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn test_ci_gate_requires_owner_acknowledgement() {
+    let repo = TestRepo::new();
+    let mut policy = repo.filename(".git-ai/policy.toml");
+    policy.set_contents(lines!["[[rules]]", "require_owner_review = true"]);
+    let mut codeowners = repo.filename(".github/CODEOWNERS");
+    codeowners.set_contents(lines!["/src/auth/ @alice"]);
+    let base = repo.stage_all_and_commit("Add policy").unwrap().commit_sha;
+
+    let mut auth = repo.filename("src/auth/login.rs");
+    auth.set_contents(lines!["fn login() {}".ai()]);
+    repo.stage_all_and_commit("Add login").unwrap();
+
+    let range = format!("{}..HEAD", base);
+    let err = repo.git_ai(&["ci-gate", &range]).unwrap_err();
+    assert!(
+        err.contains(
+            "Add login: rule `rule 1`: AI-authored lines in src/auth/login.rs not acknowledged by an owner (@alice)"
+        ),
+        "{}",
+        err
+    );
+    let output = repo
+        .git_ai(&["ci-gate", &range, "--approved-by", "alice"])
+        .unwrap();
+    assert!(output.contains("1 commit(s) pass"), "{}", output);
+
+    auth.set_contents(lines!["fn login() {}".ai(), "fn logout() {}".ai()]);
+    repo.stage_all_and_commit("Add logout\n\nReviewed-by: Alice (@alice)")
+        .unwrap();
+    let err = repo.git_ai(&["ci-gate", &range]).unwrap_err();
+    assert!(err.contains("Add login: rule"), "{}", err);
+    assert!(!err.contains("Add logout: rule"), "{}", err);
+}
@@ -0,0 +1,90 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_report_writes_treemap_and_file_pages() {
+    let repo = TestRepo::new();
+    let mut lib = repo.filename("src/lib.rs");
+    lib.set_contents(lines!["fn human() {}".human(), "fn ai() {}".ai()]);
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["readme"]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    let output = repo.path().join("out");
+    let result = repo
+        .git_ai(&["report", "--output", output.to_str().unwrap()])
+        .unwrap();
+    assert!(result.contains("Wrote a report of 2 files"), "{}", result);
+
+    let index = std::fs::read_to_string(output.join("index.html")).unwrap();
+    assert!(index.contains("<h2>src/</h2>"), "{}", index);
+    assert!(index.contains("class=\"tile mixed\""), "{}", index);
+    assert!(
+        index.contains("href=\"files/src/lib.rs.html\""),
+        "{}",
+        index
+    );
+    assert!(index.contains("class=\"tile human\""), "{}", index);
+
+    let page = std::fs::read_to_string(output.join("files/src/lib.rs.html")).unwrap();
+    assert!(
+        page.contains("1 of 2 lines (50%) written by AI."),
+        "{}",
+        page
+    );
+    assert!(
+        page.contains("<td class=\"who\">mock_ai</td><td>fn ai() {}</td>"),
+        "{}",
+        page
+    );
+    assert!(page.contains("<td class=\"who\"></td><td>fn human() {}</td>"));
+}
+
+#[test]
+fn test_report_anonymize_hides_paths_and_code() {
+    let repo = TestRepo::new();
+    let mut lib = repo.filename("src/payments/lib.rs");
+    lib.set_contents(lines!["fn human() {}".human(), "fn ai() {}".ai()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    let output = tempfile::tempdir().unwrap();
+    repo.git_ai_with_env(
+        &[
+            "report",
+            "--anonymize",
+            "--by-language",
+            "--output",
+            output.path().to_str().unwrap(),
+        ],
+        &[("GIT_AI_ANONYMIZE_KEY", "shared-key")],
+    )
+    .unwrap();
+
+    let index = std::fs::read_to_string(output.path().join("index.html")).unwrap();
+    assert!(
+        !index.contains("payments") && !index.contains("lib.rs"),
+        "{}",
+        index
+    );
+    assert!(index.contains("<td>Rust</td>"), "{}", index);
+    let pages: Vec<_> = std::fs::read_dir(output.path().join("files"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(pages.len(), 1);
+    let name = pages[0].file_name().unwrap().to_str().unwrap();
+    assert!(
+        name.starts_with("file-") && name.ends_with(".rs.html"),
+        "{}",
+        name
+    );
+    let page = std::fs::read_to_string(&pages[0]).unwrap();
+    assert!(!page.contains("fn ai()"), "{}", page);
+    assert!(
+        page.contains("<td class=\"who\">mock_ai</td><td></td>"),
+        "{}",
+        page
+    );
+}
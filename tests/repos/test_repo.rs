@@ -396,10 +396,28 @@ impl TestRepo {
     }
 
     pub fn git_ai_with_env(&self, args: &[&str], envs: &[(&str, &str)]) -> Result<String, String> {
+        self.git_ai_in_dir(args, envs, &self.path)
+    }
+
+    /// Run a git-ai command from another working directory (e.g. a linked worktree)
+    pub fn git_ai_from_working_dir(
+        &self,
+        working_dir: &std::path::Path,
+        args: &[&str],
+    ) -> Result<String, String> {
+        self.git_ai_in_dir(args, &[], working_dir)
+    }
+
+    fn git_ai_in_dir(
+        &self,
+        args: &[&str],
+        envs: &[(&str, &str)],
+        working_dir: &std::path::Path,
+    ) -> Result<String, String> {
         let binary_path = get_binary_path();
 
         let mut command = Command::new(binary_path);
-        command.args(args).current_dir(&self.path);
+        command.args(args).current_dir(working_dir);
 
         // Add config patch as environment variable if present
         if let Some(patch) = &self.config_patch
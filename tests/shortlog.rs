@@ -0,0 +1,48 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_shortlog_counts_lines_per_author() {
+    let repo = TestRepo::new();
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["readme".human()]);
+    let base = repo.stage_all_and_commit("Initial").unwrap().commit_sha;
+
+    let mut lib = repo.filename("lib.rs");
+    lib.set_contents(lines!["fn a() {}".ai(), "fn b() {}".ai()]);
+    repo.stage_all_and_commit("Add lib").unwrap();
+    let mut notes = repo.filename("NOTES.md");
+    notes.set_contents(lines!["notes".human()]);
+    repo.git(&["add", "-A"]).unwrap();
+    repo.commit_with_env(
+        "Add notes",
+        &[
+            ("GIT_AUTHOR_NAME", "Ada"),
+            ("GIT_AUTHOR_EMAIL", "ada@example.com"),
+        ],
+        None,
+    )
+    .unwrap();
+
+    let range = format!("{}..HEAD", base);
+    let output = repo.git_ai(&["shortlog", &range]).unwrap();
+    assert!(
+        output.contains("     1\tTest User       0 human       2 AI  mock_ai\n"),
+        "{}",
+        output
+    );
+    assert!(
+        output.contains("     1\tAda             1 human       0 AI\n"),
+        "{}",
+        output
+    );
+
+    let output = repo.git_ai(&["shortlog"]).unwrap();
+    assert!(
+        output.contains("     2\tTest User       1 human       2 AI  mock_ai\n"),
+        "{}",
+        output
+    );
+}
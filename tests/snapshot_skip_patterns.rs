@@ -0,0 +1,63 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+
+fn blob_count(repo: &TestRepo) -> usize {
+    fs::read_dir(repo.current_working_logs().dir.join("blobs"))
+        .map(|entries| entries.count())
+        .unwrap_or(0)
+}
+
+/// Lockfiles are never snapshot: the agent that rewrote one gets all of its lines, and
+/// a later checkpoint that didn't touch it leaves that alone.
+#[test]
+fn test_lockfile_gets_coarse_attribution_without_snapshots() {
+    let repo = TestRepo::new();
+    let mut app = repo.filename("app.js");
+    app.set_contents(lines!["base"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    fs::write(repo.path().join("app.js"), "base\nai\n").unwrap();
+    fs::write(
+        repo.path().join("package-lock.json"),
+        "{\n  \"lockfileVersion\": 3\n}\n",
+    )
+    .unwrap();
+    repo.git_ai(&["checkpoint", "mock_ai"]).unwrap();
+    assert_eq!(blob_count(&repo), 1, "only app.js should be snapshot");
+
+    fs::write(repo.path().join("app.js"), "base\nai\nhuman\n").unwrap();
+    repo.git_ai(&["checkpoint"]).unwrap();
+
+    repo.stage_all_and_commit("Add lockfile").unwrap();
+    app.assert_lines_and_blame(lines!["base".human(), "ai".ai(), "human".human()]);
+    let mut lockfile = repo.filename("package-lock.json");
+    lockfile.assert_lines_and_blame(lines!["{".ai(), "  \"lockfileVersion\": 3".ai(), "}".ai()]);
+}
+
+/// Configured patterns replace the defaults.
+#[test]
+fn test_configured_snapshot_skip_patterns() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.snapshot_skip_patterns = Some(vec!["generated/**".to_string()]);
+    });
+    let mut file = repo.filename("package-lock.json");
+    file.set_contents(lines!["base"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    fs::create_dir_all(repo.path().join("generated")).unwrap();
+    fs::write(repo.path().join("generated/api.rs"), "fn api() {}\n").unwrap();
+    fs::write(repo.path().join("package-lock.json"), "base\nai\n").unwrap();
+    repo.git_ai(&["checkpoint", "mock_ai"]).unwrap();
+    assert_eq!(
+        blob_count(&repo),
+        1,
+        "only package-lock.json should be snapshot"
+    );
+
+    repo.stage_all_and_commit("AI commit").unwrap();
+    file.assert_lines_and_blame(lines!["base".human(), "ai".ai()]);
+}
@@ -0,0 +1,67 @@
+#[macro_use]
+mod repos;
+use filetime::FileTime;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+
+/// Write `contents` to `path` in `repo` with an mtime an hour ago, well clear of the
+/// window in which stat data can't be trusted
+fn write_an_hour_ago(repo: &TestRepo, path: &str, contents: &str) {
+    let path = repo.path().join(path);
+    fs::write(&path, contents).unwrap();
+    let an_hour_ago = FileTime::from_unix_time(FileTime::now().unix_seconds() - 3600, 0);
+    filetime::set_file_mtime(&path, an_hour_ago).unwrap();
+}
+
+/// Checkpointed files whose stat data hasn't moved are not re-read, and their pending
+/// attributions carry through to the commit unchanged.
+#[test]
+fn test_checkpoint_skips_files_with_unchanged_stat() {
+    let repo = TestRepo::new();
+    let mut first = repo.filename("first.txt");
+    let mut second = repo.filename("second.txt");
+    first.set_contents(lines!["base"]);
+    second.set_contents(lines!["base"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    write_an_hour_ago(&repo, "first.txt", "base\nai one\n");
+    write_an_hour_ago(&repo, "second.txt", "base\nai two\n");
+    repo.git_ai(&["checkpoint", "mock_ai"]).unwrap();
+    assert!(
+        repo.current_working_logs()
+            .dir
+            .join("file_stats.json")
+            .exists()
+    );
+
+    fs::write(repo.path().join("second.txt"), "base\nai two\nhuman\n").unwrap();
+    let output = repo.git_ai(&["checkpoint"]).unwrap();
+    assert!(
+        output.contains("changed 1 file(s)"),
+        "only second.txt should be considered: {}",
+        output
+    );
+
+    repo.stage_all_and_commit("Mixed commit").unwrap();
+    first.assert_lines_and_blame(lines!["base".human(), "ai one".ai()]);
+    second.assert_lines_and_blame(lines!["base".human(), "ai two".ai(), "human".human()]);
+}
+
+/// A file rewritten in the second its stat data was taken is read again even though
+/// its size and mtime can't tell it apart.
+#[test]
+fn test_checkpoint_rereads_racily_written_files() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("app.txt");
+    file.set_contents(lines!["base"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    fs::write(repo.path().join("app.txt"), "base\nai 1\n").unwrap();
+    repo.git_ai(&["checkpoint", "mock_ai"]).unwrap();
+    fs::write(repo.path().join("app.txt"), "base\nhu 1\n").unwrap();
+    repo.git_ai(&["checkpoint"]).unwrap();
+
+    repo.stage_all_and_commit("Human commit").unwrap();
+    file.assert_lines_and_blame(lines!["base".human(), "hu 1".human()]);
+}
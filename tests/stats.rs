@@ -378,3 +378,125 @@ fn test_markdown_stats_formatting() {
     println!("{}", markdown);
     assert_debug_snapshot!(markdown);
 }
+
+#[test]
+fn test_stats_group_model_variants_into_families() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.model_aliases = Some(
+            [("acme-*".to_string(), "acme".to_string())]
+                .into_iter()
+                .collect(),
+        );
+    });
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["# Project"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    for (model, file) in [
+        ("claude-sonnet-4-5-20250929", "a.txt"),
+        ("anthropic/claude-sonnet-4.5", "b.txt"),
+        ("acme-large-v2", "c.txt"),
+    ] {
+        let script = format!("echo 'by agent' > {}", file);
+        repo.git_ai(&[
+            "wrap", "--tool", "mock_ai", "--model", model, "--", "sh", "-c", &script,
+        ])
+        .unwrap();
+    }
+    repo.stage_all_and_commit("Agent runs").unwrap();
+
+    let raw = repo.git_ai(&["stats", "--json"]).unwrap();
+    let stats: CommitStats = serde_json::from_str(&extract_json_object(&raw)).unwrap();
+    let keys: Vec<&str> = stats
+        .tool_model_breakdown
+        .keys()
+        .map(String::as_str)
+        .collect();
+    assert_eq!(keys, vec!["mock_ai::acme", "mock_ai::claude-sonnet-4-5"]);
+    assert_eq!(
+        stats.tool_model_breakdown["mock_ai::claude-sonnet-4-5"].ai_accepted,
+        2
+    );
+    assert_eq!(stats.tool_model_breakdown["mock_ai::acme"].ai_accepted, 1);
+}
+
+#[test]
+fn test_stats_jsonl_streams_one_object_per_commit() {
+    let repo = TestRepo::new();
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["# Project"]);
+    let first = repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let mut file = repo.filename("a.txt");
+    file.set_contents(lines!["human".human(), "agent".ai()]);
+    let second = repo.stage_all_and_commit("AI edit").unwrap();
+
+    let raw = repo.git_ai(&["stats", "--jsonl"]).unwrap();
+    let records: Vec<serde_json::Value> = raw
+        .lines()
+        .filter(|line| line.starts_with('{'))
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(records.len(), 2, "{}", raw);
+    assert_eq!(records[0]["commit"], second.commit_sha);
+    assert_eq!(records[0]["ai_accepted"], 1);
+    assert_eq!(records[1]["commit"], first.commit_sha);
+
+    let range = format!("{}..HEAD", first.commit_sha);
+    let raw = repo.git_ai(&["stats", &range, "--jsonl"]).unwrap();
+    assert_eq!(raw.lines().filter(|line| line.starts_with('{')).count(), 1);
+
+    let raw = repo
+        .git_ai(&["stats", "--jsonl", "--since", "2000-01-01"])
+        .unwrap();
+    assert_eq!(raw.lines().filter(|line| line.starts_with('{')).count(), 2);
+    assert!(repo.git_ai(&["stats", "--since", "2000-01-01"]).is_err());
+}
+
+#[test]
+fn test_stats_by_language() {
+    let mut repo = TestRepo::new();
+    repo.patch_git_ai_config(|patch| {
+        patch.language_overrides = Some(
+            [("tests/**".to_string(), "Rust tests".to_string())]
+                .into_iter()
+                .collect(),
+        );
+    });
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["# Project"]);
+    let first = repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let mut lib = repo.filename("src/lib.rs");
+    lib.set_contents(lines!["pub fn a() {}".human(), "pub fn b() {}".ai()]);
+    let mut test = repo.filename("tests/lib.rs");
+    test.set_contents(lines!["#[test]".ai(), "fn a() {}".ai()]);
+    let mut script = repo.filename("run.py");
+    script.set_contents(lines!["print('hi')".human()]);
+    repo.stage_all_and_commit("Add code").unwrap();
+
+    let raw = repo.git_ai(&["stats", "--by-language", "--json"]).unwrap();
+    let languages: serde_json::Value = serde_json::from_str(&extract_json_object(&raw)).unwrap();
+    assert_eq!(
+        languages,
+        serde_json::json!({
+            "Python": {"ai_lines": 0, "total_lines": 1},
+            "Rust": {"ai_lines": 1, "total_lines": 2},
+            "Rust tests": {"ai_lines": 2, "total_lines": 2},
+        })
+    );
+
+    let range = format!("{}..HEAD", first.commit_sha);
+    let table = repo.git_ai(&["stats", &range, "--by-language"]).unwrap();
+    assert!(
+        table.contains("Rust tests         2         2      100%"),
+        "{}",
+        table
+    );
+    assert!(
+        table.contains("Python             1         0        0%"),
+        "{}",
+        table
+    );
+}
@@ -0,0 +1,73 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+
+fn status_json(repo: &TestRepo, args: &[&str]) -> serde_json::Value {
+    let output = repo.git_ai(args).unwrap();
+    let line = output
+        .lines()
+        .find(|line| line.starts_with('{'))
+        .unwrap_or_else(|| panic!("no JSON in {}", output));
+    serde_json::from_str(line).unwrap()
+}
+
+#[test]
+fn test_status_summary_matches_exact_status() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("app.txt");
+    file.set_contents(lines!["base".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    fs::write(repo.path().join("app.txt"), "base\nai 1\nai 2\nai 3\n").unwrap();
+    repo.git_ai(&["checkpoint", "mock_ai", "app.txt"]).unwrap();
+    fs::write(
+        repo.path().join("app.txt"),
+        "base\nai 1\nai 2\nai 3\nhuman\n",
+    )
+    .unwrap();
+
+    let fast = status_json(&repo, &["status", "--json"]);
+    assert!(
+        repo.current_working_logs()
+            .dir
+            .join("status_summary.json")
+            .exists()
+    );
+    let exact = status_json(&repo, &["status", "--json", "--exact"]);
+
+    for key in [
+        "ai_accepted",
+        "ai_additions",
+        "human_additions",
+        "git_diff_added_lines",
+        "git_diff_deleted_lines",
+    ] {
+        assert_eq!(fast["stats"][key], exact["stats"][key], "{}", key);
+    }
+    assert_eq!(fast["stats"]["ai_accepted"], 3);
+    assert_eq!(fast["checkpoints"], exact["checkpoints"]);
+}
+
+#[test]
+fn test_status_summary_rebuilt_when_stale() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("app.txt");
+    file.set_contents(lines!["base".human()]);
+    repo.stage_all_and_commit("Initial").unwrap();
+
+    fs::write(repo.path().join("app.txt"), "base\nai 1\nai 2\n").unwrap();
+    repo.git_ai(&["checkpoint", "mock_ai", "app.txt"]).unwrap();
+
+    // A summary that no longer matches the checkpoints is ignored
+    let summary = repo.current_working_logs().dir.join("status_summary.json");
+    let mut stale: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&summary).unwrap()).unwrap();
+    stale["ai_lines"]["app.txt"] = 40.into();
+    stale["checkpoints_bytes"] = 1.into();
+    fs::write(&summary, stale.to_string()).unwrap();
+
+    let status = status_json(&repo, &["status", "--json"]);
+    assert_eq!(status["stats"]["ai_accepted"], 2);
+}
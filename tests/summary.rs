@@ -0,0 +1,36 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+
+#[test]
+fn test_summary_markdown_for_range() {
+    let repo = TestRepo::new();
+    let mut readme = repo.filename("README.md");
+    readme.set_contents(lines!["readme"]);
+    let base = repo.stage_all_and_commit("Initial").unwrap().commit_sha;
+
+    let mut auth = repo.filename("src/auth.rs");
+    auth.set_contents(lines!["fn login() {}".ai(), "fn logout() {}".ai()]);
+    readme.set_contents(lines!["readme", "more docs".human()]);
+    repo.stage_all_and_commit("Add login").unwrap();
+
+    let range = format!("{}..HEAD", base);
+    let output = repo
+        .git_ai(&["summary", "--range", &range, "--markdown"])
+        .unwrap();
+    assert!(output.starts_with("### AI authorship\n"), "{}", output);
+    assert!(
+        output.contains("**50%** of the 4 lines added by 1 commit were written by AI."),
+        "{}",
+        output
+    );
+    assert!(output.contains("**Tools:** mock_ai: 2 lines"), "{}", output);
+    assert!(
+        output.contains("| `src/auth.rs` | 2 of 2 | 100% |"),
+        "{}",
+        output
+    );
+    assert!(!output.contains("README.md"), "{}", output);
+    assert!(output.contains("no `push_policy` flags"), "{}", output);
+}
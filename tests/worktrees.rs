@@ -0,0 +1,75 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+use std::path::PathBuf;
+
+fn add_worktree(repo: &TestRepo, branch: &str) -> PathBuf {
+    let path = PathBuf::from(format!("{}-{}", repo.path().display(), branch));
+    repo.git_og(&[
+        "worktree",
+        "add",
+        "-q",
+        "-b",
+        branch,
+        path.to_str().unwrap(),
+    ])
+    .unwrap();
+    path
+}
+
+/// Checkpoints and commits made inside a linked worktree must attribute the commit there,
+/// with the working log kept under the worktree's own git dir.
+#[test]
+fn test_checkpoint_and_commit_in_linked_worktree() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("base.txt");
+    file.set_contents(lines!["base"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+    let base = repo
+        .git_og(&["rev-parse", "HEAD"])
+        .unwrap()
+        .trim()
+        .to_string();
+
+    let worktree = add_worktree(&repo, "feature");
+    fs::write(worktree.join("ai.txt"), "from the agent\n").unwrap();
+    repo.git_ai_from_working_dir(&worktree, &["checkpoint", "mock_ai"])
+        .unwrap();
+
+    let worktree_logs = repo
+        .path()
+        .join(".git")
+        .join("worktrees")
+        .join(worktree.file_name().unwrap())
+        .join("ai")
+        .join("working_logs")
+        .join(&base);
+    assert!(worktree_logs.exists(), "working log should be per-worktree");
+    let main_logs = repo
+        .path()
+        .join(".git")
+        .join("ai")
+        .join("working_logs")
+        .join(&base);
+    assert!(
+        !main_logs.exists() || fs::read_dir(&main_logs).unwrap().next().is_none(),
+        "main worktree should not see the linked worktree's checkpoints"
+    );
+
+    repo.git_from_working_dir(&worktree, &["add", "ai.txt"])
+        .unwrap();
+    repo.git_from_working_dir(&worktree, &["commit", "-m", "Add ai file"])
+        .unwrap();
+
+    let note = repo
+        .git_og(&["notes", "--ref=ai", "show", "feature"])
+        .expect("commit in worktree should have an authorship note");
+    assert!(note.contains("ai.txt"), "note: {}", note);
+
+    // The main worktree still attributes its own changes normally
+    file.set_contents(lines!["base", "main change".ai()]);
+    repo.stage_all_and_commit("Main change").unwrap();
+    file.assert_lines_and_blame(lines!["base".human(), "main change".ai()]);
+}
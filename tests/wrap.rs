@@ -0,0 +1,68 @@
+#[macro_use]
+mod repos;
+use repos::test_file::ExpectedLineExt;
+use repos::test_repo::TestRepo;
+use std::fs;
+
+#[test]
+fn test_wrap_attributes_only_what_the_command_changed() {
+    let repo = TestRepo::new();
+    let mut human_file = repo.filename("human.txt");
+    human_file.set_contents(lines!["base"]);
+    let mut agent_file = repo.filename("agent.txt");
+    agent_file.set_contents(lines!["base"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    // Edited by hand before the agent runs, without any checkpoint
+    fs::write(repo.path().join("human.txt"), "base\nby hand\n").unwrap();
+
+    let output = repo
+        .git_ai(&[
+            "wrap",
+            "--tool",
+            "mock_ai",
+            "--model",
+            "gpt-4.1",
+            "--",
+            "sh",
+            "-c",
+            "printf '\\nby agent' >> agent.txt && mkdir -p gen && echo 'generated' > gen/new.txt",
+        ])
+        .unwrap();
+    assert!(
+        output.contains("attributed 2 file(s) to mock_ai"),
+        "{}",
+        output
+    );
+
+    let commit = repo.stage_all_and_commit("Agent run").unwrap();
+    human_file.assert_lines_and_blame(lines!["base".human(), "by hand".human()]);
+    agent_file.assert_lines_and_blame(lines!["base".human(), "by agent".ai()]);
+    repo.filename("gen/new.txt")
+        .assert_lines_and_blame(lines!["generated".ai()]);
+
+    let prompt = commit
+        .authorship_log
+        .metadata
+        .prompts
+        .values()
+        .next()
+        .unwrap();
+    assert_eq!(prompt.agent_id.model, "gpt-4.1");
+}
+
+#[test]
+fn test_wrap_propagates_exit_code() {
+    let repo = TestRepo::new();
+    let mut file = repo.filename("a.txt");
+    file.set_contents(lines!["base"]);
+    repo.stage_all_and_commit("Initial commit").unwrap();
+
+    let err = repo
+        .git_ai(&["wrap", "--tool", "mock_ai", "--", "sh", "-c", "exit 3"])
+        .unwrap_err();
+    assert!(!err.contains("attributed"), "{}", err);
+
+    let err = repo.git_ai(&["wrap", "--", "true"]).unwrap_err();
+    assert!(err.contains("--tool <name> is required"), "{}", err);
+}